@@ -0,0 +1,481 @@
+//! Headless script runner: `space_query run -c <connection> script.sql`.
+//!
+//! Runs a script through the same engine as the editor's "Execute Script"
+//! without opening a window. Script output goes to stdout, errors to
+//! stderr, and ACCEPT prompts read from stdin.
+
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::AtomicBool;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::db::{
+    create_shared_connection, lock_connection, split_exit_action, BatchOutcome, CellValue,
+    QueryProgress, ScriptRunner, SessionState, SharedConnection,
+};
+use crate::utils::AppConfig;
+
+const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "\
+Usage: space_query run -c <connection> [options] <script.sql>
+
+Run a SQL*Plus-style script without opening the GUI.
+
+Options:
+  -c, --connection <name>   Saved connection to use (password from the keyring)
+  -D, --define <name=value> Predefine a substitution variable (repeatable)
+      --timeout <seconds>   Per-statement call timeout
+  -h, --help                Show this help
+
+ACCEPT prompts read from stdin. EXIT and WHENEVER SQLERROR EXIT set the
+exit code. EXIT commits pending work unless it says ROLLBACK; otherwise
+uncommitted work is rolled back when the script ends unless the exit
+action says COMMIT or auto-commit is enabled in the settings.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RunArgs {
+    connection: String,
+    defines: Vec<(String, String)>,
+    timeout_secs: Option<u64>,
+    script: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ParsedArgs {
+    Run(RunArgs),
+    Help,
+}
+
+/// True when the process was started as `space_query run ...`.
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    args.get(1).map(|arg| arg == "run").unwrap_or(false)
+}
+
+/// Release builds on Windows use the GUI subsystem and start without a
+/// console. Attach to the one of the shell that started us, or open a new
+/// one, so output and errors are visible.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+        fn AllocConsole() -> i32;
+    }
+
+    // SAFETY: both calls take plain values and only fail when the process
+    // already has a console, which is then used as it is.
+    unsafe {
+        if AttachConsole(ATTACH_PARENT_PROCESS) == 0 {
+            AllocConsole();
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+/// Run the `run` subcommand and return the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    attach_console();
+    let parsed = match parse_args(args.get(2..).unwrap_or(&[])) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            eprintln!();
+            eprintln!("{USAGE}");
+            return EXIT_USAGE;
+        }
+    };
+    match parsed {
+        ParsedArgs::Help => {
+            println!("{USAGE}");
+            0
+        }
+        ParsedArgs::Run(run_args) => run_script(run_args),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<ParsedArgs, String> {
+    let mut connection = None;
+    let mut defines = Vec::new();
+    let mut timeout_secs = None;
+    let mut script = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(ParsedArgs::Help),
+            "-c" | "--connection" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a connection name"))?;
+                connection = Some(value.clone());
+            }
+            "-D" | "--define" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires name=value"))?;
+                defines.push(parse_define(value)?);
+            }
+            "--timeout" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| "--timeout requires a number of seconds".to_string())?;
+                let secs = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("Invalid timeout: {value}"))?;
+                timeout_secs = if secs == 0 { None } else { Some(secs) };
+            }
+            other if other.starts_with("-D") && other.len() > 2 => {
+                defines.push(parse_define(&other[2..])?);
+            }
+            other if other.starts_with('-') => {
+                return Err(format!("Unknown option: {other}"));
+            }
+            other => {
+                if script.is_some() {
+                    return Err(format!("Unexpected argument: {other}"));
+                }
+                script = Some(other.to_string());
+            }
+        }
+    }
+
+    Ok(ParsedArgs::Run(RunArgs {
+        connection: connection.ok_or_else(|| "Missing -c <connection>".to_string())?,
+        defines,
+        timeout_secs,
+        script: script.ok_or_else(|| "Missing script file".to_string())?,
+    }))
+}
+
+fn parse_define(value: &str) -> Result<(String, String), String> {
+    let (name, val) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid define (expected name=value): {value}"))?;
+    let name = SessionState::normalize_name(name);
+    if name.is_empty() {
+        return Err(format!("Invalid define (empty name): {value}"));
+    }
+    Ok((name, val.to_string()))
+}
+
+/// Map an EXIT code token to a number; `default` stands in for
+/// `SQL.SQLCODE` when no statement failed.
+fn exit_code_from_token(code: &str, last_sqlcode: Option<i32>, default: i32) -> i32 {
+    match code.to_uppercase().as_str() {
+        "SUCCESS" => 0,
+        "FAILURE" => 1,
+        "WARNING" => 2,
+        "SQL.SQLCODE" => last_sqlcode.unwrap_or(default),
+        other => other.parse::<i32>().unwrap_or(1),
+    }
+}
+
+/// Map a batch outcome to a process exit code. `last_sqlcode` is the ORA
+/// error number of the statement that failed last, for `EXIT SQL.SQLCODE`.
+fn exit_code_for(outcome: &BatchOutcome, last_sqlcode: Option<i32>) -> i32 {
+    match outcome {
+        BatchOutcome::Completed => 0,
+        BatchOutcome::Exited { code, .. } => code
+            .as_deref()
+            .map(|code| exit_code_from_token(code, last_sqlcode, 0))
+            .unwrap_or(0),
+        BatchOutcome::Cancelled => 1,
+        BatchOutcome::Failed { exit_action } => {
+            let code = exit_action
+                .as_deref()
+                .map(|action| split_exit_action(action, false).0)
+                .unwrap_or("");
+            if code.is_empty() {
+                1
+            } else {
+                exit_code_from_token(code, last_sqlcode, 1)
+            }
+        }
+    }
+}
+
+/// Extract the number from the first `ORA-nnnnn` in an error message.
+fn parse_ora_code(message: &str) -> Option<i32> {
+    let start = message.find("ORA-")? + 4;
+    let digits: String = message[start..]
+        .chars()
+        .take_while(|ch| ch.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+fn run_script(args: RunArgs) -> i32 {
+    let config = AppConfig::load();
+    let Some(mut info) = config.get_connection_by_name(&args.connection).cloned() else {
+        eprintln!("Error: no saved connection named '{}'", args.connection);
+        return EXIT_USAGE;
+    };
+    if !info.use_wallet {
+        let Some(password) = AppConfig::get_password_for_connection(&info.name) else {
+            eprintln!(
+                "Error: no password stored in the keyring for connection '{}'",
+                info.name
+            );
+            return 1;
+        };
+        info.password = password;
+    }
+
+    let sql = match std::fs::read_to_string(&args.script) {
+        Ok(sql) => sql,
+        Err(err) => {
+            eprintln!("Error: failed to read {}: {err}", args.script);
+            return 1;
+        }
+    };
+
+    let shared = create_shared_connection();
+    let session = {
+        let mut db_conn = lock_connection(&shared);
+        db_conn.set_session_defaults(config.session_defaults.clone());
+        match db_conn.connect(info) {
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("Error: failed to connect: {err}");
+                return 1;
+            }
+        }
+        db_conn.set_auto_commit(config.auto_commit);
+        db_conn.session_state()
+    };
+    // Failures are reported on stderr; the script runs regardless, as
+    // SQL*Plus does after a failing login.sql.
+    ScriptRunner::run_session_init(&shared);
+    {
+        let mut guard = match session.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for (name, value) in &args.defines {
+            guard.define_vars.insert(name.clone(), value.clone());
+        }
+    }
+
+    let (sender, receiver) = mpsc::channel::<QueryProgress>();
+    let timeout = args.timeout_secs.map(Duration::from_secs);
+    let batch_connection = shared.clone();
+    let handle = thread::spawn(move || {
+        ScriptRunner::run_batch(
+            batch_connection,
+            sql,
+            true,
+            timeout,
+            &sender,
+            Arc::new(Mutex::new(None)),
+            Arc::new(AtomicBool::new(false)),
+        )
+    });
+
+    let last_sqlcode = print_progress(receiver, &session);
+
+    let outcome = match handle.join() {
+        Ok(outcome) => outcome,
+        Err(_) => BatchOutcome::Failed { exit_action: None },
+    };
+
+    let commit = match &outcome {
+        BatchOutcome::Exited { commit, .. } => *commit,
+        BatchOutcome::Failed {
+            exit_action: Some(action),
+        } => split_exit_action(action, false).1,
+        _ => false,
+    };
+    finish_session(&shared, commit);
+
+    exit_code_for(&outcome, last_sqlcode)
+}
+
+/// Print batch progress until the engine finishes. Returns the ORA code
+/// of the last failed statement.
+fn print_progress(
+    receiver: mpsc::Receiver<QueryProgress>,
+    session: &Arc<Mutex<SessionState>>,
+) -> Option<i32> {
+    let stdout = io::stdout();
+    let stdin = io::stdin();
+    let mut null_text = String::new();
+    let mut strip_rowid = false;
+    let mut last_sqlcode = None;
+
+    for progress in receiver {
+        let colsep = match session.lock() {
+            Ok(guard) => guard.colsep.clone(),
+            Err(poisoned) => poisoned.into_inner().colsep.clone(),
+        };
+        let mut out = stdout.lock();
+        match progress {
+            QueryProgress::ScriptOutput { lines } => {
+                for line in lines {
+                    let _ = writeln!(out, "{line}");
+                }
+            }
+            QueryProgress::SelectStart {
+                columns,
+                null_text: text,
+                edit_target,
+                ..
+            } => {
+                null_text = text;
+                strip_rowid = edit_target.is_some();
+                if columns.iter().any(|name| !name.is_empty()) {
+                    let _ = writeln!(out, "{}", columns.join(&colsep));
+                }
+            }
+            QueryProgress::Rows { rows, .. } => {
+                for row in rows {
+                    let cells: &[CellValue] = if strip_rowid && !row.is_empty() {
+                        &row[..row.len() - 1]
+                    } else {
+                        &row
+                    };
+                    let values: Vec<&str> =
+                        cells.iter().map(|cell| cell.display(&null_text)).collect();
+                    let _ = writeln!(out, "{}", values.join(&colsep));
+                }
+            }
+            QueryProgress::StatementFinished { result, .. } => {
+                if !result.success {
+                    eprintln!("{}", result.message);
+                    if let Some(code) = parse_ora_code(&result.message) {
+                        last_sqlcode = Some(code);
+                    }
+                } else if result.is_select && !result.message.is_empty() {
+                    let _ = writeln!(out, "{}", result.message);
+                }
+            }
+            QueryProgress::PromptInput { prompt, response } => {
+                let _ = write!(out, "{prompt}");
+                let _ = out.flush();
+                drop(out);
+                let mut line = String::new();
+                let answer = match stdin.lock().read_line(&mut line) {
+                    Ok(0) | Err(_) => None,
+                    Ok(_) => Some(line.trim_end_matches(['\r', '\n']).to_string()),
+                };
+                let _ = response.send(answer);
+                continue;
+            }
+            QueryProgress::ConfirmStatement { message, response } => {
+                let _ = write!(out, "{message} [y/N] ");
+                let _ = out.flush();
+                drop(out);
+                let mut line = String::new();
+                let confirmed = match stdin.lock().read_line(&mut line) {
+                    Ok(0) | Err(_) => false,
+                    Ok(_) => matches!(line.trim().to_lowercase().as_str(), "y" | "yes"),
+                };
+                let _ = response.send(confirmed);
+                continue;
+            }
+            _ => {}
+        }
+        let _ = out.flush();
+    }
+
+    last_sqlcode
+}
+
+fn finish_session(shared: &SharedConnection, commit: bool) {
+    let mut db_conn = lock_connection(shared);
+    if let Some(conn) = db_conn.get_connection() {
+        let result = if commit {
+            conn.commit()
+        } else {
+            conn.rollback()
+        };
+        if let Err(err) = result {
+            eprintln!("Database operation failed: {err}");
+        }
+    }
+    db_conn.disconnect();
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_run_arguments() {
+        let parsed = parse_args(&strings(&[
+            "-c",
+            "prod",
+            "-D",
+            "schema=HR",
+            "-Dtag=v1=2",
+            "--timeout",
+            "30",
+            "deploy.sql",
+        ]));
+        assert_eq!(
+            parsed,
+            Ok(ParsedArgs::Run(RunArgs {
+                connection: "prod".to_string(),
+                defines: vec![
+                    ("SCHEMA".to_string(), "HR".to_string()),
+                    ("TAG".to_string(), "v1=2".to_string()),
+                ],
+                timeout_secs: Some(30),
+                script: "deploy.sql".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn rejects_missing_connection_and_script() {
+        assert!(parse_args(&strings(&["deploy.sql"])).is_err());
+        assert!(parse_args(&strings(&["-c", "prod"])).is_err());
+        assert!(parse_args(&strings(&["-c", "prod", "-D", "novalue", "a.sql"])).is_err());
+        assert_eq!(parse_args(&strings(&["--help"])), Ok(ParsedArgs::Help));
+    }
+
+    #[test]
+    fn maps_whenever_sqlerror_exit_actions() {
+        let failed = |action: Option<&str>| BatchOutcome::Failed {
+            exit_action: action.map(str::to_string),
+        };
+        assert_eq!(exit_code_for(&BatchOutcome::Completed, None), 0);
+        let exited = |code: Option<&str>| BatchOutcome::Exited {
+            code: code.map(str::to_string),
+            commit: true,
+        };
+        assert_eq!(exit_code_for(&exited(None), None), 0);
+        assert_eq!(exit_code_for(&exited(Some("3")), None), 3);
+        assert_eq!(exit_code_for(&exited(Some("FAILURE")), None), 1);
+        assert_eq!(
+            exit_code_for(&exited(Some("SQL.SQLCODE")), Some(1403)),
+            1403
+        );
+        assert_eq!(exit_code_for(&exited(Some("SQL.SQLCODE")), None), 0);
+        assert_eq!(exit_code_for(&failed(None), None), 1);
+        assert_eq!(exit_code_for(&failed(Some("SUCCESS")), None), 0);
+        assert_eq!(exit_code_for(&failed(Some("warning rollback")), None), 2);
+        assert_eq!(exit_code_for(&failed(Some("3")), None), 3);
+        assert_eq!(exit_code_for(&failed(Some("SQL.SQLCODE")), Some(942)), 942);
+        assert_eq!(
+            split_exit_action("FAILURE COMMIT", false),
+            ("FAILURE", true)
+        );
+        assert_eq!(split_exit_action("ROLLBACK", false), ("", false));
+        assert_eq!(split_exit_action("3", true), ("3", true));
+        assert_eq!(split_exit_action("3 ROLLBACK", true), ("3", false));
+        assert_eq!(
+            parse_ora_code("ORA-00942: table or view does not exist"),
+            Some(942)
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Database backend abstraction.
+//!
+//! The script engine and the object browser talk to the database through
+//! [`DbBackend`] instead of `oracle::Connection`, so they can run against
+//! the in-memory mock in tests. The Oracle implementation delegates to
+//! [`QueryExecutor`] and [`ObjectBrowser`].
+
+use oracle::sql_type::RefCursor;
+use oracle::{Connection, Error as OracleError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::db::{
+    BindValue, CellValue, ColumnInfo, CompiledObject, ObjectBrowser, QueryExecutor, QueryResult,
+    ResolvedBind, TableColumnDetail,
+};
+
+pub type SharedBackend = Arc<dyn DbBackend>;
+
+/// Called once with the result columns before any row is delivered.
+pub type SelectStartFn<'a> = &'a mut dyn FnMut(&[ColumnInfo]);
+/// Called for every fetched row; returning `false` stops the fetch.
+pub type RowFn<'a> = &'a mut dyn FnMut(Vec<CellValue>) -> bool;
+
+/// Schema object categories listed by the object browser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    Table,
+    View,
+    Procedure,
+    Function,
+    Sequence,
+    Trigger,
+    Synonym,
+    Package,
+    Type,
+}
+
+impl ObjectKind {
+    pub const ALL: [ObjectKind; 9] = [
+        ObjectKind::Table,
+        ObjectKind::View,
+        ObjectKind::Procedure,
+        ObjectKind::Function,
+        ObjectKind::Sequence,
+        ObjectKind::Trigger,
+        ObjectKind::Synonym,
+        ObjectKind::Package,
+        ObjectKind::Type,
+    ];
+}
+
+/// An open cursor returned by a statement, e.g. a REFCURSOR bind or an
+/// implicit result.
+pub trait ResultCursor {
+    /// Stream the cursor's rows. `label` becomes the result's SQL text.
+    /// Returns the result summary and whether `on_row` stopped the fetch.
+    fn stream(
+        &mut self,
+        label: &str,
+        on_select_start: SelectStartFn<'_>,
+        on_row: RowFn<'_>,
+    ) -> Result<(QueryResult, bool), OracleError>;
+}
+
+/// What a non-query statement produced.
+pub struct ExecutedStatement {
+    pub row_count: u64,
+    /// Scalar OUT bind values, by bind name.
+    pub bind_updates: Vec<(String, BindValue)>,
+    /// REFCURSOR binds, by bind name.
+    pub ref_cursors: Vec<(String, Box<dyn ResultCursor>)>,
+    /// Cursors returned through DBMS_SQL.RETURN_RESULT.
+    pub implicit_results: Vec<Box<dyn ResultCursor>>,
+}
+
+pub trait DbBackend: Send + Sync {
+    /// Execute a non-query statement with resolved binds.
+    fn execute(&self, sql: &str, binds: &[ResolvedBind]) -> Result<ExecutedStatement, OracleError>;
+
+    /// Run a query with resolved binds, streaming rows through `on_row`.
+    /// Returns the result summary and whether `on_row` stopped the fetch.
+    fn query_streaming(
+        &self,
+        sql: &str,
+        binds: &[ResolvedBind],
+        on_select_start: SelectStartFn<'_>,
+        on_row: RowFn<'_>,
+    ) -> Result<(QueryResult, bool), OracleError>;
+
+    fn commit(&self) -> Result<(), OracleError>;
+    fn rollback(&self) -> Result<(), OracleError>;
+    fn call_timeout(&self) -> Result<Option<Duration>, OracleError>;
+    fn set_call_timeout(&self, timeout: Option<Duration>) -> Result<(), OracleError>;
+
+    /// Interrupt the statement currently running on this backend.
+    fn cancel(&self) -> Result<(), OracleError>;
+
+    fn enable_dbms_output(&self, buffer_size: Option<u32>) -> Result<(), OracleError>;
+    fn disable_dbms_output(&self) -> Result<(), OracleError>;
+    fn get_dbms_output(&self, max_lines: u32) -> Result<Vec<String>, OracleError>;
+
+    fn current_user(&self) -> Result<String, OracleError>;
+    fn schemas(&self) -> Result<Vec<String>, OracleError>;
+    /// Object names of one kind; `None` lists the current user's schema.
+    fn list_objects(
+        &self,
+        owner: Option<&str>,
+        kind: ObjectKind,
+    ) -> Result<Vec<String>, OracleError>;
+    fn describe_object(&self, object_name: &str) -> Result<Vec<TableColumnDetail>, OracleError>;
+    fn is_table(&self, owner: Option<&str>, table_name: &str) -> Result<bool, OracleError>;
+    /// SHOW ERRORS rows (line, position, text) for a compiled object.
+    fn fetch_compilation_errors(
+        &self,
+        object: &CompiledObject,
+    ) -> Result<Vec<Vec<String>>, OracleError>;
+}
+
+struct OracleCursor(RefCursor);
+
+impl ResultCursor for OracleCursor {
+    fn stream(
+        &mut self,
+        label: &str,
+        on_select_start: SelectStartFn<'_>,
+        on_row: RowFn<'_>,
+    ) -> Result<(QueryResult, bool), OracleError> {
+        QueryExecutor::execute_ref_cursor_streaming(
+            &mut self.0,
+            label,
+            &mut |columns| on_select_start(columns),
+            &mut |row| on_row(row),
+        )
+    }
+}
+
+impl DbBackend for Connection {
+    fn execute(&self, sql: &str, binds: &[ResolvedBind]) -> Result<ExecutedStatement, OracleError> {
+        let stmt = QueryExecutor::execute_with_binds(self, sql, binds)?;
+        let ref_cursors = QueryExecutor::extract_ref_cursors(&stmt, binds)?
+            .into_iter()
+            .map(|(name, cursor)| {
+                (
+                    name,
+                    Box::new(OracleCursor(cursor)) as Box<dyn ResultCursor>,
+                )
+            })
+            .collect();
+        let implicit_results = QueryExecutor::extract_implicit_results(&stmt)?
+            .into_iter()
+            .map(|cursor| Box::new(OracleCursor(cursor)) as Box<dyn ResultCursor>)
+            .collect();
+        let bind_updates = QueryExecutor::fetch_scalar_bind_updates(&stmt, binds)?;
+        Ok(ExecutedStatement {
+            row_count: stmt.row_count().unwrap_or(0),
+            bind_updates,
+            ref_cursors,
+            implicit_results,
+        })
+    }
+
+    fn query_streaming(
+        &self,
+        sql: &str,
+        binds: &[ResolvedBind],
+        on_select_start: SelectStartFn<'_>,
+        on_row: RowFn<'_>,
+    ) -> Result<(QueryResult, bool), OracleError> {
+        QueryExecutor::execute_select_streaming_with_binds(
+            self,
+            sql,
+            binds,
+            &mut |columns| on_select_start(columns),
+            &mut |row| on_row(row),
+        )
+    }
+
+    fn commit(&self) -> Result<(), OracleError> {
+        Connection::commit(self)
+    }
+
+    fn rollback(&self) -> Result<(), OracleError> {
+        Connection::rollback(self)
+    }
+
+    fn call_timeout(&self) -> Result<Option<Duration>, OracleError> {
+        Connection::call_timeout(self)
+    }
+
+    fn set_call_timeout(&self, timeout: Option<Duration>) -> Result<(), OracleError> {
+        Connection::set_call_timeout(self, timeout)
+    }
+
+    fn cancel(&self) -> Result<(), OracleError> {
+        self.break_execution()
+    }
+
+    fn enable_dbms_output(&self, buffer_size: Option<u32>) -> Result<(), OracleError> {
+        QueryExecutor::enable_dbms_output(self, buffer_size)
+    }
+
+    fn disable_dbms_output(&self) -> Result<(), OracleError> {
+        QueryExecutor::disable_dbms_output(self)
+    }
+
+    fn get_dbms_output(&self, max_lines: u32) -> Result<Vec<String>, OracleError> {
+        QueryExecutor::get_dbms_output(self, max_lines)
+    }
+
+    fn current_user(&self) -> Result<String, OracleError> {
+        ObjectBrowser::get_current_user(self)
+    }
+
+    fn schemas(&self) -> Result<Vec<String>, OracleError> {
+        ObjectBrowser::get_schemas(self)
+    }
+
+    fn list_objects(
+        &self,
+        owner: Option<&str>,
+        kind: ObjectKind,
+    ) -> Result<Vec<String>, OracleError> {
+        match kind {
+            ObjectKind::Table => ObjectBrowser::get_tables_for_owner(self, owner),
+            ObjectKind::View => ObjectBrowser::get_views_for_owner(self, owner),
+            ObjectKind::Procedure => ObjectBrowser::get_procedures_for_owner(self, owner),
+            ObjectKind::Function => ObjectBrowser::get_functions_for_owner(self, owner),
+            ObjectKind::Sequence => ObjectBrowser::get_sequences_for_owner(self, owner),
+            ObjectKind::Trigger => ObjectBrowser::get_triggers_for_owner(self, owner),
+            ObjectKind::Synonym => ObjectBrowser::get_synonyms_for_owner(self, owner),
+            ObjectKind::Package => ObjectBrowser::get_packages_for_owner(self, owner),
+            ObjectKind::Type => ObjectBrowser::get_types_for_owner(self, owner),
+        }
+    }
+
+    fn describe_object(&self, object_name: &str) -> Result<Vec<TableColumnDetail>, OracleError> {
+        QueryExecutor::describe_object(self, object_name)
+    }
+
+    fn is_table(&self, owner: Option<&str>, table_name: &str) -> Result<bool, OracleError> {
+        QueryExecutor::is_table(self, owner, table_name)
+    }
+
+    fn fetch_compilation_errors(
+        &self,
+        object: &CompiledObject,
+    ) -> Result<Vec<Vec<String>>, OracleError> {
+        QueryExecutor::fetch_compilation_errors(self, object)
+    }
+}
@@ -1,8 +1,140 @@
-use oracle::{Connection, Error as OracleError};
+use oracle::{Connection, Connector, Error as OracleError, Privilege};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 use crate::db::session::SessionState;
+use crate::db::session_init::SessionSettings;
+use crate::db::transaction::{transaction_open, TransactionEffect, TransactionState};
+use crate::db::SharedBackend;
+
+/// How the database is addressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionType {
+    /// `//host:port/service_name`
+    #[default]
+    ServiceName,
+    /// Host and port with a SID instead of a service name.
+    Sid,
+    /// An alias resolved by the client through `tnsnames.ora`.
+    TnsAlias,
+    /// A full Easy Connect (Plus) string, e.g.
+    /// `tcps://host:2484/svc?wallet_location=/path`.
+    EasyConnect,
+}
+
+impl ConnectionType {
+    pub const ALL: [ConnectionType; 4] = [
+        ConnectionType::ServiceName,
+        ConnectionType::Sid,
+        ConnectionType::TnsAlias,
+        ConnectionType::EasyConnect,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConnectionType::ServiceName => "Service Name",
+            ConnectionType::Sid => "SID",
+            ConnectionType::TnsAlias => "TNS Alias",
+            ConnectionType::EasyConnect => "Easy Connect",
+        }
+    }
+}
+
+/// Administrative privilege requested at logon.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectPrivilege {
+    #[default]
+    Normal,
+    Sysdba,
+    Sysoper,
+}
+
+impl ConnectPrivilege {
+    pub const ALL: [ConnectPrivilege; 3] = [
+        ConnectPrivilege::Normal,
+        ConnectPrivilege::Sysdba,
+        ConnectPrivilege::Sysoper,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConnectPrivilege::Normal => "Default",
+            ConnectPrivilege::Sysdba => "SYSDBA",
+            ConnectPrivilege::Sysoper => "SYSOPER",
+        }
+    }
+}
+
+/// What kind of database a connection points to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionEnvironment {
+    #[default]
+    Untagged,
+    Development,
+    Test,
+    Production,
+}
+
+impl ConnectionEnvironment {
+    pub const ALL: [ConnectionEnvironment; 4] = [
+        ConnectionEnvironment::Untagged,
+        ConnectionEnvironment::Development,
+        ConnectionEnvironment::Test,
+        ConnectionEnvironment::Production,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConnectionEnvironment::Untagged => "None",
+            ConnectionEnvironment::Development => "DEV",
+            ConnectionEnvironment::Test => "TEST",
+            ConnectionEnvironment::Production => "PROD",
+        }
+    }
+
+    /// `#rrggbb` used when a connection does not pick its own color.
+    pub fn default_color(self) -> &'static str {
+        match self {
+            ConnectionEnvironment::Untagged => "",
+            ConnectionEnvironment::Development => "#2e7d32",
+            ConnectionEnvironment::Test => "#b8860b",
+            ConnectionEnvironment::Production => "#c62828",
+        }
+    }
+}
+
+/// Safety checks applied to statements run on a production connection.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProductionGuards {
+    /// Refuse DML, DDL and PL/SQL.
+    pub read_only: bool,
+    /// Ask before UPDATE or DELETE without a WHERE clause.
+    pub confirm_unfiltered_dml: bool,
+    /// Ask before DROP and TRUNCATE.
+    pub confirm_drop_truncate: bool,
+}
+
+impl Default for ProductionGuards {
+    fn default() -> Self {
+        Self {
+            read_only: false,
+            confirm_unfiltered_dml: true,
+            confirm_drop_truncate: true,
+        }
+    }
+}
+
+/// Parse a `#rrggbb` color.
+pub fn parse_hex_color(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+    Some((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ConnectionInfo {
@@ -12,7 +144,32 @@ pub struct ConnectionInfo {
     pub password: String,
     pub host: String,
     pub port: u16,
+    /// Service name, or the SID when `connection_type` is `Sid`.
     pub service_name: String,
+    #[serde(default)]
+    pub connection_type: ConnectionType,
+    #[serde(default)]
+    pub tns_alias: String,
+    #[serde(default)]
+    pub easy_connect: String,
+    #[serde(default)]
+    pub privilege: ConnectPrivilege,
+    /// Authenticate with credentials from an Oracle wallet (`/@alias`)
+    /// instead of username and password. The wallet is configured through
+    /// `sqlnet.ora` in `TNS_ADMIN` or an Easy Connect `wallet_location`.
+    #[serde(default)]
+    pub use_wallet: bool,
+    #[serde(default)]
+    pub environment: ConnectionEnvironment,
+    /// `#rrggbb` marking the environment; empty for its default color.
+    #[serde(default)]
+    pub environment_color: String,
+    /// Only applied when `environment` is `Production`.
+    #[serde(default)]
+    pub guards: ProductionGuards,
+    /// Overrides of the global session settings.
+    #[serde(default)]
+    pub session: SessionSettings,
 }
 
 impl ConnectionInfo {
@@ -31,18 +188,101 @@ impl ConnectionInfo {
             host: host.to_string(),
             port,
             service_name: service_name.to_string(),
+            connection_type: ConnectionType::ServiceName,
+            tns_alias: String::new(),
+            easy_connect: String::new(),
+            privilege: ConnectPrivilege::Normal,
+            use_wallet: false,
+            environment: ConnectionEnvironment::Untagged,
+            environment_color: String::new(),
+            guards: ProductionGuards::default(),
+            session: SessionSettings::default(),
+        }
+    }
+
+    /// Whether the profile is called `name`, ignoring case.
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name.trim().to_lowercase() == name.trim().to_lowercase()
+    }
+
+    /// Color marking the connection's environment, if it is tagged.
+    pub fn environment_rgb(&self) -> Option<(u8, u8, u8)> {
+        if self.environment == ConnectionEnvironment::Untagged {
+            return None;
         }
+        parse_hex_color(&self.environment_color)
+            .or_else(|| parse_hex_color(self.environment.default_color()))
+    }
+
+    /// The guards to check statements against; `None` unless the
+    /// connection is tagged as production.
+    pub fn production_guards(&self) -> Option<&ProductionGuards> {
+        (self.environment == ConnectionEnvironment::Production).then_some(&self.guards)
     }
 
     pub fn connection_string(&self) -> String {
-        format!("//{}:{}/{}", self.host, self.port, self.service_name)
+        match self.connection_type {
+            ConnectionType::ServiceName => {
+                format!("//{}:{}/{}", self.host, self.port, self.service_name)
+            }
+            ConnectionType::Sid => format!(
+                "(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST={})(PORT={}))(CONNECT_DATA=(SID={})))",
+                self.host, self.port, self.service_name
+            ),
+            ConnectionType::TnsAlias => self.tns_alias.trim().to_string(),
+            ConnectionType::EasyConnect => self.easy_connect.trim().to_string(),
+        }
+    }
+
+    /// Where the connection points, in the short form users type.
+    pub fn target_string(&self) -> String {
+        match self.connection_type {
+            ConnectionType::ServiceName => {
+                format!("{}:{}/{}", self.host, self.port, self.service_name)
+            }
+            ConnectionType::Sid => format!("{}:{}:{}", self.host, self.port, self.service_name),
+            ConnectionType::TnsAlias => self.tns_alias.trim().to_string(),
+            ConnectionType::EasyConnect => self.easy_connect.trim().to_string(),
+        }
     }
 
     pub fn display_string(&self) -> String {
-        format!(
-            "{} ({}@@{}:{}/{})",
-            self.name, self.username, self.host, self.port, self.service_name
-        )
+        let user = if self.use_wallet {
+            ""
+        } else {
+            self.username.as_str()
+        };
+        // FLTK labels treat '@' as a symbol prefix; "@@" draws a literal '@'.
+        let mut display = format!(
+            "{} ({}@@{})",
+            self.name,
+            user,
+            self.target_string().replace('@', "@@")
+        );
+        if self.privilege != ConnectPrivilege::Normal {
+            display.push_str(&format!(" AS {}", self.privilege.label()));
+        }
+        display
+    }
+
+    fn connector(&self) -> Connector {
+        let (username, password) = if self.use_wallet {
+            ("", "")
+        } else {
+            (self.username.as_str(), self.password.as_str())
+        };
+        let mut connector = Connector::new(username, password, self.connection_string());
+        connector.external_auth(self.use_wallet);
+        match self.privilege {
+            ConnectPrivilege::Normal => {}
+            ConnectPrivilege::Sysdba => {
+                connector.privilege(Privilege::Sysdba);
+            }
+            ConnectPrivilege::Sysoper => {
+                connector.privilege(Privilege::Sysoper);
+            }
+        }
+        connector
     }
 
     /// Securely clear the password from memory by overwriting with zeros
@@ -69,68 +309,198 @@ impl Default for ConnectionInfo {
             host: "localhost".to_string(),
             port: 1521,
             service_name: "ORCL".to_string(),
+            connection_type: ConnectionType::ServiceName,
+            tns_alias: String::new(),
+            easy_connect: String::new(),
+            privilege: ConnectPrivilege::Normal,
+            use_wallet: false,
+            environment: ConnectionEnvironment::Untagged,
+            environment_color: String::new(),
+            guards: ProductionGuards::default(),
+            session: SessionSettings::default(),
         }
     }
 }
 
+/// Idle time after which a connection is pinged before it is used again.
+pub const IDLE_PING_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Error codes meaning the session is gone and every further call on the
+/// connection will fail.
+const CONNECTION_LOST_CODES: &[&str] = &[
+    "ORA-00028", // session killed
+    "ORA-01012", // not logged on
+    "ORA-02396", // idle time exceeded
+    "ORA-03113", // end-of-file on communication channel
+    "ORA-03114", // not connected
+    "ORA-03135", // connection lost contact
+    "DPI-1010",  // not connected
+    "DPI-1080",  // connection was closed
+];
+
+fn not_connected_error() -> OracleError {
+    OracleError::new(
+        oracle::ErrorKind::Other,
+        "Not connected to database".to_string(),
+    )
+}
+
+/// Whether an error message says the connection is no longer usable.
+pub fn is_connection_lost_error(message: &str) -> bool {
+    CONNECTION_LOST_CODES
+        .iter()
+        .any(|code| message.contains(code))
+}
+
 pub struct DatabaseConnection {
     connection: Option<Arc<Connection>>,
+    backend: Option<SharedBackend>,
     info: ConnectionInfo,
     connected: bool,
     auto_commit: bool,
     session: Arc<Mutex<SessionState>>,
+    last_used: Instant,
+    /// Set when the session died under us; cleared by a reconnect.
+    lost: bool,
+    transaction: TransactionState,
+    /// Global session settings the profile's own ones override.
+    session_defaults: SessionSettings,
 }
 
 impl DatabaseConnection {
     pub fn new() -> Self {
         Self {
             connection: None,
+            backend: None,
             info: ConnectionInfo::default(),
             connected: false,
             auto_commit: false,
             session: Arc::new(Mutex::new(SessionState::default())),
+            last_used: Instant::now(),
+            lost: false,
+            transaction: TransactionState::default(),
+            session_defaults: SessionSettings::application_defaults(),
         }
     }
 
     pub fn connect(&mut self, info: ConnectionInfo) -> Result<(), OracleError> {
-        let conn_str = info.connection_string();
-        let connection = Arc::new(
-            match Connection::connect(&info.username, &info.password, &conn_str) {
-                Ok(connection) => connection,
-                Err(err) => {
-                    eprintln!("Connection error: {err}");
-                    return Err(err);
-                }
-            },
-        );
-
-        Self::apply_default_session_settings(connection.as_ref());
+        let connection = Arc::new(match info.connector().connect() {
+            Ok(connection) => connection,
+            Err(err) => {
+                eprintln!("Connection error: {err}");
+                return Err(err);
+            }
+        });
 
+        self.backend = Some(connection.clone() as SharedBackend);
         self.connection = Some(connection);
         self.info = info;
         // Clear password from memory now that the connection is established
         self.info.clear_password();
         self.connected = true;
+        self.lost = false;
+        self.last_used = Instant::now();
+        self.transaction.clear();
 
         Ok(())
     }
 
-    fn apply_default_session_settings(conn: &Connection) {
-        let statements = [
-            "ALTER SESSION SET NLS_TIMESTAMP_FORMAT = 'yyyy-mm-dd hh24:mi:ss'",
-            "ALTER SESSION SET NLS_DATE_FORMAT = 'yyyy-mm-dd hh24:mi:ss'",
-        ];
+    pub fn set_session_defaults(&mut self, defaults: SessionSettings) {
+        self.session_defaults = defaults;
+    }
 
-        for statement in statements {
-            if let Err(err) = conn.execute(statement, &[]) {
-                eprintln!("Warning: failed to apply default session setting `{statement}`: {err}");
-            }
-        }
+    /// The script setting up a new session of this connection, see
+    /// `ScriptRunner::run_session_init`.
+    pub fn session_init_script(&self) -> String {
+        self.session_defaults
+            .overridden_by(&self.info.session)
+            .init_script()
+    }
+
+    /// Attach a backend that is not an Oracle connection, e.g. the mock
+    /// backend used by tests. `get_connection` stays `None`.
+    #[allow(dead_code)]
+    pub fn connect_backend(&mut self, mut info: ConnectionInfo, backend: SharedBackend) {
+        info.clear_password();
+        self.connection = None;
+        self.backend = Some(backend);
+        self.info = info;
+        self.connected = true;
+        self.lost = false;
+        self.last_used = Instant::now();
+        self.transaction.clear();
     }
 
     pub fn disconnect(&mut self) {
         self.connection = None;
+        self.backend = None;
         self.connected = false;
+        self.lost = false;
+        self.transaction.clear();
+    }
+
+    /// Drop a connection whose session is gone, keeping its details and
+    /// client-side session state for a reconnect. The transaction it had
+    /// open is kept too, so the reconnect prompt can say what was lost.
+    pub fn mark_lost(&mut self) {
+        if !self.connected {
+            return;
+        }
+        self.connection = None;
+        self.backend = None;
+        self.connected = false;
+        self.lost = true;
+    }
+
+    /// Whether the connection was dropped by the database rather than by
+    /// the user.
+    pub fn is_lost(&self) -> bool {
+        self.lost
+    }
+
+    /// Ping the database when the connection has been idle for `idle` or
+    /// longer. Returns `false`, and marks the connection lost, when the
+    /// ping fails.
+    pub fn ensure_alive(&mut self, idle: Duration) -> bool {
+        let Some(connection) = self.connection.clone() else {
+            return true;
+        };
+        if self.last_used.elapsed() >= idle {
+            if let Err(err) = connection.ping() {
+                eprintln!("Connection error: {err}");
+                self.mark_lost();
+                return false;
+            }
+        }
+        self.last_used = Instant::now();
+        true
+    }
+
+    /// Connect again with the details of a lost connection and restore
+    /// SERVEROUTPUT. Binds, DEFINEs and other client-side state are kept as
+    /// they were; the caller runs `session_init_script` for the rest.
+    pub fn reconnect(&mut self, password: String) -> Result<(), OracleError> {
+        let mut info = self.info.clone();
+        info.password = password;
+        self.connect(info)?;
+        let server_output = match self.session.lock() {
+            Ok(guard) => guard.server_output.clone(),
+            Err(poisoned) => {
+                eprintln!("Warning: session state lock was poisoned; recovering.");
+                poisoned.into_inner().server_output.clone()
+            }
+        };
+        if let Some(backend) = &self.backend {
+            let result = if server_output.enabled {
+                backend.enable_dbms_output((server_output.size > 0).then_some(server_output.size))
+            } else {
+                backend.disable_dbms_output()
+            };
+            if let Err(err) = result {
+                eprintln!("Warning: failed to restore SERVEROUTPUT after reconnect: {err}");
+            }
+        }
+        Ok(())
     }
 
     pub fn is_connected(&self) -> bool {
@@ -141,6 +511,10 @@ impl DatabaseConnection {
         self.connection.clone()
     }
 
+    pub fn get_backend(&self) -> Option<SharedBackend> {
+        self.backend.clone()
+    }
+
     pub fn get_info(&self) -> &ConnectionInfo {
         &self.info
     }
@@ -157,9 +531,91 @@ impl DatabaseConnection {
         Arc::clone(&self.session)
     }
 
+    pub fn transaction(&self) -> &TransactionState {
+        &self.transaction
+    }
+
+    /// Record what executed statements did to the transaction, asking the
+    /// database when PL/SQL ran or a transaction looks open.
+    pub fn note_transaction_effects(&mut self, effects: &[TransactionEffect]) {
+        let mut confirm = false;
+        for effect in effects {
+            confirm |= self.transaction.apply(effect);
+        }
+        if confirm || self.transaction.pending {
+            self.confirm_transaction();
+        }
+    }
+
+    /// Check `V$TRANSACTION` and correct the tracked state. Keeps the
+    /// tracked state when the database cannot be asked.
+    pub fn confirm_transaction(&mut self) -> bool {
+        if let Some(connection) = &self.connection {
+            if let Ok(open) = transaction_open(connection) {
+                self.transaction.pending = open;
+            }
+        }
+        self.transaction.pending
+    }
+
+    pub fn commit_transaction(&mut self) -> Result<(), OracleError> {
+        let Some(backend) = &self.backend else {
+            return Err(not_connected_error());
+        };
+        backend.commit()?;
+        self.transaction.clear();
+        Ok(())
+    }
+
+    pub fn rollback_transaction(&mut self) -> Result<(), OracleError> {
+        let Some(backend) = &self.backend else {
+            return Err(not_connected_error());
+        };
+        backend.rollback()?;
+        self.transaction.clear();
+        Ok(())
+    }
+
+    /// Grid edits and other DML run outside the script runner.
+    pub fn mark_transaction_pending(&mut self) {
+        if !self.auto_commit {
+            self.transaction.pending = true;
+        }
+    }
+
+    /// `name` must already be a valid identifier, see `savepoint_identifier`.
+    pub fn create_savepoint(&mut self, name: &str) -> Result<(), OracleError> {
+        self.run_transaction_statement(
+            &format!("SAVEPOINT {}", name),
+            TransactionEffect::Savepoint(name.to_string()),
+        )
+    }
+
+    pub fn rollback_to_savepoint(&mut self, name: &str) -> Result<(), OracleError> {
+        self.run_transaction_statement(
+            &format!("ROLLBACK TO SAVEPOINT {}", name),
+            TransactionEffect::RollbackToSavepoint(name.to_string()),
+        )
+    }
+
+    fn run_transaction_statement(
+        &mut self,
+        sql: &str,
+        effect: TransactionEffect,
+    ) -> Result<(), OracleError> {
+        let Some(backend) = &self.backend else {
+            return Err(not_connected_error());
+        };
+        if let Err(err) = backend.execute(sql, &[]) {
+            eprintln!("Database operation failed: {err}");
+            return Err(err);
+        }
+        self.transaction.apply(&effect);
+        Ok(())
+    }
+
     pub fn test_connection(info: &ConnectionInfo) -> Result<(), OracleError> {
-        let conn_str = info.connection_string();
-        match Connection::connect(&info.username, &info.password, &conn_str) {
+        match info.connector().connect() {
             Ok(_connection) => {}
             Err(err) => {
                 eprintln!("Connection error: {err}");
@@ -206,3 +662,127 @@ pub fn try_lock_connection(
         }
     }
 }
+
+#[cfg(test)]
+mod connection_tests {
+    use super::*;
+
+    #[test]
+    fn builds_connect_strings_per_connection_type() {
+        let mut info = ConnectionInfo::new("dev", "scott", "", "db1", 1522, "ORCL");
+        assert_eq!(info.connection_string(), "//db1:1522/ORCL");
+
+        info.connection_type = ConnectionType::Sid;
+        assert_eq!(
+            info.connection_string(),
+            "(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST=db1)(PORT=1522))(CONNECT_DATA=(SID=ORCL)))"
+        );
+        assert_eq!(info.display_string(), "dev (scott@@db1:1522:ORCL)");
+
+        info.connection_type = ConnectionType::TnsAlias;
+        info.tns_alias = " DEV_PDB ".to_string();
+        info.privilege = ConnectPrivilege::Sysdba;
+        assert_eq!(info.connection_string(), "DEV_PDB");
+        assert_eq!(info.display_string(), "dev (scott@@DEV_PDB) AS SYSDBA");
+
+        info.connection_type = ConnectionType::EasyConnect;
+        info.easy_connect = "tcps://db1:2484/svc?wallet_location=/w".to_string();
+        info.use_wallet = true;
+        assert_eq!(
+            info.connection_string(),
+            "tcps://db1:2484/svc?wallet_location=/w"
+        );
+        assert!(info.display_string().starts_with("dev (@@tcps://"));
+    }
+
+    #[test]
+    fn classifies_errors_that_end_the_session() {
+        assert!(is_connection_lost_error(
+            "ORA-03113: end-of-file on communication channel"
+        ));
+        assert!(is_connection_lost_error(
+            "DPI-1080: connection was closed by ORA-3113"
+        ));
+        assert!(!is_connection_lost_error(
+            "ORA-00942: table or view does not exist"
+        ));
+    }
+
+    #[test]
+    fn lost_connections_keep_their_details_until_disconnected() {
+        let mut connection = DatabaseConnection::new();
+        connection.mark_lost();
+        assert!(!connection.is_lost());
+
+        connection.connect_backend(
+            ConnectionInfo::new("dev", "scott", "tiger", "db1", 1521, "ORCL"),
+            Arc::new(crate::db::mock_backend::MockBackend::new()),
+        );
+        assert!(connection.ensure_alive(Duration::ZERO));
+        connection.mark_lost();
+        assert!(connection.is_lost());
+        assert!(!connection.is_connected());
+        assert_eq!(connection.get_info().name, "dev");
+
+        connection.disconnect();
+        assert!(!connection.is_lost());
+    }
+
+    #[test]
+    fn savepoints_and_commit_go_through_the_backend() {
+        let backend = Arc::new(crate::db::mock_backend::MockBackend::new());
+        let mut connection = DatabaseConnection::new();
+        connection.connect_backend(
+            ConnectionInfo::new("dev", "scott", "tiger", "db1", 1521, "ORCL"),
+            backend.clone(),
+        );
+        connection.note_transaction_effects(&[TransactionEffect::Begins]);
+        connection.create_savepoint("BEFORE_LOAD").unwrap();
+        connection.create_savepoint("AFTER_LOAD").unwrap();
+        connection.rollback_to_savepoint("BEFORE_LOAD").unwrap();
+        assert!(connection.transaction().pending);
+        assert_eq!(connection.transaction().savepoints, vec!["BEFORE_LOAD"]);
+
+        connection.commit_transaction().unwrap();
+        assert!(!connection.transaction().pending);
+        assert!(connection.transaction().savepoints.is_empty());
+        assert_eq!(backend.commit_count(), 1);
+        assert_eq!(
+            backend.executed(),
+            vec![
+                "SAVEPOINT BEFORE_LOAD",
+                "SAVEPOINT AFTER_LOAD",
+                "ROLLBACK TO SAVEPOINT BEFORE_LOAD"
+            ]
+        );
+    }
+
+    #[test]
+    fn profiles_saved_before_connection_types_still_load() {
+        let json = r#"{"name":"old","username":"u","host":"h","port":1521,"service_name":"S"}"#;
+        let info: ConnectionInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.connection_type, ConnectionType::ServiceName);
+        assert_eq!(info.privilege, ConnectPrivilege::Normal);
+        assert!(!info.use_wallet);
+        assert_eq!(info.connection_string(), "//h:1521/S");
+        assert_eq!(info.environment, ConnectionEnvironment::Untagged);
+        assert!(info.production_guards().is_none());
+    }
+
+    #[test]
+    fn environment_colors_fall_back_to_the_tag_default() {
+        let mut info = ConnectionInfo::new("prod", "app", "", "db1", 1521, "PRD");
+        assert_eq!(info.environment_rgb(), None);
+
+        info.environment = ConnectionEnvironment::Production;
+        assert_eq!(info.environment_rgb(), Some((0xc6, 0x28, 0x28)));
+        info.environment_color = "#00FF7f".to_string();
+        assert_eq!(info.environment_rgb(), Some((0x00, 0xff, 0x7f)));
+        info.environment_color = "red".to_string();
+        assert_eq!(info.environment_rgb(), Some((0xc6, 0x28, 0x28)));
+
+        let guards = info.production_guards().expect("production guards");
+        assert!(!guards.read_only);
+        assert!(guards.confirm_unfiltered_dml && guards.confirm_drop_truncate);
+    }
+}
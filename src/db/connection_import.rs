@@ -0,0 +1,738 @@
+//! Reading connections saved by other Oracle tools.
+//!
+//! Supported are SQL Developer's `connections.json` (19.x and later) and
+//! `connections.xml` (older releases), DBeaver's `data-sources.json` and the
+//! `CONNECTIONS.XML` Toad keeps and exports. SQL Developer and Toad store
+//! passwords encrypted, so only passwords DBeaver keeps in plain text come
+//! across.
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::db::{ConnectPrivilege, ConnectionEnvironment, ConnectionInfo, ConnectionType};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportSource {
+    SqlDeveloper,
+    DBeaver,
+    Toad,
+}
+
+impl ImportSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportSource::SqlDeveloper => "SQL Developer",
+            ImportSource::DBeaver => "DBeaver",
+            ImportSource::Toad => "Toad",
+        }
+    }
+}
+
+/// Connections read from one file.
+#[derive(Clone, Debug)]
+pub struct ConnectionImport {
+    pub source: ImportSource,
+    /// `password` is only set when the source held it readable.
+    pub connections: Vec<ConnectionInfo>,
+    /// Entries that were left out, with the reason.
+    pub skipped: Vec<String>,
+}
+
+/// Read and parse an export file, detecting the tool that wrote it.
+pub fn load_file(path: &Path) -> Result<ConnectionImport, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+    parse_connections(&text)
+}
+
+/// Parse export text, detecting the tool that wrote it.
+pub fn parse_connections(text: &str) -> Result<ConnectionImport, String> {
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('{') {
+        let root: Value =
+            serde_json::from_str(text).map_err(|err| format!("Invalid JSON: {err}"))?;
+        return match root.get("connections") {
+            Some(Value::Array(entries)) => Ok(sql_developer_json(entries)),
+            Some(Value::Object(_)) => Ok(dbeaver_json(&root)),
+            _ => Err("The JSON file holds no connections".to_string()),
+        };
+    }
+    if text.starts_with('<') {
+        let root = parse_xml(text)?;
+        if root.name.eq_ignore_ascii_case("References") {
+            return Ok(sql_developer_xml(&root));
+        }
+        if root.name.eq_ignore_ascii_case("Connections") {
+            return Ok(toad_xml(&root));
+        }
+        return Err(format!("Unrecognized XML document <{}>", root.name));
+    }
+    Err("Not a SQL Developer, DBeaver or Toad connection file".to_string())
+}
+
+/// Export files of the supported tools found in their default locations.
+pub fn known_export_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let Some(home) = dirs::home_dir() else {
+        return paths;
+    };
+    let mut push_existing = |path: PathBuf| {
+        if path.is_file() {
+            paths.push(path);
+        }
+    };
+
+    // SQL Developer: ~/.sqldeveloper/system<version>/o.jdeveloper.db.connection*/
+    let mut sql_developer_roots = vec![home.join(".sqldeveloper")];
+    if let Some(config_dir) = dirs::config_dir() {
+        sql_developer_roots.push(config_dir.join("SQL Developer"));
+    }
+    for root in sql_developer_roots {
+        for system in subdirectories(&root, "system") {
+            for connection_dir in subdirectories(&system, "o.jdeveloper.db.connection") {
+                push_existing(connection_dir.join("connections.json"));
+                push_existing(connection_dir.join("connections.xml"));
+            }
+        }
+    }
+
+    // DBeaver: <data dir>/DBeaverData/workspace6/<project>/.dbeaver/
+    let mut dbeaver_roots = vec![home.join(".dbeaver4")];
+    if let Some(data_dir) = dirs::data_dir() {
+        dbeaver_roots.push(data_dir.join("DBeaverData").join("workspace6"));
+    }
+    if let Some(config_dir) = dirs::config_dir() {
+        dbeaver_roots.push(config_dir.join("DBeaverData").join("workspace6"));
+    }
+    for root in dbeaver_roots {
+        for project in subdirectories(&root, "") {
+            push_existing(project.join(".dbeaver").join("data-sources.json"));
+        }
+    }
+
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn subdirectories(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(prefix))
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+/// A connection with nothing but a name, to be filled in by the readers.
+fn blank_connection(name: &str) -> ConnectionInfo {
+    ConnectionInfo::new(name.trim(), "", "", "", 1521, "")
+}
+
+fn privilege_from(role: &str) -> ConnectPrivilege {
+    match role.trim().to_uppercase().as_str() {
+        "SYSDBA" => ConnectPrivilege::Sysdba,
+        "SYSOPER" => ConnectPrivilege::Sysoper,
+        _ => ConnectPrivilege::Normal,
+    }
+}
+
+fn set_host_port(info: &mut ConnectionInfo, host: &str, port: &str) {
+    info.host = host.trim().to_string();
+    if let Ok(port) = port.trim().parse() {
+        info.port = port;
+    }
+}
+
+/// Point `info` at what a JDBC thin URL (or the part after `@`) names:
+/// `//host:port/service`, `host:port:sid`, `host:port/service`, a
+/// descriptor or a TNS alias. Returns false for an empty target.
+fn apply_jdbc_url(info: &mut ConnectionInfo, url: &str) -> bool {
+    let url = url.trim();
+    let target = url.split_once('@').map_or(url, |(_, target)| target).trim();
+    if target.is_empty() {
+        return false;
+    }
+    if target.starts_with('(') || target.contains("://") || target.contains('?') {
+        info.connection_type = ConnectionType::EasyConnect;
+        info.easy_connect = target.to_string();
+        return true;
+    }
+    let address = target.trim_start_matches("//");
+    if let Some((host_port, service)) = address.split_once('/') {
+        let (host, port) = host_port.split_once(':').unwrap_or((host_port, "1521"));
+        info.connection_type = ConnectionType::ServiceName;
+        set_host_port(info, host, port);
+        info.service_name = service.to_string();
+        return true;
+    }
+    let parts: Vec<&str> = address.split(':').collect();
+    if let [host, port, sid] = parts.as_slice() {
+        info.connection_type = ConnectionType::Sid;
+        set_host_port(info, host, port);
+        info.service_name = sid.to_string();
+        return true;
+    }
+    info.connection_type = ConnectionType::TnsAlias;
+    info.tns_alias = address.to_string();
+    true
+}
+
+/// Fill in a SQL Developer connection from its `addrType`/`info` keys.
+fn sql_developer_connection(
+    name: &str,
+    get: impl Fn(&str) -> Option<String>,
+) -> Result<ConnectionInfo, String> {
+    let field = |key: &str| get(key).unwrap_or_default();
+    let driver = field("RaptorConnectionType");
+    if !driver.is_empty() && driver != "Oracle" {
+        return Err(format!("{name}: {driver} connections are not supported"));
+    }
+    let mut info = blank_connection(name);
+    info.username = field("user");
+    info.privilege = privilege_from(&field("role"));
+    info.use_wallet = field("OS_AUTHENTICATION").eq_ignore_ascii_case("true");
+    let mapped = match field("OracleConnectionType").to_uppercase().as_str() {
+        "TNS" => {
+            let alias = get("TNSName")
+                .or_else(|| get("tnsName"))
+                .unwrap_or_else(|| field("customUrl"));
+            info.connection_type = ConnectionType::TnsAlias;
+            info.tns_alias = alias
+                .rsplit_once('@')
+                .map_or(alias.as_str(), |(_, alias)| alias)
+                .trim()
+                .to_string();
+            !info.tns_alias.is_empty()
+        }
+        "CUSTOM" => apply_jdbc_url(&mut info, &field("customUrl")),
+        _ => {
+            set_host_port(&mut info, &field("hostname"), &field("port"));
+            if let Some(service) = get("serviceName").filter(|value| !value.is_empty()) {
+                info.connection_type = ConnectionType::ServiceName;
+                info.service_name = service;
+            } else {
+                info.connection_type = ConnectionType::Sid;
+                info.service_name = field("sid");
+            }
+            !info.host.is_empty() && !info.service_name.is_empty()
+        }
+    };
+    if mapped {
+        Ok(info)
+    } else {
+        Err(format!("{name}: no database address"))
+    }
+}
+
+fn sql_developer_json(entries: &[Value]) -> ConnectionImport {
+    let mut import = ConnectionImport {
+        source: ImportSource::SqlDeveloper,
+        connections: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for entry in entries {
+        let info = entry.get("info");
+        let get = |key: &str| {
+            info.and_then(|info| info.get(key))
+                .and_then(json_text)
+                .map(|value| value.trim().to_string())
+        };
+        let name = entry
+            .get("name")
+            .and_then(json_text)
+            .or_else(|| get("ConnName"))
+            .unwrap_or_default();
+        match sql_developer_connection(&name, get) {
+            Ok(info) => import.connections.push(info),
+            Err(reason) => import.skipped.push(reason),
+        }
+    }
+    import
+}
+
+fn sql_developer_xml(root: &XmlElement) -> ConnectionImport {
+    let mut import = ConnectionImport {
+        source: ImportSource::SqlDeveloper,
+        connections: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for reference in root.children_named("Reference") {
+        let name = reference.attribute("name").unwrap_or_default();
+        let addresses: Vec<(String, String)> = reference
+            .descendants_named("StringRefAddr")
+            .into_iter()
+            .filter_map(|address| {
+                let key = address.attribute("addrType")?;
+                let value = address
+                    .child("Contents")
+                    .map(|contents| contents.text.trim().to_string())
+                    .unwrap_or_default();
+                Some((key.to_string(), value))
+            })
+            .collect();
+        let get = |key: &str| {
+            addresses
+                .iter()
+                .find(|(address_key, _)| address_key == key)
+                .map(|(_, value)| value.clone())
+        };
+        match sql_developer_connection(name, get) {
+            Ok(info) => import.connections.push(info),
+            Err(reason) => import.skipped.push(reason),
+        }
+    }
+    import
+}
+
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+fn dbeaver_json(root: &Value) -> ConnectionImport {
+    let mut import = ConnectionImport {
+        source: ImportSource::DBeaver,
+        connections: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let Some(entries) = root.get("connections").and_then(Value::as_object) else {
+        return import;
+    };
+    for (id, entry) in entries {
+        let text = |value: Option<&Value>| value.and_then(json_text).unwrap_or_default();
+        let name = entry
+            .get("name")
+            .and_then(json_text)
+            .unwrap_or_else(|| id.clone());
+        let provider = text(entry.get("provider"));
+        if !provider.eq_ignore_ascii_case("oracle") {
+            import
+                .skipped
+                .push(format!("{name}: {provider} connections are not supported"));
+            continue;
+        }
+        let configuration = entry.get("configuration").unwrap_or(&Value::Null);
+        let config = |key: &str| text(configuration.get(key)).trim().to_string();
+        let property = |key: &str| {
+            text(
+                configuration
+                    .get("provider-properties")
+                    .and_then(|properties| properties.get(key)),
+            )
+        };
+
+        let mut info = blank_connection(&name);
+        info.username = config("user");
+        if entry.get("save-password").and_then(Value::as_bool) != Some(false) {
+            info.password = text(configuration.get("password"));
+        }
+        info.privilege = privilege_from(&property("@dbeaver-role@"));
+        info.environment = match config("type").as_str() {
+            "dev" => ConnectionEnvironment::Development,
+            "test" => ConnectionEnvironment::Test,
+            "prod" => ConnectionEnvironment::Production,
+            _ => ConnectionEnvironment::Untagged,
+        };
+        let database = config("database");
+        let mapped = match property("@dbeaver-connection-type@")
+            .to_uppercase()
+            .as_str()
+        {
+            "TNS" => {
+                info.connection_type = ConnectionType::TnsAlias;
+                info.tns_alias = database;
+                !info.tns_alias.is_empty()
+            }
+            "CUSTOM" => apply_jdbc_url(&mut info, &config("url")),
+            _ if config("host").is_empty() => apply_jdbc_url(&mut info, &config("url")),
+            _ => {
+                set_host_port(&mut info, &config("host"), &config("port"));
+                info.connection_type = if property("@dbeaver-sid-service@") == "SID" {
+                    ConnectionType::Sid
+                } else {
+                    ConnectionType::ServiceName
+                };
+                info.service_name = database;
+                !info.service_name.is_empty()
+            }
+        };
+        if mapped {
+            import.connections.push(info);
+        } else {
+            import.skipped.push(format!("{name}: no database address"));
+        }
+    }
+    import
+}
+
+fn toad_xml(root: &XmlElement) -> ConnectionImport {
+    let mut import = ConnectionImport {
+        source: ImportSource::Toad,
+        connections: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for connection in root.children_named("Connection") {
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| connection.child(name))
+                .map(|element| element.text.trim().to_string())
+                .unwrap_or_default()
+        };
+        let username = field(&["UserName", "User"]);
+        let database = field(&["Database", "DBName", "TNSName"]);
+        let alias = field(&["Alias", "ConnectionName", "Name"]);
+        let name = if !alias.is_empty() {
+            alias
+        } else if database.is_empty() {
+            username.clone()
+        } else {
+            format!("{}@{}", username, database)
+        };
+
+        let mut info = blank_connection(&name);
+        info.username = username;
+        info.privilege = privilege_from(&field(&["ConnectAs", "Privilege"]));
+        info.use_wallet = field(&["OSAuthentication", "OSAuth"]).eq_ignore_ascii_case("true");
+        let host = field(&["Host"]);
+        let service = field(&["ServiceName", "Service"]);
+        let sid = field(&["SID"]);
+        let mapped = if field(&["Direct"]).eq_ignore_ascii_case("true") && !host.is_empty() {
+            set_host_port(&mut info, &host, &field(&["Port"]));
+            if service.is_empty() {
+                info.connection_type = ConnectionType::Sid;
+                info.service_name = sid;
+            } else {
+                info.connection_type = ConnectionType::ServiceName;
+                info.service_name = service;
+            }
+            !info.service_name.is_empty()
+        } else {
+            apply_jdbc_url(&mut info, &database)
+        };
+        if mapped {
+            import.connections.push(info);
+        } else {
+            import.skipped.push(format!("{name}: no database address"));
+        }
+    }
+    import
+}
+
+/// Just enough of an XML element tree for the connection files.
+#[derive(Debug, Default)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children
+            .iter()
+            .find(|child| child.name.eq_ignore_ascii_case(name))
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children
+            .iter()
+            .filter(move |child| child.name.eq_ignore_ascii_case(name))
+    }
+
+    fn descendants_named(&self, name: &str) -> Vec<&XmlElement> {
+        let mut found = Vec::new();
+        for child in &self.children {
+            if child.name.eq_ignore_ascii_case(name) {
+                found.push(child);
+            }
+            found.extend(child.descendants_named(name));
+        }
+        found
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let replacement = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match replacement {
+            Some(ch) => {
+                decoded.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn parse_attributes(text: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = text.trim();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_string();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|ch| *ch == '"' || *ch == '\'') else {
+            break;
+        };
+        let Some(close) = after[1..].find(quote) else {
+            break;
+        };
+        attributes.push((key, decode_entities(&after[1..close + 1])));
+        rest = after[close + 2..].trim_start();
+    }
+    attributes
+}
+
+/// Parse a document into its root element. Namespaces are kept as part of
+/// the name, comments, processing instructions and DOCTYPE are skipped.
+fn parse_xml(text: &str) -> Result<XmlElement, String> {
+    let mut stack: Vec<XmlElement> = vec![XmlElement::default()];
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        if let Some(current) = stack.last_mut() {
+            current.text.push_str(&decode_entities(&rest[..start]));
+        }
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after.find("-->").ok_or("Unterminated XML comment")?;
+            rest = &after[end + 3..];
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or("Unterminated CDATA section")?;
+            if let Some(current) = stack.last_mut() {
+                current.text.push_str(&after[..end]);
+            }
+            rest = &after[end + 3..];
+            continue;
+        }
+        let end = rest.find('>').ok_or("Unterminated XML tag")?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            let element = stack.pop().ok_or("Unbalanced XML")?;
+            if !element.name.eq_ignore_ascii_case(name.trim()) {
+                return Err(format!(
+                    "Expected </{}> but found </{}>",
+                    element.name,
+                    name.trim()
+                ));
+            }
+            stack
+                .last_mut()
+                .ok_or("Unbalanced XML")?
+                .children
+                .push(element);
+            continue;
+        }
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, attributes) = tag
+            .split_once(char::is_whitespace)
+            .map_or((tag, ""), |(name, attributes)| (name, attributes));
+        let element = XmlElement {
+            name: name.to_string(),
+            attributes: parse_attributes(attributes),
+            ..XmlElement::default()
+        };
+        if self_closing {
+            stack
+                .last_mut()
+                .ok_or("Unbalanced XML")?
+                .children
+                .push(element);
+        } else {
+            stack.push(element);
+        }
+    }
+    let mut document = stack.pop().ok_or("Unbalanced XML")?;
+    if !stack.is_empty() {
+        return Err(format!("Unclosed XML element <{}>", document.name));
+    }
+    document
+        .children
+        .pop()
+        .ok_or_else(|| "Empty XML document".to_string())
+}
+
+#[cfg(test)]
+mod connection_import_tests {
+    use super::*;
+
+    #[test]
+    fn sql_developer_json_maps_basic_tns_and_custom_connections() {
+        let import = parse_connections(
+            r#"{"connections":[
+                {"name":"HR","type":"jdbc","info":{"RaptorConnectionType":"Oracle",
+                 "OracleConnectionType":"BASIC","hostname":"db1","port":"1522",
+                 "serviceName":"hrpdb","user":"hr","password":"v2encrypted","role":"SYSDBA"}},
+                {"name":"Legacy","type":"jdbc","info":{"OracleConnectionType":"BASIC",
+                 "hostname":"db2","port":"1521","sid":"ORCL","user":"scott"}},
+                {"name":"Alias","type":"jdbc","info":{"OracleConnectionType":"TNS",
+                 "customUrl":"jdbc:oracle:thin:@PRODDB","user":"app"}},
+                {"name":"Cloud","type":"jdbc","info":{"OracleConnectionType":"CUSTOM",
+                 "customUrl":"jdbc:oracle:thin:@tcps://adb.example.com:1522/x_high?wallet_location=/w"}},
+                {"name":"Pg","type":"jdbc","info":{"RaptorConnectionType":"PostgreSQL"}}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(import.source, ImportSource::SqlDeveloper);
+        let hr = &import.connections[0];
+        assert_eq!(
+            (hr.host.as_str(), hr.port, hr.service_name.as_str()),
+            ("db1", 1522, "hrpdb")
+        );
+        assert_eq!(hr.connection_type, ConnectionType::ServiceName);
+        assert_eq!(hr.privilege, ConnectPrivilege::Sysdba);
+        assert_eq!(hr.password, "");
+        assert_eq!(import.connections[1].connection_type, ConnectionType::Sid);
+        assert_eq!(import.connections[1].service_name, "ORCL");
+        assert_eq!(import.connections[2].tns_alias, "PRODDB");
+        assert_eq!(
+            import.connections[3].easy_connect,
+            "tcps://adb.example.com:1522/x_high?wallet_location=/w"
+        );
+        assert_eq!(import.skipped.len(), 1);
+    }
+
+    #[test]
+    fn sql_developer_xml_reads_ref_addresses() {
+        let import = parse_connections(
+            r#"<?xml version = '1.0' encoding = 'UTF-8'?>
+            <References xmlns="http://xmlns.oracle.com/adf/jndi">
+              <Reference name="Sales &amp; Ops" className="oracle.jdeveloper.db.adapter.DatabaseProvider" xmlns="">
+                <Factory className="oracle.jdevimpl.db.adapter.DatabaseProviderFactory1212"/>
+                <RefAddresses>
+                  <StringRefAddr addrType="user"><Contents>sales</Contents></StringRefAddr>
+                  <StringRefAddr addrType="hostname"><Contents>db3</Contents></StringRefAddr>
+                  <StringRefAddr addrType="port"><Contents>1521</Contents></StringRefAddr>
+                  <StringRefAddr addrType="serviceName"><Contents>sales</Contents></StringRefAddr>
+                  <!-- <StringRefAddr addrType="role"><Contents>SYSDBA</Contents></StringRefAddr> -->
+                </RefAddresses>
+              </Reference>
+            </References>"#,
+        )
+        .unwrap();
+
+        assert_eq!(import.connections.len(), 1);
+        let info = &import.connections[0];
+        assert_eq!(info.name, "Sales & Ops");
+        assert_eq!(info.username, "sales");
+        assert_eq!(info.target_string(), "db3:1521/sales");
+        assert_eq!(info.privilege, ConnectPrivilege::Normal);
+    }
+
+    #[test]
+    fn dbeaver_keeps_oracle_connections_and_plain_passwords() {
+        let import = parse_connections(
+            r#"{"folders":{},"connections":{
+                "oracle_thin-1":{"provider":"oracle","driver":"oracle_thin","name":"Billing",
+                 "save-password":true,"configuration":{"host":"db4","port":"1521",
+                 "database":"BILL","url":"jdbc:oracle:thin:@db4:1521/BILL","type":"prod",
+                 "user":"billing","password":"secret",
+                 "provider-properties":{"@dbeaver-sid-service@":"SERVICE",
+                 "@dbeaver-connection-type@":"BASIC"}}},
+                "oracle_thin-2":{"provider":"oracle","name":"Warehouse",
+                 "configuration":{"database":"DWH","type":"dev",
+                 "provider-properties":{"@dbeaver-connection-type@":"TNS"}}},
+                "postgres-jdbc-3":{"provider":"postgresql","name":"Pg","configuration":{}}
+            }}"#,
+        )
+        .unwrap();
+
+        assert_eq!(import.source, ImportSource::DBeaver);
+        let billing = &import.connections[0];
+        assert_eq!(billing.target_string(), "db4:1521/BILL");
+        assert_eq!(billing.password, "secret");
+        assert_eq!(billing.environment, ConnectionEnvironment::Production);
+        let warehouse = &import.connections[1];
+        assert_eq!(warehouse.connection_type, ConnectionType::TnsAlias);
+        assert_eq!(warehouse.tns_alias, "DWH");
+        assert_eq!(warehouse.environment, ConnectionEnvironment::Development);
+        assert_eq!(
+            import.skipped,
+            vec!["Pg: postgresql connections are not supported"]
+        );
+    }
+
+    #[test]
+    fn toad_connections_map_direct_and_tns_entries() {
+        let import = parse_connections(
+            r#"<Connections>
+              <Connection>
+                <UserName>SCOTT</UserName><Password>5A8C</Password>
+                <Database>ORCL</Database><ConnectAs>SYSOPER</ConnectAs>
+              </Connection>
+              <Connection>
+                <UserName>HR</UserName><Alias>HR direct</Alias><Direct>True</Direct>
+                <Host>db5</Host><Port>1525</Port><SID>HRDB</SID>
+              </Connection>
+              <Connection><UserName>NOBODY</UserName></Connection>
+            </Connections>"#,
+        )
+        .unwrap();
+
+        assert_eq!(import.source, ImportSource::Toad);
+        let scott = &import.connections[0];
+        assert_eq!(scott.name, "SCOTT@ORCL");
+        assert_eq!(scott.tns_alias, "ORCL");
+        assert_eq!(scott.password, "");
+        assert_eq!(scott.privilege, ConnectPrivilege::Sysoper);
+        let hr = &import.connections[1];
+        assert_eq!(hr.name, "HR direct");
+        assert_eq!(hr.connection_type, ConnectionType::Sid);
+        assert_eq!(hr.target_string(), "db5:1525:HRDB");
+        assert_eq!(import.skipped, vec!["NOBODY: no database address"]);
+        assert!(parse_connections("<Other/>").is_err());
+    }
+}
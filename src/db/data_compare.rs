@@ -0,0 +1,633 @@
+//! Row-level comparison of one table on two connections.
+//!
+//! The source side is streamed into memory keyed by the compare key, then
+//! the target side is streamed past it. Rows are matched by key and the
+//! columns both sides share are compared by value.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use oracle::Connection;
+
+use super::{quote_identifier, sql_literal, CellValue, ColumnInfo, ObjectBrowser, QueryExecutor};
+
+/// Differing rows kept for the drill-down and the script; further rows are
+/// only counted.
+pub const MAX_RETAINED_DIFFERENCES: usize = 10_000;
+
+/// Rows fetched between two progress reports.
+const PROGRESS_INTERVAL: usize = 5_000;
+
+/// One side of a data comparison.
+#[derive(Debug, Clone)]
+pub struct DataCompareSide {
+    /// Owner in dictionary form, or `None` for the connected user.
+    pub owner: Option<String>,
+    /// Table name in dictionary form.
+    pub table: String,
+}
+
+impl DataCompareSide {
+    /// Quoted table reference, qualified when an owner is set.
+    pub fn sql_name(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!(
+                "{}.{}",
+                quote_identifier(owner),
+                quote_identifier(&self.table)
+            ),
+            None => quote_identifier(&self.table),
+        }
+    }
+}
+
+/// A row that differs between the two sides. Values are in the order of
+/// [`DataCompareResult::columns`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowDifference {
+    OnlyInSource(Vec<CellValue>),
+    OnlyInTarget(Vec<CellValue>),
+    Changed {
+        source: Vec<CellValue>,
+        target: Vec<CellValue>,
+        /// Indexes of the columns whose values differ.
+        columns: Vec<usize>,
+    },
+}
+
+impl RowDifference {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RowDifference::OnlyInSource(_) => "Missing",
+            RowDifference::OnlyInTarget(_) => "Extra",
+            RowDifference::Changed { .. } => "Different",
+        }
+    }
+
+    /// The row as it exists on either side, for its key values.
+    pub fn any_row(&self) -> &[CellValue] {
+        match self {
+            RowDifference::OnlyInSource(row) | RowDifference::OnlyInTarget(row) => row,
+            RowDifference::Changed { source, .. } => source,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DataCompareResult {
+    /// Columns present on both sides, in source order.
+    pub columns: Vec<ColumnInfo>,
+    /// Indexes into `columns` of the key columns.
+    pub key_columns: Vec<usize>,
+    /// Columns that exist on only one side and were not compared.
+    pub ignored_columns: Vec<String>,
+    pub source_rows: usize,
+    pub target_rows: usize,
+    pub matching_rows: usize,
+    pub only_in_source: usize,
+    pub only_in_target: usize,
+    pub changed: usize,
+    /// The first [`MAX_RETAINED_DIFFERENCES`] differing rows.
+    pub differences: Vec<RowDifference>,
+}
+
+impl DataCompareResult {
+    pub fn difference_count(&self) -> usize {
+        self.only_in_source + self.only_in_target + self.changed
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.differences.len() < self.difference_count()
+    }
+
+    /// Key values of a row, as `NAME = value` pairs.
+    pub fn describe_key(&self, row: &[CellValue]) -> String {
+        self.key_columns
+            .iter()
+            .map(|&index| {
+                format!(
+                    "{} = {}",
+                    self.columns[index].name,
+                    row.get(index).map_or("NULL", |value| value.display("NULL"))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+type RowKey = Vec<Option<String>>;
+
+/// Matches rows of the two sides as they are streamed in: every source
+/// row first, then every target row.
+pub struct RowComparer {
+    key_names: Vec<String>,
+    source_columns: Vec<ColumnInfo>,
+    source_key: Vec<usize>,
+    /// Source rows in fetch order; matched rows are taken out.
+    source_rows: Vec<Option<Vec<CellValue>>>,
+    source_index: HashMap<RowKey, usize>,
+    /// For each common column, its index on the source and target side.
+    common: Vec<(usize, usize)>,
+    target_key: Vec<usize>,
+    result: DataCompareResult,
+}
+
+fn column_position(columns: &[ColumnInfo], name: &str) -> Option<usize> {
+    columns.iter().position(|column| column.name == name)
+}
+
+fn row_key(row: &[CellValue], key: &[usize]) -> RowKey {
+    key.iter()
+        .map(|&index| {
+            row.get(index)
+                .and_then(|value| value.as_str().map(str::to_string))
+        })
+        .collect()
+}
+
+fn describe_row_key(names: &[String], key: &RowKey) -> String {
+    names
+        .iter()
+        .zip(key)
+        .map(|(name, value)| format!("{} = {}", name, value.as_deref().unwrap_or("NULL")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl RowComparer {
+    pub fn new(key_names: Vec<String>) -> Self {
+        Self {
+            key_names,
+            source_columns: Vec::new(),
+            source_key: Vec::new(),
+            source_rows: Vec::new(),
+            source_index: HashMap::new(),
+            common: Vec::new(),
+            target_key: Vec::new(),
+            result: DataCompareResult::default(),
+        }
+    }
+
+    fn key_positions(&self, columns: &[ColumnInfo], side: &str) -> Result<Vec<usize>, String> {
+        self.key_names
+            .iter()
+            .map(|name| {
+                column_position(columns, name).ok_or_else(|| {
+                    format!("Key column {} does not exist in the {} table", name, side)
+                })
+            })
+            .collect()
+    }
+
+    pub fn start_source(&mut self, columns: &[ColumnInfo]) -> Result<(), String> {
+        self.source_key = self.key_positions(columns, "source")?;
+        self.source_columns = columns.to_vec();
+        Ok(())
+    }
+
+    pub fn add_source(&mut self, row: Vec<CellValue>) -> Result<(), String> {
+        let key = row_key(&row, &self.source_key);
+        if self.source_index.contains_key(&key) {
+            return Err(format!(
+                "The key does not identify source rows uniquely ({})",
+                describe_row_key(&self.key_names, &key)
+            ));
+        }
+        self.source_index.insert(key, self.source_rows.len());
+        self.source_rows.push(Some(row));
+        self.result.source_rows += 1;
+        Ok(())
+    }
+
+    pub fn start_target(&mut self, columns: &[ColumnInfo]) -> Result<(), String> {
+        self.target_key = self.key_positions(columns, "target")?;
+        for (source_index, column) in self.source_columns.iter().enumerate() {
+            match column_position(columns, &column.name) {
+                Some(target_index) => {
+                    self.common.push((source_index, target_index));
+                    self.result.columns.push(column.clone());
+                }
+                None => self.result.ignored_columns.push(column.name.clone()),
+            }
+        }
+        for column in columns {
+            if column_position(&self.source_columns, &column.name).is_none() {
+                self.result.ignored_columns.push(column.name.clone());
+            }
+        }
+        self.result.key_columns = self
+            .key_names
+            .iter()
+            .filter_map(|name| column_position(&self.result.columns, name))
+            .collect();
+        Ok(())
+    }
+
+    fn project(&self, row: &[CellValue], target_side: bool) -> Vec<CellValue> {
+        self.common
+            .iter()
+            .map(|&(source, target)| {
+                let index = if target_side { target } else { source };
+                row.get(index).cloned().unwrap_or(CellValue::Null)
+            })
+            .collect()
+    }
+
+    fn record(&mut self, difference: RowDifference) {
+        if self.result.differences.len() < MAX_RETAINED_DIFFERENCES {
+            self.result.differences.push(difference);
+        }
+    }
+
+    pub fn add_target(&mut self, row: Vec<CellValue>) -> Result<(), String> {
+        self.result.target_rows += 1;
+        let key = row_key(&row, &self.target_key);
+        let Some(index) = self.source_index.get(&key).copied() else {
+            self.result.only_in_target += 1;
+            let target = self.project(&row, true);
+            self.record(RowDifference::OnlyInTarget(target));
+            return Ok(());
+        };
+        let Some(source_row) = self.source_rows[index].take() else {
+            return Err(format!(
+                "The key does not identify target rows uniquely ({})",
+                describe_row_key(&self.key_names, &key)
+            ));
+        };
+        let source = self.project(&source_row, false);
+        let target = self.project(&row, true);
+        // Values are compared as text so that a NUMBER column matches a
+        // FLOAT one holding the same value.
+        let columns: Vec<usize> = (0..source.len())
+            .filter(|&index| source[index].as_str() != target[index].as_str())
+            .collect();
+        if columns.is_empty() {
+            self.result.matching_rows += 1;
+        } else {
+            self.result.changed += 1;
+            self.record(RowDifference::Changed {
+                source,
+                target,
+                columns,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> DataCompareResult {
+        let rows = std::mem::take(&mut self.source_rows);
+        for row in rows.into_iter().flatten() {
+            self.result.only_in_source += 1;
+            let source = self.project(&row, false);
+            self.record(RowDifference::OnlyInSource(source));
+        }
+        self.result
+    }
+}
+
+/// Primary key columns of a table, in key order.
+pub fn primary_key_columns(
+    conn: &Connection,
+    side: &DataCompareSide,
+) -> Result<Vec<String>, String> {
+    let constraints = match ObjectBrowser::get_table_constraints_for_owner(
+        conn,
+        side.owner.as_deref(),
+        &side.table,
+    ) {
+        Ok(constraints) => constraints,
+        Err(err) => {
+            eprintln!("Database operation failed: {err}");
+            return Err(err.to_string());
+        }
+    };
+    constraints
+        .iter()
+        .find(|constraint| constraint.constraint_type == "PRIMARY KEY")
+        .map(|constraint| {
+            constraint
+                .columns
+                .split(',')
+                .map(|column| column.trim().to_string())
+                .collect()
+        })
+        .ok_or_else(|| format!("{} has no primary key; enter the key columns", side.table))
+}
+
+fn stream_side(
+    conn: &Connection,
+    side: &DataCompareSide,
+    on_start: &mut dyn FnMut(&[ColumnInfo]) -> Result<(), String>,
+    on_row: &mut dyn FnMut(Vec<CellValue>) -> Result<(), String>,
+    progress: &mut dyn FnMut(usize),
+) -> Result<(), String> {
+    // Rows are matched by hashed key, so they can come in any order.
+    let sql = format!("SELECT * FROM {}", side.sql_name());
+    let failure: RefCell<Option<String>> = RefCell::new(None);
+    let mut fetched = 0usize;
+    let outcome = QueryExecutor::execute_select_streaming(
+        conn,
+        &sql,
+        &mut |columns: &[ColumnInfo]| {
+            if let Err(err) = on_start(columns) {
+                *failure.borrow_mut() = Some(err);
+            }
+        },
+        &mut |row: Vec<CellValue>| {
+            if failure.borrow().is_some() {
+                return false;
+            }
+            if let Err(err) = on_row(row) {
+                *failure.borrow_mut() = Some(err);
+                return false;
+            }
+            fetched += 1;
+            if fetched.is_multiple_of(PROGRESS_INTERVAL) {
+                progress(fetched);
+            }
+            true
+        },
+    );
+    match outcome {
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("Database operation failed: {err}");
+            return Err(err.to_string());
+        }
+    }
+    match failure.into_inner() {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Compare the rows of `source` on `source_conn` with those of `target` on
+/// `target_conn`, matching rows by `key_names`. `progress` receives the
+/// side being read and the rows fetched so far.
+pub fn compare_table_data(
+    source_conn: &Connection,
+    source: &DataCompareSide,
+    target_conn: &Connection,
+    target: &DataCompareSide,
+    key_names: Vec<String>,
+    progress: &mut dyn FnMut(&str, usize),
+) -> Result<DataCompareResult, String> {
+    // Both callbacks of a side feed the comparer.
+    let comparer = RefCell::new(RowComparer::new(key_names));
+    stream_side(
+        source_conn,
+        source,
+        &mut |columns| comparer.borrow_mut().start_source(columns),
+        &mut |row| comparer.borrow_mut().add_source(row),
+        &mut |rows| progress("source", rows),
+    )?;
+    stream_side(
+        target_conn,
+        target,
+        &mut |columns| comparer.borrow_mut().start_target(columns),
+        &mut |row| comparer.borrow_mut().add_target(row),
+        &mut |rows| progress("target", rows),
+    )?;
+    Ok(comparer.into_inner().finish())
+}
+
+fn key_condition(result: &DataCompareResult, row: &[CellValue]) -> String {
+    result
+        .key_columns
+        .iter()
+        .map(|&index| {
+            let column = quote_identifier(&result.columns[index].name);
+            match &row[index] {
+                CellValue::Null => format!("{column} IS NULL"),
+                value => format!("{column} = {}", sql_literal(value)),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// DML that makes the target table hold the source rows: INSERTs for
+/// missing rows, MERGEs for changed ones and DELETEs for extra ones. Only
+/// the retained differences are covered.
+pub fn data_sync_script(
+    result: &DataCompareResult,
+    target: &DataCompareSide,
+    source_label: &str,
+    target_label: &str,
+) -> String {
+    let table = target.sql_name();
+    let names: Vec<String> = result
+        .columns
+        .iter()
+        .map(|column| quote_identifier(&column.name))
+        .collect();
+    let mut script = format!(
+        "-- Data synchronization of {}\n-- Source: {}\n-- Target: {}\n",
+        table, source_label, target_label
+    );
+    if result.is_truncated() {
+        script.push_str(&format!(
+            "-- Only the first {} of {} differing rows are included\n",
+            result.differences.len(),
+            result.difference_count()
+        ));
+    }
+    if !result.ignored_columns.is_empty() {
+        script.push_str(&format!(
+            "-- Columns not on both sides are left alone: {}\n",
+            result.ignored_columns.join(", ")
+        ));
+    }
+    script.push('\n');
+
+    for difference in &result.differences {
+        match difference {
+            RowDifference::OnlyInSource(row) => {
+                let values: Vec<String> = row.iter().map(sql_literal).collect();
+                script.push_str(&format!(
+                    "INSERT INTO {} ({}) VALUES ({});\n",
+                    table,
+                    names.join(", "),
+                    values.join(", ")
+                ));
+            }
+            RowDifference::Changed {
+                source, columns, ..
+            } => {
+                let selected: Vec<String> = result
+                    .key_columns
+                    .iter()
+                    .chain(columns)
+                    .map(|&index| format!("{} {}", sql_literal(&source[index]), names[index]))
+                    .collect();
+                // `=` never matches a NULL key value.
+                let on: Vec<String> = result
+                    .key_columns
+                    .iter()
+                    .map(|&index| match source[index] {
+                        CellValue::Null => format!("t.{} IS NULL", names[index]),
+                        _ => format!("t.{0} = s.{0}", names[index]),
+                    })
+                    .collect();
+                let set: Vec<String> = columns
+                    .iter()
+                    .map(|&index| format!("t.{0} = s.{0}", names[index]))
+                    .collect();
+                script.push_str(&format!(
+                    "MERGE INTO {} t\nUSING (SELECT {} FROM dual) s\nON ({})\nWHEN MATCHED THEN UPDATE SET {};\n",
+                    table,
+                    selected.join(", "),
+                    on.join(" AND "),
+                    set.join(", ")
+                ));
+            }
+            RowDifference::OnlyInTarget(row) => {
+                script.push_str(&format!(
+                    "DELETE FROM {} WHERE {};\n",
+                    table,
+                    key_condition(result, row)
+                ));
+            }
+        }
+    }
+    script
+}
+
+#[cfg(test)]
+mod data_compare_tests {
+    use super::*;
+    use crate::db::CellKind;
+
+    fn column(name: &str, kind: CellKind) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: String::new(),
+            kind,
+        }
+    }
+
+    fn number(value: &str) -> CellValue {
+        CellValue::Number(value.to_string())
+    }
+
+    fn text(value: &str) -> CellValue {
+        CellValue::Text(value.to_string())
+    }
+
+    fn compare() -> DataCompareResult {
+        let mut comparer = RowComparer::new(vec!["ID".to_string()]);
+        comparer
+            .start_source(&[
+                column("ID", CellKind::Number),
+                column("NAME", CellKind::Text),
+                column("NOTE", CellKind::Text),
+            ])
+            .unwrap();
+        for (id, name) in [("1", "one"), ("2", "two"), ("3", "three")] {
+            comparer
+                .add_source(vec![number(id), text(name), CellValue::Null])
+                .unwrap();
+        }
+        comparer
+            .start_target(&[
+                column("NAME", CellKind::Text),
+                column("ID", CellKind::Number),
+            ])
+            .unwrap();
+        for (id, name) in [("2", "TWO"), ("3", "three"), ("4", "four")] {
+            comparer.add_target(vec![text(name), number(id)]).unwrap();
+        }
+        comparer.finish()
+    }
+
+    #[test]
+    fn rows_are_matched_by_key_across_column_orders() {
+        let result = compare();
+        assert_eq!(result.ignored_columns, vec!["NOTE".to_string()]);
+        assert_eq!(
+            (
+                result.source_rows,
+                result.target_rows,
+                result.matching_rows,
+                result.only_in_source,
+                result.only_in_target,
+                result.changed
+            ),
+            (3, 3, 1, 1, 1, 1)
+        );
+        assert_eq!(
+            result.differences,
+            vec![
+                RowDifference::Changed {
+                    source: vec![number("2"), text("two")],
+                    target: vec![number("2"), text("TWO")],
+                    columns: vec![1],
+                },
+                RowDifference::OnlyInTarget(vec![number("4"), text("four")]),
+                RowDifference::OnlyInSource(vec![number("1"), text("one")]),
+            ]
+        );
+        assert_eq!(result.describe_key(&[number("4"), text("four")]), "ID = 4");
+    }
+
+    #[test]
+    fn duplicate_keys_are_rejected() {
+        let mut comparer = RowComparer::new(vec!["ID".to_string()]);
+        comparer
+            .start_source(&[column("ID", CellKind::Number)])
+            .unwrap();
+        comparer.add_source(vec![number("1")]).unwrap();
+        assert!(comparer.add_source(vec![number("1")]).is_err());
+        assert!(RowComparer::new(vec!["MISSING".to_string()])
+            .start_source(&[column("ID", CellKind::Number)])
+            .is_err());
+    }
+
+    #[test]
+    fn script_inserts_merges_and_deletes() {
+        let result = compare();
+        let target = DataCompareSide {
+            owner: Some("APP".to_string()),
+            table: "ITEMS".to_string(),
+        };
+        let script = data_sync_script(&result, &target, "dev", "prod");
+        assert!(script.contains("-- Columns not on both sides are left alone: NOTE\n"));
+        assert!(script.contains(
+            "MERGE INTO \"APP\".\"ITEMS\" t\nUSING (SELECT 2 \"ID\", 'two' \"NAME\" FROM dual) s\nON (t.\"ID\" = s.\"ID\")\nWHEN MATCHED THEN UPDATE SET t.\"NAME\" = s.\"NAME\";\n"
+        ));
+        assert!(script.contains("DELETE FROM \"APP\".\"ITEMS\" WHERE \"ID\" = 4;\n"));
+        assert!(script
+            .ends_with("INSERT INTO \"APP\".\"ITEMS\" (\"ID\", \"NAME\") VALUES (1, 'one');\n"));
+    }
+
+    #[test]
+    fn merge_matches_null_key_values() {
+        let mut comparer = RowComparer::new(vec!["REGION".to_string(), "ID".to_string()]);
+        let columns = [
+            column("REGION", CellKind::Text),
+            column("ID", CellKind::Number),
+            column("NAME", CellKind::Text),
+        ];
+        comparer.start_source(&columns).unwrap();
+        comparer
+            .add_source(vec![CellValue::Null, number("1"), text("one")])
+            .unwrap();
+        comparer.start_target(&columns).unwrap();
+        comparer
+            .add_target(vec![CellValue::Null, number("1"), text("ONE")])
+            .unwrap();
+        let result = comparer.finish();
+        assert_eq!(result.changed, 1);
+
+        let target = DataCompareSide {
+            owner: None,
+            table: "ITEMS".to_string(),
+        };
+        let script = data_sync_script(&result, &target, "dev", "prod");
+        assert!(
+            script.contains("ON (t.\"REGION\" IS NULL AND t.\"ID\" = s.\"ID\")\n"),
+            "{script}"
+        );
+    }
+}
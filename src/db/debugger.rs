@@ -0,0 +1,913 @@
+//! PL/SQL debugging through `DBMS_DEBUG`.
+//!
+//! A debug run uses two sessions of its own: the target session runs the
+//! script with debugging switched on, and the controller session attaches
+//! to it to set breakpoints, step and read values. Both are separate from
+//! the session of the query tab the run was started from.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use oracle::sql_type::OracleType;
+use oracle::{Connection, Error as OracleError};
+
+use super::{
+    lock_connection, BatchOutcome, QueryExecutor, QueryProgress, ScriptEventSink, ScriptRunner,
+    SharedConnection,
+};
+
+/// Longest variable value read back from the target.
+const MAX_VALUE_LENGTH: u32 = 4000;
+
+/// Where a program unit's breakpoints live in `DBMS_DEBUG`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DebugNamespace {
+    /// Package and type specs, and standalone procedures and functions.
+    TopLevel,
+    /// Package and type bodies.
+    Body,
+    Trigger,
+}
+
+impl DebugNamespace {
+    pub fn for_object_type(object_type: &str) -> Self {
+        match object_type {
+            "PACKAGE BODY" | "TYPE BODY" => DebugNamespace::Body,
+            "TRIGGER" => DebugNamespace::Trigger,
+            _ => DebugNamespace::TopLevel,
+        }
+    }
+
+    fn plsql_constant(self) -> &'static str {
+        match self {
+            DebugNamespace::TopLevel => "sys.dbms_debug.namespace_pkgspec_or_toplevel",
+            DebugNamespace::Body => "sys.dbms_debug.namespace_pkg_body",
+            DebugNamespace::Trigger => "sys.dbms_debug.namespace_trigger",
+        }
+    }
+
+    fn source_types(self) -> &'static str {
+        match self {
+            DebugNamespace::TopLevel => "'PROCEDURE', 'FUNCTION', 'PACKAGE', 'TYPE'",
+            DebugNamespace::Body => "'PACKAGE BODY', 'TYPE BODY'",
+            DebugNamespace::Trigger => "'TRIGGER'",
+        }
+    }
+}
+
+/// A stored program unit, identified the way `DBMS_DEBUG` names it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DebugUnit {
+    /// Owner in dictionary form; `None` for the connected user.
+    pub owner: Option<String>,
+    pub name: String,
+    pub namespace: DebugNamespace,
+}
+
+impl DebugUnit {
+    /// Whether `other` names the same unit, treating a missing owner as
+    /// matching any owner.
+    pub fn matches(&self, other: &DebugUnit) -> bool {
+        self.name == other.name
+            && self.namespace == other.namespace
+            && match (&self.owner, &other.owner) {
+                (Some(left), Some(right)) => left == right,
+                _ => true,
+            }
+    }
+}
+
+/// A `CREATE` statement for a program unit found in editor text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceUnit {
+    pub unit: DebugUnit,
+    pub object_type: String,
+    /// 1-based editor line holding line 1 of the stored source.
+    pub first_line: usize,
+    /// Last editor line of the unit.
+    pub last_line: usize,
+}
+
+impl SourceUnit {
+    /// The unit line for an editor line inside this unit.
+    pub fn unit_line(&self, editor_line: usize) -> Option<usize> {
+        (self.first_line..=self.last_line)
+            .contains(&editor_line)
+            .then(|| editor_line - self.first_line + 1)
+    }
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '$' | '#')
+}
+
+/// Whitespace separated words of `text` with their byte offsets.
+fn words_with_offsets(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+    for (index, ch) in text.char_indices() {
+        if ch.is_whitespace() {
+            if let Some(begin) = start.take() {
+                words.push((begin, &text[begin..index]));
+            }
+        } else if start.is_none() {
+            start = Some(index);
+        }
+    }
+    if let Some(begin) = start {
+        words.push((begin, &text[begin..]));
+    }
+    words
+}
+
+/// Program units created by `text`, in order. The stored source starts at
+/// the unit's type keyword, so that is where unit line 1 is anchored.
+pub fn source_units(text: &str) -> Vec<SourceUnit> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset);
+    let line_count = line_starts.len();
+
+    let mut units: Vec<SourceUnit> = Vec::new();
+    let mut terminators: Vec<usize> = Vec::new();
+    for (line_index, &start) in line_starts.iter().enumerate() {
+        let end = line_starts
+            .get(line_index + 1)
+            .map_or(text.len(), |next| next - 1);
+        let line = &text[start..end];
+        let trimmed = line.trim();
+        if trimmed == "/" {
+            terminators.push(line_index + 1);
+            continue;
+        }
+        if !trimmed
+            .get(..6)
+            .is_some_and(|word| word.eq_ignore_ascii_case("CREATE"))
+        {
+            continue;
+        }
+        let rest = &text[start..];
+        let Some(object) = QueryExecutor::parse_compiled_object(rest) else {
+            continue;
+        };
+        let type_keyword = object.object_type.split(' ').next().unwrap_or_default();
+        let Some(&(offset, _)) = words_with_offsets(rest)
+            .iter()
+            .skip(1)
+            .find(|(_, word)| word.eq_ignore_ascii_case(type_keyword))
+        else {
+            continue;
+        };
+        units.push(SourceUnit {
+            unit: DebugUnit {
+                owner: object.owner.clone(),
+                name: object.name.clone(),
+                namespace: DebugNamespace::for_object_type(&object.object_type),
+            },
+            object_type: object.object_type,
+            first_line: line_of(start + offset),
+            last_line: line_count,
+        });
+    }
+    let starts: Vec<usize> = units.iter().map(|unit| unit.first_line).collect();
+    for (index, unit) in units.iter_mut().enumerate() {
+        let next_start = starts.get(index + 1).copied().unwrap_or(line_count + 1);
+        let terminator = terminators
+            .iter()
+            .copied()
+            .find(|&line| line > unit.first_line && line < next_start);
+        unit.last_line = terminator.unwrap_or(next_start).saturating_sub(1);
+    }
+    units
+}
+
+/// The unit and unit line of an editor line.
+pub fn unit_location(units: &[SourceUnit], editor_line: usize) -> Option<(&SourceUnit, usize)> {
+    units
+        .iter()
+        .find_map(|unit| unit.unit_line(editor_line).map(|line| (unit, line)))
+}
+
+/// A routine a debug run can call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPoint {
+    pub owner: Option<String>,
+    pub package: Option<String>,
+    pub name: String,
+}
+
+impl EntryPoint {
+    pub fn qualified_name(&self) -> String {
+        let mut parts: Vec<&str> = Vec::new();
+        if let Some(owner) = &self.owner {
+            parts.push(owner);
+        }
+        if let Some(package) = &self.package {
+            parts.push(package);
+        }
+        parts.push(&self.name);
+        parts.join(".")
+    }
+}
+
+/// Standalone procedures and functions created in `text`, and the
+/// routines declared in its package bodies.
+pub fn entry_points(text: &str) -> Vec<EntryPoint> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut points: Vec<EntryPoint> = Vec::new();
+    for unit in source_units(text) {
+        match unit.object_type.as_str() {
+            "PROCEDURE" | "FUNCTION" => points.push(EntryPoint {
+                owner: unit.unit.owner.clone(),
+                package: None,
+                name: unit.unit.name.clone(),
+            }),
+            "PACKAGE BODY" => {
+                for line in lines.iter().take(unit.last_line).skip(unit.first_line) {
+                    let words = words_with_offsets(line);
+                    let (Some((_, keyword)), Some((_, name))) = (words.first(), words.get(1))
+                    else {
+                        continue;
+                    };
+                    if !(keyword.eq_ignore_ascii_case("PROCEDURE")
+                        || keyword.eq_ignore_ascii_case("FUNCTION"))
+                    {
+                        continue;
+                    }
+                    let name: String = name
+                        .trim_start_matches('"')
+                        .chars()
+                        .take_while(|&ch| is_identifier_char(ch))
+                        .collect::<String>()
+                        .to_uppercase();
+                    let point = EntryPoint {
+                        owner: unit.unit.owner.clone(),
+                        package: Some(unit.unit.name.clone()),
+                        name,
+                    };
+                    if !point.name.is_empty() && !points.contains(&point) {
+                        points.push(point);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    points
+}
+
+const NOT_VARIABLE_WORDS: [&str; 24] = [
+    "BEGIN",
+    "END",
+    "IF",
+    "ELSE",
+    "ELSIF",
+    "LOOP",
+    "WHILE",
+    "FOR",
+    "RETURN",
+    "CURSOR",
+    "TYPE",
+    "SUBTYPE",
+    "PRAGMA",
+    "EXCEPTION",
+    "PROCEDURE",
+    "FUNCTION",
+    "DECLARE",
+    "CREATE",
+    "PACKAGE",
+    "WHEN",
+    "THEN",
+    "NULL",
+    "SELECT",
+    "OPEN",
+];
+
+/// Names that look like variable or parameter declarations in `source`
+/// up to `line`. This is a heuristic: `DBMS_DEBUG` has no way to list a
+/// frame's variables, so candidates are read back and those it does not
+/// know are dropped.
+pub fn declared_variables(source: &[String], line: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for text in source.iter().take(line) {
+        let trimmed = text.trim_start();
+        let upper = trimmed.to_uppercase();
+        let fragments: Vec<&str> =
+            if upper.starts_with("PROCEDURE") || upper.starts_with("FUNCTION") {
+                // Parameters follow the parenthesis of a routine header.
+                trimmed
+                    .split_once('(')
+                    .map(|(_, parameters)| parameters.split(',').collect())
+                    .unwrap_or_default()
+            } else {
+                trimmed.split(',').collect()
+            };
+        for fragment in fragments {
+            let words = words_with_offsets(fragment);
+            let (Some((_, first)), Some((_, second))) = (words.first(), words.get(1)) else {
+                continue;
+            };
+            let first_upper = first.to_uppercase();
+            if NOT_VARIABLE_WORDS.contains(&first_upper.as_str())
+                || second.starts_with(":=")
+                || !first.chars().next().is_some_and(char::is_alphabetic)
+                || !first.chars().all(is_identifier_char)
+            {
+                continue;
+            }
+            if !names.contains(&first_upper) {
+                names.push(first_upper);
+            }
+        }
+    }
+    names
+}
+
+/// How far the target runs before breaking again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugStep {
+    StepInto,
+    StepOver,
+    StepOut,
+    Continue,
+    Abort,
+}
+
+impl DebugStep {
+    fn break_flags(self) -> &'static str {
+        match self {
+            DebugStep::StepInto => "sys.dbms_debug.break_next_line + sys.dbms_debug.break_any_call",
+            DebugStep::StepOver => "sys.dbms_debug.break_next_line",
+            DebugStep::StepOut => "sys.dbms_debug.break_any_return",
+            DebugStep::Continue => "0",
+            DebugStep::Abort => "sys.dbms_debug.abort_execution",
+        }
+    }
+}
+
+/// A breakpoint on a line of a stored unit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DebugBreakpoint {
+    pub unit: DebugUnit,
+    pub line: usize,
+}
+
+/// Run the target on: the breakpoint set is replaced first.
+pub struct DebugCommand {
+    pub step: DebugStep,
+    pub breakpoints: Vec<DebugBreakpoint>,
+}
+
+/// Where the target stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugLocation {
+    /// `None` for the anonymous block of the script.
+    pub unit: Option<DebugUnit>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct DebugSnapshot {
+    pub location: DebugLocation,
+    pub stack: Vec<String>,
+    pub variables: Vec<(String, String)>,
+}
+
+pub enum DebugEvent {
+    Status(String),
+    Paused(DebugSnapshot),
+    Output(Vec<String>),
+    /// The target left the debugger, or the controller gave up.
+    Finished(Result<(), String>),
+}
+
+fn database_error(err: OracleError) -> String {
+    eprintln!("Database operation failed: {err}");
+    err.to_string()
+}
+
+fn check_status(status: i64, call: &str) -> Result<(), String> {
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(format!("DBMS_DEBUG.{call} returned status {status}"))
+    }
+}
+
+/// Switch debugging on in the target session and return its debug
+/// session id.
+pub fn initialize_target(conn: &Connection) -> Result<String, String> {
+    let mut stmt = conn
+        .statement(
+            "BEGIN :id := sys.dbms_debug.initialize(); sys.dbms_debug.debug_on(TRUE, FALSE); END;",
+        )
+        .build()
+        .map_err(database_error)?;
+    stmt.bind("id", &OracleType::Varchar2(128))
+        .map_err(database_error)?;
+    stmt.execute(&[]).map_err(database_error)?;
+    let id: Option<String> = stmt.bind_value("id").map_err(database_error)?;
+    id.ok_or_else(|| "DBMS_DEBUG.INITIALIZE returned no session id".to_string())
+}
+
+/// Compile `unit` with debug information so breakpoints in it are hit.
+pub fn compile_for_debug(conn: &Connection, unit: &SourceUnit) -> Result<(), String> {
+    let name = match &unit.unit.owner {
+        Some(owner) => format!("\"{}\".\"{}\"", owner, unit.unit.name),
+        None => format!("\"{}\"", unit.unit.name),
+    };
+    let sql = match unit.object_type.as_str() {
+        "PACKAGE BODY" => format!("ALTER PACKAGE {name} COMPILE DEBUG BODY"),
+        "TYPE BODY" => format!("ALTER TYPE {name} COMPILE DEBUG BODY"),
+        object_type => format!("ALTER {object_type} {name} COMPILE DEBUG"),
+    };
+    conn.execute(&sql, &[]).map(|_| ()).map_err(database_error)
+}
+
+/// The controller side of a debug run.
+struct DebugController {
+    conn: Arc<Connection>,
+    /// Breakpoint numbers `DBMS_DEBUG` assigned.
+    breakpoints: HashMap<DebugBreakpoint, i64>,
+    sources: HashMap<DebugUnit, Vec<String>>,
+    /// The script's PL/SQL, for the variables of the anonymous block.
+    script_lines: Vec<String>,
+}
+
+struct RuntimeInfo {
+    finished: bool,
+    location: DebugLocation,
+}
+
+impl DebugController {
+    fn attach(&self, session_id: &str) -> Result<(), String> {
+        let mut stmt = self
+            .conn
+            .statement("BEGIN sys.dbms_debug.attach_session(:id); END;")
+            .build()
+            .map_err(database_error)?;
+        stmt.bind("id", &session_id).map_err(database_error)?;
+        stmt.execute(&[]).map_err(database_error)
+    }
+
+    fn detach(&self) {
+        if let Err(err) = self
+            .conn
+            .execute("BEGIN sys.dbms_debug.detach_session; END;", &[])
+        {
+            eprintln!("Database operation failed: {err}");
+        }
+    }
+
+    /// Run `call` (`synchronize` or `continue`) and read the runtime info.
+    fn runtime_call(&self, name: &str, call: &str) -> Result<RuntimeInfo, String> {
+        let sql = format!(
+            "DECLARE
+  info sys.dbms_debug.runtime_info;
+BEGIN
+  :status := {call};
+  :finished := CASE WHEN info.terminated = 1 OR info.reason = sys.dbms_debug.reason_knl_exit THEN 1 ELSE 0 END;
+  :line := info.line#;
+  :owner := info.program.owner;
+  :name := info.program.name;
+  :namespace := CASE info.program.namespace
+    WHEN sys.dbms_debug.namespace_pkg_body THEN 'BODY'
+    WHEN sys.dbms_debug.namespace_trigger THEN 'TRIGGER'
+    ELSE 'TOPLEVEL' END;
+END;"
+        );
+        let mut stmt = self.conn.statement(&sql).build().map_err(database_error)?;
+        for name in ["status", "finished", "line"] {
+            stmt.bind(name, &OracleType::Number(0, 0))
+                .map_err(database_error)?;
+        }
+        for name in ["owner", "name", "namespace"] {
+            stmt.bind(name, &OracleType::Varchar2(128))
+                .map_err(database_error)?;
+        }
+        stmt.execute(&[]).map_err(database_error)?;
+        let status: i64 = stmt.bind_value("status").map_err(database_error)?;
+        check_status(status, name)?;
+        let finished: i64 = stmt.bind_value("finished").map_err(database_error)?;
+        let line: Option<i64> = stmt.bind_value("line").map_err(database_error)?;
+        let owner: Option<String> = stmt.bind_value("owner").map_err(database_error)?;
+        let name: Option<String> = stmt.bind_value("name").map_err(database_error)?;
+        let namespace: Option<String> = stmt.bind_value("namespace").map_err(database_error)?;
+        let unit = name.filter(|name| !name.is_empty()).map(|name| DebugUnit {
+            owner,
+            name,
+            namespace: match namespace.as_deref() {
+                Some("BODY") => DebugNamespace::Body,
+                Some("TRIGGER") => DebugNamespace::Trigger,
+                _ => DebugNamespace::TopLevel,
+            },
+        });
+        Ok(RuntimeInfo {
+            finished: finished != 0,
+            location: DebugLocation {
+                unit,
+                line: line.unwrap_or(0).max(0) as usize,
+            },
+        })
+    }
+
+    fn synchronize(&self) -> Result<RuntimeInfo, String> {
+        self.runtime_call(
+            "SYNCHRONIZE",
+            "sys.dbms_debug.synchronize(info, sys.dbms_debug.info_getlineinfo)",
+        )
+    }
+
+    fn resume(&self, step: DebugStep) -> Result<RuntimeInfo, String> {
+        self.runtime_call(
+            "CONTINUE",
+            &format!(
+                "sys.dbms_debug.continue(info, {}, sys.dbms_debug.info_getlineinfo)",
+                step.break_flags()
+            ),
+        )
+    }
+
+    fn set_breakpoint(&self, breakpoint: &DebugBreakpoint) -> Result<i64, String> {
+        let sql = format!(
+            "DECLARE
+  program sys.dbms_debug.program_info;
+  number_out BINARY_INTEGER;
+BEGIN
+  program.namespace := {};
+  program.name := :name;
+  program.owner := NVL(:owner, SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA'));
+  program.dblink := NULL;
+  :status := sys.dbms_debug.set_breakpoint(program, :line, number_out, 0, 0);
+  :breakpoint := number_out;
+END;",
+            breakpoint.unit.namespace.plsql_constant()
+        );
+        let mut stmt = self.conn.statement(&sql).build().map_err(database_error)?;
+        stmt.bind("name", &breakpoint.unit.name)
+            .map_err(database_error)?;
+        stmt.bind("owner", &breakpoint.unit.owner)
+            .map_err(database_error)?;
+        stmt.bind("line", &(breakpoint.line as i64))
+            .map_err(database_error)?;
+        stmt.bind("status", &OracleType::Number(0, 0))
+            .map_err(database_error)?;
+        stmt.bind("breakpoint", &OracleType::Number(0, 0))
+            .map_err(database_error)?;
+        stmt.execute(&[]).map_err(database_error)?;
+        let status: i64 = stmt.bind_value("status").map_err(database_error)?;
+        check_status(status, "SET_BREAKPOINT")?;
+        stmt.bind_value("breakpoint").map_err(database_error)
+    }
+
+    fn delete_breakpoint(&self, number: i64) -> Result<(), String> {
+        let mut stmt = self
+            .conn
+            .statement("BEGIN :status := sys.dbms_debug.delete_breakpoint(:breakpoint); END;")
+            .build()
+            .map_err(database_error)?;
+        stmt.bind("status", &OracleType::Number(0, 0))
+            .map_err(database_error)?;
+        stmt.bind("breakpoint", &number).map_err(database_error)?;
+        stmt.execute(&[]).map_err(database_error)?;
+        let status: i64 = stmt.bind_value("status").map_err(database_error)?;
+        check_status(status, "DELETE_BREAKPOINT")
+    }
+
+    /// Make the target's breakpoints match `wanted`. Failures are reported
+    /// but do not stop the run.
+    fn sync_breakpoints(&mut self, wanted: &[DebugBreakpoint], events: &mpsc::Sender<DebugEvent>) {
+        let stale: Vec<DebugBreakpoint> = self
+            .breakpoints
+            .keys()
+            .filter(|breakpoint| !wanted.contains(breakpoint))
+            .cloned()
+            .collect();
+        for breakpoint in stale {
+            if let Some(number) = self.breakpoints.remove(&breakpoint) {
+                if let Err(err) = self.delete_breakpoint(number) {
+                    let _ = events.send(DebugEvent::Status(err));
+                }
+            }
+        }
+        for breakpoint in wanted {
+            if self.breakpoints.contains_key(breakpoint) {
+                continue;
+            }
+            match self.set_breakpoint(breakpoint) {
+                Ok(number) => {
+                    self.breakpoints.insert(breakpoint.clone(), number);
+                }
+                Err(err) => {
+                    let _ = events.send(DebugEvent::Status(format!(
+                        "Breakpoint at {} line {} not set: {}",
+                        breakpoint.unit.name, breakpoint.line, err
+                    )));
+                }
+            }
+        }
+    }
+
+    fn backtrace(&self) -> Result<Vec<String>, String> {
+        let mut stmt = self
+            .conn
+            .statement(
+                "DECLARE listing VARCHAR2(32767); BEGIN sys.dbms_debug.print_backtrace(listing); :listing := listing; END;",
+            )
+            .build()
+            .map_err(database_error)?;
+        stmt.bind("listing", &OracleType::Varchar2(32767))
+            .map_err(database_error)?;
+        stmt.execute(&[]).map_err(database_error)?;
+        let listing: Option<String> = stmt.bind_value("listing").map_err(database_error)?;
+        Ok(listing
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn value_of(&self, name: &str) -> Result<Option<String>, String> {
+        let sql = format!(
+            "DECLARE
+  value_out VARCHAR2({MAX_VALUE_LENGTH});
+BEGIN
+  :status := sys.dbms_debug.get_value(:name, 0, value_out, NULL);
+  :value := value_out;
+END;"
+        );
+        let mut stmt = self.conn.statement(&sql).build().map_err(database_error)?;
+        stmt.bind("status", &OracleType::Number(0, 0))
+            .map_err(database_error)?;
+        stmt.bind("name", &name).map_err(database_error)?;
+        stmt.bind("value", &OracleType::Varchar2(MAX_VALUE_LENGTH))
+            .map_err(database_error)?;
+        stmt.execute(&[]).map_err(database_error)?;
+        let status: i64 = stmt.bind_value("status").map_err(database_error)?;
+        if status != 0 {
+            return Ok(None);
+        }
+        let value: Option<String> = stmt.bind_value("value").map_err(database_error)?;
+        Ok(Some(value.unwrap_or_else(|| "NULL".to_string())))
+    }
+
+    fn source_of(&mut self, unit: &DebugUnit) -> Vec<String> {
+        if let Some(lines) = self.sources.get(unit) {
+            return lines.clone();
+        }
+        let sql = format!(
+            "SELECT text FROM all_source WHERE owner = NVL(:1, SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')) AND name = :2 AND type IN ({}) ORDER BY type, line",
+            unit.namespace.source_types()
+        );
+        let lines: Vec<String> = match self.conn.query(&sql, &[&unit.owner, &unit.name]) {
+            Ok(rows) => rows
+                .filter_map(|row| row.ok())
+                .map(|row| {
+                    row.get::<_, Option<String>>(0)
+                        .ok()
+                        .flatten()
+                        .unwrap_or_default()
+                        .trim_end_matches('\n')
+                        .to_string()
+                })
+                .collect(),
+            Err(err) => {
+                eprintln!("Database operation failed: {err}");
+                Vec::new()
+            }
+        };
+        self.sources.insert(unit.clone(), lines.clone());
+        lines
+    }
+
+    fn snapshot(&mut self, location: DebugLocation) -> DebugSnapshot {
+        let stack = self.backtrace().unwrap_or_else(|err| vec![err]);
+        let source = match &location.unit {
+            Some(unit) => self.source_of(unit),
+            None => self.script_lines.clone(),
+        };
+        let mut variables = Vec::new();
+        for name in declared_variables(&source, location.line) {
+            match self.value_of(&name) {
+                Ok(Some(value)) => variables.push((name, value)),
+                Ok(None) => {}
+                Err(err) => {
+                    variables.push((name, err));
+                    break;
+                }
+            }
+        }
+        DebugSnapshot {
+            location,
+            stack,
+            variables,
+        }
+    }
+
+    fn report(&mut self, info: RuntimeInfo, events: &mpsc::Sender<DebugEvent>) -> bool {
+        if info.finished {
+            return false;
+        }
+        let snapshot = self.snapshot(info.location);
+        let _ = events.send(DebugEvent::Paused(snapshot));
+        true
+    }
+}
+
+/// The PL/SQL block of a script, as the target compiles it: from its
+/// `DECLARE` or `BEGIN` line up to the `/` that ends it.
+fn script_block_lines(script: &str) -> Vec<String> {
+    script
+        .lines()
+        .skip_while(|line| {
+            let word = line.trim_start().to_uppercase();
+            !(word.starts_with("DECLARE") || word.starts_with("BEGIN"))
+        })
+        .take_while(|line| line.trim() != "/")
+        .map(str::to_string)
+        .collect()
+}
+
+/// Forwards the target's script output to the debugger.
+struct TargetSink {
+    events: mpsc::Sender<DebugEvent>,
+    wake: fn(),
+}
+
+impl ScriptEventSink for TargetSink {
+    fn emit(&self, event: QueryProgress) {
+        let lines = match event {
+            QueryProgress::ScriptOutput { lines } => lines,
+            QueryProgress::Rows { rows, .. } => rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| cell.display("").to_string())
+                        .collect::<Vec<_>>()
+                        .join("\t")
+                })
+                .collect(),
+            QueryProgress::SelectStart { columns, .. } => vec![columns.join("\t")],
+            QueryProgress::StatementFinished { result, .. } if !result.message.is_empty() => {
+                vec![result.message]
+            }
+            QueryProgress::PromptInput { response, .. } => {
+                let _ = response.send(None);
+                return;
+            }
+            _ => return,
+        };
+        let _ = self.events.send(DebugEvent::Output(lines));
+        (self.wake)();
+    }
+}
+
+/// Start a debug run of `script` on `target`, controlled from `controller`.
+/// Both must be connected sessions of their own. Events are sent to
+/// `events`, each followed by a call to `wake`.
+pub fn start_debug_session(
+    target: SharedConnection,
+    controller: SharedConnection,
+    script: String,
+    commands: mpsc::Receiver<DebugCommand>,
+    events: mpsc::Sender<DebugEvent>,
+    wake: fn(),
+) -> Result<(), String> {
+    let target_conn = lock_connection(&target)
+        .get_connection()
+        .ok_or_else(|| "The debug target session is not connected".to_string())?;
+    let controller_conn = lock_connection(&controller)
+        .get_connection()
+        .ok_or_else(|| "The debugger session is not connected".to_string())?;
+    let session_id = initialize_target(&target_conn)?;
+
+    let target_done = Arc::new(AtomicBool::new(false));
+    let target_events = events.clone();
+    let target_done_for_thread = target_done.clone();
+    let controller_for_break = controller_conn.clone();
+    let script_for_target = script.clone();
+    thread::spawn(move || {
+        let sink = TargetSink {
+            events: target_events.clone(),
+            wake,
+        };
+        let outcome = ScriptRunner::run_batch(
+            target.clone(),
+            script_for_target,
+            true,
+            None,
+            &sink,
+            Arc::new(Mutex::new(None)),
+            Arc::new(AtomicBool::new(false)),
+        );
+        if let Err(err) = target_conn.execute("BEGIN sys.dbms_debug.debug_off; END;", &[]) {
+            eprintln!("Database operation failed: {err}");
+        }
+        target_done_for_thread.store(true, Ordering::SeqCst);
+        let message = match outcome {
+            BatchOutcome::Completed | BatchOutcome::Exited { .. } => "Debug run finished",
+            BatchOutcome::Failed { .. } => "Debug run finished with errors",
+            BatchOutcome::Cancelled => "Debug run cancelled",
+        };
+        let _ = target_events.send(DebugEvent::Status(message.to_string()));
+        // The controller may still be waiting for a target that never
+        // reached the debugger, e.g. when the block did not compile.
+        if let Err(err) = controller_for_break.break_execution() {
+            eprintln!("Database operation failed: {err}");
+        }
+        wake();
+    });
+
+    thread::spawn(move || {
+        let mut debugger = DebugController {
+            conn: controller_conn,
+            breakpoints: HashMap::new(),
+            sources: HashMap::new(),
+            script_lines: script_block_lines(&script),
+        };
+        let result = (|| {
+            debugger.attach(&session_id)?;
+            let _ = events.send(DebugEvent::Status("Waiting for the target...".to_string()));
+            wake();
+            let mut info = debugger.synchronize()?;
+            loop {
+                if !debugger.report(info, &events) {
+                    return Ok(());
+                }
+                wake();
+                let Ok(DebugCommand { step, breakpoints }) = commands.recv() else {
+                    // The debugger window closed; stop the run.
+                    debugger.sync_breakpoints(&[], &events);
+                    debugger.resume(DebugStep::Abort)?;
+                    return Ok(());
+                };
+                debugger.sync_breakpoints(&breakpoints, &events);
+                let _ = events.send(DebugEvent::Status("Running...".to_string()));
+                wake();
+                info = debugger.resume(step)?;
+            }
+        })();
+        let result = if target_done.load(Ordering::SeqCst) {
+            // Errors after the target ended come from the break above.
+            Ok(())
+        } else {
+            result
+        };
+        debugger.detach();
+        let _ = events.send(DebugEvent::Finished(result));
+        wake();
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod debugger_tests {
+    use super::*;
+
+    const SCRIPT: &str = "-- helpers\nCREATE OR REPLACE\n  PACKAGE BODY app.billing AS\n  PROCEDURE charge(p_id IN NUMBER, p_amount NUMBER) IS\n    v_total NUMBER := 0;\n  BEGIN\n    v_total := p_amount;\n  END charge;\n  FUNCTION total RETURN NUMBER IS BEGIN RETURN 1; END;\nEND billing;\n/\n\nCREATE PROCEDURE cleanup AS\nBEGIN\n  NULL;\nEND;\n/\n";
+
+    #[test]
+    fn units_are_anchored_on_their_type_keyword() {
+        let units = source_units(SCRIPT);
+        assert_eq!(units.len(), 2);
+        assert_eq!(
+            units[0].unit,
+            DebugUnit {
+                owner: Some("APP".to_string()),
+                name: "BILLING".to_string(),
+                namespace: DebugNamespace::Body,
+            }
+        );
+        assert_eq!((units[0].first_line, units[0].last_line), (3, 10));
+        assert_eq!((units[1].first_line, units[1].last_line), (13, 16));
+        let (unit, line) = unit_location(&units, 7).unwrap();
+        assert_eq!((unit.unit.name.as_str(), line), ("BILLING", 5));
+        assert!(unit_location(&units, 12).is_none());
+    }
+
+    #[test]
+    fn entry_points_include_package_routines() {
+        let names: Vec<String> = entry_points(SCRIPT)
+            .iter()
+            .map(EntryPoint::qualified_name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["APP.BILLING.CHARGE", "APP.BILLING.TOTAL", "CLEANUP"]
+        );
+    }
+
+    #[test]
+    fn declared_variables_read_parameters_and_declarations() {
+        let source: Vec<String> = SCRIPT.lines().skip(2).map(str::to_string).collect();
+        assert_eq!(
+            declared_variables(&source, 5),
+            vec!["P_ID", "P_AMOUNT", "V_TOTAL"]
+        );
+    }
+}
@@ -0,0 +1,860 @@
+//! Writing query results to files in several formats.
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, NaiveDateTime};
+use oracle::Connection;
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::db::{sql_literal, CellValue, ColumnInfo, QueryExecutor};
+
+/// Rows an XLSX worksheet can hold, including the header row.
+const XLSX_MAX_ROWS: u32 = 1_048_576;
+/// Significant digits an Excel number keeps; longer numbers are written as text.
+const XLSX_NUMBER_DIGITS: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Json,
+    SqlInsert,
+    Markdown,
+    Html,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 7] = [
+        ExportFormat::Csv,
+        ExportFormat::Tsv,
+        ExportFormat::Json,
+        ExportFormat::SqlInsert,
+        ExportFormat::Markdown,
+        ExportFormat::Html,
+        ExportFormat::Xlsx,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Tsv => "TSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::SqlInsert => "SQL INSERT",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Html => "HTML",
+            ExportFormat::Xlsx => "Excel",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Json => "json",
+            ExportFormat::SqlInsert => "sql",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    /// Filter string for the save file dialog.
+    pub fn file_filter(self) -> String {
+        format!("{} Files\t*.{}", self.label(), self.extension())
+    }
+}
+
+/// When delimited text fields are wrapped in double quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Only fields containing the delimiter, quotes or line breaks.
+    Minimal,
+    Always,
+    Never,
+}
+
+impl QuoteStyle {
+    pub const ALL: [QuoteStyle; 3] = [QuoteStyle::Minimal, QuoteStyle::Always, QuoteStyle::Never];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            QuoteStyle::Minimal => "When needed",
+            QuoteStyle::Always => "Always",
+            QuoteStyle::Never => "Never",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// Write column names before the rows. Markdown tables always have one.
+    pub include_header: bool,
+    /// Text written for NULL cells. JSON and SQL use their own NULL.
+    pub null_text: String,
+    /// CSV field separator; TSV always uses a tab.
+    pub delimiter: char,
+    pub quote_style: QuoteStyle,
+    /// strftime pattern (e.g. `%d/%m/%Y`) for DATE and TIMESTAMP cells.
+    /// `None` keeps the fetched text. SQL scripts ignore it.
+    pub date_format: Option<String>,
+    /// Target table of SQL INSERT scripts.
+    pub table_name: String,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            include_header: true,
+            null_text: String::new(),
+            delimiter: ',',
+            quote_style: QuoteStyle::Minimal,
+            date_format: None,
+            table_name: "EXPORT_TABLE".to_string(),
+        }
+    }
+}
+
+impl ExportOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.format == ExportFormat::Csv && matches!(self.delimiter, '"' | '\n' | '\r') {
+            return Err("The delimiter cannot be a double quote or a line break.".to_string());
+        }
+        if let Some(pattern) = &self.date_format {
+            if pattern.trim().is_empty()
+                || StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error))
+            {
+                return Err(format!("Invalid date format: {}", pattern));
+            }
+        }
+        if self.format == ExportFormat::SqlInsert && self.table_name.trim().is_empty() {
+            return Err("SQL INSERT export needs a target table name.".to_string());
+        }
+        Ok(())
+    }
+
+    fn field_delimiter(&self) -> char {
+        if self.format == ExportFormat::Tsv {
+            '\t'
+        } else {
+            self.delimiter
+        }
+    }
+}
+
+enum ExportOutput {
+    Text(Box<dyn Write>),
+    Xlsx(Box<XlsxOutput>),
+}
+
+struct XlsxOutput {
+    workbook: Workbook,
+    worksheet: Worksheet,
+    header_format: Format,
+    path: PathBuf,
+    next_row: u32,
+}
+
+/// Writes rows one at a time, so large results never have to be held in memory.
+pub struct ResultExporter {
+    options: ExportOptions,
+    headers: Vec<String>,
+    /// `INSERT INTO table (columns) VALUES (` for SQL scripts.
+    insert_prefix: String,
+    rows_written: usize,
+    output: ExportOutput,
+}
+
+impl ResultExporter {
+    /// Create the file at `path` and write everything that precedes the rows.
+    pub fn create(path: &Path, options: ExportOptions, headers: &[String]) -> Result<Self, String> {
+        options.validate()?;
+        let output = if options.format == ExportFormat::Xlsx {
+            let mut workbook = Workbook::new();
+            let worksheet = workbook.new_worksheet_with_constant_memory();
+            ExportOutput::Xlsx(Box::new(XlsxOutput {
+                workbook,
+                worksheet,
+                header_format: Format::new().set_bold(),
+                path: path.to_path_buf(),
+                next_row: 0,
+            }))
+        } else {
+            let file = match File::create(path) {
+                Ok(file) => file,
+                Err(err) => {
+                    eprintln!("Export error: {err}");
+                    return Err(err.to_string());
+                }
+            };
+            ExportOutput::Text(Box::new(BufWriter::new(file)))
+        };
+        Self::start(options, headers, output)
+    }
+
+    /// Exporter for the text formats that writes into `writer`.
+    #[allow(dead_code)]
+    pub fn to_writer(
+        writer: Box<dyn Write>,
+        options: ExportOptions,
+        headers: &[String],
+    ) -> Result<Self, String> {
+        options.validate()?;
+        if options.format == ExportFormat::Xlsx {
+            return Err("XLSX export needs a file path.".to_string());
+        }
+        Self::start(options, headers, ExportOutput::Text(writer))
+    }
+
+    fn start(
+        options: ExportOptions,
+        headers: &[String],
+        output: ExportOutput,
+    ) -> Result<Self, String> {
+        let insert_prefix = if options.format == ExportFormat::SqlInsert {
+            format!(
+                "INSERT INTO {} ({}) VALUES (",
+                options.table_name.trim(),
+                headers
+                    .iter()
+                    .map(|name| sql_column_name(name))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        } else {
+            String::new()
+        };
+        let mut exporter = Self {
+            options,
+            headers: headers.to_vec(),
+            insert_prefix,
+            rows_written: 0,
+            output,
+        };
+        exporter.write_prelude()?;
+        Ok(exporter)
+    }
+
+    fn write_prelude(&mut self) -> Result<(), String> {
+        let mut text = String::new();
+        match self.options.format {
+            ExportFormat::Csv | ExportFormat::Tsv => {
+                if self.options.include_header {
+                    text = self.delimited_line(self.headers.iter().map(|h| Cow::from(h.as_str())));
+                }
+            }
+            ExportFormat::Json => text.push('['),
+            ExportFormat::SqlInsert => {}
+            ExportFormat::Markdown => {
+                text = markdown_line(self.headers.iter().map(|h| Cow::from(h.as_str())));
+                text.push('|');
+                for _ in &self.headers {
+                    text.push_str(" --- |");
+                }
+                text.push('\n');
+            }
+            ExportFormat::Html => {
+                text.push_str(
+                    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+                     <title>Query Results</title>\n</head>\n<body>\n<table border=\"1\">\n",
+                );
+                if self.options.include_header {
+                    text.push_str("<thead>\n<tr>");
+                    for header in &self.headers {
+                        let _ = write!(text, "<th>{}</th>", html_escape(header));
+                    }
+                    text.push_str("</tr>\n</thead>\n");
+                }
+                text.push_str("<tbody>\n");
+            }
+            ExportFormat::Xlsx => {
+                if let ExportOutput::Xlsx(xlsx) = &mut self.output {
+                    if self.options.include_header {
+                        for (col, header) in self.headers.iter().enumerate() {
+                            if let Err(err) = xlsx.worksheet.write_string_with_format(
+                                0,
+                                col as u16,
+                                header.as_str(),
+                                &xlsx.header_format,
+                            ) {
+                                return Err(err.to_string());
+                            }
+                        }
+                        xlsx.next_row = 1;
+                    }
+                }
+            }
+        }
+        self.write_text(&text)
+    }
+
+    pub fn write_row(&mut self, row: &[CellValue]) -> Result<(), String> {
+        let mut text = String::new();
+        match self.options.format {
+            ExportFormat::Csv | ExportFormat::Tsv => {
+                text = self.delimited_line(row.iter().map(|cell| self.cell_text(cell)));
+            }
+            ExportFormat::Json => {
+                text.push_str(if self.rows_written == 0 {
+                    "\n  {"
+                } else {
+                    ",\n  {"
+                });
+                for (col, cell) in row.iter().enumerate() {
+                    if col > 0 {
+                        text.push_str(", ");
+                    }
+                    let name = self.headers.get(col).map(String::as_str).unwrap_or("");
+                    let _ = write!(text, "{}: {}", json_string(name), self.json_value(cell));
+                }
+                text.push('}');
+            }
+            ExportFormat::SqlInsert => {
+                text.push_str(&self.insert_prefix);
+                text.push_str(
+                    &row.iter()
+                        .map(sql_literal)
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                );
+                text.push_str(");\n");
+            }
+            ExportFormat::Markdown => {
+                text = markdown_line(row.iter().map(|cell| self.cell_text(cell)));
+            }
+            ExportFormat::Html => {
+                text.push_str("<tr>");
+                for cell in row {
+                    let _ = write!(text, "<td>{}</td>", html_escape(&self.cell_text(cell)));
+                }
+                text.push_str("</tr>\n");
+            }
+            ExportFormat::Xlsx => self.write_xlsx_row(row)?,
+        }
+        self.write_text(&text)?;
+        self.rows_written += 1;
+        Ok(())
+    }
+
+    /// Complete the file and return the number of rows written.
+    pub fn finish(mut self) -> Result<usize, String> {
+        let closing = match self.options.format {
+            ExportFormat::Json if self.rows_written == 0 => "]\n",
+            ExportFormat::Json => "\n]\n",
+            ExportFormat::Html => "</tbody>\n</table>\n</body>\n</html>\n",
+            _ => "",
+        };
+        self.write_text(closing)?;
+        match self.output {
+            ExportOutput::Text(mut writer) => match writer.flush() {
+                Ok(()) => {}
+                Err(err) => {
+                    eprintln!("Export error: {err}");
+                    return Err(err.to_string());
+                }
+            },
+            ExportOutput::Xlsx(xlsx) => {
+                let XlsxOutput {
+                    mut workbook,
+                    worksheet,
+                    path,
+                    ..
+                } = *xlsx;
+                workbook.push_worksheet(worksheet);
+                match workbook.save(&path) {
+                    Ok(()) => {}
+                    Err(err) => {
+                        eprintln!("Export error: {err}");
+                        return Err(err.to_string());
+                    }
+                }
+            }
+        }
+        Ok(self.rows_written)
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), String> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        if let ExportOutput::Text(writer) = &mut self.output {
+            if let Err(err) = writer.write_all(text.as_bytes()) {
+                eprintln!("Export error: {err}");
+                return Err(err.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn write_xlsx_row(&mut self, row: &[CellValue]) -> Result<(), String> {
+        let texts: Vec<String> = row
+            .iter()
+            .map(|cell| self.cell_text(cell).into_owned())
+            .collect();
+        let ExportOutput::Xlsx(xlsx) = &mut self.output else {
+            return Ok(());
+        };
+        let XlsxOutput {
+            worksheet,
+            next_row,
+            ..
+        } = xlsx.as_mut();
+        if *next_row >= XLSX_MAX_ROWS {
+            return Err(format!(
+                "An XLSX sheet holds at most {} rows.",
+                XLSX_MAX_ROWS
+            ));
+        }
+        for (col, (cell, text)) in row.iter().zip(texts.iter()).enumerate() {
+            let col = col as u16;
+            let written = match cell.as_f64() {
+                Some(number)
+                    if number.is_finite() && significant_digits(text) <= XLSX_NUMBER_DIGITS =>
+                {
+                    worksheet.write_number(*next_row, col, number).map(|_| ())
+                }
+                _ if text.is_empty() => Ok(()),
+                _ => worksheet
+                    .write_string(*next_row, col, text.as_str())
+                    .map(|_| ()),
+            };
+            if let Err(err) = written {
+                return Err(err.to_string());
+            }
+        }
+        *next_row += 1;
+        Ok(())
+    }
+
+    fn cell_text<'a>(&'a self, cell: &'a CellValue) -> Cow<'a, str> {
+        match (cell, &self.options.date_format) {
+            (CellValue::Date(text) | CellValue::Timestamp(text), Some(pattern)) => {
+                match format_date(text, pattern) {
+                    Some(formatted) => Cow::Owned(formatted),
+                    None => Cow::Borrowed(text),
+                }
+            }
+            _ => Cow::Borrowed(cell.display(&self.options.null_text)),
+        }
+    }
+
+    fn json_value(&self, cell: &CellValue) -> String {
+        match cell {
+            CellValue::Null => "null".to_string(),
+            CellValue::Number(text) => match json_number(text) {
+                Some(number) => number,
+                None => json_string(text),
+            },
+            _ => json_string(&self.cell_text(cell)),
+        }
+    }
+
+    fn delimited_line<'a>(&self, fields: impl Iterator<Item = Cow<'a, str>>) -> String {
+        let delimiter = self.options.field_delimiter();
+        let mut line = String::new();
+        for (index, field) in fields.enumerate() {
+            if index > 0 {
+                line.push(delimiter);
+            }
+            let quote = match self.options.quote_style {
+                QuoteStyle::Always => true,
+                QuoteStyle::Never => false,
+                QuoteStyle::Minimal => field
+                    .chars()
+                    .any(|ch| ch == delimiter || matches!(ch, '"' | '\n' | '\r')),
+            };
+            if quote {
+                line.push('"');
+                line.push_str(&field.replace('"', "\"\""));
+                line.push('"');
+            } else {
+                line.push_str(&field);
+            }
+        }
+        line.push('\n');
+        line
+    }
+}
+
+/// Write rows that are already in memory to `path`.
+pub fn export_rows_to_file(
+    path: &Path,
+    options: ExportOptions,
+    headers: &[String],
+    rows: &[Vec<CellValue>],
+) -> Result<usize, String> {
+    let mut exporter = ResultExporter::create(path, options, headers)?;
+    for row in rows {
+        exporter.write_row(row)?;
+    }
+    exporter.finish()
+}
+
+/// Run `sql` and stream every row straight into `path`, independent of how
+/// many rows the grid has loaded. Returns the number of rows written.
+pub fn export_query_to_file(
+    conn: &Connection,
+    sql: &str,
+    path: &Path,
+    options: ExportOptions,
+) -> Result<usize, String> {
+    options.validate()?;
+    let exporter: RefCell<Option<ResultExporter>> = RefCell::new(None);
+    let failure: RefCell<Option<String>> = RefCell::new(None);
+
+    let outcome = QueryExecutor::execute_select_streaming(
+        conn,
+        sql,
+        &mut |columns: &[ColumnInfo]| {
+            let headers: Vec<String> = columns.iter().map(|col| col.name.clone()).collect();
+            match ResultExporter::create(path, options.clone(), &headers) {
+                Ok(created) => *exporter.borrow_mut() = Some(created),
+                Err(err) => *failure.borrow_mut() = Some(err),
+            }
+        },
+        &mut |row: Vec<CellValue>| {
+            let mut exporter = exporter.borrow_mut();
+            let Some(exporter) = exporter.as_mut() else {
+                return false;
+            };
+            match exporter.write_row(&row) {
+                Ok(()) => true,
+                Err(err) => {
+                    *failure.borrow_mut() = Some(err);
+                    false
+                }
+            }
+        },
+    );
+
+    match outcome {
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("Database operation failed: {err}");
+            return Err(err.to_string());
+        }
+    }
+    if let Some(err) = failure.into_inner() {
+        return Err(err);
+    }
+    match exporter.into_inner() {
+        Some(exporter) => exporter.finish(),
+        None => Err("The statement did not return any columns.".to_string()),
+    }
+}
+
+/// Reformat a DATE/TIMESTAMP cell, laid out as
+/// [`CELL_DATE_MASK`](crate::db::CELL_DATE_MASK) with optional `.FF` and
+/// ` TZH:TZM`.
+fn format_date(text: &str, pattern: &str) -> Option<String> {
+    let text = text.trim();
+    let mut formatted = String::new();
+    if let Ok(value) = DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f %:z") {
+        write!(formatted, "{}", value.format(pattern)).ok()?;
+    } else {
+        let value = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f").ok()?;
+        write!(formatted, "{}", value.format(pattern)).ok()?;
+    }
+    Some(formatted)
+}
+
+fn json_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Oracle writes numbers like `.5`; JSON needs the leading zero.
+fn json_number(text: &str) -> Option<String> {
+    let text = text.trim();
+    let normalized = if let Some(rest) = text.strip_prefix("-.") {
+        format!("-0.{}", rest)
+    } else if let Some(rest) = text.strip_prefix('.') {
+        format!("0.{}", rest)
+    } else {
+        text.to_string()
+    };
+    normalized
+        .parse::<serde_json::Number>()
+        .ok()
+        .map(|_| normalized)
+}
+
+fn significant_digits(text: &str) -> usize {
+    let mantissa = text.split(['e', 'E']).next().unwrap_or("");
+    mantissa
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>()
+        .trim_start_matches('0')
+        .len()
+}
+
+fn sql_column_name(name: &str) -> String {
+    let simple = name
+        .chars()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_uppercase())
+        && name.chars().all(|ch| {
+            ch.is_ascii_uppercase() || ch.is_ascii_digit() || matches!(ch, '_' | '$' | '#')
+        });
+    if simple {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+fn markdown_line<'a>(fields: impl Iterator<Item = Cow<'a, str>>) -> String {
+    let mut line = String::from("|");
+    for field in fields {
+        let escaped = field
+            .replace('\\', "\\\\")
+            .replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace('\n', "<br>");
+        let _ = write!(line, " {} |", escaped);
+    }
+    line.push('\n');
+    line
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn export(options: ExportOptions, headers: &[&str], rows: Vec<Vec<CellValue>>) -> String {
+        let buffer = SharedBuffer::default();
+        let headers: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+        let mut exporter =
+            ResultExporter::to_writer(Box::new(buffer.clone()), options, &headers).unwrap();
+        for row in &rows {
+            exporter.write_row(row).unwrap();
+        }
+        exporter.finish().unwrap();
+        let bytes = buffer.0.borrow().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    fn sample_rows() -> Vec<Vec<CellValue>> {
+        vec![
+            vec![
+                CellValue::Number("1".to_string()),
+                CellValue::Text("a,\"b\"".to_string()),
+                CellValue::Date("2024-03-05 14:07:09".to_string()),
+            ],
+            vec![
+                CellValue::Number(".5".to_string()),
+                CellValue::Null,
+                CellValue::Null,
+            ],
+        ]
+    }
+
+    #[test]
+    fn delimited_text_honors_quoting_null_and_header_options() {
+        let csv = export(
+            ExportOptions {
+                null_text: "(null)".to_string(),
+                ..ExportOptions::default()
+            },
+            &["ID", "NAME", "CREATED"],
+            sample_rows(),
+        );
+        assert_eq!(
+            csv,
+            "ID,NAME,CREATED\n1,\"a,\"\"b\"\"\",2024-03-05 14:07:09\n.5,(null),(null)\n"
+        );
+
+        let tsv = export(
+            ExportOptions {
+                format: ExportFormat::Tsv,
+                include_header: false,
+                quote_style: QuoteStyle::Always,
+                ..ExportOptions::default()
+            },
+            &["ID", "NAME", "CREATED"],
+            sample_rows(),
+        );
+        assert_eq!(
+            tsv,
+            "\"1\"\t\"a,\"\"b\"\"\"\t\"2024-03-05 14:07:09\"\n\".5\"\t\"\"\t\"\"\n"
+        );
+
+        let semicolon = export(
+            ExportOptions {
+                delimiter: ';',
+                quote_style: QuoteStyle::Never,
+                include_header: false,
+                ..ExportOptions::default()
+            },
+            &["ID", "NAME", "CREATED"],
+            sample_rows(),
+        );
+        assert!(semicolon.starts_with("1;a,\"b\";2024-03-05 14:07:09\n"));
+    }
+
+    #[test]
+    fn json_export_writes_typed_values() {
+        let json = export(
+            ExportOptions {
+                format: ExportFormat::Json,
+                null_text: "(null)".to_string(),
+                ..ExportOptions::default()
+            },
+            &["ID", "NAME", "CREATED"],
+            sample_rows(),
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!([
+                {"ID": 1, "NAME": "a,\"b\"", "CREATED": "2024-03-05 14:07:09"},
+                {"ID": 0.5, "NAME": null, "CREATED": null}
+            ])
+        );
+
+        let empty = export(
+            ExportOptions {
+                format: ExportFormat::Json,
+                ..ExportOptions::default()
+            },
+            &["ID"],
+            Vec::new(),
+        );
+        assert_eq!(empty, "[]\n");
+    }
+
+    #[test]
+    fn sql_export_writes_insert_statements_for_the_target_table() {
+        let sql = export(
+            ExportOptions {
+                format: ExportFormat::SqlInsert,
+                table_name: "hr.emp_copy".to_string(),
+                ..ExportOptions::default()
+            },
+            &["ID", "Name", "CREATED"],
+            vec![vec![
+                CellValue::Number("7".to_string()),
+                CellValue::Text("O'Brien".to_string()),
+                CellValue::Date("2024-03-05 14:07:09".to_string()),
+            ]],
+        );
+        assert_eq!(
+            sql,
+            "INSERT INTO hr.emp_copy (ID, \"Name\", CREATED) VALUES (7, 'O''Brien', \
+             TO_DATE('2024-03-05 14:07:09', 'YYYY-MM-DD HH24:MI:SS'));\n"
+        );
+    }
+
+    #[test]
+    fn markdown_and_html_escape_cell_text() {
+        let rows = vec![vec![
+            CellValue::Text("a|b".to_string()),
+            CellValue::Text("<x & y>".to_string()),
+        ]];
+        let markdown = export(
+            ExportOptions {
+                format: ExportFormat::Markdown,
+                include_header: false,
+                ..ExportOptions::default()
+            },
+            &["A", "B"],
+            rows.clone(),
+        );
+        assert_eq!(markdown, "| A | B |\n| --- | --- |\n| a\\|b | <x & y> |\n");
+
+        let html = export(
+            ExportOptions {
+                format: ExportFormat::Html,
+                ..ExportOptions::default()
+            },
+            &["A", "B"],
+            rows,
+        );
+        assert!(html.contains("<thead>\n<tr><th>A</th><th>B</th></tr>\n</thead>"));
+        assert!(html.contains("<tr><td>a|b</td><td>&lt;x &amp; y&gt;</td></tr>"));
+        assert!(html.ends_with("</table>\n</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn date_format_applies_to_dates_and_timestamps() {
+        let csv = export(
+            ExportOptions {
+                include_header: false,
+                date_format: Some("%d/%m/%Y %H:%M".to_string()),
+                ..ExportOptions::default()
+            },
+            &["D", "T", "TZ", "TEXT"],
+            vec![vec![
+                CellValue::Date("2024-03-05 14:07:09".to_string()),
+                CellValue::Timestamp("2024-03-05 14:07:09.123456".to_string()),
+                CellValue::Timestamp("2024-03-05 14:07:09.5 +09:00".to_string()),
+                CellValue::Text("2024-03-05 14:07:09".to_string()),
+            ]],
+        );
+        assert_eq!(
+            csv,
+            "05/03/2024 14:07,05/03/2024 14:07,05/03/2024 14:07,2024-03-05 14:07:09\n"
+        );
+    }
+
+    #[test]
+    fn options_validation_rejects_unusable_settings() {
+        assert!(ExportOptions::default().validate().is_ok());
+        assert!(ExportOptions {
+            delimiter: '"',
+            ..ExportOptions::default()
+        }
+        .validate()
+        .is_err());
+        assert!(ExportOptions {
+            date_format: Some("%Q".to_string()),
+            ..ExportOptions::default()
+        }
+        .validate()
+        .is_err());
+        assert!(ExportOptions {
+            format: ExportFormat::SqlInsert,
+            table_name: " ".to_string(),
+            ..ExportOptions::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,371 @@
+//! Scriptable in-memory backend for tests.
+//!
+//! Statements are matched by their SQL text, ignoring case, runs of
+//! whitespace and a trailing semicolon. Queries without a registered
+//! result fail with ORA-00942; other statements succeed with no rows
+//! affected.
+
+use oracle::{Error as OracleError, ErrorKind};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::db::{
+    CellKind, CellValue, ColumnInfo, CompiledObject, DbBackend, ExecutedStatement, ObjectKind,
+    QueryResult, ResolvedBind, RowFn, SelectStartFn, TableColumnDetail,
+};
+
+#[derive(Clone)]
+enum MockResponse {
+    Rows {
+        columns: Vec<ColumnInfo>,
+        rows: Vec<Vec<CellValue>>,
+    },
+    RowCount(u64),
+    Error(String),
+}
+
+#[derive(Default)]
+struct MockState {
+    responses: Vec<(String, MockResponse)>,
+    output: Vec<(String, Vec<String>)>,
+    tables: Vec<(String, Vec<TableColumnDetail>)>,
+    objects: Vec<(ObjectKind, String)>,
+    compilation_errors: Vec<(String, Vec<Vec<String>>)>,
+    current_user: String,
+    dbms_output_enabled: bool,
+    dbms_output: Vec<String>,
+    call_timeout: Option<Duration>,
+    executed: Vec<String>,
+    commits: usize,
+    rollbacks: usize,
+}
+
+pub struct MockBackend {
+    state: Mutex<MockState>,
+}
+
+fn normalize_sql(sql: &str) -> String {
+    sql.trim()
+        .trim_end_matches(';')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase()
+}
+
+fn mock_error(message: &str) -> OracleError {
+    OracleError::new(ErrorKind::Other, message.to_string())
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(MockState {
+                current_user: "MOCK".to_string(),
+                ..MockState::default()
+            }),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn respond(self, sql: &str, response: MockResponse) -> Self {
+        self.state().responses.push((normalize_sql(sql), response));
+        self
+    }
+
+    /// Answer `sql` with text columns and rows.
+    pub fn with_rows(self, sql: &str, columns: &[&str], rows: &[&[&str]]) -> Self {
+        let columns = columns
+            .iter()
+            .map(|name| ColumnInfo {
+                name: name.to_string(),
+                data_type: "VARCHAR2".to_string(),
+                kind: CellKind::Text,
+            })
+            .collect();
+        let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|value| CellValue::from(*value)).collect())
+            .collect();
+        self.respond(sql, MockResponse::Rows { columns, rows })
+    }
+
+    pub fn with_row_count(self, sql: &str, row_count: u64) -> Self {
+        self.respond(sql, MockResponse::RowCount(row_count))
+    }
+
+    /// Fail `sql` with `message`, e.g. `ORA-00001: unique constraint ...`.
+    pub fn with_error(self, sql: &str, message: &str) -> Self {
+        self.respond(sql, MockResponse::Error(message.to_string()))
+    }
+
+    /// Lines `sql` writes with DBMS_OUTPUT.PUT_LINE.
+    pub fn with_output(self, sql: &str, lines: &[&str]) -> Self {
+        let lines = lines.iter().map(|line| line.to_string()).collect();
+        self.state().output.push((normalize_sql(sql), lines));
+        self
+    }
+
+    /// A table in the current schema with `(name, data type)` columns.
+    pub fn with_table(self, name: &str, columns: &[(&str, &str)]) -> Self {
+        let details = columns
+            .iter()
+            .map(|(column, data_type)| TableColumnDetail {
+                name: column.to_uppercase(),
+                data_type: data_type.to_uppercase(),
+                data_length: 0,
+                data_precision: None,
+                data_scale: None,
+                nullable: true,
+                default_value: None,
+                is_primary_key: false,
+            })
+            .collect();
+        {
+            let mut state = self.state();
+            state.tables.push((name.to_uppercase(), details));
+            state.objects.push((ObjectKind::Table, name.to_uppercase()));
+        }
+        self
+    }
+
+    pub fn with_object(self, kind: ObjectKind, name: &str) -> Self {
+        self.state().objects.push((kind, name.to_uppercase()));
+        self
+    }
+
+    /// SHOW ERRORS rows (line, position, text) for the named object.
+    pub fn with_compilation_errors(self, object_name: &str, rows: &[[&str; 3]]) -> Self {
+        let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|value| value.to_string()).collect())
+            .collect();
+        self.state()
+            .compilation_errors
+            .push((object_name.to_uppercase(), rows));
+        self
+    }
+
+    /// SQL text of every executed statement and query, in order.
+    pub fn executed(&self) -> Vec<String> {
+        self.state().executed.clone()
+    }
+
+    pub fn commit_count(&self) -> usize {
+        self.state().commits
+    }
+
+    pub fn rollback_count(&self) -> usize {
+        self.state().rollbacks
+    }
+
+    fn run(&self, sql: &str) -> Option<MockResponse> {
+        let key = normalize_sql(sql);
+        let mut state = self.state();
+        state.executed.push(sql.to_string());
+        if state.dbms_output_enabled {
+            let lines: Vec<String> = state
+                .output
+                .iter()
+                .filter(|(pattern, _)| *pattern == key)
+                .flat_map(|(_, lines)| lines.clone())
+                .collect();
+            state.dbms_output.extend(lines);
+        }
+        state
+            .responses
+            .iter()
+            .find(|(pattern, _)| *pattern == key)
+            .map(|(_, response)| response.clone())
+    }
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DbBackend for MockBackend {
+    fn execute(
+        &self,
+        sql: &str,
+        _binds: &[ResolvedBind],
+    ) -> Result<ExecutedStatement, OracleError> {
+        let row_count = match self.run(sql) {
+            Some(MockResponse::Error(message)) => return Err(mock_error(&message)),
+            Some(MockResponse::RowCount(count)) => count,
+            Some(MockResponse::Rows { rows, .. }) => rows.len() as u64,
+            None => 0,
+        };
+        Ok(ExecutedStatement {
+            row_count,
+            bind_updates: Vec::new(),
+            ref_cursors: Vec::new(),
+            implicit_results: Vec::new(),
+        })
+    }
+
+    fn query_streaming(
+        &self,
+        sql: &str,
+        _binds: &[ResolvedBind],
+        on_select_start: SelectStartFn<'_>,
+        on_row: RowFn<'_>,
+    ) -> Result<(QueryResult, bool), OracleError> {
+        let (columns, rows) = match self.run(sql) {
+            Some(MockResponse::Rows { columns, rows }) => (columns, rows),
+            Some(MockResponse::Error(message)) => return Err(mock_error(&message)),
+            _ => return Err(mock_error("ORA-00942: table or view does not exist")),
+        };
+        on_select_start(&columns);
+        let mut row_count = 0usize;
+        let mut stopped = false;
+        for row in rows {
+            row_count += 1;
+            if !on_row(row) {
+                stopped = true;
+                break;
+            }
+        }
+        Ok((
+            QueryResult::new_select_streamed(sql, columns, row_count, Duration::ZERO),
+            stopped,
+        ))
+    }
+
+    fn commit(&self) -> Result<(), OracleError> {
+        self.state().commits += 1;
+        Ok(())
+    }
+
+    fn rollback(&self) -> Result<(), OracleError> {
+        self.state().rollbacks += 1;
+        Ok(())
+    }
+
+    fn call_timeout(&self) -> Result<Option<Duration>, OracleError> {
+        Ok(self.state().call_timeout)
+    }
+
+    fn set_call_timeout(&self, timeout: Option<Duration>) -> Result<(), OracleError> {
+        self.state().call_timeout = timeout;
+        Ok(())
+    }
+
+    fn cancel(&self) -> Result<(), OracleError> {
+        Ok(())
+    }
+
+    fn enable_dbms_output(&self, _buffer_size: Option<u32>) -> Result<(), OracleError> {
+        self.state().dbms_output_enabled = true;
+        Ok(())
+    }
+
+    fn disable_dbms_output(&self) -> Result<(), OracleError> {
+        let mut state = self.state();
+        state.dbms_output_enabled = false;
+        state.dbms_output.clear();
+        Ok(())
+    }
+
+    fn get_dbms_output(&self, max_lines: u32) -> Result<Vec<String>, OracleError> {
+        let mut state = self.state();
+        let count = state.dbms_output.len().min(max_lines.max(1) as usize);
+        Ok(state.dbms_output.drain(..count).collect())
+    }
+
+    fn current_user(&self) -> Result<String, OracleError> {
+        Ok(self.state().current_user.clone())
+    }
+
+    fn schemas(&self) -> Result<Vec<String>, OracleError> {
+        Ok(vec![self.state().current_user.clone()])
+    }
+
+    fn list_objects(
+        &self,
+        _owner: Option<&str>,
+        kind: ObjectKind,
+    ) -> Result<Vec<String>, OracleError> {
+        let mut names: Vec<String> = self
+            .state()
+            .objects
+            .iter()
+            .filter(|(object_kind, _)| *object_kind == kind)
+            .map(|(_, name)| name.clone())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    fn describe_object(&self, object_name: &str) -> Result<Vec<TableColumnDetail>, OracleError> {
+        let name = object_name.to_uppercase();
+        self.state()
+            .tables
+            .iter()
+            .find(|(table, _)| *table == name)
+            .map(|(_, columns)| columns.clone())
+            .ok_or_else(|| mock_error(&format!("ORA-04043: object {name} does not exist")))
+    }
+
+    fn is_table(&self, _owner: Option<&str>, table_name: &str) -> Result<bool, OracleError> {
+        let name = table_name.to_uppercase();
+        Ok(self.state().tables.iter().any(|(table, _)| *table == name))
+    }
+
+    fn fetch_compilation_errors(
+        &self,
+        object: &CompiledObject,
+    ) -> Result<Vec<Vec<String>>, OracleError> {
+        let name = object.name.to_uppercase();
+        Ok(self
+            .state()
+            .compilation_errors
+            .iter()
+            .find(|(object_name, _)| *object_name == name)
+            .map(|(_, rows)| rows.clone())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod mock_backend_tests {
+    use super::*;
+
+    #[test]
+    fn lists_objects_by_kind() {
+        let backend = MockBackend::new()
+            .with_table("emp", &[("empno", "NUMBER")])
+            .with_object(ObjectKind::View, "emp_v")
+            .with_object(ObjectKind::Table, "dept");
+
+        assert_eq!(
+            backend.list_objects(None, ObjectKind::Table).unwrap(),
+            vec!["DEPT".to_string(), "EMP".to_string()]
+        );
+        assert_eq!(
+            backend.list_objects(None, ObjectKind::View).unwrap(),
+            vec!["EMP_V".to_string()]
+        );
+        assert!(backend.is_table(None, "Emp").unwrap());
+        assert!(!backend.is_table(None, "emp_v").unwrap());
+    }
+
+    #[test]
+    fn unknown_queries_fail_like_a_missing_table() {
+        let backend = MockBackend::new();
+        let err = backend
+            .query_streaming("SELECT * FROM nowhere", &[], &mut |_| {}, &mut |_| true)
+            .err()
+            .map(|err| err.to_string())
+            .unwrap_or_default();
+        assert!(err.starts_with("ORA-00942"), "{err}");
+    }
+}
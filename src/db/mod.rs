@@ -1,7 +1,33 @@
+pub mod backend;
 pub mod connection;
+pub mod connection_import;
+pub mod data_compare;
+pub mod debugger;
+pub mod export;
+#[cfg(test)]
+pub mod mock_backend;
 pub mod query;
+pub mod schema_compare;
+pub mod script_runner;
 pub mod session;
+pub mod session_init;
+pub mod session_monitor;
+pub mod stored_source;
+pub mod tnsnames;
+pub mod top_sql;
+pub mod transaction;
 
+pub use backend::*;
 pub use connection::*;
+pub use data_compare::*;
+pub use debugger::*;
+pub use export::*;
 pub use query::*;
+pub use schema_compare::*;
+pub use script_runner::*;
 pub use session::*;
+pub use session_init::*;
+pub use session_monitor::*;
+pub use stored_source::*;
+pub use top_sql::*;
+pub use transaction::*;
@@ -0,0 +1,712 @@
+use super::{CellKind, CellValue, ColumnInfo, CELL_DATE_MASK};
+
+/// Alias of the ROWID column appended to SELECTs whose rows can be edited.
+pub const ROWID_COLUMN_ALIAS: &str = "SPACE_QUERY_ROWID";
+
+/// Top-level keywords that make a query's rows impossible to map back to
+/// a single table row.
+const NON_EDITABLE_KEYWORDS: [&str; 16] = [
+    "DISTINCT",
+    "UNIQUE",
+    "UNION",
+    "INTERSECT",
+    "MINUS",
+    "EXCEPT",
+    "GROUP",
+    "HAVING",
+    "CONNECT",
+    "START",
+    "JOIN",
+    "PIVOT",
+    "UNPIVOT",
+    "MODEL",
+    "MATCH_RECOGNIZE",
+    "INTO",
+];
+
+const AGGREGATE_FUNCTIONS: [&str; 13] = [
+    "COUNT",
+    "SUM",
+    "AVG",
+    "MIN",
+    "MAX",
+    "LISTAGG",
+    "STDDEV",
+    "VARIANCE",
+    "MEDIAN",
+    "COLLECT",
+    "XMLAGG",
+    "JSON_ARRAYAGG",
+    "JSON_OBJECTAGG",
+];
+
+/// Keywords that end the FROM clause of a single-table query.
+const FROM_CLAUSE_TERMINATORS: [&str; 5] = ["WHERE", "ORDER", "FETCH", "OFFSET", "FOR"];
+
+/// Names that look like columns in a select list but are not table columns.
+const PSEUDO_COLUMNS: [&str; 11] = [
+    "ROWID",
+    "ROWNUM",
+    "LEVEL",
+    "NULL",
+    "SYSDATE",
+    "SYSTIMESTAMP",
+    "CURRENT_DATE",
+    "CURRENT_TIMESTAMP",
+    "LOCALTIMESTAMP",
+    "USER",
+    "UID",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Word,
+    QuotedIdentifier,
+    Literal,
+    Symbol,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    start: usize,
+    end: usize,
+    /// Parenthesis depth the token sits at; `(` and `)` carry the outer depth.
+    pub(super) depth: usize,
+}
+
+impl Token<'_> {
+    pub(super) fn is_word(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(word)
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        self.kind == TokenKind::Symbol && self.text == symbol
+    }
+
+    fn is_identifier(&self) -> bool {
+        matches!(self.kind, TokenKind::Word | TokenKind::QuotedIdentifier)
+    }
+
+    /// Dictionary form of an identifier: unquoted names fold to upper case.
+    fn normalized(&self) -> String {
+        match self.kind {
+            TokenKind::QuotedIdentifier => self.text.trim_matches('"').to_string(),
+            _ => self.text.to_uppercase(),
+        }
+    }
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '$' || ch == '#'
+}
+
+/// Split `sql` into tokens, skipping whitespace and comments.
+/// Returns `None` for unterminated literals, comments or parentheses.
+pub(super) fn tokenize(sql: &str) -> Option<Vec<Token<'_>>> {
+    let chars: Vec<(usize, char)> = sql.char_indices().collect();
+    let byte_at = |index: usize| chars.get(index).map(|(pos, _)| *pos).unwrap_or(sql.len());
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0usize;
+
+    while i < chars.len() {
+        let ch = chars[i].1;
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        let start = i;
+
+        if ch.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if ch == '-' && next == Some('-') {
+            while i < chars.len() && chars[i].1 != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if ch == '/' && next == Some('*') {
+            i += 2;
+            loop {
+                if i + 1 >= chars.len() {
+                    return None;
+                }
+                if chars[i].1 == '*' && chars[i + 1].1 == '/' {
+                    i += 2;
+                    break;
+                }
+                i += 1;
+            }
+            continue;
+        }
+
+        let kind = if ch == '\'' {
+            i += 1;
+            loop {
+                let (_, c) = *chars.get(i)?;
+                i += 1;
+                if c == '\'' {
+                    if chars.get(i).map(|(_, c)| *c) == Some('\'') {
+                        i += 1;
+                        continue;
+                    }
+                    break;
+                }
+            }
+            TokenKind::Literal
+        } else if ch == '"' {
+            i += 1;
+            loop {
+                let (_, c) = *chars.get(i)?;
+                i += 1;
+                if c == '"' {
+                    break;
+                }
+            }
+            TokenKind::QuotedIdentifier
+        } else if ch.is_ascii_digit() {
+            while i < chars.len() && (is_word_char(chars[i].1) || chars[i].1 == '.') {
+                i += 1;
+            }
+            TokenKind::Literal
+        } else if is_word_char(ch) {
+            while i < chars.len() && is_word_char(chars[i].1) {
+                i += 1;
+            }
+            let word = &sql[byte_at(start)..byte_at(i)];
+            let is_q_prefix = word.eq_ignore_ascii_case("q") || word.eq_ignore_ascii_case("nq");
+            if is_q_prefix && chars.get(i).map(|(_, c)| *c) == Some('\'') {
+                // q'[...]' literal: the delimiter follows the opening quote.
+                let (_, open) = *chars.get(i + 1)?;
+                let close = match open {
+                    '[' => ']',
+                    '(' => ')',
+                    '{' => '}',
+                    '<' => '>',
+                    other => other,
+                };
+                i += 2;
+                loop {
+                    let (_, c) = *chars.get(i)?;
+                    i += 1;
+                    if c == close && chars.get(i).map(|(_, c)| *c) == Some('\'') {
+                        i += 1;
+                        break;
+                    }
+                }
+                TokenKind::Literal
+            } else if (word.eq_ignore_ascii_case("n") || word.eq_ignore_ascii_case("x"))
+                && chars.get(i).map(|(_, c)| *c) == Some('\'')
+            {
+                // N'...' and X'...' literals.
+                i += 1;
+                loop {
+                    let (_, c) = *chars.get(i)?;
+                    i += 1;
+                    if c == '\'' {
+                        if chars.get(i).map(|(_, c)| *c) == Some('\'') {
+                            i += 1;
+                            continue;
+                        }
+                        break;
+                    }
+                }
+                TokenKind::Literal
+            } else {
+                TokenKind::Word
+            }
+        } else {
+            i += 1;
+            TokenKind::Symbol
+        };
+
+        let text = &sql[byte_at(start)..byte_at(i)];
+        let token_depth = if kind == TokenKind::Symbol && text == ")" {
+            depth = depth.checked_sub(1)?;
+            depth
+        } else {
+            depth
+        };
+        if kind == TokenKind::Symbol && text == "(" {
+            depth += 1;
+        }
+        tokens.push(Token {
+            kind,
+            text,
+            start: byte_at(start),
+            end: byte_at(i),
+            depth: token_depth,
+        });
+    }
+
+    if depth != 0 {
+        return None;
+    }
+    Some(tokens)
+}
+
+/// Index of the `)` matching the `(` at `open`.
+fn matching_paren(tokens: &[Token], open: usize) -> Option<usize> {
+    let depth = tokens.get(open)?.depth;
+    tokens
+        .iter()
+        .enumerate()
+        .skip(open + 1)
+        .find(|(_, token)| token.depth == depth && token.is_symbol(")"))
+        .map(|(index, _)| index)
+}
+
+/// Whether `value` starts like [`CELL_DATE_MASK`], e.g. `2024-01-31`.
+fn in_cell_date_layout(value: &str) -> bool {
+    let bytes = value.trim().as_bytes();
+    bytes.len() >= 10
+        && bytes[..10]
+            .iter()
+            .enumerate()
+            .all(|(index, byte)| match index {
+                4 | 7 => *byte == b'-',
+                _ => byte.is_ascii_digit(),
+            })
+}
+
+/// SQL expression converting `operand` (a bind placeholder or a quoted
+/// literal holding `value`) from the text the grid shows back into the
+/// column's type. Dates typed in another layout are read with the
+/// session's NLS format.
+fn typed_value_expression(kind: CellKind, value: &str, operand: &str) -> String {
+    match kind {
+        CellKind::Date | CellKind::Timestamp if !in_cell_date_layout(value) => {
+            let function = if kind == CellKind::Date {
+                "TO_DATE"
+            } else {
+                "TO_TIMESTAMP"
+            };
+            format!("{}({})", function, operand)
+        }
+        CellKind::Date => format!("TO_DATE({}, '{}')", operand, CELL_DATE_MASK),
+        CellKind::Timestamp => {
+            let trimmed = value.trim();
+            let with_tz = trimmed
+                .rsplit(' ')
+                .next()
+                .map(|last| last.starts_with('+') || last.starts_with('-'))
+                .unwrap_or(false);
+            let has_fraction = trimmed
+                .split(' ')
+                .nth(1)
+                .map(|time| time.contains('.'))
+                .unwrap_or(false);
+            let mut mask = String::from(CELL_DATE_MASK);
+            if has_fraction {
+                mask.push_str(".FF");
+            }
+            if with_tz {
+                mask.push_str(" TZH:TZM");
+                format!("TO_TIMESTAMP_TZ({}, '{}')", operand, mask)
+            } else {
+                format!("TO_TIMESTAMP({}, '{}')", operand, mask)
+            }
+        }
+        CellKind::Raw => format!("HEXTORAW({})", operand),
+        CellKind::Number | CellKind::Text | CellKind::Lob => operand.to_string(),
+    }
+}
+
+/// A column or table name as a quoted identifier.
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Self-contained SQL literal for a cell, as used in generated INSERT scripts.
+pub fn sql_literal(value: &CellValue) -> String {
+    match (value.kind(), value.as_str()) {
+        (Some(CellKind::Number), Some(text)) => text.to_string(),
+        (Some(kind), Some(text)) => {
+            typed_value_expression(kind, text, &format!("'{}'", text.replace('\'', "''")))
+        }
+        _ => "NULL".to_string(),
+    }
+}
+
+/// The table behind a single-table SELECT whose rows can be written back
+/// through their ROWID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditableTable {
+    /// Owner in dictionary form, when the query qualifies the table.
+    pub owner: Option<String>,
+    /// Table name in dictionary form.
+    pub name: String,
+    /// Table reference as written in the query; generated DML reuses it.
+    pub sql_name: String,
+    /// Dictionary column behind each select-list item (`None` for
+    /// expressions), or `None` when the query selects `*`.
+    columns: Option<Vec<Option<String>>>,
+}
+
+impl EditableTable {
+    /// Recognise a SELECT whose rows map one-to-one onto rows of a single
+    /// table, returning the table and the query rewritten to also fetch
+    /// each row's ROWID as a trailing `ROWID_COLUMN_ALIAS` column.
+    ///
+    /// This is deliberately conservative: joins, subqueries in FROM,
+    /// set operators, grouping, DISTINCT and aggregates are all rejected.
+    pub fn parse_select(sql: &str) -> Option<(EditableTable, String)> {
+        let mut tokens = tokenize(sql)?;
+        while tokens.last().map(|token| token.is_symbol(";")) == Some(true) {
+            tokens.pop();
+        }
+        if !tokens.first()?.is_word("SELECT") {
+            return None;
+        }
+        if tokens.iter().any(|token| {
+            token.depth == 0
+                && NON_EDITABLE_KEYWORDS
+                    .iter()
+                    .any(|keyword| token.is_word(keyword))
+        }) {
+            return None;
+        }
+
+        let from_index = tokens
+            .iter()
+            .position(|token| token.depth == 0 && token.is_word("FROM"))?;
+        let select_list = &tokens[1..from_index];
+        if select_list.is_empty() || Self::has_aggregate(select_list) {
+            return None;
+        }
+
+        let from_end = tokens
+            .iter()
+            .enumerate()
+            .skip(from_index + 1)
+            .find(|(_, token)| {
+                token.depth == 0
+                    && FROM_CLAUSE_TERMINATORS
+                        .iter()
+                        .any(|keyword| token.is_word(keyword))
+            })
+            .map(|(index, _)| index)
+            .unwrap_or(tokens.len());
+        let from_clause = &tokens[from_index + 1..from_end];
+        let (owner, name, alias) = Self::parse_table_reference(from_clause)?;
+
+        let table_tokens = if owner.is_some() {
+            &from_clause[..3]
+        } else {
+            &from_clause[..1]
+        };
+        let sql_name = table_tokens
+            .iter()
+            .map(|token| token.text)
+            .collect::<Vec<&str>>()
+            .join("");
+        let qualifier = alias
+            .map(|token| token.text.to_string())
+            .unwrap_or_else(|| sql_name.clone());
+        let columns = Self::select_list_columns(select_list, &qualifier, name)?;
+
+        // Append the ROWID after the last select-list token so a trailing
+        // line comment cannot swallow it.
+        let list_end = select_list.last()?.end;
+        let mut rewritten = String::with_capacity(sql.len() + 64);
+        match select_list {
+            [star] if star.is_symbol("*") => {
+                rewritten.push_str(&sql[..star.start]);
+                rewritten.push_str(&qualifier);
+                rewritten.push_str(".*");
+            }
+            _ => rewritten.push_str(&sql[..list_end]),
+        }
+        rewritten.push_str(&format!(", {}.ROWID AS {}", qualifier, ROWID_COLUMN_ALIAS));
+        rewritten.push_str(&sql[list_end..]);
+
+        Some((
+            EditableTable {
+                owner: owner.map(|token| token.normalized()),
+                name: name.normalized(),
+                sql_name,
+                columns,
+            },
+            rewritten,
+        ))
+    }
+
+    fn has_aggregate(select_list: &[Token]) -> bool {
+        select_list.iter().enumerate().any(|(index, token)| {
+            let is_call = token.depth == 0
+                && AGGREGATE_FUNCTIONS.iter().any(|name| token.is_word(name))
+                && select_list
+                    .get(index + 1)
+                    .map(|next| next.is_symbol("("))
+                    .unwrap_or(false);
+            if !is_call {
+                return false;
+            }
+            // An aggregate with OVER (...) is an analytic function: one value per row.
+            let is_analytic = matching_paren(select_list, index + 1)
+                .and_then(|close| select_list.get(close + 1))
+                .map(|next| next.is_word("OVER"))
+                .unwrap_or(false);
+            !is_analytic
+        })
+    }
+
+    /// `[owner.]table [alias]`, with nothing else in the FROM clause.
+    #[allow(clippy::type_complexity)]
+    fn parse_table_reference<'t, 'a>(
+        from_clause: &'t [Token<'a>],
+    ) -> Option<(Option<&'t Token<'a>>, &'t Token<'a>, Option<&'t Token<'a>>)> {
+        let is_alias = |token: &Token| {
+            token.is_identifier()
+                && !token.is_word("AS")
+                && !token.is_word("SAMPLE")
+                && !token.is_word("PARTITION")
+        };
+        match from_clause {
+            [name] if name.is_identifier() => Some((None, name, None)),
+            [name, alias] if name.is_identifier() && is_alias(alias) => {
+                Some((None, name, Some(alias)))
+            }
+            [owner, dot, name]
+                if owner.is_identifier() && dot.is_symbol(".") && name.is_identifier() =>
+            {
+                Some((Some(owner), name, None))
+            }
+            [owner, dot, name, alias]
+                if owner.is_identifier()
+                    && dot.is_symbol(".")
+                    && name.is_identifier()
+                    && is_alias(alias) =>
+            {
+                Some((Some(owner), name, Some(alias)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Map select-list items to table columns. Returns `Some(None)` for a
+    /// lone `*` (column headers are then the column names) and `None` when
+    /// `*` is mixed with other items.
+    fn select_list_columns(
+        select_list: &[Token],
+        qualifier: &str,
+        table_name: &Token,
+    ) -> Option<Option<Vec<Option<String>>>> {
+        let items: Vec<&[Token]> = select_list
+            .split(|token| token.depth == 0 && token.is_symbol(","))
+            .collect();
+        let refers_to_table = |token: &Token| {
+            token.text.eq_ignore_ascii_case(qualifier)
+                || token.normalized() == table_name.normalized()
+        };
+        let is_star = |item: &[Token]| match item {
+            [star] => star.is_symbol("*"),
+            [prefix, dot, star] => {
+                refers_to_table(prefix) && dot.is_symbol(".") && star.is_symbol("*")
+            }
+            _ => false,
+        };
+
+        if items.iter().any(|item| is_star(item)) {
+            return if items.len() == 1 { Some(None) } else { None };
+        }
+
+        let is_column = |token: &Token| {
+            token.kind == TokenKind::QuotedIdentifier
+                || (token.kind == TokenKind::Word
+                    && !PSEUDO_COLUMNS.iter().any(|name| token.is_word(name)))
+        };
+        let columns = items
+            .iter()
+            .map(|item| {
+                let (column, rest) = match item {
+                    [prefix, dot, column, rest @ ..]
+                        if refers_to_table(prefix) && dot.is_symbol(".") && is_column(column) =>
+                    {
+                        (column, rest)
+                    }
+                    [column, rest @ ..] if is_column(column) => (column, rest),
+                    _ => return None,
+                };
+                let plain_alias = match rest {
+                    [] => true,
+                    [alias] => alias.is_identifier(),
+                    [as_kw, alias] => as_kw.is_word("AS") && alias.is_identifier(),
+                    _ => false,
+                };
+                plain_alias.then(|| column.normalized())
+            })
+            .collect();
+        Some(Some(columns))
+    }
+
+    /// Pair the fetched result columns (without the ROWID column) with the
+    /// table columns they came from.
+    pub fn edit_target(&self, columns: &[ColumnInfo]) -> Option<EditTarget> {
+        let names: Vec<Option<String>> = match &self.columns {
+            Some(mapped) if mapped.len() == columns.len() => mapped.clone(),
+            Some(_) => return None,
+            None => columns
+                .iter()
+                .map(|column| Some(column.name.clone()))
+                .collect(),
+        };
+        Some(EditTarget {
+            table: self.sql_name.clone(),
+            columns: names
+                .into_iter()
+                .zip(columns)
+                .map(|(name, column)| {
+                    name.map(|name| EditColumn {
+                        name,
+                        kind: column.kind,
+                    })
+                })
+                .collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditColumn {
+    /// Column name in dictionary form.
+    pub name: String,
+    pub kind: CellKind,
+}
+
+/// Where edits made in a result grid are written back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditTarget {
+    /// Table reference used in generated DML.
+    pub table: String,
+    /// Table column behind each result column; `None` for expressions.
+    pub columns: Vec<Option<EditColumn>>,
+}
+
+/// One pending row edit, with values keyed by result column index.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowChange {
+    Insert {
+        values: Vec<(usize, CellValue)>,
+    },
+    Update {
+        rowid: String,
+        values: Vec<(usize, CellValue)>,
+    },
+    Delete {
+        rowid: String,
+    },
+}
+
+/// A generated DML statement with its positional bind values (`:1`, `:2`, ...).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DmlStatement {
+    pub sql: String,
+    pub binds: Vec<String>,
+}
+
+impl DmlStatement {
+    /// The statement followed by its bind values, for review before applying.
+    pub fn preview(&self) -> String {
+        let mut text = format!("{};", self.sql);
+        for (index, value) in self.binds.iter().enumerate() {
+            text.push_str(&format!(
+                "\n  -- :{} = '{}'",
+                index + 1,
+                value.replace('\'', "''")
+            ));
+        }
+        text
+    }
+}
+
+impl EditTarget {
+    /// Whether cells of `column` can be changed. LOB columns stay read-only.
+    pub fn is_editable_column(&self, column: usize) -> bool {
+        self.column(column)
+            .map(|column| column.kind != CellKind::Lob)
+            .unwrap_or(false)
+    }
+
+    fn column(&self, column: usize) -> Option<&EditColumn> {
+        self.columns.get(column).and_then(Option::as_ref)
+    }
+
+    /// Editable columns among `values`, as (quoted name, SQL expression),
+    /// pushing bind values as it goes. NULLs are written literally.
+    fn assignments(
+        &self,
+        values: &[(usize, CellValue)],
+        binds: &mut Vec<String>,
+    ) -> Vec<(String, String)> {
+        values
+            .iter()
+            .filter(|(column, _)| self.is_editable_column(*column))
+            .filter_map(|(column, value)| {
+                let edit_column = self.column(*column)?;
+                let expression = match value.as_str() {
+                    Some(text) => {
+                        binds.push(text.to_string());
+                        typed_value_expression(edit_column.kind, text, &format!(":{}", binds.len()))
+                    }
+                    None => "NULL".to_string(),
+                };
+                Some((quote_identifier(&edit_column.name), expression))
+            })
+            .collect()
+    }
+
+    /// Parameterized DML for one row change; `None` when nothing editable changed.
+    pub fn build_dml(&self, change: &RowChange) -> Option<DmlStatement> {
+        let mut binds = Vec::new();
+        let sql = match change {
+            RowChange::Insert { values } => {
+                let non_null: Vec<(usize, CellValue)> = values
+                    .iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .cloned()
+                    .collect();
+                let mut assignments = self.assignments(&non_null, &mut binds);
+                if assignments.is_empty() {
+                    // An all-NULL row still needs one column in the statement.
+                    let first = (0..self.columns.len())
+                        .find(|&column| self.is_editable_column(column))
+                        .and_then(|column| self.column(column))?;
+                    assignments.push((quote_identifier(&first.name), "NULL".to_string()));
+                }
+                let (names, expressions): (Vec<String>, Vec<String>) =
+                    assignments.into_iter().unzip();
+                format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    self.table,
+                    names.join(", "),
+                    expressions.join(", ")
+                )
+            }
+            RowChange::Update { rowid, values } => {
+                let assignments = self.assignments(values, &mut binds);
+                if assignments.is_empty() {
+                    return None;
+                }
+                let set_list = assignments
+                    .into_iter()
+                    .map(|(name, expression)| format!("{} = {}", name, expression))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                binds.push(rowid.clone());
+                format!(
+                    "UPDATE {} SET {} WHERE ROWID = :{}",
+                    self.table,
+                    set_list,
+                    binds.len()
+                )
+            }
+            RowChange::Delete { rowid } => {
+                binds.push(rowid.clone());
+                format!("DELETE FROM {} WHERE ROWID = :1", self.table)
+            }
+        };
+        Some(DmlStatement { sql, binds })
+    }
+}
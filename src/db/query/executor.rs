@@ -1,11 +1,21 @@
-use oracle::sql_type::{OracleType, RefCursor};
+use oracle::sql_type::{OracleType, RefCursor, Timestamp, ToSql};
 use oracle::{Connection, Error as OracleError, Row, Statement};
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 
 use crate::db::session::{BindDataType, BindValue, CompiledObject, SessionState};
 
-use super::{ColumnInfo, ProcedureArgument, QueryResult, ResolvedBind, ScriptItem};
+use super::{
+    CellKind, CellValue, ColumnInfo, DmlStatement, ExecutionPlan, PlanRuntimeStats, PlanSource,
+    PlanStep, ProcedureArgument, QueryResult, ResolvedBind, ScriptItem,
+};
+
+/// Savepoint taken before applying result grid edits.
+const GRID_EDIT_SAVEPOINT: &str = "SPACE_QUERY_GRID_EDIT";
+
+/// Savepoint taken before EXPLAIN PLAN when the user has a transaction
+/// open, so its PLAN_TABLE rows can be rolled back without touching it.
+const EXPLAIN_PLAN_SAVEPOINT: &str = "SPACE_QUERY_EXPLAIN";
 
 pub struct QueryExecutor;
 
@@ -1060,7 +1070,7 @@ impl QueryExecutor {
     ) -> Result<(QueryResult, bool), OracleError>
     where
         F: FnMut(&[ColumnInfo]),
-        G: FnMut(Vec<String>) -> bool,
+        G: FnMut(Vec<CellValue>) -> bool,
     {
         let statements = Self::split_statements_with_blocks(sql);
 
@@ -1352,10 +1362,16 @@ impl QueryExecutor {
             .map(|col| ColumnInfo {
                 name: col.name().to_string(),
                 data_type: format!("{:?}", col.oracle_type()),
+                kind: CellKind::from_oracle(col.oracle_type()),
             })
             .collect();
+        let column_types: Vec<OracleType> = result_set
+            .column_info()
+            .iter()
+            .map(|col| col.oracle_type().clone())
+            .collect();
 
-        let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut rows: Vec<Vec<CellValue>> = Vec::new();
 
         for row_result in result_set {
             let row: Row = match row_result {
@@ -1365,12 +1381,7 @@ impl QueryExecutor {
                     return Err(err);
                 }
             };
-            let mut row_data: Vec<String> = Vec::with_capacity(column_info.len());
-
-            for i in 0..column_info.len() {
-                let value: Option<String> = row.get(i).unwrap_or(None);
-                row_data.push(value.unwrap_or_else(|| "NULL".to_string()));
-            }
+            let row_data = Self::read_row_cells(&row, &column_types);
 
             rows.push(row_data);
         }
@@ -1384,6 +1395,25 @@ impl QueryExecutor {
         ))
     }
 
+    fn read_row_cells(row: &Row, column_types: &[OracleType]) -> Vec<CellValue> {
+        column_types
+            .iter()
+            .enumerate()
+            .map(|(i, oracle_type)| {
+                let value: Option<String> = match CellKind::from_oracle(oracle_type) {
+                    // Formatted here rather than by NLS_DATE_FORMAT, in the
+                    // layout of CELL_DATE_MASK.
+                    CellKind::Date | CellKind::Timestamp => row
+                        .get::<_, Option<Timestamp>>(i)
+                        .unwrap_or(None)
+                        .map(|value| value.to_string()),
+                    _ => row.get(i).unwrap_or(None),
+                };
+                CellValue::from_oracle(oracle_type, value)
+            })
+            .collect()
+    }
+
     /// Execute a SELECT statement with streaming results.
     /// on_row returns true to continue, false to stop fetching.
     /// Returns (QueryResult, was_cancelled) tuple.
@@ -1395,7 +1425,7 @@ impl QueryExecutor {
     ) -> Result<(QueryResult, bool), OracleError>
     where
         F: FnMut(&[ColumnInfo]),
-        G: FnMut(Vec<String>) -> bool,
+        G: FnMut(Vec<CellValue>) -> bool,
     {
         let start = Instant::now();
         let mut stmt = match conn.statement(sql).build() {
@@ -1419,8 +1449,14 @@ impl QueryExecutor {
             .map(|col| ColumnInfo {
                 name: col.name().to_string(),
                 data_type: format!("{:?}", col.oracle_type()),
+                kind: CellKind::from_oracle(col.oracle_type()),
             })
             .collect();
+        let column_types: Vec<OracleType> = result_set
+            .column_info()
+            .iter()
+            .map(|col| col.oracle_type().clone())
+            .collect();
 
         on_select_start(&column_info);
 
@@ -1435,12 +1471,7 @@ impl QueryExecutor {
                     return Err(err);
                 }
             };
-            let mut row_data: Vec<String> = Vec::with_capacity(column_info.len());
-
-            for i in 0..column_info.len() {
-                let value: Option<String> = row.get(i).unwrap_or(None);
-                row_data.push(value.unwrap_or_else(|| "NULL".to_string()));
-            }
+            let row_data = Self::read_row_cells(&row, &column_types);
 
             let should_continue = on_row(row_data);
             row_count += 1;
@@ -1467,7 +1498,7 @@ impl QueryExecutor {
     ) -> Result<(QueryResult, bool), OracleError>
     where
         F: FnMut(&[ColumnInfo]),
-        G: FnMut(Vec<String>) -> bool,
+        G: FnMut(Vec<CellValue>) -> bool,
     {
         let start = Instant::now();
         let mut stmt = match conn.statement(sql).build() {
@@ -1495,8 +1526,14 @@ impl QueryExecutor {
             .map(|col| ColumnInfo {
                 name: col.name().to_string(),
                 data_type: format!("{:?}", col.oracle_type()),
+                kind: CellKind::from_oracle(col.oracle_type()),
             })
             .collect();
+        let column_types: Vec<OracleType> = result_set
+            .column_info()
+            .iter()
+            .map(|col| col.oracle_type().clone())
+            .collect();
 
         on_select_start(&column_info);
 
@@ -1511,12 +1548,7 @@ impl QueryExecutor {
                     return Err(err);
                 }
             };
-            let mut row_data: Vec<String> = Vec::with_capacity(column_info.len());
-
-            for i in 0..column_info.len() {
-                let value: Option<String> = row.get(i).unwrap_or(None);
-                row_data.push(value.unwrap_or_else(|| "NULL".to_string()));
-            }
+            let row_data = Self::read_row_cells(&row, &column_types);
 
             let should_continue = on_row(row_data);
             row_count += 1;
@@ -1534,6 +1566,73 @@ impl QueryExecutor {
         ))
     }
 
+    /// Whether `owner.table_name` is a table (not a view or synonym); `None`
+    /// looks in the session's current schema.
+    pub fn is_table(
+        conn: &Connection,
+        owner: Option<&str>,
+        table_name: &str,
+    ) -> Result<bool, OracleError> {
+        let sql = "SELECT COUNT(*) FROM all_tables \
+                   WHERE owner = NVL(:1, SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')) \
+                   AND table_name = :2";
+        let row = match conn.query_row(sql, &[&owner, &table_name]) {
+            Ok(row) => row,
+            Err(err) => {
+                eprintln!("Database operation failed: {err}");
+                return Err(err);
+            }
+        };
+        let count: i64 = row.get(0)?;
+        Ok(count > 0)
+    }
+
+    /// Run generated grid DML inside the current transaction without
+    /// committing. A failing statement rolls back to a savepoint taken
+    /// first, so either every change is applied or none is.
+    /// Returns the number of rows each statement affected.
+    pub fn execute_dml_statements(
+        conn: &Connection,
+        statements: &[DmlStatement],
+    ) -> Result<Vec<u64>, OracleError> {
+        if let Err(err) = conn.execute(&format!("SAVEPOINT {}", GRID_EDIT_SAVEPOINT), &[]) {
+            eprintln!("Database operation failed: {err}");
+            return Err(err);
+        }
+
+        let mut counts = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let binds: Vec<&dyn ToSql> = statement
+                .binds
+                .iter()
+                .map(|value| value as &dyn ToSql)
+                .collect();
+            let result = conn
+                .execute(&statement.sql, &binds)
+                .and_then(|stmt| stmt.row_count());
+            match result {
+                Ok(count) => counts.push(count),
+                Err(err) => {
+                    eprintln!("Database operation failed: {err}");
+                    if let Err(rollback_err) = Self::rollback_dml_statements(conn) {
+                        eprintln!("Database operation failed: {rollback_err}");
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Undo the statements run by the last `execute_dml_statements` call.
+    pub fn rollback_dml_statements(conn: &Connection) -> Result<(), OracleError> {
+        conn.execute(
+            &format!("ROLLBACK TO SAVEPOINT {}", GRID_EDIT_SAVEPOINT),
+            &[],
+        )
+        .map(|_| ())
+    }
+
     pub fn execute_ref_cursor_streaming<F, G>(
         cursor: &mut RefCursor,
         sql: &str,
@@ -1542,7 +1641,7 @@ impl QueryExecutor {
     ) -> Result<(QueryResult, bool), OracleError>
     where
         F: FnMut(&[ColumnInfo]),
-        G: FnMut(Vec<String>) -> bool,
+        G: FnMut(Vec<CellValue>) -> bool,
     {
         let start = Instant::now();
         let result_set = match cursor.query() {
@@ -1559,8 +1658,14 @@ impl QueryExecutor {
             .map(|col| ColumnInfo {
                 name: col.name().to_string(),
                 data_type: format!("{:?}", col.oracle_type()),
+                kind: CellKind::from_oracle(col.oracle_type()),
             })
             .collect();
+        let column_types: Vec<OracleType> = result_set
+            .column_info()
+            .iter()
+            .map(|col| col.oracle_type().clone())
+            .collect();
 
         on_select_start(&column_info);
 
@@ -1575,12 +1680,7 @@ impl QueryExecutor {
                     return Err(err);
                 }
             };
-            let mut row_data: Vec<String> = Vec::with_capacity(column_info.len());
-
-            for i in 0..column_info.len() {
-                let value: Option<String> = row.get(i).unwrap_or(None);
-                row_data.push(value.unwrap_or_else(|| "NULL".to_string()));
-            }
+            let row_data = Self::read_row_cells(&row, &column_types);
 
             let should_continue = on_row(row_data);
             row_count += 1;
@@ -2338,9 +2438,92 @@ impl QueryExecutor {
         Ok(rows)
     }
 
-    pub fn get_explain_plan(conn: &Connection, sql: &str) -> Result<Vec<String>, OracleError> {
-        let explain_sql = format!("EXPLAIN PLAN FOR {}", sql);
-        match conn.execute(&explain_sql, &[]) {
+    /// Explain `sql` into `PLAN_TABLE` and read the plan back as a tree of
+    /// steps. The plan rows are removed again afterwards.
+    pub fn get_explain_plan(conn: &Connection, sql: &str) -> Result<ExecutionPlan, OracleError> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or(0);
+        let statement_id = format!("SPACE_QUERY_{nanos}");
+        let explain_sql = format!(
+            "EXPLAIN PLAN SET STATEMENT_ID = '{}' INTO PLAN_TABLE FOR {}",
+            statement_id,
+            sql.trim().trim_end_matches(';')
+        );
+        // A partial rollback leaves the transaction EXPLAIN PLAN started
+        // open, so without one of the user's a full rollback cleans up.
+        let user_transaction = crate::db::transaction_open(conn).unwrap_or(true);
+        if user_transaction {
+            if let Err(err) = conn.execute(&format!("SAVEPOINT {}", EXPLAIN_PLAN_SAVEPOINT), &[]) {
+                eprintln!("Database operation failed: {err}");
+                return Err(err);
+            }
+        }
+
+        let steps = match conn.execute(&explain_sql, &[]) {
+            Ok(_stmt) => {
+                let plan_sql = "SELECT id, parent_id, depth, operation, options, object_owner, \
+                     object_name, cost, cardinality, bytes, access_predicates, filter_predicates \
+                     FROM plan_table WHERE statement_id = :1 ORDER BY id";
+                Self::query_plan_steps(conn, plan_sql, &[&statement_id], false)
+            }
+            Err(err) => {
+                eprintln!("Database operation failed: {err}");
+                Err(err)
+            }
+        };
+
+        let cleared = if user_transaction {
+            conn.execute(
+                &format!("ROLLBACK TO SAVEPOINT {}", EXPLAIN_PLAN_SAVEPOINT),
+                &[],
+            )
+            .map(|_| ())
+        } else {
+            conn.rollback()
+        };
+        if let Err(err) = cleared {
+            eprintln!("Warning: failed to clear explained plan rows: {err}");
+        }
+
+        Ok(ExecutionPlan::new(PlanSource::Explain, steps?))
+    }
+
+    /// The plan of a cached cursor, with last-execution statistics when
+    /// the statement ran with `STATISTICS_LEVEL = ALL` or the
+    /// `GATHER_PLAN_STATISTICS` hint.
+    pub fn get_cursor_plan(
+        conn: &Connection,
+        sql_id: &str,
+        child_number: u32,
+    ) -> Result<ExecutionPlan, OracleError> {
+        let plan_sql = "SELECT id, parent_id, depth, operation, options, object_owner, \
+             object_name, cost, cardinality, bytes, access_predicates, filter_predicates, \
+             last_starts, last_output_rows, last_elapsed_time, last_cr_buffer_gets, \
+             last_disk_reads \
+             FROM v$sql_plan_statistics_all WHERE sql_id = :1 AND child_number = :2 \
+             ORDER BY id";
+        let steps = Self::query_plan_steps(conn, plan_sql, &[&sql_id, &child_number], true)?;
+        Ok(ExecutionPlan::new(
+            PlanSource::Cursor {
+                sql_id: sql_id.to_string(),
+                child_number,
+            },
+            steps,
+        ))
+    }
+
+    /// Run the query `sql` with plan statistics enabled, fetch and discard
+    /// its rows, and return the actual plan it used.
+    pub fn autotrace(conn: &Connection, sql: &str) -> Result<ExecutionPlan, OracleError> {
+        let previous_level: String = conn
+            .query_row_as::<String>(
+                "SELECT value FROM v$parameter WHERE name = 'statistics_level'",
+                &[],
+            )
+            .unwrap_or_else(|_| "TYPICAL".to_string());
+        match conn.execute("ALTER SESSION SET STATISTICS_LEVEL = ALL", &[]) {
             Ok(_stmt) => {}
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -2348,9 +2531,41 @@ impl QueryExecutor {
             }
         }
 
-        let plan_sql =
-            "SELECT plan_table_output FROM TABLE(DBMS_XPLAN.DISPLAY('PLAN_TABLE', NULL, 'ALL'))";
-        let mut stmt = match conn.statement(plan_sql).build() {
+        let executed = Self::run_and_discard_rows(conn, sql.trim().trim_end_matches(';'));
+        // Read the cursor before anything else runs on this session.
+        let cursor = conn.query_row_as::<(Option<String>, Option<u32>)>(
+            "SELECT prev_sql_id, prev_child_number FROM v$session \
+             WHERE sid = SYS_CONTEXT('USERENV', 'SID')",
+            &[],
+        );
+
+        let restore_sql = format!(
+            "ALTER SESSION SET STATISTICS_LEVEL = {}",
+            previous_level.replace('\'', "")
+        );
+        if let Err(err) = conn.execute(&restore_sql, &[]) {
+            eprintln!("Warning: failed to restore STATISTICS_LEVEL: {err}");
+        }
+
+        executed?;
+        let (sql_id, child_number) = match cursor {
+            Ok((Some(sql_id), child_number)) => (sql_id, child_number.unwrap_or(0)),
+            Ok((None, _)) => {
+                return Err(OracleError::new(
+                    oracle::ErrorKind::Other,
+                    "The executed statement was not found in V$SESSION".to_string(),
+                ));
+            }
+            Err(err) => {
+                eprintln!("Database operation failed: {err}");
+                return Err(err);
+            }
+        };
+        Self::get_cursor_plan(conn, &sql_id, child_number)
+    }
+
+    fn run_and_discard_rows(conn: &Connection, sql: &str) -> Result<(), OracleError> {
+        let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -2364,8 +2579,40 @@ impl QueryExecutor {
                 return Err(err);
             }
         };
+        for row_result in rows {
+            if let Err(err) = row_result {
+                eprintln!("Database operation failed: {err}");
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Read plan rows whose first twelve columns follow `PLAN_TABLE`
+    /// (id .. filter_predicates), optionally followed by the five
+    /// `LAST_*` statistics columns.
+    fn query_plan_steps(
+        conn: &Connection,
+        sql: &str,
+        params: &[&dyn ToSql],
+        with_stats: bool,
+    ) -> Result<Vec<PlanStep>, OracleError> {
+        let mut stmt = match conn.statement(sql).build() {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                eprintln!("Database operation failed: {err}");
+                return Err(err);
+            }
+        };
+        let rows = match stmt.query(params) {
+            Ok(rows) => rows,
+            Err(err) => {
+                eprintln!("Database operation failed: {err}");
+                return Err(err);
+            }
+        };
 
-        let mut plan_lines: Vec<String> = Vec::new();
+        let mut steps = Vec::new();
         for row_result in rows {
             let row: Row = match row_result {
                 Ok(row) => row,
@@ -2374,19 +2621,38 @@ impl QueryExecutor {
                     return Err(err);
                 }
             };
-            let line: Option<String> = match row.get(0) {
-                Ok(line) => line,
-                Err(err) => {
-                    eprintln!("Database operation failed: {err}");
-                    return Err(err);
+            let runtime = if with_stats {
+                let starts: Option<u64> = row.get(12)?;
+                match starts {
+                    Some(_) => Some(PlanRuntimeStats {
+                        starts,
+                        actual_rows: row.get(13)?,
+                        elapsed_us: row.get(14)?,
+                        buffer_gets: row.get(15)?,
+                        disk_reads: row.get(16)?,
+                    }),
+                    None => None,
                 }
+            } else {
+                None
             };
-            if let Some(l) = line {
-                plan_lines.push(l);
-            }
+            steps.push(PlanStep {
+                id: row.get(0)?,
+                parent_id: row.get(1)?,
+                depth: row.get::<_, Option<u32>>(2)?.unwrap_or(0),
+                operation: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                options: row.get(4)?,
+                object_owner: row.get(5)?,
+                object_name: row.get(6)?,
+                cost: row.get(7)?,
+                cardinality: row.get(8)?,
+                bytes: row.get(9)?,
+                access_predicates: row.get(10)?,
+                filter_predicates: row.get(11)?,
+                runtime,
+            });
         }
-
-        Ok(plan_lines)
+        Ok(steps)
     }
 }
 
@@ -2450,40 +2716,236 @@ impl ObjectBrowser {
     }
 
     pub fn get_tables(conn: &Connection) -> Result<Vec<String>, OracleError> {
-        let sql = "SELECT table_name FROM user_tables ORDER BY table_name";
-        Self::get_object_list(conn, sql)
+        Self::get_tables_for_owner(conn, None)
     }
 
     pub fn get_views(conn: &Connection) -> Result<Vec<String>, OracleError> {
-        let sql = "SELECT view_name FROM user_views ORDER BY view_name";
-        Self::get_object_list(conn, sql)
+        Self::get_views_for_owner(conn, None)
+    }
+
+    /// List schemas visible to the connected user (ALL_USERS).
+    pub fn get_schemas(conn: &Connection) -> Result<Vec<String>, OracleError> {
+        let sql = "SELECT username FROM all_users ORDER BY username";
+        Self::get_object_list(conn, sql, &[])
+    }
+
+    /// Name of the connected user, i.e. the schema the USER_* views describe.
+    pub fn get_current_user(conn: &Connection) -> Result<String, OracleError> {
+        let sql = "SELECT USER FROM DUAL";
+        let row = match conn.query_row(sql, &[]) {
+            Ok(row) => row,
+            Err(err) => {
+                eprintln!("Database operation failed: {err}");
+                return Err(err);
+            }
+        };
+        row.get(0)
+    }
+
+    // Owner-aware variants: `None` reads the connected user's schema through
+    // USER_* views, `Some(owner)` reads another schema through ALL_* views.
+
+    pub fn get_tables_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+    ) -> Result<Vec<String>, OracleError> {
+        match owner {
+            Some(owner) => Self::get_object_list(
+                conn,
+                "SELECT table_name FROM all_tables WHERE owner = :1 ORDER BY table_name",
+                &[owner],
+            ),
+            None => Self::get_object_list(
+                conn,
+                "SELECT table_name FROM user_tables ORDER BY table_name",
+                &[],
+            ),
+        }
+    }
+
+    pub fn get_views_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+    ) -> Result<Vec<String>, OracleError> {
+        match owner {
+            Some(owner) => Self::get_object_list(
+                conn,
+                "SELECT view_name FROM all_views WHERE owner = :1 ORDER BY view_name",
+                &[owner],
+            ),
+            None => Self::get_object_list(
+                conn,
+                "SELECT view_name FROM user_views ORDER BY view_name",
+                &[],
+            ),
+        }
+    }
+
+    pub fn get_procedures_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+    ) -> Result<Vec<String>, OracleError> {
+        match owner {
+            Some(owner) => Self::get_object_list(
+                conn,
+                "SELECT object_name FROM all_procedures WHERE owner = :1 AND object_type = 'PROCEDURE' ORDER BY object_name",
+                &[owner],
+            ),
+            None => Self::get_object_list(
+                conn,
+                "SELECT object_name FROM user_procedures WHERE object_type = 'PROCEDURE' ORDER BY object_name",
+                &[],
+            ),
+        }
+    }
+
+    pub fn get_functions_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+    ) -> Result<Vec<String>, OracleError> {
+        match owner {
+            Some(owner) => Self::get_object_list(
+                conn,
+                "SELECT object_name FROM all_procedures WHERE owner = :1 AND object_type = 'FUNCTION' ORDER BY object_name",
+                &[owner],
+            ),
+            None => Self::get_object_list(
+                conn,
+                "SELECT object_name FROM user_procedures WHERE object_type = 'FUNCTION' ORDER BY object_name",
+                &[],
+            ),
+        }
+    }
+
+    pub fn get_sequences_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+    ) -> Result<Vec<String>, OracleError> {
+        match owner {
+            Some(owner) => Self::get_object_list(
+                conn,
+                "SELECT sequence_name FROM all_sequences WHERE sequence_owner = :1 ORDER BY sequence_name",
+                &[owner],
+            ),
+            None => Self::get_object_list(
+                conn,
+                "SELECT sequence_name FROM user_sequences ORDER BY sequence_name",
+                &[],
+            ),
+        }
     }
 
-    pub fn get_procedures(conn: &Connection) -> Result<Vec<String>, OracleError> {
-        let sql = "SELECT object_name FROM user_procedures WHERE object_type = 'PROCEDURE' ORDER BY object_name";
-        Self::get_object_list(conn, sql)
+    pub fn get_triggers_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+    ) -> Result<Vec<String>, OracleError> {
+        match owner {
+            Some(owner) => Self::get_object_list(
+                conn,
+                "SELECT trigger_name FROM all_triggers WHERE owner = :1 ORDER BY trigger_name",
+                &[owner],
+            ),
+            None => Self::get_object_list(
+                conn,
+                "SELECT trigger_name FROM user_triggers ORDER BY trigger_name",
+                &[],
+            ),
+        }
+    }
+
+    pub fn get_synonyms_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+    ) -> Result<Vec<String>, OracleError> {
+        match owner {
+            Some(owner) => Self::get_object_list(
+                conn,
+                "SELECT synonym_name FROM all_synonyms WHERE owner = :1 ORDER BY synonym_name",
+                &[owner],
+            ),
+            None => Self::get_object_list(
+                conn,
+                "SELECT synonym_name FROM user_synonyms ORDER BY synonym_name",
+                &[],
+            ),
+        }
     }
 
-    pub fn get_functions(conn: &Connection) -> Result<Vec<String>, OracleError> {
-        let sql = "SELECT object_name FROM user_procedures WHERE object_type = 'FUNCTION' ORDER BY object_name";
-        Self::get_object_list(conn, sql)
+    pub fn get_packages_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+    ) -> Result<Vec<String>, OracleError> {
+        match owner {
+            Some(owner) => Self::get_object_list(
+                conn,
+                "SELECT object_name FROM all_objects WHERE owner = :1 AND object_type = 'PACKAGE' ORDER BY object_name",
+                &[owner],
+            ),
+            None => Self::get_object_list(
+                conn,
+                "SELECT object_name FROM user_objects WHERE object_type = 'PACKAGE' ORDER BY object_name",
+                &[],
+            ),
+        }
     }
 
-    pub fn get_sequences(conn: &Connection) -> Result<Vec<String>, OracleError> {
-        let sql = "SELECT sequence_name FROM user_sequences ORDER BY sequence_name";
-        Self::get_object_list(conn, sql)
+    pub fn get_types_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+    ) -> Result<Vec<String>, OracleError> {
+        match owner {
+            Some(owner) => Self::get_object_list(
+                conn,
+                "SELECT object_name FROM all_objects WHERE owner = :1 AND object_type = 'TYPE' ORDER BY object_name",
+                &[owner],
+            ),
+            None => Self::get_object_list(
+                conn,
+                "SELECT object_name FROM user_objects WHERE object_type = 'TYPE' ORDER BY object_name",
+                &[],
+            ),
+        }
     }
 
-    pub fn get_triggers(conn: &Connection) -> Result<Vec<String>, OracleError> {
-        let sql = "SELECT trigger_name FROM user_triggers ORDER BY trigger_name";
-        Self::get_object_list(conn, sql)
+    /// Uppercase bind values for dictionary lookups, prefixed by the owner
+    /// when one is given.
+    fn dictionary_binds(owner: Option<&str>, values: &[&str]) -> Vec<String> {
+        owner
+            .into_iter()
+            .chain(values.iter().copied())
+            .map(|value| value.to_uppercase())
+            .collect()
     }
 
     pub fn get_sequence_info(
         conn: &Connection,
         seq_name: &str,
     ) -> Result<SequenceInfo, OracleError> {
-        let sql = r#"
+        Self::get_sequence_info_for_owner(conn, None, seq_name)
+    }
+
+    pub fn get_sequence_info_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        seq_name: &str,
+    ) -> Result<SequenceInfo, OracleError> {
+        let sql = if owner.is_some() {
+            r#"
+            SELECT
+                sequence_name,
+                TO_CHAR(min_value),
+                TO_CHAR(max_value),
+                TO_CHAR(increment_by),
+                cycle_flag,
+                order_flag,
+                TO_CHAR(cache_size),
+                TO_CHAR(last_number)
+            FROM all_sequences
+            WHERE sequence_owner = :1
+              AND sequence_name = :2
+            "#
+        } else {
+            r#"
             SELECT
                 sequence_name,
                 TO_CHAR(min_value),
@@ -2495,7 +2957,8 @@ impl ObjectBrowser {
                 TO_CHAR(last_number)
             FROM user_sequences
             WHERE sequence_name = :1
-        "#;
+            "#
+        };
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
@@ -2503,7 +2966,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let row = match stmt.query_row(&[&seq_name.to_uppercase()]) {
+        let binds = Self::dictionary_binds(owner, &[seq_name]);
+        let row = match stmt.query_row(&Self::bind_refs(&binds)) {
             Ok(row) => row,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -2532,13 +2996,24 @@ impl ObjectBrowser {
         })
     }
 
-    pub fn get_synonyms(conn: &Connection) -> Result<Vec<String>, OracleError> {
-        let sql = "SELECT synonym_name FROM user_synonyms ORDER BY synonym_name";
-        Self::get_object_list(conn, sql)
-    }
-
-    pub fn get_synonym_info(conn: &Connection, syn_name: &str) -> Result<SynonymInfo, OracleError> {
-        let sql = r#"
+    pub fn get_synonym_info_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        syn_name: &str,
+    ) -> Result<SynonymInfo, OracleError> {
+        let sql = if owner.is_some() {
+            r#"
+            SELECT
+                synonym_name,
+                table_owner,
+                table_name,
+                db_link
+            FROM all_synonyms
+            WHERE owner = :1
+              AND synonym_name = :2
+            "#
+        } else {
+            r#"
             SELECT
                 synonym_name,
                 table_owner,
@@ -2546,7 +3021,8 @@ impl ObjectBrowser {
                 db_link
             FROM user_synonyms
             WHERE synonym_name = :1
-        "#;
+            "#
+        };
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
@@ -2554,7 +3030,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let row = match stmt.query_row(&[&syn_name.to_uppercase()]) {
+        let binds = Self::dictionary_binds(owner, &[syn_name]);
+        let row = match stmt.query_row(&Self::bind_refs(&binds)) {
             Ok(row) => row,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -2575,20 +3052,23 @@ impl ObjectBrowser {
         })
     }
 
-    pub fn get_packages(conn: &Connection) -> Result<Vec<String>, OracleError> {
-        let sql = "SELECT object_name FROM user_objects WHERE object_type = 'PACKAGE' ORDER BY object_name";
-        Self::get_object_list(conn, sql)
+    pub fn get_package_routines(
+        conn: &Connection,
+        package_name: &str,
+    ) -> Result<Vec<PackageRoutine>, OracleError> {
+        Self::get_package_routines_for_owner(conn, None, package_name)
     }
 
-    pub fn get_package_routines(
+    pub fn get_package_routines_for_owner(
         conn: &Connection,
+        owner: Option<&str>,
         package_name: &str,
     ) -> Result<Vec<PackageRoutine>, OracleError> {
         // Fast path: parse package spec source from USER_SOURCE to identify
         // PROCEDURE vs FUNCTION declarations. This avoids the slow
         // user_arguments view entirely, which is the main bottleneck.
         let pkg_upper = package_name.to_uppercase();
-        if let Ok(routines) = Self::get_package_routines_from_source(conn, &pkg_upper) {
+        if let Ok(routines) = Self::get_package_routines_from_source(conn, owner, &pkg_upper) {
             if !routines.is_empty() {
                 return Ok(routines);
             }
@@ -2596,7 +3076,7 @@ impl ObjectBrowser {
 
         // Fallback: query user_procedures + user_arguments if source parsing
         // returned no results (e.g. wrapped/encrypted packages)
-        Self::get_package_routines_from_dict(conn, &pkg_upper)
+        Self::get_package_routines_from_dict(conn, owner, &pkg_upper)
     }
 
     /// Parse package spec source text to extract PROCEDURE/FUNCTION declarations.
@@ -2604,11 +3084,17 @@ impl ObjectBrowser {
     /// table scan with no complex joins.
     fn get_package_routines_from_source(
         conn: &Connection,
+        owner: Option<&str>,
         package_name: &str,
     ) -> Result<Vec<PackageRoutine>, OracleError> {
-        let sql = "SELECT text FROM user_source WHERE name = :1 AND type = 'PACKAGE' ORDER BY line";
+        let sql = if owner.is_some() {
+            "SELECT text FROM all_source WHERE owner = :1 AND name = :2 AND type = 'PACKAGE' ORDER BY line"
+        } else {
+            "SELECT text FROM user_source WHERE name = :1 AND type = 'PACKAGE' ORDER BY line"
+        };
         let mut stmt = conn.statement(sql).build()?;
-        let rows = stmt.query(&[&package_name])?;
+        let binds = Self::dictionary_binds(owner, &[package_name]);
+        let rows = stmt.query(&Self::bind_refs(&binds))?;
 
         let mut source = String::new();
         for row_result in rows {
@@ -2740,9 +3226,33 @@ impl ObjectBrowser {
     /// Used when source parsing fails (e.g. wrapped/encrypted packages).
     fn get_package_routines_from_dict(
         conn: &Connection,
+        owner: Option<&str>,
         package_name: &str,
     ) -> Result<Vec<PackageRoutine>, OracleError> {
-        let sql = r#"
+        let sql = if owner.is_some() {
+            r#"
+            SELECT DISTINCT
+                p.procedure_name,
+                CASE
+                    WHEN EXISTS (
+                        SELECT 1 FROM all_arguments a
+                        WHERE a.owner = p.owner
+                        AND a.package_name = p.object_name
+                        AND a.object_name = p.procedure_name
+                        AND a.position = 0
+                        AND (a.overload = p.overload OR (a.overload IS NULL AND p.overload IS NULL))
+                    ) THEN 'FUNCTION'
+                    ELSE 'PROCEDURE'
+                END AS routine_type
+            FROM all_procedures p
+            WHERE p.owner = :1
+              AND p.object_type = 'PACKAGE'
+              AND p.object_name = :2
+              AND p.procedure_name IS NOT NULL
+            ORDER BY p.procedure_name
+            "#
+        } else {
+            r#"
             SELECT DISTINCT
                 p.procedure_name,
                 CASE
@@ -2760,7 +3270,8 @@ impl ObjectBrowser {
               AND p.object_name = :1
               AND p.procedure_name IS NOT NULL
             ORDER BY p.procedure_name
-        "#;
+            "#
+        };
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
@@ -2768,7 +3279,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let rows = match stmt.query(&[&package_name]) {
+        let binds = Self::dictionary_binds(owner, &[package_name]);
+        let rows = match stmt.query(&Self::bind_refs(&binds)) {
             Ok(rows) => rows,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -2899,7 +3411,7 @@ impl ObjectBrowser {
         conn: &Connection,
         procedure_name: &str,
     ) -> Result<Vec<ProcedureArgument>, OracleError> {
-        Self::get_procedure_arguments_inner(conn, None, procedure_name)
+        Self::get_procedure_arguments_inner(conn, None, None, procedure_name)
     }
 
     pub fn get_package_procedure_arguments(
@@ -2907,15 +3419,79 @@ impl ObjectBrowser {
         package_name: &str,
         procedure_name: &str,
     ) -> Result<Vec<ProcedureArgument>, OracleError> {
-        Self::get_procedure_arguments_inner(conn, Some(package_name), procedure_name)
+        Self::get_procedure_arguments_inner(conn, None, Some(package_name), procedure_name)
+    }
+
+    pub fn get_procedure_arguments_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        procedure_name: &str,
+    ) -> Result<Vec<ProcedureArgument>, OracleError> {
+        Self::get_procedure_arguments_inner(conn, owner, None, procedure_name)
+    }
+
+    pub fn get_package_procedure_arguments_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        package_name: &str,
+        procedure_name: &str,
+    ) -> Result<Vec<ProcedureArgument>, OracleError> {
+        Self::get_procedure_arguments_inner(conn, owner, Some(package_name), procedure_name)
     }
 
     fn get_procedure_arguments_inner(
         conn: &Connection,
+        owner: Option<&str>,
         package_name: Option<&str>,
         procedure_name: &str,
     ) -> Result<Vec<ProcedureArgument>, OracleError> {
-        let sql = if package_name.is_some() {
+        let sql = if owner.is_some() {
+            if package_name.is_some() {
+                r#"
+            SELECT
+                argument_name,
+                position,
+                sequence,
+                data_type,
+                in_out,
+                data_length,
+                data_precision,
+                data_scale,
+                type_owner,
+                type_name,
+                pls_type,
+                overload,
+                default_value
+            FROM all_arguments
+            WHERE owner = :1
+              AND package_name = :2
+              AND object_name = :3
+            ORDER BY NVL(overload, 0), position, sequence
+            "#
+            } else {
+                r#"
+            SELECT
+                argument_name,
+                position,
+                sequence,
+                data_type,
+                in_out,
+                data_length,
+                data_precision,
+                data_scale,
+                type_owner,
+                type_name,
+                pls_type,
+                overload,
+                default_value
+            FROM all_arguments
+            WHERE owner = :1
+              AND package_name IS NULL
+              AND object_name = :2
+            ORDER BY NVL(overload, 0), position, sequence
+            "#
+            }
+        } else if package_name.is_some() {
             r#"
             SELECT
                 argument_name,
@@ -2967,21 +3543,15 @@ impl ObjectBrowser {
             }
         };
 
-        let rows = if let Some(pkg_name) = package_name {
-            match stmt.query(&[&pkg_name.to_uppercase(), &procedure_name.to_uppercase()]) {
-                Ok(rows) => rows,
-                Err(err) => {
-                    eprintln!("Database operation failed: {err}");
-                    return Err(err);
-                }
-            }
-        } else {
-            match stmt.query(&[&procedure_name.to_uppercase()]) {
-                Ok(rows) => rows,
-                Err(err) => {
-                    eprintln!("Database operation failed: {err}");
-                    return Err(err);
-                }
+        let binds = match package_name {
+            Some(pkg_name) => Self::dictionary_binds(owner, &[pkg_name, procedure_name]),
+            None => Self::dictionary_binds(owner, &[procedure_name]),
+        };
+        let rows = match stmt.query(&Self::bind_refs(&binds)) {
+            Ok(rows) => rows,
+            Err(err) => {
+                eprintln!("Database operation failed: {err}");
+                return Err(err);
             }
         };
 
@@ -3107,12 +3677,16 @@ impl ObjectBrowser {
         Ok(arguments)
     }
 
-    #[allow(dead_code)]
-    pub fn get_table_columns(
+    pub fn get_table_columns_for_owner(
         conn: &Connection,
+        owner: Option<&str>,
         table_name: &str,
     ) -> Result<Vec<ColumnInfo>, OracleError> {
-        let sql = "SELECT column_name, data_type FROM user_tab_columns WHERE table_name = :1 ORDER BY column_id";
+        let sql = if owner.is_some() {
+            "SELECT column_name, data_type FROM all_tab_columns WHERE owner = :1 AND table_name = :2 ORDER BY column_id"
+        } else {
+            "SELECT column_name, data_type FROM user_tab_columns WHERE table_name = :1 ORDER BY column_id"
+        };
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
@@ -3120,7 +3694,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let rows = match stmt.query(&[&table_name.to_uppercase()]) {
+        let binds = Self::dictionary_binds(owner, &[table_name]);
+        let rows = match stmt.query(&Self::bind_refs(&binds)) {
             Ok(rows) => rows,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -3151,13 +3726,26 @@ impl ObjectBrowser {
                     return Err(err);
                 }
             };
-            columns.push(ColumnInfo { name, data_type });
+            let kind = CellKind::from_type_name(&data_type);
+            columns.push(ColumnInfo {
+                name,
+                data_type,
+                kind,
+            });
         }
 
         Ok(columns)
     }
 
-    fn get_object_list(conn: &Connection, sql: &str) -> Result<Vec<String>, OracleError> {
+    fn bind_refs(binds: &[String]) -> Vec<&dyn ToSql> {
+        binds.iter().map(|value| value as &dyn ToSql).collect()
+    }
+
+    fn get_object_list(
+        conn: &Connection,
+        sql: &str,
+        binds: &[&str],
+    ) -> Result<Vec<String>, OracleError> {
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
@@ -3165,7 +3753,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let rows = match stmt.query(&[]) {
+        let binds = Self::dictionary_binds(None, binds);
+        let rows = match stmt.query(&Self::bind_refs(&binds)) {
             Ok(rows) => rows,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -3199,7 +3788,19 @@ impl ObjectBrowser {
         conn: &Connection,
         object_name: &str,
     ) -> Result<Vec<String>, OracleError> {
-        let sql = "SELECT DISTINCT object_type FROM user_objects WHERE object_name = :1";
+        Self::get_object_types_for_owner(conn, None, object_name)
+    }
+
+    pub fn get_object_types_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        object_name: &str,
+    ) -> Result<Vec<String>, OracleError> {
+        let sql = if owner.is_some() {
+            "SELECT DISTINCT object_type FROM all_objects WHERE owner = :1 AND object_name = :2"
+        } else {
+            "SELECT DISTINCT object_type FROM user_objects WHERE object_name = :1"
+        };
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
@@ -3207,7 +3808,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let rows = match stmt.query(&[&object_name.to_uppercase()]) {
+        let binds = Self::dictionary_binds(owner, &[object_name]);
+        let rows = match stmt.query(&Self::bind_refs(&binds)) {
             Ok(rows) => rows,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -3242,7 +3844,40 @@ impl ObjectBrowser {
         conn: &Connection,
         table_name: &str,
     ) -> Result<Vec<TableColumnDetail>, OracleError> {
-        let sql = r#"
+        Self::get_table_structure_for_owner(conn, None, table_name)
+    }
+
+    pub fn get_table_structure_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        table_name: &str,
+    ) -> Result<Vec<TableColumnDetail>, OracleError> {
+        let sql = if owner.is_some() {
+            r#"
+            SELECT
+                c.column_name,
+                c.data_type,
+                c.data_length,
+                c.data_precision,
+                c.data_scale,
+                c.nullable,
+                c.data_default,
+                (SELECT 'PK' FROM all_cons_columns cc
+                 JOIN all_constraints con
+                   ON cc.owner = con.owner
+                  AND cc.constraint_name = con.constraint_name
+                 WHERE con.constraint_type = 'P'
+                 AND cc.owner = c.owner
+                 AND cc.table_name = c.table_name
+                 AND cc.column_name = c.column_name
+                 AND ROWNUM = 1) as is_pk
+            FROM all_tab_columns c
+            WHERE c.owner = :1
+              AND c.table_name = :2
+            ORDER BY c.column_id
+            "#
+        } else {
+            r#"
             SELECT
                 c.column_name,
                 c.data_type,
@@ -3260,7 +3895,8 @@ impl ObjectBrowser {
             FROM user_tab_columns c
             WHERE c.table_name = :1
             ORDER BY c.column_id
-        "#;
+            "#
+        };
 
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
@@ -3269,7 +3905,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let rows = match stmt.query(&[&table_name.to_uppercase()]) {
+        let binds = Self::dictionary_binds(owner, &[table_name]);
+        let rows = match stmt.query(&Self::bind_refs(&binds)) {
             Ok(rows) => rows,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -3358,11 +3995,28 @@ impl ObjectBrowser {
     }
 
     /// Get indexes for a table
-    pub fn get_table_indexes(
+    pub fn get_table_indexes_for_owner(
         conn: &Connection,
+        owner: Option<&str>,
         table_name: &str,
     ) -> Result<Vec<IndexInfo>, OracleError> {
-        let sql = r#"
+        let sql = if owner.is_some() {
+            r#"
+            SELECT
+                i.index_name,
+                i.uniqueness,
+                LISTAGG(ic.column_name, ', ') WITHIN GROUP (ORDER BY ic.column_position) as columns
+            FROM all_indexes i
+            JOIN all_ind_columns ic
+              ON i.owner = ic.index_owner
+             AND i.index_name = ic.index_name
+            WHERE i.table_owner = :1
+              AND i.table_name = :2
+            GROUP BY i.index_name, i.uniqueness
+            ORDER BY i.index_name
+            "#
+        } else {
+            r#"
             SELECT
                 i.index_name,
                 i.uniqueness,
@@ -3372,7 +4026,8 @@ impl ObjectBrowser {
             WHERE i.table_name = :1
             GROUP BY i.index_name, i.uniqueness
             ORDER BY i.index_name
-        "#;
+            "#
+        };
 
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
@@ -3381,7 +4036,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let rows = match stmt.query(&[&table_name.to_uppercase()]) {
+        let binds = Self::dictionary_binds(owner, &[table_name]);
+        let rows = match stmt.query(&Self::bind_refs(&binds)) {
             Ok(rows) => rows,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -3430,11 +4086,32 @@ impl ObjectBrowser {
     }
 
     /// Get constraints for a table
-    pub fn get_table_constraints(
+    pub fn get_table_constraints_for_owner(
         conn: &Connection,
+        owner: Option<&str>,
         table_name: &str,
     ) -> Result<Vec<ConstraintInfo>, OracleError> {
-        let sql = r#"
+        let sql = if owner.is_some() {
+            r#"
+            SELECT
+                c.constraint_name,
+                c.constraint_type,
+                LISTAGG(cc.column_name, ', ') WITHIN GROUP (ORDER BY cc.position) as columns,
+                c.r_constraint_name,
+                (SELECT r.table_name FROM all_constraints r
+                 WHERE r.owner = c.r_owner
+                   AND r.constraint_name = c.r_constraint_name) as ref_table
+            FROM all_constraints c
+            LEFT JOIN all_cons_columns cc
+              ON c.owner = cc.owner
+             AND c.constraint_name = cc.constraint_name
+            WHERE c.owner = :1
+              AND c.table_name = :2
+            GROUP BY c.constraint_name, c.constraint_type, c.r_owner, c.r_constraint_name
+            ORDER BY c.constraint_type, c.constraint_name
+            "#
+        } else {
+            r#"
             SELECT
                 c.constraint_name,
                 c.constraint_type,
@@ -3446,7 +4123,8 @@ impl ObjectBrowser {
             WHERE c.table_name = :1
             GROUP BY c.constraint_name, c.constraint_type, c.r_constraint_name
             ORDER BY c.constraint_type, c.constraint_name
-        "#;
+            "#
+        };
 
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
@@ -3455,7 +4133,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let rows = match stmt.query(&[&table_name.to_uppercase()]) {
+        let binds = Self::dictionary_binds(owner, &[table_name]);
+        let rows = match stmt.query(&Self::bind_refs(&binds)) {
             Ok(rows) => rows,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -3518,165 +4197,57 @@ impl ObjectBrowser {
     }
 
     /// Generate DDL for a table
-    pub fn get_table_ddl(conn: &Connection, table_name: &str) -> Result<String, OracleError> {
-        let sql = "SELECT DBMS_METADATA.GET_DDL('TABLE', :1) FROM DUAL";
-        let mut stmt = match conn.statement(sql).build() {
-            Ok(stmt) => stmt,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let row = match stmt.query_row(&[&table_name.to_uppercase()]) {
-            Ok(row) => row,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let ddl: String = match row.get(0) {
-            Ok(ddl) => ddl,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        Ok(Self::normalize_generated_ddl(ddl))
+    pub fn get_table_ddl_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        table_name: &str,
+    ) -> Result<String, OracleError> {
+        Self::get_object_ddl_for_owner(conn, owner, "TABLE", table_name)
     }
 
     /// Generate DDL for a view
-    pub fn get_view_ddl(conn: &Connection, view_name: &str) -> Result<String, OracleError> {
-        let sql = "SELECT DBMS_METADATA.GET_DDL('VIEW', :1) FROM DUAL";
-        let mut stmt = match conn.statement(sql).build() {
-            Ok(stmt) => stmt,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let row = match stmt.query_row(&[&view_name.to_uppercase()]) {
-            Ok(row) => row,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let ddl: String = match row.get(0) {
-            Ok(ddl) => ddl,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        Ok(Self::normalize_generated_ddl(ddl))
+    pub fn get_view_ddl_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        view_name: &str,
+    ) -> Result<String, OracleError> {
+        Self::get_object_ddl_for_owner(conn, owner, "VIEW", view_name)
     }
 
     /// Generate DDL for a procedure
-    pub fn get_procedure_ddl(conn: &Connection, proc_name: &str) -> Result<String, OracleError> {
-        let sql = "SELECT DBMS_METADATA.GET_DDL('PROCEDURE', :1) FROM DUAL";
-        let mut stmt = match conn.statement(sql).build() {
-            Ok(stmt) => stmt,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let row = match stmt.query_row(&[&proc_name.to_uppercase()]) {
-            Ok(row) => row,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let ddl: String = match row.get(0) {
-            Ok(ddl) => ddl,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        Ok(Self::normalize_generated_ddl(ddl))
+    pub fn get_procedure_ddl_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        proc_name: &str,
+    ) -> Result<String, OracleError> {
+        Self::get_object_ddl_for_owner(conn, owner, "PROCEDURE", proc_name)
     }
 
     /// Generate DDL for a function
-    pub fn get_function_ddl(conn: &Connection, func_name: &str) -> Result<String, OracleError> {
-        let sql = "SELECT DBMS_METADATA.GET_DDL('FUNCTION', :1) FROM DUAL";
-        let mut stmt = match conn.statement(sql).build() {
-            Ok(stmt) => stmt,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let row = match stmt.query_row(&[&func_name.to_uppercase()]) {
-            Ok(row) => row,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let ddl: String = match row.get(0) {
-            Ok(ddl) => ddl,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        Ok(Self::normalize_generated_ddl(ddl))
+    pub fn get_function_ddl_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        func_name: &str,
+    ) -> Result<String, OracleError> {
+        Self::get_object_ddl_for_owner(conn, owner, "FUNCTION", func_name)
     }
 
     /// Generate DDL for a sequence
-    pub fn get_sequence_ddl(conn: &Connection, seq_name: &str) -> Result<String, OracleError> {
-        let sql = "SELECT DBMS_METADATA.GET_DDL('SEQUENCE', :1) FROM DUAL";
-        let mut stmt = match conn.statement(sql).build() {
-            Ok(stmt) => stmt,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let row = match stmt.query_row(&[&seq_name.to_uppercase()]) {
-            Ok(row) => row,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let ddl: String = match row.get(0) {
-            Ok(ddl) => ddl,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        Ok(Self::normalize_generated_ddl(ddl))
+    pub fn get_sequence_ddl_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        seq_name: &str,
+    ) -> Result<String, OracleError> {
+        Self::get_object_ddl_for_owner(conn, owner, "SEQUENCE", seq_name)
     }
 
     /// Generate DDL for a synonym
-    pub fn get_synonym_ddl(conn: &Connection, syn_name: &str) -> Result<String, OracleError> {
-        let sql = "SELECT DBMS_METADATA.GET_DDL('SYNONYM', :1) FROM DUAL";
-        let mut stmt = match conn.statement(sql).build() {
-            Ok(stmt) => stmt,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let row = match stmt.query_row(&[&syn_name.to_uppercase()]) {
-            Ok(row) => row,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let ddl: String = match row.get(0) {
-            Ok(ddl) => ddl,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        Ok(Self::normalize_generated_ddl(ddl))
+    pub fn get_synonym_ddl_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        syn_name: &str,
+    ) -> Result<String, OracleError> {
+        Self::get_object_ddl_for_owner(conn, owner, "SYNONYM", syn_name)
     }
 
     /// Generate DDL for a package specification
@@ -3684,29 +4255,15 @@ impl ObjectBrowser {
         conn: &Connection,
         package_name: &str,
     ) -> Result<String, OracleError> {
-        let sql = "SELECT DBMS_METADATA.GET_DDL('PACKAGE', :1) FROM DUAL";
-        let mut stmt = match conn.statement(sql).build() {
-            Ok(stmt) => stmt,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let row = match stmt.query_row(&[&package_name.to_uppercase()]) {
-            Ok(row) => row,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        let ddl: String = match row.get(0) {
-            Ok(ddl) => ddl,
-            Err(err) => {
-                eprintln!("Database operation failed: {err}");
-                return Err(err);
-            }
-        };
-        Ok(Self::normalize_generated_ddl(ddl))
+        Self::get_package_spec_ddl_for_owner(conn, None, package_name)
+    }
+
+    pub fn get_package_spec_ddl_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        package_name: &str,
+    ) -> Result<String, OracleError> {
+        Self::get_object_ddl_for_owner(conn, owner, "PACKAGE", package_name)
     }
 
     /// Generate DDL for any supported object type.
@@ -3715,7 +4272,18 @@ impl ObjectBrowser {
         object_type: &str,
         object_name: &str,
     ) -> Result<String, OracleError> {
-        let sql = "SELECT DBMS_METADATA.GET_DDL(:1, :2) FROM DUAL";
+        Self::get_object_ddl_for_owner(conn, None, object_type, object_name)
+    }
+
+    /// Generate DDL for any supported object type in the given schema.
+    /// DBMS_METADATA falls back to the current schema when `owner` is `None`.
+    pub fn get_object_ddl_for_owner(
+        conn: &Connection,
+        owner: Option<&str>,
+        object_type: &str,
+        object_name: &str,
+    ) -> Result<String, OracleError> {
+        let sql = "SELECT DBMS_METADATA.GET_DDL(:1, :2, :3) FROM DUAL";
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
@@ -3723,8 +4291,12 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let row = match stmt.query_row(&[&object_type.to_uppercase(), &object_name.to_uppercase()])
-        {
+        let owner_upper = owner.map(|owner| owner.to_uppercase());
+        let row = match stmt.query_row(&[
+            &object_type.to_uppercase(),
+            &object_name.to_uppercase(),
+            &owner_upper,
+        ]) {
             Ok(row) => row,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -3742,15 +4314,23 @@ impl ObjectBrowser {
     }
 
     /// Get compilation errors for a compilable object (procedure, function, package, etc.)
-    pub fn get_compilation_errors(
+    pub fn get_compilation_errors_for_owner(
         conn: &Connection,
+        owner: Option<&str>,
         object_name: &str,
         object_type: &str,
     ) -> Result<Vec<CompilationError>, OracleError> {
-        let sql = "SELECT line, position, text, attribute \
-                   FROM user_errors \
-                   WHERE name = :1 AND type = :2 \
-                   ORDER BY sequence";
+        let sql = if owner.is_some() {
+            "SELECT line, position, text, attribute \
+             FROM all_errors \
+             WHERE owner = :1 AND name = :2 AND type = :3 \
+             ORDER BY sequence"
+        } else {
+            "SELECT line, position, text, attribute \
+             FROM user_errors \
+             WHERE name = :1 AND type = :2 \
+             ORDER BY sequence"
+        };
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
@@ -3758,7 +4338,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let rows = match stmt.query(&[&object_name.to_uppercase(), &object_type.to_uppercase()]) {
+        let binds = Self::dictionary_binds(owner, &[object_name, object_type]);
+        let rows = match stmt.query(&Self::bind_refs(&binds)) {
             Ok(rows) => rows,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
@@ -3798,12 +4379,17 @@ impl ObjectBrowser {
     }
 
     /// Get the compilation status of an object from user_objects
-    pub fn get_object_status(
+    pub fn get_object_status_for_owner(
         conn: &Connection,
+        owner: Option<&str>,
         object_name: &str,
         object_type: &str,
     ) -> Result<String, OracleError> {
-        let sql = "SELECT status FROM user_objects WHERE object_name = :1 AND object_type = :2";
+        let sql = if owner.is_some() {
+            "SELECT status FROM all_objects WHERE owner = :1 AND object_name = :2 AND object_type = :3"
+        } else {
+            "SELECT status FROM user_objects WHERE object_name = :1 AND object_type = :2"
+        };
         let mut stmt = match conn.statement(sql).build() {
             Ok(stmt) => stmt,
             Err(err) => {
@@ -3811,8 +4397,8 @@ impl ObjectBrowser {
                 return Err(err);
             }
         };
-        let row = match stmt.query_row(&[&object_name.to_uppercase(), &object_type.to_uppercase()])
-        {
+        let binds = Self::dictionary_binds(owner, &[object_name, object_type]);
+        let row = match stmt.query_row(&Self::bind_refs(&binds)) {
             Ok(row) => row,
             Err(err) => {
                 eprintln!("Database operation failed: {err}");
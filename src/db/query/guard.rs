@@ -0,0 +1,118 @@
+use super::edit::tokenize;
+use super::QueryExecutor;
+use crate::db::transaction::DDL_KEYWORDS;
+use crate::db::ProductionGuards;
+
+/// What the production guards of a connection say about a statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatementGuard {
+    Allowed,
+    /// The connection is read-only; carries the reason.
+    Refused(String),
+    /// Run only after the user agrees; carries the question.
+    Confirm(String),
+}
+
+const DML_KEYWORDS: [&str; 5] = ["INSERT", "UPDATE", "DELETE", "MERGE", "LOCK"];
+
+const PLSQL_KEYWORDS: [&str; 5] = ["BEGIN", "DECLARE", "CALL", "EXEC", "EXECUTE"];
+
+/// Statement text shown in confirmations, cut to a readable length.
+fn statement_preview(sql: &str) -> String {
+    const MAX_CHARS: usize = 300;
+    let flattened = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flattened.chars().count() > MAX_CHARS {
+        let cut: String = flattened.chars().take(MAX_CHARS).collect();
+        format!("{}...", cut)
+    } else {
+        flattened
+    }
+}
+
+/// `DROP TABLE`, `TRUNCATE CLUSTER`, or just the keyword when the object
+/// type is not recognized.
+fn ddl_label(keyword: &str, sql: &str) -> String {
+    match QueryExecutor::parse_ddl_object_type(sql) {
+        "Object" => keyword.to_string(),
+        object_type => format!("{} {}", keyword, object_type.to_uppercase()),
+    }
+}
+
+/// Whether `sql` has a WHERE clause outside subqueries. Unparseable text
+/// counts as having none.
+fn has_top_level_where(sql: &str) -> bool {
+    tokenize(sql).is_some_and(|tokens| {
+        tokens
+            .iter()
+            .any(|token| token.depth == 0 && token.is_word("WHERE"))
+    })
+}
+
+/// Whether `sql` locks rows with FOR UPDATE, ignoring literals and
+/// comments. Unparseable text counts as locking.
+pub(crate) fn has_for_update(sql: &str) -> bool {
+    tokenize(sql).is_none_or(|tokens| {
+        tokens
+            .windows(2)
+            .any(|pair| pair[0].is_word("FOR") && pair[1].is_word("UPDATE"))
+    })
+}
+
+impl QueryExecutor {
+    /// Check `sql` against the guards of a production connection.
+    pub fn check_statement_guard(sql: &str, guards: &ProductionGuards) -> StatementGuard {
+        let Some(keyword) = Self::leading_keyword(sql) else {
+            return StatementGuard::Allowed;
+        };
+        let keyword = keyword.trim_end_matches(';').to_string();
+        let upper = Self::strip_leading_comments(sql).to_uppercase();
+        let is_ddl = DDL_KEYWORDS.contains(&keyword.as_str())
+            && !(keyword == "ALTER"
+                && upper
+                    .split_whitespace()
+                    .nth(1)
+                    .is_some_and(|word| word == "SESSION"));
+
+        if guards.read_only {
+            let refused = if DML_KEYWORDS.contains(&keyword.as_str()) {
+                Some(format!("{} is not allowed", keyword))
+            } else if matches!(keyword.as_str(), "SELECT" | "WITH") && has_for_update(sql) {
+                Some("SELECT ... FOR UPDATE is not allowed".to_string())
+            } else if is_ddl {
+                Some(format!("{} is not allowed", ddl_label(&keyword, sql)))
+            } else if PLSQL_KEYWORDS.contains(&keyword.as_str()) {
+                Some("PL/SQL blocks and procedure calls are not allowed".to_string())
+            } else {
+                None
+            };
+            if let Some(reason) = refused {
+                return StatementGuard::Refused(format!(
+                    "{}: the production connection is read-only",
+                    reason
+                ));
+            }
+        }
+
+        if guards.confirm_unfiltered_dml
+            && matches!(keyword.as_str(), "UPDATE" | "DELETE")
+            && !has_top_level_where(sql)
+        {
+            return StatementGuard::Confirm(format!(
+                "This {} has no WHERE clause and changes every row of the table:\n\n{}\n\n\
+                 Run it on the production connection?",
+                keyword,
+                statement_preview(sql)
+            ));
+        }
+
+        if guards.confirm_drop_truncate && matches!(keyword.as_str(), "DROP" | "TRUNCATE") {
+            return StatementGuard::Confirm(format!(
+                "{} on the production connection:\n\n{}\n\nRun it?",
+                ddl_label(&keyword, sql),
+                statement_preview(sql)
+            ));
+        }
+
+        StatementGuard::Allowed
+    }
+}
@@ -1,8 +1,14 @@
+mod edit;
 mod executor;
+mod guard;
+mod plan;
 mod script;
 mod types;
 
+pub use edit::*;
 pub use executor::*;
+pub use guard::*;
+pub use plan::*;
 pub use types::*;
 
 #[cfg(test)]
@@ -0,0 +1,226 @@
+use std::collections::HashSet;
+
+use super::CellValue;
+
+/// Share of the plan's total weight a step must account for by itself to be
+/// highlighted as expensive.
+const HOT_STEP_SHARE: f64 = 0.2;
+/// At most this many steps are highlighted.
+const MAX_HOT_STEPS: usize = 3;
+
+/// Runtime statistics of a plan step from its last execution, as shown by
+/// `DBMS_XPLAN.DISPLAY_CURSOR(format => 'ALLSTATS LAST')`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlanRuntimeStats {
+    pub starts: Option<u64>,
+    pub actual_rows: Option<u64>,
+    /// Elapsed time in microseconds, including the step's children.
+    pub elapsed_us: Option<u64>,
+    pub buffer_gets: Option<u64>,
+    pub disk_reads: Option<u64>,
+}
+
+/// One row operation of an execution plan.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlanStep {
+    pub id: u32,
+    pub parent_id: Option<u32>,
+    pub depth: u32,
+    pub operation: String,
+    pub options: Option<String>,
+    pub object_owner: Option<String>,
+    pub object_name: Option<String>,
+    pub cost: Option<u64>,
+    pub cardinality: Option<u64>,
+    pub bytes: Option<u64>,
+    pub access_predicates: Option<String>,
+    pub filter_predicates: Option<String>,
+    /// Present for plans read from the cursor cache with statistics.
+    pub runtime: Option<PlanRuntimeStats>,
+}
+
+impl PlanStep {
+    /// Operation and options, e.g. `TABLE ACCESS BY INDEX ROWID`.
+    pub fn operation_label(&self) -> String {
+        match &self.options {
+            Some(options) if !options.is_empty() => format!("{} {}", self.operation, options),
+            _ => self.operation.clone(),
+        }
+    }
+
+    /// `OWNER.NAME`, or just the name for the current schema.
+    pub fn object_label(&self) -> String {
+        match (&self.object_owner, &self.object_name) {
+            (Some(owner), Some(name)) if !owner.is_empty() => format!("{owner}.{name}"),
+            (_, Some(name)) => name.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Where a plan came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanSource {
+    /// `EXPLAIN PLAN` estimates from `PLAN_TABLE`.
+    Explain,
+    /// A cached cursor's plan, with runtime statistics when they were gathered.
+    Cursor { sql_id: String, child_number: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionPlan {
+    pub source: PlanSource,
+    /// Steps ordered by id; the root has id 0.
+    pub steps: Vec<PlanStep>,
+}
+
+impl ExecutionPlan {
+    pub fn new(source: PlanSource, mut steps: Vec<PlanStep>) -> Self {
+        steps.sort_by_key(|step| step.id);
+        Self { source, steps }
+    }
+
+    pub fn has_runtime_stats(&self) -> bool {
+        self.steps.iter().any(|step| step.runtime.is_some())
+    }
+
+    pub fn children(&self, id: u32) -> impl Iterator<Item = &PlanStep> {
+        self.steps
+            .iter()
+            .filter(move |step| step.parent_id == Some(id))
+    }
+
+    pub fn has_children(&self, id: u32) -> bool {
+        self.children(id).next().is_some()
+    }
+
+    /// Steps not hidden under a collapsed ancestor, in display order.
+    pub fn visible_steps(&self, collapsed: &HashSet<u32>) -> Vec<&PlanStep> {
+        let mut hidden: HashSet<u32> = HashSet::new();
+        self.steps
+            .iter()
+            .filter(|step| {
+                let hide = step
+                    .parent_id
+                    .is_some_and(|parent| hidden.contains(&parent) || collapsed.contains(&parent));
+                if hide {
+                    hidden.insert(step.id);
+                }
+                !hide
+            })
+            .collect()
+    }
+
+    /// What a step costs: elapsed time for actual plans, otherwise the
+    /// optimizer cost. Both include the step's children.
+    fn weight(&self, step: &PlanStep) -> Option<u64> {
+        if self.has_runtime_stats() {
+            step.runtime.as_ref().and_then(|stats| stats.elapsed_us)
+        } else {
+            step.cost
+        }
+    }
+
+    /// A step's weight minus its children's, i.e. the work done by the step
+    /// itself.
+    pub fn self_weight(&self, step: &PlanStep) -> u64 {
+        let total = self.weight(step).unwrap_or(0);
+        let children: u64 = self
+            .children(step.id)
+            .map(|child| self.weight(child).unwrap_or(0))
+            .sum();
+        total.saturating_sub(children)
+    }
+
+    /// Ids of the steps that do the largest share of the work.
+    pub fn expensive_step_ids(&self) -> Vec<u32> {
+        let total: u64 = self.steps.iter().map(|step| self.self_weight(step)).sum();
+        if total == 0 {
+            return Vec::new();
+        }
+        let mut weighted: Vec<(u64, u32)> = self
+            .steps
+            .iter()
+            .map(|step| (self.self_weight(step), step.id))
+            .filter(|(weight, _)| *weight as f64 >= total as f64 * HOT_STEP_SHARE)
+            .collect();
+        weighted.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        weighted
+            .into_iter()
+            .take(MAX_HOT_STEPS)
+            .map(|(_, id)| id)
+            .collect()
+    }
+
+    pub fn export_headers(&self) -> Vec<String> {
+        let mut headers: Vec<String> = [
+            "ID",
+            "PARENT_ID",
+            "OPERATION",
+            "OBJECT",
+            "COST",
+            "CARDINALITY",
+            "BYTES",
+            "ACCESS_PREDICATES",
+            "FILTER_PREDICATES",
+        ]
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+        if self.has_runtime_stats() {
+            for name in [
+                "STARTS",
+                "ACTUAL_ROWS",
+                "ELAPSED_US",
+                "BUFFER_GETS",
+                "DISK_READS",
+            ] {
+                headers.push(name.to_string());
+            }
+        }
+        headers
+    }
+
+    /// One row per step matching `export_headers`. Operations are indented
+    /// by depth so the tree shape survives flat formats.
+    pub fn export_rows(&self) -> Vec<Vec<CellValue>> {
+        let number = |value: Option<u64>| match value {
+            Some(value) => CellValue::Number(value.to_string()),
+            None => CellValue::Null,
+        };
+        let text = |value: &Option<String>| match value {
+            Some(value) if !value.is_empty() => CellValue::Text(value.clone()),
+            _ => CellValue::Null,
+        };
+        let with_stats = self.has_runtime_stats();
+        self.steps
+            .iter()
+            .map(|step| {
+                let mut row = vec![
+                    CellValue::Number(step.id.to_string()),
+                    number(step.parent_id.map(u64::from)),
+                    CellValue::Text(format!(
+                        "{}{}",
+                        " ".repeat(step.depth as usize),
+                        step.operation_label()
+                    )),
+                    text(&Some(step.object_label())),
+                    number(step.cost),
+                    number(step.cardinality),
+                    number(step.bytes),
+                    text(&step.access_predicates),
+                    text(&step.filter_predicates),
+                ];
+                if with_stats {
+                    let stats = step.runtime.clone().unwrap_or_default();
+                    row.push(number(stats.starts));
+                    row.push(number(stats.actual_rows));
+                    row.push(number(stats.elapsed_us));
+                    row.push(number(stats.buffer_gets));
+                    row.push(number(stats.disk_reads));
+                }
+                row
+            })
+            .collect()
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::HashSet;
 
 /// Helper to extract statements from ScriptItems
 fn get_statements(items: &[ScriptItem]) -> Vec<&str> {
@@ -3615,3 +3616,364 @@ fn test_parse_whenever_sqlerror_exit_sql_sqlcode() {
         items.first()
     );
 }
+
+#[test]
+fn test_cell_value_from_oracle_uses_column_type() {
+    use oracle::sql_type::OracleType;
+
+    assert_eq!(
+        CellValue::from_oracle(&OracleType::Number(10, 2), Some("12.5".to_string())),
+        CellValue::Number("12.5".to_string())
+    );
+    assert_eq!(
+        CellValue::from_oracle(&OracleType::Date, Some("2024-01-02 03:04:05".to_string())),
+        CellValue::Date("2024-01-02 03:04:05".to_string())
+    );
+    assert_eq!(
+        CellValue::from_oracle(&OracleType::CLOB, Some("body".to_string())),
+        CellValue::Lob("body".to_string())
+    );
+    assert_eq!(
+        CellValue::from_oracle(&OracleType::Varchar2(10), None),
+        CellValue::Null
+    );
+}
+
+#[test]
+fn test_cell_value_null_text_only_applies_to_real_nulls() {
+    let real_null = CellValue::Null;
+    let text_null = CellValue::Text("NULL".to_string());
+
+    assert_eq!(real_null.display("(null)"), "(null)");
+    assert_eq!(text_null.display("(null)"), "NULL");
+}
+
+#[test]
+fn test_cell_value_compare_orders_numbers_numerically_with_nulls_last() {
+    let mut cells = vec![
+        CellValue::Null,
+        CellValue::Number("10".to_string()),
+        CellValue::Number("9".to_string()),
+        CellValue::Number("-1.5".to_string()),
+    ];
+    cells.sort_by(|a, b| a.compare(b));
+
+    assert_eq!(
+        cells,
+        vec![
+            CellValue::Number("-1.5".to_string()),
+            CellValue::Number("9".to_string()),
+            CellValue::Number("10".to_string()),
+            CellValue::Null,
+        ]
+    );
+}
+
+fn column(name: &str, kind: CellKind) -> ColumnInfo {
+    ColumnInfo {
+        name: name.to_string(),
+        data_type: String::new(),
+        kind,
+    }
+}
+
+#[test]
+fn test_editable_select_appends_rowid_for_single_table_queries() {
+    let (table, sql) =
+        EditableTable::parse_select("SELECT * FROM scott.emp e WHERE deptno = 10").unwrap();
+    assert_eq!(table.owner.as_deref(), Some("SCOTT"));
+    assert_eq!(table.name, "EMP");
+    assert_eq!(table.sql_name, "scott.emp");
+    assert_eq!(
+        sql,
+        "SELECT e.*, e.ROWID AS SPACE_QUERY_ROWID FROM scott.emp e WHERE deptno = 10"
+    );
+
+    let (_, sql) =
+        EditableTable::parse_select("SELECT ename -- name\nFROM \"Emp\" ORDER BY 1").unwrap();
+    assert_eq!(
+        sql,
+        "SELECT ename, \"Emp\".ROWID AS SPACE_QUERY_ROWID -- name\nFROM \"Emp\" ORDER BY 1"
+    );
+
+    let (_, sql) = EditableTable::parse_select(
+        "SELECT ename, ROW_NUMBER() OVER (ORDER BY sal) rn FROM emp FOR UPDATE",
+    )
+    .unwrap();
+    assert!(sql.contains("rn, emp.ROWID AS SPACE_QUERY_ROWID FROM emp FOR UPDATE"));
+}
+
+#[test]
+fn test_editable_select_rejects_queries_without_a_single_row_source() {
+    for sql in [
+        "SELECT a.x FROM a JOIN b ON a.id = b.id",
+        "SELECT x FROM a, b",
+        "SELECT x FROM (SELECT x FROM a)",
+        "SELECT DISTINCT x FROM a",
+        "SELECT x, COUNT(*) FROM a GROUP BY x",
+        "SELECT COUNT(*) FROM a",
+        "SELECT x FROM a UNION SELECT x FROM b",
+        "WITH q AS (SELECT 1 x FROM dual) SELECT x FROM q",
+        "SELECT x FROM remote_table@dblink",
+        "SELECT a.*, 1 FROM a",
+        "UPDATE a SET x = 1",
+    ] {
+        assert!(
+            EditableTable::parse_select(sql).is_none(),
+            "expected read-only: {sql}"
+        );
+    }
+}
+
+#[test]
+fn test_edit_target_maps_select_items_to_table_columns() {
+    let (table, _) =
+        EditableTable::parse_select("SELECT e.ename AS name, sal * 2, hiredate FROM emp e")
+            .unwrap();
+    let target = table
+        .edit_target(&[
+            column("NAME", CellKind::Text),
+            column("SAL*2", CellKind::Number),
+            column("HIREDATE", CellKind::Date),
+        ])
+        .unwrap();
+
+    assert_eq!(target.columns[0].as_ref().unwrap().name, "ENAME");
+    assert!(!target.is_editable_column(1));
+    assert!(target.is_editable_column(2));
+}
+
+#[test]
+fn test_edit_target_builds_parameterized_dml() {
+    let target = EditTarget {
+        table: "emp".to_string(),
+        columns: vec![
+            Some(EditColumn {
+                name: "ENAME".to_string(),
+                kind: CellKind::Text,
+            }),
+            Some(EditColumn {
+                name: "HIREDATE".to_string(),
+                kind: CellKind::Date,
+            }),
+            Some(EditColumn {
+                name: "NOTES".to_string(),
+                kind: CellKind::Lob,
+            }),
+        ],
+    };
+
+    let update = target
+        .build_dml(&RowChange::Update {
+            rowid: "AAAR3sAAEAAAACXAAA".to_string(),
+            values: vec![
+                (0, CellValue::Text("KING".to_string())),
+                (1, CellValue::Null),
+                (2, CellValue::Lob("ignored".to_string())),
+            ],
+        })
+        .unwrap();
+    assert_eq!(
+        update.sql,
+        "UPDATE emp SET \"ENAME\" = :1, \"HIREDATE\" = NULL WHERE ROWID = :2"
+    );
+    assert_eq!(update.binds, vec!["KING", "AAAR3sAAEAAAACXAAA"]);
+
+    let insert = target
+        .build_dml(&RowChange::Insert {
+            values: vec![
+                (0, CellValue::Text("SCOTT".to_string())),
+                (1, CellValue::Text("2024-01-02 00:00:00".to_string())),
+            ],
+        })
+        .unwrap();
+    assert_eq!(
+        insert.sql,
+        "INSERT INTO emp (\"ENAME\", \"HIREDATE\") VALUES (:1, TO_DATE(:2, 'YYYY-MM-DD HH24:MI:SS'))"
+    );
+
+    let delete = target
+        .build_dml(&RowChange::Delete {
+            rowid: "AAAR3sAAEAAAACXAAB".to_string(),
+        })
+        .unwrap();
+    assert_eq!(delete.sql, "DELETE FROM emp WHERE ROWID = :1");
+    assert_eq!(delete.binds, vec!["AAAR3sAAEAAAACXAAB"]);
+}
+
+#[test]
+fn test_date_cells_do_not_depend_on_the_session_date_format() {
+    // As a session with NLS_DATE_FORMAT = 'dd.mm.yyyy' would print them.
+    let target = EditTarget {
+        table: "emp".to_string(),
+        columns: vec![Some(EditColumn {
+            name: "HIREDATE".to_string(),
+            kind: CellKind::Date,
+        })],
+    };
+    let typed = target
+        .build_dml(&RowChange::Insert {
+            values: vec![(0, CellValue::Text("16.10.2026".to_string()))],
+        })
+        .unwrap();
+    assert_eq!(
+        typed.sql,
+        "INSERT INTO emp (\"HIREDATE\") VALUES (TO_DATE(:1))"
+    );
+    assert_eq!(
+        sql_literal(&CellValue::Timestamp("16.10.2026 08:30:00,5".to_string())),
+        "TO_TIMESTAMP('16.10.2026 08:30:00,5')"
+    );
+
+    // Fetched cells keep the client layout, so scripts carry a fixed mask.
+    assert_eq!(
+        sql_literal(&CellValue::Date("2026-10-16 00:00:00".to_string())),
+        "TO_DATE('2026-10-16 00:00:00', 'YYYY-MM-DD HH24:MI:SS')"
+    );
+    assert_eq!(
+        CellValue::Date("2026-10-16 00:00:00".to_string())
+            .compare(&CellValue::Date("2025-12-31 00:00:00".to_string())),
+        std::cmp::Ordering::Greater
+    );
+}
+
+fn plan_step(id: u32, parent_id: Option<u32>, depth: u32, operation: &str, cost: u64) -> PlanStep {
+    PlanStep {
+        id,
+        parent_id,
+        depth,
+        operation: operation.to_string(),
+        cost: Some(cost),
+        ..PlanStep::default()
+    }
+}
+
+fn sample_plan() -> ExecutionPlan {
+    ExecutionPlan::new(
+        PlanSource::Explain,
+        vec![
+            plan_step(3, Some(1), 2, "TABLE ACCESS", 90),
+            plan_step(0, None, 0, "SELECT STATEMENT", 100),
+            plan_step(1, Some(0), 1, "HASH JOIN", 100),
+            plan_step(2, Some(1), 2, "INDEX", 5),
+        ],
+    )
+}
+
+#[test]
+fn test_plan_visible_steps_hide_collapsed_subtrees() {
+    let plan = sample_plan();
+    let all: Vec<u32> = plan
+        .visible_steps(&HashSet::new())
+        .iter()
+        .map(|step| step.id)
+        .collect();
+    assert_eq!(all, vec![0, 1, 2, 3]);
+
+    let collapsed: HashSet<u32> = [1].into_iter().collect();
+    let visible: Vec<u32> = plan
+        .visible_steps(&collapsed)
+        .iter()
+        .map(|step| step.id)
+        .collect();
+    assert_eq!(visible, vec![0, 1]);
+}
+
+#[test]
+fn test_plan_expensive_steps_use_self_cost() {
+    let plan = sample_plan();
+    assert_eq!(plan.self_weight(&plan.steps[1]), 5);
+    assert_eq!(plan.expensive_step_ids(), vec![3]);
+}
+
+#[test]
+fn test_plan_expensive_steps_prefer_actual_time() {
+    let mut plan = sample_plan();
+    for (step, elapsed) in plan.steps.iter_mut().zip([1000, 990, 900, 90]) {
+        step.runtime = Some(PlanRuntimeStats {
+            elapsed_us: Some(elapsed),
+            ..PlanRuntimeStats::default()
+        });
+    }
+    assert_eq!(plan.expensive_step_ids(), vec![2]);
+}
+
+#[test]
+fn test_plan_export_rows_indent_operations() {
+    let plan = sample_plan();
+    let rows = plan.export_rows();
+    assert_eq!(plan.export_headers().len(), rows[0].len());
+    assert_eq!(rows[2][2], CellValue::Text("  INDEX".to_string()));
+    assert_eq!(rows[0][1], CellValue::Null);
+}
+
+#[test]
+fn test_read_only_guard_refuses_changes() {
+    let guards = crate::db::ProductionGuards {
+        read_only: true,
+        ..crate::db::ProductionGuards::default()
+    };
+    let check = |sql: &str| QueryExecutor::check_statement_guard(sql, &guards);
+    assert_eq!(check("SELECT * FROM emp"), StatementGuard::Allowed);
+    assert_eq!(
+        check("ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY-MM-DD'"),
+        StatementGuard::Allowed
+    );
+    assert!(matches!(
+        check("insert into emp values (1)"),
+        StatementGuard::Refused(reason) if reason.starts_with("INSERT is not allowed")
+    ));
+    assert!(matches!(
+        check("-- cleanup\nDROP TABLE emp"),
+        StatementGuard::Refused(reason) if reason.starts_with("DROP TABLE is not allowed")
+    ));
+    assert!(matches!(
+        check("SELECT * FROM emp FOR UPDATE"),
+        StatementGuard::Refused(_)
+    ));
+    assert_eq!(
+        check("SELECT 'FOR UPDATE' AS hint FROM dual -- for update"),
+        StatementGuard::Allowed
+    );
+    assert!(matches!(
+        check("DISASSOCIATE STATISTICS FROM TABLES emp"),
+        StatementGuard::Refused(_)
+    ));
+    assert!(matches!(
+        check("BEGIN pkg.run; END;"),
+        StatementGuard::Refused(_)
+    ));
+}
+
+#[test]
+fn test_production_guard_confirms_unfiltered_dml_and_drops() {
+    let guards = crate::db::ProductionGuards::default();
+    let check = |sql: &str| QueryExecutor::check_statement_guard(sql, &guards);
+    assert!(matches!(
+        check("DELETE FROM emp"),
+        StatementGuard::Confirm(_)
+    ));
+    assert!(matches!(
+        check("UPDATE emp SET sal = (SELECT MAX(sal) FROM emp WHERE deptno = 10)"),
+        StatementGuard::Confirm(_)
+    ));
+    assert_eq!(
+        check("UPDATE emp SET sal = 0 WHERE empno = 7369"),
+        StatementGuard::Allowed
+    );
+    assert!(matches!(
+        check("truncate table emp"),
+        StatementGuard::Confirm(question) if question.starts_with("TRUNCATE TABLE")
+    ));
+    assert_eq!(check("INSERT INTO emp VALUES (1)"), StatementGuard::Allowed);
+
+    let relaxed = crate::db::ProductionGuards {
+        confirm_unfiltered_dml: false,
+        confirm_drop_truncate: false,
+        ..crate::db::ProductionGuards::default()
+    };
+    assert_eq!(
+        QueryExecutor::check_statement_guard("DROP TABLE emp", &relaxed),
+        StatementGuard::Allowed
+    );
+}
@@ -1617,11 +1617,15 @@ impl QueryExecutor {
         }
 
         if upper == "EXIT" || upper.starts_with("EXIT ") {
-            return Some(ToolCommand::Exit);
+            return Some(ToolCommand::Exit {
+                action: Self::exit_command_action(trimmed),
+            });
         }
 
         if upper == "QUIT" || upper.starts_with("QUIT ") {
-            return Some(ToolCommand::Quit);
+            return Some(ToolCommand::Quit {
+                action: Self::exit_command_action(trimmed),
+            });
         }
 
         if (upper == "CONNECT"
@@ -2174,6 +2178,16 @@ impl QueryExecutor {
         }
     }
 
+    /// The arguments of EXIT or QUIT, e.g. `3 ROLLBACK`.
+    fn exit_command_action(raw: &str) -> Option<String> {
+        let rest = raw[4..].trim();
+        if rest.is_empty() {
+            None
+        } else {
+            Some(rest.to_string())
+        }
+    }
+
     fn parse_whenever_oserror_command(raw: &str) -> ToolCommand {
         let rest = raw[16..].trim();
         if rest.is_empty() {
@@ -1,5 +1,8 @@
+use std::cmp::Ordering;
 use std::time::Duration;
 
+use oracle::sql_type::OracleType;
+
 use crate::db::session::{BindDataType, ComputeMode};
 
 #[derive(Debug, Clone)]
@@ -7,6 +10,183 @@ pub struct ColumnInfo {
     pub name: String,
     #[allow(dead_code)]
     pub data_type: String,
+    pub kind: CellKind,
+}
+
+/// The kind of value a result column holds, as far as the grid cares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellKind {
+    Number,
+    Text,
+    Date,
+    Timestamp,
+    Lob,
+    Raw,
+}
+
+impl CellKind {
+    pub fn from_oracle(oracle_type: &OracleType) -> Self {
+        match oracle_type {
+            OracleType::Number(_, _)
+            | OracleType::Float(_)
+            | OracleType::BinaryFloat
+            | OracleType::BinaryDouble
+            | OracleType::Int64
+            | OracleType::UInt64 => CellKind::Number,
+            OracleType::Date => CellKind::Date,
+            OracleType::Timestamp(_) | OracleType::TimestampTZ(_) | OracleType::TimestampLTZ(_) => {
+                CellKind::Timestamp
+            }
+            OracleType::CLOB
+            | OracleType::NCLOB
+            | OracleType::BLOB
+            | OracleType::BFILE
+            | OracleType::Long
+            | OracleType::LongRaw => CellKind::Lob,
+            OracleType::Raw(_) => CellKind::Raw,
+            _ => CellKind::Text,
+        }
+    }
+
+    /// Kind for a data dictionary type name such as `NUMBER` or `TIMESTAMP(6)`.
+    pub fn from_type_name(type_name: &str) -> Self {
+        let upper = type_name.trim().to_uppercase();
+        let base = upper.split('(').next().unwrap_or_default().trim();
+        match base {
+            "NUMBER" | "FLOAT" | "BINARY_FLOAT" | "BINARY_DOUBLE" | "INTEGER" => CellKind::Number,
+            "DATE" => CellKind::Date,
+            "CLOB" | "NCLOB" | "BLOB" | "BFILE" | "LONG" | "LONG RAW" => CellKind::Lob,
+            "RAW" => CellKind::Raw,
+            _ if base.starts_with("TIMESTAMP") => CellKind::Timestamp,
+            _ => CellKind::Text,
+        }
+    }
+}
+
+/// Oracle mask of the text in DATE and TIMESTAMP cells, before fractional
+/// seconds and time zone. The client formats fetched values itself, so
+/// cells use this layout whatever the session's NLS formats say.
+pub const CELL_DATE_MASK: &str = "YYYY-MM-DD HH24:MI:SS";
+
+/// A single result cell. Non-null values keep the text Oracle returned,
+/// tagged with the kind of column they came from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Null,
+    Number(String),
+    Text(String),
+    Date(String),
+    Timestamp(String),
+    Lob(String),
+    Raw(String),
+}
+
+impl CellValue {
+    /// Build a cell from a fetched column value using the column's Oracle type.
+    pub fn from_oracle(oracle_type: &OracleType, value: Option<String>) -> Self {
+        Self::with_kind(CellKind::from_oracle(oracle_type), value)
+    }
+
+    pub fn with_kind(kind: CellKind, value: Option<String>) -> Self {
+        let Some(value) = value else {
+            return CellValue::Null;
+        };
+        match kind {
+            CellKind::Number => CellValue::Number(value),
+            CellKind::Text => CellValue::Text(value),
+            CellKind::Date => CellValue::Date(value),
+            CellKind::Timestamp => CellValue::Timestamp(value),
+            CellKind::Lob => CellValue::Lob(value),
+            CellKind::Raw => CellValue::Raw(value),
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, CellValue::Null)
+    }
+
+    /// Kind of a non-null value.
+    pub fn kind(&self) -> Option<CellKind> {
+        match self {
+            CellValue::Null => None,
+            CellValue::Number(_) => Some(CellKind::Number),
+            CellValue::Text(_) => Some(CellKind::Text),
+            CellValue::Date(_) => Some(CellKind::Date),
+            CellValue::Timestamp(_) => Some(CellKind::Timestamp),
+            CellValue::Lob(_) => Some(CellKind::Lob),
+            CellValue::Raw(_) => Some(CellKind::Raw),
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, CellValue::Number(_))
+    }
+
+    /// Raw text of a non-null value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            CellValue::Null => None,
+            CellValue::Number(value)
+            | CellValue::Text(value)
+            | CellValue::Date(value)
+            | CellValue::Timestamp(value)
+            | CellValue::Lob(value)
+            | CellValue::Raw(value) => Some(value),
+        }
+    }
+
+    /// Text to show for this cell; only real NULLs render as `null_text`.
+    pub fn display<'a>(&'a self, null_text: &'a str) -> &'a str {
+        self.as_str().unwrap_or(null_text)
+    }
+
+    /// Numeric value of a NUMBER/FLOAT cell.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            CellValue::Number(value) => value.trim().parse::<f64>().ok(),
+            _ => None,
+        }
+    }
+
+    /// Type-aware ordering: numbers compare numerically, dates and timestamps
+    /// chronologically, and NULLs sort last.
+    pub fn compare(&self, other: &CellValue) -> Ordering {
+        match (self, other) {
+            (CellValue::Null, CellValue::Null) => Ordering::Equal,
+            (CellValue::Null, _) => Ordering::Greater,
+            (_, CellValue::Null) => Ordering::Less,
+            (CellValue::Number(_), CellValue::Number(_)) => match (self.as_f64(), other.as_f64()) {
+                (Some(left), Some(right)) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+                _ => self.display("").cmp(other.display("")),
+            },
+            (
+                CellValue::Date(left) | CellValue::Timestamp(left),
+                CellValue::Date(right) | CellValue::Timestamp(right),
+            ) => Self::date_parts(left).cmp(&Self::date_parts(right)),
+            _ => self.display("").cmp(other.display("")),
+        }
+    }
+
+    /// Numeric components of a date/timestamp string, most significant first.
+    fn date_parts(value: &str) -> Vec<u64> {
+        value
+            .split(|ch: char| !ch.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse::<u64>().unwrap_or(u64::MAX))
+            .collect()
+    }
+}
+
+impl From<String> for CellValue {
+    fn from(value: String) -> Self {
+        CellValue::Text(value)
+    }
+}
+
+impl From<&str> for CellValue {
+    fn from(value: &str) -> Self {
+        CellValue::Text(value.to_string())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -32,7 +212,7 @@ pub struct QueryResult {
     #[allow(dead_code)]
     pub sql: String,
     pub columns: Vec<ColumnInfo>,
-    pub rows: Vec<Vec<String>>,
+    pub rows: Vec<Vec<CellValue>>,
     pub row_count: usize,
     pub execution_time: Duration,
     pub message: String,
@@ -173,8 +353,13 @@ pub enum ToolCommand {
     WheneverOsError {
         exit: bool,
     },
-    Exit,
-    Quit,
+    /// Carries the exit code and COMMIT/ROLLBACK arguments, if any.
+    Exit {
+        action: Option<String>,
+    },
+    Quit {
+        action: Option<String>,
+    },
     RunScript {
         path: String,
         relative_to_caller: bool,
@@ -205,7 +390,7 @@ impl QueryResult {
     pub fn new_select(
         sql: &str,
         columns: Vec<ColumnInfo>,
-        rows: Vec<Vec<String>>,
+        rows: Vec<Vec<CellValue>>,
         execution_time: Duration,
     ) -> Self {
         let row_count = rows.len();
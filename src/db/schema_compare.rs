@@ -0,0 +1,784 @@
+//! Comparison of two schemas, possibly on different connections.
+//!
+//! Each side is read into a [`SchemaSnapshot`] through the object browser
+//! queries. Tables are compared by their columns, indexes and constraints,
+//! everything else by its generated DDL.
+
+use std::collections::{BTreeMap, HashSet};
+
+use oracle::{Connection, Error as OracleError};
+
+use super::{ConstraintInfo, IndexInfo, ObjectBrowser, TableColumnDetail};
+
+/// Compared object types, in the order a synchronization script creates
+/// them.
+pub const COMPARED_OBJECT_TYPES: [&str; 8] = [
+    "SEQUENCE",
+    "TABLE",
+    "VIEW",
+    "FUNCTION",
+    "PROCEDURE",
+    "PACKAGE",
+    "TRIGGER",
+    "SYNONYM",
+];
+
+/// Line diffs above this many cells of the comparison table are shown as
+/// a plain replacement instead.
+const MAX_LINE_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Default)]
+pub struct TableShape {
+    pub columns: Vec<TableColumnDetail>,
+    pub indexes: Vec<IndexInfo>,
+    pub constraints: Vec<ConstraintInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SchemaObject {
+    pub object_type: String,
+    pub name: String,
+    /// DDL with the owner prefix removed, terminated for running as a script.
+    pub ddl: String,
+    pub table: Option<TableShape>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SchemaSnapshot {
+    pub owner: String,
+    /// Keyed by type rank in [`COMPARED_OBJECT_TYPES`] and name.
+    pub objects: BTreeMap<(usize, String), SchemaObject>,
+}
+
+impl SchemaSnapshot {
+    pub fn insert(&mut self, object: SchemaObject) {
+        let rank = type_rank(&object.object_type);
+        self.objects.insert((rank, object.name.clone()), object);
+    }
+
+    /// Read the objects of `owner`, or of the connected user for `None`.
+    /// `progress` is told which object type is being read.
+    pub fn load(
+        conn: &Connection,
+        owner: Option<&str>,
+        progress: &mut dyn FnMut(&str),
+    ) -> Result<Self, OracleError> {
+        let owner_name = match owner {
+            Some(owner) => owner.to_uppercase(),
+            None => ObjectBrowser::get_current_user(conn)?,
+        };
+        // Leave out storage clauses, which differ between databases, and
+        // terminate statements so the DDL can be run as a script.
+        if let Err(err) = conn.execute(
+            "BEGIN \
+               DBMS_METADATA.SET_TRANSFORM_PARAM(DBMS_METADATA.SESSION_TRANSFORM, 'SEGMENT_ATTRIBUTES', FALSE); \
+               DBMS_METADATA.SET_TRANSFORM_PARAM(DBMS_METADATA.SESSION_TRANSFORM, 'SQLTERMINATOR', TRUE); \
+             END;",
+            &[],
+        ) {
+            eprintln!("Could not set DDL transform parameters: {err}");
+        }
+        let result = Self::load_objects(conn, owner, &owner_name, progress);
+        if let Err(err) = conn.execute(
+            "BEGIN DBMS_METADATA.SET_TRANSFORM_PARAM(DBMS_METADATA.SESSION_TRANSFORM, 'DEFAULT'); END;",
+            &[],
+        ) {
+            eprintln!("Could not reset DDL transform parameters: {err}");
+        }
+        result
+    }
+
+    fn load_objects(
+        conn: &Connection,
+        owner: Option<&str>,
+        owner_name: &str,
+        progress: &mut dyn FnMut(&str),
+    ) -> Result<Self, OracleError> {
+        let mut snapshot = Self {
+            owner: owner_name.to_string(),
+            objects: BTreeMap::new(),
+        };
+        for object_type in COMPARED_OBJECT_TYPES {
+            progress(object_type);
+            let names = match object_type {
+                "SEQUENCE" => ObjectBrowser::get_sequences_for_owner(conn, owner)?,
+                "TABLE" => ObjectBrowser::get_tables_for_owner(conn, owner)?,
+                "VIEW" => ObjectBrowser::get_views_for_owner(conn, owner)?,
+                "FUNCTION" => ObjectBrowser::get_functions_for_owner(conn, owner)?,
+                "PROCEDURE" => ObjectBrowser::get_procedures_for_owner(conn, owner)?,
+                "PACKAGE" => ObjectBrowser::get_packages_for_owner(conn, owner)?,
+                "TRIGGER" => ObjectBrowser::get_triggers_for_owner(conn, owner)?,
+                _ => ObjectBrowser::get_synonyms_for_owner(conn, owner)?,
+            };
+            // Dropped tables linger in the recycle bin under BIN$ names.
+            for name in names.into_iter().filter(|name| !name.starts_with("BIN$")) {
+                let ddl = match ObjectBrowser::get_object_ddl_for_owner(
+                    conn,
+                    Some(owner_name),
+                    object_type,
+                    &name,
+                ) {
+                    Ok(ddl) => strip_owner(&ddl, owner_name),
+                    Err(err) => format!("-- DDL unavailable: {err}"),
+                };
+                let table = if object_type == "TABLE" {
+                    Some(TableShape {
+                        columns: ObjectBrowser::get_table_structure_for_owner(conn, owner, &name)?,
+                        indexes: ObjectBrowser::get_table_indexes_for_owner(conn, owner, &name)?,
+                        constraints: ObjectBrowser::get_table_constraints_for_owner(
+                            conn, owner, &name,
+                        )?,
+                    })
+                } else {
+                    None
+                };
+                snapshot.insert(SchemaObject {
+                    object_type: object_type.to_string(),
+                    name,
+                    ddl,
+                    table,
+                });
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+fn type_rank(object_type: &str) -> usize {
+    COMPARED_OBJECT_TYPES
+        .iter()
+        .position(|candidate| candidate.eq_ignore_ascii_case(object_type))
+        .unwrap_or(COMPARED_OBJECT_TYPES.len())
+}
+
+/// Remove `"OWNER".` qualifiers so DDL of different schemas compares equal.
+fn strip_owner(ddl: &str, owner: &str) -> String {
+    ddl.replace(&format!("\"{}\".", owner), "")
+}
+
+/// DDL reduced to what matters when comparing: whitespace runs collapsed and
+/// a sequence's current position dropped.
+fn comparable_ddl(object_type: &str, ddl: &str) -> String {
+    let words: Vec<&str> = ddl.split_whitespace().collect();
+    let mut kept: Vec<&str> = Vec::with_capacity(words.len());
+    let mut index = 0;
+    while index < words.len() {
+        if object_type == "SEQUENCE"
+            && words[index].eq_ignore_ascii_case("START")
+            && words
+                .get(index + 1)
+                .is_some_and(|word| word.eq_ignore_ascii_case("WITH"))
+        {
+            index += 3;
+            continue;
+        }
+        kept.push(words[index]);
+        index += 1;
+    }
+    kept.join(" ")
+}
+
+fn column_definition(column: &TableColumnDetail) -> String {
+    let mut definition = column.get_type_display();
+    if let Some(default) = column
+        .default_value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        definition.push_str(&format!(" DEFAULT {default}"));
+    }
+    if !column.nullable {
+        definition.push_str(" NOT NULL");
+    }
+    definition
+}
+
+/// System-named constraints, such as the NOT NULL checks Oracle creates
+/// for columns.
+fn is_system_named(name: &str) -> bool {
+    name.starts_with("SYS_C")
+}
+
+fn index_signature(index: &IndexInfo) -> (bool, String) {
+    (index.is_unique, index.columns.clone())
+}
+
+fn constraint_signature(constraint: &ConstraintInfo) -> (String, String, Option<String>) {
+    (
+        constraint.constraint_type.clone(),
+        constraint.columns.clone(),
+        constraint.ref_table.clone(),
+    )
+}
+
+/// One structural difference of a table present on both sides.
+#[derive(Debug, Clone)]
+pub enum TableChange {
+    AddColumn {
+        name: String,
+        definition: String,
+    },
+    ModifyColumn {
+        source: TableColumnDetail,
+        target: TableColumnDetail,
+    },
+    DropColumn {
+        name: String,
+    },
+    AddIndex(IndexInfo),
+    DropIndex(IndexInfo),
+    AddConstraint(ConstraintInfo),
+    DropConstraint(ConstraintInfo),
+}
+
+impl TableChange {
+    pub fn describe(&self) -> String {
+        match self {
+            TableChange::AddColumn { name, definition } => {
+                format!("Column {name} {definition} is missing in target")
+            }
+            TableChange::ModifyColumn { source, target } => format!(
+                "Column {}: {} in source, {} in target",
+                source.name,
+                column_definition(source),
+                column_definition(target)
+            ),
+            TableChange::DropColumn { name } => format!("Column {name} exists only in target"),
+            TableChange::AddIndex(index) => {
+                format!(
+                    "Index {} ({}) is missing in target",
+                    index.name, index.columns
+                )
+            }
+            TableChange::DropIndex(index) => {
+                format!(
+                    "Index {} ({}) exists only in target",
+                    index.name, index.columns
+                )
+            }
+            TableChange::AddConstraint(constraint) => format!(
+                "{} {} ({}) is missing in target",
+                constraint.constraint_type, constraint.name, constraint.columns
+            ),
+            TableChange::DropConstraint(constraint) => format!(
+                "{} {} ({}) exists only in target",
+                constraint.constraint_type, constraint.name, constraint.columns
+            ),
+        }
+    }
+
+    /// The statement bringing the target in line. Anything that loses data
+    /// or can't be derived from the dictionary is commented out.
+    pub fn statement(&self, table: &str) -> String {
+        match self {
+            TableChange::AddColumn { name, definition } => {
+                format!("ALTER TABLE {table} ADD ({name} {definition});")
+            }
+            TableChange::ModifyColumn { source, target } => {
+                let mut clause = source.get_type_display();
+                let source_default = source.default_value.as_deref().map(str::trim);
+                let target_default = target.default_value.as_deref().map(str::trim);
+                if source_default != target_default {
+                    clause.push_str(&format!(
+                        " DEFAULT {}",
+                        source_default.filter(|d| !d.is_empty()).unwrap_or("NULL")
+                    ));
+                }
+                if source.nullable != target.nullable {
+                    clause.push_str(if source.nullable {
+                        " NULL"
+                    } else {
+                        " NOT NULL"
+                    });
+                }
+                format!("ALTER TABLE {table} MODIFY ({} {clause});", source.name)
+            }
+            TableChange::DropColumn { name } => {
+                format!("-- ALTER TABLE {table} DROP COLUMN {name};")
+            }
+            TableChange::AddIndex(index) => format!(
+                "CREATE {}INDEX {} ON {table} ({});",
+                if index.is_unique { "UNIQUE " } else { "" },
+                index.name,
+                index.columns
+            ),
+            TableChange::DropIndex(index) => format!("-- DROP INDEX {};", index.name),
+            TableChange::AddConstraint(constraint) => {
+                let name = if is_system_named(&constraint.name) {
+                    String::new()
+                } else {
+                    format!("CONSTRAINT {} ", constraint.name)
+                };
+                match constraint.constraint_type.as_str() {
+                    "PRIMARY KEY" | "UNIQUE" => format!(
+                        "ALTER TABLE {table} ADD {name}{} ({});",
+                        constraint.constraint_type, constraint.columns
+                    ),
+                    "FOREIGN KEY" => format!(
+                        "ALTER TABLE {table} ADD {name}FOREIGN KEY ({}) REFERENCES {};",
+                        constraint.columns,
+                        constraint.ref_table.as_deref().unwrap_or("?")
+                    ),
+                    _ => format!(
+                        "-- ALTER TABLE {table} ADD {name}CHECK (...); -- condition on ({}), see source DDL",
+                        constraint.columns
+                    ),
+                }
+            }
+            TableChange::DropConstraint(constraint) => format!(
+                "-- ALTER TABLE {table} DROP CONSTRAINT {};",
+                constraint.name
+            ),
+        }
+    }
+}
+
+/// Structural differences of a table, source against target.
+pub fn compare_tables(source: &TableShape, target: &TableShape) -> Vec<TableChange> {
+    let mut changes = Vec::new();
+    for column in &source.columns {
+        match target.columns.iter().find(|c| c.name == column.name) {
+            None => changes.push(TableChange::AddColumn {
+                name: column.name.clone(),
+                definition: column_definition(column),
+            }),
+            Some(other) if column_definition(other) != column_definition(column) => {
+                changes.push(TableChange::ModifyColumn {
+                    source: column.clone(),
+                    target: other.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for column in &target.columns {
+        if !source.columns.iter().any(|c| c.name == column.name) {
+            changes.push(TableChange::DropColumn {
+                name: column.name.clone(),
+            });
+        }
+    }
+
+    // Indexes and constraints match by what they cover, since names are
+    // often generated.
+    let target_indexes: HashSet<_> = target.indexes.iter().map(index_signature).collect();
+    let source_indexes: HashSet<_> = source.indexes.iter().map(index_signature).collect();
+    // Key constraints bring their own index.
+    let constraint_columns: HashSet<&str> = source
+        .constraints
+        .iter()
+        .chain(&target.constraints)
+        .filter(|c| matches!(c.constraint_type.as_str(), "PRIMARY KEY" | "UNIQUE"))
+        .map(|c| c.columns.as_str())
+        .collect();
+    let backs_key =
+        |index: &IndexInfo| index.is_unique && constraint_columns.contains(index.columns.as_str());
+    for index in &source.indexes {
+        if !(target_indexes.contains(&index_signature(index)) || backs_key(index)) {
+            changes.push(TableChange::AddIndex(index.clone()));
+        }
+    }
+    for index in &target.indexes {
+        if !(source_indexes.contains(&index_signature(index)) || backs_key(index)) {
+            changes.push(TableChange::DropIndex(index.clone()));
+        }
+    }
+
+    // System-named checks are the columns' NOT NULL, compared above.
+    let relevant =
+        |c: &&ConstraintInfo| !(c.constraint_type == "CHECK" && is_system_named(&c.name));
+    let target_constraints: HashSet<_> = target
+        .constraints
+        .iter()
+        .filter(relevant)
+        .map(constraint_signature)
+        .collect();
+    let source_constraints: HashSet<_> = source
+        .constraints
+        .iter()
+        .filter(relevant)
+        .map(constraint_signature)
+        .collect();
+    for constraint in source.constraints.iter().filter(relevant) {
+        if !target_constraints.contains(&constraint_signature(constraint)) {
+            changes.push(TableChange::AddConstraint(constraint.clone()));
+        }
+    }
+    for constraint in target.constraints.iter().filter(relevant) {
+        if !source_constraints.contains(&constraint_signature(constraint)) {
+            changes.push(TableChange::DropConstraint(constraint.clone()));
+        }
+    }
+    changes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Only in the source; the target needs it created.
+    MissingInTarget,
+    /// Only in the target.
+    ExtraInTarget,
+    Different,
+}
+
+impl DiffKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            DiffKind::MissingInTarget => "Missing",
+            DiffKind::ExtraInTarget => "Extra",
+            DiffKind::Different => "Different",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectDiff {
+    pub object_type: String,
+    pub name: String,
+    pub kind: DiffKind,
+    pub source_ddl: Option<String>,
+    pub target_ddl: Option<String>,
+    /// For tables present on both sides.
+    pub table_changes: Vec<TableChange>,
+}
+
+/// Objects that differ between the two snapshots, in script order.
+pub fn compare_snapshots(source: &SchemaSnapshot, target: &SchemaSnapshot) -> Vec<ObjectDiff> {
+    let mut diffs = Vec::new();
+    for (key, object) in &source.objects {
+        let Some(other) = target.objects.get(key) else {
+            diffs.push(ObjectDiff {
+                object_type: object.object_type.clone(),
+                name: object.name.clone(),
+                kind: DiffKind::MissingInTarget,
+                source_ddl: Some(object.ddl.clone()),
+                target_ddl: None,
+                table_changes: Vec::new(),
+            });
+            continue;
+        };
+        let (differs, table_changes) = match (&object.table, &other.table) {
+            (Some(shape), Some(other_shape)) => {
+                let changes = compare_tables(shape, other_shape);
+                (!changes.is_empty(), changes)
+            }
+            _ => (
+                comparable_ddl(&object.object_type, &object.ddl)
+                    != comparable_ddl(&other.object_type, &other.ddl),
+                Vec::new(),
+            ),
+        };
+        if differs {
+            diffs.push(ObjectDiff {
+                object_type: object.object_type.clone(),
+                name: object.name.clone(),
+                kind: DiffKind::Different,
+                source_ddl: Some(object.ddl.clone()),
+                target_ddl: Some(other.ddl.clone()),
+                table_changes,
+            });
+        }
+    }
+    for (key, object) in &target.objects {
+        if !source.objects.contains_key(key) {
+            diffs.push(ObjectDiff {
+                object_type: object.object_type.clone(),
+                name: object.name.clone(),
+                kind: DiffKind::ExtraInTarget,
+                source_ddl: None,
+                target_ddl: Some(object.ddl.clone()),
+                table_changes: Vec::new(),
+            });
+        }
+    }
+    diffs.sort_by_key(|diff| (type_rank(&diff.object_type), diff.name.clone()));
+    diffs
+}
+
+fn commented(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("-- {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A script that makes the target match the source. Drops are left
+/// commented out for review.
+pub fn synchronization_script(
+    diffs: &[ObjectDiff],
+    source_label: &str,
+    target_label: &str,
+) -> String {
+    let mut script = vec![format!(
+        "-- Synchronize {target_label} with {source_label}\n\
+         -- Generated {}. Review before running; drops are commented out.",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    )];
+    for diff in diffs {
+        let header = format!(
+            "-- {} {}: {}",
+            diff.object_type,
+            diff.name,
+            diff.kind.label().to_lowercase()
+        );
+        let body = match diff.kind {
+            DiffKind::MissingInTarget => diff.source_ddl.clone().unwrap_or_default(),
+            DiffKind::ExtraInTarget => format!("-- DROP {} {};", diff.object_type, diff.name),
+            DiffKind::Different if !diff.table_changes.is_empty() => diff
+                .table_changes
+                .iter()
+                .map(|change| change.statement(&diff.name))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            DiffKind::Different => match diff.object_type.as_str() {
+                // Generated DDL of these is CREATE OR REPLACE.
+                "VIEW" | "FUNCTION" | "PROCEDURE" | "PACKAGE" | "TRIGGER" | "SYNONYM" => {
+                    diff.source_ddl.clone().unwrap_or_default()
+                }
+                _ => format!(
+                    "-- DROP {} {};\n{}",
+                    diff.object_type,
+                    diff.name,
+                    commented(diff.source_ddl.as_deref().unwrap_or_default())
+                ),
+            },
+        };
+        script.push(format!("{header}\n{body}"));
+    }
+    script.join("\n\n") + "\n"
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    /// Only in the first text.
+    Removed(String),
+    /// Only in the second text.
+    Added(String),
+}
+
+/// Line diff of `old` against `new`, from their longest common subsequence.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old.len().saturating_mul(new.len()) > MAX_LINE_DIFF_CELLS {
+        let mut lines: Vec<DiffLine> = old
+            .iter()
+            .map(|l| DiffLine::Removed(l.to_string()))
+            .collect();
+        lines.extend(new.iter().map(|l| DiffLine::Added(l.to_string())));
+        return lines;
+    }
+    // common[i][j]: LCS length of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut common = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i * width + j] = if old[i] == new[j] {
+                common[(i + 1) * width + j + 1] + 1
+            } else {
+                common[(i + 1) * width + j].max(common[i * width + j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if j < new.len()
+            && (i == old.len() || common[i * width + j + 1] >= common[(i + 1) * width + j])
+        {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        } else {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        }
+    }
+    lines
+}
+
+/// Pair diff lines into side-by-side rows; runs of removed and added lines
+/// share rows.
+pub fn side_by_side(lines: &[DiffLine]) -> Vec<(Option<String>, Option<String>)> {
+    let mut rows = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    let mut added: Vec<String> = Vec::new();
+    let flush = |rows: &mut Vec<(Option<String>, Option<String>)>,
+                 removed: &mut Vec<String>,
+                 added: &mut Vec<String>| {
+        let count = removed.len().max(added.len());
+        let mut removed = removed.drain(..);
+        let mut added = added.drain(..);
+        for _ in 0..count {
+            rows.push((removed.next(), added.next()));
+        }
+    };
+    for line in lines {
+        match line {
+            DiffLine::Same(text) => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push((Some(text.clone()), Some(text.clone())));
+            }
+            DiffLine::Removed(text) => removed.push(text.clone()),
+            DiffLine::Added(text) => added.push(text.clone()),
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+#[cfg(test)]
+mod schema_compare_tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, length: i32, nullable: bool) -> TableColumnDetail {
+        TableColumnDetail {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            data_length: length,
+            data_precision: None,
+            data_scale: None,
+            nullable,
+            default_value: None,
+            is_primary_key: false,
+        }
+    }
+
+    fn constraint(name: &str, constraint_type: &str, columns: &str) -> ConstraintInfo {
+        ConstraintInfo {
+            name: name.to_string(),
+            constraint_type: constraint_type.to_string(),
+            columns: columns.to_string(),
+            ref_table: None,
+        }
+    }
+
+    fn table(name: &str, shape: TableShape) -> SchemaObject {
+        SchemaObject {
+            object_type: "TABLE".to_string(),
+            name: name.to_string(),
+            ddl: format!("CREATE TABLE {name} (...);"),
+            table: Some(shape),
+        }
+    }
+
+    #[test]
+    fn tables_compare_columns_and_ignore_generated_names() {
+        let source = TableShape {
+            columns: vec![
+                column("ID", "NUMBER", 22, false),
+                column("NAME", "VARCHAR2", 100, true),
+                column("EMAIL", "VARCHAR2", 200, true),
+            ],
+            indexes: vec![IndexInfo {
+                name: "SYS_C001".to_string(),
+                is_unique: true,
+                columns: "ID".to_string(),
+            }],
+            constraints: vec![
+                constraint("SYS_C001", "PRIMARY KEY", "ID"),
+                constraint("SYS_C002", "CHECK", "ID"),
+            ],
+        };
+        let target = TableShape {
+            columns: vec![
+                column("ID", "NUMBER", 22, false),
+                column("NAME", "VARCHAR2", 50, true),
+                column("LEGACY", "CHAR", 1, true),
+            ],
+            indexes: vec![IndexInfo {
+                name: "SYS_C999".to_string(),
+                is_unique: true,
+                columns: "ID".to_string(),
+            }],
+            constraints: vec![constraint("SYS_C999", "PRIMARY KEY", "ID")],
+        };
+        let statements: Vec<String> = compare_tables(&source, &target)
+            .iter()
+            .map(|change| change.statement("EMP"))
+            .collect();
+        assert_eq!(
+            statements,
+            vec![
+                "ALTER TABLE EMP MODIFY (NAME VARCHAR2(100));",
+                "ALTER TABLE EMP ADD (EMAIL VARCHAR2(200));",
+                "-- ALTER TABLE EMP DROP COLUMN LEGACY;",
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshots_report_missing_extra_and_changed_objects_in_script_order() {
+        let view = |name: &str, ddl: &str| SchemaObject {
+            object_type: "VIEW".to_string(),
+            name: name.to_string(),
+            ddl: ddl.to_string(),
+            table: None,
+        };
+        let sequence = |start: u32| SchemaObject {
+            object_type: "SEQUENCE".to_string(),
+            name: "EMP_SEQ".to_string(),
+            ddl: format!("CREATE SEQUENCE EMP_SEQ INCREMENT BY 1 START WITH {start} CACHE 20;"),
+            table: None,
+        };
+        let mut source = SchemaSnapshot::default();
+        source.insert(view(
+            "V_EMP",
+            "CREATE OR REPLACE VIEW V_EMP AS SELECT id FROM emp;",
+        ));
+        source.insert(table("DEPT", TableShape::default()));
+        source.insert(sequence(100));
+        let mut target = SchemaSnapshot::default();
+        target.insert(view(
+            "V_EMP",
+            "CREATE OR REPLACE VIEW V_EMP AS\n  SELECT id, name FROM emp;",
+        ));
+        target.insert(view(
+            "V_OLD",
+            "CREATE OR REPLACE VIEW V_OLD AS SELECT 1 FROM dual;",
+        ));
+        target.insert(sequence(5));
+
+        let diffs = compare_snapshots(&source, &target);
+        let summary: Vec<(&str, &str, DiffKind)> = diffs
+            .iter()
+            .map(|d| (d.object_type.as_str(), d.name.as_str(), d.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("TABLE", "DEPT", DiffKind::MissingInTarget),
+                ("VIEW", "V_EMP", DiffKind::Different),
+                ("VIEW", "V_OLD", DiffKind::ExtraInTarget),
+            ]
+        );
+        let script = synchronization_script(&diffs, "DEV", "TEST");
+        assert!(script.contains("CREATE TABLE DEPT (...);"));
+        assert!(script.contains("CREATE OR REPLACE VIEW V_EMP AS SELECT id FROM emp;"));
+        assert!(script.contains("-- DROP VIEW V_OLD;"));
+    }
+
+    #[test]
+    fn side_by_side_pairs_replaced_lines() {
+        let rows = side_by_side(&diff_lines("a\nb\nc\n", "a\nx\nc\nd\n"));
+        let some = |text: &str| Some(text.to_string());
+        assert_eq!(
+            rows,
+            vec![
+                (some("a"), some("a")),
+                (some("b"), some("x")),
+                (some("c"), some("c")),
+                (None, some("d")),
+            ]
+        );
+        assert_eq!(
+            strip_owner("CREATE TABLE \"HR\".\"EMP\"", "HR"),
+            "CREATE TABLE \"EMP\""
+        );
+    }
+}
@@ -2,6 +2,8 @@ use oracle::sql_type::OracleType;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::db::query::CellValue;
+
 #[derive(Debug, Clone)]
 pub enum BindDataType {
     Number,
@@ -27,7 +29,7 @@ pub struct BindVar {
 #[derive(Debug, Clone)]
 pub struct CursorResult {
     pub columns: Vec<String>,
-    pub rows: Vec<Vec<String>>,
+    pub rows: Vec<Vec<CellValue>>,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,186 @@
+//! Settings applied to every new session: NLS formats, time zone, current
+//! schema, `DBMS_APPLICATION_INFO` names and a login script, turned into a
+//! script the script engine runs after connecting.
+
+use serde::{Deserialize, Serialize};
+
+/// Session settings; an empty field is left alone, or inherits the global
+/// value when the settings belong to a connection profile.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionSettings {
+    pub nls_date_format: String,
+    pub nls_timestamp_format: String,
+    /// Decimal and group separator, e.g. `.,`.
+    pub nls_numeric_characters: String,
+    /// e.g. `+09:00`, `Europe/Berlin` or `LOCAL`.
+    pub time_zone: String,
+    pub current_schema: String,
+    pub module: String,
+    pub action: String,
+    /// Path of a script run last, like SQL*Plus `login.sql`.
+    pub login_script: String,
+}
+
+fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Schema names are used as typed when they are plain identifiers and
+/// quoted otherwise.
+fn schema_identifier(value: &str) -> String {
+    let plain = value.chars().next().is_some_and(|c| c.is_alphabetic())
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '#'));
+    if plain {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.trim_matches('"').replace('"', ""))
+    }
+}
+
+impl SessionSettings {
+    /// What sessions get before anything is configured.
+    pub fn application_defaults() -> Self {
+        Self {
+            nls_date_format: "yyyy-mm-dd hh24:mi:ss".to_string(),
+            nls_timestamp_format: "yyyy-mm-dd hh24:mi:ss".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// These settings with every field `profile` sets taking its value.
+    pub fn overridden_by(&self, profile: &SessionSettings) -> Self {
+        let pick = |global: &String, own: &String| {
+            if own.trim().is_empty() {
+                global.clone()
+            } else {
+                own.clone()
+            }
+        };
+        Self {
+            nls_date_format: pick(&self.nls_date_format, &profile.nls_date_format),
+            nls_timestamp_format: pick(&self.nls_timestamp_format, &profile.nls_timestamp_format),
+            nls_numeric_characters: pick(
+                &self.nls_numeric_characters,
+                &profile.nls_numeric_characters,
+            ),
+            time_zone: pick(&self.time_zone, &profile.time_zone),
+            current_schema: pick(&self.current_schema, &profile.current_schema),
+            module: pick(&self.module, &profile.module),
+            action: pick(&self.action, &profile.action),
+            login_script: pick(&self.login_script, &profile.login_script),
+        }
+    }
+
+    /// Number of fields that are set, for summaries.
+    pub fn configured_count(&self) -> usize {
+        [
+            &self.nls_date_format,
+            &self.nls_timestamp_format,
+            &self.nls_numeric_characters,
+            &self.time_zone,
+            &self.current_schema,
+            &self.module,
+            &self.action,
+            &self.login_script,
+        ]
+        .iter()
+        .filter(|value| !value.trim().is_empty())
+        .count()
+    }
+
+    /// The script that applies these settings; empty when nothing is set.
+    pub fn init_script(&self) -> String {
+        let mut lines = Vec::new();
+        for (parameter, value) in [
+            ("NLS_DATE_FORMAT", &self.nls_date_format),
+            ("NLS_TIMESTAMP_FORMAT", &self.nls_timestamp_format),
+            ("NLS_NUMERIC_CHARACTERS", &self.nls_numeric_characters),
+            ("TIME_ZONE", &self.time_zone),
+        ] {
+            let value = value.trim();
+            if !value.is_empty() {
+                lines.push(format!(
+                    "ALTER SESSION SET {} = {};",
+                    parameter,
+                    string_literal(value)
+                ));
+            }
+        }
+        let schema = self.current_schema.trim();
+        if !schema.is_empty() {
+            lines.push(format!(
+                "ALTER SESSION SET CURRENT_SCHEMA = {};",
+                schema_identifier(schema)
+            ));
+        }
+        let module = self.module.trim();
+        let action = self.action.trim();
+        if !module.is_empty() {
+            lines.push(format!(
+                "BEGIN DBMS_APPLICATION_INFO.SET_MODULE({}, {}); END;\n/",
+                string_literal(module),
+                if action.is_empty() {
+                    "NULL".to_string()
+                } else {
+                    string_literal(action)
+                }
+            ));
+        } else if !action.is_empty() {
+            lines.push(format!(
+                "BEGIN DBMS_APPLICATION_INFO.SET_ACTION({}); END;\n/",
+                string_literal(action)
+            ));
+        }
+        let login_script = self.login_script.trim();
+        if !login_script.is_empty() {
+            lines.push(format!("@\"{}\"", login_script.trim_matches('"')));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod session_init_tests {
+    use super::*;
+
+    #[test]
+    fn profile_values_override_the_global_ones() {
+        let global = SessionSettings::application_defaults();
+        let profile = SessionSettings {
+            nls_date_format: "dd.mm.yyyy".to_string(),
+            current_schema: "hr".to_string(),
+            ..SessionSettings::default()
+        };
+        let merged = global.overridden_by(&profile);
+        assert_eq!(merged.nls_date_format, "dd.mm.yyyy");
+        assert_eq!(merged.nls_timestamp_format, global.nls_timestamp_format);
+        assert_eq!(merged.current_schema, "hr");
+        assert_eq!(merged.module, "");
+        assert_eq!(profile.configured_count(), 2);
+    }
+
+    #[test]
+    fn init_script_sets_each_configured_value() {
+        let settings = SessionSettings {
+            nls_numeric_characters: ",.".to_string(),
+            time_zone: "Europe/Berlin".to_string(),
+            current_schema: "Sales Data".to_string(),
+            module: "Reports".to_string(),
+            action: "O'Neil".to_string(),
+            login_script: "/home/me/login.sql".to_string(),
+            ..SessionSettings::default()
+        };
+        assert_eq!(
+            settings.init_script(),
+            "ALTER SESSION SET NLS_NUMERIC_CHARACTERS = ',.';\n\
+             ALTER SESSION SET TIME_ZONE = 'Europe/Berlin';\n\
+             ALTER SESSION SET CURRENT_SCHEMA = \"Sales Data\";\n\
+             BEGIN DBMS_APPLICATION_INFO.SET_MODULE('Reports', 'O''Neil'); END;\n/\n\
+             @\"/home/me/login.sql\""
+        );
+        assert_eq!(SessionSettings::default().init_script(), "");
+    }
+}
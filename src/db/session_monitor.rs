@@ -0,0 +1,386 @@
+//! Session browsing and lock monitoring through `V$SESSION`, `V$SQL`,
+//! `V$OPEN_CURSOR` and `V$LOCK`.
+
+use std::collections::{HashMap, HashSet};
+
+use oracle::{Connection, Error as OracleError};
+
+/// One row of `V$SESSION`, with the start of its current SQL.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionInfo {
+    pub sid: i64,
+    pub serial: i64,
+    pub username: String,
+    pub status: String,
+    pub os_user: String,
+    pub machine: String,
+    pub program: String,
+    pub event: String,
+    pub wait_class: String,
+    /// Seconds since the session's last call started (`LAST_CALL_ET`).
+    pub elapsed_seconds: i64,
+    pub blocking_session: Option<i64>,
+    pub sql_id: Option<String>,
+    pub sql_child_number: Option<i64>,
+    /// `USER` or `BACKGROUND`.
+    pub session_type: String,
+    pub sql_text: String,
+}
+
+impl SessionInfo {
+    pub fn is_background(&self) -> bool {
+        self.session_type.eq_ignore_ascii_case("BACKGROUND")
+    }
+}
+
+/// What the session list shows.
+#[derive(Debug, Clone, Default)]
+pub struct SessionFilter {
+    /// Case-insensitive text matched against user, machine, program, event
+    /// and SQL.
+    pub text: String,
+    pub active_only: bool,
+    pub hide_background: bool,
+    /// Only sessions that block or wait on others.
+    pub blocking_only: bool,
+}
+
+impl SessionFilter {
+    pub fn matches(&self, session: &SessionInfo, roles: &HashMap<i64, BlockRole>) -> bool {
+        if self.active_only && !session.status.eq_ignore_ascii_case("ACTIVE") {
+            return false;
+        }
+        if self.hide_background && session.is_background() {
+            return false;
+        }
+        if self.blocking_only && !roles.contains_key(&session.sid) {
+            return false;
+        }
+        let text = self.text.trim().to_lowercase();
+        if text.is_empty() {
+            return true;
+        }
+        session.sid.to_string() == text
+            || [
+                &session.username,
+                &session.os_user,
+                &session.machine,
+                &session.program,
+                &session.event,
+                &session.sql_text,
+            ]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&text))
+    }
+}
+
+/// A session's part in a blocking chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockRole {
+    /// Blocks others and waits on nobody: the head of a chain.
+    Blocker,
+    /// Waits on another session, possibly blocking more in turn.
+    Waiter,
+}
+
+/// Blocking chain roles of the sessions involved in one.
+pub fn blocking_roles(sessions: &[SessionInfo]) -> HashMap<i64, BlockRole> {
+    let mut roles = HashMap::new();
+    for session in sessions {
+        if let Some(blocker) = session.blocking_session {
+            roles.insert(session.sid, BlockRole::Waiter);
+            roles.entry(blocker).or_insert(BlockRole::Blocker);
+        }
+    }
+    roles
+}
+
+/// The chain from `sid` up to the session at its head, `sid` first.
+pub fn blocking_chain(sessions: &[SessionInfo], sid: i64) -> Vec<i64> {
+    let blockers: HashMap<i64, i64> = sessions
+        .iter()
+        .filter_map(|session| {
+            session
+                .blocking_session
+                .map(|blocker| (session.sid, blocker))
+        })
+        .collect();
+    let mut chain = vec![sid];
+    let mut seen: HashSet<i64> = HashSet::from([sid]);
+    let mut current = sid;
+    while let Some(&blocker) = blockers.get(&current) {
+        chain.push(blocker);
+        if !seen.insert(blocker) {
+            // A deadlock: the chain loops back on itself.
+            break;
+        }
+        current = blocker;
+    }
+    chain
+}
+
+/// Sessions waiting on `sid`, directly or further down the chain.
+pub fn blocked_sessions(sessions: &[SessionInfo], sid: i64) -> Vec<i64> {
+    let mut blocked = Vec::new();
+    let mut pending = vec![sid];
+    while let Some(blocker) = pending.pop() {
+        for session in sessions {
+            if session.blocking_session == Some(blocker)
+                && session.sid != sid
+                && !blocked.contains(&session.sid)
+            {
+                blocked.push(session.sid);
+                pending.push(session.sid);
+            }
+        }
+    }
+    blocked
+}
+
+pub fn format_elapsed(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
+
+/// A lock held or requested by a session (`V$LOCK`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionLock {
+    pub lock_type: String,
+    pub id1: i64,
+    pub id2: i64,
+    pub mode_held: i64,
+    pub mode_requested: i64,
+    pub seconds: i64,
+    /// Whether the lock blocks another session.
+    pub blocking: bool,
+    /// Object the lock is on, for DML locks.
+    pub object_name: Option<String>,
+}
+
+pub fn lock_mode_name(mode: i64) -> &'static str {
+    match mode {
+        0 => "None",
+        1 => "Null",
+        2 => "Row Share",
+        3 => "Row Exclusive",
+        4 => "Share",
+        5 => "Share Row Exclusive",
+        6 => "Exclusive",
+        _ => "Unknown",
+    }
+}
+
+/// What a selected session is running and holding.
+#[derive(Debug, Clone, Default)]
+pub struct SessionDetail {
+    pub sql_text: String,
+    pub plan: Vec<String>,
+    /// SQL id and text of each open cursor.
+    pub open_cursors: Vec<(String, String)>,
+    pub locks: Vec<SessionLock>,
+}
+
+fn database_error(err: OracleError) -> String {
+    eprintln!("Database operation failed: {err}");
+    err.to_string()
+}
+
+fn text(row: &oracle::Row, index: usize) -> String {
+    row.get::<_, Option<String>>(index)
+        .unwrap_or(None)
+        .unwrap_or_default()
+}
+
+/// All sessions of the instance, by SID.
+pub fn list_sessions(conn: &Connection) -> Result<Vec<SessionInfo>, String> {
+    let sql = "SELECT s.sid, s.serial#, s.username, s.status, s.osuser, s.machine, s.program, \
+               s.event, s.wait_class, s.last_call_et, s.blocking_session, s.sql_id, \
+               s.sql_child_number, s.type, SUBSTR(q.sql_text, 1, 200) \
+               FROM v$session s \
+               LEFT JOIN v$sql q ON q.sql_id = s.sql_id AND q.child_number = s.sql_child_number \
+               ORDER BY s.sid";
+    let rows = conn.query(sql, &[]).map_err(database_error)?;
+    let mut sessions = Vec::new();
+    for row_result in rows {
+        let row = row_result.map_err(database_error)?;
+        sessions.push(SessionInfo {
+            sid: row.get(0).unwrap_or(0),
+            serial: row.get(1).unwrap_or(0),
+            username: text(&row, 2),
+            status: text(&row, 3),
+            os_user: text(&row, 4),
+            machine: text(&row, 5),
+            program: text(&row, 6),
+            event: text(&row, 7),
+            wait_class: text(&row, 8),
+            elapsed_seconds: row.get::<_, Option<i64>>(9).unwrap_or(None).unwrap_or(0),
+            blocking_session: row.get(10).unwrap_or(None),
+            sql_id: row.get(11).unwrap_or(None),
+            sql_child_number: row.get(12).unwrap_or(None),
+            session_type: text(&row, 13),
+            sql_text: text(&row, 14),
+        });
+    }
+    Ok(sessions)
+}
+
+/// Current SQL with its plan, open cursors and locks of `session`.
+pub fn session_detail(conn: &Connection, session: &SessionInfo) -> Result<SessionDetail, String> {
+    let mut detail = SessionDetail::default();
+    if let Some(sql_id) = &session.sql_id {
+        let rows = conn
+            .query(
+                "SELECT sql_fulltext FROM v$sql WHERE sql_id = :1 AND ROWNUM = 1",
+                &[sql_id],
+            )
+            .map_err(database_error)?;
+        if let Some(row) = rows.flatten().next() {
+            detail.sql_text = text(&row, 0);
+        }
+        let child = session.sql_child_number.unwrap_or(0);
+        match conn.query(
+            "SELECT plan_table_output FROM TABLE(DBMS_XPLAN.DISPLAY_CURSOR(:1, :2, 'TYPICAL'))",
+            &[sql_id, &child],
+        ) {
+            Ok(rows) => {
+                detail.plan = rows.flatten().map(|row| text(&row, 0)).collect();
+            }
+            Err(err) => {
+                // DISPLAY_CURSOR needs extra grants; the rest is still useful.
+                detail.plan = vec![format!("Plan not available: {}", database_error(err))];
+            }
+        }
+    }
+
+    let rows = conn
+        .query(
+            "SELECT sql_id, sql_text FROM v$open_cursor WHERE sid = :1 ORDER BY sql_id",
+            &[&session.sid],
+        )
+        .map_err(database_error)?;
+    for row_result in rows {
+        let row = row_result.map_err(database_error)?;
+        detail.open_cursors.push((text(&row, 0), text(&row, 1)));
+    }
+
+    let rows = conn
+        .query(
+            "SELECT l.type, l.id1, l.id2, l.lmode, l.request, l.ctime, l.block, \
+             (SELECT o.owner || '.' || o.object_name FROM all_objects o \
+              WHERE l.type = 'TM' AND o.object_id = l.id1) \
+             FROM v$lock l WHERE l.sid = :1 ORDER BY l.block DESC, l.type",
+            &[&session.sid],
+        )
+        .map_err(database_error)?;
+    for row_result in rows {
+        let row = row_result.map_err(database_error)?;
+        detail.locks.push(SessionLock {
+            lock_type: text(&row, 0),
+            id1: row.get(1).unwrap_or(0),
+            id2: row.get(2).unwrap_or(0),
+            mode_held: row.get(3).unwrap_or(0),
+            mode_requested: row.get(4).unwrap_or(0),
+            seconds: row.get(5).unwrap_or(0),
+            blocking: row.get::<_, i64>(6).unwrap_or(0) > 0,
+            object_name: row.get(7).unwrap_or(None),
+        });
+    }
+    Ok(detail)
+}
+
+/// `ALTER SYSTEM KILL SESSION` for `sid,serial#`.
+pub fn kill_session(
+    conn: &Connection,
+    sid: i64,
+    serial: i64,
+    immediate: bool,
+) -> Result<(), String> {
+    let sql = format!(
+        "ALTER SYSTEM KILL SESSION '{},{}'{}",
+        sid,
+        serial,
+        if immediate { " IMMEDIATE" } else { "" }
+    );
+    conn.execute(&sql, &[]).map(|_| ()).map_err(database_error)
+}
+
+#[cfg(test)]
+mod session_monitor_tests {
+    use super::*;
+
+    fn session(sid: i64, blocking_session: Option<i64>) -> SessionInfo {
+        SessionInfo {
+            sid,
+            status: "ACTIVE".to_string(),
+            session_type: "USER".to_string(),
+            blocking_session,
+            ..SessionInfo::default()
+        }
+    }
+
+    #[test]
+    fn chains_run_from_waiter_to_head() {
+        let sessions = vec![
+            session(10, None),
+            session(20, Some(10)),
+            session(30, Some(20)),
+            session(40, None),
+        ];
+        let roles = blocking_roles(&sessions);
+        assert_eq!(roles.get(&10), Some(&BlockRole::Blocker));
+        assert_eq!(roles.get(&20), Some(&BlockRole::Waiter));
+        assert_eq!(roles.get(&30), Some(&BlockRole::Waiter));
+        assert!(!roles.contains_key(&40));
+        assert_eq!(blocking_chain(&sessions, 30), vec![30, 20, 10]);
+        assert_eq!(blocked_sessions(&sessions, 10), vec![20, 30]);
+    }
+
+    #[test]
+    fn deadlocked_chains_stop_at_the_loop() {
+        let sessions = vec![session(1, Some(2)), session(2, Some(1))];
+        assert_eq!(blocking_chain(&sessions, 1), vec![1, 2, 1]);
+        assert_eq!(blocked_sessions(&sessions, 1), vec![2]);
+    }
+
+    #[test]
+    fn filters_combine_status_kind_and_text() {
+        let mut idle = session(7, None);
+        idle.status = "INACTIVE".to_string();
+        idle.machine = "build-01".to_string();
+        let mut background = session(8, None);
+        background.session_type = "BACKGROUND".to_string();
+        let roles = HashMap::new();
+
+        let filter = SessionFilter {
+            text: "BUILD".to_string(),
+            ..SessionFilter::default()
+        };
+        assert!(filter.matches(&idle, &roles));
+        assert!(!filter.matches(&background, &roles));
+
+        let filter = SessionFilter {
+            active_only: true,
+            hide_background: true,
+            ..SessionFilter::default()
+        };
+        assert!(!filter.matches(&idle, &roles));
+        assert!(!filter.matches(&background, &roles));
+        assert!(SessionFilter {
+            text: "8".to_string(),
+            ..SessionFilter::default()
+        }
+        .matches(&background, &roles));
+    }
+
+    #[test]
+    fn elapsed_time_is_hours_minutes_seconds() {
+        assert_eq!(format_elapsed(0), "0:00:00");
+        assert_eq!(format_elapsed(3_725), "1:02:05");
+        assert_eq!(format_elapsed(-5), "0:00:00");
+    }
+}
@@ -0,0 +1,309 @@
+//! Editing stored program units in place: loading their source from the
+//! dictionary, compiling the edited text and reading back where it failed.
+
+use oracle::{Connection, Error as OracleError};
+
+use super::{source_units, CompilationError, ObjectBrowser, QueryExecutor, SourceUnit};
+
+/// A stored program opened for editing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceObject {
+    /// Owner in dictionary form; `None` for the connected user.
+    pub owner: Option<String>,
+    pub name: String,
+    /// `PACKAGE`, `PROCEDURE`, `FUNCTION`, `TYPE` or `TRIGGER`.
+    pub object_type: String,
+    /// `LAST_DDL_TIME` of the object when it was opened or last compiled.
+    pub last_ddl_time: String,
+}
+
+impl SourceObject {
+    pub fn label(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("{}.{} ({})", owner, self.name, self.object_type),
+            None => format!("{} ({})", self.name, self.object_type),
+        }
+    }
+
+    /// Dictionary types holding the object's source, spec first.
+    fn source_types(&self) -> Vec<String> {
+        match self.object_type.as_str() {
+            "PACKAGE" | "TYPE" => vec![
+                self.object_type.clone(),
+                format!("{} BODY", self.object_type),
+            ],
+            object_type => vec![object_type.to_string()],
+        }
+    }
+
+    /// Whether `other` is the same database object.
+    pub fn same_object(&self, other: &SourceObject) -> bool {
+        self.owner == other.owner
+            && self.name == other.name
+            && self.object_type == other.object_type
+    }
+}
+
+/// The `CREATE OR REPLACE` script of a stored program.
+#[derive(Debug, Clone)]
+pub struct EditableSource {
+    pub object: SourceObject,
+    pub text: String,
+}
+
+/// A compiler message placed in the editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceDiagnostic {
+    /// 1-based editor line.
+    pub line: usize,
+    /// 1-based character column.
+    pub column: usize,
+    pub message: String,
+    pub is_warning: bool,
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '$' | '#')
+}
+
+/// Byte offset of the first whole-word, case-insensitive occurrence of
+/// `name` in `text`, quoted or not.
+fn find_name(text: &str, name: &str) -> Option<usize> {
+    let upper = text.to_uppercase();
+    let target = name.to_uppercase();
+    if upper.len() != text.len() {
+        return None;
+    }
+    let mut from = 0;
+    while let Some(found) = upper[from..].find(&target) {
+        let start = from + found;
+        let end = start + target.len();
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        let starts_word = !before.is_some_and(is_identifier_char);
+        let ends_word = !after.is_some_and(is_identifier_char);
+        if starts_word && ends_word {
+            return Some(if before == Some('"') {
+                start - 1
+            } else {
+                start
+            });
+        }
+        from = end;
+    }
+    None
+}
+
+/// Turn dictionary source lines into a `CREATE OR REPLACE` statement,
+/// qualifying the name with `owner` when it is not already.
+pub fn create_statement(source: &str, owner: Option<&str>, name: &str) -> String {
+    let mut text = source.trim_end().to_string();
+    if let Some(owner) = owner {
+        if let Some(at) = find_name(&text, name) {
+            if !text[..at].trim_end().ends_with('.') {
+                text.insert_str(at, &format!("\"{}\".", owner));
+            }
+        }
+    }
+    format!("CREATE OR REPLACE {}\n/\n", text)
+}
+
+fn query_source(
+    conn: &Connection,
+    owner: Option<&str>,
+    name: &str,
+    source_type: &str,
+) -> Result<String, OracleError> {
+    let sql = "SELECT text FROM all_source \
+               WHERE owner = NVL(:1, SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')) \
+               AND name = :2 AND type = :3 ORDER BY line";
+    let rows = match conn.query(sql, &[&owner, &name, &source_type]) {
+        Ok(rows) => rows,
+        Err(err) => {
+            eprintln!("Database operation failed: {err}");
+            return Err(err);
+        }
+    };
+    let mut text = String::new();
+    for row_result in rows {
+        let row = match row_result {
+            Ok(row) => row,
+            Err(err) => {
+                eprintln!("Database operation failed: {err}");
+                return Err(err);
+            }
+        };
+        let line: Option<String> = row.get(0).unwrap_or(None);
+        text.push_str(&line.unwrap_or_default());
+    }
+    Ok(text)
+}
+
+/// `LAST_DDL_TIME` of the object, the latest of spec and body.
+pub fn object_last_ddl_time(
+    conn: &Connection,
+    object: &SourceObject,
+) -> Result<String, OracleError> {
+    let types = object.source_types();
+    let body_type = types.last().cloned().unwrap_or_default();
+    let sql = "SELECT TO_CHAR(MAX(last_ddl_time), 'YYYY-MM-DD HH24:MI:SS') FROM all_objects \
+               WHERE owner = NVL(:1, SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')) \
+               AND object_name = :2 AND object_type IN (:3, :4)";
+    let row = match conn.query_row(
+        sql,
+        &[&object.owner, &object.name, &object.object_type, &body_type],
+    ) {
+        Ok(row) => row,
+        Err(err) => {
+            eprintln!("Database operation failed: {err}");
+            return Err(err);
+        }
+    };
+    let time: Option<String> = row.get(0).unwrap_or(None);
+    Ok(time.unwrap_or_default())
+}
+
+/// Load the source of a stored program as the script that recreates it.
+/// Packages and types get their spec and body in one script.
+pub fn load_editable_source(
+    conn: &Connection,
+    owner: Option<&str>,
+    name: &str,
+    object_type: &str,
+) -> Result<EditableSource, String> {
+    let mut object = SourceObject {
+        owner: owner.map(|owner| owner.to_string()),
+        name: name.to_string(),
+        object_type: object_type.to_string(),
+        last_ddl_time: String::new(),
+    };
+    let mut text = String::new();
+    for source_type in object.source_types() {
+        let source = query_source(conn, owner, name, &source_type).map_err(|e| e.to_string())?;
+        if source.trim().is_empty() {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&create_statement(&source, owner, name));
+    }
+    if text.is_empty() {
+        return Err(format!("No source found for {}", object.label()));
+    }
+    object.last_ddl_time = object_last_ddl_time(conn, &object).map_err(|e| e.to_string())?;
+    Ok(EditableSource { object, text })
+}
+
+/// Place the compiler messages of `unit` on editor lines. Line 1 of the
+/// stored source starts at the type keyword, so its columns are shifted by
+/// whatever precedes that keyword in the editor.
+pub fn unit_diagnostics(
+    unit: &SourceUnit,
+    errors: &[CompilationError],
+    lines: &[&str],
+) -> Vec<SourceDiagnostic> {
+    let keyword = unit
+        .object_type
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    let first_line_offset = lines
+        .get(unit.first_line.saturating_sub(1))
+        .and_then(|line| {
+            let upper = line.to_uppercase();
+            (upper.len() == line.len())
+                .then(|| upper.find(keyword))
+                .flatten()
+                .map(|at| line[..at].chars().count())
+        })
+        .unwrap_or(0);
+    errors
+        .iter()
+        .map(|error| {
+            let unit_line = usize::try_from(error.line).unwrap_or(0).max(1);
+            let mut column = usize::try_from(error.position).unwrap_or(0).max(1);
+            if unit_line == 1 {
+                column += first_line_offset;
+            }
+            SourceDiagnostic {
+                line: (unit.first_line + unit_line - 1).min(unit.last_line),
+                column,
+                message: error.text.clone(),
+                is_warning: error.attribute.eq_ignore_ascii_case("WARNING"),
+            }
+        })
+        .collect()
+}
+
+/// Run the `CREATE` statements of `text` and collect the compiler messages
+/// of every unit it creates, in editor order.
+pub fn compile_source(conn: &Connection, text: &str) -> Result<Vec<SourceDiagnostic>, String> {
+    for statement in QueryExecutor::split_statements_with_blocks(text) {
+        if let Err(err) = conn.execute(&statement, &[]) {
+            eprintln!("Database operation failed: {err}");
+            return Err(err.to_string());
+        }
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    let mut diagnostics = Vec::new();
+    for unit in source_units(text) {
+        let errors = ObjectBrowser::get_compilation_errors_for_owner(
+            conn,
+            unit.unit.owner.as_deref(),
+            &unit.unit.name,
+            &unit.object_type,
+        )
+        .map_err(|err| err.to_string())?;
+        diagnostics.extend(unit_diagnostics(&unit, &errors, &lines));
+    }
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod stored_source_tests {
+    use super::*;
+
+    #[test]
+    fn create_statement_qualifies_the_name_once() {
+        assert_eq!(
+            create_statement("PACKAGE pkg_a AS\n  x NUMBER;\nEND;\n", Some("HR"), "PKG_A"),
+            "CREATE OR REPLACE PACKAGE \"HR\".pkg_a AS\n  x NUMBER;\nEND;\n/\n"
+        );
+        assert_eq!(
+            create_statement("package \"HR\".\"PKG_A\" as end;", Some("HR"), "PKG_A"),
+            "CREATE OR REPLACE package \"HR\".\"PKG_A\" as end;\n/\n"
+        );
+        assert_eq!(
+            create_statement("TRIGGER trg_a_bi\nBEFORE INSERT ON a", None, "TRG_A_BI"),
+            "CREATE OR REPLACE TRIGGER trg_a_bi\nBEFORE INSERT ON a\n/\n"
+        );
+    }
+
+    #[test]
+    fn first_line_columns_account_for_the_create_prefix() {
+        let text = "CREATE OR REPLACE PROCEDURE p IS\nBEGIN\n  x := 1;\nEND;\n/\n";
+        let units = source_units(text);
+        let lines: Vec<&str> = text.lines().collect();
+        let errors = [
+            CompilationError {
+                line: 1,
+                position: 11,
+                text: "PLS-00103".to_string(),
+                attribute: "ERROR".to_string(),
+            },
+            CompilationError {
+                line: 3,
+                position: 3,
+                text: "PLS-00201".to_string(),
+                attribute: "WARNING".to_string(),
+            },
+        ];
+        let diagnostics = unit_diagnostics(&units[0], &errors, &lines);
+        assert_eq!(diagnostics[0].line, 1);
+        assert_eq!(diagnostics[0].column, 29);
+        assert_eq!(diagnostics[1].line, 3);
+        assert_eq!(diagnostics[1].column, 3);
+        assert!(diagnostics[1].is_warning);
+    }
+}
@@ -0,0 +1,186 @@
+//! `tnsnames.ora` lookup and parsing.
+//!
+//! The file is looked up the way the Oracle client does it: in `$TNS_ADMIN`,
+//! then in `$ORACLE_HOME/network/admin`. Aliases are only read here to fill
+//! the connection dialog; the client resolves them itself when connecting.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `IFILE` nesting deeper than this is ignored.
+const MAX_IFILE_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TnsEntry {
+    pub alias: String,
+    /// The connect descriptor with runs of whitespace collapsed.
+    pub descriptor: String,
+}
+
+/// Directory holding `tnsnames.ora` and `sqlnet.ora`, if one can be found.
+pub fn tns_admin_dir() -> Option<PathBuf> {
+    let from_env = |var: &str, suffix: &[&str]| {
+        let value = std::env::var_os(var).filter(|value| !value.is_empty())?;
+        let mut path = PathBuf::from(value);
+        for part in suffix {
+            path.push(part);
+        }
+        path.is_dir().then_some(path)
+    };
+    from_env("TNS_ADMIN", &[]).or_else(|| from_env("ORACLE_HOME", &["network", "admin"]))
+}
+
+pub fn tnsnames_path() -> Option<PathBuf> {
+    let path = tns_admin_dir()?.join("tnsnames.ora");
+    path.is_file().then_some(path)
+}
+
+/// Aliases defined in the resolved `tnsnames.ora`, sorted and deduplicated.
+/// Returns an empty list when there is no such file.
+pub fn load_aliases() -> Vec<String> {
+    let Some(path) = tnsnames_path() else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    load_entries(&path, 0, &mut entries);
+    let mut aliases: Vec<String> = entries.into_iter().map(|entry| entry.alias).collect();
+    aliases.sort_by_key(|alias| alias.to_uppercase());
+    aliases.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    aliases
+}
+
+fn load_entries(path: &Path, depth: usize, entries: &mut Vec<TnsEntry>) {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) => {
+            eprintln!("Failed to read {}: {err}", path.display());
+            return;
+        }
+    };
+    for entry in parse_tnsnames(&text) {
+        if entry.alias.eq_ignore_ascii_case("IFILE") {
+            if depth < MAX_IFILE_DEPTH {
+                let included = PathBuf::from(entry.descriptor.trim_matches(['"', '\'']));
+                let included = match path.parent() {
+                    Some(parent) if included.is_relative() => parent.join(included),
+                    _ => included,
+                };
+                load_entries(&included, depth + 1, entries);
+            }
+            continue;
+        }
+        entries.push(entry);
+    }
+}
+
+/// Parse `tnsnames.ora` text. An entry may define several comma-separated
+/// aliases for one descriptor; `IFILE = path` lines come back as entries
+/// named `IFILE`.
+pub fn parse_tnsnames(text: &str) -> Vec<TnsEntry> {
+    let text: String = text
+        .lines()
+        .map(|line| match line.find('#') {
+            Some(pos) => &line[..pos],
+            None => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut entries = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    let mut name_start = 0usize;
+
+    while let Some((idx, ch)) = chars.next() {
+        if ch != '=' {
+            continue;
+        }
+        let names = text[name_start..idx].trim().to_string();
+
+        while chars.peek().is_some_and(|(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+        let Some(&(value_start, first)) = chars.peek() else {
+            break;
+        };
+        let value_end = if first == '(' {
+            let mut depth = 0usize;
+            let mut end = text.len();
+            for (pos, c) in chars.by_ref() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => {
+                        depth = depth.saturating_sub(1);
+                        if depth == 0 {
+                            end = pos + 1;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            end
+        } else {
+            let mut end = text.len();
+            for (pos, c) in chars.by_ref() {
+                if c == '\n' {
+                    end = pos;
+                    break;
+                }
+            }
+            end
+        };
+        name_start = value_end;
+
+        let descriptor = text[value_start..value_end]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        for alias in names.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+            entries.push(TnsEntry {
+                alias: alias.to_string(),
+                descriptor: descriptor.clone(),
+            });
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tnsnames_tests {
+    use super::*;
+
+    #[test]
+    fn parses_multiline_entries_and_comments() {
+        let text = "\
+# Development
+DEV =
+  (DESCRIPTION =
+    (ADDRESS = (PROTOCOL = TCP)(HOST = dev-db)(PORT = 1521)) # primary
+    (CONNECT_DATA = (SERVICE_NAME = devpdb))
+  )
+
+PROD.example.com=(DESCRIPTION=(ADDRESS=(PROTOCOL=TCPS)(HOST=prod)(PORT=2484))(CONNECT_DATA=(SID=PRD)))
+";
+        let entries = parse_tnsnames(text);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].alias, "DEV");
+        assert_eq!(
+            entries[0].descriptor,
+            "(DESCRIPTION = (ADDRESS = (PROTOCOL = TCP)(HOST = dev-db)(PORT = 1521)) \
+             (CONNECT_DATA = (SERVICE_NAME = devpdb)) )"
+        );
+        assert_eq!(entries[1].alias, "PROD.example.com");
+        assert!(entries[1].descriptor.contains("(SID=PRD)"));
+    }
+
+    #[test]
+    fn splits_alias_lists_and_keeps_ifile_lines() {
+        let text = "IFILE = /etc/oracle/extra.ora\nA, B = (DESCRIPTION=(ADDRESS=(HOST=h)))\n";
+        let entries = parse_tnsnames(text);
+        let aliases: Vec<&str> = entries.iter().map(|e| e.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["IFILE", "A", "B"]);
+        assert_eq!(entries[0].descriptor, "/etc/oracle/extra.ora");
+        assert_eq!(entries[1].descriptor, entries[2].descriptor);
+    }
+}
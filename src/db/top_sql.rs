@@ -0,0 +1,237 @@
+//! Top statements of the shared pool from `V$SQLSTATS`, and the queries
+//! that show a statement's text, actual plan and SQL Monitor report.
+
+use oracle::{Connection, Error as OracleError};
+
+/// What the top statements are ranked by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopSqlMetric {
+    ElapsedTime,
+    CpuTime,
+    BufferGets,
+    Executions,
+}
+
+impl TopSqlMetric {
+    pub const ALL: [TopSqlMetric; 4] = [
+        TopSqlMetric::ElapsedTime,
+        TopSqlMetric::CpuTime,
+        TopSqlMetric::BufferGets,
+        TopSqlMetric::Executions,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TopSqlMetric::ElapsedTime => "Elapsed time",
+            TopSqlMetric::CpuTime => "CPU time",
+            TopSqlMetric::BufferGets => "Buffer gets",
+            TopSqlMetric::Executions => "Executions",
+        }
+    }
+
+    fn column(self) -> &'static str {
+        match self {
+            TopSqlMetric::ElapsedTime => "st.elapsed_time",
+            TopSqlMetric::CpuTime => "st.cpu_time",
+            TopSqlMetric::BufferGets => "st.buffer_gets",
+            TopSqlMetric::Executions => "st.executions",
+        }
+    }
+}
+
+/// One statement of the top list.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TopSqlEntry {
+    pub sql_id: String,
+    pub parsing_schema: String,
+    pub executions: i64,
+    pub elapsed_seconds: f64,
+    pub cpu_seconds: f64,
+    pub buffer_gets: i64,
+    pub disk_reads: i64,
+    pub rows_processed: i64,
+    pub sql_text: String,
+}
+
+impl TopSqlEntry {
+    /// Average elapsed milliseconds per execution.
+    pub fn elapsed_per_execution_ms(&self) -> f64 {
+        if self.executions > 0 {
+            self.elapsed_seconds * 1000.0 / self.executions as f64
+        } else {
+            self.elapsed_seconds * 1000.0
+        }
+    }
+}
+
+fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// SQL ids are short base-32 strings; anything else is rejected before it
+/// is put into a query.
+pub fn valid_sql_id(sql_id: &str) -> bool {
+    !sql_id.is_empty() && sql_id.len() <= 13 && sql_id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// The top `limit` statements by `metric`, optionally only those parsed
+/// by `schema`. Values are literal so the query can also run in a tab.
+pub fn top_sql_query(metric: TopSqlMetric, schema: Option<&str>, limit: usize) -> String {
+    let schema_filter = match schema {
+        Some(schema) => format!(" WHERE q.parsing_schema_name = {}", string_literal(schema)),
+        None => String::new(),
+    };
+    format!(
+        "SELECT * FROM (SELECT st.sql_id, q.parsing_schema_name, st.executions, \
+         ROUND(st.elapsed_time / 1e6, 3) AS elapsed_s, ROUND(st.cpu_time / 1e6, 3) AS cpu_s, \
+         st.buffer_gets, st.disk_reads, st.rows_processed, \
+         ROUND(st.elapsed_time / GREATEST(st.executions, 1) / 1e3, 3) AS elapsed_per_exec_ms, \
+         SUBSTR(st.sql_text, 1, 200) AS sql_text \
+         FROM v$sqlstats st \
+         JOIN (SELECT sql_id, MAX(parsing_schema_name) AS parsing_schema_name \
+         FROM v$sql GROUP BY sql_id) q ON q.sql_id = st.sql_id{} \
+         ORDER BY {} DESC) WHERE ROWNUM <= {}",
+        schema_filter,
+        metric.column(),
+        limit.max(1)
+    )
+}
+
+/// Full text of a statement from the shared pool.
+pub fn sql_text_query(sql_id: &str) -> String {
+    format!(
+        "SELECT sql_id, sql_fulltext FROM v$sqlstats WHERE sql_id = {}",
+        string_literal(sql_id)
+    )
+}
+
+/// Plans of all cursors of a statement, with the row source statistics of
+/// the last execution where they were collected.
+pub fn cursor_plan_query(sql_id: &str) -> String {
+    format!(
+        "SELECT plan_table_output FROM TABLE(DBMS_XPLAN.DISPLAY_CURSOR({}, NULL, 'ALLSTATS LAST'))",
+        string_literal(sql_id)
+    )
+}
+
+/// The text SQL Monitor report of the statement's last monitored run.
+/// Needs the Tuning Pack.
+pub fn sql_monitor_query(sql_id: &str) -> String {
+    format!(
+        "SELECT DBMS_SQLTUNE.REPORT_SQL_MONITOR(sql_id => {}, type => 'TEXT') AS report FROM dual",
+        string_literal(sql_id)
+    )
+}
+
+fn database_error(err: OracleError) -> String {
+    eprintln!("Database operation failed: {err}");
+    err.to_string()
+}
+
+pub fn load_top_sql(
+    conn: &Connection,
+    metric: TopSqlMetric,
+    schema: Option<&str>,
+    limit: usize,
+) -> Result<Vec<TopSqlEntry>, String> {
+    let rows = conn
+        .query(&top_sql_query(metric, schema, limit), &[])
+        .map_err(database_error)?;
+    let mut entries = Vec::new();
+    for row_result in rows {
+        let row = row_result.map_err(database_error)?;
+        entries.push(TopSqlEntry {
+            sql_id: row
+                .get::<_, Option<String>>(0)
+                .unwrap_or(None)
+                .unwrap_or_default(),
+            parsing_schema: row
+                .get::<_, Option<String>>(1)
+                .unwrap_or(None)
+                .unwrap_or_default(),
+            executions: row.get(2).unwrap_or(0),
+            elapsed_seconds: row.get(3).unwrap_or(0.0),
+            cpu_seconds: row.get(4).unwrap_or(0.0),
+            buffer_gets: row.get(5).unwrap_or(0),
+            disk_reads: row.get(6).unwrap_or(0),
+            rows_processed: row.get(7).unwrap_or(0),
+            sql_text: row
+                .get::<_, Option<String>>(9)
+                .unwrap_or(None)
+                .unwrap_or_default(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Schemas that parsed statements still in the shared pool.
+pub fn parsing_schemas(conn: &Connection) -> Result<Vec<String>, String> {
+    let rows = conn
+        .query(
+            "SELECT DISTINCT parsing_schema_name FROM v$sql \
+             WHERE parsing_schema_name IS NOT NULL ORDER BY 1",
+            &[],
+        )
+        .map_err(database_error)?;
+    let mut schemas = Vec::new();
+    for row_result in rows {
+        let row = row_result.map_err(database_error)?;
+        if let Ok(schema) = row.get::<_, String>(0) {
+            schemas.push(schema);
+        }
+    }
+    Ok(schemas)
+}
+
+/// Whether `CONTROL_MANAGEMENT_PACK_ACCESS` allows the Tuning Pack, which
+/// SQL Monitor reports need.
+pub fn tuning_pack_enabled(conn: &Connection) -> Result<bool, String> {
+    let row = conn
+        .query_row(
+            "SELECT value FROM v$parameter WHERE name = 'control_management_pack_access'",
+            &[],
+        )
+        .map_err(database_error)?;
+    let value: Option<String> = row.get(0).unwrap_or(None);
+    Ok(value.is_some_and(|value| value.to_uppercase().contains("TUNING")))
+}
+
+#[cfg(test)]
+mod top_sql_tests {
+    use super::*;
+
+    #[test]
+    fn top_query_orders_by_metric_and_filters_schema() {
+        let sql = top_sql_query(TopSqlMetric::BufferGets, Some("O'BRIEN"), 25);
+        assert!(sql.contains("ORDER BY st.buffer_gets DESC"));
+        assert!(sql.contains("WHERE q.parsing_schema_name = 'O''BRIEN'"));
+        assert!(sql.ends_with("WHERE ROWNUM <= 25"));
+
+        let sql = top_sql_query(TopSqlMetric::Executions, None, 0);
+        assert!(!sql.contains("parsing_schema_name = "));
+        assert!(sql.ends_with("WHERE ROWNUM <= 1"));
+    }
+
+    #[test]
+    fn sql_ids_are_checked_before_use() {
+        assert!(valid_sql_id("7ztv2z24kw0s0"));
+        assert!(!valid_sql_id(""));
+        assert!(!valid_sql_id("abc' OR 1=1"));
+        assert!(cursor_plan_query("7ztv2z24kw0s0").contains("DISPLAY_CURSOR('7ztv2z24kw0s0', NULL"));
+    }
+
+    #[test]
+    fn per_execution_time_handles_unexecuted_cursors() {
+        let entry = TopSqlEntry {
+            executions: 4,
+            elapsed_seconds: 2.0,
+            ..TopSqlEntry::default()
+        };
+        assert_eq!(entry.elapsed_per_execution_ms(), 500.0);
+        let parsed_only = TopSqlEntry {
+            elapsed_seconds: 0.5,
+            ..TopSqlEntry::default()
+        };
+        assert_eq!(parsed_only.elapsed_per_execution_ms(), 500.0);
+    }
+}
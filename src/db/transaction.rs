@@ -0,0 +1,269 @@
+//! Client-side view of the session's open transaction: which statements
+//! start or end one, the savepoints taken since, and the `V$TRANSACTION`
+//! query that confirms it.
+
+use oracle::{Connection, Error as OracleError};
+
+use crate::db::query::has_for_update;
+
+/// What running a statement does to the session's transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionEffect {
+    /// Queries, session settings and tool commands leave it alone.
+    Unchanged,
+    /// DML and row locks start a transaction or add to the open one.
+    Begins,
+    /// COMMIT, ROLLBACK and DDL, which commits implicitly.
+    Ends,
+    Savepoint(String),
+    RollbackToSavepoint(String),
+    /// PL/SQL may or may not have changed data; only the database knows.
+    Unknown,
+}
+
+/// Statements that commit implicitly, apart from `ALTER SESSION` and
+/// `ALTER SYSTEM`.
+pub(crate) const DDL_KEYWORDS: &[&str] = &[
+    "CREATE",
+    "ALTER",
+    "DROP",
+    "TRUNCATE",
+    "RENAME",
+    "GRANT",
+    "REVOKE",
+    "COMMENT",
+    "ANALYZE",
+    "AUDIT",
+    "NOAUDIT",
+    "FLASHBACK",
+    "PURGE",
+    "ASSOCIATE",
+    "DISASSOCIATE",
+];
+
+/// Savepoint names are case-insensitive unless quoted.
+fn savepoint_name(token: &str) -> String {
+    let token = token.trim_end_matches(';');
+    if token.starts_with('"') {
+        token.trim_matches('"').to_string()
+    } else {
+        token.to_uppercase()
+    }
+}
+
+/// Classify `sql`, which must not start with comments.
+pub fn transaction_effect(sql: &str) -> TransactionEffect {
+    let upper = sql.trim_start().to_uppercase();
+    let words: Vec<&str> = upper.split_whitespace().collect();
+    let original: Vec<&str> = sql.split_whitespace().collect();
+    let Some(first) = words.first() else {
+        return TransactionEffect::Unchanged;
+    };
+    let first = first.trim_end_matches(';');
+    match first {
+        "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "LOCK" => TransactionEffect::Begins,
+        "SELECT" | "WITH" if has_for_update(sql) => TransactionEffect::Begins,
+        "COMMIT" => TransactionEffect::Ends,
+        "ROLLBACK" => {
+            // ROLLBACK [WORK] TO [SAVEPOINT] name
+            let mut index = 1;
+            if words.get(index) == Some(&"WORK") {
+                index += 1;
+            }
+            if words.get(index) != Some(&"TO") {
+                return TransactionEffect::Ends;
+            }
+            index += 1;
+            if words.get(index) == Some(&"SAVEPOINT") {
+                index += 1;
+            }
+            match original.get(index) {
+                Some(name) => TransactionEffect::RollbackToSavepoint(savepoint_name(name)),
+                None => TransactionEffect::Ends,
+            }
+        }
+        "SAVEPOINT" => match original.get(1) {
+            Some(name) => TransactionEffect::Savepoint(savepoint_name(name)),
+            None => TransactionEffect::Unchanged,
+        },
+        "ALTER" if matches!(words.get(1), Some(&"SESSION") | Some(&"SYSTEM")) => {
+            TransactionEffect::Unchanged
+        }
+        "BEGIN" | "DECLARE" | "CALL" | "EXEC" | "EXECUTE" => TransactionEffect::Unknown,
+        keyword if DDL_KEYWORDS.contains(&keyword) => TransactionEffect::Ends,
+        _ => TransactionEffect::Unchanged,
+    }
+}
+
+/// The open transaction as far as this client knows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionState {
+    pub pending: bool,
+    /// Savepoints of the open transaction, oldest first.
+    pub savepoints: Vec<String>,
+}
+
+impl TransactionState {
+    /// Apply what a successful statement did. Returns `true` when the
+    /// database has to be asked whether a transaction is open.
+    pub fn apply(&mut self, effect: &TransactionEffect) -> bool {
+        match effect {
+            TransactionEffect::Unchanged => false,
+            TransactionEffect::Begins => {
+                self.pending = true;
+                false
+            }
+            TransactionEffect::Ends => {
+                self.clear();
+                false
+            }
+            TransactionEffect::Savepoint(name) => {
+                // Reusing a name moves the savepoint.
+                self.savepoints.retain(|existing| existing != name);
+                self.savepoints.push(name.clone());
+                false
+            }
+            TransactionEffect::RollbackToSavepoint(name) => {
+                if let Some(position) = self.savepoints.iter().position(|sp| sp == name) {
+                    self.savepoints.truncate(position + 1);
+                }
+                false
+            }
+            TransactionEffect::Unknown => true,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pending = false;
+        self.savepoints.clear();
+    }
+
+    /// Status bar text, empty when nothing is pending.
+    pub fn summary(&self) -> String {
+        match (self.pending, self.savepoints.len()) {
+            (false, _) => String::new(),
+            (true, 0) => "Transaction pending".to_string(),
+            (true, 1) => "Transaction pending (1 savepoint)".to_string(),
+            (true, count) => format!("Transaction pending ({} savepoints)", count),
+        }
+    }
+}
+
+/// Normalize a savepoint name typed by the user, or reject it.
+pub fn savepoint_identifier(name: &str) -> Option<String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '#'));
+    valid.then(|| name.to_uppercase())
+}
+
+/// Whether the session has an open transaction. Uses `V$TRANSACTION`, and
+/// `DBMS_TRANSACTION` for users who cannot read it.
+pub fn transaction_open(conn: &Connection) -> Result<bool, OracleError> {
+    let confirmed = conn.query_row_as::<i64>(
+        "SELECT COUNT(*) FROM v$transaction t \
+         JOIN v$session s ON s.taddr = t.addr \
+         WHERE s.sid = SYS_CONTEXT('USERENV', 'SID')",
+        &[],
+    );
+    match confirmed {
+        Ok(count) => Ok(count > 0),
+        Err(_) => {
+            let id = conn
+                .query_row_as::<Option<String>>(
+                    "SELECT DBMS_TRANSACTION.LOCAL_TRANSACTION_ID FROM dual",
+                    &[],
+                )
+                .map_err(|err| {
+                    eprintln!("Database operation failed: {err}");
+                    err
+                })?;
+            Ok(id.is_some())
+        }
+    }
+}
+
+#[cfg(test)]
+mod transaction_tests {
+    use super::*;
+
+    #[test]
+    fn classifies_statements_by_their_effect() {
+        assert_eq!(
+            transaction_effect("update emp set sal = 0"),
+            TransactionEffect::Begins
+        );
+        assert_eq!(
+            transaction_effect("SELECT * FROM emp FOR UPDATE NOWAIT"),
+            TransactionEffect::Begins
+        );
+        assert_eq!(
+            transaction_effect("SELECT * FROM emp"),
+            TransactionEffect::Unchanged
+        );
+        assert_eq!(
+            transaction_effect("SELECT *\nFROM emp\nFOR UPDATE"),
+            TransactionEffect::Begins
+        );
+        assert_eq!(
+            transaction_effect("SELECT ' for update' FROM dual"),
+            TransactionEffect::Unchanged
+        );
+        assert_eq!(transaction_effect("commit work"), TransactionEffect::Ends);
+        assert_eq!(transaction_effect("ROLLBACK"), TransactionEffect::Ends);
+        assert_eq!(
+            transaction_effect("create table t (id number)"),
+            TransactionEffect::Ends
+        );
+        assert_eq!(
+            transaction_effect("ALTER SESSION SET NLS_DATE_FORMAT = 'yyyy'"),
+            TransactionEffect::Unchanged
+        );
+        assert_eq!(
+            transaction_effect("savepoint before_load;"),
+            TransactionEffect::Savepoint("BEFORE_LOAD".to_string())
+        );
+        assert_eq!(
+            transaction_effect("rollback work to savepoint \"Mixed\""),
+            TransactionEffect::RollbackToSavepoint("Mixed".to_string())
+        );
+        assert_eq!(
+            transaction_effect("BEGIN pkg.run; END;"),
+            TransactionEffect::Unknown
+        );
+    }
+
+    #[test]
+    fn tracks_savepoints_until_the_transaction_ends() {
+        let mut state = TransactionState::default();
+        assert!(!state.apply(&TransactionEffect::Begins));
+        for name in ["A", "B", "C"] {
+            state.apply(&TransactionEffect::Savepoint(name.to_string()));
+        }
+        state.apply(&TransactionEffect::Savepoint("A".to_string()));
+        assert_eq!(state.savepoints, vec!["B", "C", "A"]);
+
+        state.apply(&TransactionEffect::RollbackToSavepoint("C".to_string()));
+        assert_eq!(state.savepoints, vec!["B", "C"]);
+        assert_eq!(state.summary(), "Transaction pending (2 savepoints)");
+        assert!(state.apply(&TransactionEffect::Unknown));
+
+        state.apply(&TransactionEffect::Ends);
+        assert_eq!(state, TransactionState::default());
+        assert_eq!(state.summary(), "");
+    }
+
+    #[test]
+    fn savepoint_names_are_checked() {
+        assert_eq!(
+            savepoint_identifier(" before_load "),
+            Some("BEFORE_LOAD".to_string())
+        );
+        assert_eq!(savepoint_identifier("1st"), None);
+        assert_eq!(savepoint_identifier("x; DROP TABLE t"), None);
+    }
+}
@@ -4,6 +4,7 @@
 )]
 
 mod app;
+mod cli;
 mod db;
 mod ui;
 mod utils;
@@ -11,6 +12,11 @@ mod utils;
 use app::App;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if cli::is_cli_invocation(&args) {
+        std::process::exit(cli::run(&args));
+    }
+
     let app = App::new();
     app.run();
 }
@@ -1,11 +1,13 @@
 use fltk::{
     app,
     browser::HoldBrowser,
-    button::Button,
+    button::{Button, CheckButton},
     enums::FrameType,
     frame::Frame,
     group::Flex,
     input::{Input, SecretInput},
+    menu::Choice,
+    misc::InputChoice,
     prelude::*,
     window::Window,
 };
@@ -14,18 +16,221 @@ use std::rc::Rc;
 use std::sync::mpsc;
 use std::thread;
 
-use crate::db::{ConnectionInfo, DatabaseConnection};
-use crate::ui::center_on_main;
+use crate::db::{
+    parse_hex_color, tnsnames, ConnectPrivilege, ConnectionEnvironment, ConnectionInfo,
+    ConnectionType, DatabaseConnection, ProductionGuards, SessionSettings,
+};
 use crate::ui::constants::*;
 use crate::ui::theme;
+use crate::ui::{center_on_main, edit_profile_session_settings, show_connection_import_dialog};
 use crate::utils::AppConfig;
 
 pub struct ConnectionDialog;
 
+/// The editable connection fields, shared by the dialog callbacks.
+#[derive(Clone)]
+struct ConnectionForm {
+    name: Input,
+    user: Input,
+    pass: SecretInput,
+    type_choice: Choice,
+    host: Input,
+    port: Input,
+    service_label: Frame,
+    service: Input,
+    tns_alias: InputChoice,
+    easy_connect: Input,
+    privilege: Choice,
+    use_wallet: CheckButton,
+    environment: Choice,
+    environment_color: Input,
+    read_only: CheckButton,
+    confirm_unfiltered_dml: CheckButton,
+    confirm_drop_truncate: CheckButton,
+    session: Rc<RefCell<SessionSettings>>,
+    session_summary: Frame,
+}
+
+impl ConnectionForm {
+    fn connection_type(&self) -> ConnectionType {
+        let index = self.type_choice.value().max(0) as usize;
+        ConnectionType::ALL.get(index).copied().unwrap_or_default()
+    }
+
+    fn environment(&self) -> ConnectionEnvironment {
+        let index = self.environment.value().max(0) as usize;
+        ConnectionEnvironment::ALL
+            .get(index)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn to_info(&self) -> ConnectionInfo {
+        let port: u16 = self.port.value().parse().unwrap_or(1521);
+        let mut info = ConnectionInfo::new(
+            &self.name.value(),
+            &self.user.value(),
+            &self.pass.value(),
+            &self.host.value(),
+            port,
+            &self.service.value(),
+        );
+        info.connection_type = self.connection_type();
+        info.tns_alias = self.tns_alias.value().unwrap_or_default();
+        info.easy_connect = self.easy_connect.value();
+        let privilege_index = self.privilege.value().max(0) as usize;
+        info.privilege = ConnectPrivilege::ALL
+            .get(privilege_index)
+            .copied()
+            .unwrap_or_default();
+        info.use_wallet = self.use_wallet.is_checked();
+        info.environment = self.environment();
+        let color = self.environment_color.value();
+        if parse_hex_color(&color).is_some() {
+            info.environment_color = color.trim().to_lowercase();
+        }
+        info.guards = ProductionGuards {
+            read_only: self.read_only.is_checked(),
+            confirm_unfiltered_dml: self.confirm_unfiltered_dml.is_checked(),
+            confirm_drop_truncate: self.confirm_drop_truncate.is_checked(),
+        };
+        info.session = self.session.borrow().clone();
+        info
+    }
+
+    fn load(&mut self, info: &ConnectionInfo, password: &str) {
+        self.name.set_value(&info.name);
+        self.user.set_value(&info.username);
+        self.pass.set_value(password);
+        let type_index = ConnectionType::ALL
+            .iter()
+            .position(|ty| *ty == info.connection_type)
+            .unwrap_or(0);
+        self.type_choice.set_value(type_index as i32);
+        self.host.set_value(&info.host);
+        self.port.set_value(&info.port.to_string());
+        self.service.set_value(&info.service_name);
+        self.tns_alias.set_value(&info.tns_alias);
+        self.easy_connect.set_value(&info.easy_connect);
+        let privilege_index = ConnectPrivilege::ALL
+            .iter()
+            .position(|privilege| *privilege == info.privilege)
+            .unwrap_or(0);
+        self.privilege.set_value(privilege_index as i32);
+        self.use_wallet.set_checked(info.use_wallet);
+        let environment_index = ConnectionEnvironment::ALL
+            .iter()
+            .position(|environment| *environment == info.environment)
+            .unwrap_or(0);
+        self.environment.set_value(environment_index as i32);
+        self.environment_color.set_value(&info.environment_color);
+        self.read_only.set_checked(info.guards.read_only);
+        self.confirm_unfiltered_dml
+            .set_checked(info.guards.confirm_unfiltered_dml);
+        self.confirm_drop_truncate
+            .set_checked(info.guards.confirm_drop_truncate);
+        *self.session.borrow_mut() = info.session.clone();
+        self.refresh_session_summary();
+        self.update_enabled();
+    }
+
+    fn refresh_session_summary(&mut self) {
+        let label = match self.session.borrow().configured_count() {
+            0 => "Global defaults".to_string(),
+            1 => "1 override".to_string(),
+            count => format!("{} overrides", count),
+        };
+        self.session_summary.set_label(&label);
+    }
+
+    /// Enable only the inputs the selected connection type and login use.
+    fn update_enabled(&mut self) {
+        fn set_active<W: WidgetExt>(widget: &mut W, active: bool) {
+            if active {
+                widget.activate();
+            } else {
+                widget.deactivate();
+            }
+        }
+
+        let connection_type = self.connection_type();
+        let uses_host = matches!(
+            connection_type,
+            ConnectionType::ServiceName | ConnectionType::Sid
+        );
+        set_active(&mut self.host, uses_host);
+        set_active(&mut self.port, uses_host);
+        set_active(&mut self.service, uses_host);
+        self.service_label
+            .set_label(if connection_type == ConnectionType::Sid {
+                "SID:"
+            } else {
+                "Service:"
+            });
+        set_active(
+            &mut self.tns_alias,
+            connection_type == ConnectionType::TnsAlias,
+        );
+        set_active(
+            &mut self.easy_connect,
+            connection_type == ConnectionType::EasyConnect,
+        );
+        let password_login = !self.use_wallet.is_checked();
+        set_active(&mut self.user, password_login);
+        set_active(&mut self.pass, password_login);
+        let environment = self.environment();
+        set_active(
+            &mut self.environment_color,
+            environment != ConnectionEnvironment::Untagged,
+        );
+        let production = environment == ConnectionEnvironment::Production;
+        set_active(&mut self.read_only, production);
+        set_active(&mut self.confirm_unfiltered_dml, production);
+        set_active(&mut self.confirm_drop_truncate, production);
+    }
+}
+
+fn form_row(label: &str, right_col: &mut Flex) -> Flex {
+    let mut row = Flex::default();
+    row.set_type(fltk::group::FlexType::Row);
+    row.set_spacing(DIALOG_SPACING);
+    let mut row_label = Frame::default().with_label(label);
+    row_label.set_label_color(theme::text_primary());
+    row.fixed(&row_label, FORM_LABEL_WIDTH);
+    right_col.fixed(&row, INPUT_ROW_HEIGHT);
+    row
+}
+
+fn styled_input() -> Input {
+    let mut input = Input::default();
+    input.set_color(theme::input_bg());
+    input.set_text_color(theme::text_primary());
+    input
+}
+
+fn styled_check(label: &str, tooltip: &str) -> CheckButton {
+    let mut check = CheckButton::default().with_label(label);
+    check.set_label_color(theme::text_secondary());
+    check.set_tooltip(tooltip);
+    check
+}
+
+fn styled_choice(labels: &[&str]) -> Choice {
+    let mut choice = Choice::default();
+    choice.set_color(theme::input_bg());
+    choice.set_text_color(theme::text_primary());
+    for label in labels {
+        choice.add_choice(label);
+    }
+    choice.set_value(0);
+    choice
+}
+
 impl ConnectionDialog {
     pub fn show_with_registry(popups: Rc<RefCell<Vec<Window>>>) -> Option<ConnectionInfo> {
         enum DialogMessage {
             DeleteSelected,
+            Import,
             Test(ConnectionInfo),
             TestResult(Result<(), String>),
             Save(ConnectionInfo),
@@ -42,7 +247,7 @@ impl ConnectionDialog {
         fltk::group::Group::set_current(None::<&fltk::group::Group>);
 
         let dialog_w = 620;
-        let dialog_h = 400;
+        let dialog_h = 680;
         let mut dialog = Window::default()
             .with_size(dialog_w, dialog_h)
             .with_label("Connect to Oracle Database");
@@ -84,6 +289,13 @@ impl ConnectionDialog {
         delete_btn.set_frame(FrameType::RFlatBox);
         left_col.fixed(&delete_btn, BUTTON_HEIGHT);
 
+        let mut import_btn = Button::default().with_label("Import...");
+        import_btn.set_color(theme::button_subtle());
+        import_btn.set_label_color(theme::text_primary());
+        import_btn.set_frame(FrameType::RFlatBox);
+        import_btn.set_tooltip("Import connections from SQL Developer, DBeaver or Toad");
+        left_col.fixed(&import_btn, BUTTON_HEIGHT);
+
         left_col.end();
         root.fixed(&left_col, left_w);
 
@@ -138,41 +350,143 @@ impl ConnectionDialog {
         server_header.set_label_color(theme::text_secondary());
         right_col.fixed(&server_header, LABEL_ROW_HEIGHT);
 
+        // Connection type
+        let type_flex = form_row("Type:", &mut right_col);
+        let type_labels: Vec<&str> = ConnectionType::ALL.iter().map(|ty| ty.label()).collect();
+        let type_choice = styled_choice(&type_labels);
+        type_flex.end();
+
         // Host
-        let mut host_flex = Flex::default();
-        host_flex.set_type(fltk::group::FlexType::Row);
-        let mut host_label = Frame::default().with_label("Host:");
-        host_label.set_label_color(theme::text_primary());
-        host_flex.fixed(&host_label, FORM_LABEL_WIDTH);
-        let mut host_input = Input::default();
+        let host_flex = form_row("Host:", &mut right_col);
+        let mut host_input = styled_input();
         host_input.set_value("localhost");
-        host_input.set_color(theme::input_bg());
-        host_input.set_text_color(theme::text_primary());
         host_flex.end();
-        right_col.fixed(&host_flex, INPUT_ROW_HEIGHT);
 
-        // Port + Service on same row
+        // Port + Service/SID on same row
         let mut port_svc_flex = Flex::default();
         port_svc_flex.set_type(fltk::group::FlexType::Row);
         port_svc_flex.set_spacing(DIALOG_SPACING);
         let mut port_label = Frame::default().with_label("Port:");
         port_label.set_label_color(theme::text_primary());
         port_svc_flex.fixed(&port_label, 40);
-        let mut port_input = Input::default();
+        let mut port_input = styled_input();
         port_input.set_value("1521");
-        port_input.set_color(theme::input_bg());
-        port_input.set_text_color(theme::text_primary());
         port_svc_flex.fixed(&port_input, 60);
         let mut svc_label = Frame::default().with_label("Service:");
         svc_label.set_label_color(theme::text_primary());
         port_svc_flex.fixed(&svc_label, 60);
-        let mut service_input = Input::default();
+        let mut service_input = styled_input();
         service_input.set_value("ORCL");
-        service_input.set_color(theme::input_bg());
-        service_input.set_text_color(theme::text_primary());
         port_svc_flex.end();
         right_col.fixed(&port_svc_flex, INPUT_ROW_HEIGHT);
 
+        // TNS alias, offered from tnsnames.ora
+        let tns_flex = form_row("TNS Alias:", &mut right_col);
+        let mut tns_alias_input = InputChoice::default();
+        tns_alias_input.set_color(theme::input_bg());
+        tns_alias_input.set_text_color(theme::text_primary());
+        for alias in tnsnames::load_aliases() {
+            tns_alias_input.add(&alias.replace('/', "\\/"));
+        }
+        match tnsnames::tnsnames_path() {
+            Some(path) => tns_alias_input.set_tooltip(&format!("Aliases from {}", path.display())),
+            None => {
+                tns_alias_input.set_tooltip("No tnsnames.ora found in TNS_ADMIN or ORACLE_HOME")
+            }
+        }
+        tns_flex.end();
+
+        // Easy Connect (Plus) string
+        let ez_flex = form_row("Easy Connect:", &mut right_col);
+        let mut easy_connect_input = styled_input();
+        easy_connect_input.set_tooltip("e.g. tcps://host:2484/service?wallet_location=/path");
+        ez_flex.end();
+
+        // Privilege + wallet login
+        let role_flex = form_row("Role:", &mut right_col);
+        let privilege_labels: Vec<&str> = ConnectPrivilege::ALL
+            .iter()
+            .map(|privilege| privilege.label())
+            .collect();
+        let privilege_choice = styled_choice(&privilege_labels);
+        let mut wallet_check = CheckButton::default().with_label("Use wallet");
+        wallet_check.set_label_color(theme::text_secondary());
+        wallet_check.set_tooltip("Log in with credentials from the Oracle wallet (/@alias)");
+        role_flex.end();
+
+        // Environment tag and the guards of production connections
+        let mut environment_header = Frame::default().with_label("Environment");
+        environment_header.set_label_color(theme::text_secondary());
+        right_col.fixed(&environment_header, LABEL_ROW_HEIGHT);
+
+        let mut tag_flex = form_row("Tag:", &mut right_col);
+        let environment_labels: Vec<&str> = ConnectionEnvironment::ALL
+            .iter()
+            .map(|environment| environment.label())
+            .collect();
+        let environment_choice = styled_choice(&environment_labels);
+        let mut color_label = Frame::default().with_label("Color:");
+        color_label.set_label_color(theme::text_primary());
+        tag_flex.fixed(&color_label, 45);
+        let mut color_input = styled_input();
+        color_input.set_tooltip("#rrggbb; leave empty for the tag's default color");
+        tag_flex.fixed(&color_input, 90);
+        tag_flex.end();
+
+        let default_guards = ProductionGuards::default();
+        let guards_flex = form_row("Guards:", &mut right_col);
+        let read_only_check = styled_check(
+            "Read-only",
+            "Refuse DML, DDL and PL/SQL on this production connection",
+        );
+        read_only_check.set_checked(default_guards.read_only);
+        let drop_check = styled_check("Confirm DROP/TRUNCATE", "Ask before DROP or TRUNCATE");
+        drop_check.set_checked(default_guards.confirm_drop_truncate);
+        guards_flex.end();
+
+        let unfiltered_flex = form_row("", &mut right_col);
+        let unfiltered_check = styled_check(
+            "Confirm UPDATE/DELETE without WHERE",
+            "Ask before an UPDATE or DELETE that changes every row",
+        );
+        unfiltered_check.set_checked(default_guards.confirm_unfiltered_dml);
+        unfiltered_flex.end();
+
+        // NLS settings, schema and login script overriding the global ones
+        let mut session_flex = form_row("Session:", &mut right_col);
+        let mut session_btn = Button::default().with_label("Settings...");
+        session_btn.set_color(theme::button_secondary());
+        session_btn.set_label_color(theme::text_primary());
+        session_btn.set_frame(FrameType::RFlatBox);
+        session_btn.set_tooltip("NLS formats, time zone, schema and login script");
+        session_flex.fixed(&session_btn, BUTTON_WIDTH);
+        let mut session_summary = Frame::default();
+        session_summary.set_label_color(theme::text_secondary());
+        session_summary.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+        session_flex.end();
+
+        let form = ConnectionForm {
+            name: name_input.clone(),
+            user: user_input.clone(),
+            pass: pass_input.clone(),
+            type_choice: type_choice.clone(),
+            host: host_input,
+            port: port_input,
+            service_label: svc_label,
+            service: service_input,
+            tns_alias: tns_alias_input,
+            easy_connect: easy_connect_input,
+            privilege: privilege_choice,
+            use_wallet: wallet_check.clone(),
+            environment: environment_choice.clone(),
+            environment_color: color_input,
+            read_only: read_only_check,
+            confirm_unfiltered_dml: unfiltered_check,
+            confirm_drop_truncate: drop_check,
+            session: Rc::new(RefCell::new(SessionSettings::default())),
+            session_summary,
+        };
+
         // Save connection button
         let mut save_flex = Flex::default();
         save_flex.set_type(fltk::group::FlexType::Row);
@@ -231,40 +545,44 @@ impl ConnectionDialog {
 
         popups.borrow_mut().push(dialog.clone());
 
+        // Form field dependencies
+        let mut form_for_type = form.clone();
+        let mut type_choice = type_choice;
+        type_choice.set_callback(move |_| form_for_type.update_enabled());
+        let mut form_for_wallet = form.clone();
+        wallet_check.set_callback(move |_| form_for_wallet.update_enabled());
+        let mut form_for_environment = form.clone();
+        let mut environment_choice = environment_choice;
+        environment_choice.set_callback(move |_| form_for_environment.update_enabled());
+        form.clone().update_enabled();
+        form.clone().refresh_session_summary();
+        let mut form_for_session = form.clone();
+        session_btn.set_callback(move |_| {
+            let current = form_for_session.session.borrow().clone();
+            if let Some(edited) = edit_profile_session_settings(&current) {
+                *form_for_session.session.borrow_mut() = edited;
+                form_for_session.refresh_session_summary();
+            }
+        });
+
         // Saved connection selection callback
         let config_cb = config.clone();
-        let mut name_input_cb = name_input.clone();
-        let mut user_input_cb = user_input.clone();
-        let mut pass_input_cb = pass_input.clone();
-        let mut host_input_cb = host_input.clone();
-        let mut port_input_cb = port_input.clone();
-        let mut service_input_cb = service_input.clone();
+        let mut form_for_click = form.clone();
         let sender_for_click = sender.clone();
 
         saved_browser.set_callback(move |browser| {
             if let Some(selected) = browser.selected_text() {
                 let cfg = config_cb.borrow();
                 if let Some(conn) = cfg.get_connection_by_name(&selected) {
-                    name_input_cb.set_value(&conn.name);
-                    user_input_cb.set_value(&conn.username);
                     // Load password from OS keyring on demand.
                     let password =
                         AppConfig::get_password_for_connection(&conn.name).unwrap_or_default();
-                    pass_input_cb.set_value(&password);
-                    host_input_cb.set_value(&conn.host);
-                    port_input_cb.set_value(&conn.port.to_string());
-                    service_input_cb.set_value(&conn.service_name);
+                    form_for_click.load(conn, &password);
 
                     // Double click to connect immediately
                     if app::event_clicks() {
-                        let info = ConnectionInfo::new(
-                            &conn.name,
-                            &conn.username,
-                            &password,
-                            &conn.host,
-                            conn.port,
-                            &conn.service_name,
-                        );
+                        let mut info = conn.clone();
+                        info.password = password;
                         let _ = sender_for_click.send(DialogMessage::Connect(info, true));
                         app::awake();
                     }
@@ -279,74 +597,33 @@ impl ConnectionDialog {
             app::awake();
         });
 
+        let sender_for_import = sender.clone();
+        import_btn.set_callback(move |_| {
+            let _ = sender_for_import.send(DialogMessage::Import);
+            app::awake();
+        });
+
         // Save button callback
         let sender_for_save = sender.clone();
-        let name_input_save = name_input.clone();
-        let user_input_save = user_input.clone();
-        let pass_input_save = pass_input.clone();
-        let host_input_save = host_input.clone();
-        let port_input_save = port_input.clone();
-        let service_input_save = service_input.clone();
-
+        let form_for_save = form.clone();
         save_btn.set_callback(move |_| {
-            let port: u16 = port_input_save.value().parse().unwrap_or(1521);
-            let info = ConnectionInfo::new(
-                &name_input_save.value(),
-                &user_input_save.value(),
-                &pass_input_save.value(),
-                &host_input_save.value(),
-                port,
-                &service_input_save.value(),
-            );
-
-            let _ = sender_for_save.send(DialogMessage::Save(info));
+            let _ = sender_for_save.send(DialogMessage::Save(form_for_save.to_info()));
             app::awake();
         });
 
         // Test button callback
         let sender_for_test = sender.clone();
-        let name_input_test = name_input.clone();
-        let user_input_test = user_input.clone();
-        let pass_input_test = pass_input.clone();
-        let host_input_test = host_input.clone();
-        let port_input_test = port_input.clone();
-        let service_input_test = service_input.clone();
-
+        let form_for_test = form.clone();
         test_btn.set_callback(move |_| {
-            let port: u16 = port_input_test.value().parse().unwrap_or(1521);
-            let info = ConnectionInfo::new(
-                &name_input_test.value(),
-                &user_input_test.value(),
-                &pass_input_test.value(),
-                &host_input_test.value(),
-                port,
-                &service_input_test.value(),
-            );
-
-            let _ = sender_for_test.send(DialogMessage::Test(info));
+            let _ = sender_for_test.send(DialogMessage::Test(form_for_test.to_info()));
             app::awake();
         });
 
         // Connect button callback
         let sender_for_connect = sender.clone();
-        let name_input_conn = name_input.clone();
-        let user_input_conn = user_input.clone();
-        let pass_input_conn = pass_input.clone();
-        let host_input_conn = host_input.clone();
-        let port_input_conn = port_input.clone();
-        let service_input_conn = service_input.clone();
-
+        let form_for_connect = form.clone();
         connect_btn.set_callback(move |_| {
-            let port: u16 = port_input_conn.value().parse().unwrap_or(1521);
-            let info = ConnectionInfo::new(
-                &name_input_conn.value(),
-                &user_input_conn.value(),
-                &pass_input_conn.value(),
-                &host_input_conn.value(),
-                port,
-                &service_input_conn.value(),
-            );
-
+            let info = form_for_connect.to_info();
             let _ = sender_for_connect.send(DialogMessage::Connect(info, false));
             app::awake();
         });
@@ -393,6 +670,32 @@ impl ConnectionDialog {
                             fltk::dialog::alert_default("Please select a connection to delete");
                         }
                     }
+                    DialogMessage::Import => {
+                        let saved: Vec<ConnectionInfo> = config
+                            .borrow()
+                            .get_all_connections()
+                            .into_iter()
+                            .cloned()
+                            .collect();
+                        let Some((imported, replace_existing)) =
+                            show_connection_import_dialog(&saved)
+                        else {
+                            continue;
+                        };
+                        let mut cfg = config.borrow_mut();
+                        let (added, replaced) = cfg.import_connections(imported, replace_existing);
+                        if let Err(e) = cfg.save() {
+                            fltk::dialog::alert_default(&format!("Failed to save config: {}", e));
+                        }
+                        saved_browser.clear();
+                        for conn in cfg.get_all_connections() {
+                            saved_browser.add(&conn.name);
+                        }
+                        fltk::dialog::message_default(&format!(
+                            "Imported {} new connections, replaced {}",
+                            added, replaced
+                        ));
+                    }
                     DialogMessage::Test(info) => {
                         let sender = sender.clone();
                         thread::spawn(move || {
@@ -0,0 +1,280 @@
+use fltk::{
+    app,
+    browser::CheckBrowser,
+    dialog::{FileDialog, FileDialogType},
+    frame::Frame,
+    group::{Flex, FlexType},
+    menu::Choice,
+    misc::InputChoice,
+    prelude::*,
+    window::Window,
+};
+use std::path::Path;
+use std::sync::mpsc;
+
+use crate::db::{connection_import, ConnectionInfo};
+use crate::ui::constants::*;
+use crate::ui::schema_compare_dialog::{labeled, styled_button};
+use crate::ui::{center_on_main, configured_ui_font_size, theme};
+
+/// One line of the preview list.
+fn preview_line(info: &ConnectionInfo, saved: &[ConnectionInfo]) -> String {
+    let mut line = format!("{}  -  ", info.name);
+    if !info.username.is_empty() {
+        line.push_str(&info.username);
+        line.push('@');
+    }
+    line.push_str(&info.target_string());
+    if !info.password.is_empty() {
+        line.push_str("  [password]");
+    }
+    if saved.iter().any(|existing| existing.has_name(&info.name)) {
+        line.push_str("  (already saved)");
+    }
+    line
+}
+
+fn status_text(import: &connection_import::ConnectionImport) -> String {
+    let mut status = format!(
+        "{}: {} connections",
+        import.source.label(),
+        import.connections.len()
+    );
+    if !import.skipped.is_empty() {
+        status.push_str(&format!(", {} skipped", import.skipped.len()));
+    }
+    status
+}
+
+/// Pick connections to import from a SQL Developer, DBeaver or Toad file.
+/// Returns the chosen connections and whether they replace saved ones of
+/// the same name.
+pub fn show_connection_import_dialog(
+    saved: &[ConnectionInfo],
+) -> Option<(Vec<ConnectionInfo>, bool)> {
+    #[derive(Clone, Copy)]
+    enum DialogMessage {
+        Browse,
+        Load,
+        SelectAll,
+        SelectNone,
+        ShowSkipped,
+        Import,
+        Cancel,
+    }
+
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 640;
+    let height = 480;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("Import Connections");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+    dialog.make_modal(true);
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let mut hint = Frame::default().with_label(
+        "SQL Developer connections.json/.xml, DBeaver data-sources.json or Toad CONNECTIONS.XML",
+    );
+    hint.set_label_color(theme::text_secondary());
+    main_flex.fixed(&hint, LABEL_ROW_HEIGHT);
+
+    let mut file_row = Flex::default();
+    file_row.set_type(FlexType::Row);
+    file_row.set_spacing(DIALOG_SPACING);
+    labeled(&mut file_row, "File:", 40);
+    let mut path_input = InputChoice::default();
+    path_input.set_color(theme::input_bg());
+    path_input.set_text_color(theme::text_primary());
+    let known_paths = connection_import::known_export_paths();
+    for path in &known_paths {
+        path_input.add(&path.to_string_lossy().replace('/', "\\/"));
+    }
+    if let Some(first) = known_paths.first() {
+        path_input.set_value(&first.to_string_lossy());
+    }
+    let mut browse_btn = styled_button("Browse...", theme::button_secondary());
+    file_row.fixed(&browse_btn, BUTTON_WIDTH);
+    let mut load_btn = styled_button("Load", theme::button_primary());
+    file_row.fixed(&load_btn, BUTTON_WIDTH);
+    file_row.end();
+    main_flex.fixed(&file_row, INPUT_ROW_HEIGHT);
+
+    let mut browser = CheckBrowser::default();
+    browser.set_color(theme::input_bg());
+    browser.set_selection_color(theme::selection_strong());
+    browser.set_text_size(configured_ui_font_size());
+
+    let mut select_row = Flex::default();
+    select_row.set_type(FlexType::Row);
+    select_row.set_spacing(DIALOG_SPACING);
+    let mut all_btn = styled_button("Select All", theme::button_subtle());
+    select_row.fixed(&all_btn, BUTTON_WIDTH);
+    let mut none_btn = styled_button("Select None", theme::button_subtle());
+    select_row.fixed(&none_btn, BUTTON_WIDTH);
+    let mut skipped_btn = styled_button("Skipped...", theme::button_subtle());
+    skipped_btn.set_tooltip("Entries that could not be imported, with the reason");
+    select_row.fixed(&skipped_btn, BUTTON_WIDTH);
+    let _select_spacer = Frame::default();
+    labeled(&mut select_row, "Saved names:", 95);
+    let mut duplicate_choice = Choice::default();
+    duplicate_choice.add_choice("Skip|Replace");
+    duplicate_choice.set_value(0);
+    duplicate_choice.set_color(theme::input_bg());
+    duplicate_choice.set_text_color(theme::text_primary());
+    duplicate_choice.set_tooltip("What to do with connections whose name is already saved");
+    select_row.fixed(&duplicate_choice, 90);
+    select_row.end();
+    main_flex.fixed(&select_row, BUTTON_ROW_HEIGHT);
+
+    let mut status = Frame::default();
+    status.set_label_color(theme::text_secondary());
+    status.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    main_flex.fixed(&status, LABEL_ROW_HEIGHT);
+
+    let mut button_row = Flex::default();
+    button_row.set_type(FlexType::Row);
+    button_row.set_spacing(DIALOG_SPACING);
+    let _button_spacer = Frame::default();
+    let mut import_btn = styled_button("Import", theme::button_primary());
+    button_row.fixed(&import_btn, BUTTON_WIDTH);
+    let mut cancel_btn = styled_button("Cancel", theme::button_subtle());
+    button_row.fixed(&cancel_btn, BUTTON_WIDTH);
+    button_row.end();
+    main_flex.fixed(&button_row, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    let (sender, receiver) = mpsc::channel::<DialogMessage>();
+    for (button, message) in [
+        (&mut browse_btn, DialogMessage::Browse),
+        (&mut load_btn, DialogMessage::Load),
+        (&mut all_btn, DialogMessage::SelectAll),
+        (&mut none_btn, DialogMessage::SelectNone),
+        (&mut skipped_btn, DialogMessage::ShowSkipped),
+        (&mut import_btn, DialogMessage::Import),
+        (&mut cancel_btn, DialogMessage::Cancel),
+    ] {
+        let sender = sender.clone();
+        button.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+
+    dialog.show();
+    if !known_paths.is_empty() {
+        let _ = sender.send(DialogMessage::Load);
+    }
+
+    let mut loaded: Option<connection_import::ConnectionImport> = None;
+    let mut result = None;
+    while dialog.shown() {
+        app::wait();
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                DialogMessage::Browse => {
+                    let mut file_dialog = FileDialog::new(FileDialogType::BrowseFile);
+                    file_dialog.set_filter("Connection Files\t*.{json,xml,XML}\nAll Files\t*.*");
+                    file_dialog.show();
+                    let filename = file_dialog.filename();
+                    if !filename.as_os_str().is_empty() {
+                        path_input.set_value(&filename.to_string_lossy());
+                        let _ = sender.send(DialogMessage::Load);
+                    }
+                }
+                DialogMessage::Load => {
+                    let path = path_input.value().unwrap_or_default();
+                    if path.trim().is_empty() {
+                        continue;
+                    }
+                    browser.clear();
+                    match connection_import::load_file(Path::new(path.trim())) {
+                        Ok(import) => {
+                            for info in &import.connections {
+                                browser.add(&preview_line(info, saved), true);
+                            }
+                            status.set_label(&status_text(&import));
+                            loaded = Some(import);
+                        }
+                        Err(err) => {
+                            status.set_label(&err);
+                            loaded = None;
+                        }
+                    }
+                    browser.redraw();
+                }
+                DialogMessage::SelectAll => {
+                    browser.check_all();
+                }
+                DialogMessage::SelectNone => {
+                    browser.check_none();
+                }
+                DialogMessage::ShowSkipped => match &loaded {
+                    Some(import) if !import.skipped.is_empty() => {
+                        fltk::dialog::message_default(&import.skipped.join("\n"))
+                    }
+                    _ => fltk::dialog::message_default("Nothing was skipped"),
+                },
+                DialogMessage::Import => {
+                    let Some(count) = loaded.as_ref().map(|import| import.connections.len()) else {
+                        fltk::dialog::alert_default("Load a connection file first");
+                        continue;
+                    };
+                    let checked: Vec<bool> = (1..=count as i32)
+                        .map(|line| browser.checked(line))
+                        .collect();
+                    if !checked.contains(&true) {
+                        fltk::dialog::alert_default("Select the connections to import");
+                        continue;
+                    }
+                    let chosen: Vec<ConnectionInfo> = loaded
+                        .take()
+                        .map(|import| import.connections)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .zip(checked)
+                        .filter_map(|(info, checked)| checked.then_some(info))
+                        .collect();
+                    result = Some((chosen, duplicate_choice.value() == 1));
+                    dialog.hide();
+                }
+                DialogMessage::Cancel => dialog.hide(),
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod connection_import_dialog_tests {
+    use super::*;
+
+    #[test]
+    fn preview_marks_saved_names_and_readable_passwords() {
+        let saved = vec![ConnectionInfo::new("HR", "hr", "", "db1", 1521, "hrpdb")];
+        let mut info = ConnectionInfo::new("HR", "hr", "secret", "db1", 1521, "hrpdb");
+        assert_eq!(
+            preview_line(&info, &saved),
+            "HR  -  hr@db1:1521/hrpdb  [password]  (already saved)"
+        );
+        info.name = "Billing".to_string();
+        info.password.clear();
+        assert_eq!(preview_line(&info, &saved), "Billing  -  hr@db1:1521/hrpdb");
+    }
+}
@@ -57,6 +57,15 @@ pub const MENU_BAR_HEIGHT: i32 = 30;
 /// Height of the application status bar.
 pub const STATUS_BAR_HEIGHT: i32 = 25;
 
+/// Width of the transaction indicator at the right of the status bar.
+pub const TRANSACTION_INDICATOR_WIDTH: i32 = 240;
+
+/// Width of the environment badge at the left of the status bar.
+pub const ENVIRONMENT_BADGE_WIDTH: i32 = 70;
+
+/// Width of the colored frame around the window of a tagged connection.
+pub const ENVIRONMENT_BORDER_WIDTH: i32 = 3;
+
 /// Height of the filter input in the object browser.
 pub const FILTER_INPUT_HEIGHT: i32 = 28;
 
@@ -0,0 +1,441 @@
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    enums::CallbackTrigger,
+    frame::Frame,
+    group::{Flex, FlexType},
+    input::Input,
+    prelude::*,
+    window::Window,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::db::{
+    compare_table_data, data_sync_script, primary_key_columns, CellValue, DataCompareResult,
+    DataCompareSide, RowDifference, SharedConnection,
+};
+use crate::ui::constants::*;
+use crate::ui::schema_compare_dialog::{endpoint_connection, endpoint_row, labeled, styled_button};
+use crate::ui::{center_on_main, configured_ui_font_size, theme, CompareEndpoint, SyncScript};
+
+enum CompareUpdate {
+    Progress(String),
+    Done(Result<DataCompareResult, String>),
+}
+
+/// Key column names from a comma separated list, in dictionary form.
+fn parse_key_columns(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(|name| name.trim().to_uppercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn compare_side(schema: &str, table: &str) -> DataCompareSide {
+    DataCompareSide {
+        owner: (!schema.is_empty()).then(|| schema.to_string()),
+        table: table.to_string(),
+    }
+}
+
+fn summary(result: &DataCompareResult) -> String {
+    let mut text = format!(
+        "Source {} rows, target {} rows: {} matching, {} missing in target, {} extra in target, {} different",
+        result.source_rows,
+        result.target_rows,
+        result.matching_rows,
+        result.only_in_source,
+        result.only_in_target,
+        result.changed
+    );
+    if result.is_truncated() {
+        text.push_str(&format!(" (first {} listed)", result.differences.len()));
+    }
+    text
+}
+
+fn browser_text(value: &CellValue) -> String {
+    value
+        .display("(null)")
+        .chars()
+        .map(|ch| if ch == '\t' || ch == '\n' { ' ' } else { ch })
+        .collect()
+}
+
+/// Lines of the drill-down grid for one differing row: a line per column
+/// with the source and target values, differing values highlighted.
+fn detail_lines(result: &DataCompareResult, difference: &RowDifference) -> Vec<String> {
+    result
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let (source, target, changed) = match difference {
+                RowDifference::OnlyInSource(row) => {
+                    (browser_text(&row[index]), String::new(), false)
+                }
+                RowDifference::OnlyInTarget(row) => {
+                    (String::new(), browser_text(&row[index]), false)
+                }
+                RowDifference::Changed {
+                    source,
+                    target,
+                    columns,
+                } => (
+                    browser_text(&source[index]),
+                    browser_text(&target[index]),
+                    columns.contains(&index),
+                ),
+            };
+            let prefix = if changed {
+                format!("@C{}@.", theme::button_warning().bits())
+            } else {
+                "@.".to_string()
+            };
+            format!(
+                "{prefix}{}\t{prefix}{source}\t{prefix}{target}",
+                column.name
+            )
+        })
+        .collect()
+}
+
+/// Compare the rows of a table on two connections. Returns the DML that
+/// brings the target in line when the user asks for it.
+pub fn show_data_compare_dialog(
+    endpoints: Vec<CompareEndpoint>,
+    popups: Rc<RefCell<Vec<Window>>>,
+) -> Option<SyncScript> {
+    #[derive(Clone, Copy)]
+    enum DialogMessage {
+        Compare,
+        Select,
+        Script,
+        Close,
+    }
+
+    if endpoints.is_empty() {
+        fltk::dialog::alert_default("Connect at least one query tab to compare data");
+        return None;
+    }
+
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 1100;
+    let height = 680;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("Data Compare");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+    dialog.make_modal(true);
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let (source_choice, source_schema) = endpoint_row(&mut main_flex, "Source:", &endpoints, 0);
+    let target_default = if endpoints.len() > 1 { 1 } else { 0 };
+    let (target_choice, target_schema) =
+        endpoint_row(&mut main_flex, "Target:", &endpoints, target_default);
+
+    let mut table_row = Flex::default();
+    table_row.set_type(FlexType::Row);
+    table_row.set_spacing(DIALOG_SPACING);
+    labeled(&mut table_row, "Table:", 60);
+    let mut table_input = Input::default();
+    table_input.set_color(theme::input_bg());
+    table_input.set_text_color(theme::text_primary());
+    table_input.set_trigger(CallbackTrigger::EnterKeyAlways);
+    labeled(&mut table_row, "Key:", 60);
+    let mut key_input = Input::default();
+    key_input.set_color(theme::input_bg());
+    key_input.set_text_color(theme::text_primary());
+    key_input.set_tooltip("Comma separated key columns; leave empty for the primary key");
+    table_row.fixed(&key_input, 200);
+    let mut compare_btn = styled_button("Compare", theme::button_primary());
+    table_row.fixed(&compare_btn, BUTTON_WIDTH);
+    table_row.end();
+    main_flex.fixed(&table_row, INPUT_ROW_HEIGHT);
+
+    let mut status = Frame::default().with_label("Choose the table to compare");
+    status.set_label_color(theme::text_secondary());
+    status.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    main_flex.fixed(&status, LABEL_ROW_HEIGHT);
+
+    let mut content = Flex::default();
+    content.set_type(FlexType::Row);
+    content.set_spacing(DIALOG_SPACING);
+    let mut browser = HoldBrowser::default();
+    browser.set_color(theme::input_bg());
+    browser.set_selection_color(theme::selection_strong());
+    browser.set_text_size(configured_ui_font_size());
+    browser.set_column_char('\t');
+    browser.set_column_widths(&[80]);
+    content.fixed(&browser, 380);
+    let mut detail = HoldBrowser::default();
+    detail.set_color(theme::editor_bg());
+    detail.set_selection_color(theme::selection_strong());
+    detail.set_text_size(configured_ui_font_size());
+    detail.set_column_char('\t');
+    detail.set_column_widths(&[180, 220]);
+    content.end();
+
+    let mut button_flex = Flex::default();
+    button_flex.set_type(FlexType::Row);
+    button_flex.set_spacing(DIALOG_SPACING);
+    let _spacer = Frame::default();
+    let mut script_btn = styled_button("Generate Script", theme::button_secondary());
+    let mut close_btn = styled_button("Close", theme::button_subtle());
+    button_flex.fixed(&script_btn, BUTTON_WIDTH_LARGE);
+    button_flex.fixed(&close_btn, BUTTON_WIDTH);
+    button_flex.end();
+    main_flex.fixed(&button_flex, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    popups.borrow_mut().push(dialog.clone());
+
+    let (sender, receiver) = mpsc::channel::<DialogMessage>();
+    for (button, message) in [
+        (&mut compare_btn, DialogMessage::Compare),
+        (&mut script_btn, DialogMessage::Script),
+        (&mut close_btn, DialogMessage::Close),
+    ] {
+        let sender = sender.clone();
+        button.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+    let sender_for_input = sender.clone();
+    table_input.set_callback(move |_| {
+        let _ = sender_for_input.send(DialogMessage::Compare);
+        app::awake();
+    });
+    let sender_for_browser = sender.clone();
+    browser.set_callback(move |_| {
+        let _ = sender_for_browser.send(DialogMessage::Select);
+        app::awake();
+    });
+
+    dialog.show();
+    let _ = table_input.take_focus();
+
+    let (update_sender, update_receiver) = mpsc::channel::<CompareUpdate>();
+    let mut compared: Option<DataCompareResult> = None;
+    // Source label, target label, target side and its connection.
+    let mut compared_sides: Option<(String, String, DataCompareSide, SharedConnection)> = None;
+    let mut running = false;
+    let mut result: Option<SyncScript> = None;
+
+    while dialog.shown() {
+        app::wait();
+        while let Ok(update) = update_receiver.try_recv() {
+            match update {
+                CompareUpdate::Progress(text) => status.set_label(&text),
+                CompareUpdate::Done(outcome) => {
+                    running = false;
+                    compare_btn.activate();
+                    browser.clear();
+                    detail.clear();
+                    match outcome {
+                        Ok(found) => {
+                            for difference in &found.differences {
+                                browser.add(&format!(
+                                    "@.{}\t@.{}",
+                                    difference.label(),
+                                    found.describe_key(difference.any_row()).replace('\t', " ")
+                                ));
+                            }
+                            status.set_label(&summary(&found));
+                            compared = Some(found);
+                        }
+                        Err(err) => {
+                            compared = None;
+                            status.set_label("Compare failed");
+                            fltk::dialog::alert_default(&format!("Data compare failed: {}", err));
+                        }
+                    }
+                }
+            }
+        }
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                DialogMessage::Compare => {
+                    if running {
+                        continue;
+                    }
+                    let table = table_input.value().trim().to_uppercase();
+                    if table.is_empty() {
+                        fltk::dialog::alert_default("Enter the table to compare");
+                        continue;
+                    }
+                    let source_index = source_choice.value();
+                    let target_index = target_choice.value();
+                    let (Some(source), Some(target)) = (
+                        usize::try_from(source_index)
+                            .ok()
+                            .and_then(|index| endpoints.get(index)),
+                        usize::try_from(target_index)
+                            .ok()
+                            .and_then(|index| endpoints.get(index)),
+                    ) else {
+                        continue;
+                    };
+                    let source_side =
+                        compare_side(&source_schema.value().trim().to_uppercase(), &table);
+                    let target_side =
+                        compare_side(&target_schema.value().trim().to_uppercase(), &table);
+                    if source_index == target_index && source_side.owner == target_side.owner {
+                        fltk::dialog::alert_default(
+                            "Choose a different target connection or schema",
+                        );
+                        continue;
+                    }
+                    compared_sides = Some((
+                        format!("{} {}", source.label, source_side.sql_name()),
+                        format!("{} {}", target.label, target_side.sql_name()),
+                        target_side.clone(),
+                        target.connection.clone(),
+                    ));
+                    running = true;
+                    compare_btn.deactivate();
+                    browser.clear();
+                    detail.clear();
+                    compared = None;
+                    status.set_label("Comparing...");
+                    let key_names = parse_key_columns(&key_input.value());
+                    let source_connection = source.connection.clone();
+                    let target_connection = target.connection.clone();
+                    let update_sender = update_sender.clone();
+                    thread::spawn(move || {
+                        let outcome = (|| {
+                            let source_conn = endpoint_connection(&source_connection, "source")?;
+                            let target_conn = endpoint_connection(&target_connection, "target")?;
+                            let key_names = if key_names.is_empty() {
+                                primary_key_columns(&source_conn, &source_side)?
+                            } else {
+                                key_names
+                            };
+                            let mut progress = |side: &str, rows: usize| {
+                                let _ = update_sender.send(CompareUpdate::Progress(format!(
+                                    "Reading {side} rows... {rows}"
+                                )));
+                                app::awake();
+                            };
+                            compare_table_data(
+                                &source_conn,
+                                &source_side,
+                                &target_conn,
+                                &target_side,
+                                key_names,
+                                &mut progress,
+                            )
+                        })();
+                        let _ = update_sender.send(CompareUpdate::Done(outcome));
+                        app::awake();
+                    });
+                }
+                DialogMessage::Select => {
+                    let Some(found) = &compared else {
+                        continue;
+                    };
+                    let Some(difference) = usize::try_from(browser.value() - 1)
+                        .ok()
+                        .and_then(|index| found.differences.get(index))
+                    else {
+                        continue;
+                    };
+                    detail.clear();
+                    detail.add("@b@.Column\t@b@.Source\t@b@.Target");
+                    for line in detail_lines(found, difference) {
+                        detail.add(&line);
+                    }
+                }
+                DialogMessage::Script => {
+                    if running {
+                        continue;
+                    }
+                    let (Some(found), Some((source_label, target_label, target_side, target))) =
+                        (&compared, &compared_sides)
+                    else {
+                        fltk::dialog::alert_default("Compare the table first");
+                        continue;
+                    };
+                    if found.differences.is_empty() {
+                        fltk::dialog::message_default("The rows match; there is nothing to do");
+                        continue;
+                    }
+                    result = Some(SyncScript {
+                        script: data_sync_script(found, target_side, source_label, target_label),
+                        target: target.clone(),
+                    });
+                    dialog.hide();
+                }
+                DialogMessage::Close => dialog.hide(),
+            }
+        }
+    }
+
+    popups
+        .borrow_mut()
+        .retain(|w| w.as_widget_ptr() != dialog.as_widget_ptr());
+
+    result
+}
+
+#[cfg(test)]
+mod data_compare_dialog_tests {
+    use super::*;
+    use crate::db::{CellKind, ColumnInfo};
+
+    #[test]
+    fn key_columns_are_split_and_uppercased() {
+        assert_eq!(
+            parse_key_columns(" id, line_no ,"),
+            vec!["ID".to_string(), "LINE_NO".to_string()]
+        );
+        assert!(parse_key_columns("  ").is_empty());
+    }
+
+    #[test]
+    fn detail_lines_highlight_changed_columns_only() {
+        let result = DataCompareResult {
+            columns: ["ID", "NAME"]
+                .iter()
+                .map(|name| ColumnInfo {
+                    name: name.to_string(),
+                    data_type: String::new(),
+                    kind: CellKind::Text,
+                })
+                .collect(),
+            key_columns: vec![0],
+            ..DataCompareResult::default()
+        };
+        let difference = RowDifference::Changed {
+            source: vec![CellValue::Text("1".into()), CellValue::Text("a\tb".into())],
+            target: vec![CellValue::Text("1".into()), CellValue::Null],
+            columns: vec![1],
+        };
+        let lines = detail_lines(&result, &difference);
+        assert_eq!(lines[0], "@.ID\t@.1\t@.1");
+        let prefix = format!("@C{}@.", theme::button_warning().bits());
+        assert_eq!(
+            lines[1],
+            format!("{prefix}NAME\t{prefix}a b\t{prefix}(null)")
+        );
+    }
+}
@@ -0,0 +1,503 @@
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    frame::Frame,
+    group::{Flex, FlexType},
+    menu::Choice,
+    prelude::*,
+    text::{TextBuffer, TextDisplay, TextEditor},
+    window::Window,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::db::{
+    compile_for_debug, create_shared_connection, entry_points, lock_connection, source_units,
+    start_debug_session, try_lock_connection, unit_location, ConnectionInfo, DebugBreakpoint,
+    DebugCommand, DebugEvent, DebugLocation, DebugStep, EntryPoint, ObjectBrowser, ScriptRunner,
+    SharedConnection, SourceUnit,
+};
+use crate::ui::constants::*;
+use crate::ui::schema_compare_dialog::{labeled, styled_button};
+use crate::ui::{
+    center_on_main, configured_editor_profile, configured_ui_font_size, theme, ObjectBrowserWidget,
+    SqlEditorWidget,
+};
+use crate::utils::AppConfig;
+
+thread_local! {
+    static DEBUGGER_OPEN: Cell<bool> = const { Cell::new(false) };
+}
+
+const EMPTY_SCRIPT: &str = "BEGIN\n  NULL;\nEND;\n/\n";
+
+/// Breakpoints of the editor lines that fall inside a program unit.
+fn unit_breakpoints(units: &[SourceUnit], lines: &[usize]) -> Vec<DebugBreakpoint> {
+    lines
+        .iter()
+        .filter_map(|&line| {
+            unit_location(units, line).map(|(unit, unit_line)| DebugBreakpoint {
+                unit: unit.unit.clone(),
+                line: unit_line,
+            })
+        })
+        .collect()
+}
+
+/// The editor line of a location in one of the editor's program units.
+fn editor_line(units: &[SourceUnit], location: &DebugLocation) -> Option<usize> {
+    let unit = location.unit.as_ref()?;
+    let source = units.iter().find(|source| source.unit.matches(unit))?;
+    let line = source.first_line + location.line.checked_sub(1)?;
+    (line <= source.last_line).then_some(line)
+}
+
+fn describe_location(location: &DebugLocation) -> String {
+    match &location.unit {
+        Some(unit) => match &unit.owner {
+            Some(owner) => format!("{}.{} line {}", owner, unit.name, location.line),
+            None => format!("{} line {}", unit.name, location.line),
+        },
+        None => format!("anonymous block line {}", location.line),
+    }
+}
+
+/// Fetch the arguments of `entry` and build the block that calls it.
+fn prepare_script(connection: &SharedConnection, entry: &EntryPoint) -> Result<String, String> {
+    let Some(guard) = try_lock_connection(connection) else {
+        return Err("The connection is busy with another query".to_string());
+    };
+    let Some(conn) = guard.get_connection() else {
+        return Err("Not connected to database".to_string());
+    };
+    let arguments = match &entry.package {
+        Some(package) => ObjectBrowser::get_package_procedure_arguments_for_owner(
+            conn.as_ref(),
+            entry.owner.as_deref(),
+            package,
+            &entry.name,
+        ),
+        None => ObjectBrowser::get_procedure_arguments_for_owner(
+            conn.as_ref(),
+            entry.owner.as_deref(),
+            &entry.name,
+        ),
+    };
+    match arguments {
+        Ok(arguments) => Ok(ObjectBrowserWidget::build_procedure_script(
+            &entry.qualified_name(),
+            &arguments,
+        )),
+        Err(err) => {
+            eprintln!("Database operation failed: {err}");
+            Err(err.to_string())
+        }
+    }
+}
+
+/// Open a session of its own with the tab's connection details.
+fn open_session(info: &ConnectionInfo) -> Result<SharedConnection, String> {
+    let connection = create_shared_connection();
+    {
+        let mut db_conn = lock_connection(&connection);
+        db_conn.set_session_defaults(AppConfig::load().session_defaults);
+        db_conn
+            .connect(info.clone())
+            .map_err(|err| err.to_string())?;
+    }
+    ScriptRunner::run_session_init(&connection);
+    Ok(connection)
+}
+
+/// Debug the program units in `editor` on sessions of their own, leaving
+/// the tab's session free. Breakpoints come from the editor gutter.
+pub fn show_debugger_dialog(
+    editor: SqlEditorWidget,
+    connection: SharedConnection,
+    info: Option<ConnectionInfo>,
+    popups: Rc<RefCell<Vec<Window>>>,
+) {
+    #[derive(Clone, Copy)]
+    enum DialogMessage {
+        Prepare,
+        Start,
+        Step(DebugStep),
+        Close,
+    }
+
+    let Some(mut info) = info else {
+        fltk::dialog::alert_default("Connect the query tab before debugging");
+        return;
+    };
+    if DEBUGGER_OPEN.with(|open| open.replace(true)) {
+        return;
+    }
+    if !info.use_wallet {
+        let password = AppConfig::get_password_for_connection(&info.name).or_else(|| {
+            fltk::dialog::password_default(
+                &format!("Password for {} (debug sessions):", info.username),
+                "",
+            )
+        });
+        let Some(password) = password else {
+            DEBUGGER_OPEN.with(|open| open.set(false));
+            return;
+        };
+        info.password = password;
+    }
+
+    let points = entry_points(&editor.get_text());
+
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 900;
+    let height = 660;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("PL/SQL Debugger");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let mut entry_row = Flex::default();
+    entry_row.set_type(FlexType::Row);
+    entry_row.set_spacing(DIALOG_SPACING);
+    labeled(&mut entry_row, "Call:", 60);
+    let mut entry_choice = Choice::default();
+    entry_choice.set_color(theme::input_bg());
+    entry_choice.set_text_color(theme::text_primary());
+    for point in &points {
+        entry_choice.add_choice(&point.qualified_name().replace('/', "\\/"));
+    }
+    if !points.is_empty() {
+        entry_choice.set_value(0);
+    }
+    let mut prepare_btn = styled_button("Prepare Script", theme::button_secondary());
+    entry_row.fixed(&prepare_btn, BUTTON_WIDTH_LARGE);
+    entry_row.end();
+    main_flex.fixed(&entry_row, INPUT_ROW_HEIGHT);
+
+    let mut script_buffer = TextBuffer::default();
+    script_buffer.set_text(EMPTY_SCRIPT);
+    let mut script_editor = TextEditor::default();
+    script_editor.set_buffer(script_buffer.clone());
+    script_editor.set_color(theme::editor_bg());
+    script_editor.set_text_color(theme::text_primary());
+    script_editor.set_cursor_color(theme::text_primary());
+    script_editor.set_text_font(configured_editor_profile().normal);
+    script_editor.set_text_size(configured_ui_font_size());
+    main_flex.fixed(&script_editor, 130);
+
+    let mut button_row = Flex::default();
+    button_row.set_type(FlexType::Row);
+    button_row.set_spacing(DIALOG_SPACING);
+    let mut start_btn = styled_button("Start", theme::button_primary());
+    let mut into_btn = styled_button("Step Into", theme::button_secondary());
+    let mut over_btn = styled_button("Step Over", theme::button_secondary());
+    let mut out_btn = styled_button("Step Out", theme::button_secondary());
+    let mut continue_btn = styled_button("Continue", theme::button_secondary());
+    let mut stop_btn = styled_button("Stop", theme::button_danger());
+    for button in [
+        &start_btn,
+        &into_btn,
+        &over_btn,
+        &out_btn,
+        &continue_btn,
+        &stop_btn,
+    ] {
+        button_row.fixed(button, BUTTON_WIDTH);
+    }
+    let _spacer = Frame::default();
+    button_row.end();
+    main_flex.fixed(&button_row, BUTTON_ROW_HEIGHT);
+
+    let mut status =
+        Frame::default().with_label("Set breakpoints in the editor gutter, then Start");
+    status.set_label_color(theme::text_secondary());
+    status.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    main_flex.fixed(&status, LABEL_ROW_HEIGHT);
+
+    let mut content = Flex::default();
+    content.set_type(FlexType::Row);
+    content.set_spacing(DIALOG_SPACING);
+    let mut stack_browser = HoldBrowser::default();
+    stack_browser.set_color(theme::input_bg());
+    stack_browser.set_selection_color(theme::selection_strong());
+    stack_browser.set_text_size(configured_ui_font_size());
+    content.fixed(&stack_browser, 340);
+    let mut variables_browser = HoldBrowser::default();
+    variables_browser.set_color(theme::input_bg());
+    variables_browser.set_selection_color(theme::selection_strong());
+    variables_browser.set_text_size(configured_ui_font_size());
+    variables_browser.set_column_char('\t');
+    variables_browser.set_column_widths(&[180]);
+    content.end();
+
+    let output_buffer = TextBuffer::default();
+    let mut output_display = TextDisplay::default();
+    output_display.set_buffer(output_buffer.clone());
+    output_display.set_color(theme::editor_bg());
+    output_display.set_text_color(theme::text_primary());
+    output_display.set_text_font(configured_editor_profile().normal);
+    output_display.set_text_size(configured_ui_font_size());
+    main_flex.fixed(&output_display, 120);
+
+    let mut close_row = Flex::default();
+    close_row.set_type(FlexType::Row);
+    let _spacer = Frame::default();
+    let mut close_btn = styled_button("Close", theme::button_subtle());
+    close_row.fixed(&close_btn, BUTTON_WIDTH);
+    close_row.end();
+    main_flex.fixed(&close_row, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    popups.borrow_mut().push(dialog.clone());
+
+    let (sender, receiver) = mpsc::channel::<DialogMessage>();
+    for (button, message) in [
+        (&mut prepare_btn, DialogMessage::Prepare),
+        (&mut start_btn, DialogMessage::Start),
+        (&mut into_btn, DialogMessage::Step(DebugStep::StepInto)),
+        (&mut over_btn, DialogMessage::Step(DebugStep::StepOver)),
+        (&mut out_btn, DialogMessage::Step(DebugStep::StepOut)),
+        (&mut continue_btn, DialogMessage::Step(DebugStep::Continue)),
+        (&mut stop_btn, DialogMessage::Step(DebugStep::Abort)),
+        (&mut close_btn, DialogMessage::Close),
+    ] {
+        let sender = sender.clone();
+        button.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+
+    let mut step_buttons = [into_btn, over_btn, out_btn, continue_btn, stop_btn];
+    let set_paused = |buttons: &mut [fltk::button::Button], paused: bool| {
+        for button in buttons.iter_mut() {
+            if paused {
+                button.activate();
+            } else {
+                button.deactivate();
+            }
+        }
+    };
+    set_paused(&mut step_buttons, false);
+    if points.is_empty() {
+        prepare_btn.deactivate();
+    }
+
+    dialog.show();
+
+    let (script_sender, script_receiver) = mpsc::channel::<Result<String, String>>();
+    let (event_sender, event_receiver) = mpsc::channel::<DebugEvent>();
+    let mut commands: Option<mpsc::Sender<DebugCommand>> = None;
+    let mut running = false;
+
+    while dialog.shown() {
+        app::wait();
+        while let Ok(result) = script_receiver.try_recv() {
+            prepare_btn.activate();
+            match result {
+                Ok(script) => script_buffer.set_text(&script),
+                Err(err) => {
+                    fltk::dialog::alert_default(&format!("Failed to prepare the call: {}", err))
+                }
+            }
+        }
+        while let Ok(event) = event_receiver.try_recv() {
+            match event {
+                DebugEvent::Status(text) => status.set_label(&text),
+                DebugEvent::Output(lines) => {
+                    let mut output = output_buffer.clone();
+                    for line in lines {
+                        output.append(&line);
+                        output.append("\n");
+                    }
+                    let end = output.length();
+                    output_display.scroll(output_display.count_lines(0, end, true), 0);
+                }
+                DebugEvent::Paused(snapshot) => {
+                    status.set_label(&format!(
+                        "Paused at {}",
+                        describe_location(&snapshot.location)
+                    ));
+                    let units = source_units(&editor.get_text());
+                    editor.set_debug_line(editor_line(&units, &snapshot.location));
+                    stack_browser.clear();
+                    for frame in &snapshot.stack {
+                        stack_browser.add(&format!("@.{}", frame));
+                    }
+                    variables_browser.clear();
+                    for (name, value) in &snapshot.variables {
+                        variables_browser.add(&format!(
+                            "@.{}\t@.{}",
+                            name,
+                            value.replace(['\t', '\n'], " ")
+                        ));
+                    }
+                    set_paused(&mut step_buttons, true);
+                }
+                DebugEvent::Finished(result) => {
+                    running = false;
+                    commands = None;
+                    editor.set_debug_line(None);
+                    set_paused(&mut step_buttons, false);
+                    start_btn.activate();
+                    match result {
+                        Ok(()) => status.set_label("Debug run ended"),
+                        Err(err) => {
+                            status.set_label("Debug run failed");
+                            fltk::dialog::alert_default(&format!("Debugger error: {}", err));
+                        }
+                    }
+                }
+            }
+        }
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                DialogMessage::Prepare => {
+                    let Some(entry) = usize::try_from(entry_choice.value())
+                        .ok()
+                        .and_then(|index| points.get(index))
+                        .cloned()
+                    else {
+                        continue;
+                    };
+                    prepare_btn.deactivate();
+                    let connection = connection.clone();
+                    let script_sender = script_sender.clone();
+                    thread::spawn(move || {
+                        let _ = script_sender.send(prepare_script(&connection, &entry));
+                        app::awake();
+                    });
+                }
+                DialogMessage::Start => {
+                    if running {
+                        continue;
+                    }
+                    let script = script_buffer.text();
+                    if script.trim().is_empty() {
+                        fltk::dialog::alert_default("Enter the block to debug");
+                        continue;
+                    }
+                    running = true;
+                    start_btn.deactivate();
+                    stack_browser.clear();
+                    variables_browser.clear();
+                    output_buffer.clone().set_text("");
+                    status.set_label("Opening debug sessions...");
+                    let (command_sender, command_receiver) = mpsc::channel::<DebugCommand>();
+                    commands = Some(command_sender);
+                    let units = source_units(&editor.get_text());
+                    let info = info.clone();
+                    let event_sender = event_sender.clone();
+                    thread::spawn(move || {
+                        let started = (|| {
+                            let target = open_session(&info)?;
+                            let controller = open_session(&info)?;
+                            if let Some(conn) = lock_connection(&target).get_connection() {
+                                for unit in &units {
+                                    compile_for_debug(&conn, unit)?;
+                                }
+                            }
+                            start_debug_session(
+                                target,
+                                controller,
+                                script,
+                                command_receiver,
+                                event_sender.clone(),
+                                app::awake,
+                            )
+                        })();
+                        if let Err(err) = started {
+                            let _ = event_sender.send(DebugEvent::Finished(Err(err)));
+                            app::awake();
+                        }
+                    });
+                }
+                DialogMessage::Step(step) => {
+                    let Some(command_sender) = &commands else {
+                        continue;
+                    };
+                    let units = source_units(&editor.get_text());
+                    let command = DebugCommand {
+                        step,
+                        breakpoints: unit_breakpoints(&units, &editor.breakpoint_lines()),
+                    };
+                    if command_sender.send(command).is_ok() {
+                        set_paused(&mut step_buttons, false);
+                        editor.set_debug_line(None);
+                    }
+                }
+                DialogMessage::Close => dialog.hide(),
+            }
+        }
+    }
+
+    // Dropping the command channel aborts a paused run.
+    drop(commands);
+    editor.set_debug_line(None);
+    popups
+        .borrow_mut()
+        .retain(|w| w.as_widget_ptr() != dialog.as_widget_ptr());
+    DEBUGGER_OPEN.with(|open| open.set(false));
+}
+
+#[cfg(test)]
+mod debugger_dialog_tests {
+    use super::*;
+
+    const PACKAGE_SCRIPT: &str = "SELECT 1 FROM dual;\n\
+        CREATE OR REPLACE PACKAGE BODY pkg AS\n\
+        PROCEDURE run IS\n\
+        BEGIN\n\
+        NULL;\n\
+        END;\n\
+        END pkg;\n\
+        /\n";
+
+    #[test]
+    fn breakpoints_outside_units_are_dropped() {
+        let units = source_units(PACKAGE_SCRIPT);
+        let breakpoints = unit_breakpoints(&units, &[1, 5]);
+        assert_eq!(breakpoints.len(), 1);
+        assert_eq!(breakpoints[0].unit.name, "PKG");
+        assert_eq!(breakpoints[0].line, 4);
+    }
+
+    #[test]
+    fn paused_locations_map_back_to_editor_lines() {
+        let units = source_units(PACKAGE_SCRIPT);
+        let unit = units[0].unit.clone();
+        let location = DebugLocation {
+            unit: Some(unit.clone()),
+            line: 4,
+        };
+        assert_eq!(editor_line(&units, &location), Some(5));
+        let outside = DebugLocation {
+            unit: Some(unit),
+            line: 40,
+        };
+        assert_eq!(editor_line(&units, &outside), None);
+        let anonymous = DebugLocation {
+            unit: None,
+            line: 1,
+        };
+        assert_eq!(editor_line(&units, &anonymous), None);
+    }
+}
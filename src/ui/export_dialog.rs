@@ -0,0 +1,279 @@
+use fltk::{
+    app,
+    button::{Button, CheckButton},
+    enums::FrameType,
+    frame::Frame,
+    group::{Flex, FlexType},
+    input::Input,
+    menu::Choice,
+    prelude::*,
+    window::Window,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::db::{ExportFormat, ExportOptions, QuoteStyle};
+use crate::ui::constants::*;
+use crate::ui::{center_on_main, theme};
+
+pub struct ExportRequest {
+    pub options: ExportOptions,
+    /// Re-run the query and stream every row to disk instead of exporting
+    /// the rows loaded in the grid.
+    pub all_rows: bool,
+}
+
+fn form_row(label: &str) -> Flex {
+    let mut row = Flex::default().with_size(0, INPUT_ROW_HEIGHT);
+    row.set_type(FlexType::Row);
+    row.set_spacing(DIALOG_SPACING);
+    let mut row_label = Frame::default().with_label(label);
+    row_label.set_label_color(theme::text_primary());
+    row.fixed(&row_label, FORM_LABEL_WIDTH);
+    row
+}
+
+fn styled_input(value: &str) -> Input {
+    let mut input = Input::default();
+    input.set_value(value);
+    input.set_color(theme::input_bg());
+    input.set_text_color(theme::text_primary());
+    input
+}
+
+fn styled_choice(labels: &[&str]) -> Choice {
+    let mut choice = Choice::default();
+    choice.set_color(theme::input_bg());
+    choice.set_text_color(theme::text_primary());
+    for label in labels {
+        choice.add_choice(label);
+    }
+    choice.set_value(0);
+    choice
+}
+
+/// Accepts a single character, or `\t` for a tab.
+fn parse_delimiter(value: &str) -> Option<char> {
+    if value == "\\t" {
+        return Some('\t');
+    }
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+fn update_option_states(
+    format: ExportFormat,
+    delimiter: &mut Input,
+    quoting: &mut Choice,
+    header: &mut CheckButton,
+    table_name: &mut Input,
+) {
+    let delimited = matches!(format, ExportFormat::Csv | ExportFormat::Tsv);
+    if format == ExportFormat::Csv {
+        delimiter.activate();
+    } else {
+        delimiter.deactivate();
+    }
+    if delimited {
+        quoting.activate();
+    } else {
+        quoting.deactivate();
+    }
+    if matches!(
+        format,
+        ExportFormat::Csv | ExportFormat::Tsv | ExportFormat::Html | ExportFormat::Xlsx
+    ) {
+        header.activate();
+    } else {
+        header.deactivate();
+    }
+    if format == ExportFormat::SqlInsert {
+        table_name.activate();
+    } else {
+        table_name.deactivate();
+    }
+}
+
+/// Ask for the export format and options. `can_export_all` enables
+/// re-running the result's query to export every row.
+pub fn show_export_dialog(can_export_all: bool) -> Option<ExportRequest> {
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let defaults = ExportOptions::default();
+    let width = 460;
+    let height = 390;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("Export Results");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+    dialog.make_modal(true);
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let format_labels: Vec<&str> = ExportFormat::ALL.iter().map(|f| f.label()).collect();
+    let format_row = form_row("Format:");
+    let mut format_choice = styled_choice(&format_labels);
+    format_row.end();
+    main_flex.fixed(&format_row, INPUT_ROW_HEIGHT);
+
+    let rows_row = form_row("Rows:");
+    let mut rows_choice = styled_choice(&["Loaded rows", "All rows (re-run query)"]);
+    if !can_export_all {
+        rows_choice.deactivate();
+    }
+    rows_row.end();
+    main_flex.fixed(&rows_row, INPUT_ROW_HEIGHT);
+
+    let mut header_row = Flex::default().with_size(0, CHECKBOX_ROW_HEIGHT);
+    header_row.set_type(FlexType::Row);
+    let mut header_check = CheckButton::default().with_label("Include header row");
+    header_check.set_label_color(theme::text_secondary());
+    header_check.set_checked(defaults.include_header);
+    header_row.end();
+    main_flex.fixed(&header_row, CHECKBOX_ROW_HEIGHT);
+
+    let null_row = form_row("NULL as:");
+    let null_input = styled_input(&defaults.null_text);
+    null_row.end();
+    main_flex.fixed(&null_row, INPUT_ROW_HEIGHT);
+
+    let mut delimiter_row = form_row("Delimiter:");
+    let mut delimiter_input = styled_input(&defaults.delimiter.to_string());
+    delimiter_input.set_tooltip("One character; \\t for a tab");
+    delimiter_row.fixed(&delimiter_input, NUMERIC_INPUT_WIDTH);
+    let _delimiter_spacer = Frame::default();
+    delimiter_row.end();
+    main_flex.fixed(&delimiter_row, INPUT_ROW_HEIGHT);
+
+    let quote_labels: Vec<&str> = QuoteStyle::ALL.iter().map(|q| q.label()).collect();
+    let quote_row = form_row("Quoting:");
+    let mut quote_choice = styled_choice(&quote_labels);
+    quote_row.end();
+    main_flex.fixed(&quote_row, INPUT_ROW_HEIGHT);
+
+    let date_row = form_row("Date format:");
+    let mut date_input = styled_input("");
+    date_input.set_tooltip("strftime pattern, e.g. %Y-%m-%d %H:%M:%S");
+    date_row.end();
+    main_flex.fixed(&date_row, INPUT_ROW_HEIGHT);
+
+    let table_row = form_row("Table name:");
+    let mut table_input = styled_input(&defaults.table_name);
+    table_row.end();
+    main_flex.fixed(&table_row, INPUT_ROW_HEIGHT);
+
+    let mut date_hint = Frame::default().with_label("Empty date format keeps dates as fetched");
+    date_hint.set_label_color(theme::text_secondary());
+    main_flex.fixed(&date_hint, LABEL_ROW_HEIGHT);
+
+    let _filler = Frame::default();
+
+    let mut button_row = Flex::default();
+    button_row.set_type(FlexType::Row);
+    button_row.set_spacing(DIALOG_SPACING);
+    let _btn_spacer = Frame::default();
+    let mut cancel_btn = Button::default()
+        .with_size(BUTTON_WIDTH, BUTTON_HEIGHT)
+        .with_label("Cancel");
+    cancel_btn.set_color(theme::button_secondary());
+    cancel_btn.set_label_color(theme::text_primary());
+    cancel_btn.set_frame(FrameType::RFlatBox);
+    let mut ok_btn = Button::default()
+        .with_size(BUTTON_WIDTH, BUTTON_HEIGHT)
+        .with_label("Export...");
+    ok_btn.set_color(theme::button_primary());
+    ok_btn.set_label_color(theme::text_primary());
+    ok_btn.set_frame(FrameType::RFlatBox);
+    button_row.fixed(&cancel_btn, BUTTON_WIDTH);
+    button_row.fixed(&ok_btn, BUTTON_WIDTH);
+    button_row.end();
+    main_flex.fixed(&button_row, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    dialog.show();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    update_option_states(
+        defaults.format,
+        &mut delimiter_input,
+        &mut quote_choice,
+        &mut header_check,
+        &mut table_input,
+    );
+
+    let mut delimiter_for_format = delimiter_input.clone();
+    let mut quote_for_format = quote_choice.clone();
+    let mut header_for_format = header_check.clone();
+    let mut table_for_format = table_input.clone();
+    format_choice.set_callback(move |choice| {
+        let format = ExportFormat::ALL[choice.value().max(0) as usize];
+        update_option_states(
+            format,
+            &mut delimiter_for_format,
+            &mut quote_for_format,
+            &mut header_for_format,
+            &mut table_for_format,
+        );
+    });
+
+    let result = Rc::new(RefCell::new(None::<ExportRequest>));
+    let result_for_ok = result.clone();
+    let mut dialog_handle = dialog.clone();
+    ok_btn.set_callback(move |_| {
+        let Some(delimiter) = parse_delimiter(&delimiter_input.value()) else {
+            fltk::dialog::alert_default("The delimiter must be a single character.");
+            return;
+        };
+        let date_format = date_input.value().trim().to_string();
+        let options = ExportOptions {
+            format: ExportFormat::ALL[format_choice.value().max(0) as usize],
+            include_header: header_check.is_checked(),
+            null_text: null_input.value(),
+            delimiter,
+            quote_style: QuoteStyle::ALL[quote_choice.value().max(0) as usize],
+            date_format: if date_format.is_empty() {
+                None
+            } else {
+                Some(date_format)
+            },
+            table_name: table_input.value().trim().to_string(),
+        };
+        if let Err(err) = options.validate() {
+            fltk::dialog::alert_default(&err);
+            return;
+        }
+        *result_for_ok.borrow_mut() = Some(ExportRequest {
+            options,
+            all_rows: rows_choice.active() && rows_choice.value() == 1,
+        });
+        dialog_handle.hide();
+        app::awake();
+    });
+
+    let mut dialog_handle = dialog.clone();
+    cancel_btn.set_callback(move |_| {
+        dialog_handle.hide();
+        app::awake();
+    });
+
+    while dialog.shown() {
+        app::wait();
+    }
+
+    let final_result = result.borrow_mut().take();
+    final_result
+}
@@ -0,0 +1,301 @@
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    button::Button,
+    enums::{CallbackTrigger, FrameType},
+    frame::Frame,
+    group::{Flex, FlexType},
+    input::Input,
+    prelude::*,
+    window::Window,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc;
+
+use crate::ui::constants::*;
+use crate::ui::find_replace::OptionChecks;
+use crate::ui::{
+    build_search_regex, center_on_main, configured_ui_font_size, find_all_matches, theme,
+    FindReplaceDialog, QueryTabId, SearchOptions,
+};
+
+/// Longest line excerpt shown for a match.
+const MAX_EXCERPT_CHARS: usize = 200;
+
+/// The text of one query tab to search.
+pub struct TabSearchSource {
+    pub tab_id: QueryTabId,
+    pub label: String,
+    pub text: String,
+}
+
+/// One match found by [`search_tabs`]; offsets are bytes into the tab text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabMatch {
+    pub tab_id: QueryTabId,
+    pub label: String,
+    /// 1-based line number of the match start.
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+    pub excerpt: String,
+}
+
+/// Find every match of `search_text` in the given tabs, in tab order.
+pub fn search_tabs(
+    sources: &[TabSearchSource],
+    search_text: &str,
+    options: SearchOptions,
+) -> Result<Vec<TabMatch>, String> {
+    let regex = build_search_regex(search_text, options)?;
+    let mut matches = Vec::new();
+    for source in sources {
+        let text = &source.text;
+        let mut line = 1usize;
+        let mut counted_to = 0usize;
+        for (start, end) in find_all_matches(text, &regex, options, 0, text.len()) {
+            line += text[counted_to..start].matches('\n').count();
+            counted_to = start;
+            let line_start = text[..start].rfind('\n').map_or(0, |pos| pos + 1);
+            let line_end = text[start..]
+                .find('\n')
+                .map_or(text.len(), |pos| start + pos);
+            let excerpt: String = text[line_start..line_end]
+                .trim()
+                .chars()
+                .take(MAX_EXCERPT_CHARS)
+                .collect();
+            matches.push(TabMatch {
+                tab_id: source.tab_id,
+                label: source.label.clone(),
+                line,
+                start,
+                end,
+                excerpt,
+            });
+        }
+    }
+    Ok(matches)
+}
+
+fn browser_line(found: &TabMatch) -> String {
+    let excerpt = found.excerpt.replace('\t', " ");
+    format!("@.{}\t@.{}\t@.{}", found.label, found.line, excerpt)
+}
+
+/// Search all open query tabs and list every match. Returns the match the
+/// user chose to go to.
+pub fn show_find_in_tabs_dialog(
+    sources: Vec<TabSearchSource>,
+    popups: Rc<RefCell<Vec<Window>>>,
+) -> Option<TabMatch> {
+    #[derive(Clone, Copy)]
+    enum DialogMessage {
+        Search,
+        GoTo,
+        Close,
+    }
+
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 760;
+    let height = 480;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("Find in Tabs");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+    dialog.make_modal(true);
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let mut find_flex = Flex::default();
+    find_flex.set_type(FlexType::Row);
+    find_flex.set_spacing(DIALOG_SPACING);
+    let mut find_label = Frame::default().with_label("Find:");
+    find_label.set_label_color(theme::text_primary());
+    find_flex.fixed(&find_label, FORM_LABEL_WIDTH);
+    let mut find_input = Input::default();
+    find_input.set_color(theme::input_bg());
+    find_input.set_text_color(theme::text_primary());
+    find_input.set_trigger(CallbackTrigger::EnterKeyAlways);
+    let mut search_btn = Button::default().with_label("Search");
+    search_btn.set_color(theme::button_primary());
+    search_btn.set_label_color(theme::text_primary());
+    search_btn.set_frame(FrameType::RFlatBox);
+    find_flex.fixed(&search_btn, BUTTON_WIDTH);
+    find_flex.end();
+    main_flex.fixed(&find_flex, INPUT_ROW_HEIGHT);
+
+    let mut options_flex = Flex::default();
+    options_flex.set_type(FlexType::Row);
+    let option_checks = OptionChecks::new(&mut options_flex, FindReplaceDialog::session_options());
+    options_flex.end();
+    main_flex.fixed(&options_flex, CHECKBOX_ROW_HEIGHT);
+
+    let mut results = HoldBrowser::default();
+    results.set_color(theme::input_bg());
+    results.set_selection_color(theme::selection_strong());
+    results.set_text_size(configured_ui_font_size());
+    results.set_column_char('\t');
+    results.set_column_widths(&[180, 60]);
+
+    let mut status = Frame::default();
+    status.set_label_color(theme::text_secondary());
+    status.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    main_flex.fixed(&status, LABEL_ROW_HEIGHT);
+
+    let mut button_flex = Flex::default();
+    button_flex.set_type(FlexType::Row);
+    button_flex.set_spacing(DIALOG_SPACING);
+    let _spacer = Frame::default();
+    let mut goto_btn = Button::default().with_label("Go To");
+    goto_btn.set_color(theme::button_secondary());
+    goto_btn.set_label_color(theme::text_primary());
+    goto_btn.set_frame(FrameType::RFlatBox);
+    let mut close_btn = Button::default().with_label("Close");
+    close_btn.set_color(theme::button_subtle());
+    close_btn.set_label_color(theme::text_primary());
+    close_btn.set_frame(FrameType::RFlatBox);
+    button_flex.fixed(&goto_btn, BUTTON_WIDTH);
+    button_flex.fixed(&close_btn, BUTTON_WIDTH_SMALL);
+    button_flex.end();
+    main_flex.fixed(&button_flex, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    popups.borrow_mut().push(dialog.clone());
+
+    let (sender, receiver) = mpsc::channel::<DialogMessage>();
+    for (button, message) in [
+        (&mut search_btn, DialogMessage::Search),
+        (&mut goto_btn, DialogMessage::GoTo),
+        (&mut close_btn, DialogMessage::Close),
+    ] {
+        let sender = sender.clone();
+        button.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+    let sender_for_input = sender.clone();
+    find_input.set_callback(move |_| {
+        let _ = sender_for_input.send(DialogMessage::Search);
+        app::awake();
+    });
+    let sender_for_results = sender.clone();
+    results.set_callback(move |_| {
+        if app::event_clicks() {
+            let _ = sender_for_results.send(DialogMessage::GoTo);
+            app::awake();
+        }
+    });
+
+    dialog.show();
+    let _ = find_input.take_focus();
+
+    let mut matches: Vec<TabMatch> = Vec::new();
+    let mut chosen: Option<TabMatch> = None;
+
+    while dialog.shown() {
+        app::wait();
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                DialogMessage::Search => {
+                    let search_text = find_input.value();
+                    if search_text.is_empty() {
+                        continue;
+                    }
+                    matches = match search_tabs(&sources, &search_text, option_checks.options()) {
+                        Ok(matches) => matches,
+                        Err(err) => {
+                            fltk::dialog::alert_default(&err);
+                            continue;
+                        }
+                    };
+                    results.clear();
+                    for found in &matches {
+                        results.add(&browser_line(found));
+                    }
+                    let mut tab_ids: Vec<QueryTabId> =
+                        matches.iter().map(|found| found.tab_id).collect();
+                    tab_ids.dedup();
+                    status.set_label(&format!(
+                        "{} matches in {} of {} tabs",
+                        matches.len(),
+                        tab_ids.len(),
+                        sources.len()
+                    ));
+                }
+                DialogMessage::GoTo => {
+                    let index = results.value() - 1;
+                    if let Some(found) = usize::try_from(index)
+                        .ok()
+                        .and_then(|index| matches.get(index))
+                    {
+                        chosen = Some(found.clone());
+                        dialog.hide();
+                    }
+                }
+                DialogMessage::Close => dialog.hide(),
+            }
+        }
+    }
+
+    popups
+        .borrow_mut()
+        .retain(|w| w.as_widget_ptr() != dialog.as_widget_ptr());
+
+    chosen
+}
+
+#[cfg(test)]
+mod find_in_tabs_tests {
+    use super::*;
+
+    #[test]
+    fn reports_line_numbers_and_excerpts_per_tab() {
+        let sources = vec![
+            TabSearchSource {
+                tab_id: 1,
+                label: "Query 1".to_string(),
+                text: "select *\n  from emp\n where emp.id = 1;".to_string(),
+            },
+            TabSearchSource {
+                tab_id: 2,
+                label: "Query 2".to_string(),
+                text: "select 1 from dual;".to_string(),
+            },
+        ];
+        let matches = search_tabs(
+            &sources,
+            "emp",
+            SearchOptions {
+                whole_word: true,
+                ..SearchOptions::default()
+            },
+        )
+        .unwrap();
+        let summary: Vec<(QueryTabId, usize, &str)> = matches
+            .iter()
+            .map(|found| (found.tab_id, found.line, found.excerpt.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(1, 2, "from emp"), (1, 3, "where emp.id = 1;")]
+        );
+        assert_eq!(&sources[0].text[matches[0].start..matches[0].end], "emp");
+    }
+}
@@ -11,17 +11,29 @@ use fltk::{
     text::{TextBuffer, TextEditor},
     window::Window,
 };
+use regex::{Regex, RegexBuilder};
 use std::cell::RefCell;
 use std::rc::Rc;
 
 /// Find/Replace dialog
 pub struct FindReplaceDialog;
 
+/// How the search text is matched.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    /// Only match text not touching identifier characters on either side.
+    pub whole_word: bool,
+    /// Treat the search text as a regular expression; the replacement may
+    /// then refer to capture groups as `$1` or `${name}`.
+    pub use_regex: bool,
+}
+
 #[derive(Clone, Default)]
 struct FindReplaceSessionState {
     find_text: String,
     replace_text: String,
-    case_sensitive: bool,
+    options: SearchOptions,
     search_pos: i32,
     last_search_text: String,
 }
@@ -48,7 +60,7 @@ fn normalize_search_pos(text: &str, pos: i32) -> i32 {
 fn save_find_replace_state(
     find_input: &Input,
     replace_input: Option<&Input>,
-    case_check: &CheckButton,
+    options: SearchOptions,
     search_pos: i32,
     last_search_text: &str,
 ) {
@@ -58,17 +70,56 @@ fn save_find_replace_state(
         if let Some(replace_input) = replace_input {
             state.replace_text = replace_input.value();
         }
-        state.case_sensitive = case_check.value();
+        state.options = options;
         state.search_pos = search_pos.max(0);
         state.last_search_text = last_search_text.to_string();
     });
 }
 
+/// The search option check boxes shared by the find dialogs.
+#[derive(Clone)]
+pub(crate) struct OptionChecks {
+    case_sensitive: CheckButton,
+    whole_word: CheckButton,
+    use_regex: CheckButton,
+}
+
+impl OptionChecks {
+    /// Add the check boxes to the current group.
+    pub(crate) fn new(flex: &mut Flex, options: SearchOptions) -> Self {
+        let mut make = |label: &str, width: i32, value: bool| {
+            let mut check = CheckButton::default().with_label(label);
+            check.set_label_color(theme::text_secondary());
+            check.set_value(value);
+            flex.fixed(&check, width);
+            check
+        };
+        Self {
+            case_sensitive: make("Case sensitive", 120, options.case_sensitive),
+            whole_word: make("Whole word", 100, options.whole_word),
+            use_regex: make("Regex", 70, options.use_regex),
+        }
+    }
+
+    pub(crate) fn options(&self) -> SearchOptions {
+        SearchOptions {
+            case_sensitive: self.case_sensitive.value(),
+            whole_word: self.whole_word.value(),
+            use_regex: self.use_regex.value(),
+        }
+    }
+}
+
 impl FindReplaceDialog {
     pub fn has_search_text() -> bool {
         FIND_REPLACE_SESSION.with(|state| !state.borrow().find_text.is_empty())
     }
 
+    /// Search options used by the last find, for dialogs that start from them.
+    pub fn session_options() -> SearchOptions {
+        FIND_REPLACE_SESSION.with(|state| state.borrow().options)
+    }
+
     pub fn show_find_with_registry(
         editor: &mut TextEditor,
         buffer: &mut TextBuffer,
@@ -95,17 +146,17 @@ impl FindReplaceDialog {
         enum DialogMessage {
             FindNext {
                 search_text: String,
-                case_sensitive: bool,
+                options: SearchOptions,
             },
             Replace {
                 search_text: String,
                 replace_text: String,
-                case_sensitive: bool,
+                options: SearchOptions,
             },
             ReplaceAll {
                 search_text: String,
                 replace_text: String,
-                case_sensitive: bool,
+                options: SearchOptions,
             },
             Close,
         }
@@ -120,12 +171,12 @@ impl FindReplaceDialog {
         let current_group = fltk::group::Group::try_current();
         fltk::group::Group::set_current(None::<&fltk::group::Group>);
 
-        let mut dialog = Window::default().with_size(450, height).with_label(title);
+        let mut dialog = Window::default().with_size(520, height).with_label(title);
         center_on_main(&mut dialog);
         dialog.set_color(theme::panel_raised());
         dialog.make_modal(true);
 
-        let mut main_flex = Flex::default().with_pos(10, 10).with_size(430, height - 20);
+        let mut main_flex = Flex::default().with_pos(10, 10).with_size(500, height - 20);
         main_flex.set_type(fltk::group::FlexType::Column);
         main_flex.set_spacing(DIALOG_SPACING);
 
@@ -159,11 +210,14 @@ impl FindReplaceDialog {
             None
         };
 
+        let session_snapshot = FIND_REPLACE_SESSION.with(|state| state.borrow().clone());
+
         // Options row
         let mut options_flex = Flex::default();
         options_flex.set_type(fltk::group::FlexType::Row);
-        let mut case_check = CheckButton::default().with_label("Case sensitive");
-        case_check.set_label_color(theme::text_secondary());
+        let option_checks = OptionChecks::new(&mut options_flex, session_snapshot.options);
+        let mut selection_check = CheckButton::default().with_label("In selection");
+        selection_check.set_label_color(theme::text_secondary());
         options_flex.end();
         main_flex.fixed(&options_flex, CHECKBOX_ROW_HEIGHT);
 
@@ -224,12 +278,10 @@ impl FindReplaceDialog {
         fltk::group::Group::set_current(current_group.as_ref());
 
         popups.borrow_mut().push(dialog.clone());
-        let session_snapshot = FIND_REPLACE_SESSION.with(|state| state.borrow().clone());
 
         if !session_snapshot.find_text.is_empty() {
             find_input.set_value(&session_snapshot.find_text);
         }
-        case_check.set_value(session_snapshot.case_sensitive);
 
         if let Some(mut replace_input_widget) = replace_input.clone() {
             if !session_snapshot.replace_text.is_empty() {
@@ -237,6 +289,22 @@ impl FindReplaceDialog {
             }
         }
 
+        // "In selection" searches the range selected when the dialog opened;
+        // a selection spanning several lines turns it on by default.
+        let mut scope = buffer
+            .selection_position()
+            .filter(|(start, end)| start != end)
+            .map(|(start, end)| (start.min(end) as usize, start.max(end) as usize));
+        match scope {
+            Some((start, end)) => {
+                let selected = buffer
+                    .text_range(start as i32, end as i32)
+                    .unwrap_or_default();
+                selection_check.set_value(selected.contains('\n'));
+            }
+            None => selection_check.deactivate(),
+        }
+
         // State for search
         let initial_search_pos = normalize_search_pos(&buffer.text(), session_snapshot.search_pos);
         let search_pos = Rc::new(RefCell::new(initial_search_pos));
@@ -247,7 +315,7 @@ impl FindReplaceDialog {
         // Find Next callback
         let sender_for_find = sender.clone();
         let find_input_clone = find_input.clone();
-        let case_check_clone = case_check.clone();
+        let option_checks_clone = option_checks.clone();
         find_next_btn.set_callback(move |_| {
             let search_text = find_input_clone.value();
             if search_text.is_empty() {
@@ -256,7 +324,7 @@ impl FindReplaceDialog {
 
             let _ = sender_for_find.send(DialogMessage::FindNext {
                 search_text,
-                case_sensitive: case_check_clone.value(),
+                options: option_checks_clone.options(),
             });
             app::awake();
         });
@@ -264,7 +332,7 @@ impl FindReplaceDialog {
         // Enter key in find input triggers Find Next
         let sender_for_find_enter = sender.clone();
         let find_input_enter = find_input.clone();
-        let case_check_enter = case_check.clone();
+        let option_checks_enter = option_checks.clone();
         find_input.set_callback(move |_| {
             let search_text = find_input_enter.value();
             if search_text.is_empty() {
@@ -272,7 +340,7 @@ impl FindReplaceDialog {
             }
             let _ = sender_for_find_enter.send(DialogMessage::FindNext {
                 search_text,
-                case_sensitive: case_check_enter.value(),
+                options: option_checks_enter.options(),
             });
             app::awake();
         });
@@ -288,7 +356,7 @@ impl FindReplaceDialog {
                     return;
                 }
             };
-            let case_check_clone = case_check.clone();
+            let option_checks_clone = option_checks.clone();
 
             replace_btn.set_callback(move |_| {
                 let search_text = find_input_clone.value();
@@ -301,7 +369,7 @@ impl FindReplaceDialog {
                 let _ = sender_for_replace.send(DialogMessage::Replace {
                     search_text,
                     replace_text,
-                    case_sensitive: case_check_clone.value(),
+                    options: option_checks_clone.options(),
                 });
                 app::awake();
             });
@@ -318,7 +386,7 @@ impl FindReplaceDialog {
                     return;
                 }
             };
-            let case_check_clone = case_check.clone();
+            let option_checks_clone = option_checks.clone();
 
             replace_all_btn.set_callback(move |_| {
                 let search_text = find_input_clone.value();
@@ -331,7 +399,7 @@ impl FindReplaceDialog {
                 let _ = sender_for_replace_all.send(DialogMessage::ReplaceAll {
                     search_text,
                     replace_text,
-                    case_sensitive: case_check_clone.value(),
+                    options: option_checks_clone.options(),
                 });
                 app::awake();
             });
@@ -350,36 +418,48 @@ impl FindReplaceDialog {
         let mut editor = editor.clone();
         let find_input_state = find_input.clone();
         let replace_input_state = replace_input.clone();
-        let case_check_state = case_check.clone();
+        let option_checks_state = option_checks.clone();
         let search_pos_state = search_pos.clone();
         let last_search_text_state = last_search_text.clone();
 
         while dialog.shown() {
             fltk::app::wait();
             while let Ok(message) = receiver.try_recv() {
+                let text = buffer.text();
+                let (range_start, range_end) = match scope {
+                    Some(range) if selection_check.value() => range,
+                    _ => (0, text.len()),
+                };
                 match message {
                     DialogMessage::FindNext {
                         search_text,
-                        case_sensitive,
+                        options,
                     } => {
+                        let regex = match build_search_regex(&search_text, options) {
+                            Ok(regex) => regex,
+                            Err(err) => {
+                                fltk::dialog::alert_default(&err);
+                                continue;
+                            }
+                        };
                         if *last_search_text.borrow() != search_text {
-                            *search_pos.borrow_mut() = 0;
+                            *search_pos.borrow_mut() = range_start as i32;
                             *last_search_text.borrow_mut() = search_text.clone();
                         }
-                        let text = buffer.text();
-                        let start_pos = normalize_search_pos(&text, *search_pos.borrow());
+                        let start_pos = normalize_search_pos(&text, *search_pos.borrow())
+                            .max(range_start as i32);
                         *search_pos.borrow_mut() = start_pos;
 
                         if let Some((match_start, match_end)) =
-                            find_next_match(&text, &search_text, start_pos, case_sensitive)
+                            find_match_in(&text, &regex, options, start_pos as usize, range_end)
                         {
                             buffer.select(match_start as i32, match_end as i32);
                             editor.set_insert_position(match_end as i32);
                             editor.show_insert_position();
                             // Use match_end instead of match_start + 1 to avoid UTF-8 boundary issues
                             *search_pos.borrow_mut() = match_end.min(text.len()) as i32;
-                        } else if start_pos > 0 {
-                            *search_pos.borrow_mut() = 0;
+                        } else if start_pos as usize > range_start {
+                            *search_pos.borrow_mut() = range_start as i32;
                             fltk::dialog::message_default(
                                 "Reached end, searching from beginning...",
                             );
@@ -390,24 +470,40 @@ impl FindReplaceDialog {
                     DialogMessage::Replace {
                         search_text,
                         replace_text,
-                        case_sensitive,
+                        options,
                     } => {
+                        let regex = match build_search_regex(&search_text, options) {
+                            Ok(regex) => regex,
+                            Err(err) => {
+                                fltk::dialog::alert_default(&err);
+                                continue;
+                            }
+                        };
                         if *last_search_text.borrow() != search_text {
                             *last_search_text.borrow_mut() = search_text.clone();
                         }
                         if let Some((start, end)) = buffer.selection_position() {
-                            let selected = buffer.text_range(start, end).unwrap_or_default();
-                            let matches = if case_sensitive {
-                                selected == search_text
-                            } else {
-                                selected.to_ascii_lowercase() == search_text.to_ascii_lowercase()
-                            };
-
-                            if matches {
-                                buffer.remove(start, end);
-                                buffer.insert(start, &replace_text);
-                                let next_pos = start + replace_text.len() as i32;
+                            let (start, end) = (start as usize, end as usize);
+                            let is_match = find_match_in(&text, &regex, options, start, range_end)
+                                == Some((start, end));
+
+                            if is_match {
+                                let replacement = expand_replacement(
+                                    &text,
+                                    &regex,
+                                    options,
+                                    start,
+                                    &replace_text,
+                                );
+                                buffer.replace(start as i32, end as i32, &replacement);
+                                let next_pos = (start + replacement.len()) as i32;
                                 editor.set_insert_position(next_pos);
+                                if let Some((scope_start, scope_end)) = scope {
+                                    scope = Some((
+                                        scope_start,
+                                        (scope_end + replacement.len()).saturating_sub(end - start),
+                                    ));
+                                }
                                 *search_pos.borrow_mut() =
                                     normalize_search_pos(&buffer.text(), next_pos);
                             }
@@ -416,61 +512,47 @@ impl FindReplaceDialog {
                     DialogMessage::ReplaceAll {
                         search_text,
                         replace_text,
-                        case_sensitive,
+                        options,
                     } => {
                         if *last_search_text.borrow() != search_text {
                             *last_search_text.borrow_mut() = search_text.clone();
                         }
-                        if search_text.is_empty() {
-                            fltk::dialog::message_default("Search text is empty");
-                            continue;
-                        }
-                        let text = buffer.text();
-                        let new_text = if case_sensitive {
-                            text.replace(&search_text, &replace_text)
-                        } else {
-                            let mut result = String::with_capacity(text.len());
-                            let mut search_pos = 0usize;
-                            while let Some((match_start, match_end)) =
-                                find_next_match(&text, &search_text, search_pos as i32, false)
-                            {
-                                result.push_str(text.get(search_pos..match_start).unwrap_or(""));
-                                result.push_str(&replace_text);
-                                search_pos = match_end;
-                                if search_pos >= text.len() {
-                                    break;
-                                }
+                        let regex = match build_search_regex(&search_text, options) {
+                            Ok(regex) => regex,
+                            Err(err) => {
+                                fltk::dialog::alert_default(&err);
+                                continue;
                             }
-                            result.push_str(text.get(search_pos..).unwrap_or(""));
-                            result
                         };
+                        let (replaced, count) = replace_all_in(
+                            &text,
+                            &regex,
+                            options,
+                            range_start,
+                            range_end,
+                            &replace_text,
+                        );
 
-                        let count = if case_sensitive {
-                            text.matches(&search_text).count()
-                        } else {
-                            let mut count = 0usize;
-                            let mut search_pos = 0usize;
-                            while let Some((_match_start, match_end)) =
-                                find_next_match(&text, &search_text, search_pos as i32, false)
-                            {
-                                count += 1;
-                                search_pos = match_end;
-                                if search_pos >= text.len() {
-                                    break;
-                                }
+                        if count > 0 {
+                            // One buffer edit, so the word undo history records
+                            // the whole replacement as a single step.
+                            buffer.replace(range_start as i32, range_end as i32, &replaced);
+                            if let Some((scope_start, _)) = scope {
+                                scope = Some((scope_start, scope_start + replaced.len()));
+                                buffer.select(
+                                    scope_start as i32,
+                                    (scope_start + replaced.len()) as i32,
+                                );
                             }
-                            count
-                        };
-
-                        buffer.set_text(&new_text);
-                        *search_pos.borrow_mut() = 0;
+                        }
+                        *search_pos.borrow_mut() = range_start as i32;
                         fltk::dialog::message_default(&format!("Replaced {} occurrences", count));
                     }
                     DialogMessage::Close => {
                         save_find_replace_state(
                             &find_input_state,
                             replace_input_state.as_ref(),
-                            &case_check_state,
+                            option_checks_state.options(),
                             *search_pos_state.borrow(),
                             &last_search_text_state.borrow(),
                         );
@@ -482,7 +564,7 @@ impl FindReplaceDialog {
                     save_find_replace_state(
                         &find_input_state,
                         replace_input_state.as_ref(),
-                        &case_check_state,
+                        option_checks_state.options(),
                         *search_pos_state.borrow(),
                         &last_search_text_state.borrow(),
                     );
@@ -493,7 +575,7 @@ impl FindReplaceDialog {
         save_find_replace_state(
             &find_input_state,
             replace_input_state.as_ref(),
-            &case_check_state,
+            option_checks_state.options(),
             *search_pos_state.borrow(),
             &last_search_text_state.borrow(),
         );
@@ -509,6 +591,9 @@ impl FindReplaceDialog {
         if session.find_text.is_empty() {
             return false;
         }
+        let Ok(regex) = build_search_regex(&session.find_text, session.options) else {
+            return false;
+        };
 
         let text = buffer.text();
         let start_pos = if session.last_search_text != session.find_text {
@@ -517,14 +602,20 @@ impl FindReplaceDialog {
             normalize_search_pos(&text, session.search_pos)
         };
 
-        let found = find_next_match(&text, &session.find_text, start_pos, session.case_sensitive)
-            .or_else(|| {
-                if start_pos > 0 {
-                    find_next_match(&text, &session.find_text, 0, session.case_sensitive)
-                } else {
-                    None
-                }
-            });
+        let found = find_match_in(
+            &text,
+            &regex,
+            session.options,
+            start_pos as usize,
+            text.len(),
+        )
+        .or_else(|| {
+            if start_pos > 0 {
+                find_match_in(&text, &regex, session.options, 0, text.len())
+            } else {
+                None
+            }
+        });
 
         if let Some((match_start, match_end)) = found {
             buffer.select(match_start as i32, match_end as i32);
@@ -549,61 +640,213 @@ impl FindReplaceDialog {
         search_text: &str,
         case_sensitive: bool,
     ) -> bool {
-        if search_text.is_empty() {
+        let options = SearchOptions {
+            case_sensitive,
+            ..SearchOptions::default()
+        };
+        let Ok(regex) = build_search_regex(search_text, options) else {
             return false;
-        }
+        };
 
         let current_pos = editor.insert_position();
         let text = buffer.text();
-        let start_pos = normalize_search_pos(&text, current_pos);
+        let start_pos = normalize_search_pos(&text, current_pos) as usize;
 
-        if let Some((match_start, match_end)) =
-            find_next_match(&text, search_text, start_pos, case_sensitive)
-        {
+        // Try from the cursor, then from the beginning
+        let found = find_match_in(&text, &regex, options, start_pos, text.len())
+            .or_else(|| find_match_in(&text, &regex, options, 0, text.len()));
+        if let Some((match_start, match_end)) = found {
             buffer.select(match_start as i32, match_end as i32);
             editor.set_insert_position(match_end as i32);
             editor.show_insert_position();
             true
         } else {
-            // Try from beginning
-            if let Some((match_start, match_end)) =
-                find_next_match(&text, search_text, 0, case_sensitive)
-            {
-                buffer.select(match_start as i32, match_end as i32);
-                editor.set_insert_position(match_end as i32);
-                editor.show_insert_position();
-                true
-            } else {
-                false
-            }
+            false
         }
     }
 }
 
-fn find_next_match(
+/// Compile the search text into a regex honouring `options`. Plain text is
+/// escaped, so every mode is matched the same way.
+pub fn build_search_regex(search_text: &str, options: SearchOptions) -> Result<Regex, String> {
+    if search_text.is_empty() {
+        return Err("Search text is empty".to_string());
+    }
+    let pattern = if options.use_regex {
+        search_text.to_string()
+    } else {
+        regex::escape(search_text)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|err| format!("Invalid regular expression: {err}"))
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '$' || ch == '#'
+}
+
+fn is_whole_word(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+}
+
+/// First non-empty match starting in `start..end` and ending by `end`.
+pub fn find_match_in(
     text: &str,
-    search_text: &str,
-    start_pos: i32,
-    case_sensitive: bool,
+    regex: &Regex,
+    options: SearchOptions,
+    start: usize,
+    end: usize,
 ) -> Option<(usize, usize)> {
-    if search_text.is_empty() || text.is_empty() {
-        return None;
+    let end = normalize_search_pos(text, end as i32) as usize;
+    let haystack = &text[..end];
+    let mut pos = normalize_search_pos(text, start as i32) as usize;
+    while pos <= end {
+        let found = regex.find_at(haystack, pos)?;
+        if !found.is_empty()
+            && (!options.whole_word || is_whole_word(text, found.start(), found.end()))
+        {
+            return Some((found.start(), found.end()));
+        }
+        // Retry one character further on.
+        pos = found.start()
+            + haystack[found.start()..]
+                .chars()
+                .next()
+                .map_or(1, char::len_utf8);
     }
-    let start_pos = if start_pos < 0 { 0 } else { start_pos as usize };
-    let Some(haystack) = text.get(start_pos..) else {
-        return None;
-    };
-    if case_sensitive {
-        let pos = haystack.find(search_text)?;
-        let match_start = start_pos + pos;
-        let match_end = match_start + search_text.len();
-        return Some((match_start, match_end));
+    None
+}
+
+/// Every match in `start..end`, in order and without overlaps.
+pub fn find_all_matches(
+    text: &str,
+    regex: &Regex,
+    options: SearchOptions,
+    start: usize,
+    end: usize,
+) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let mut pos = start;
+    while let Some((match_start, match_end)) = find_match_in(text, regex, options, pos, end) {
+        matches.push((match_start, match_end));
+        pos = match_end;
+    }
+    matches
+}
+
+/// The replacement for the match starting at `match_start`, with capture
+/// group references expanded in regex mode.
+fn expand_replacement(
+    text: &str,
+    regex: &Regex,
+    options: SearchOptions,
+    match_start: usize,
+    replacement: &str,
+) -> String {
+    if !options.use_regex {
+        return replacement.to_string();
+    }
+    let mut expanded = String::new();
+    if let Some(captures) = regex.captures_at(text, match_start) {
+        captures.expand(replacement, &mut expanded);
+    }
+    expanded
+}
+
+/// Replace every match in `start..end`. Returns the new text of that range
+/// and the number of replacements.
+pub fn replace_all_in(
+    text: &str,
+    regex: &Regex,
+    options: SearchOptions,
+    start: usize,
+    end: usize,
+    replacement: &str,
+) -> (String, usize) {
+    let matches = find_all_matches(text, regex, options, start, end);
+    let mut result = String::with_capacity(end.saturating_sub(start));
+    let mut pos = start;
+    for (match_start, match_end) in &matches {
+        result.push_str(&text[pos..*match_start]);
+        result.push_str(&expand_replacement(
+            &text[..end],
+            regex,
+            options,
+            *match_start,
+            replacement,
+        ));
+        pos = *match_end;
+    }
+    result.push_str(&text[pos..end]);
+    (result, matches.len())
+}
+
+#[cfg(test)]
+mod find_replace_tests {
+    use super::*;
+
+    fn options(case_sensitive: bool, whole_word: bool, use_regex: bool) -> SearchOptions {
+        SearchOptions {
+            case_sensitive,
+            whole_word,
+            use_regex,
+        }
     }
 
-    let haystack_lower = haystack.to_ascii_lowercase();
-    let search_lower = search_text.to_ascii_lowercase();
-    let pos = haystack_lower.find(&search_lower)?;
-    let match_start = start_pos + pos;
-    let match_end = match_start + search_text.len();
-    Some((match_start, match_end))
+    #[test]
+    fn plain_search_escapes_regex_syntax_and_ignores_case() {
+        let text = "select a.* from emp a where A.* is not null";
+        let opts = options(false, false, false);
+        let regex = build_search_regex("a.*", opts).unwrap();
+        assert_eq!(
+            find_all_matches(text, &regex, opts, 0, text.len()),
+            vec![(7, 10), (28, 31)]
+        );
+    }
+
+    #[test]
+    fn whole_word_skips_identifier_substrings() {
+        let text = "emp emp_id emp$x xemp (emp)";
+        let opts = options(false, true, false);
+        let regex = build_search_regex("emp", opts).unwrap();
+        assert_eq!(
+            find_all_matches(text, &regex, opts, 0, text.len()),
+            vec![(0, 3), (23, 26)]
+        );
+    }
+
+    #[test]
+    fn regex_replace_expands_capture_groups() {
+        let text = "v_name := :name;\nv_id := :id;";
+        let opts = options(true, false, true);
+        let regex = build_search_regex(r"(\w+) := :(\w+)", opts).unwrap();
+        let (replaced, count) = replace_all_in(text, &regex, opts, 0, text.len(), "$2 => $1");
+        assert_eq!(count, 2);
+        assert_eq!(replaced, "name => v_name;\nid => v_id;");
+    }
+
+    #[test]
+    fn replace_all_stays_inside_the_range() {
+        let text = "x x x x";
+        let opts = options(true, false, false);
+        let regex = build_search_regex("x", opts).unwrap();
+        let (replaced, count) = replace_all_in(text, &regex, opts, 2, 5, "y");
+        assert_eq!((replaced.as_str(), count), ("y y", 2));
+    }
+
+    #[test]
+    fn empty_matches_are_skipped_and_bad_patterns_reported() {
+        let text = "abc";
+        let opts = options(true, false, true);
+        let regex = build_search_regex("x*", opts).unwrap();
+        assert_eq!(find_match_in(text, &regex, opts, 0, text.len()), None);
+        assert!(build_search_regex("(", opts)
+            .unwrap_err()
+            .starts_with("Invalid regular expression"));
+    }
 }
@@ -791,6 +791,9 @@ pub struct IntellisenseData {
     pub views: Vec<String>,
     pub procedures: Vec<String>,
     pub functions: Vec<String>,
+    /// Schemas visible to the connected user, used to resolve `OWNER.NAME`
+    /// references to relations outside the user's own schema.
+    pub schemas: Vec<String>,
     table_entries: Vec<NameEntry>,
     view_entries: Vec<NameEntry>,
     procedure_entries: Vec<NameEntry>,
@@ -814,6 +817,7 @@ impl IntellisenseData {
             views: Vec::new(),
             procedures: Vec::new(),
             functions: Vec::new(),
+            schemas: Vec::new(),
             table_entries: Vec::new(),
             view_entries: Vec::new(),
             procedure_entries: Vec::new(),
@@ -1119,6 +1123,12 @@ impl IntellisenseData {
             || self.views.iter().any(|v| v.to_uppercase() == upper)
     }
 
+    pub fn is_known_schema(&self, name: &str) -> bool {
+        self.schemas
+            .iter()
+            .any(|schema| schema.eq_ignore_ascii_case(name))
+    }
+
     pub fn rebuild_indices(&mut self) {
         self.table_entries = Self::build_entries(&self.tables);
         self.view_entries = Self::build_entries(&self.views);
@@ -3,7 +3,7 @@ use fltk::{
     button::Button,
     dialog::{FileDialog, FileDialogType},
     draw::set_cursor,
-    enums::{Cursor, FrameType},
+    enums::{Color, Cursor, FrameType},
     frame::Frame,
     group::{Flex, FlexType, Group, Tile},
     input::IntInput,
@@ -18,24 +18,31 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::rc::{Rc, Weak};
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::db::{
-    create_shared_connection, lock_connection, try_lock_connection, ObjectBrowser, QueryResult,
-    SharedConnection,
+    compile_source, create_shared_connection, export_query_to_file, export_rows_to_file,
+    lock_connection, object_last_ddl_time, try_lock_connection, DatabaseConnection, EditableSource,
+    ObjectBrowser, QueryExecutor, QueryProgress, ScriptRunner, SharedConnection, SourceDiagnostic,
+    SourceObject, StatementGuard, TransactionState,
 };
 use crate::ui::constants::*;
 use crate::ui::theme;
 use crate::ui::{
-    font_settings, show_settings_dialog, ConnectionDialog, FindReplaceDialog, HighlightData,
-    IntellisenseData, MenuBarBuilder, ObjectBrowserWidget, QueryHistoryDialog, QueryProgress,
-    QueryTabId, QueryTabsWidget, ResultTabsWidget, SqlAction, SqlEditorWidget,
+    font_settings, show_data_compare_dialog, show_debugger_dialog, show_export_dialog,
+    show_find_in_tabs_dialog, show_savepoint_dialog, show_schema_compare_dialog,
+    show_session_browser_dialog, show_settings_dialog, show_snippet_dialog, show_top_sql_dialog,
+    CompareDialog, CompareEndpoint, ConnectionDialog, FindReplaceDialog, HighlightData,
+    IntellisenseData, MenuBarBuilder, ObjectBrowserWidget, QueryHistoryDialog, QueryTabId,
+    QueryTabsWidget, ResultTabsWidget, SqlAction, SqlEditorWidget, TabSearchSource,
 };
 use crate::utils::{AppConfig, QueryHistory};
 
 #[derive(Clone)]
 struct SchemaUpdate {
+    connection: SharedConnection,
     data: IntellisenseData,
     highlight_data: HighlightData,
 }
@@ -48,9 +55,18 @@ struct QueryEditorTab {
     sql_buffer: TextBuffer,
     current_file: Option<PathBuf>,
     is_dirty: bool,
+    /// Tabs opened with "New Tab" share the connection of the tab they were
+    /// opened from; "New Tab on New Connection..." gets its own session.
+    connection: SharedConnection,
+    connection_info: Option<crate::db::ConnectionInfo>,
+    /// Stored program the tab edits; saving compiles it.
+    source_object: Option<SourceObject>,
+    /// Last known transaction state of the tab's session.
+    transaction: TransactionState,
 }
 
 pub struct AppState {
+    /// Connection of the active query tab.
     pub connection: SharedConnection,
     query_tabs: QueryTabsWidget,
     query_top_group: Group,
@@ -64,6 +80,16 @@ pub struct AppState {
     pub result_tab_offset: usize,
     pub object_browser: ObjectBrowserWidget,
     pub status_bar: Frame,
+    /// Right end of the status bar; says when the active session has an
+    /// open transaction.
+    transaction_indicator: Frame,
+    /// Left end of the status bar; names the environment of the active
+    /// connection in its color.
+    environment_badge: Frame,
+    status_row: Flex,
+    /// Its margin shows the window background, which takes the
+    /// environment color, as a border.
+    main_flex: Flex,
     pub fetch_row_counts: HashMap<usize, usize>,
     pub current_file: Rc<RefCell<Option<PathBuf>>>,
     pub popups: Rc<RefCell<Vec<Window>>>,
@@ -75,21 +101,54 @@ pub struct AppState {
     pub last_fetch_status_update: Instant,
     schema_sender: Option<std::sync::mpsc::Sender<SchemaUpdate>>,
     file_sender: Option<std::sync::mpsc::Sender<FileActionResult>>,
+    conn_sender: Option<std::sync::mpsc::Sender<ConnectionResult>>,
+    /// Set from the reconnect prompt until the reconnect it started ends.
+    reconnect_prompt_open: bool,
 }
 
 impl AppState {
+    /// One compare endpoint per connected session, named after its
+    /// connection.
+    fn compare_endpoints(&self) -> Vec<CompareEndpoint> {
+        let mut endpoints: Vec<CompareEndpoint> = Vec::new();
+        for tab in &self.editor_tabs {
+            let Some(info) = &tab.connection_info else {
+                continue;
+            };
+            if endpoints
+                .iter()
+                .any(|endpoint| Arc::ptr_eq(&endpoint.connection, &tab.connection))
+            {
+                continue;
+            }
+            let mut label = info.name.clone();
+            if endpoints.iter().any(|endpoint| endpoint.label == label) {
+                label = format!("{} ({})", info.name, tab.base_label);
+            }
+            endpoints.push(CompareEndpoint {
+                label,
+                connection: tab.connection.clone(),
+            });
+        }
+        endpoints
+    }
+
     fn tab_display_label(tab: &QueryEditorTab) -> String {
-        let mut label = match &tab.current_file {
-            Some(path) => path
+        let mut label = match (&tab.source_object, &tab.current_file) {
+            (Some(object), _) => object.label(),
+            (None, Some(path)) => path
                 .file_name()
                 .unwrap_or_default()
                 .to_string_lossy()
                 .to_string(),
-            None => tab.base_label.clone(),
+            (None, None) => tab.base_label.clone(),
         };
         if tab.is_dirty {
             label.push('*');
         }
+        if let Some(info) = &tab.connection_info {
+            label.push_str(&format!(" @ {}", info.name));
+        }
         label
     }
 
@@ -115,10 +174,128 @@ impl AppState {
         self.sql_editor = tab.sql_editor;
         self.sql_buffer = tab.sql_buffer;
         *self.current_file.borrow_mut() = tab.current_file;
+        if !Arc::ptr_eq(&self.connection, &tab.connection) {
+            self.connection = tab.connection.clone();
+            *self.connection_info.borrow_mut() = tab.connection_info.clone();
+            let status = match &tab.connection_info {
+                Some(info) => format!("Connected | {}", info.display_string()),
+                None => "Not connected".to_string(),
+            };
+            self.status_bar.set_label(&status);
+            // Auto-commit is per connection; keep the menu check in sync.
+            let auto_commit = try_lock_connection(&tab.connection).map(|conn| conn.auto_commit());
+            if let (Some(enabled), Some(menu)) =
+                (auto_commit, app::widget_from_id::<MenuBar>("main_menu"))
+            {
+                if let Some(mut item) = menu.find_item("&Tools/&Auto-Commit\t") {
+                    if enabled {
+                        item.set();
+                    } else {
+                        item.clear();
+                    }
+                }
+            }
+            self.object_browser.set_connection(tab.connection);
+        }
         self.refresh_window_title();
+        self.refresh_transaction_indicator();
+        self.refresh_environment_marks();
         true
     }
 
+    /// Record the connection `info` on every tab sharing `connection` and
+    /// relabel them.
+    fn set_connection_info(
+        &mut self,
+        connection: &SharedConnection,
+        info: Option<crate::db::ConnectionInfo>,
+    ) {
+        for index in 0..self.editor_tabs.len() {
+            if !Arc::ptr_eq(&self.editor_tabs[index].connection, connection) {
+                continue;
+            }
+            self.editor_tabs[index].connection_info = info.clone();
+            // A new session, or none, has no open transaction.
+            self.editor_tabs[index].transaction = TransactionState::default();
+            let tab_id = self.editor_tabs[index].tab_id;
+            let label = Self::tab_display_label(&self.editor_tabs[index]);
+            self.query_tabs.set_tab_label(tab_id, &label);
+        }
+        if Arc::ptr_eq(&self.connection, connection) {
+            *self.connection_info.borrow_mut() = info;
+        }
+        self.refresh_window_title();
+        self.refresh_transaction_indicator();
+        self.refresh_environment_marks();
+    }
+
+    /// Record the transaction state of `connection` on every tab sharing it.
+    fn set_transaction_state(&mut self, connection: &SharedConnection, state: &TransactionState) {
+        for tab in self
+            .editor_tabs
+            .iter_mut()
+            .filter(|tab| Arc::ptr_eq(&tab.connection, connection))
+        {
+            tab.transaction = state.clone();
+        }
+        self.refresh_transaction_indicator();
+    }
+
+    fn refresh_transaction_indicator(&mut self) {
+        let summary = self
+            .find_tab_index(self.active_editor_tab_id)
+            .map(|index| self.editor_tabs[index].transaction.summary())
+            .unwrap_or_default();
+        if summary.is_empty() {
+            self.transaction_indicator.set_color(theme::accent());
+        } else {
+            self.transaction_indicator
+                .set_color(theme::button_warning());
+        }
+        self.transaction_indicator.set_label(&summary);
+        self.transaction_indicator.redraw();
+    }
+
+    /// Color the tabs of tagged connections, and the window border and
+    /// status bar badge after the active one.
+    fn refresh_environment_marks(&mut self) {
+        fn environment_color(info: Option<&crate::db::ConnectionInfo>) -> Option<Color> {
+            info.and_then(|info| info.environment_rgb())
+                .map(|(r, g, b)| Color::from_rgb(r, g, b))
+        }
+
+        for index in 0..self.editor_tabs.len() {
+            let tab = &self.editor_tabs[index];
+            let color = environment_color(tab.connection_info.as_ref());
+            let tab_id = tab.tab_id;
+            self.query_tabs.set_tab_color(tab_id, color);
+        }
+
+        let info = self.connection_info.borrow().clone();
+        match (environment_color(info.as_ref()), info) {
+            (Some(color), Some(info)) => {
+                self.environment_badge.set_label(info.environment.label());
+                self.environment_badge.set_color(color);
+                self.environment_badge.show();
+                self.main_flex.set_margin(ENVIRONMENT_BORDER_WIDTH);
+                self.window.set_color(color);
+            }
+            _ => {
+                self.environment_badge.hide();
+                self.main_flex.set_margin(0);
+                self.window.set_color(theme::window_bg());
+            }
+        }
+        self.status_row.layout();
+        self.main_flex.layout();
+        self.window.redraw();
+    }
+
+    fn tab_connection(&self, tab_id: QueryTabId) -> Option<SharedConnection> {
+        self.find_tab_index(tab_id)
+            .map(|index| self.editor_tabs[index].connection.clone())
+    }
+
     fn is_any_query_running(&self) -> bool {
         self.editor_tabs
             .iter()
@@ -161,6 +338,18 @@ impl AppState {
         }
     }
 
+    fn set_tab_source_object(&mut self, tab_id: QueryTabId, object: Option<SourceObject>) {
+        let Some(index) = self.find_tab_index(tab_id) else {
+            return;
+        };
+        self.editor_tabs[index].source_object = object;
+        let label = Self::tab_display_label(&self.editor_tabs[index]);
+        self.query_tabs.set_tab_label(tab_id, &label);
+        if self.active_editor_tab_id == tab_id {
+            self.refresh_window_title();
+        }
+    }
+
     fn set_tab_file_path(&mut self, tab_id: QueryTabId, path: Option<PathBuf>) {
         let Some(index) = self.find_tab_index(tab_id) else {
             return;
@@ -192,8 +381,19 @@ pub struct MainWindow {
 
 #[derive(Clone)]
 enum ConnectionResult {
-    Success(crate::db::ConnectionInfo),
+    Success {
+        connection: SharedConnection,
+        info: Box<crate::db::ConnectionInfo>,
+        /// Session settings or login script statements that failed.
+        init_errors: Vec<String>,
+    },
     Failure(String),
+    /// A lost connection was connected again, or failed to.
+    Reconnected {
+        connection: SharedConnection,
+        result: Result<Box<crate::db::ConnectionInfo>, String>,
+        init_errors: Vec<String>,
+    },
 }
 
 enum FileActionResult {
@@ -203,13 +403,34 @@ enum FileActionResult {
     },
     Export {
         path: PathBuf,
-        row_count: usize,
-        result: Result<(), String>,
+        result: Result<usize, String>,
     },
+    /// Saving a stored-program tab compiled `sql_text`, or stopped first.
+    Compile {
+        tab_id: QueryTabId,
+        object: SourceObject,
+        sql_text: String,
+        result: CompileResult,
+    },
+}
+
+/// What saving a stored-program tab did in the database.
+enum CompileResult {
+    /// The object was changed in the database at this time after the tab
+    /// opened it; nothing was compiled.
+    Changed(String),
+    Compiled {
+        diagnostics: Vec<SourceDiagnostic>,
+        last_ddl_time: String,
+    },
+    Failed(String),
 }
 
 enum SaveTabOutcome {
     Saved,
+    /// Compiling on a background thread; the result arrives through
+    /// `file_sender`.
+    Compiling,
     Cancelled,
     Failed(String),
 }
@@ -220,6 +441,15 @@ impl MainWindow {
         tab_id: QueryTabId,
         force_save_as: bool,
     ) -> SaveTabOutcome {
+        let edits_source = {
+            let s = state.borrow();
+            s.find_tab_index(tab_id)
+                .is_some_and(|index| s.editor_tabs[index].source_object.is_some())
+        };
+        if edits_source && !force_save_as {
+            return Self::compile_source_tab(state, tab_id);
+        }
+
         let (current_file, sql_text) = {
             let s = state.borrow();
             let Some(sql_text) = s.tab_sql_text(tab_id) else {
@@ -263,6 +493,160 @@ impl MainWindow {
         SaveTabOutcome::Saved
     }
 
+    /// Saving a tab tied to a stored program compiles it in the database,
+    /// after a warning when the object changed since it was opened.
+    fn compile_source_tab(state: &Rc<RefCell<AppState>>, tab_id: QueryTabId) -> SaveTabOutcome {
+        let (connection, object, sql_text, file_sender) = {
+            let s = state.borrow();
+            let Some(index) = s.find_tab_index(tab_id) else {
+                return SaveTabOutcome::Cancelled;
+            };
+            let tab = &s.editor_tabs[index];
+            let Some(object) = tab.source_object.clone() else {
+                return SaveTabOutcome::Cancelled;
+            };
+            let Some(file_sender) = s.file_sender.clone() else {
+                return SaveTabOutcome::Cancelled;
+            };
+            (
+                tab.connection.clone(),
+                object,
+                tab.sql_buffer.text(),
+                file_sender,
+            )
+        };
+
+        {
+            let Some(conn_guard) = try_lock_connection(&connection) else {
+                return SaveTabOutcome::Failed(
+                    "Connection is busy. Try again after the current operation finishes."
+                        .to_string(),
+                );
+            };
+            if conn_guard.get_connection().is_none() {
+                return SaveTabOutcome::Failed("Not connected to database".to_string());
+            }
+            if let Some(guards) = conn_guard.get_info().production_guards() {
+                if let StatementGuard::Refused(reason) =
+                    QueryExecutor::check_statement_guard(&sql_text, guards)
+                {
+                    return SaveTabOutcome::Failed(reason);
+                }
+            }
+        }
+
+        state
+            .borrow_mut()
+            .status_bar
+            .set_label(&format!("Compiling {}...", object.label()));
+        Self::spawn_source_compile(connection, tab_id, object, sql_text, false, file_sender);
+        SaveTabOutcome::Compiling
+    }
+
+    /// Compile the text of a stored-program tab on a background thread and
+    /// report through `file_sender`. Unless `overwrite` is set, an object
+    /// changed in the database since the tab opened it is left alone.
+    fn spawn_source_compile(
+        connection: SharedConnection,
+        tab_id: QueryTabId,
+        object: SourceObject,
+        sql_text: String,
+        overwrite: bool,
+        file_sender: std::sync::mpsc::Sender<FileActionResult>,
+    ) {
+        thread::spawn(move || {
+            let result = match try_lock_connection(&connection) {
+                None => CompileResult::Failed(
+                    "Connection is busy. Try again after the current operation finishes."
+                        .to_string(),
+                ),
+                Some(conn_guard) => match conn_guard.get_connection() {
+                    None => CompileResult::Failed("Not connected to database".to_string()),
+                    Some(db_conn) => {
+                        let changed_at = object_last_ddl_time(db_conn.as_ref(), &object)
+                            .ok()
+                            .filter(|changed_at| *changed_at != object.last_ddl_time);
+                        match changed_at {
+                            Some(changed_at) if !overwrite => CompileResult::Changed(changed_at),
+                            _ => match compile_source(db_conn.as_ref(), &sql_text) {
+                                Ok(diagnostics) => CompileResult::Compiled {
+                                    diagnostics,
+                                    last_ddl_time: object_last_ddl_time(db_conn.as_ref(), &object)
+                                        .unwrap_or_else(|_| object.last_ddl_time.clone()),
+                                },
+                                Err(err) => CompileResult::Failed(err),
+                            },
+                        }
+                    }
+                },
+            };
+            let _ = file_sender.send(FileActionResult::Compile {
+                tab_id,
+                object,
+                sql_text,
+                result,
+            });
+            app::awake();
+        });
+    }
+
+    /// Show the outcome of compiling a stored-program tab. Returns the
+    /// change time when the object changed in the database and the user
+    /// has to decide whether to compile over it.
+    fn finish_source_compile(
+        s: &mut AppState,
+        tab_id: QueryTabId,
+        object: &SourceObject,
+        sql_text: &str,
+        result: CompileResult,
+    ) -> Option<String> {
+        let (diagnostics, last_ddl_time) = match result {
+            CompileResult::Changed(changed_at) => return Some(changed_at),
+            CompileResult::Failed(err) => {
+                let conn_info = s.connection_info.borrow().clone();
+                s.status_bar.set_label(&format_status(
+                    &format!("Compile of {} failed", object.label()),
+                    &conn_info,
+                ));
+                fltk::dialog::alert_default(&format!("Failed to save SQL file: {}", err));
+                return None;
+            }
+            CompileResult::Compiled {
+                diagnostics,
+                last_ddl_time,
+            } => (diagnostics, last_ddl_time),
+        };
+        let index = s.find_tab_index(tab_id)?;
+        let errors = diagnostics.iter().filter(|d| !d.is_warning).count();
+        let warnings = diagnostics.len() - errors;
+        // Edits made while compiling keep the tab dirty.
+        let unchanged = s.editor_tabs[index].sql_buffer.text() == sql_text;
+        s.editor_tabs[index].sql_editor.set_diagnostics(diagnostics);
+        let message = if errors == 0 && warnings == 0 {
+            format!("Compiled {}", object.label())
+        } else {
+            format!(
+                "Compiled {} with {} error(s) and {} warning(s)",
+                object.label(),
+                errors,
+                warnings
+            )
+        };
+        s.set_tab_source_object(
+            tab_id,
+            Some(SourceObject {
+                last_ddl_time,
+                ..object.clone()
+            }),
+        );
+        if unchanged {
+            s.set_tab_dirty(tab_id, false);
+        }
+        let conn_info = s.connection_info.borrow().clone();
+        s.status_bar.set_label(&format_status(&message, &conn_info));
+        None
+    }
+
     fn confirm_save_if_dirty(
         state: &Rc<RefCell<AppState>>,
         tab_id: QueryTabId,
@@ -290,7 +674,8 @@ impl MainWindow {
         match choice {
             Some(1) => match Self::save_tab(state, tab_id, false) {
                 SaveTabOutcome::Saved => true,
-                SaveTabOutcome::Cancelled => false,
+                // The tab stays until the compile has finished.
+                SaveTabOutcome::Compiling | SaveTabOutcome::Cancelled => false,
                 SaveTabOutcome::Failed(err) => {
                     fltk::dialog::alert_default(&format!("Failed to save SQL file: {}", err));
                     false
@@ -311,6 +696,72 @@ impl MainWindow {
         true
     }
 
+    /// Ask whether to commit or roll back the open transaction of `db_conn`
+    /// before its session ends. Returns `false` when the user cancels.
+    fn confirm_pending_transaction(db_conn: &mut DatabaseConnection, action_verb: &str) -> bool {
+        if !db_conn.is_connected() || !db_conn.confirm_transaction() {
+            return true;
+        }
+
+        let choice = fltk::dialog::choice2_default(
+            &format!(
+                "{} has uncommitted changes ({}).\n\
+                 Do you want to commit or roll them back before {}?",
+                db_conn.get_info().display_string(),
+                db_conn.transaction().summary(),
+                action_verb
+            ),
+            "Cancel",
+            "Commit",
+            "Rollback",
+        );
+        let result = match choice {
+            Some(1) => db_conn
+                .commit_transaction()
+                .map_err(|err| format!("Commit failed: {}", err)),
+            Some(2) => db_conn
+                .rollback_transaction()
+                .map_err(|err| format!("Rollback failed: {}", err)),
+            _ => return false,
+        };
+        match result {
+            Ok(()) => true,
+            // A dead session must not keep the user from leaving it.
+            Err(err) => {
+                fltk::dialog::choice2_default(
+                    &format!(
+                        "{}\n\nContinue anyway? Uncommitted changes will be lost.",
+                        err
+                    ),
+                    "Cancel",
+                    "Continue",
+                    "",
+                ) == Some(1)
+            }
+        }
+    }
+
+    fn confirm_pending_transactions_for_all_sessions(state: &Rc<RefCell<AppState>>) -> bool {
+        let mut connections: Vec<SharedConnection> = Vec::new();
+        for tab in &state.borrow().editor_tabs {
+            if !connections
+                .iter()
+                .any(|connection| Arc::ptr_eq(connection, &tab.connection))
+            {
+                connections.push(tab.connection.clone());
+            }
+        }
+        for connection in connections {
+            // A busy session is running a query; leave it to the database.
+            if let Some(mut db_conn) = try_lock_connection(&connection) {
+                if !Self::confirm_pending_transaction(&mut db_conn, "exiting") {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
     pub fn new() -> Self {
         let config = AppConfig::load();
         let connection = create_shared_connection();
@@ -612,6 +1063,10 @@ impl MainWindow {
             sql_buffer: sql_buffer.clone(),
             current_file: None,
             is_dirty: false,
+            connection: connection.clone(),
+            connection_info: None,
+            source_object: None,
+            transaction: TransactionState::default(),
         }];
 
         right_flex.resizable(&right_tile);
@@ -621,11 +1076,25 @@ impl MainWindow {
         content_flex.end();
         main_flex.resizable(&content_flex);
 
+        let mut status_row = Flex::default();
+        status_row.set_type(FlexType::Row);
+        let mut environment_badge = Frame::default();
+        environment_badge.set_frame(FrameType::FlatBox);
+        environment_badge.set_label_color(Color::White);
+        environment_badge.set_label_font(fltk::enums::Font::HelveticaBold);
+        status_row.fixed(&environment_badge, ENVIRONMENT_BADGE_WIDTH);
+        environment_badge.hide();
         let mut status_bar = Frame::default().with_label("Not connected");
         status_bar.set_frame(FrameType::FlatBox);
         status_bar.set_color(theme::accent());
         status_bar.set_label_color(theme::text_primary());
-        main_flex.fixed(&status_bar, STATUS_BAR_HEIGHT);
+        let mut transaction_indicator = Frame::default();
+        transaction_indicator.set_frame(FrameType::FlatBox);
+        transaction_indicator.set_color(theme::accent());
+        transaction_indicator.set_label_color(theme::text_primary());
+        status_row.fixed(&transaction_indicator, TRANSACTION_INDICATOR_WIDTH);
+        status_row.end();
+        main_flex.fixed(&status_row, STATUS_BAR_HEIGHT);
         main_flex.end();
         window.end();
         window.make_resizable(true);
@@ -644,6 +1113,10 @@ impl MainWindow {
             result_tab_offset: 0,
             object_browser,
             status_bar,
+            transaction_indicator,
+            environment_badge,
+            status_row,
+            main_flex,
             fetch_row_counts: HashMap::new(),
             current_file: Rc::new(RefCell::new(None)),
             popups: Rc::new(RefCell::new(Vec::new())),
@@ -655,8 +1128,21 @@ impl MainWindow {
             last_fetch_status_update: Instant::now(),
             schema_sender: None,
             file_sender: None,
+            conn_sender: None,
+            reconnect_prompt_open: false,
         }));
 
+        let weak_state_for_grid_edit = Rc::downgrade(&state);
+        state
+            .borrow_mut()
+            .result_tabs
+            .set_edit_apply_handler(Rc::new(move |statements, on_done| {
+                if let Some(state_for_grid_edit) = weak_state_for_grid_edit.upgrade() {
+                    let sql_editor = state_for_grid_edit.borrow().sql_editor.clone();
+                    sql_editor.apply_grid_changes(statements, on_done);
+                }
+            }));
+
         let weak_state_for_execute = Rc::downgrade(&state);
         execute_btn.set_callback(move |_| {
             if let Some(state_for_execute) = weak_state_for_execute.upgrade() {
@@ -795,6 +1281,67 @@ impl MainWindow {
         }
     }
 
+    fn open_snippets_dialog(state: &Rc<RefCell<AppState>>) {
+        let popups = state.borrow().popups.clone();
+        let Some(snippet) = show_snippet_dialog(popups) else {
+            return;
+        };
+        // Placeholder prompts run their own event loop; don't hold the state.
+        let sql_editor = state.borrow().sql_editor.clone();
+        sql_editor.insert_snippet(&snippet);
+    }
+
+    fn open_compare_dialog(
+        state: &Rc<RefCell<AppState>>,
+        show: CompareDialog,
+        schema_sender: std::sync::mpsc::Sender<SchemaUpdate>,
+        file_sender: std::sync::mpsc::Sender<FileActionResult>,
+    ) {
+        let (endpoints, popups) = {
+            let s = state.borrow();
+            (s.compare_endpoints(), s.popups.clone())
+        };
+        let Some(sync) = show(endpoints, popups) else {
+            return;
+        };
+        // The script opens in a new tab on the target's session.
+        Self::open_query_editor_tab(state, sync.target, schema_sender, file_sender);
+        let mut buffer = state.borrow().sql_buffer.clone();
+        buffer.set_text(&sync.script);
+        state.borrow().sql_editor.refresh_highlighting();
+    }
+
+    fn open_find_in_tabs_dialog(state: &Rc<RefCell<AppState>>) {
+        let (sources, popups) = {
+            let s = state.borrow();
+            let sources: Vec<TabSearchSource> = s
+                .editor_tabs
+                .iter()
+                .map(|tab| TabSearchSource {
+                    tab_id: tab.tab_id,
+                    label: AppState::tab_display_label(tab),
+                    text: tab.sql_buffer.text(),
+                })
+                .collect();
+            (sources, s.popups.clone())
+        };
+        let Some(found) = show_find_in_tabs_dialog(sources, popups) else {
+            return;
+        };
+
+        let mut s = state.borrow_mut();
+        s.query_tabs.select(found.tab_id);
+        if !s.set_active_editor_tab(found.tab_id) {
+            return;
+        }
+        let mut buffer = s.sql_buffer.clone();
+        let mut editor = s.sql_editor.get_editor();
+        buffer.select(found.start as i32, found.end as i32);
+        editor.set_insert_position(found.end as i32);
+        editor.show_insert_position();
+        s.sql_editor.focus();
+    }
+
     fn adjust_query_layout(state: &mut AppState) {
         let mut right_tile = state.right_tile.clone();
         let mut query_top_group = state.query_top_group.clone();
@@ -911,21 +1458,48 @@ impl MainWindow {
         }
     }
 
-    fn create_query_editor_tab(state: &mut AppState) -> Option<QueryTabId> {
+    /// Open a query tab on `connection`. Passing the active tab's connection
+    /// shares its session; a fresh one from `create_shared_connection` gives
+    /// the tab a session of its own.
+    fn create_query_editor_tab(
+        state: &mut AppState,
+        connection: SharedConnection,
+    ) -> Option<QueryTabId> {
         let label = format!("Query {}", state.next_editor_tab_number);
         state.next_editor_tab_number = state.next_editor_tab_number.saturating_add(1);
         let tab_id = state.query_tabs.add_tab(&label);
         let group = state.query_tabs.tab_group(tab_id)?;
         group.begin();
-        let editor =
-            SqlEditorWidget::new(state.connection.clone(), state.query_timeout_input.clone());
+        let editor = SqlEditorWidget::new(connection.clone(), state.query_timeout_input.clone());
         let mut editor_group = editor.get_group().clone();
         editor_group.resize(group.x(), group.y(), group.w(), group.h());
         editor_group.layout();
         group.resizable(&editor_group);
         group.end();
-        let inherited_intellisense = state.sql_editor.get_intellisense_data().borrow().clone();
-        *editor.get_intellisense_data().borrow_mut() = inherited_intellisense;
+        let shares_active = Arc::ptr_eq(&connection, &state.connection);
+        let connection_info = if shares_active {
+            let inherited_intellisense = state.sql_editor.get_intellisense_data().borrow().clone();
+            *editor.get_intellisense_data().borrow_mut() = inherited_intellisense;
+            state.connection_info.borrow().clone()
+        } else if let Some(sibling) = state
+            .editor_tabs
+            .iter()
+            .find(|tab| Arc::ptr_eq(&tab.connection, &connection))
+        {
+            // Another tab already owns this session; share what it knows.
+            let inherited_intellisense =
+                sibling.sql_editor.get_intellisense_data().borrow().clone();
+            *editor.get_intellisense_data().borrow_mut() = inherited_intellisense;
+            sibling.connection_info.clone()
+        } else {
+            None
+        };
+        let transaction = state
+            .editor_tabs
+            .iter()
+            .find(|tab| Arc::ptr_eq(&tab.connection, &connection))
+            .map(|tab| tab.transaction.clone())
+            .unwrap_or_default();
         let buffer = editor.get_buffer();
         state.editor_tabs.push(QueryEditorTab {
             tab_id,
@@ -934,12 +1508,89 @@ impl MainWindow {
             sql_buffer: buffer.clone(),
             current_file: None,
             is_dirty: false,
+            connection,
+            connection_info,
+            source_object: None,
+            transaction,
         });
+        if state.editor_tabs[state.editor_tabs.len() - 1]
+            .connection_info
+            .is_some()
+        {
+            let label =
+                AppState::tab_display_label(&state.editor_tabs[state.editor_tabs.len() - 1]);
+            state.query_tabs.set_tab_label(tab_id, &label);
+        }
         state.query_tabs.select(tab_id);
         let _ = state.set_active_editor_tab(tab_id);
         Some(tab_id)
     }
 
+    fn open_query_editor_tab(
+        state: &Rc<RefCell<AppState>>,
+        connection: SharedConnection,
+        schema_sender: std::sync::mpsc::Sender<SchemaUpdate>,
+        file_sender: std::sync::mpsc::Sender<FileActionResult>,
+    ) {
+        let created_tab_id = {
+            let mut s = state.borrow_mut();
+            let created = Self::create_query_editor_tab(&mut s, connection);
+            s.right_tile.redraw();
+            created
+        };
+        if let Some(tab_id) = created_tab_id {
+            Self::attach_editor_callbacks(state, tab_id, schema_sender);
+            Self::attach_file_drop_callback(state, tab_id, file_sender);
+            state.borrow_mut().sql_editor.focus();
+            app::redraw();
+        }
+    }
+
+    /// Open a stored program's source in a tab tied to the object, or bring
+    /// forward the tab already editing it.
+    fn open_source_tab(state: &Rc<RefCell<AppState>>, source: EditableSource) {
+        let (tab_id, schema_sender, file_sender) = {
+            let mut s = state.borrow_mut();
+            let connection = s.connection.clone();
+            let existing = s
+                .editor_tabs
+                .iter()
+                .find(|tab| {
+                    Arc::ptr_eq(&tab.connection, &connection)
+                        && tab
+                            .source_object
+                            .as_ref()
+                            .is_some_and(|object| object.same_object(&source.object))
+                })
+                .map(|tab| tab.tab_id);
+            if let Some(tab_id) = existing {
+                s.query_tabs.select(tab_id);
+                if s.set_active_editor_tab(tab_id) {
+                    s.sql_editor.focus();
+                }
+                return;
+            }
+            let Some(tab_id) = Self::create_query_editor_tab(&mut s, connection) else {
+                return;
+            };
+            s.sql_buffer.set_text(&source.text);
+            s.sql_editor.reset_undo_redo_history();
+            s.set_tab_source_object(tab_id, Some(source.object));
+            s.set_tab_dirty(tab_id, false);
+            s.sql_editor.refresh_highlighting();
+            s.right_tile.redraw();
+            (tab_id, s.schema_sender.clone(), s.file_sender.clone())
+        };
+        if let Some(schema_sender) = schema_sender {
+            Self::attach_editor_callbacks(state, tab_id, schema_sender);
+        }
+        if let Some(file_sender) = file_sender {
+            Self::attach_file_drop_callback(state, tab_id, file_sender);
+        }
+        state.borrow_mut().sql_editor.focus();
+        app::redraw();
+    }
+
     fn close_query_editor_tab(state: &Rc<RefCell<AppState>>, tab_id: QueryTabId) -> bool {
         {
             let s = state.borrow();
@@ -958,6 +1609,30 @@ impl MainWindow {
             return false;
         }
 
+        // The session goes with the tab unless another tab still uses it;
+        // the last tab's session is kept for the tab that replaces it.
+        let closing_session = {
+            let s = state.borrow();
+            s.tab_connection(tab_id).filter(|connection| {
+                s.editor_tabs.len() > 1
+                    && !s
+                        .editor_tabs
+                        .iter()
+                        .any(|tab| tab.tab_id != tab_id && Arc::ptr_eq(&tab.connection, connection))
+            })
+        };
+        if let Some(connection) = closing_session {
+            let Some(mut db_conn) = try_lock_connection(&connection) else {
+                fltk::dialog::alert_default(
+                    "Connection is busy. Try again after the current operation finishes.",
+                );
+                return false;
+            };
+            if !Self::confirm_pending_transaction(&mut db_conn, "closing this tab") {
+                return false;
+            }
+        }
+
         let (created_tab_id, schema_sender, file_sender) = {
             let mut s = state.borrow_mut();
             let Some(index) = s.find_tab_index(tab_id) else {
@@ -972,7 +1647,9 @@ impl MainWindow {
 
             let mut created_tab_id = None;
             if s.editor_tabs.is_empty() {
-                let Some(new_tab_id) = MainWindow::create_query_editor_tab(&mut s) else {
+                let connection = s.connection.clone();
+                let Some(new_tab_id) = MainWindow::create_query_editor_tab(&mut s, connection)
+                else {
                     return false;
                 };
                 created_tab_id = Some(new_tab_id);
@@ -1013,12 +1690,16 @@ impl MainWindow {
         true
     }
 
-    fn apply_schema_to_all_editors(
+    fn apply_schema_to_connection_editors(
         state: &mut AppState,
+        connection: &SharedConnection,
         data: &IntellisenseData,
         highlight_data: &HighlightData,
     ) {
         for tab in &mut state.editor_tabs {
+            if !Arc::ptr_eq(&tab.connection, connection) {
+                continue;
+            }
             *tab.sql_editor.get_intellisense_data().borrow_mut() = data.clone();
             tab.sql_editor
                 .get_highlighter()
@@ -1027,6 +1708,172 @@ impl MainWindow {
         }
     }
 
+    /// Connect `connection` on a background thread and report the outcome
+    /// through `conn_sender`. Tabs sharing the connection follow it.
+    fn connect_in_background(
+        state: &Rc<RefCell<AppState>>,
+        connection: SharedConnection,
+        info: crate::db::ConnectionInfo,
+        conn_sender: std::sync::mpsc::Sender<ConnectionResult>,
+    ) {
+        let session_defaults = {
+            let mut s = state.borrow_mut();
+            s.status_bar
+                .set_label(&format!("Connecting to {}...", info.display_string()));
+            let session_defaults = s.config.borrow().session_defaults.clone();
+            session_defaults
+        };
+        thread::spawn(move || {
+            let mut db_conn = lock_connection(&connection);
+            db_conn.set_session_defaults(session_defaults);
+            match db_conn.connect(info.clone()) {
+                Ok(_) => {
+                    let session = db_conn.session_state();
+                    drop(db_conn);
+                    match session.lock() {
+                        Ok(mut guard) => guard.reset(),
+                        Err(poisoned) => {
+                            eprintln!("Warning: session state lock was poisoned; recovering.");
+                            poisoned.into_inner().reset();
+                        }
+                    }
+                    let init_errors = ScriptRunner::run_session_init(&connection);
+                    // Clear password before sending info across channel
+                    let mut info = info;
+                    info.clear_password();
+                    let _ = conn_sender.send(ConnectionResult::Success {
+                        connection,
+                        info: Box::new(info),
+                        init_errors,
+                    });
+                    app::awake();
+                }
+                Err(e) => {
+                    let _ = conn_sender.send(ConnectionResult::Failure(e.to_string()));
+                    app::awake();
+                }
+            }
+        });
+    }
+
+    /// Tell the user a session was lost and offer to connect it again with
+    /// the same details, keeping binds, DEFINEs and SERVEROUTPUT. The
+    /// reconnect runs on a background thread and reports through
+    /// `conn_sender`.
+    fn offer_reconnect(state: &Rc<RefCell<AppState>>, connection: SharedConnection, message: &str) {
+        let conn_sender = {
+            let mut s = state.borrow_mut();
+            if s.reconnect_prompt_open {
+                return;
+            }
+            let Some(conn_sender) = s.conn_sender.clone() else {
+                return;
+            };
+            s.reconnect_prompt_open = true;
+            conn_sender
+        };
+        let lost = {
+            let guard = lock_connection(&connection);
+            guard
+                .is_lost()
+                .then(|| (guard.get_info().clone(), guard.transaction().summary()))
+        };
+        let password = lost.and_then(|(info, transaction)| {
+            let lost_work = if transaction.is_empty() {
+                "Uncommitted changes made in that session were rolled back by the \
+                 database and are lost."
+                    .to_string()
+            } else {
+                format!(
+                    "The session had uncommitted changes ({}). The database rolled them \
+                     back and they are lost.",
+                    transaction
+                )
+            };
+            let choice = fltk::dialog::choice2_default(
+                &format!(
+                    "The connection {} was lost:\n{}\n\n{}\n\n\
+                     Reconnect? Bind variables, DEFINEs and SERVEROUTPUT are restored.",
+                    info.display_string(),
+                    message.lines().next().unwrap_or_default(),
+                    lost_work
+                ),
+                "Stay Disconnected",
+                "Reconnect",
+                "",
+            );
+            if choice != Some(1) {
+                return None;
+            }
+            if info.use_wallet {
+                Some(String::new())
+            } else {
+                AppConfig::get_password_for_connection(&info.name).or_else(|| {
+                    fltk::dialog::password_default(&format!("Password for {}:", info.username), "")
+                })
+            }
+        });
+
+        let Some(password) = password else {
+            state.borrow_mut().reconnect_prompt_open = false;
+            return;
+        };
+        state.borrow_mut().status_bar.set_label("Reconnecting...");
+        thread::spawn(move || {
+            let reconnected = lock_connection(&connection).reconnect(password);
+            let (result, init_errors) = match reconnected {
+                Ok(()) => {
+                    let init_errors = ScriptRunner::run_session_init(&connection);
+                    let info = lock_connection(&connection).get_info().clone();
+                    (Ok(Box::new(info)), init_errors)
+                }
+                Err(err) => (Err(err.to_string()), Vec::new()),
+            };
+            let _ = conn_sender.send(ConnectionResult::Reconnected {
+                connection,
+                result,
+                init_errors,
+            });
+            app::awake();
+        });
+    }
+
+    /// Load table, view and schema names for intellisense and highlighting
+    /// on a background thread.
+    fn spawn_schema_load(
+        connection: SharedConnection,
+        schema_sender: std::sync::mpsc::Sender<SchemaUpdate>,
+    ) {
+        thread::spawn(move || {
+            let conn = {
+                let conn_guard = lock_connection(&connection);
+                conn_guard.get_connection()
+            };
+            if let Some(conn) = conn {
+                let mut data = IntellisenseData::new();
+                let mut highlight_data = HighlightData::new();
+                if let Ok(tables) = ObjectBrowser::get_tables(conn.as_ref()) {
+                    highlight_data.tables = tables.clone();
+                    data.tables = tables;
+                }
+                if let Ok(views) = ObjectBrowser::get_views(conn.as_ref()) {
+                    highlight_data.views = views.clone();
+                    data.views = views;
+                }
+                if let Ok(schemas) = ObjectBrowser::get_schemas(conn.as_ref()) {
+                    data.schemas = schemas;
+                }
+                data.rebuild_indices();
+                let _ = schema_sender.send(SchemaUpdate {
+                    connection,
+                    data,
+                    highlight_data,
+                });
+                app::awake();
+            }
+        });
+    }
+
     fn attach_editor_callbacks(
         state: &Rc<RefCell<AppState>>,
         tab_id: QueryTabId,
@@ -1128,9 +1975,15 @@ impl MainWindow {
                     s.status_bar
                         .set_label(&format_status("Executing query...", &conn_info));
                 }
-                QueryProgress::SelectStart { index, columns } => {
+                QueryProgress::SelectStart {
+                    index,
+                    columns,
+                    null_text,
+                    edit_target,
+                } => {
                     let tab_index = s.result_tab_offset + index;
-                    s.result_tabs.start_streaming(tab_index, &columns);
+                    s.result_tabs
+                        .start_streaming(tab_index, &columns, &null_text, edit_target);
                     s.fetch_row_counts.insert(index, 0);
                     s.last_fetch_status_update = Instant::now();
                     let conn_info = s.connection_info.borrow().clone();
@@ -1158,7 +2011,7 @@ impl MainWindow {
                 QueryProgress::ScriptOutput { lines } => {
                     s.result_tabs.append_script_output_lines(&lines);
                 }
-                QueryProgress::PromptInput { .. } => {}
+                QueryProgress::PromptInput { .. } | QueryProgress::ConfirmStatement { .. } => {}
                 QueryProgress::AutoCommitChanged { enabled } => {
                     if let Some(menu) = app::widget_from_id::<MenuBar>("main_menu") {
                         if let Some(mut item) = menu.find_item("&Tools/&Auto-Commit\t") {
@@ -1178,45 +2031,32 @@ impl MainWindow {
                     s.status_bar.set_label(&format_status(status, &conn_info));
                 }
                 QueryProgress::ConnectionChanged { info } => {
+                    let Some(connection) = s.tab_connection(tab_id) else {
+                        return;
+                    };
+                    let is_active = Arc::ptr_eq(&connection, &s.connection);
+                    s.set_connection_info(&connection, info.as_deref().cloned());
                     if let Some(info) = info {
-                        *s.connection_info.borrow_mut() = Some(info.clone());
-                        s.status_bar
-                            .set_label(&format!("Connected | {}", info.display_string()));
-                        s.object_browser.refresh();
-                        s.sql_editor.focus();
-
-                        let schema_sender = schema_sender_for_progress.clone();
-                        let connection = s.connection.clone();
-                        thread::spawn(move || {
-                            let conn = {
-                                let conn_guard = lock_connection(&connection);
-                                conn_guard.get_connection()
-                            };
-                            if let Some(conn) = conn {
-                                let mut data = IntellisenseData::new();
-                                let mut highlight_data = HighlightData::new();
-                                if let Ok(tables) = ObjectBrowser::get_tables(conn.as_ref()) {
-                                    highlight_data.tables = tables.clone();
-                                    data.tables = tables;
-                                }
-                                if let Ok(views) = ObjectBrowser::get_views(conn.as_ref()) {
-                                    highlight_data.views = views.clone();
-                                    data.views = views;
-                                }
-                                data.rebuild_indices();
-                                let _ = schema_sender.send(SchemaUpdate {
-                                    data,
-                                    highlight_data,
-                                });
-                                app::awake();
-                            }
-                        });
+                        if is_active {
+                            s.status_bar
+                                .set_label(&format!("Connected | {}", info.display_string()));
+                            s.object_browser.reset_schema();
+                            s.object_browser.refresh();
+                            s.sql_editor.focus();
+                        }
+                        Self::spawn_schema_load(connection, schema_sender_for_progress.clone());
                     } else {
-                        *s.connection_info.borrow_mut() = None;
-                        s.status_bar.set_label("Disconnected");
+                        if is_active {
+                            s.status_bar.set_label("Disconnected");
+                        }
                         let reset_data = IntellisenseData::new();
                         let reset_highlight = HighlightData::new();
-                        Self::apply_schema_to_all_editors(&mut s, &reset_data, &reset_highlight);
+                        Self::apply_schema_to_connection_editors(
+                            &mut s,
+                            &connection,
+                            &reset_data,
+                            &reset_highlight,
+                        );
                     }
                 }
                 QueryProgress::StatementFinished { index, result, .. } => {
@@ -1228,6 +2068,7 @@ impl MainWindow {
                         s.result_tabs.select_script_output();
                     }
                     if result.is_select {
+                        s.result_tabs.set_source_sql(tab_index, &result.sql);
                         s.result_tabs.finish_streaming(tab_index);
                     } else {
                         s.result_tabs.display_result(tab_index, &result);
@@ -1238,6 +2079,33 @@ impl MainWindow {
                     s.result_tabs.finish_all_streaming();
                     s.fetch_row_counts.clear();
                 }
+                QueryProgress::TransactionChanged { state } => {
+                    let Some(connection) = s.tab_connection(tab_id) else {
+                        return;
+                    };
+                    s.set_transaction_state(&connection, &state);
+                }
+                QueryProgress::ConnectionLost { message } => {
+                    let Some(connection) = s.tab_connection(tab_id) else {
+                        return;
+                    };
+                    s.set_connection_info(&connection, None);
+                    if Arc::ptr_eq(&connection, &s.connection) {
+                        s.status_bar.set_label("Connection lost");
+                    }
+                    s.result_tabs.append_script_output_lines(&[format!(
+                        "Connection lost: {}",
+                        message.lines().next().unwrap_or_default()
+                    )]);
+                    s.result_tabs.select_script_output();
+                    // Prompt once this callback has released the state.
+                    let weak_state = weak_state_for_progress.clone();
+                    app::add_timeout3(0.0, move |_| {
+                        if let Some(state) = weak_state.upgrade() {
+                            MainWindow::offer_reconnect(&state, connection.clone(), &message);
+                        }
+                    });
+                }
             }
         });
 
@@ -1310,6 +2178,10 @@ impl MainWindow {
             let Some(state_for_browser) = weak_state_for_browser.upgrade() else {
                 return;
             };
+            if let SqlAction::EditSource(source) = action {
+                MainWindow::open_source_tab(&state_for_browser, source);
+                return;
+            }
             let mut s = state_for_browser.borrow_mut();
             match action {
                 SqlAction::Set(sql) => {
@@ -1349,6 +2221,7 @@ impl MainWindow {
                 SqlAction::Execute(sql) => {
                     s.sql_editor.execute_sql_text(&sql);
                 }
+                SqlAction::EditSource(_) => {}
             }
         });
 
@@ -1428,8 +2301,9 @@ impl MainWindow {
                             let mut s = state.borrow_mut();
                             let mut data = update.data;
                             data.rebuild_indices();
-                            MainWindow::apply_schema_to_all_editors(
+                            MainWindow::apply_schema_to_connection_editors(
                                 &mut s,
+                                &update.connection,
                                 &data,
                                 &update.highlight_data,
                             );
@@ -1451,46 +2325,34 @@ impl MainWindow {
                         Ok(result) => {
                             let mut s = state.borrow_mut();
                             match result {
-                                ConnectionResult::Success(info) => {
-                                    *s.connection_info.borrow_mut() = Some(info.clone());
-                                    s.status_bar.set_label(&format!(
-                                        "Connected | {}",
-                                        info.display_string()
-                                    ));
-                                    s.object_browser.refresh();
-                                    s.sql_editor.focus();
+                                ConnectionResult::Success {
+                                    connection,
+                                    info,
+                                    init_errors,
+                                } => {
+                                    s.set_connection_info(&connection, Some(*info.clone()));
+                                    if !init_errors.is_empty() {
+                                        let lines: Vec<String> = init_errors
+                                            .iter()
+                                            .map(|err| format!("Session setup failed: {}", err))
+                                            .collect();
+                                        s.result_tabs.append_script_output_lines(&lines);
+                                    }
+                                    if Arc::ptr_eq(&connection, &s.connection) {
+                                        s.status_bar.set_label(&format!(
+                                            "Connected | {}",
+                                            info.display_string()
+                                        ));
+                                        s.object_browser.reset_schema();
+                                        s.object_browser.refresh();
+                                        s.sql_editor.focus();
+                                    }
 
                                     // Start schema update after successful connection
-                                    let schema_sender = schema_sender.clone();
-                                    let connection = s.connection.clone();
-                                    thread::spawn(move || {
-                                        let conn = {
-                                            let conn_guard = lock_connection(&connection);
-                                            conn_guard.get_connection()
-                                        };
-                                        if let Some(conn) = conn {
-                                            let mut data = IntellisenseData::new();
-                                            let mut highlight_data = HighlightData::new();
-                                            if let Ok(tables) =
-                                                ObjectBrowser::get_tables(conn.as_ref())
-                                            {
-                                                highlight_data.tables = tables.clone();
-                                                data.tables = tables;
-                                            }
-                                            if let Ok(views) =
-                                                ObjectBrowser::get_views(conn.as_ref())
-                                            {
-                                                highlight_data.views = views.clone();
-                                                data.views = views;
-                                            }
-                                            data.rebuild_indices();
-                                            let _ = schema_sender.send(SchemaUpdate {
-                                                data,
-                                                highlight_data,
-                                            });
-                                            app::awake();
-                                        }
-                                    });
+                                    MainWindow::spawn_schema_load(
+                                        connection,
+                                        schema_sender.clone(),
+                                    );
                                 }
                                 ConnectionResult::Failure(err) => {
                                     s.status_bar.set_label("Connection failed");
@@ -1500,6 +2362,44 @@ impl MainWindow {
                                     )]);
                                     s.result_tabs.select_script_output();
                                 }
+                                ConnectionResult::Reconnected {
+                                    connection,
+                                    result,
+                                    init_errors,
+                                } => {
+                                    s.reconnect_prompt_open = false;
+                                    let is_active = Arc::ptr_eq(&connection, &s.connection);
+                                    match result {
+                                        Ok(info) => {
+                                            s.set_connection_info(&connection, Some(*info.clone()));
+                                            let mut lines = vec![format!(
+                                                "Reconnected to {}. Uncommitted work from the \
+                                                 lost session is gone.",
+                                                info.display_string()
+                                            )];
+                                            lines.extend(init_errors.iter().map(|err| {
+                                                format!("Session setup failed: {}", err)
+                                            }));
+                                            s.result_tabs.append_script_output_lines(&lines);
+                                            if is_active {
+                                                s.status_bar.set_label(&format!(
+                                                    "Reconnected | {} | uncommitted work was lost",
+                                                    info.display_string()
+                                                ));
+                                            }
+                                        }
+                                        Err(err) => {
+                                            if is_active {
+                                                s.status_bar.set_label("Reconnect failed");
+                                            }
+                                            s.result_tabs.append_script_output_lines(&[format!(
+                                                "Reconnect failed: {}",
+                                                err
+                                            )]);
+                                            s.result_tabs.select_script_output();
+                                        }
+                                    }
+                                }
                             }
                         }
                         Err(std::sync::mpsc::TryRecvError::Empty) => break,
@@ -1518,12 +2418,14 @@ impl MainWindow {
                     match r.try_recv() {
                         Ok(result) => {
                             let mut created_tab_for_open: Option<QueryTabId> = None;
+                            let mut changed_source = None;
                             {
                                 let mut s = state.borrow_mut();
                                 match result {
                                     FileActionResult::OpenInNewTab { path, result } => match result {
                                         Ok(content) => {
-                                            if let Some(tab_id) = MainWindow::create_query_editor_tab(&mut s)
+                                            let connection = s.connection.clone();
+                                            if let Some(tab_id) = MainWindow::create_query_editor_tab(&mut s, connection)
                                             {
                                                 s.sql_buffer.set_text(&content);
                                                 s.sql_editor.reset_undo_redo_history();
@@ -1542,12 +2444,8 @@ impl MainWindow {
                                             ));
                                         }
                                     },
-                                    FileActionResult::Export {
-                                        path,
-                                        row_count,
-                                        result,
-                                    } => match result {
-                                        Ok(()) => {
+                                    FileActionResult::Export { path, result } => match result {
+                                        Ok(row_count) => {
                                             let file_label = path
                                                 .file_name()
                                                 .unwrap_or_default()
@@ -1563,11 +2461,64 @@ impl MainWindow {
                                         }
                                         Err(err) => {
                                             fltk::dialog::alert_default(&format!(
-                                                "Failed to export CSV: {}",
+                                                "Failed to export results: {}",
                                                 err
                                             ));
                                         }
                                     },
+                                    FileActionResult::Compile {
+                                        tab_id,
+                                        object,
+                                        sql_text,
+                                        result,
+                                    } => {
+                                        let changed_at = MainWindow::finish_source_compile(
+                                            &mut s, tab_id, &object, &sql_text, result,
+                                        );
+                                        changed_source = changed_at.map(|changed_at| {
+                                            (tab_id, object, sql_text, changed_at)
+                                        });
+                                    }
+                                }
+                            }
+
+                            // Asked once the state is released, as the dialog
+                            // runs the event loop.
+                            if let Some((tab_id, object, sql_text, changed_at)) = changed_source {
+                                let choice = fltk::dialog::choice2_default(
+                                    &format!(
+                                        "{} was changed in the database at {} after it was opened here.\nCompile this version over it?",
+                                        object.label(),
+                                        changed_at
+                                    ),
+                                    "Cancel",
+                                    "Compile",
+                                    "",
+                                );
+                                let connection = {
+                                    let s = state.borrow();
+                                    s.find_tab_index(tab_id)
+                                        .map(|index| s.editor_tabs[index].connection.clone())
+                                };
+                                match connection {
+                                    Some(connection) if choice == Some(1) => {
+                                        MainWindow::spawn_source_compile(
+                                            connection,
+                                            tab_id,
+                                            object,
+                                            sql_text,
+                                            true,
+                                            file_sender.clone(),
+                                        );
+                                    }
+                                    _ => {
+                                        let mut s = state.borrow_mut();
+                                        let conn_info = s.connection_info.borrow().clone();
+                                        s.status_bar.set_label(&format_status(
+                                            &format!("Compile of {} cancelled", object.label()),
+                                            &conn_info,
+                                        ));
+                                    }
                                 }
                             }
 
@@ -1620,6 +2571,7 @@ impl MainWindow {
             let mut s = state.borrow_mut();
             s.schema_sender = Some(schema_sender.clone());
             s.file_sender = Some(file_sender.clone());
+            s.conn_sender = Some(conn_sender.clone());
         }
         schedule_poll(
             schema_receiver,
@@ -1657,38 +2609,21 @@ impl MainWindow {
                                 (s.popups.clone(), s.connection.clone())
                             };
                             if let Some(info) = ConnectionDialog::show_with_registry(popups) {
-                                let conn_sender = conn_sender.clone();
-                                {
-                                    let mut s = state_for_menu.borrow_mut();
-                                    s.status_bar.set_label(&format!("Connecting to {}...", info.display_string()));
-                                }
-                                thread::spawn(move || {
-                                    let mut db_conn = lock_connection(&connection);
-                                    match db_conn.connect(info.clone()) {
-                                        Ok(_) => {
-                                            let session = db_conn.session_state();
-                                            drop(db_conn);
-                                            match session.lock() {
-                                                Ok(mut guard) => guard.reset(),
-                                                Err(poisoned) => {
-                                                    eprintln!(
-                                                        "Warning: session state lock was poisoned; recovering."
-                                                    );
-                                                    poisoned.into_inner().reset();
-                                                }
-                                            }
-                                            // Clear password before sending info across channel
-                                            let mut info = info;
-                                            info.clear_password();
-                                            let _ = conn_sender.send(ConnectionResult::Success(info));
-                                            app::awake();
-                                        }
-                                        Err(e) => {
-                                            let _ = conn_sender.send(ConnectionResult::Failure(e.to_string()));
-                                            app::awake();
-                                        }
+                                // Connecting again replaces the current session.
+                                if let Some(mut db_conn) = try_lock_connection(&connection) {
+                                    if !MainWindow::confirm_pending_transaction(
+                                        &mut db_conn,
+                                        "connecting again",
+                                    ) {
+                                        return;
                                     }
-                                });
+                                }
+                                MainWindow::connect_in_background(
+                                    &state_for_menu,
+                                    connection,
+                                    info,
+                                    conn_sender.clone(),
+                                );
                             }
                         }
                         "File/Disconnect" => {
@@ -1699,6 +2634,12 @@ impl MainWindow {
                                 );
                                 return;
                             };
+                            if !MainWindow::confirm_pending_transaction(
+                                &mut db_conn,
+                                "disconnecting",
+                            ) {
+                                return;
+                            }
                             db_conn.disconnect();
                             let session = db_conn.session_state();
                             drop(db_conn);
@@ -1713,12 +2654,13 @@ impl MainWindow {
                             }
 
                             let mut s = state_for_menu.borrow_mut();
-                            *s.connection_info.borrow_mut() = None;
+                            s.set_connection_info(&connection, None);
                             s.status_bar.set_label("Disconnected");
                             let reset_data = IntellisenseData::new();
                             let reset_highlight = HighlightData::new();
-                            MainWindow::apply_schema_to_all_editors(
+                            MainWindow::apply_schema_to_connection_editors(
                                 &mut s,
+                                &connection,
                                 &reset_data,
                                 &reset_highlight,
                             );
@@ -1839,25 +2781,30 @@ impl MainWindow {
                         }
                         "Query/Execute" => state_for_menu.borrow_mut().sql_editor.execute_current(),
                         "Query/New Tab" => {
-                            let created_tab_id = {
-                                let mut s = state_for_menu.borrow_mut();
-                                let created = MainWindow::create_query_editor_tab(&mut s);
-                                s.right_tile.redraw();
-                                created
-                            };
-                            if let Some(tab_id) = created_tab_id {
-                                MainWindow::attach_editor_callbacks(
+                            let connection = state_for_menu.borrow().connection.clone();
+                            MainWindow::open_query_editor_tab(
+                                &state_for_menu,
+                                connection,
+                                schema_sender.clone(),
+                                file_sender.clone(),
+                            );
+                        }
+                        "Query/New Tab on New Connection..." => {
+                            let popups = state_for_menu.borrow().popups.clone();
+                            if let Some(info) = ConnectionDialog::show_with_registry(popups) {
+                                let connection = create_shared_connection();
+                                MainWindow::open_query_editor_tab(
                                     &state_for_menu,
-                                    tab_id,
+                                    connection.clone(),
                                     schema_sender.clone(),
+                                    file_sender.clone(),
                                 );
-                                MainWindow::attach_file_drop_callback(
+                                MainWindow::connect_in_background(
                                     &state_for_menu,
-                                    tab_id,
-                                    file_sender.clone(),
+                                    connection,
+                                    info,
+                                    conn_sender.clone(),
                                 );
-                                state_for_menu.borrow_mut().sql_editor.focus();
-                                app::redraw();
                             }
                         }
                         "Query/Close Tab" => {
@@ -1877,37 +2824,94 @@ impl MainWindow {
                             state_for_menu.borrow_mut().sql_editor.quick_describe_at_cursor();
                         }
                         "Query/Explain Plan" => state_for_menu.borrow_mut().sql_editor.explain_current(),
+                        "Query/Autotrace" => state_for_menu.borrow_mut().sql_editor.autotrace_current(),
                         "Query/Commit" => state_for_menu.borrow_mut().sql_editor.commit(),
                         "Query/Rollback" => state_for_menu.borrow_mut().sql_editor.rollback(),
+                        "Query/Savepoints..." => {
+                            let (editor, connection, popups) = {
+                                let s = state_for_menu.borrow();
+                                (
+                                    s.sql_editor.clone(),
+                                    s.connection.clone(),
+                                    s.popups.clone(),
+                                )
+                            };
+                            show_savepoint_dialog(editor, connection, popups);
+                        }
                         "Tools/Refresh Objects" => state_for_menu.borrow_mut().object_browser.refresh(),
                         "Tools/Export Results..." => {
-                            let has_data = state_for_menu.borrow().result_tabs.has_data();
+                            let (has_data, source_sql, connection) = {
+                                let s = state_for_menu.borrow();
+                                (
+                                    s.result_tabs.has_data(),
+                                    s.result_tabs.current_source_sql(),
+                                    s.connection.clone(),
+                                )
+                            };
                             if !has_data {
                                 fltk::dialog::alert_default("No results to export");
                                 return;
                             }
 
+                            let Some(request) = show_export_dialog(source_sql.is_some()) else {
+                                return;
+                            };
+                            let format = request.options.format;
                             let mut dialog = FileDialog::new(FileDialogType::BrowseSaveFile);
-                            dialog.set_filter("CSV Files\t*.csv");
+                            dialog.set_filter(&format.file_filter());
                             dialog.show();
-                            let filename = dialog.filename();
+                            let mut filename = dialog.filename();
                             if filename.as_os_str().is_empty() {
                                 return;
                             }
+                            if filename.extension().is_none() {
+                                filename.set_extension(format.extension());
+                            }
 
-                            let csv = state_for_menu.borrow().result_tabs.export_to_csv();
-                            let row_count = state_for_menu.borrow().result_tabs.row_count();
                             let sender = file_sender.clone();
-                            thread::spawn(move || {
-                                let result =
-                                    fs::write(&filename, csv).map_err(|err| err.to_string());
-                                let _ = sender.send(FileActionResult::Export {
-                                    path: filename,
-                                    row_count,
-                                    result,
+                            if request.all_rows {
+                                let Some(sql) = source_sql else {
+                                    return;
+                                };
+                                let options = request.options;
+                                thread::spawn(move || {
+                                    let result = match try_lock_connection(&connection) {
+                                        None => Err(
+                                            "A query is already running. Please wait for it to complete."
+                                                .to_string(),
+                                        ),
+                                        Some(conn_guard) => match conn_guard.get_connection() {
+                                            Some(db_conn) if conn_guard.is_connected() => {
+                                                export_query_to_file(
+                                                    db_conn.as_ref(),
+                                                    &sql,
+                                                    &filename,
+                                                    options,
+                                                )
+                                            }
+                                            _ => Err("Not connected to database".to_string()),
+                                        },
+                                    };
+                                    let _ = sender.send(FileActionResult::Export {
+                                        path: filename,
+                                        result,
+                                    });
+                                    app::awake();
                                 });
-                                app::awake();
-                            });
+                            } else {
+                                let (headers, rows) =
+                                    state_for_menu.borrow().result_tabs.export_snapshot();
+                                let options = request.options;
+                                thread::spawn(move || {
+                                    let result =
+                                        export_rows_to_file(&filename, options, &headers, &rows);
+                                    let _ = sender.send(FileActionResult::Export {
+                                        path: filename,
+                                        result,
+                                    });
+                                    app::awake();
+                                });
+                            }
                         }
                         "Edit/Find..." => {
                             let (mut editor, mut buffer, popups) = {
@@ -1938,6 +2942,9 @@ impl MainWindow {
                             };
                             FindReplaceDialog::show_replace_with_registry(&mut editor, &mut buffer, popups);
                         }
+                        "Edit/Find in Tabs..." => {
+                            MainWindow::open_find_in_tabs_dialog(&state_for_menu);
+                        }
                         "Edit/Format SQL" => {
                             state_for_menu.borrow_mut().sql_editor.format_selected_sql();
                         }
@@ -1962,6 +2969,56 @@ impl MainWindow {
                         "Tools/Query History..." => {
                             MainWindow::open_query_history_dialog(&state_for_menu);
                         }
+                        "Tools/SQL Snippets..." => {
+                            MainWindow::open_snippets_dialog(&state_for_menu);
+                        }
+                        "Tools/Schema Compare..." => {
+                            MainWindow::open_compare_dialog(
+                                &state_for_menu,
+                                show_schema_compare_dialog,
+                                schema_sender.clone(),
+                                file_sender.clone(),
+                            );
+                        }
+                        "Tools/Data Compare..." => {
+                            MainWindow::open_compare_dialog(
+                                &state_for_menu,
+                                show_data_compare_dialog,
+                                schema_sender.clone(),
+                                file_sender.clone(),
+                            );
+                        }
+                        "Tools/Debugger..." => {
+                            let (editor, connection, info, popups) = {
+                                let s = state_for_menu.borrow();
+                                let info = s.connection_info.borrow().clone();
+                                (
+                                    s.sql_editor.clone(),
+                                    s.connection.clone(),
+                                    info,
+                                    s.popups.clone(),
+                                )
+                            };
+                            show_debugger_dialog(editor, connection, info, popups);
+                        }
+                        "Tools/Session Browser..." => {
+                            let (connection, popups) = {
+                                let s = state_for_menu.borrow();
+                                (s.connection.clone(), s.popups.clone())
+                            };
+                            show_session_browser_dialog(connection, popups);
+                        }
+                        "Tools/Top SQL..." => {
+                            let (editor, connection, popups) = {
+                                let s = state_for_menu.borrow();
+                                (
+                                    s.sql_editor.clone(),
+                                    s.connection.clone(),
+                                    s.popups.clone(),
+                                )
+                            };
+                            show_top_sql_dialog(editor, connection, popups);
+                        }
                         "Tools/Auto-Commit" => {
                             let mut item = m.find_item("&Tools/&Auto-Commit\t");
                             let enabled = item.as_ref().map(|item| item.value()).unwrap_or(false);
@@ -2010,6 +3067,7 @@ impl MainWindow {
                                     config.result_font = settings.font;
                                     config.result_font_size = settings.result_size;
                                     config.result_cell_max_chars = settings.result_cell_max_chars;
+                                    config.session_defaults = settings.session_defaults;
                                     config.save()
                                 };
                                 if let Err(err) = save_result {
@@ -2046,6 +3104,9 @@ impl MainWindow {
                 if !MainWindow::confirm_save_for_all_dirty_tabs(&state) {
                     return;
                 }
+                if !MainWindow::confirm_pending_transactions_for_all_sessions(&state) {
+                    return;
+                }
                 let (popups, editor_tabs, mut result_tabs) = {
                     let s = state.borrow();
                     (
@@ -2113,50 +3174,6 @@ impl MainWindow {
         }
     }
 
-    #[allow(dead_code)]
-    fn export_results_csv(
-        path: &PathBuf,
-        result: &QueryResult,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut output = String::new();
-
-        let headers: Vec<String> = result.columns.iter().map(|c| c.name.clone()).collect();
-        output.push_str(&Self::csv_row(&headers));
-        output.push('\n');
-
-        for row in &result.rows {
-            output.push_str(&Self::csv_row(row));
-            output.push('\n');
-        }
-
-        match fs::write(path, output) {
-            Ok(()) => {}
-            Err(err) => {
-                eprintln!("CSV export error: {err}");
-                return Err(Box::new(err));
-            }
-        }
-        Ok(())
-    }
-
-    #[allow(dead_code)]
-    fn csv_row(values: &[String]) -> String {
-        values
-            .iter()
-            .map(|value| Self::csv_escape(value))
-            .collect::<Vec<String>>()
-            .join(",")
-    }
-
-    #[allow(dead_code)]
-    fn csv_escape(value: &str) -> String {
-        if value.contains(',') || value.contains('"') || value.contains('\n') {
-            format!("\"{}\"", value.replace('"', "\"\""))
-        } else {
-            value.to_string()
-        }
-    }
-
     #[allow(dead_code)]
     fn format_query_history(history: &QueryHistory) -> String {
         if history.queries.is_empty() {
@@ -203,6 +203,12 @@ impl MenuBarBuilder {
             MenuFlag::Normal,
             forward_menu_callback,
         );
+        menu.add(
+            "&Edit/Find in &Tabs...\t",
+            Shortcut::Ctrl | Shortcut::Command | Shortcut::Shift | 'h',
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
         menu.add(
             "&Edit/",
             Shortcut::None,
@@ -247,6 +253,12 @@ impl MenuBarBuilder {
             MenuFlag::Normal,
             forward_menu_callback,
         );
+        menu.add(
+            "&Query/New Tab on New &Connection...\t",
+            Shortcut::Command | Shortcut::Shift | 't',
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
         menu.add(
             "&Query/&Close Tab\t",
             Shortcut::Command | 'w',
@@ -301,6 +313,12 @@ impl MenuBarBuilder {
             MenuFlag::Normal,
             forward_menu_callback,
         );
+        menu.add(
+            "&Query/&Autotrace\t",
+            Shortcut::Shift | fltk::enums::Key::F6,
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
         menu.add(
             "&Query/",
             Shortcut::None,
@@ -319,6 +337,12 @@ impl MenuBarBuilder {
             MenuFlag::Normal,
             forward_menu_callback,
         );
+        menu.add(
+            "&Query/Save&points...\t",
+            Shortcut::None,
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
 
         // Tools menu
         menu.add(
@@ -345,6 +369,42 @@ impl MenuBarBuilder {
             MenuFlag::Normal,
             forward_menu_callback,
         );
+        menu.add(
+            "&Tools/SQL &Snippets...\t",
+            Shortcut::None,
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
+        menu.add(
+            "&Tools/Schema &Compare...\t",
+            Shortcut::None,
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
+        menu.add(
+            "&Tools/&Data Compare...\t",
+            Shortcut::None,
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
+        menu.add(
+            "&Tools/De&bugger...\t",
+            Shortcut::None,
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
+        menu.add(
+            "&Tools/Session Bro&wser...\t",
+            Shortcut::None,
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
+        menu.add(
+            "&Tools/&Top SQL...\t",
+            Shortcut::None,
+            MenuFlag::Normal,
+            forward_menu_callback,
+        );
         menu.add(
             "&Tools/",
             Shortcut::None,
@@ -397,6 +457,7 @@ impl MenuBarBuilder {
                     Ctrl+F - Find\n\
                     F3 - Find Next\n\
                     Ctrl+H - Replace\n\
+                    Ctrl+Shift+H - Find in Tabs\n\
                     Ctrl+Shift+F - Format SQL\n\
                     Ctrl+/ - Toggle Comment\n\
                     Ctrl+U - Uppercase Selection\n\
@@ -404,17 +465,26 @@ impl MenuBarBuilder {
                     Ctrl+Space - Intellisense\n\n\
                     Query:\n\
                     Cmd/Ctrl+T - New Query Tab\n\
+                    Cmd/Ctrl+Shift+T - New Query Tab on New Connection\n\
                     Cmd/Ctrl+W - Close Query Tab\n\
                     Ctrl+Enter - Execute Statement\n\
                     F5 - Execute Script\n\
                     F9 - Execute Statement\n\
                     F6 - Explain Plan\n\
+                    Shift+F6 - Autotrace (actual plan)\n\
                     F7 - Commit\n\
                     F8 - Rollback\n\
+                    Savepoints - no shortcut (create, list and roll back to savepoints)\n\
                     F4 - Quick Describe (Editor)\n\n\
                     Tools:\n\
                     Ctrl+E - Export Results\n\
-                    Query History - no shortcut\n\n\
+                    Query History - no shortcut\n\
+                    SQL Snippets - no shortcut (type a snippet's trigger word in the editor)\n\
+                    Schema Compare - no shortcut\n\
+                    Data Compare - no shortcut\n\
+                    Debugger - no shortcut (click the line numbers to toggle breakpoints)\n\
+                    Session Browser - no shortcut (sessions, blocking locks, kill session)\n\
+                    Top SQL - no shortcut (shared pool statements, actual plans, SQL Monitor reports)\n\n\
                     Results Table:\n\
                     Ctrl+C - Copy Selected Cells\n\
                     Ctrl+Shift+C - Copy with Headers\n\
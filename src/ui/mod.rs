@@ -1,5 +1,10 @@
 pub mod connection_dialog;
+pub mod connection_import_dialog;
 pub mod constants;
+pub mod data_compare_dialog;
+pub mod debugger_dialog;
+pub mod export_dialog;
+pub mod find_in_tabs;
 pub mod find_replace;
 pub mod font_settings;
 pub mod intellisense;
@@ -7,18 +12,31 @@ pub mod intellisense_context;
 pub mod main_window;
 pub mod menu;
 pub mod object_browser;
+pub mod plan_view;
 pub mod query_history;
 pub mod query_tabs;
 pub mod result_table;
 pub mod result_tabs;
+pub mod result_view;
+pub mod savepoint_dialog;
+pub mod schema_compare_dialog;
+pub mod session_browser_dialog;
+pub mod session_settings_form;
 pub mod settings_dialog;
+pub mod snippet_dialog;
 pub mod sql_editor;
 pub mod syntax_highlight;
 pub mod theme;
+pub mod top_sql_dialog;
 
 use fltk::{app, prelude::WidgetExt, prelude::WindowExt, window::Window};
 
 pub use connection_dialog::*;
+pub use connection_import_dialog::*;
+pub use data_compare_dialog::*;
+pub use debugger_dialog::*;
+pub use export_dialog::*;
+pub use find_in_tabs::*;
 pub use find_replace::*;
 pub use font_settings::*;
 pub use intellisense::*;
@@ -29,9 +47,15 @@ pub use query_history::*;
 pub use query_tabs::*;
 pub use result_table::*;
 pub use result_tabs::*;
+pub use savepoint_dialog::*;
+pub use schema_compare_dialog::*;
+pub use session_browser_dialog::*;
+pub use session_settings_form::*;
 pub use settings_dialog::*;
+pub use snippet_dialog::*;
 pub use sql_editor::*;
 pub use syntax_highlight::*;
+pub use top_sql_dialog::*;
 
 pub fn center_on_main(window: &mut Window) {
     if let Some(main) = app::widget_from_id::<Window>("main_window") {
@@ -3,18 +3,20 @@ use fltk::{
     enums::{Event, Key},
     group::{Flex, FlexType},
     input::Input,
+    menu::Choice,
     prelude::*,
     tree::{Tree, TreeItem, TreeSelect},
 };
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::sync::Arc;
 use std::thread;
 
 use crate::db::{
-    lock_connection, try_lock_connection, CompilationError, ConstraintInfo, IndexInfo,
-    ObjectBrowser, PackageRoutine, ProcedureArgument, SequenceInfo, SharedConnection, SynonymInfo,
-    TableColumnDetail,
+    load_editable_source, lock_connection, try_lock_connection, CompilationError, ConstraintInfo,
+    EditableSource, IndexInfo, ObjectBrowser, ObjectKind, PackageRoutine, ProcedureArgument,
+    SequenceInfo, SharedConnection, SynonymInfo, TableColumnDetail,
 };
 use crate::ui::constants::*;
 use crate::ui::font_settings::FontProfile;
@@ -27,6 +29,8 @@ pub enum SqlAction {
     Insert(String),
     Append(String),
     Execute(String),
+    /// Open a stored program's source in a tab tied to the object.
+    EditSource(EditableSource),
 }
 
 /// Callback type for executing SQL from object browser
@@ -56,7 +60,14 @@ struct ObjectCache {
     triggers: Vec<String>,
     synonyms: Vec<String>,
     packages: Vec<String>,
+    types: Vec<String>,
     package_routines: HashMap<String, Vec<PackageRoutine>>,
+    /// Schemas visible to the connected user, offered in the schema selector.
+    schemas: Vec<String>,
+    /// The connected user, whose objects are read through USER_* views.
+    current_user: String,
+    /// Schema the object lists were read from (None = connected user's own).
+    owner: Option<String>,
 }
 
 #[derive(Clone)]
@@ -76,12 +87,14 @@ enum ObjectActionResult {
     SequenceInfo(Result<SequenceInfo, String>),
     SynonymInfo(Result<SynonymInfo, String>),
     Ddl(Result<String, String>),
+    Source(Result<EditableSource, String>),
     RoutineScript {
         qualified_name: String,
         routine_type: String,
         result: Result<String, String>,
     },
     PackageRoutines {
+        owner: Option<String>,
         package_name: String,
         result: Result<Vec<PackageRoutine>, String>,
     },
@@ -98,8 +111,12 @@ enum ObjectActionResult {
 pub struct ObjectBrowserWidget {
     flex: Flex,
     tree: Tree,
-    connection: SharedConnection,
+    /// Connection of the active query tab; swapped when the tab changes.
+    connection: Rc<RefCell<SharedConnection>>,
     sql_callback: SqlExecuteCallback,
+    schema_choice: Choice,
+    /// Schema browsed instead of the connected user's own (None = own schema).
+    selected_schema: Rc<RefCell<Option<String>>>,
     filter_input: Input,
     object_cache: Rc<RefCell<ObjectCache>>,
     refresh_sender: std::sync::mpsc::Sender<ObjectCache>,
@@ -113,6 +130,13 @@ impl ObjectBrowserWidget {
         flex.set_type(FlexType::Column);
         flex.set_spacing(DIALOG_SPACING);
 
+        // Schema selector (connected user's schema by default)
+        let mut schema_choice = Choice::default();
+        schema_choice.set_color(theme::input_bg());
+        schema_choice.set_text_color(theme::text_primary());
+        schema_choice.set_tooltip("Schema to browse");
+        flex.fixed(&schema_choice, FILTER_INPUT_HEIGHT);
+
         // Filter input with modern styling
         let mut filter_input = Input::default();
         filter_input.set_color(theme::input_bg());
@@ -139,6 +163,7 @@ impl ObjectBrowserWidget {
         tree.add("Triggers");
         tree.add("Synonyms");
         tree.add("Packages");
+        tree.add("Types");
 
         // Make tree resizable (takes remaining space after filter input)
         flex.resizable(&tree);
@@ -169,6 +194,9 @@ impl ObjectBrowserWidget {
         if let Some(mut item) = tree.find_item("Packages") {
             item.close();
         }
+        if let Some(mut item) = tree.find_item("Types") {
+            item.close();
+        }
 
         let sql_callback: SqlExecuteCallback = Rc::new(RefCell::new(None));
         let object_cache = Rc::new(RefCell::new(ObjectCache::default()));
@@ -179,7 +207,9 @@ impl ObjectBrowserWidget {
         let mut widget = Self {
             flex,
             tree,
-            connection,
+            connection: Rc::new(RefCell::new(connection)),
+            schema_choice,
+            selected_schema: Rc::new(RefCell::new(None)),
             filter_input,
             object_cache,
             sql_callback,
@@ -188,6 +218,7 @@ impl ObjectBrowserWidget {
         };
         widget.setup_callbacks();
         widget.setup_filter_callback();
+        widget.setup_schema_callback();
         widget.setup_refresh_handler(refresh_receiver);
         widget.setup_action_handler(action_receiver);
         widget
@@ -198,6 +229,8 @@ impl ObjectBrowserWidget {
     }
 
     pub fn apply_font_settings(&mut self, profile: FontProfile, ui_size: i32) {
+        self.schema_choice.set_text_font(profile.normal);
+        self.schema_choice.set_text_size(ui_size);
         self.filter_input.set_text_font(profile.normal);
         self.filter_input.set_text_size(ui_size);
         self.tree.set_item_label_font(profile.normal);
@@ -210,6 +243,7 @@ impl ObjectBrowserWidget {
         let (x, y, w, h) = (self.tree.x(), self.tree.y(), self.tree.w(), self.tree.h());
         self.tree.resize(x, y, w, h);
         self.flex.layout();
+        self.schema_choice.redraw();
         self.filter_input.redraw();
         self.tree.redraw();
     }
@@ -226,10 +260,81 @@ impl ObjectBrowserWidget {
         });
     }
 
+    fn setup_schema_callback(&mut self) {
+        let mut tree = self.tree.clone();
+        let object_cache = self.object_cache.clone();
+        let selected_schema = self.selected_schema.clone();
+        let sender = self.refresh_sender.clone();
+        let connection = self.connection.clone();
+
+        self.schema_choice.set_callback(move |choice| {
+            let Some(schema) = choice.choice() else {
+                return;
+            };
+            let (schemas, current_user) = {
+                let cache = object_cache.borrow();
+                (cache.schemas.clone(), cache.current_user.clone())
+            };
+            let owner = if schema.eq_ignore_ascii_case(&current_user) {
+                None
+            } else {
+                Some(schema)
+            };
+            if *selected_schema.borrow() == owner {
+                return;
+            }
+            *selected_schema.borrow_mut() = owner.clone();
+
+            // Keep the schema list so the selector stays populated while reloading.
+            *object_cache.borrow_mut() = ObjectCache {
+                schemas,
+                current_user,
+                owner: owner.clone(),
+                ..ObjectCache::default()
+            };
+            ObjectBrowserWidget::clear_tree_items(&mut tree);
+            tree.redraw();
+            let connection = connection.borrow().clone();
+            ObjectBrowserWidget::spawn_refresh(connection, sender.clone(), owner);
+        });
+    }
+
+    /// Repopulate the schema selector when the visible schema list changes and
+    /// keep the browsed schema selected.
+    fn sync_schema_choice(
+        choice: &mut Choice,
+        cache: &ObjectCache,
+        selected_schema: &Rc<RefCell<Option<String>>>,
+    ) {
+        if cache.schemas.is_empty() {
+            return;
+        }
+        let existing: Vec<String> = (0..choice.size())
+            .filter_map(|idx| choice.text(idx))
+            .collect();
+        if existing != cache.schemas {
+            choice.clear();
+            for schema in &cache.schemas {
+                choice.add_choice(schema);
+            }
+        }
+        let active = selected_schema
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| cache.current_user.clone());
+        if let Some(idx) = cache.schemas.iter().position(|schema| *schema == active) {
+            if choice.value() != idx as i32 {
+                choice.set_value(idx as i32);
+            }
+        }
+    }
+
     fn setup_refresh_handler(&mut self, refresh_receiver: std::sync::mpsc::Receiver<ObjectCache>) {
         let tree = self.tree.clone();
         let object_cache = self.object_cache.clone();
         let filter_input = self.filter_input.clone();
+        let schema_choice = self.schema_choice.clone();
+        let selected_schema = self.selected_schema.clone();
 
         // Wrap receiver in Rc<RefCell> to share across timeout callbacks
         let receiver: Rc<RefCell<std::sync::mpsc::Receiver<ObjectCache>>> =
@@ -240,6 +345,8 @@ impl ObjectBrowserWidget {
             mut tree: Tree,
             object_cache: Rc<RefCell<ObjectCache>>,
             filter_input: Input,
+            mut schema_choice: Choice,
+            selected_schema: Rc<RefCell<Option<String>>>,
         ) {
             let mut disconnected = false;
             // Process any pending messages
@@ -248,6 +355,16 @@ impl ObjectBrowserWidget {
                 loop {
                     match r.try_recv() {
                         Ok(cache) => {
+                            // Drop results of a refresh that was superseded by a
+                            // schema switch.
+                            if cache.owner != *selected_schema.borrow() {
+                                continue;
+                            }
+                            ObjectBrowserWidget::sync_schema_choice(
+                                &mut schema_choice,
+                                &cache,
+                                &selected_schema,
+                            );
                             *object_cache.borrow_mut() = cache.clone();
                             let filter_text = filter_input.value().to_lowercase();
                             ObjectBrowserWidget::populate_tree(&mut tree, &cache, &filter_text);
@@ -273,12 +390,21 @@ impl ObjectBrowserWidget {
                     tree.clone(),
                     Rc::clone(&object_cache),
                     filter_input.clone(),
+                    schema_choice.clone(),
+                    Rc::clone(&selected_schema),
                 );
             });
         }
 
         // Start polling
-        schedule_poll(receiver, tree, object_cache, filter_input);
+        schedule_poll(
+            receiver,
+            tree,
+            object_cache,
+            filter_input,
+            schema_choice,
+            selected_schema,
+        );
     }
 
     fn setup_action_handler(
@@ -470,6 +596,21 @@ impl ObjectBrowserWidget {
                                 ));
                             }
                         },
+                        ObjectActionResult::Source(result) => match result {
+                            Ok(source) => {
+                                let cb_opt = sql_callback.borrow_mut().take();
+                                if let Some(mut cb) = cb_opt {
+                                    cb(SqlAction::EditSource(source));
+                                    *sql_callback.borrow_mut() = Some(cb);
+                                }
+                            }
+                            Err(err) => {
+                                fltk::dialog::alert_default(&format!(
+                                    "Failed to load source: {}",
+                                    err
+                                ));
+                            }
+                        },
                         ObjectActionResult::RoutineScript {
                             qualified_name,
                             routine_type,
@@ -500,11 +641,16 @@ impl ObjectBrowserWidget {
                             }
                         }
                         ObjectActionResult::PackageRoutines {
+                            owner,
                             package_name,
                             result,
                         } => match result {
                             Ok(routines) => {
                                 let mut cache = object_cache.borrow_mut();
+                                if cache.owner != owner {
+                                    // The schema was switched while loading.
+                                    continue;
+                                }
                                 cache.package_routines.insert(package_name, routines);
                                 let filter_text = filter_input.value().to_lowercase();
                                 ObjectBrowserWidget::populate_tree(&mut tree, &cache, &filter_text);
@@ -599,11 +745,14 @@ impl ObjectBrowserWidget {
         let sql_callback = self.sql_callback.clone();
         let action_sender = self.action_sender.clone();
         let object_cache = self.object_cache.clone();
+        let selected_schema = self.selected_schema.clone();
 
         self.tree.handle(move |t, ev| {
             if !t.active() {
                 return false;
             }
+            let owner = selected_schema.borrow().clone();
+            let connection = connection.borrow().clone();
             match ev {
                 Event::Push => {
                     let mouse_button = fltk::app::event_mouse_button();
@@ -621,6 +770,7 @@ impl ObjectBrowserWidget {
                                 &item,
                                 &sql_callback,
                                 &action_sender,
+                                owner.as_deref(),
                             );
                         } else if let Some(item) = t.first_selected_item() {
                             Self::show_context_menu(
@@ -628,6 +778,7 @@ impl ObjectBrowserWidget {
                                 &item,
                                 &sql_callback,
                                 &action_sender,
+                                owner.as_deref(),
                             );
                         }
                         return true;
@@ -651,6 +802,7 @@ impl ObjectBrowserWidget {
                                         if should_fetch {
                                             let connection = connection.clone();
                                             let sender = action_sender.clone();
+                                            let owner = owner.clone();
                                             thread::spawn(move || {
                                                 // Try to acquire connection lock without blocking
                                                 let Some(conn_guard) =
@@ -669,8 +821,9 @@ impl ObjectBrowserWidget {
                                                 } else if let Some(db_conn) =
                                                     conn_guard.get_connection()
                                                 {
-                                                    ObjectBrowser::get_package_routines(
+                                                    ObjectBrowser::get_package_routines_for_owner(
                                                         db_conn.as_ref(),
+                                                        owner.as_deref(),
                                                         &package_name,
                                                     )
                                                     .map_err(|err| err.to_string())
@@ -680,6 +833,7 @@ impl ObjectBrowserWidget {
 
                                                 let _ = sender.send(
                                                     ObjectActionResult::PackageRoutines {
+                                                        owner,
                                                         package_name,
                                                         result,
                                                     },
@@ -693,7 +847,9 @@ impl ObjectBrowserWidget {
                                 }
 
                                 // Double-click on other items: insert text into SQL editor
-                                if let Some(insert_text) = Self::get_insert_text(&item) {
+                                if let Some(insert_text) =
+                                    Self::get_insert_text(&item, owner.as_deref())
+                                {
                                     // Take the callback out, call it, then put it back
                                     // This ensures the RefCell is not borrowed during callback execution
                                     let cb_opt = sql_callback.borrow_mut().take();
@@ -721,7 +877,7 @@ impl ObjectBrowserWidget {
                                 if object_type == "TABLES" || object_type == "VIEWS" {
                                     let sql = format!(
                                         "SELECT * FROM {} WHERE ROWNUM <= 100",
-                                        object_name
+                                        Self::qualify_name(owner.as_deref(), &object_name)
                                     );
                                     // Take the callback out, call it, then put it back
                                     let cb_opt = sql_callback.borrow_mut().take();
@@ -797,7 +953,7 @@ impl ObjectBrowserWidget {
 
         match parent_type_upper.as_str() {
             "TABLES" | "VIEWS" | "PROCEDURES" | "FUNCTIONS" | "SEQUENCES" | "TRIGGERS"
-            | "SYNONYMS" | "PACKAGES" => Some(ObjectItem::Simple {
+            | "SYNONYMS" | "PACKAGES" | "TYPES" => Some(ObjectItem::Simple {
                 object_type: parent_type_upper,
                 object_name,
             }),
@@ -805,18 +961,32 @@ impl ObjectBrowserWidget {
         }
     }
 
-    fn get_insert_text(item: &TreeItem) -> Option<String> {
+    fn get_insert_text(item: &TreeItem, owner: Option<&str>) -> Option<String> {
         match Self::get_item_info(item) {
-            Some(ObjectItem::Simple { object_name, .. }) => Some(object_name),
+            Some(ObjectItem::Simple { object_name, .. }) => {
+                Some(Self::qualify_name(owner, &object_name))
+            }
             Some(ObjectItem::PackageRoutine {
                 package_name,
                 routine_name,
                 ..
-            }) => Some(format!("{}.{}", package_name, routine_name)),
+            }) => Some(format!(
+                "{}.{}",
+                Self::qualify_name(owner, &package_name),
+                routine_name
+            )),
             None => None,
         }
     }
 
+    /// Prefix an object name with its owner when browsing another schema.
+    fn qualify_name(owner: Option<&str>, name: &str) -> String {
+        match owner {
+            Some(owner) => format!("{}.{}", owner, name),
+            None => name.to_string(),
+        }
+    }
+
     fn build_simple_procedure_script(qualified_name: &str) -> String {
         format!("BEGIN\n  {};\nEND;\n/\n", qualified_name)
     }
@@ -832,7 +1002,10 @@ impl ObjectBrowserWidget {
         )
     }
 
-    fn build_procedure_script(qualified_name: &str, arguments: &[ProcedureArgument]) -> String {
+    pub(crate) fn build_procedure_script(
+        qualified_name: &str,
+        arguments: &[ProcedureArgument],
+    ) -> String {
         if arguments.is_empty() {
             return Self::build_simple_procedure_script(qualified_name);
         }
@@ -1187,7 +1360,9 @@ impl ObjectBrowserWidget {
         item: &TreeItem,
         sql_callback: &SqlExecuteCallback,
         action_sender: &std::sync::mpsc::Sender<ObjectActionResult>,
+        owner: Option<&str>,
     ) {
+        let owner_name = owner.map(|owner| owner.to_string());
         if let Some(item_info) = Self::get_item_info(item) {
             let menu_choices = match &item_info {
                 ObjectItem::Simple { object_type, .. } if object_type == "TABLES" => {
@@ -1200,16 +1375,16 @@ impl ObjectBrowserWidget {
                     if object_type == "PROCEDURES" || object_type == "FUNCTIONS" =>
                 {
                     if object_type == "PROCEDURES" {
-                        "Execute Procedure|Check Compilation|Generate DDL"
+                        "Execute Procedure|Check Compilation|Generate DDL|Edit Source"
                     } else {
-                        "Execute Function|Check Compilation|Generate DDL"
+                        "Execute Function|Check Compilation|Generate DDL|Edit Source"
                     }
                 }
                 ObjectItem::Simple { object_type, .. } if object_type == "SEQUENCES" => {
                     "View Info|Generate DDL"
                 }
                 ObjectItem::Simple { object_type, .. } if object_type == "TRIGGERS" => {
-                    "Check Compilation|Generate DDL"
+                    "Check Compilation|Generate DDL|Edit Source"
                 }
                 ObjectItem::Simple { object_type, .. } if object_type == "SYNONYMS" => {
                     "View Info|Generate DDL"
@@ -1222,7 +1397,10 @@ impl ObjectBrowserWidget {
                     }
                 }
                 ObjectItem::Simple { object_type, .. } if object_type == "PACKAGES" => {
-                    "Check Compilation|Generate DDL"
+                    "Check Compilation|Generate DDL|Edit Source"
+                }
+                ObjectItem::Simple { object_type, .. } if object_type == "TYPES" => {
+                    "Check Compilation|Generate DDL|Edit Source"
                 }
                 _ => return,
             };
@@ -1260,7 +1438,10 @@ impl ObjectBrowserWidget {
                             return;
                         }
                         drop(conn_guard);
-                        let sql = format!("SELECT * FROM {} WHERE ROWNUM <= 100", object_name);
+                        let sql = format!(
+                            "SELECT * FROM {} WHERE ROWNUM <= 100",
+                            Self::qualify_name(owner, object_name)
+                        );
                         let cb_opt = sql_callback.borrow_mut().take();
                         if let Some(mut cb) = cb_opt {
                             cb(SqlAction::Execute(sql));
@@ -1278,7 +1459,9 @@ impl ObjectBrowserWidget {
                     {
                         let connection = connection.clone();
                         let sender = action_sender.clone();
+                        let owner = owner_name.clone();
                         let object_name = object_name.clone();
+                        let qualified_name = Self::qualify_name(owner.as_deref(), &object_name);
                         let routine_type = if label == "Execute Function" {
                             "FUNCTION".to_string()
                         } else {
@@ -1296,13 +1479,14 @@ impl ObjectBrowserWidget {
                             let result = if !conn_guard.is_connected() {
                                 Err("Not connected to database".to_string())
                             } else if let Some(db_conn) = conn_guard.get_connection() {
-                                ObjectBrowser::get_procedure_arguments(
+                                ObjectBrowser::get_procedure_arguments_for_owner(
                                     db_conn.as_ref(),
+                                    owner.as_deref(),
                                     &object_name,
                                 )
                                 .map(|arguments| {
                                     ObjectBrowserWidget::build_procedure_script(
-                                        &object_name,
+                                        &qualified_name,
                                         &arguments,
                                     )
                                 })
@@ -1312,7 +1496,7 @@ impl ObjectBrowserWidget {
                             };
 
                             let _ = sender.send(ObjectActionResult::RoutineScript {
-                                qualified_name: object_name,
+                                qualified_name,
                                 routine_type,
                                 result,
                             });
@@ -1332,7 +1516,12 @@ impl ObjectBrowserWidget {
                     {
                         let connection = connection.clone();
                         let sender = action_sender.clone();
-                        let qualified_name = format!("{}.{}", package_name, routine_name);
+                        let owner = owner_name.clone();
+                        let qualified_name = format!(
+                            "{}.{}",
+                            Self::qualify_name(owner.as_deref(), package_name),
+                            routine_name
+                        );
                         let package_name = package_name.clone();
                         let routine_name = routine_name.clone();
                         let routine_type = routine_type.clone();
@@ -1348,8 +1537,9 @@ impl ObjectBrowserWidget {
                             let result = if !conn_guard.is_connected() {
                                 Err("Not connected to database".to_string())
                             } else if let Some(db_conn) = conn_guard.get_connection() {
-                                ObjectBrowser::get_package_procedure_arguments(
+                                ObjectBrowser::get_package_procedure_arguments_for_owner(
                                     db_conn.as_ref(),
+                                    owner.as_deref(),
                                     &package_name,
                                     &routine_name,
                                 )
@@ -1385,10 +1575,12 @@ impl ObjectBrowserWidget {
                             "FUNCTIONS" => "FUNCTION",
                             "PACKAGES" => "PACKAGE",
                             "TRIGGERS" => "TRIGGER",
+                            "TYPES" => "TYPE",
                             _ => return,
                         };
                         let connection = connection.clone();
                         let sender = action_sender.clone();
+                        let owner = owner_name.clone();
                         let object_name = object_name.clone();
                         let object_type = db_object_type.to_string();
                         thread::spawn(move || {
@@ -1409,39 +1601,47 @@ impl ObjectBrowserWidget {
                                 });
                                 app::awake();
                             } else if let Some(db_conn) = conn_guard.get_connection() {
-                                let status = ObjectBrowser::get_object_status(
+                                let status = ObjectBrowser::get_object_status_for_owner(
                                     db_conn.as_ref(),
+                                    owner.as_deref(),
                                     &object_name,
                                     &object_type,
                                 )
                                 .unwrap_or_else(|_| "UNKNOWN".to_string());
 
-                                // Also check PACKAGE BODY status for packages
-                                let body_status = if object_type == "PACKAGE" {
-                                    ObjectBrowser::get_object_status(
+                                // Also check the body status for packages and types
+                                let has_body = matches!(object_type.as_str(), "PACKAGE" | "TYPE");
+                                let body_type = format!("{} BODY", object_type);
+                                let body_status = if has_body {
+                                    ObjectBrowser::get_object_status_for_owner(
                                         db_conn.as_ref(),
+                                        owner.as_deref(),
                                         &object_name,
-                                        "PACKAGE BODY",
+                                        &body_type,
                                     )
                                     .ok()
                                 } else {
                                     None
                                 };
 
-                                let mut errors = ObjectBrowser::get_compilation_errors(
+                                let mut errors = ObjectBrowser::get_compilation_errors_for_owner(
                                     db_conn.as_ref(),
+                                    owner.as_deref(),
                                     &object_name,
                                     &object_type,
                                 )
                                 .unwrap_or_default();
 
-                                // For packages, also get PACKAGE BODY errors
-                                if object_type == "PACKAGE" {
-                                    if let Ok(body_errors) = ObjectBrowser::get_compilation_errors(
-                                        db_conn.as_ref(),
-                                        &object_name,
-                                        "PACKAGE BODY",
-                                    ) {
+                                // For packages and types, also get the body errors
+                                if has_body {
+                                    if let Ok(body_errors) =
+                                        ObjectBrowser::get_compilation_errors_for_owner(
+                                            db_conn.as_ref(),
+                                            owner.as_deref(),
+                                            &object_name,
+                                            &body_type,
+                                        )
+                                    {
                                         errors.extend(body_errors);
                                     }
                                 }
@@ -1472,10 +1672,54 @@ impl ObjectBrowserWidget {
                             // conn_guard drops here, releasing the lock
                         });
                     }
+                    (
+                        "Edit Source",
+                        ObjectItem::Simple {
+                            object_type,
+                            object_name,
+                        },
+                    ) => {
+                        let db_object_type = match object_type.as_str() {
+                            "PROCEDURES" => "PROCEDURE",
+                            "FUNCTIONS" => "FUNCTION",
+                            "PACKAGES" => "PACKAGE",
+                            "TRIGGERS" => "TRIGGER",
+                            "TYPES" => "TYPE",
+                            _ => return,
+                        };
+                        let connection = connection.clone();
+                        let sender = action_sender.clone();
+                        let owner = owner_name.clone();
+                        let object_name = object_name.clone();
+                        thread::spawn(move || {
+                            // Try to acquire connection lock without blocking
+                            let Some(conn_guard) = try_lock_connection(&connection) else {
+                                // Query is already running, notify user
+                                let _ = sender.send(ObjectActionResult::QueryAlreadyRunning);
+                                app::awake();
+                                return;
+                            };
+
+                            let result = match conn_guard.get_connection() {
+                                Some(db_conn) if conn_guard.is_connected() => load_editable_source(
+                                    db_conn.as_ref(),
+                                    owner.as_deref(),
+                                    &object_name,
+                                    db_object_type,
+                                ),
+                                _ => Err("Not connected to database".to_string()),
+                            };
+                            let _ = sender.send(ObjectActionResult::Source(result));
+                            app::awake();
+                            // conn_guard drops here, releasing the lock
+                        });
+                    }
                     ("View Structure", ObjectItem::Simple { object_name, .. }) => {
                         let connection = connection.clone();
                         let sender = action_sender.clone();
-                        let table_name = object_name.clone();
+                        let owner = owner_name.clone();
+                        let object_name = object_name.clone();
+                        let table_name = Self::qualify_name(owner.as_deref(), &object_name);
                         thread::spawn(move || {
                             // Try to acquire connection lock without blocking
                             let Some(conn_guard) = try_lock_connection(&connection) else {
@@ -1488,8 +1732,12 @@ impl ObjectBrowserWidget {
                             let result = if !conn_guard.is_connected() {
                                 Err("Not connected to database".to_string())
                             } else if let Some(db_conn) = conn_guard.get_connection() {
-                                ObjectBrowser::get_table_structure(db_conn.as_ref(), &table_name)
-                                    .map_err(|err| err.to_string())
+                                ObjectBrowser::get_table_structure_for_owner(
+                                    db_conn.as_ref(),
+                                    owner.as_deref(),
+                                    &object_name,
+                                )
+                                .map_err(|err| err.to_string())
                             } else {
                                 Err("Not connected to database".to_string())
                             };
@@ -1502,7 +1750,9 @@ impl ObjectBrowserWidget {
                     ("View Indexes", ObjectItem::Simple { object_name, .. }) => {
                         let connection = connection.clone();
                         let sender = action_sender.clone();
-                        let table_name = object_name.clone();
+                        let owner = owner_name.clone();
+                        let object_name = object_name.clone();
+                        let table_name = Self::qualify_name(owner.as_deref(), &object_name);
                         thread::spawn(move || {
                             // Try to acquire connection lock without blocking
                             let Some(conn_guard) = try_lock_connection(&connection) else {
@@ -1515,8 +1765,12 @@ impl ObjectBrowserWidget {
                             let result = if !conn_guard.is_connected() {
                                 Err("Not connected to database".to_string())
                             } else if let Some(db_conn) = conn_guard.get_connection() {
-                                ObjectBrowser::get_table_indexes(db_conn.as_ref(), &table_name)
-                                    .map_err(|err| err.to_string())
+                                ObjectBrowser::get_table_indexes_for_owner(
+                                    db_conn.as_ref(),
+                                    owner.as_deref(),
+                                    &object_name,
+                                )
+                                .map_err(|err| err.to_string())
                             } else {
                                 Err("Not connected to database".to_string())
                             };
@@ -1529,7 +1783,9 @@ impl ObjectBrowserWidget {
                     ("View Constraints", ObjectItem::Simple { object_name, .. }) => {
                         let connection = connection.clone();
                         let sender = action_sender.clone();
-                        let table_name = object_name.clone();
+                        let owner = owner_name.clone();
+                        let object_name = object_name.clone();
+                        let table_name = Self::qualify_name(owner.as_deref(), &object_name);
                         thread::spawn(move || {
                             // Try to acquire connection lock without blocking
                             let Some(conn_guard) = try_lock_connection(&connection) else {
@@ -1542,8 +1798,12 @@ impl ObjectBrowserWidget {
                             let result = if !conn_guard.is_connected() {
                                 Err("Not connected to database".to_string())
                             } else if let Some(db_conn) = conn_guard.get_connection() {
-                                ObjectBrowser::get_table_constraints(db_conn.as_ref(), &table_name)
-                                    .map_err(|err| err.to_string())
+                                ObjectBrowser::get_table_constraints_for_owner(
+                                    db_conn.as_ref(),
+                                    owner.as_deref(),
+                                    &object_name,
+                                )
+                                .map_err(|err| err.to_string())
                             } else {
                                 Err("Not connected to database".to_string())
                             };
@@ -1562,6 +1822,7 @@ impl ObjectBrowserWidget {
                     ) => {
                         let connection = connection.clone();
                         let sender = action_sender.clone();
+                        let owner = owner_name.clone();
                         let name = object_name.clone();
                         let obj_type = object_type.clone();
                         thread::spawn(move || {
@@ -1604,8 +1865,9 @@ impl ObjectBrowserWidget {
                             if let Some(db_conn) = conn_guard.get_connection() {
                                 match obj_type.as_str() {
                                     "SYNONYMS" => {
-                                        let result = ObjectBrowser::get_synonym_info(
+                                        let result = ObjectBrowser::get_synonym_info_for_owner(
                                             db_conn.as_ref(),
+                                            owner.as_deref(),
                                             &name,
                                         )
                                         .map_err(|err| err.to_string());
@@ -1613,8 +1875,9 @@ impl ObjectBrowserWidget {
                                             sender.send(ObjectActionResult::SynonymInfo(result));
                                     }
                                     _ => {
-                                        let result = ObjectBrowser::get_sequence_info(
+                                        let result = ObjectBrowser::get_sequence_info_for_owner(
                                             db_conn.as_ref(),
+                                            owner.as_deref(),
                                             &name,
                                         )
                                         .map_err(|err| err.to_string());
@@ -1645,11 +1908,13 @@ impl ObjectBrowserWidget {
                             "TRIGGERS" => Some("TRIGGER"),
                             "SYNONYMS" => Some("SYNONYM"),
                             "PACKAGES" => Some("PACKAGE"),
+                            "TYPES" => Some("TYPE"),
                             _ => None,
                         };
                         if let Some(obj_type) = obj_type {
                             let connection = connection.clone();
                             let sender = action_sender.clone();
+                            let owner = owner_name.clone();
                             let object_type = obj_type.to_string();
                             let object_name = object_name.clone();
                             thread::spawn(move || {
@@ -1665,37 +1930,47 @@ impl ObjectBrowserWidget {
                                     Err("Not connected to database".to_string())
                                 } else if let Some(db_conn) = conn_guard.get_connection() {
                                     match object_type.as_str() {
-                                        "TABLE" => ObjectBrowser::get_table_ddl(
+                                        "TABLE" => ObjectBrowser::get_table_ddl_for_owner(
                                             db_conn.as_ref(),
+                                            owner.as_deref(),
                                             &object_name,
                                         ),
-                                        "VIEW" => ObjectBrowser::get_view_ddl(
+                                        "VIEW" => ObjectBrowser::get_view_ddl_for_owner(
                                             db_conn.as_ref(),
+                                            owner.as_deref(),
                                             &object_name,
                                         ),
-                                        "PROCEDURE" => ObjectBrowser::get_procedure_ddl(
+                                        "PROCEDURE" => ObjectBrowser::get_procedure_ddl_for_owner(
                                             db_conn.as_ref(),
+                                            owner.as_deref(),
                                             &object_name,
                                         ),
-                                        "FUNCTION" => ObjectBrowser::get_function_ddl(
+                                        "FUNCTION" => ObjectBrowser::get_function_ddl_for_owner(
                                             db_conn.as_ref(),
+                                            owner.as_deref(),
                                             &object_name,
                                         ),
-                                        "SEQUENCE" => ObjectBrowser::get_sequence_ddl(
+                                        "SEQUENCE" => ObjectBrowser::get_sequence_ddl_for_owner(
                                             db_conn.as_ref(),
+                                            owner.as_deref(),
                                             &object_name,
                                         ),
-                                        "TRIGGER" => ObjectBrowser::get_object_ddl(
-                                            db_conn.as_ref(),
-                                            "TRIGGER",
-                                            &object_name,
-                                        ),
-                                        "SYNONYM" => ObjectBrowser::get_synonym_ddl(
+                                        "TRIGGER" | "TYPE" => {
+                                            ObjectBrowser::get_object_ddl_for_owner(
+                                                db_conn.as_ref(),
+                                                owner.as_deref(),
+                                                &object_type,
+                                                &object_name,
+                                            )
+                                        }
+                                        "SYNONYM" => ObjectBrowser::get_synonym_ddl_for_owner(
                                             db_conn.as_ref(),
+                                            owner.as_deref(),
                                             &object_name,
                                         ),
-                                        "PACKAGE" => ObjectBrowser::get_package_spec_ddl(
+                                        "PACKAGE" => ObjectBrowser::get_package_spec_ddl_for_owner(
                                             db_conn.as_ref(),
+                                            owner.as_deref(),
                                             &object_name,
                                         ),
                                         _ => return,
@@ -1776,11 +2051,39 @@ impl ObjectBrowserWidget {
         // First clear items and filter
         self.clear_items();
         self.filter_input.set_value("");
-        *self.object_cache.borrow_mut() = ObjectCache::default();
+        let owner = self.selected_schema.borrow().clone();
+        *self.object_cache.borrow_mut() = ObjectCache {
+            owner: owner.clone(),
+            ..ObjectCache::default()
+        };
 
-        let sender = self.refresh_sender.clone();
-        let connection = self.connection.clone();
+        let connection = self.connection.borrow().clone();
+        Self::spawn_refresh(connection, self.refresh_sender.clone(), owner);
+    }
+
+    /// Browse `connection` instead, e.g. when another query tab is selected.
+    /// Does nothing if it is already the browsed connection.
+    pub fn set_connection(&mut self, connection: SharedConnection) {
+        if Arc::ptr_eq(&self.connection.borrow(), &connection) {
+            return;
+        }
+        *self.connection.borrow_mut() = connection;
+        self.reset_schema();
+        self.refresh();
+    }
 
+    /// Go back to browsing the connected user's own schema, e.g. after the
+    /// connection changed.
+    pub fn reset_schema(&mut self) {
+        *self.selected_schema.borrow_mut() = None;
+        self.schema_choice.clear();
+    }
+
+    fn spawn_refresh(
+        connection: SharedConnection,
+        sender: std::sync::mpsc::Sender<ObjectCache>,
+        owner: Option<String>,
+    ) {
         thread::spawn(move || {
             // Acquire connection lock and hold it during all queries
             let conn_guard = lock_connection(&connection);
@@ -1788,55 +2091,45 @@ impl ObjectBrowserWidget {
                 return;
             }
 
-            let Some(db_conn) = conn_guard.get_connection() else {
+            let Some(backend) = conn_guard.get_backend() else {
                 return;
             };
             // Keep conn_guard alive (don't drop it) so the lock is held during execution
 
-            let mut cache = ObjectCache::default();
+            let mut cache = ObjectCache {
+                owner: owner.clone(),
+                ..ObjectCache::default()
+            };
             let send_update = |sender: &std::sync::mpsc::Sender<ObjectCache>,
                                cache: &ObjectCache| {
                 let _ = sender.send(cache.clone());
                 app::awake();
             };
 
-            if let Ok(tables) = ObjectBrowser::get_tables(db_conn.as_ref()) {
-                cache.tables = tables;
-                send_update(&sender, &cache);
-            }
-
-            if let Ok(views) = ObjectBrowser::get_views(db_conn.as_ref()) {
-                cache.views = views;
-                send_update(&sender, &cache);
-            }
-
-            if let Ok(procedures) = ObjectBrowser::get_procedures(db_conn.as_ref()) {
-                cache.procedures = procedures;
-                send_update(&sender, &cache);
-            }
-
-            if let Ok(functions) = ObjectBrowser::get_functions(db_conn.as_ref()) {
-                cache.functions = functions;
-                send_update(&sender, &cache);
-            }
-
-            if let Ok(sequences) = ObjectBrowser::get_sequences(db_conn.as_ref()) {
-                cache.sequences = sequences;
-                send_update(&sender, &cache);
+            if let Ok(current_user) = backend.current_user() {
+                cache.current_user = current_user;
             }
-
-            if let Ok(triggers) = ObjectBrowser::get_triggers(db_conn.as_ref()) {
-                cache.triggers = triggers;
-                send_update(&sender, &cache);
-            }
-
-            if let Ok(synonyms) = ObjectBrowser::get_synonyms(db_conn.as_ref()) {
-                cache.synonyms = synonyms;
+            if let Ok(schemas) = backend.schemas() {
+                cache.schemas = schemas;
                 send_update(&sender, &cache);
             }
 
-            if let Ok(packages) = ObjectBrowser::get_packages(db_conn.as_ref()) {
-                cache.packages = packages;
+            let owner = owner.as_deref();
+            for kind in ObjectKind::ALL {
+                let Ok(names) = backend.list_objects(owner, kind) else {
+                    continue;
+                };
+                match kind {
+                    ObjectKind::Table => cache.tables = names,
+                    ObjectKind::View => cache.views = names,
+                    ObjectKind::Procedure => cache.procedures = names,
+                    ObjectKind::Function => cache.functions = names,
+                    ObjectKind::Sequence => cache.sequences = names,
+                    ObjectKind::Trigger => cache.triggers = names,
+                    ObjectKind::Synonym => cache.synonyms = names,
+                    ObjectKind::Package => cache.packages = names,
+                    ObjectKind::Type => cache.types = names,
+                }
                 send_update(&sender, &cache);
             }
 
@@ -1858,6 +2151,7 @@ impl ObjectBrowserWidget {
             "Triggers",
             "Synonyms",
             "Packages",
+            "Types",
         ];
 
         for category in categories {
@@ -1883,6 +2177,7 @@ impl ObjectBrowserWidget {
             "Triggers",
             "Synonyms",
             "Packages",
+            "Types",
         ];
 
         for category in categories {
@@ -1934,6 +2229,11 @@ impl ObjectBrowserWidget {
                 tree.add(&format!("Synonyms/{}", syn));
             }
         }
+        for object_type in &cache.types {
+            if filter_text.is_empty() || object_type.to_lowercase().contains(filter_text) {
+                tree.add(&format!("Types/{}", object_type));
+            }
+        }
 
         for package in &cache.packages {
             let routines = cache
@@ -0,0 +1,362 @@
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    button::Button,
+    dialog::{FileDialog, FileDialogType},
+    enums::FrameType,
+    frame::Frame,
+    group::{Flex, FlexType},
+    prelude::*,
+    text::{TextBuffer, TextDisplay},
+    window::Window,
+};
+use std::collections::HashSet;
+use std::sync::mpsc;
+
+use crate::db::{export_rows_to_file, ExecutionPlan, PlanSource, PlanStep};
+use crate::ui::constants::*;
+use crate::ui::{
+    center_on_main, configured_editor_profile, configured_ui_font_size, show_export_dialog, theme,
+};
+
+/// Browser column widths: operation, object, cost, rows, bytes, access and
+/// filter predicates, then the runtime columns of actual plans.
+const PLAN_COLUMN_WIDTHS: [i32; 11] = [340, 170, 70, 80, 80, 220, 220, 60, 80, 90, 80];
+
+#[derive(Clone, Copy)]
+enum PlanMessage {
+    Select,
+    Toggle,
+    ExpandAll,
+    CollapseAll,
+    Export,
+    Close,
+}
+
+fn format_number(value: Option<u64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn format_elapsed(elapsed_us: Option<u64>) -> String {
+    elapsed_us
+        .map(|us| format!("{:.2}", us as f64 / 1000.0))
+        .unwrap_or_default()
+}
+
+fn header_line(with_stats: bool) -> String {
+    let mut columns = vec![
+        "Operation",
+        "Object",
+        "Cost",
+        "Rows",
+        "Bytes",
+        "Access",
+        "Filter",
+    ];
+    if with_stats {
+        columns.extend(["Starts", "A-Rows", "A-Time ms", "Buffers"]);
+    }
+    columns
+        .iter()
+        .map(|column| format!("@C{}@b@.{column}", theme::text_primary().bits()))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+fn step_line(
+    plan: &ExecutionPlan,
+    step: &PlanStep,
+    collapsed: &HashSet<u32>,
+    hot: bool,
+    with_stats: bool,
+) -> String {
+    let marker = if !plan.has_children(step.id) {
+        "    "
+    } else if collapsed.contains(&step.id) {
+        "[+] "
+    } else {
+        "[-] "
+    };
+    let mut columns = vec![
+        format!(
+            "{}{}{}",
+            "  ".repeat(step.depth as usize),
+            marker,
+            step.operation_label()
+        ),
+        step.object_label(),
+        format_number(step.cost),
+        format_number(step.cardinality),
+        format_number(step.bytes),
+        step.access_predicates.clone().unwrap_or_default(),
+        step.filter_predicates.clone().unwrap_or_default(),
+    ];
+    if with_stats {
+        let stats = step.runtime.clone().unwrap_or_default();
+        columns.push(format_number(stats.starts));
+        columns.push(format_number(stats.actual_rows));
+        columns.push(format_elapsed(stats.elapsed_us));
+        columns.push(format_number(stats.buffer_gets));
+    }
+    // Format codes apply per column; "@." ends them so text starting with
+    // '@' is shown as is.
+    let color = theme::text_primary().bits();
+    let prefix = if hot {
+        format!("@C{color}@B{}@b@.", theme::button_warning().bits())
+    } else {
+        format!("@C{color}@.")
+    };
+    columns
+        .iter()
+        .map(|column| format!("{prefix}{}", column.replace('\t', " ")))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+fn step_details(plan: &ExecutionPlan, step: &PlanStep) -> String {
+    let mut lines = vec![format!(
+        "Id {}: {} {}",
+        step.id,
+        step.operation_label(),
+        step.object_label()
+    )];
+    lines.push(format!(
+        "Cost {}  Rows {}  Bytes {}  Self weight {}",
+        format_number(step.cost),
+        format_number(step.cardinality),
+        format_number(step.bytes),
+        plan.self_weight(step)
+    ));
+    if let Some(stats) = &step.runtime {
+        lines.push(format!(
+            "Actual: starts {}  rows {}  time {} ms  buffers {}  reads {}",
+            format_number(stats.starts),
+            format_number(stats.actual_rows),
+            format_elapsed(stats.elapsed_us),
+            format_number(stats.buffer_gets),
+            format_number(stats.disk_reads)
+        ));
+    }
+    if let Some(access) = &step.access_predicates {
+        lines.push(format!("Access: {access}"));
+    }
+    if let Some(filter) = &step.filter_predicates {
+        lines.push(format!("Filter: {filter}"));
+    }
+    lines.join("\n")
+}
+
+/// Fill the browser with the visible steps; returns the step id of each
+/// line after the header.
+fn populate(
+    browser: &mut HoldBrowser,
+    plan: &ExecutionPlan,
+    collapsed: &HashSet<u32>,
+    hot: &[u32],
+) -> Vec<u32> {
+    let with_stats = plan.has_runtime_stats();
+    browser.clear();
+    browser.add(&header_line(with_stats));
+    let mut line_ids = Vec::new();
+    for step in plan.visible_steps(collapsed) {
+        let is_hot = hot.contains(&step.id);
+        browser.add(&step_line(plan, step, collapsed, is_hot, with_stats));
+        line_ids.push(step.id);
+    }
+    line_ids
+}
+
+fn export_plan(plan: &ExecutionPlan) {
+    let Some(request) = show_export_dialog(false) else {
+        return;
+    };
+    let format = request.options.format;
+    let mut dialog = FileDialog::new(FileDialogType::BrowseSaveFile);
+    dialog.set_filter(&format.file_filter());
+    dialog.show();
+    let mut filename = dialog.filename();
+    if filename.as_os_str().is_empty() {
+        return;
+    }
+    if filename.extension().is_none() {
+        filename.set_extension(format.extension());
+    }
+    match export_rows_to_file(
+        &filename,
+        request.options,
+        &plan.export_headers(),
+        &plan.export_rows(),
+    ) {
+        Ok(count) => fltk::dialog::message_default(&format!(
+            "Exported {} plan steps to {}",
+            count,
+            filename.display()
+        )),
+        Err(err) => fltk::dialog::alert_default(&format!("Failed to export plan: {}", err)),
+    }
+}
+
+fn styled_button(label: &str, color: fltk::enums::Color) -> Button {
+    let mut button = Button::default().with_label(label);
+    button.set_color(color);
+    button.set_label_color(theme::text_primary());
+    button.set_frame(FrameType::RFlatBox);
+    button
+}
+
+/// Show an execution plan as a collapsible tree. Double-click an operation
+/// to fold its children; the most expensive steps are highlighted.
+pub fn show_plan_dialog(plan: &ExecutionPlan) {
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 1100;
+    let height = 600;
+    let title = match &plan.source {
+        PlanSource::Explain => "Explain Plan".to_string(),
+        PlanSource::Cursor {
+            sql_id,
+            child_number,
+        } => format!("Actual Plan - SQL_ID {sql_id}, child {child_number}"),
+    };
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label(&title);
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+    dialog.make_modal(true);
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let hint = if plan.source == PlanSource::Explain {
+        "Optimizer estimates. Highlighted steps carry the most cost."
+    } else if plan.has_runtime_stats() {
+        "Last execution statistics. Highlighted steps took the most time."
+    } else {
+        "No runtime statistics were gathered for this cursor."
+    };
+    let mut hint_label = Frame::default().with_label(hint);
+    hint_label.set_label_color(theme::text_secondary());
+    main_flex.fixed(&hint_label, LABEL_ROW_HEIGHT);
+
+    let mut browser = HoldBrowser::default();
+    browser.set_color(theme::input_bg());
+    browser.set_selection_color(theme::selection_strong());
+    browser.set_text_size(configured_ui_font_size());
+    browser.set_column_char('\t');
+    let column_count = if plan.has_runtime_stats() { 11 } else { 7 };
+    browser.set_column_widths(&PLAN_COLUMN_WIDTHS[..column_count]);
+
+    let mut details = TextDisplay::default();
+    let details_buffer = TextBuffer::default();
+    details.set_buffer(details_buffer.clone());
+    details.set_color(theme::editor_bg());
+    details.set_text_color(theme::text_primary());
+    details.set_text_font(configured_editor_profile().normal);
+    details.set_text_size(configured_ui_font_size());
+    main_flex.fixed(&details, 110);
+
+    let mut button_row = Flex::default();
+    button_row.set_type(FlexType::Row);
+    button_row.set_spacing(DIALOG_SPACING);
+    let mut expand_btn = styled_button("Expand All", theme::button_subtle());
+    let mut collapse_btn = styled_button("Collapse All", theme::button_subtle());
+    let _spacer = Frame::default();
+    let mut export_btn = styled_button("Export...", theme::button_secondary());
+    let mut close_btn = styled_button("Close", theme::button_primary());
+    button_row.fixed(&expand_btn, BUTTON_WIDTH_LARGE);
+    button_row.fixed(&collapse_btn, BUTTON_WIDTH_LARGE);
+    button_row.fixed(&export_btn, BUTTON_WIDTH);
+    button_row.fixed(&close_btn, BUTTON_WIDTH);
+    button_row.end();
+    main_flex.fixed(&button_row, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    let (sender, receiver) = mpsc::channel::<PlanMessage>();
+    let sender_for_browser = sender.clone();
+    browser.set_callback(move |_| {
+        let message = if app::event_clicks() {
+            PlanMessage::Toggle
+        } else {
+            PlanMessage::Select
+        };
+        let _ = sender_for_browser.send(message);
+        app::awake();
+    });
+    for (button, message) in [
+        (&mut expand_btn, PlanMessage::ExpandAll),
+        (&mut collapse_btn, PlanMessage::CollapseAll),
+        (&mut export_btn, PlanMessage::Export),
+        (&mut close_btn, PlanMessage::Close),
+    ] {
+        let sender = sender.clone();
+        button.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+
+    let hot = plan.expensive_step_ids();
+    let mut collapsed: HashSet<u32> = HashSet::new();
+    let mut line_ids = populate(&mut browser, plan, &collapsed, &hot);
+
+    dialog.show();
+    let _ = close_btn.take_focus();
+
+    while dialog.shown() {
+        app::wait();
+        while let Ok(message) = receiver.try_recv() {
+            // Line 1 is the header.
+            let selected_id = usize::try_from(browser.value() - 2)
+                .ok()
+                .and_then(|index| line_ids.get(index).copied());
+            match message {
+                PlanMessage::Select => {
+                    let text = selected_id
+                        .and_then(|id| plan.steps.iter().find(|step| step.id == id))
+                        .map(|step| step_details(plan, step))
+                        .unwrap_or_default();
+                    details_buffer.clone().set_text(&text);
+                }
+                PlanMessage::Toggle => {
+                    let Some(id) = selected_id.filter(|id| plan.has_children(*id)) else {
+                        continue;
+                    };
+                    if !collapsed.remove(&id) {
+                        collapsed.insert(id);
+                    }
+                    line_ids = populate(&mut browser, plan, &collapsed, &hot);
+                    if let Some(index) = line_ids.iter().position(|line_id| *line_id == id) {
+                        browser.select(index as i32 + 2);
+                    }
+                }
+                PlanMessage::ExpandAll => {
+                    collapsed.clear();
+                    line_ids = populate(&mut browser, plan, &collapsed, &hot);
+                }
+                PlanMessage::CollapseAll => {
+                    collapsed = plan
+                        .steps
+                        .iter()
+                        .filter(|step| step.parent_id.is_some() && plan.has_children(step.id))
+                        .map(|step| step.id)
+                        .collect();
+                    line_ids = populate(&mut browser, plan, &collapsed, &hot);
+                }
+                PlanMessage::Export => export_plan(plan),
+                PlanMessage::Close => dialog.hide(),
+            }
+        }
+    }
+}
@@ -1,14 +1,17 @@
 use fltk::{
     app,
     browser::HoldBrowser,
-    button::Button,
-    enums::FrameType,
+    button::{Button, CheckButton},
+    enums::{CallbackTrigger, FrameType},
     group::Flex,
+    input::{Input, IntInput},
+    menu::Choice,
     prelude::*,
     text::{StyleTableEntry, TextBuffer, TextDisplay},
     window::Window,
 };
 use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 use std::sync::{mpsc, OnceLock};
 use std::thread;
@@ -17,32 +20,22 @@ use crate::ui::center_on_main;
 use crate::ui::constants::*;
 use crate::ui::theme;
 use crate::ui::{configured_editor_profile, configured_ui_font_size};
-use crate::utils::config::{QueryHistory, QueryHistoryEntry};
+use crate::utils::config::{HistoryRecord, QueryHistory, QueryHistoryEntry};
 
-enum HistoryCommand {
-    Add(QueryHistoryEntry),
-    Clear,
-}
+/// At most this many rows are listed; narrow the filters to see others.
+const MAX_LISTED_ROWS: usize = 5_000;
 
-fn history_writer_sender() -> &'static mpsc::Sender<HistoryCommand> {
-    static HISTORY_WRITER: OnceLock<mpsc::Sender<HistoryCommand>> = OnceLock::new();
+fn history_writer_sender() -> &'static mpsc::Sender<HistoryRecord> {
+    static HISTORY_WRITER: OnceLock<mpsc::Sender<HistoryRecord>> = OnceLock::new();
     HISTORY_WRITER.get_or_init(|| {
-        let (sender, receiver) = mpsc::channel::<HistoryCommand>();
+        let (sender, receiver) = mpsc::channel::<HistoryRecord>();
         thread::spawn(move || {
             let mut history = QueryHistory::load();
-            while let Ok(cmd) = receiver.recv() {
-                match cmd {
-                    HistoryCommand::Add(entry) => history.add_entry(entry),
-                    HistoryCommand::Clear => history.queries.clear(),
-                }
-                // Drain any pending commands before saving
-                while let Ok(next) = receiver.try_recv() {
-                    match next {
-                        HistoryCommand::Add(entry) => history.add_entry(entry),
-                        HistoryCommand::Clear => history.queries.clear(),
-                    }
-                }
-                if let Err(err) = history.save() {
+            while let Ok(record) = receiver.recv() {
+                // Drain any pending records before saving
+                let mut records = vec![record];
+                records.extend(receiver.try_iter());
+                if let Err(err) = history.commit(records) {
                     eprintln!("Query history save error: {err}");
                 }
             }
@@ -51,6 +44,170 @@ fn history_writer_sender() -> &'static mpsc::Sender<HistoryCommand> {
     })
 }
 
+fn send_history_record(record: HistoryRecord) {
+    if let Err(err) = history_writer_sender().send(record) {
+        // Fallback: if channel is disconnected, save directly
+        let mut history = QueryHistory::load();
+        if let Err(err) = history.commit(vec![err.0]) {
+            eprintln!("Query history save error: {err}");
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Succeeded,
+    Failed,
+}
+
+/// Which history entries the dialog lists.
+#[derive(Clone, Debug, Default)]
+pub struct HistoryFilter {
+    /// Words that must all appear in the SQL, connection name, error
+    /// message or tags, ignoring case.
+    pub text: String,
+    pub connection: Option<String>,
+    pub status: StatusFilter,
+    /// Inclusive `YYYY-MM-DD` bounds on the run date.
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    pub min_duration_ms: Option<u64>,
+    pub max_duration_ms: Option<u64>,
+    pub pinned_only: bool,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, entry: &QueryHistoryEntry) -> bool {
+        if self.pinned_only && !entry.pinned {
+            return false;
+        }
+        match self.status {
+            StatusFilter::All => {}
+            StatusFilter::Succeeded if !entry.success => return false,
+            StatusFilter::Failed if entry.success => return false,
+            _ => {}
+        }
+        if let Some(connection) = &self.connection {
+            if &entry.connection_name != connection {
+                return false;
+            }
+        }
+        let date = entry.timestamp.get(..10).unwrap_or(&entry.timestamp);
+        if self.from_date.as_deref().is_some_and(|from| date < from)
+            || self.to_date.as_deref().is_some_and(|to| date > to)
+        {
+            return false;
+        }
+        if self
+            .min_duration_ms
+            .is_some_and(|min| entry.execution_time_ms < min)
+            || self
+                .max_duration_ms
+                .is_some_and(|max| entry.execution_time_ms > max)
+        {
+            return false;
+        }
+        let text = self.text.trim();
+        if text.is_empty() {
+            return true;
+        }
+        let haystack = format!(
+            "{}\n{}\n{}\n{}",
+            entry.sql,
+            entry.connection_name,
+            entry.error_message.as_deref().unwrap_or(""),
+            entry.tags.join(" ")
+        )
+        .to_lowercase();
+        text.to_lowercase()
+            .split_whitespace()
+            .all(|word| haystack.contains(word))
+    }
+}
+
+/// A listed history row: one entry, or all runs of the same SQL when
+/// grouping.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryRow {
+    /// Index of the newest entry of the row in the history.
+    pub index: usize,
+    /// Indexes of every entry of the row, newest first.
+    pub members: Vec<usize>,
+    pub pinned: bool,
+}
+
+fn sql_group_key(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Rows for the entries matching `filter`, pinned rows first and then most
+/// recent first. With `group`, identical SQL (ignoring whitespace) is one
+/// row.
+pub fn history_rows(
+    queries: &VecDeque<QueryHistoryEntry>,
+    filter: &HistoryFilter,
+    group: bool,
+) -> Vec<HistoryRow> {
+    let mut rows: Vec<HistoryRow> = Vec::new();
+    let mut row_by_sql: HashMap<String, usize> = HashMap::new();
+    for (index, entry) in queries.iter().enumerate() {
+        if !filter.matches(entry) {
+            continue;
+        }
+        if group {
+            let key = sql_group_key(&entry.sql);
+            if let Some(&row_index) = row_by_sql.get(&key) {
+                let row = &mut rows[row_index];
+                row.members.push(index);
+                row.pinned |= entry.pinned;
+                continue;
+            }
+            row_by_sql.insert(key, rows.len());
+        }
+        rows.push(HistoryRow {
+            index,
+            members: vec![index],
+            pinned: entry.pinned,
+        });
+    }
+    // Stable, so recency order is kept within each half.
+    rows.sort_by_key(|row| !row.pinned);
+    rows
+}
+
+fn parse_date_bound(value: &str) -> Option<String> {
+    chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+fn parse_tags(value: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in value
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+    {
+        if !tags
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(tag))
+        {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// Escape text for use as a menu item label.
+fn choice_label(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('/', "\\/")
+        .replace('&', "&&")
+        .replace('_', "\\_")
+}
+
 fn parse_error_line(message: &str) -> Option<usize> {
     let lowercase = message.to_ascii_lowercase();
     for needle in ["line ", "line:"] {
@@ -114,29 +271,110 @@ pub struct QueryHistoryDialog;
 
 impl QueryHistoryDialog {
     pub fn show_with_registry(popups: Rc<RefCell<Vec<Window>>>) -> Option<String> {
+        #[derive(Clone, Copy)]
         enum DialogMessage {
-            UpdatePreview(usize),
+            Refilter,
+            UpdatePreview,
             UseSelected,
+            TogglePin,
+            EditTags,
             ClearHistory,
             Close,
         }
 
         let history = QueryHistory::load();
+        let mut queries = history.queries;
 
         let current_group = fltk::group::Group::try_current();
         fltk::group::Group::set_current(None::<&fltk::group::Group>);
 
         let mut dialog = Window::default()
-            .with_size(800, 500)
+            .with_size(960, 620)
             .with_label("Query History");
         center_on_main(&mut dialog);
         dialog.set_color(theme::panel_raised());
         dialog.make_modal(true);
 
-        let mut main_flex = Flex::default().with_pos(10, 10).with_size(780, 480);
+        let mut main_flex = Flex::default().with_pos(10, 10).with_size(940, 600);
         main_flex.set_type(fltk::group::FlexType::Column);
         main_flex.set_spacing(DIALOG_SPACING);
 
+        let filter_label = |text: &str, row: &mut Flex, width: i32| {
+            let mut label = fltk::frame::Frame::default().with_label(text);
+            label.set_label_color(theme::text_primary());
+            row.fixed(&label, width);
+        };
+        let styled_input = |mut input: Input| {
+            input.set_color(theme::input_bg());
+            input.set_text_color(theme::text_primary());
+            input
+        };
+
+        // Filter rows
+        let mut search_row = Flex::default();
+        search_row.set_type(fltk::group::FlexType::Row);
+        search_row.set_spacing(DIALOG_SPACING);
+        filter_label("Search:", &mut search_row, 60);
+        let mut search_input = styled_input(Input::default());
+        filter_label("Connection:", &mut search_row, 80);
+        let mut connection_choice = Choice::default();
+        connection_choice.set_color(theme::input_bg());
+        connection_choice.set_text_color(theme::text_primary());
+        connection_choice.add_choice("All");
+        let mut connection_names: Vec<String> = queries
+            .iter()
+            .map(|entry| entry.connection_name.clone())
+            .collect();
+        connection_names.sort();
+        connection_names.dedup();
+        for name in &connection_names {
+            connection_choice.add_choice(&choice_label(name));
+        }
+        connection_choice.set_value(0);
+        search_row.fixed(&connection_choice, 160);
+        filter_label("Status:", &mut search_row, 50);
+        let mut status_choice = Choice::default();
+        status_choice.set_color(theme::input_bg());
+        status_choice.set_text_color(theme::text_primary());
+        for label in ["All", "Succeeded", "Failed"] {
+            status_choice.add_choice(label);
+        }
+        status_choice.set_value(0);
+        search_row.fixed(&status_choice, 110);
+        search_row.end();
+        main_flex.fixed(&search_row, INPUT_ROW_HEIGHT);
+
+        let mut range_row = Flex::default();
+        range_row.set_type(fltk::group::FlexType::Row);
+        range_row.set_spacing(DIALOG_SPACING);
+        filter_label("From:", &mut range_row, 60);
+        let mut from_input = styled_input(Input::default());
+        from_input.set_tooltip("YYYY-MM-DD");
+        range_row.fixed(&from_input, 100);
+        filter_label("To:", &mut range_row, 30);
+        let mut to_input = styled_input(Input::default());
+        to_input.set_tooltip("YYYY-MM-DD");
+        range_row.fixed(&to_input, 100);
+        filter_label("Min ms:", &mut range_row, 55);
+        let mut min_input = IntInput::default();
+        min_input.set_color(theme::input_bg());
+        min_input.set_text_color(theme::text_primary());
+        range_row.fixed(&min_input, 80);
+        filter_label("Max ms:", &mut range_row, 55);
+        let mut max_input = IntInput::default();
+        max_input.set_color(theme::input_bg());
+        max_input.set_text_color(theme::text_primary());
+        range_row.fixed(&max_input, 80);
+        let _spacer = fltk::frame::Frame::default();
+        let mut pinned_check = CheckButton::default().with_label("Pinned only");
+        pinned_check.set_label_color(theme::text_secondary());
+        range_row.fixed(&pinned_check, 110);
+        let mut group_check = CheckButton::default().with_label("Group identical SQL");
+        group_check.set_label_color(theme::text_secondary());
+        range_row.fixed(&group_check, 170);
+        range_row.end();
+        main_flex.fixed(&range_row, INPUT_ROW_HEIGHT);
+
         // Top section with list and preview
         let mut content_flex = Flex::default();
         content_flex.set_type(fltk::group::FlexType::Row);
@@ -147,30 +385,18 @@ impl QueryHistoryDialog {
         list_flex.set_type(fltk::group::FlexType::Column);
         list_flex.set_spacing(DIALOG_SPACING);
 
-        let mut list_label =
-            fltk::frame::Frame::default().with_label("Query History (Most Recent First):");
+        let mut list_label = fltk::frame::Frame::default();
         list_label.set_label_color(theme::text_primary());
         list_flex.fixed(&list_label, LABEL_ROW_HEIGHT);
 
         let mut browser = HoldBrowser::default();
         browser.set_color(theme::input_bg());
         browser.set_selection_color(theme::selection_strong());
-
-        // Populate browser with history entries
-        for entry in history.queries.iter() {
-            let color_prefix = if entry.success { "@C255 " } else { "@C1 " };
-            let display = format!(
-                "{color_prefix}{} | {} | {}ms | {} rows",
-                entry.timestamp,
-                truncate_sql(&entry.sql, 50),
-                entry.execution_time_ms,
-                entry.row_count
-            );
-            browser.add(&display);
-        }
+        browser.set_column_char('\t');
+        browser.set_column_widths(&[24, 140, 44, 70]);
 
         list_flex.end();
-        content_flex.fixed(&list_flex, 350);
+        content_flex.fixed(&list_flex, 500);
 
         // Right - SQL preview
         let mut preview_flex = Flex::default();
@@ -220,6 +446,20 @@ impl QueryHistoryDialog {
         button_flex.set_type(fltk::group::FlexType::Row);
         button_flex.set_spacing(DIALOG_SPACING);
 
+        let mut pin_btn = Button::default()
+            .with_size(BUTTON_WIDTH, BUTTON_HEIGHT)
+            .with_label("Pin");
+        pin_btn.set_color(theme::button_secondary());
+        pin_btn.set_label_color(theme::text_primary());
+        pin_btn.set_frame(FrameType::RFlatBox);
+
+        let mut tags_btn = Button::default()
+            .with_size(BUTTON_WIDTH, BUTTON_HEIGHT)
+            .with_label("Tags...");
+        tags_btn.set_color(theme::button_secondary());
+        tags_btn.set_label_color(theme::text_primary());
+        tags_btn.set_frame(FrameType::RFlatBox);
+
         let _spacer = fltk::frame::Frame::default();
 
         let mut use_btn = Button::default()
@@ -243,6 +483,8 @@ impl QueryHistoryDialog {
         close_btn.set_label_color(theme::text_primary());
         close_btn.set_frame(FrameType::RFlatBox);
 
+        button_flex.fixed(&pin_btn, BUTTON_WIDTH);
+        button_flex.fixed(&tags_btn, BUTTON_WIDTH);
         button_flex.fixed(&use_btn, BUTTON_WIDTH_LARGE);
         button_flex.fixed(&clear_btn, BUTTON_WIDTH_LARGE);
         button_flex.fixed(&close_btn, BUTTON_WIDTH);
@@ -255,63 +497,175 @@ impl QueryHistoryDialog {
 
         popups.borrow_mut().push(dialog.clone());
         // State for selected query
-        let selected_sql: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
-        let queries: Rc<RefCell<Vec<QueryHistoryEntry>>> = Rc::new(RefCell::new(history.queries));
+        let mut selected_sql: Option<String> = None;
 
         let (sender, receiver) = mpsc::channel::<DialogMessage>();
 
+        // Any filter change relists the history
+        fn refilter_on<W: WidgetExt>(widget: &mut W, sender: &mpsc::Sender<DialogMessage>) {
+            let sender = sender.clone();
+            widget.set_callback(move |_| {
+                let _ = sender.send(DialogMessage::Refilter);
+                app::awake();
+            });
+        }
+        for input in [&mut search_input, &mut from_input, &mut to_input] {
+            input.set_trigger(CallbackTrigger::Changed);
+            refilter_on(input, &sender);
+        }
+        for input in [&mut min_input, &mut max_input] {
+            input.set_trigger(CallbackTrigger::Changed);
+            refilter_on(input, &sender);
+        }
+        refilter_on(&mut connection_choice, &sender);
+        refilter_on(&mut status_choice, &sender);
+        refilter_on(&mut pinned_check, &sender);
+        refilter_on(&mut group_check, &sender);
+
         // Browser selection callback - update preview
         let sender_for_preview = sender.clone();
-        browser.set_callback(move |b| {
-            let selected = b.value();
-            if selected > 0 {
-                if let Some(idx) = (selected - 1).try_into().ok() {
-                    let _ = sender_for_preview.send(DialogMessage::UpdatePreview(idx));
-                    app::awake();
-                }
-            }
-        });
-
-        // Use Query button
-        let sender_for_use = sender.clone();
-        use_btn.set_callback(move |_| {
-            let _ = sender_for_use.send(DialogMessage::UseSelected);
-            app::awake();
-        });
-
-        // Clear History button
-        let sender_for_clear = sender.clone();
-        clear_btn.set_callback(move |_| {
-            let _ = sender_for_clear.send(DialogMessage::ClearHistory);
+        browser.set_callback(move |_| {
+            let message = if app::event_clicks() {
+                DialogMessage::UseSelected
+            } else {
+                DialogMessage::UpdatePreview
+            };
+            let _ = sender_for_preview.send(message);
             app::awake();
         });
 
-        // Close button
-        let sender_for_close = sender.clone();
-        close_btn.set_callback(move |_| {
-            let _ = sender_for_close.send(DialogMessage::Close);
-            app::awake();
-        });
+        for (button, message) in [
+            (&mut pin_btn, DialogMessage::TogglePin),
+            (&mut tags_btn, DialogMessage::EditTags),
+            (&mut use_btn, DialogMessage::UseSelected),
+            (&mut clear_btn, DialogMessage::ClearHistory),
+            (&mut close_btn, DialogMessage::Close),
+        ] {
+            let sender = sender.clone();
+            button.set_callback(move |_| {
+                let _ = sender.send(message);
+                app::awake();
+            });
+        }
 
         dialog.show();
+        let _ = search_input.take_focus();
 
         let mut preview_buffer = preview_buffer.clone();
         let mut preview_style_buffer = preview_style_buffer.clone();
         let mut error_buffer = error_buffer.clone();
-        let mut error_display = error_display.clone();
-        let mut error_label = error_label.clone();
         let preview_flex_for_error = preview_flex.clone();
-        let mut browser = browser.clone();
+        let mut rows: Vec<HistoryRow> = Vec::new();
+        let _ = sender.send(DialogMessage::Refilter);
+
         while dialog.shown() {
             fltk::app::wait();
             while let Ok(message) = receiver.try_recv() {
+                let selected_row = usize::try_from(browser.value() - 1)
+                    .ok()
+                    .and_then(|index| rows.get(index))
+                    .cloned();
                 match message {
-                    DialogMessage::UpdatePreview(index) => {
-                        let queries = queries.borrow();
-                        if let Some(entry) = queries.get(index) {
+                    DialogMessage::Refilter => {
+                        let connection = connection_choice
+                            .value()
+                            .checked_sub(1)
+                            .and_then(|index| connection_names.get(index as usize))
+                            .cloned();
+                        let status = match status_choice.value() {
+                            1 => StatusFilter::Succeeded,
+                            2 => StatusFilter::Failed,
+                            _ => StatusFilter::All,
+                        };
+                        let filter = HistoryFilter {
+                            text: search_input.value(),
+                            connection,
+                            status,
+                            from_date: parse_date_bound(&from_input.value()),
+                            to_date: parse_date_bound(&to_input.value()),
+                            min_duration_ms: min_input.value().trim().parse().ok(),
+                            max_duration_ms: max_input.value().trim().parse().ok(),
+                            pinned_only: pinned_check.value(),
+                        };
+                        let grouped = group_check.value();
+                        rows = history_rows(&queries, &filter, grouped);
+
+                        browser.clear();
+                        for row in rows.iter().take(MAX_LISTED_ROWS) {
+                            let Some(entry) = queries.get(row.index) else {
+                                continue;
+                            };
+                            let color = if entry.success {
+                                theme::text_primary()
+                            } else {
+                                theme::button_danger()
+                            };
+                            let prefix = format!("@C{}@.", color.bits());
+                            let runs = if grouped {
+                                format!("{}x", row.members.len())
+                            } else {
+                                String::new()
+                            };
+                            browser.add(&format!(
+                                "{prefix}{}\t{prefix}{}\t{prefix}{}\t{prefix}{}ms\t{prefix}{}",
+                                if row.pinned { "*" } else { "" },
+                                entry.timestamp,
+                                runs,
+                                entry.execution_time_ms,
+                                truncate_sql(&entry.sql, 80)
+                            ));
+                        }
+                        let shown = rows.len().min(MAX_LISTED_ROWS);
+                        let label = if shown < rows.len() {
+                            format!(
+                                "Showing {} of {} matches; refine the filters to see more",
+                                shown,
+                                rows.len()
+                            )
+                        } else {
+                            format!("{} of {} entries", rows.len(), queries.len())
+                        };
+                        list_label.set_label(&label);
+                        preview_buffer.set_text("");
+                        preview_style_buffer.set_text("");
+                        error_buffer.set_text("");
+                        error_display.hide();
+                        error_label.hide();
+                        preview_flex_for_error.layout();
+                    }
+                    DialogMessage::UpdatePreview => {
+                        let Some(row) = selected_row else {
+                            continue;
+                        };
+                        if let Some(entry) = queries.get(row.index) {
                             preview_buffer.set_text(&entry.sql);
                             let styles = build_preview_styles(&entry.sql, entry.error_line);
                             preview_style_buffer.set_text(&styles);
+                            let mut details = vec![format!(
+                                "{} | {} rows",
+                                entry.connection_name, entry.row_count
+                            )];
+                            if row.members.len() > 1 {
+                                details.push(format!("run {} times", row.members.len()));
+                            }
+                            let mut tags: Vec<String> = Vec::new();
+                            for member in &row.members {
+                                for tag in queries
+                                    .get(*member)
+                                    .map(|e| e.tags.clone())
+                                    .unwrap_or_default()
+                                {
+                                    if !tags.contains(&tag) {
+                                        tags.push(tag);
+                                    }
+                                }
+                            }
+                            if !tags.is_empty() {
+                                details.push(format!("tags: {}", tags.join(", ")));
+                            }
+                            preview_label
+                                .set_label(&format!("SQL Preview ({})", details.join(", ")));
+                            pin_btn.set_label(if row.pinned { "Unpin" } else { "Pin" });
                             if entry.success {
                                 error_buffer.set_text("");
                                 error_display.hide();
@@ -329,39 +683,66 @@ impl QueryHistoryDialog {
                         }
                     }
                     DialogMessage::UseSelected => {
-                        let selected = browser.value();
-                        if selected > 0 {
-                            if let Ok(idx) = usize::try_from(selected - 1) {
-                                let queries = queries.borrow();
-                                if let Some(entry) = queries.get(idx) {
-                                    *selected_sql.borrow_mut() = Some(entry.sql.clone());
-                                    dialog.hide();
-                                }
-                            }
+                        if let Some(entry) = selected_row.and_then(|row| queries.get(row.index)) {
+                            selected_sql = Some(entry.sql.clone());
+                            dialog.hide();
                         } else {
                             fltk::dialog::alert_default("Please select a query from the list");
                         }
                     }
+                    DialogMessage::TogglePin => {
+                        let Some(row) = selected_row else {
+                            fltk::dialog::alert_default("Please select a query from the list");
+                            continue;
+                        };
+                        // Unpinning a group unpins every run; pinning pins the newest.
+                        let targets = if row.pinned {
+                            row.members.clone()
+                        } else {
+                            vec![row.index]
+                        };
+                        for index in targets {
+                            if let Some(entry) = queries.get_mut(index) {
+                                entry.pinned = !row.pinned;
+                                send_history_record(HistoryRecord::Pin {
+                                    id: entry.id,
+                                    pinned: entry.pinned,
+                                });
+                            }
+                        }
+                        let _ = sender.send(DialogMessage::Refilter);
+                    }
+                    DialogMessage::EditTags => {
+                        let Some(entry) = selected_row.and_then(|row| queries.get_mut(row.index))
+                        else {
+                            fltk::dialog::alert_default("Please select a query from the list");
+                            continue;
+                        };
+                        let current = entry.tags.join(", ");
+                        if let Some(value) =
+                            fltk::dialog::input_default("Tags (comma separated):", &current)
+                        {
+                            entry.tags = parse_tags(&value);
+                            send_history_record(HistoryRecord::Tags {
+                                id: entry.id,
+                                tags: entry.tags.clone(),
+                            });
+                            let _ = sender.send(DialogMessage::UpdatePreview);
+                        }
+                    }
                     DialogMessage::ClearHistory => {
                         let choice = fltk::dialog::choice2_default(
-                            "Are you sure you want to clear all query history?",
+                            "Clear all unpinned query history? Pinned queries are kept.",
                             "Cancel",
-                            "Clear All",
+                            "Clear",
                             "",
                         );
                         if choice == Some(1) {
                             // Notify the background writer so its in-memory
                             // history is cleared along with the file.
-                            let _ = history_writer_sender().send(HistoryCommand::Clear);
-                            app::awake();
-                            queries.borrow_mut().clear();
-                            browser.clear();
-                            preview_buffer.set_text("");
-                            preview_style_buffer.set_text("");
-                            error_buffer.set_text("");
-                            error_display.hide();
-                            error_label.hide();
-                            preview_flex_for_error.layout();
+                            send_history_record(HistoryRecord::Clear);
+                            queries.retain(|entry| entry.pinned);
+                            let _ = sender.send(DialogMessage::Refilter);
                         }
                     }
                     DialogMessage::Close => {
@@ -376,8 +757,7 @@ impl QueryHistoryDialog {
             .borrow_mut()
             .retain(|w| w.as_widget_ptr() != dialog.as_widget_ptr());
 
-        let result = selected_sql.borrow().clone();
-        result
+        selected_sql
     }
 
     /// Add a query to history
@@ -396,6 +776,7 @@ impl QueryHistoryDialog {
         };
         let error_line = error_message.as_deref().and_then(parse_error_line);
         let entry = QueryHistoryEntry {
+            id: 0,
             sql: sql.to_string(),
             timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             execution_time_ms,
@@ -404,17 +785,11 @@ impl QueryHistoryDialog {
             success,
             error_message,
             error_line,
+            pinned: false,
+            tags: Vec::new(),
         };
-
-        if let Err(err) = history_writer_sender().send(HistoryCommand::Add(entry)) {
-            app::awake();
-            // Fallback: if channel is disconnected, save directly
-            if let HistoryCommand::Add(entry) = err.0 {
-                let mut history = QueryHistory::load();
-                history.add_entry(entry);
-                let _ = history.save();
-            }
-        }
+        send_history_record(HistoryRecord::Add { entry });
+        app::awake();
     }
 }
 
@@ -452,7 +827,24 @@ fn truncate_sql(sql: &str, max_len: usize) -> String {
 
 #[cfg(test)]
 mod query_history_tests {
-    use super::truncate_sql;
+    use super::*;
+
+    fn entry(sql: &str, timestamp: &str, success: bool, pinned: bool) -> QueryHistoryEntry {
+        QueryHistoryEntry {
+            id: 0,
+            sql: sql.to_string(),
+            timestamp: timestamp.to_string(),
+            execution_time_ms: 10,
+            row_count: 0,
+            connection_name: "dev".to_string(),
+            success,
+            error_message: (!success)
+                .then(|| "ORA-00942: table or view does not exist".to_string()),
+            error_line: None,
+            pinned,
+            tags: Vec::new(),
+        }
+    }
 
     #[test]
     fn truncate_sql_preserves_multibyte_text_while_normalizing_whitespace() {
@@ -465,4 +857,51 @@ mod query_history_tests {
         let sql = "가나다라마바사";
         assert_eq!(truncate_sql(sql, 5), "가나다라마...");
     }
+
+    #[test]
+    fn filter_combines_text_status_and_date_range() {
+        let failed = entry("select * from emp", "2026-03-02 10:00:00", false, false);
+        let filter = HistoryFilter {
+            text: "EMP ora-00942".to_string(),
+            status: StatusFilter::Failed,
+            from_date: parse_date_bound("2026-03-01"),
+            to_date: parse_date_bound("2026-03-02"),
+            ..HistoryFilter::default()
+        };
+        assert!(filter.matches(&failed));
+        let later = entry("select * from emp", "2026-03-03 00:00:01", false, false);
+        assert!(!filter.matches(&later));
+        assert_eq!(parse_date_bound("03/01/2026"), None);
+    }
+
+    #[test]
+    fn grouping_counts_runs_and_lists_pinned_first() {
+        let queries: VecDeque<QueryHistoryEntry> = vec![
+            entry("select 1\n  from dual", "2026-03-03", true, false),
+            entry("select 2 from dual", "2026-03-02", true, true),
+            entry("select 1 from dual", "2026-03-01", true, false),
+        ]
+        .into();
+        let rows = history_rows(&queries, &HistoryFilter::default(), true);
+        assert_eq!(
+            rows,
+            vec![
+                HistoryRow {
+                    index: 1,
+                    members: vec![1],
+                    pinned: true
+                },
+                HistoryRow {
+                    index: 0,
+                    members: vec![0, 2],
+                    pinned: false
+                },
+            ]
+        );
+        assert_eq!(
+            history_rows(&queries, &HistoryFilter::default(), false).len(),
+            3
+        );
+        assert_eq!(parse_tags("perf, Perf,, nightly "), vec!["perf", "nightly"]);
+    }
 }
@@ -1,4 +1,5 @@
 use fltk::{
+    enums::Color,
     group::{Group, Tabs, TabsOverflow},
     prelude::*,
 };
@@ -24,6 +25,8 @@ pub struct QueryTabsWidget {
 struct TabEntry {
     id: QueryTabId,
     group: Group,
+    /// Header color marking the tab's connection environment.
+    color: Option<Color>,
 }
 
 struct CallbackSuppressGuard {
@@ -63,6 +66,18 @@ impl QueryTabsWidget {
         }
     }
 
+    /// The selected tab's header and the border under the headers take its
+    /// environment color.
+    fn apply_selection_color(tabs: &mut Tabs, entries: &[TabEntry]) {
+        let selected_ptr = tabs.value().map(|selected| selected.as_widget_ptr());
+        let color = entries
+            .iter()
+            .find(|entry| Some(entry.group.as_widget_ptr()) == selected_ptr)
+            .and_then(|entry| entry.color)
+            .unwrap_or_else(theme::selection_strong);
+        tabs.set_selection_color(color);
+    }
+
     pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
         let mut tabs = Tabs::new(x, y, w, h, None);
         tabs.end();
@@ -84,6 +99,7 @@ impl QueryTabsWidget {
         let on_select_for_cb = on_select.clone();
         let suppress_for_cb = suppress_select_callback_depth.clone();
         tabs.set_callback(move |tabs| {
+            Self::apply_selection_color(tabs, &entries_for_cb.borrow());
             if suppress_for_cb.get() > 0 {
                 return;
             }
@@ -144,10 +160,12 @@ impl QueryTabsWidget {
         self.entries.borrow_mut().push(TabEntry {
             id: tab_id,
             group: group.clone(),
+            color: None,
         });
         let _suppress_guard =
             CallbackSuppressGuard::new(self.suppress_select_callback_depth.clone());
         let _ = self.tabs.set_value(&group);
+        Self::apply_selection_color(&mut self.tabs, &self.entries.borrow());
         Self::layout_children(&self.tabs);
         self.tabs.redraw();
         tab_id
@@ -158,6 +176,7 @@ impl QueryTabsWidget {
             let _suppress_guard =
                 CallbackSuppressGuard::new(self.suppress_select_callback_depth.clone());
             let _ = self.tabs.set_value(&group);
+            Self::apply_selection_color(&mut self.tabs, &self.entries.borrow());
             self.tabs.redraw();
         }
     }
@@ -180,6 +199,25 @@ impl QueryTabsWidget {
         }
     }
 
+    /// Color the header of `tab_id`, or give it back the default look.
+    pub fn set_tab_color(&mut self, tab_id: QueryTabId, color: Option<Color>) {
+        {
+            let mut entries = self.entries.borrow_mut();
+            let Some(entry) = entries.iter_mut().find(|entry| entry.id == tab_id) else {
+                return;
+            };
+            if entry.color == color {
+                return;
+            }
+            entry.color = color;
+            entry
+                .group
+                .set_selection_color(color.unwrap_or(Color::Background));
+        }
+        Self::apply_selection_color(&mut self.tabs, &self.entries.borrow());
+        self.tabs.redraw();
+    }
+
     pub fn close_tab(&mut self, tab_id: QueryTabId) -> bool {
         let group = {
             let mut entries = self.entries.borrow_mut();
@@ -195,6 +233,7 @@ impl QueryTabsWidget {
             self.tabs.remove(&group);
         }
         fltk::group::Group::delete(group);
+        Self::apply_selection_color(&mut self.tabs, &self.entries.borrow());
         Self::layout_children(&self.tabs);
         self.tabs.redraw();
         true
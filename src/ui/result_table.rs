@@ -14,11 +14,15 @@ use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 
-use crate::db::QueryResult;
+use crate::db::{CellValue, DmlStatement, EditTarget, QueryResult};
 use crate::ui::constants::*;
 use crate::ui::font_settings::{configured_editor_profile, FontProfile};
+use crate::ui::result_view::{ResultRows, RowEditState, SortOrder};
 use crate::ui::theme;
 
+/// Applies DML generated from grid edits, then reports the outcome.
+pub type EditApplyHandler = Rc<dyn Fn(Vec<DmlStatement>, Box<dyn FnOnce(Result<String, String>)>)>;
+
 fn byte_index_after_n_chars(s: &str, n: usize) -> usize {
     if n == 0 {
         return 0;
@@ -69,20 +73,63 @@ pub struct ResultTableWidget {
     table: Table,
     headers: Rc<RefCell<Vec<String>>>,
     /// Buffer for pending rows during streaming
-    pending_rows: Rc<RefCell<Vec<Vec<String>>>>,
+    pending_rows: Rc<RefCell<Vec<Vec<CellValue>>>>,
     /// Pending column width updates
     pending_widths: Rc<RefCell<Vec<i32>>>,
     /// Last UI update time
     last_flush: Rc<RefCell<Instant>>,
-    /// The sole data store: full original data (non-truncated).
+    /// The sole data store: full original data (non-truncated) in fetch order,
+    /// plus the client-side sort/filter view over it.
     /// draw_cell reads from here on demand — no data duplication.
-    full_data: Rc<RefCell<Vec<Vec<String>>>>,
+    full_data: Rc<RefCell<ResultRows>>,
+    /// Whether the per-column filter row is shown under the column headers.
+    show_filter_row: Rc<Cell<bool>>,
+    /// Text shown in place of NULL cells (SET NULL).
+    null_text: Rc<RefCell<String>>,
     /// Maximum displayed characters per cell; full text remains in full_data for copy/export.
     max_cell_display_chars: Rc<Cell<usize>>,
     /// How many rows have been sampled for column width calculation
     width_sampled_rows: Rc<RefCell<usize>>,
     font_profile: Rc<Cell<FontProfile>>,
     font_size: Rc<Cell<u32>>,
+    /// ROWIDs split off buffered rows of an editable result.
+    pending_rowids: Rc<RefCell<Vec<Option<String>>>>,
+    source_sql: Rc<RefCell<Option<String>>>,
+    editing: GridEditing,
+}
+
+/// Editing state shared by the table callbacks.
+#[derive(Clone, Default)]
+struct GridEditing {
+    /// Set when the current result maps onto a single table's rows.
+    target: Rc<RefCell<Option<EditTarget>>>,
+    streaming: Rc<Cell<bool>>,
+    /// True while pending edits are being written to the database.
+    applying: Rc<Cell<bool>>,
+    apply_handler: Rc<RefCell<Option<EditApplyHandler>>>,
+}
+
+impl GridEditing {
+    fn is_active(&self) -> bool {
+        !self.streaming.get() && !self.applying.get() && self.target.borrow().is_some()
+    }
+
+    fn can_edit_cell(&self, full_data: &Rc<RefCell<ResultRows>>, row: i32, col: i32) -> bool {
+        if !self.is_active() || row < 0 || col < 0 {
+            return false;
+        }
+        let column_editable = self
+            .target
+            .borrow()
+            .as_ref()
+            .map(|target| target.is_editable_column(col as usize))
+            .unwrap_or(false);
+        column_editable
+            && full_data
+                .try_borrow()
+                .map(|data| data.is_row_editable(row as usize))
+                .unwrap_or(false)
+    }
 }
 
 #[derive(Default)]
@@ -179,8 +226,32 @@ impl ResultTableWidget {
         let font_size = self.font_size.get();
         self.table
             .set_row_height_all(Self::row_height_for_font(font_size));
-        self.table
-            .set_col_header_height(Self::header_height_for_font(font_size));
+        self.table.set_col_header_height(Self::col_header_height(
+            font_size,
+            self.show_filter_row.get(),
+        ));
+    }
+
+    /// Column header height; the filter row doubles it.
+    fn col_header_height(font_size: u32, show_filter_row: bool) -> i32 {
+        let height = Self::header_height_for_font(font_size);
+        if show_filter_row {
+            height * 2
+        } else {
+            height
+        }
+    }
+
+    fn header_label(name: &str, sort: Option<(usize, SortOrder)>, col: usize) -> String {
+        match sort {
+            Some((sorted_col, SortOrder::Ascending)) if sorted_col == col => {
+                format!("{} ▲", name)
+            }
+            Some((sorted_col, SortOrder::Descending)) if sorted_col == col => {
+                format!("{} ▼", name)
+            }
+            _ => name.to_string(),
+        }
     }
 
     fn row_height_for_font(size: u32) -> i32 {
@@ -223,7 +294,8 @@ impl ResultTableWidget {
 
     fn update_widths_with_row(
         widths: &mut Vec<i32>,
-        row: &[String],
+        row: &[CellValue],
+        null_text: &str,
         font_size: u32,
         max_cell_display_chars: usize,
     ) {
@@ -233,7 +305,7 @@ impl ResultTableWidget {
         }
         for (i, cell) in row.iter().enumerate() {
             widths[i] = widths[i].max(Self::estimate_display_width(
-                cell,
+                cell.display(null_text),
                 font_size,
                 max_cell_display_chars,
             ));
@@ -242,7 +314,8 @@ impl ResultTableWidget {
 
     fn compute_column_widths(
         headers: &[String],
-        rows: &[Vec<String>],
+        rows: &[Vec<CellValue>],
+        null_text: &str,
         font_size: u32,
         max_cell_display_chars: usize,
     ) -> Vec<i32> {
@@ -253,7 +326,13 @@ impl ResultTableWidget {
 
         let sample_count = rows.len().min(WIDTH_SAMPLE_ROWS);
         for row in rows.iter().take(sample_count) {
-            Self::update_widths_with_row(&mut widths, row, font_size, max_cell_display_chars);
+            Self::update_widths_with_row(
+                &mut widths,
+                row,
+                null_text,
+                font_size,
+                max_cell_display_chars,
+            );
         }
 
         widths
@@ -279,6 +358,7 @@ impl ResultTableWidget {
 
         let font_size = self.font_size.get();
         let max_cell_display_chars = self.max_cell_display_chars.get();
+        let null_text = self.null_text.borrow().clone();
         let mut widths: Vec<i32> = headers
             .iter()
             .map(|h| Self::estimate_text_width(h, font_size))
@@ -288,7 +368,13 @@ impl ResultTableWidget {
         {
             let full_data = self.full_data.borrow();
             for row in full_data.iter().take(WIDTH_SAMPLE_ROWS) {
-                Self::update_widths_with_row(&mut widths, row, font_size, max_cell_display_chars);
+                Self::update_widths_with_row(
+                    &mut widths,
+                    row,
+                    &null_text,
+                    font_size,
+                    max_cell_display_chars,
+                );
                 sampled += 1;
             }
         }
@@ -297,7 +383,13 @@ impl ResultTableWidget {
             let pending = self.pending_rows.borrow();
             let remaining = WIDTH_SAMPLE_ROWS - sampled;
             for row in pending.iter().take(remaining) {
-                Self::update_widths_with_row(&mut widths, row, font_size, max_cell_display_chars);
+                Self::update_widths_with_row(
+                    &mut widths,
+                    row,
+                    &null_text,
+                    font_size,
+                    max_cell_display_chars,
+                );
             }
         }
 
@@ -311,7 +403,9 @@ impl ResultTableWidget {
 
     pub fn with_size(x: i32, y: i32, w: i32, h: i32) -> Self {
         let headers: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
-        let full_data: Rc<RefCell<Vec<Vec<String>>>> = Rc::new(RefCell::new(Vec::new()));
+        let full_data: Rc<RefCell<ResultRows>> = Rc::new(RefCell::new(ResultRows::default()));
+        let null_text = Rc::new(RefCell::new("NULL".to_string()));
+        let show_filter_row = Rc::new(Cell::new(false));
         let font_profile = Rc::new(Cell::new(configured_editor_profile()));
         let font_size = Rc::new(Cell::new(DEFAULT_FONT_SIZE as u32));
         let max_cell_display_chars =
@@ -333,10 +427,16 @@ impl ResultTableWidget {
         // Capture theme colors once for draw_cell (avoids per-cell function calls)
         let cell_bg = theme::table_cell_bg();
         let cell_fg = theme::text_primary();
+        let null_fg = theme::text_muted();
         let sel_bg = theme::selection_soft();
         let header_bg = theme::table_header_bg();
         let header_fg = theme::text_primary();
+        let filter_bg = theme::input_bg();
         let border_color = theme::table_border();
+        let modified_fg = theme::button_warning();
+        let inserted_fg = theme::button_success();
+        let deleted_fg = theme::button_danger();
+        let editing = GridEditing::default();
 
         // Virtual rendering: draw_cell reads directly from full_data on demand.
         // Only visible cells are rendered — no per-cell data stored in the Table widget.
@@ -346,6 +446,8 @@ impl ResultTableWidget {
         let font_profile_for_draw = font_profile.clone();
         let font_size_for_draw = font_size.clone();
         let max_cell_display_chars_for_draw = max_cell_display_chars.clone();
+        let null_text_for_draw = null_text.clone();
+        let show_filter_row_for_draw = show_filter_row.clone();
 
         table.draw_cell(move |_t, ctx, row, col, x, y, w, h| {
             let font_profile = font_profile_for_draw.get();
@@ -359,18 +461,52 @@ impl ResultTableWidget {
                     draw::draw_box(FrameType::FlatBox, x, y, w, h, header_bg);
                     draw::set_draw_color(header_fg);
                     draw::set_font(font_profile.bold, font_size);
+                    let show_filters = show_filter_row_for_draw.get();
+                    let title_h = if show_filters { h / 2 } else { h };
+                    let (sort, filter_text) = full_data_for_draw
+                        .try_borrow()
+                        .map(|data| (data.sort(), data.filter_text(col as usize).to_string()))
+                        .unwrap_or_default();
                     if let Ok(hdrs) = headers_for_draw.try_borrow() {
                         if let Some(text) = hdrs.get(col as usize) {
+                            let label = Self::header_label(text, sort, col as usize);
                             draw::draw_text2(
-                                text,
+                                &label,
                                 x + TABLE_CELL_PADDING,
                                 y,
                                 w - TABLE_CELL_PADDING * 2,
-                                h,
+                                title_h,
                                 Align::Left,
                             );
                         }
                     }
+                    if show_filters {
+                        let filter_y = y + title_h;
+                        let filter_h = h - title_h;
+                        draw::draw_box(
+                            FrameType::FlatBox,
+                            x + 2,
+                            filter_y + 2,
+                            w - 4,
+                            filter_h - 4,
+                            filter_bg,
+                        );
+                        draw::set_font(font_profile.normal, font_size);
+                        let (text, color) = if filter_text.is_empty() {
+                            ("filter", null_fg)
+                        } else {
+                            (filter_text.as_str(), cell_fg)
+                        };
+                        draw::set_draw_color(color);
+                        draw::draw_text2(
+                            text,
+                            x + TABLE_CELL_PADDING,
+                            filter_y,
+                            w - TABLE_CELL_PADDING * 2,
+                            filter_h,
+                            Align::Left,
+                        );
+                    }
                     draw::set_draw_color(border_color);
                     draw::draw_line(x, y + h - 1, x + w, y + h - 1);
                     draw::pop_clip();
@@ -380,7 +516,22 @@ impl ResultTableWidget {
                     draw::draw_box(FrameType::FlatBox, x, y, w, h, header_bg);
                     draw::set_draw_color(header_fg);
                     draw::set_font(font_profile.normal, font_size);
-                    let text = (row + 1).to_string();
+                    // Number rows by fetch position so sorted rows keep their identity.
+                    let (source_row, edit_state) = full_data_for_draw
+                        .try_borrow()
+                        .map(|data| {
+                            (
+                                data.source_index(row as usize).unwrap_or(row as usize),
+                                data.row_edit_state(row as usize),
+                            )
+                        })
+                        .unwrap_or((row as usize, RowEditState::Unchanged));
+                    let text = match edit_state {
+                        RowEditState::Unchanged => (source_row + 1).to_string(),
+                        RowEditState::Modified => format!("* {}", source_row + 1),
+                        RowEditState::Inserted => "+".to_string(),
+                        RowEditState::Deleted => format!("- {}", source_row + 1),
+                    };
                     draw::draw_text2(&text, x, y, w - TABLE_CELL_PADDING, h, Align::Right);
                     draw::set_draw_color(border_color);
                     draw::draw_line(x + w - 1, y, x + w - 1, y + h);
@@ -395,8 +546,26 @@ impl ResultTableWidget {
                     draw::set_font(font_profile.normal, font_size);
 
                     if let Ok(data) = full_data_for_draw.try_borrow() {
+                        let edit_state = data.row_edit_state(row as usize);
                         if let Some(row_data) = data.get(row as usize) {
-                            if let Some(cell_val) = row_data.get(col as usize) {
+                            if let Some(cell) = row_data.get(col as usize) {
+                                let null_text = null_text_for_draw.borrow();
+                                let cell_val = cell.display(&null_text);
+                                // Numbers line up on the right, like SQL*Plus output.
+                                let align = if cell.is_numeric() {
+                                    Align::Right
+                                } else {
+                                    Align::Left
+                                };
+                                match edit_state {
+                                    RowEditState::Deleted => draw::set_draw_color(deleted_fg),
+                                    RowEditState::Inserted => draw::set_draw_color(inserted_fg),
+                                    _ if data.is_cell_modified(row as usize, col as usize) => {
+                                        draw::set_draw_color(modified_fg)
+                                    }
+                                    _ if cell.is_null() => draw::set_draw_color(null_fg),
+                                    _ => {}
+                                }
                                 let max_chars = max_cell_display_chars_for_draw.get();
                                 if let Some(truncated_end) =
                                     truncated_content_end(cell_val, max_chars)
@@ -409,7 +578,7 @@ impl ResultTableWidget {
                                             y,
                                             w - TABLE_CELL_PADDING * 2,
                                             h,
-                                            Align::Left,
+                                            align,
                                         );
                                     }
                                     draw::draw_text2(
@@ -427,11 +596,15 @@ impl ResultTableWidget {
                                         y,
                                         w - TABLE_CELL_PADDING * 2,
                                         h,
-                                        Align::Left,
+                                        align,
                                     );
                                 }
                             }
                         }
+                        if edit_state == RowEditState::Deleted {
+                            draw::set_draw_color(deleted_fg);
+                            draw::draw_line(x, y + h / 2, x + w, y + h / 2);
+                        }
                     }
 
                     draw::set_draw_color(border_color);
@@ -449,8 +622,11 @@ impl ResultTableWidget {
 
         let mut table_for_handle = table.clone();
         let full_data_for_handle = full_data.clone();
+        let null_text_for_handle = null_text.clone();
+        let show_filter_row_for_handle = show_filter_row.clone();
         let font_profile_for_handle = font_profile.clone();
         let font_size_for_handle = font_size.clone();
+        let editing_for_handle = editing.clone();
         table.handle(move |_, ev| {
             if !table_for_handle.active() {
                 return false;
@@ -458,17 +634,59 @@ impl ResultTableWidget {
             match ev {
                 Event::Push => {
                     if app::event_mouse_button() == app::MouseButton::Right {
+                        let null_text = null_text_for_handle.borrow().clone();
                         Self::show_context_menu(
                             &table_for_handle,
                             &headers_for_handle,
                             &full_data_for_handle,
+                            &null_text,
+                            &show_filter_row_for_handle,
+                            font_size_for_handle.get(),
+                            &editing_for_handle,
                         );
                         return true;
                     }
                     // Left click - start drag selection
                     if app::event_mouse_button() == app::MouseButton::Left {
                         let _ = table_for_handle.take_focus();
+                        if let Some((col, in_filter_row)) = Self::get_col_header_at_mouse(
+                            &table_for_handle,
+                            show_filter_row_for_handle.get(),
+                        ) {
+                            if in_filter_row {
+                                Self::edit_column_filter(
+                                    &mut table_for_handle,
+                                    &headers_for_handle,
+                                    &full_data_for_handle,
+                                    col,
+                                    font_size_for_handle.get(),
+                                );
+                            } else {
+                                if let Ok(mut data) = full_data_for_handle.try_borrow_mut() {
+                                    data.cycle_sort(col as usize);
+                                }
+                                Self::refresh_view(
+                                    &mut table_for_handle,
+                                    &full_data_for_handle,
+                                    font_size_for_handle.get(),
+                                );
+                            }
+                            return true;
+                        }
                         if let Some((row, col)) = Self::get_cell_at_mouse(&table_for_handle) {
+                            if app::event_clicks()
+                                && editing_for_handle.can_edit_cell(&full_data_for_handle, row, col)
+                            {
+                                Self::edit_cell(
+                                    &mut table_for_handle,
+                                    &headers_for_handle,
+                                    &full_data_for_handle,
+                                    &editing_for_handle,
+                                    row,
+                                    col,
+                                );
+                                return true;
+                            }
                             if app::event_clicks() {
                                 // Clone the cell value so the Ref is dropped before
                                 // entering the modal dialog's event loop. Holding the
@@ -478,7 +696,10 @@ impl ResultTableWidget {
                                     full_data_for_handle.try_borrow().ok().and_then(|data| {
                                         data.get(row as usize)
                                             .and_then(|r| r.get(col as usize))
-                                            .cloned()
+                                            .map(|cell| {
+                                                cell.display(&null_text_for_handle.borrow())
+                                                    .to_string()
+                                            })
                                     });
                                 if let Some(cell_val) = cell_val_owned {
                                     Self::show_cell_text_dialog(
@@ -538,6 +759,21 @@ impl ResultTableWidget {
                         return Self::should_consume_boundary_arrow(&table_for_handle, key);
                     }
 
+                    if key == Key::F2 {
+                        let (row, col, _, _) = table_for_handle.get_selection();
+                        if editing_for_handle.can_edit_cell(&full_data_for_handle, row, col) {
+                            Self::edit_cell(
+                                &mut table_for_handle,
+                                &headers_for_handle,
+                                &full_data_for_handle,
+                                &editing_for_handle,
+                                row,
+                                col,
+                            );
+                        }
+                        return true;
+                    }
+
                     if ctrl_or_cmd {
                         match key {
                             k if (k == Key::from_char('c') || k == Key::from_char('C'))
@@ -547,6 +783,7 @@ impl ResultTableWidget {
                                     &table_for_handle,
                                     &headers_for_handle,
                                     &full_data_for_handle,
+                                    &null_text_for_handle.borrow(),
                                 );
                                 return true;
                             }
@@ -564,6 +801,7 @@ impl ResultTableWidget {
                                     &table_for_handle,
                                     &headers_for_handle,
                                     &full_data_for_handle,
+                                    &null_text_for_handle.borrow(),
                                 );
                                 return true;
                             }
@@ -587,6 +825,7 @@ impl ResultTableWidget {
                             &table_for_handle,
                             &headers_for_handle,
                             &full_data_for_handle,
+                            &null_text_for_handle.borrow(),
                         );
                         return true;
                     }
@@ -595,6 +834,7 @@ impl ResultTableWidget {
                             &table_for_handle,
                             &headers_for_handle,
                             &full_data_for_handle,
+                            &null_text_for_handle.borrow(),
                         );
                         return true;
                     }
@@ -620,10 +860,15 @@ impl ResultTableWidget {
             pending_widths: Rc::new(RefCell::new(Vec::new())),
             last_flush: Rc::new(RefCell::new(Instant::now())),
             full_data,
+            show_filter_row,
+            null_text,
             max_cell_display_chars,
             width_sampled_rows: Rc::new(RefCell::new(0)),
             font_profile,
             font_size,
+            pending_rowids: Rc::new(RefCell::new(Vec::new())),
+            source_sql: Rc::new(RefCell::new(None)),
+            editing,
         }
     }
 
@@ -774,7 +1019,11 @@ impl ResultTableWidget {
     fn show_context_menu(
         table: &Table,
         headers: &Rc<RefCell<Vec<String>>>,
-        full_data: &Rc<RefCell<Vec<Vec<String>>>>,
+        full_data: &Rc<RefCell<ResultRows>>,
+        null_text: &str,
+        show_filter_row: &Rc<Cell<bool>>,
+        font_size: u32,
+        editing: &GridEditing,
     ) {
         let mouse_x = app::event_x();
         let mouse_y = app::event_y();
@@ -799,6 +1048,21 @@ impl ResultTableWidget {
         menu.set_color(theme::panel_raised());
         menu.set_text_color(theme::text_primary());
         menu.add_choice("Copy|Copy with Headers|Copy All");
+        let filter_row_label = if show_filter_row.get() {
+            "Hide Filter Row"
+        } else {
+            "Show Filter Row"
+        };
+        menu.add_choice(&format!(
+            "{}|Clear Filters|Original Order",
+            filter_row_label
+        ));
+        if editing.is_active() {
+            menu.add_choice("Edit Cell|Set NULL|Insert Row|Delete Row(s)");
+            if full_data.borrow().has_pending_edits() {
+                menu.add_choice("Review and Apply Changes...|Discard Changes");
+            }
+        }
 
         if let Some(ref group) = current_group {
             fltk::group::Group::set_current(Some(group));
@@ -808,12 +1072,42 @@ impl ResultTableWidget {
             let choice_label = choice.label().unwrap_or_default();
             match choice_label.as_str() {
                 "Copy" => {
-                    Self::copy_selected_to_clipboard(&table, headers, full_data);
+                    Self::copy_selected_to_clipboard(&table, headers, full_data, null_text);
                 }
                 "Copy with Headers" => {
-                    Self::copy_selected_with_headers(&table, headers, full_data);
+                    Self::copy_selected_with_headers(&table, headers, full_data, null_text);
+                }
+                "Copy All" => Self::copy_all_to_clipboard(headers, full_data, null_text),
+                "Show Filter Row" | "Hide Filter Row" => {
+                    let show = !show_filter_row.get();
+                    show_filter_row.set(show);
+                    table.set_col_header_height(Self::col_header_height(font_size, show));
+                    table.redraw();
+                }
+                "Clear Filters" => {
+                    full_data.borrow_mut().clear_filters();
+                    Self::refresh_view(&mut table, full_data, font_size);
+                }
+                "Original Order" => {
+                    full_data.borrow_mut().set_sort(None);
+                    Self::refresh_view(&mut table, full_data, font_size);
+                }
+                "Edit Cell" => {
+                    let (row, col, _, _) = table.get_selection();
+                    if editing.can_edit_cell(full_data, row, col) {
+                        Self::edit_cell(&mut table, headers, full_data, editing, row, col);
+                    }
+                }
+                "Set NULL" => Self::set_selection_null(&mut table, full_data, editing),
+                "Insert Row" => Self::insert_row(&mut table, headers, full_data, font_size),
+                "Delete Row(s)" => Self::toggle_selected_rows_deleted(&mut table, full_data),
+                "Review and Apply Changes..." => {
+                    Self::review_and_apply_changes(&table, full_data, editing, font_size);
+                }
+                "Discard Changes" => {
+                    full_data.borrow_mut().discard_edits();
+                    Self::refresh_view(&mut table, full_data, font_size);
                 }
-                "Copy All" => Self::copy_all_to_clipboard(headers, full_data),
                 _ => {}
             }
         }
@@ -821,10 +1115,309 @@ impl ResultTableWidget {
         MenuButton::delete(menu);
     }
 
+    /// Prompt for a new value of one cell. An empty value stores NULL.
+    fn edit_cell(
+        table: &mut Table,
+        headers: &Rc<RefCell<Vec<String>>>,
+        full_data: &Rc<RefCell<ResultRows>>,
+        editing: &GridEditing,
+        row: i32,
+        col: i32,
+    ) {
+        let Some(kind) = editing.target.borrow().as_ref().and_then(|target| {
+            target
+                .columns
+                .get(col as usize)
+                .and_then(|column| column.as_ref().map(|column| column.kind))
+        }) else {
+            return;
+        };
+        let column_name = headers
+            .borrow()
+            .get(col as usize)
+            .cloned()
+            .unwrap_or_default();
+        let current = full_data
+            .borrow()
+            .get(row as usize)
+            .and_then(|cells| cells.get(col as usize))
+            .and_then(|cell| cell.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let prompt = format!("{} (leave empty for NULL):", column_name);
+        if let Some(text) = fltk::dialog::input_default(&prompt, &current) {
+            let value = if text.is_empty() {
+                CellValue::Null
+            } else {
+                CellValue::with_kind(kind, Some(text))
+            };
+            full_data
+                .borrow_mut()
+                .set_cell(row as usize, col as usize, value);
+            table.redraw();
+        }
+    }
+
+    fn set_selection_null(
+        table: &mut Table,
+        full_data: &Rc<RefCell<ResultRows>>,
+        editing: &GridEditing,
+    ) {
+        let (row_top, col_left, row_bot, col_right) = table.get_selection();
+        if row_top < 0 || col_left < 0 {
+            return;
+        }
+        for row in row_top..=row_bot {
+            for col in col_left..=col_right {
+                if editing.can_edit_cell(full_data, row, col) {
+                    full_data
+                        .borrow_mut()
+                        .set_cell(row as usize, col as usize, CellValue::Null);
+                }
+            }
+        }
+        table.redraw();
+    }
+
+    fn insert_row(
+        table: &mut Table,
+        headers: &Rc<RefCell<Vec<String>>>,
+        full_data: &Rc<RefCell<ResultRows>>,
+        font_size: u32,
+    ) {
+        let column_count = headers.borrow().len();
+        let (row, visible_rows) = {
+            let mut data = full_data.borrow_mut();
+            let row = data.insert_row(column_count);
+            (row as i32, data.len() as i32)
+        };
+        table.set_rows(visible_rows);
+        table.set_row_height_all(Self::row_height_for_font(font_size));
+        table.set_selection(row, 0, row, 0);
+        table.set_top_row(row);
+        table.redraw();
+    }
+
+    /// Mark the selected rows for deletion; rows already marked are restored.
+    fn toggle_selected_rows_deleted(table: &mut Table, full_data: &Rc<RefCell<ResultRows>>) {
+        let (row_top, _, row_bot, _) = table.get_selection();
+        if row_top < 0 {
+            return;
+        }
+        {
+            let mut data = full_data.borrow_mut();
+            for row in row_top..=row_bot {
+                let row = row as usize;
+                if data.is_row_editable(row) || data.row_edit_state(row) == RowEditState::Deleted {
+                    data.toggle_deleted(row);
+                }
+            }
+        }
+        table.redraw();
+    }
+
+    /// Show the DML for the pending edits and, once confirmed, apply it
+    /// through the edit handler. Edits stay pending if applying fails.
+    fn review_and_apply_changes(
+        table: &Table,
+        full_data: &Rc<RefCell<ResultRows>>,
+        editing: &GridEditing,
+        font_size: u32,
+    ) {
+        let statements: Vec<DmlStatement> = {
+            let target = editing.target.borrow();
+            let Some(target) = target.as_ref() else {
+                return;
+            };
+            full_data
+                .borrow()
+                .pending_changes()
+                .iter()
+                .filter_map(|change| target.build_dml(change))
+                .collect()
+        };
+        if statements.is_empty() {
+            fltk::dialog::message_default("There are no changes to apply.");
+            return;
+        }
+        let Some(handler) = editing.apply_handler.borrow().clone() else {
+            fltk::dialog::alert_default("Changes cannot be applied from this grid.");
+            return;
+        };
+
+        let preview = statements
+            .iter()
+            .map(DmlStatement::preview)
+            .collect::<Vec<String>>()
+            .join("\n\n");
+        if !Self::confirm_changes_dialog(&preview, font_size) {
+            return;
+        }
+
+        editing.applying.set(true);
+        let applying = editing.applying.clone();
+        let full_data = full_data.clone();
+        let mut table = table.clone();
+        handler(
+            statements,
+            Box::new(move |result| {
+                applying.set(false);
+                match result {
+                    Ok(_) => {
+                        full_data.borrow_mut().accept_edits();
+                        let visible_rows = full_data.borrow().len() as i32;
+                        table.unset_selection();
+                        table.set_rows(visible_rows);
+                        table.set_row_height_all(Self::row_height_for_font(font_size));
+                        table.redraw();
+                    }
+                    Err(err) => {
+                        fltk::dialog::alert_default(&format!("Changes were not applied:\n{}", err));
+                    }
+                }
+            }),
+        );
+    }
+
+    fn confirm_changes_dialog(sql_text: &str, font_size: u32) -> bool {
+        let current_group = Group::try_current();
+        Group::set_current(None::<&Group>);
+
+        let mut dialog = Window::default()
+            .with_size(760, 520)
+            .with_label("Apply Changes");
+        dialog.set_color(theme::panel_raised());
+        dialog.make_modal(true);
+
+        let mut display = TextDisplay::new(10, 10, 740, 460, None);
+        display.set_color(theme::editor_bg());
+        display.set_text_color(theme::text_primary());
+        display.set_text_font(configured_editor_profile().normal);
+        display.set_text_size(font_size as i32);
+
+        let mut buf = TextBuffer::default();
+        buf.set_text(sql_text);
+        display.set_buffer(buf);
+
+        let confirmed = Rc::new(Cell::new(false));
+
+        let mut apply_btn = Button::new(
+            760 - 20 - BUTTON_WIDTH * 2,
+            480,
+            BUTTON_WIDTH,
+            BUTTON_HEIGHT,
+            "Apply",
+        );
+        apply_btn.set_color(theme::button_primary());
+        apply_btn.set_label_color(theme::text_primary());
+        apply_btn.set_frame(FrameType::RFlatBox);
+
+        let mut cancel_btn = Button::new(
+            760 - 10 - BUTTON_WIDTH,
+            480,
+            BUTTON_WIDTH,
+            BUTTON_HEIGHT,
+            "Cancel",
+        );
+        cancel_btn.set_color(theme::button_secondary());
+        cancel_btn.set_label_color(theme::text_primary());
+        cancel_btn.set_frame(FrameType::RFlatBox);
+
+        let mut dialog_for_apply = dialog.clone();
+        let confirmed_for_apply = confirmed.clone();
+        apply_btn.set_callback(move |_| {
+            confirmed_for_apply.set(true);
+            dialog_for_apply.hide();
+            app::awake();
+        });
+
+        let mut dialog_for_cancel = dialog.clone();
+        cancel_btn.set_callback(move |_| {
+            dialog_for_cancel.hide();
+            app::awake();
+        });
+
+        dialog.end();
+        dialog.show();
+        Group::set_current(current_group.as_ref());
+
+        while dialog.shown() {
+            app::wait();
+        }
+        confirmed.get()
+    }
+
+    /// Column header under the mouse, and whether the click landed in the filter row.
+    fn get_col_header_at_mouse(table: &Table, show_filter_row: bool) -> Option<(i32, bool)> {
+        let cols = table.cols();
+        if cols <= 0 {
+            return None;
+        }
+
+        let mouse_x = app::event_x();
+        let mouse_y = app::event_y();
+        let header_h = table.col_header_height();
+        let header_top = table.y();
+        if mouse_y < header_top
+            || mouse_y >= header_top + header_h
+            || mouse_x < table.x() + table.row_header_width()
+        {
+            return None;
+        }
+
+        let start_col = table.col_position().max(0).min(cols - 1);
+        for col in start_col..cols {
+            let Some((cx, cy, cw, ch)) = table.find_cell(TableContext::ColHeader, 0, col) else {
+                break;
+            };
+            if mouse_x >= cx && mouse_x < cx + cw {
+                let in_filter_row = show_filter_row && mouse_y >= cy + ch / 2;
+                return Some((col, in_filter_row));
+            }
+            if cx > mouse_x {
+                break;
+            }
+        }
+
+        None
+    }
+
+    /// Re-sync the table with the visible rows after the sort or filters change.
+    fn refresh_view(table: &mut Table, full_data: &Rc<RefCell<ResultRows>>, font_size: u32) {
+        let visible_rows = full_data.try_borrow().map(|data| data.len()).unwrap_or(0);
+        table.unset_selection();
+        table.set_rows(visible_rows as i32);
+        table.set_row_height_all(Self::row_height_for_font(font_size));
+        table.redraw();
+    }
+
+    fn edit_column_filter(
+        table: &mut Table,
+        headers: &Rc<RefCell<Vec<String>>>,
+        full_data: &Rc<RefCell<ResultRows>>,
+        col: i32,
+        font_size: u32,
+    ) {
+        let column_name = headers
+            .borrow()
+            .get(col as usize)
+            .cloned()
+            .unwrap_or_default();
+        let current = full_data.borrow().filter_text(col as usize).to_string();
+        let prompt = format!(
+            "Filter {} (text, =, <>, <, <=, >, >=, NULL, !NULL):",
+            column_name
+        );
+        if let Some(text) = fltk::dialog::input_default(&prompt, &current) {
+            full_data.borrow_mut().set_filter(col as usize, &text);
+            Self::refresh_view(table, full_data, font_size);
+        }
+    }
+
     fn copy_selected_to_clipboard(
         table: &Table,
         _headers: &Rc<RefCell<Vec<String>>>,
-        full_data: &Rc<RefCell<Vec<Vec<String>>>>,
+        full_data: &Rc<RefCell<ResultRows>>,
+        null_text: &str,
     ) -> usize {
         let (row_top, col_left, row_bot, col_right) = table.get_selection();
         if row_top < 0 || col_left < 0 {
@@ -849,7 +1442,7 @@ impl ResultTableWidget {
                     .get(row as usize)
                     .and_then(|r| r.get(col as usize))
                 {
-                    result.push_str(val);
+                    result.push_str(val.display(null_text));
                 }
             }
         }
@@ -865,7 +1458,8 @@ impl ResultTableWidget {
     fn copy_selected_with_headers(
         table: &Table,
         headers: &Rc<RefCell<Vec<String>>>,
-        full_data: &Rc<RefCell<Vec<Vec<String>>>>,
+        full_data: &Rc<RefCell<ResultRows>>,
+        null_text: &str,
     ) -> usize {
         let (row_top, col_left, row_bot, col_right) = table.get_selection();
         if row_top < 0 || col_left < 0 {
@@ -901,7 +1495,7 @@ impl ResultTableWidget {
                     .get(row as usize)
                     .and_then(|r| r.get(col as usize))
                 {
-                    result.push_str(val);
+                    result.push_str(val.display(null_text));
                 }
             }
             result.push('\n');
@@ -917,7 +1511,8 @@ impl ResultTableWidget {
 
     fn copy_all_to_clipboard(
         headers: &Rc<RefCell<Vec<String>>>,
-        full_data: &Rc<RefCell<Vec<Vec<String>>>>,
+        full_data: &Rc<RefCell<ResultRows>>,
+        null_text: &str,
     ) {
         let headers = headers.borrow();
         let full_data = full_data.borrow();
@@ -935,7 +1530,7 @@ impl ResultTableWidget {
                 if i > 0 {
                     result.push('\t');
                 }
-                result.push_str(cell);
+                result.push_str(cell.display(null_text));
             }
             result.push('\n');
         }
@@ -946,7 +1541,9 @@ impl ResultTableWidget {
     }
 
     pub fn display_result(&mut self, result: &QueryResult) {
+        self.editing.streaming.set(false);
         if !result.is_select {
+            *self.source_sql.borrow_mut() = None;
             let font_size = self.font_size.get();
             let max_cell_display_chars = self.max_cell_display_chars.get();
             self.table.set_rows(1);
@@ -958,12 +1555,17 @@ impl ResultTableWidget {
                     .min(1200);
             self.table.set_col_width(0, message_width);
             *self.headers.borrow_mut() = vec!["Result".to_string()];
-            *self.full_data.borrow_mut() = vec![vec![result.message.clone()]];
+            {
+                let mut full_data = self.full_data.borrow_mut();
+                full_data.clear();
+                full_data.set_rows(vec![vec![CellValue::Text(result.message.clone())]]);
+            }
             self.table.redraw();
             return;
         }
 
-        if result.rows.is_empty() && result.row_count > 0 && self.table.rows() > 0 {
+        if result.rows.is_empty() && result.row_count > 0 && self.full_data.borrow().total_len() > 0
+        {
             let col_names: Vec<String> = result.columns.iter().map(|c| c.name.clone()).collect();
             let col_count = col_names.len() as i32;
             if self.table.cols() < col_count {
@@ -989,6 +1591,7 @@ impl ResultTableWidget {
         let widths = Self::compute_column_widths(
             &col_names,
             &result.rows,
+            &self.null_text.borrow(),
             font_size,
             max_cell_display_chars,
         );
@@ -997,7 +1600,7 @@ impl ResultTableWidget {
 
         // Store data directly — draw_cell reads from full_data on demand.
         // No per-cell set_cell_value calls needed!
-        *self.full_data.borrow_mut() = result.rows.clone();
+        self.full_data.borrow_mut().set_rows(result.rows.clone());
         *self.headers.borrow_mut() = col_names;
         self.table.redraw();
     }
@@ -1007,6 +1610,10 @@ impl ResultTableWidget {
 
         // Clear any pending data from previous queries
         self.pending_rows.borrow_mut().clear();
+        self.pending_rowids.borrow_mut().clear();
+        *self.source_sql.borrow_mut() = None;
+        *self.editing.target.borrow_mut() = None;
+        self.editing.streaming.set(true);
         self.pending_widths.borrow_mut().clear();
         self.full_data.borrow_mut().clear();
         *self.last_flush.borrow_mut() = Instant::now();
@@ -1032,8 +1639,25 @@ impl ResultTableWidget {
         self.table.redraw();
     }
 
+    /// Make the streamed result editable. Rows appended afterwards must carry
+    /// their ROWID as a trailing cell.
+    pub fn set_edit_target(&mut self, target: Option<EditTarget>) {
+        *self.editing.target.borrow_mut() = target;
+    }
+
+    pub fn set_edit_apply_handler(&mut self, handler: EditApplyHandler) {
+        *self.editing.apply_handler.borrow_mut() = Some(handler);
+    }
+
     /// Append rows to the buffer. UI is updated periodically for performance.
-    pub fn append_rows(&mut self, rows: Vec<Vec<String>>) {
+    pub fn append_rows(&mut self, mut rows: Vec<Vec<CellValue>>) {
+        if self.editing.target.borrow().is_some() {
+            let mut rowids = self.pending_rowids.borrow_mut();
+            for row in rows.iter_mut() {
+                rowids.push(row.pop().and_then(|cell| cell.as_str().map(str::to_string)));
+            }
+        }
+
         // Only compute column widths for the first WIDTH_SAMPLE_ROWS rows
         let sampled = *self.width_sampled_rows.borrow();
         if sampled < WIDTH_SAMPLE_ROWS {
@@ -1041,6 +1665,7 @@ impl ResultTableWidget {
             let mut widths = self.pending_widths.borrow_mut();
             let min_width = Self::min_col_width_for_font(self.font_size.get());
             let max_cell_display_chars = self.max_cell_display_chars.get();
+            let null_text = self.null_text.borrow();
             if widths.len() < max_cols {
                 widths.resize(max_cols, min_width);
             }
@@ -1050,11 +1675,13 @@ impl ResultTableWidget {
                 Self::update_widths_with_row(
                     &mut widths,
                     row,
+                    &null_text,
                     self.font_size.get(),
                     max_cell_display_chars,
                 );
             }
             drop(widths);
+            drop(null_text);
             *self.width_sampled_rows.borrow_mut() = sampled + sample_count;
         }
 
@@ -1077,15 +1704,11 @@ impl ResultTableWidget {
     /// Data is moved (not cloned) from pending_rows into full_data.
     /// Only the table row count is updated — draw_cell handles rendering on demand.
     pub fn flush_pending(&mut self) {
-        let rows_to_add: Vec<Vec<String>> = self.pending_rows.borrow_mut().drain(..).collect();
+        let rows_to_add: Vec<Vec<CellValue>> = self.pending_rows.borrow_mut().drain(..).collect();
         if rows_to_add.is_empty() {
             return;
         }
 
-        let new_rows_count = rows_to_add.len() as i32;
-        let current_rows = self.table.rows();
-        let new_total = current_rows + new_rows_count;
-
         // Update column widths
         {
             let widths = self.pending_widths.borrow();
@@ -1104,7 +1727,17 @@ impl ResultTableWidget {
         }
 
         // Move data into full_data — zero-copy, no clone!
-        self.full_data.borrow_mut().extend(rows_to_add);
+        // Active sort/filters are applied to the new rows as they arrive.
+        let rowids: Vec<Option<String>> = self.pending_rowids.borrow_mut().drain(..).collect();
+        let new_total = {
+            let mut full_data = self.full_data.borrow_mut();
+            if rowids.is_empty() {
+                full_data.extend(rows_to_add);
+            } else {
+                full_data.extend_with_rowids(rows_to_add, rowids);
+            }
+            full_data.len() as i32
+        };
 
         // Just update row count — draw_cell reads from full_data on demand
         self.table.set_rows(new_total);
@@ -1114,9 +1747,16 @@ impl ResultTableWidget {
         self.table.redraw();
     }
 
+    /// Set the text drawn for NULL cells (SQL*Plus `SET NULL`).
+    pub fn set_null_text(&mut self, null_text: &str) {
+        *self.null_text.borrow_mut() = null_text.to_string();
+        self.table.redraw();
+    }
+
     /// Call this when streaming is complete to flush any remaining buffered rows
     pub fn finish_streaming(&mut self) {
         self.flush_pending();
+        self.editing.streaming.set(false);
         self.table.redraw();
     }
 
@@ -1139,6 +1779,13 @@ impl ResultTableWidget {
             pending_widths.clear();
             pending_widths.shrink_to_fit();
         }
+        {
+            let mut pending_rowids = self.pending_rowids.borrow_mut();
+            pending_rowids.clear();
+            pending_rowids.shrink_to_fit();
+        }
+        *self.source_sql.borrow_mut() = None;
+        *self.editing.target.borrow_mut() = None;
         {
             let mut full_data = self.full_data.borrow_mut();
             full_data.clear();
@@ -1150,7 +1797,12 @@ impl ResultTableWidget {
     }
 
     pub fn copy(&self) -> usize {
-        let count = Self::copy_selected_to_clipboard(&self.table, &self.headers, &self.full_data);
+        let count = Self::copy_selected_to_clipboard(
+            &self.table,
+            &self.headers,
+            &self.full_data,
+            &self.null_text.borrow(),
+        );
         if count > 0 {
             let rows = (self.table.get_selection().2 - self.table.get_selection().0 + 1) as usize;
             let cols = (self.table.get_selection().3 - self.table.get_selection().1 + 1) as usize;
@@ -1160,7 +1812,12 @@ impl ResultTableWidget {
     }
 
     pub fn copy_with_headers(&self) {
-        Self::copy_selected_with_headers(&self.table, &self.headers, &self.full_data);
+        Self::copy_selected_with_headers(
+            &self.table,
+            &self.headers,
+            &self.full_data,
+            &self.null_text.borrow(),
+        );
     }
 
     pub fn select_all(&mut self) {
@@ -1181,6 +1838,7 @@ impl ResultTableWidget {
         }
 
         let full_data = self.full_data.borrow();
+        let null_text = self.null_text.borrow();
         let rows = (row_bot - row_top + 1) as usize;
         let cols = (col_right - col_left + 1) as usize;
         let mut result = String::with_capacity(rows * cols * 16);
@@ -1196,7 +1854,7 @@ impl ResultTableWidget {
                     .get(row as usize)
                     .and_then(|r| r.get(col as usize))
                 {
-                    result.push_str(val);
+                    result.push_str(val.display(&null_text));
                 }
             }
         }
@@ -1208,41 +1866,23 @@ impl ResultTableWidget {
         }
     }
 
-    /// Export all data to CSV format
-    pub fn export_to_csv(&self) -> String {
-        let headers = self.headers.borrow();
-        let full_data = self.full_data.borrow();
-        let row_count = full_data.len();
-        let col_count = headers.len();
-        let mut csv = String::with_capacity((row_count + 1) * col_count * 20);
-
-        // Header row
-        let header_line: Vec<String> = headers.iter().map(|h| Self::escape_csv_field(h)).collect();
-        csv.push_str(&header_line.join(","));
-        csv.push('\n');
-
-        // Data rows
-        for row in full_data.iter() {
-            for (i, cell) in row.iter().enumerate() {
-                if i > 0 {
-                    csv.push(',');
-                }
-                csv.push_str(&Self::escape_csv_field(cell));
-            }
-            csv.push('\n');
-        }
+    /// Column names and visible rows, in display order, for exporting.
+    pub fn export_snapshot(&self) -> (Vec<String>, Vec<Vec<CellValue>>) {
+        let headers = self.headers.borrow().clone();
+        let rows = self.full_data.borrow().iter().cloned().collect();
+        (headers, rows)
+    }
 
-        csv
+    /// SQL of the SELECT that produced the current rows.
+    pub fn source_sql(&self) -> Option<String> {
+        self.source_sql.borrow().clone()
     }
 
-    fn escape_csv_field(field: &str) -> String {
-        if field.contains(',') || field.contains('"') || field.contains('\n') {
-            format!("\"{}\"", field.replace('"', "\"\""))
-        } else {
-            field.to_string()
-        }
+    pub fn set_source_sql(&mut self, sql: &str) {
+        *self.source_sql.borrow_mut() = Some(sql.to_string());
     }
 
+    #[allow(dead_code)]
     pub fn row_count(&self) -> usize {
         self.table.rows() as usize
     }
@@ -1307,6 +1947,13 @@ impl ResultTableWidget {
             pending_widths.clear();
             pending_widths.shrink_to_fit();
         }
+        {
+            let mut pending_rowids = self.pending_rowids.borrow_mut();
+            pending_rowids.clear();
+            pending_rowids.shrink_to_fit();
+        }
+        *self.source_sql.borrow_mut() = None;
+        *self.editing.target.borrow_mut() = None;
         {
             let mut full_data = self.full_data.borrow_mut();
             full_data.clear();
@@ -8,8 +8,10 @@ use fltk::{
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
+use crate::db::{CellValue, EditTarget};
 use crate::ui::constants;
 use crate::ui::font_settings::{configured_editor_profile, FontProfile};
+use crate::ui::result_table::EditApplyHandler;
 use crate::ui::theme;
 use crate::ui::ResultTableWidget;
 
@@ -22,6 +24,7 @@ pub struct ResultTabsWidget {
     font_profile: Rc<Cell<FontProfile>>,
     font_size: Rc<Cell<u32>>,
     max_cell_display_chars: Rc<Cell<usize>>,
+    edit_apply_handler: Rc<RefCell<Option<EditApplyHandler>>>,
 }
 
 #[derive(Clone)]
@@ -209,6 +212,7 @@ impl ResultTabsWidget {
             font_profile,
             font_size,
             max_cell_display_chars,
+            edit_apply_handler: Rc::new(RefCell::new(None)),
         }
     }
 
@@ -231,6 +235,11 @@ impl ResultTabsWidget {
         self.max_cell_display_chars.set(max_chars);
     }
 
+    /// Set how result grids apply their pending edits; used by every new tab.
+    pub fn set_edit_apply_handler(&mut self, handler: EditApplyHandler) {
+        *self.edit_apply_handler.borrow_mut() = Some(handler);
+    }
+
     pub fn clear(&mut self) {
         let tabs_to_delete: Vec<_> = self.data.borrow_mut().drain(..).collect();
         for tab in tabs_to_delete {
@@ -302,6 +311,9 @@ impl ResultTabsWidget {
         let mut table = ResultTableWidget::with_size(x, y, w, h);
         table.apply_font_settings(self.font_profile.get(), self.font_size.get());
         table.set_max_cell_display_chars(self.max_cell_display_chars.get());
+        if let Some(handler) = self.edit_apply_handler.borrow().clone() {
+            table.set_edit_apply_handler(handler);
+        }
         let widget = table.get_widget();
         group.resizable(&widget);
         group.end();
@@ -316,14 +328,22 @@ impl ResultTabsWidget {
         *self.active_index.borrow_mut() = Some(new_index);
     }
 
-    pub fn start_streaming(&mut self, index: usize, columns: &[String]) {
+    pub fn start_streaming(
+        &mut self,
+        index: usize,
+        columns: &[String],
+        null_text: &str,
+        edit_target: Option<EditTarget>,
+    ) {
         if let Some(tab) = self.data.borrow().get(index) {
             let mut table = tab.table.clone();
+            table.set_null_text(null_text);
             table.start_streaming(columns);
+            table.set_edit_target(edit_target);
         }
     }
 
-    pub fn append_rows(&mut self, index: usize, rows: Vec<Vec<String>>) {
+    pub fn append_rows(&mut self, index: usize, rows: Vec<Vec<CellValue>>) {
         if let Some(tab) = self.data.borrow().get(index) {
             let mut table = tab.table.clone();
             table.append_rows(rows);
@@ -352,12 +372,24 @@ impl ResultTabsWidget {
         }
     }
 
-    pub fn export_to_csv(&self) -> String {
+    pub fn export_snapshot(&self) -> (Vec<String>, Vec<Vec<CellValue>>) {
         self.current_table()
-            .map(|table| table.export_to_csv())
+            .map(|table| table.export_snapshot())
             .unwrap_or_default()
     }
 
+    pub fn current_source_sql(&self) -> Option<String> {
+        self.current_table().and_then(|table| table.source_sql())
+    }
+
+    pub fn set_source_sql(&mut self, index: usize, sql: &str) {
+        if let Some(tab) = self.data.borrow().get(index) {
+            let mut table = tab.table.clone();
+            table.set_source_sql(sql);
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn row_count(&self) -> usize {
         self.current_table()
             .map(|table| table.row_count())
@@ -0,0 +1,706 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::db::{CellValue, RowChange};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    /// Header click cycle: ascending, descending, then back to fetch order.
+    pub fn next(current: Option<SortOrder>) -> Option<SortOrder> {
+        match current {
+            None => Some(SortOrder::Ascending),
+            Some(SortOrder::Ascending) => Some(SortOrder::Descending),
+            Some(SortOrder::Descending) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A per-column filter parsed from the text typed into the filter row.
+///
+/// Plain text matches cells containing it (case-insensitive). `NULL` and
+/// `!NULL` match on nullness, and a leading `=`, `!=`, `<>`, `<`, `<=`, `>`
+/// or `>=` compares using the cell's type (numbers numerically, dates
+/// chronologically).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnFilter {
+    Contains(String),
+    IsNull,
+    IsNotNull,
+    Compare(FilterOp, String),
+}
+
+impl ColumnFilter {
+    pub fn parse(text: &str) -> Option<Self> {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+        if trimmed.eq_ignore_ascii_case("NULL") {
+            return Some(ColumnFilter::IsNull);
+        }
+        if trimmed.eq_ignore_ascii_case("!NULL") || trimmed.eq_ignore_ascii_case("NOT NULL") {
+            return Some(ColumnFilter::IsNotNull);
+        }
+
+        const OPERATORS: [(&str, FilterOp); 7] = [
+            ("<>", FilterOp::Ne),
+            ("!=", FilterOp::Ne),
+            ("<=", FilterOp::Le),
+            (">=", FilterOp::Ge),
+            ("<", FilterOp::Lt),
+            (">", FilterOp::Gt),
+            ("=", FilterOp::Eq),
+        ];
+        for (prefix, op) in OPERATORS {
+            if let Some(operand) = trimmed.strip_prefix(prefix) {
+                return Some(ColumnFilter::Compare(op, operand.trim().to_string()));
+            }
+        }
+
+        Some(ColumnFilter::Contains(trimmed.to_lowercase()))
+    }
+
+    pub fn matches(&self, cell: &CellValue) -> bool {
+        match self {
+            ColumnFilter::IsNull => cell.is_null(),
+            ColumnFilter::IsNotNull => !cell.is_null(),
+            ColumnFilter::Contains(needle) => cell
+                .as_str()
+                .map(|value| value.to_lowercase().contains(needle))
+                .unwrap_or(false),
+            ColumnFilter::Compare(op, operand) => {
+                if cell.is_null() {
+                    return false;
+                }
+                let ordering = Self::compare_to_operand(cell, operand);
+                match op {
+                    FilterOp::Eq => ordering == Ordering::Equal,
+                    FilterOp::Ne => ordering != Ordering::Equal,
+                    FilterOp::Lt => ordering == Ordering::Less,
+                    FilterOp::Le => ordering != Ordering::Greater,
+                    FilterOp::Gt => ordering == Ordering::Greater,
+                    FilterOp::Ge => ordering != Ordering::Less,
+                }
+            }
+        }
+    }
+
+    fn compare_to_operand(cell: &CellValue, operand: &str) -> Ordering {
+        let operand_cell = match cell {
+            CellValue::Number(_) => CellValue::Number(operand.to_string()),
+            CellValue::Date(_) => CellValue::Date(operand.to_string()),
+            CellValue::Timestamp(_) => CellValue::Timestamp(operand.to_string()),
+            _ => {
+                let value = cell.as_str().unwrap_or_default().to_lowercase();
+                return value.as_str().cmp(operand.to_lowercase().as_str());
+            }
+        };
+        if cell.is_numeric() && operand_cell.as_f64().is_none() {
+            // Not a number: fall back to comparing the text.
+            return cell.display("").cmp(operand);
+        }
+        cell.compare(&operand_cell)
+    }
+}
+
+/// Fetched result rows plus the visible order produced by the active sort
+/// and column filters. Rows are always kept in fetch order; `view` maps
+/// display rows back to them so clearing the sort restores the original order.
+#[derive(Debug, Default)]
+pub struct ResultRows {
+    rows: Vec<Vec<CellValue>>,
+    /// ROWID of each fetched row when the result is editable; empty otherwise.
+    rowids: Vec<Option<String>>,
+    edits: RowEdits,
+    /// Visible rows in display order; `None` shows every row in fetch order.
+    view: Option<Vec<usize>>,
+    sort: Option<(usize, SortOrder)>,
+    filter_text: Vec<String>,
+    filters: Vec<Option<ColumnFilter>>,
+}
+
+/// Grid edits not yet applied to the database, keyed by fetch-order index.
+#[derive(Debug, Default)]
+struct RowEdits {
+    /// Value each changed cell had when fetched.
+    originals: BTreeMap<(usize, usize), CellValue>,
+    inserted: BTreeSet<usize>,
+    deleted: BTreeSet<usize>,
+}
+
+/// How a row differs from what was fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowEditState {
+    Unchanged,
+    Modified,
+    Inserted,
+    Deleted,
+}
+
+impl ResultRows {
+    /// Number of visible rows.
+    pub fn len(&self) -> usize {
+        match &self.view {
+            Some(view) => view.len(),
+            None => self.rows.len(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of fetched rows, including rows hidden by filters.
+    pub fn total_len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Fetch-order index of a visible row.
+    pub fn source_index(&self, display_row: usize) -> Option<usize> {
+        match &self.view {
+            Some(view) => view.get(display_row).copied(),
+            None => (display_row < self.rows.len()).then_some(display_row),
+        }
+    }
+
+    pub fn get(&self, display_row: usize) -> Option<&Vec<CellValue>> {
+        self.source_index(display_row)
+            .and_then(|index| self.rows.get(index))
+    }
+
+    /// Visible rows in display order.
+    pub fn iter(&self) -> impl Iterator<Item = &Vec<CellValue>> + '_ {
+        (0..self.len()).filter_map(move |display_row| self.get(display_row))
+    }
+
+    /// Replace the fetched rows, keeping the current sort and filters.
+    pub fn set_rows(&mut self, rows: Vec<Vec<CellValue>>) {
+        self.rows = rows;
+        self.rowids.clear();
+        self.edits = RowEdits::default();
+        self.rebuild_view();
+    }
+
+    /// Append newly fetched editable rows along with their ROWIDs.
+    pub fn extend_with_rowids(&mut self, rows: Vec<Vec<CellValue>>, rowids: Vec<Option<String>>) {
+        self.rowids.resize(self.rows.len(), None);
+        self.rowids.extend(rowids);
+        self.extend(rows);
+    }
+
+    /// Append newly fetched rows, merging them into the current sort order.
+    pub fn extend(&mut self, rows: Vec<Vec<CellValue>>) {
+        let start = self.rows.len();
+        self.rows.extend(rows);
+        if self.view.is_none() {
+            return;
+        }
+
+        let mut added: Vec<usize> = (start..self.rows.len())
+            .filter(|&index| self.row_matches(index))
+            .collect();
+        let Some(sort) = self.sort else {
+            if let Some(view) = self.view.as_mut() {
+                view.extend(added);
+            }
+            return;
+        };
+
+        added.sort_by(|&a, &b| self.compare_rows(a, b, sort));
+        let existing = self.view.take().unwrap_or_default();
+        let mut merged = Vec::with_capacity(existing.len() + added.len());
+        let (mut left, mut right) = (0usize, 0usize);
+        while left < existing.len() && right < added.len() {
+            // Ties keep the earlier-fetched row first, matching a stable sort.
+            if self.compare_rows(existing[left], added[right], sort) != Ordering::Greater {
+                merged.push(existing[left]);
+                left += 1;
+            } else {
+                merged.push(added[right]);
+                right += 1;
+            }
+        }
+        merged.extend_from_slice(&existing[left..]);
+        merged.extend_from_slice(&added[right..]);
+        self.view = Some(merged);
+    }
+
+    /// Drop all rows along with the sort and filters.
+    pub fn clear(&mut self) {
+        self.rows.clear();
+        self.rowids.clear();
+        self.edits = RowEdits::default();
+        self.view = None;
+        self.sort = None;
+        self.filter_text.clear();
+        self.filters.clear();
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.rows.shrink_to_fit();
+        self.rowids.shrink_to_fit();
+        self.filter_text.shrink_to_fit();
+        self.filters.shrink_to_fit();
+    }
+
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    pub fn set_sort(&mut self, sort: Option<(usize, SortOrder)>) {
+        self.sort = sort;
+        self.rebuild_view();
+    }
+
+    /// Advance the sort on `column` through ascending, descending and fetch order.
+    pub fn cycle_sort(&mut self, column: usize) {
+        let current = self
+            .sort
+            .and_then(|(sorted_column, order)| (sorted_column == column).then_some(order));
+        let next = SortOrder::next(current).map(|order| (column, order));
+        self.set_sort(next);
+    }
+
+    pub fn filter_text(&self, column: usize) -> &str {
+        self.filter_text
+            .get(column)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    pub fn set_filter(&mut self, column: usize, text: &str) {
+        if self.filter_text.len() <= column {
+            self.filter_text.resize(column + 1, String::new());
+            self.filters.resize(column + 1, None);
+        }
+        self.filter_text[column] = text.trim().to_string();
+        self.filters[column] = ColumnFilter::parse(text);
+        self.rebuild_view();
+    }
+
+    pub fn clear_filters(&mut self) {
+        self.filter_text.clear();
+        self.filters.clear();
+        self.rebuild_view();
+    }
+
+    pub fn has_filters(&self) -> bool {
+        self.filters.iter().any(Option::is_some)
+    }
+
+    /// Whether the visible row can be edited: it was fetched with a ROWID
+    /// or inserted in the grid, and is not marked for deletion.
+    pub fn is_row_editable(&self, display_row: usize) -> bool {
+        let Some(index) = self.source_index(display_row) else {
+            return false;
+        };
+        let known_row = self.edits.inserted.contains(&index)
+            || self.rowids.get(index).map(Option::is_some).unwrap_or(false);
+        known_row && !self.edits.deleted.contains(&index)
+    }
+
+    pub fn row_edit_state(&self, display_row: usize) -> RowEditState {
+        let Some(index) = self.source_index(display_row) else {
+            return RowEditState::Unchanged;
+        };
+        if self.edits.deleted.contains(&index) {
+            RowEditState::Deleted
+        } else if self.edits.inserted.contains(&index) {
+            RowEditState::Inserted
+        } else if self
+            .edits
+            .originals
+            .range((index, 0)..(index + 1, 0))
+            .next()
+            .is_some()
+        {
+            RowEditState::Modified
+        } else {
+            RowEditState::Unchanged
+        }
+    }
+
+    pub fn is_cell_modified(&self, display_row: usize, column: usize) -> bool {
+        self.source_index(display_row)
+            .map(|index| self.edits.originals.contains_key(&(index, column)))
+            .unwrap_or(false)
+    }
+
+    /// Change a cell of a visible row, remembering the fetched value.
+    /// The row keeps its place in the view until the sort or filters change.
+    pub fn set_cell(&mut self, display_row: usize, column: usize, value: CellValue) {
+        let Some(index) = self.source_index(display_row) else {
+            return;
+        };
+        let Some(cell) = self.rows.get_mut(index).and_then(|row| row.get_mut(column)) else {
+            return;
+        };
+        if *cell == value {
+            return;
+        }
+        let previous = std::mem::replace(cell, value);
+        if self.edits.inserted.contains(&index) {
+            return;
+        }
+        let key = (index, column);
+        match self.edits.originals.get(&key) {
+            Some(original) if *original == self.rows[index][column] => {
+                self.edits.originals.remove(&key);
+            }
+            Some(_) => {}
+            None => {
+                self.edits.originals.insert(key, previous);
+            }
+        }
+    }
+
+    /// Add an all-NULL row at the end of the view and return its display row.
+    /// New rows stay visible whatever the filters say.
+    pub fn insert_row(&mut self, column_count: usize) -> usize {
+        let index = self.rows.len();
+        self.rows.push(vec![CellValue::Null; column_count]);
+        self.rowids.resize(index + 1, None);
+        self.edits.inserted.insert(index);
+        if let Some(view) = self.view.as_mut() {
+            view.push(index);
+        }
+        self.len() - 1
+    }
+
+    /// Mark a visible row for deletion, or unmark it.
+    pub fn toggle_deleted(&mut self, display_row: usize) {
+        let Some(index) = self.source_index(display_row) else {
+            return;
+        };
+        if !self.edits.deleted.remove(&index) {
+            self.edits.deleted.insert(index);
+        }
+    }
+
+    pub fn has_pending_edits(&self) -> bool {
+        !self.edits.originals.is_empty()
+            || !self.edits.inserted.is_empty()
+            || !self.edits.deleted.is_empty()
+    }
+
+    /// Pending edits as row changes, in fetch order.
+    pub fn pending_changes(&self) -> Vec<RowChange> {
+        let mut changes = Vec::new();
+        let mut touched: BTreeSet<usize> = self
+            .edits
+            .originals
+            .keys()
+            .map(|(index, _)| *index)
+            .collect();
+        touched.extend(self.edits.inserted.iter().copied());
+        touched.extend(self.edits.deleted.iter().copied());
+
+        for index in touched {
+            let inserted = self.edits.inserted.contains(&index);
+            let deleted = self.edits.deleted.contains(&index);
+            let rowid = self.rowids.get(index).cloned().flatten();
+            let Some(row) = self.rows.get(index) else {
+                continue;
+            };
+            match (inserted, deleted, rowid) {
+                (true, true, _) => {}
+                (true, false, _) => changes.push(RowChange::Insert {
+                    values: row.iter().cloned().enumerate().collect(),
+                }),
+                (false, true, Some(rowid)) => changes.push(RowChange::Delete { rowid }),
+                (false, false, Some(rowid)) => {
+                    let values = self
+                        .edits
+                        .originals
+                        .range((index, 0)..(index + 1, 0))
+                        .filter_map(|((_, column), _)| {
+                            row.get(*column).map(|cell| (*column, cell.clone()))
+                        })
+                        .collect();
+                    changes.push(RowChange::Update { rowid, values });
+                }
+                (false, _, None) => {}
+            }
+        }
+        changes
+    }
+
+    /// Put back fetched values and drop inserted rows.
+    pub fn discard_edits(&mut self) {
+        let edits = std::mem::take(&mut self.edits);
+        for ((index, column), original) in edits.originals {
+            if let Some(cell) = self.rows.get_mut(index).and_then(|row| row.get_mut(column)) {
+                *cell = original;
+            }
+        }
+        self.remove_rows(&edits.inserted);
+    }
+
+    /// The pending edits were written to the database: deleted rows go away
+    /// and everything else becomes the new baseline. Inserted rows have no
+    /// ROWID yet, so they stay read-only until the query is run again.
+    pub fn accept_edits(&mut self) {
+        let edits = std::mem::take(&mut self.edits);
+        self.remove_rows(&edits.deleted);
+    }
+
+    fn remove_rows(&mut self, indexes: &BTreeSet<usize>) {
+        if indexes.is_empty() {
+            return;
+        }
+        let rows = std::mem::take(&mut self.rows);
+        self.rows = rows
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !indexes.contains(index))
+            .map(|(_, row)| row)
+            .collect();
+        let rowids = std::mem::take(&mut self.rowids);
+        self.rowids = rowids
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !indexes.contains(index))
+            .map(|(_, rowid)| rowid)
+            .collect();
+        self.rebuild_view();
+    }
+
+    fn row_matches(&self, index: usize) -> bool {
+        if self.edits.inserted.contains(&index) {
+            return true;
+        }
+        let Some(row) = self.rows.get(index) else {
+            return false;
+        };
+        self.filters
+            .iter()
+            .enumerate()
+            .all(|(column, filter)| match filter {
+                Some(filter) => row
+                    .get(column)
+                    .map(|cell| filter.matches(cell))
+                    .unwrap_or(false),
+                None => true,
+            })
+    }
+
+    fn compare_rows(&self, a: usize, b: usize, sort: (usize, SortOrder)) -> Ordering {
+        let (column, order) = sort;
+        let left = self.rows.get(a).and_then(|row| row.get(column));
+        let right = self.rows.get(b).and_then(|row| row.get(column));
+        let ordering = match (left, right) {
+            (Some(left), Some(right)) => match order {
+                SortOrder::Ascending => left.compare(right),
+                // NULLs stay last in both directions.
+                SortOrder::Descending => match (left.is_null(), right.is_null()) {
+                    (false, false) => right.compare(left),
+                    _ => left.compare(right),
+                },
+            },
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        ordering.then(a.cmp(&b))
+    }
+
+    fn rebuild_view(&mut self) {
+        if self.sort.is_none() && !self.has_filters() {
+            self.view = None;
+            return;
+        }
+
+        let mut view: Vec<usize> = (0..self.rows.len())
+            .filter(|&index| self.row_matches(index))
+            .collect();
+        if let Some(sort) = self.sort {
+            view.sort_by(|&a, &b| self.compare_rows(a, b, sort));
+        }
+        self.view = Some(view);
+    }
+}
+
+#[cfg(test)]
+mod result_view_tests {
+    use super::*;
+
+    fn number(value: &str) -> CellValue {
+        CellValue::Number(value.to_string())
+    }
+
+    fn text(value: &str) -> CellValue {
+        CellValue::Text(value.to_string())
+    }
+
+    fn sample_rows() -> ResultRows {
+        let mut rows = ResultRows::default();
+        rows.set_rows(vec![
+            vec![number("10"), text("beta")],
+            vec![number("9"), text("Alpha")],
+            vec![CellValue::Null, text("gamma")],
+            vec![number("100"), text("alphabet")],
+        ]);
+        rows
+    }
+
+    fn first_column(rows: &ResultRows) -> Vec<String> {
+        rows.iter()
+            .map(|row| row[0].display("NULL").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn cycle_sort_orders_numbers_numerically_then_restores_fetch_order() {
+        let mut rows = sample_rows();
+
+        rows.cycle_sort(0);
+        assert_eq!(first_column(&rows), vec!["9", "10", "100", "NULL"]);
+
+        rows.cycle_sort(0);
+        assert_eq!(first_column(&rows), vec!["100", "10", "9", "NULL"]);
+
+        rows.cycle_sort(0);
+        assert_eq!(rows.sort(), None);
+        assert_eq!(first_column(&rows), vec!["10", "9", "NULL", "100"]);
+    }
+
+    #[test]
+    fn filters_hide_non_matching_rows_and_combine_with_sort() {
+        let mut rows = sample_rows();
+
+        rows.set_filter(1, "alpha");
+        assert_eq!(first_column(&rows), vec!["9", "100"]);
+        assert_eq!(rows.total_len(), 4);
+
+        rows.set_sort(Some((0, SortOrder::Descending)));
+        assert_eq!(first_column(&rows), vec!["100", "9"]);
+
+        rows.clear_filters();
+        assert_eq!(first_column(&rows), vec!["100", "10", "9", "NULL"]);
+    }
+
+    #[test]
+    fn comparison_filters_use_cell_types() {
+        let mut rows = sample_rows();
+
+        rows.set_filter(0, ">= 10");
+        assert_eq!(first_column(&rows), vec!["10", "100"]);
+
+        rows.set_filter(0, "NULL");
+        assert_eq!(first_column(&rows), vec!["NULL"]);
+
+        rows.set_filter(0, "!NULL");
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[test]
+    fn extend_merges_streamed_rows_into_active_sort_and_filter() {
+        let mut rows = sample_rows();
+        rows.set_sort(Some((0, SortOrder::Ascending)));
+        rows.set_filter(1, "a");
+
+        rows.extend(vec![
+            vec![number("50"), text("delta")],
+            vec![number("1"), text("zzz")],
+            vec![number("5"), text("omega")],
+        ]);
+
+        assert_eq!(
+            first_column(&rows),
+            vec!["5", "9", "10", "50", "100", "NULL"]
+        );
+    }
+
+    #[test]
+    fn date_filters_compare_chronologically() {
+        let mut rows = ResultRows::default();
+        rows.set_rows(vec![
+            vec![CellValue::Date("2024-01-09 00:00:00".to_string())],
+            vec![CellValue::Date("2024-01-10 00:00:00".to_string())],
+        ]);
+
+        rows.set_filter(0, "> 2024-01-09 12:00:00");
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows.source_index(0), Some(1));
+    }
+
+    #[test]
+    fn edits_become_row_changes_and_discard_restores_fetched_rows() {
+        let mut rows = ResultRows::default();
+        rows.extend_with_rowids(
+            vec![
+                vec![number("1"), text("a")],
+                vec![number("2"), text("b")],
+                vec![number("3"), text("c")],
+            ],
+            vec![
+                Some("AAA1".to_string()),
+                Some("AAA2".to_string()),
+                Some("AAA3".to_string()),
+            ],
+        );
+        rows.set_sort(Some((0, SortOrder::Descending)));
+
+        rows.set_cell(0, 1, text("changed"));
+        rows.toggle_deleted(2);
+        let inserted = rows.insert_row(2);
+        rows.set_cell(inserted, 0, number("4"));
+
+        assert_eq!(rows.row_edit_state(0), RowEditState::Modified);
+        assert!(rows.is_cell_modified(0, 1));
+        assert!(!rows.is_row_editable(2));
+        assert_eq!(
+            rows.pending_changes(),
+            vec![
+                RowChange::Delete {
+                    rowid: "AAA1".to_string()
+                },
+                RowChange::Update {
+                    rowid: "AAA3".to_string(),
+                    values: vec![(1, text("changed"))],
+                },
+                RowChange::Insert {
+                    values: vec![(0, number("4")), (1, CellValue::Null)],
+                },
+            ]
+        );
+
+        rows.discard_edits();
+        assert!(!rows.has_pending_edits());
+        assert_eq!(rows.total_len(), 3);
+        assert_eq!(rows.get(0).map(|row| row[1].clone()), Some(text("c")));
+    }
+
+    #[test]
+    fn accepting_edits_drops_deleted_rows() {
+        let mut rows = ResultRows::default();
+        rows.extend_with_rowids(
+            vec![vec![number("1")], vec![number("2")]],
+            vec![Some("AAA1".to_string()), Some("AAA2".to_string())],
+        );
+
+        rows.toggle_deleted(0);
+        rows.accept_edits();
+
+        assert_eq!(first_column(&rows), vec!["2"]);
+        assert!(rows.is_row_editable(0));
+        assert!(rows.pending_changes().is_empty());
+    }
+}
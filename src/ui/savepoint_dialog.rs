@@ -0,0 +1,250 @@
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    frame::Frame,
+    group::{Flex, FlexType},
+    input::Input,
+    prelude::*,
+    window::Window,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::db::{savepoint_identifier, try_lock_connection, SharedConnection, TransactionState};
+use crate::ui::constants::*;
+use crate::ui::schema_compare_dialog::{labeled, styled_button};
+use crate::ui::{center_on_main, configured_ui_font_size, theme, SqlEditorWidget};
+
+thread_local! {
+    static SAVEPOINTS_OPEN: Cell<bool> = const { Cell::new(false) };
+}
+
+enum SavepointAction {
+    Refresh,
+    Create(String),
+    RollbackTo(String),
+}
+
+fn status_text(state: &TransactionState) -> String {
+    if state.pending {
+        state.summary()
+    } else {
+        "No transaction pending".to_string()
+    }
+}
+
+/// Create savepoints in the session of `connection`, list the ones taken
+/// in the open transaction and roll back to them.
+pub fn show_savepoint_dialog(
+    editor: SqlEditorWidget,
+    connection: SharedConnection,
+    popups: Rc<RefCell<Vec<Window>>>,
+) {
+    #[derive(Clone, Copy)]
+    enum DialogMessage {
+        Create,
+        RollbackTo,
+        Refresh,
+        Close,
+    }
+
+    if SAVEPOINTS_OPEN.with(|open| open.replace(true)) {
+        return;
+    }
+
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 420;
+    let height = 360;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("Savepoints");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let mut create_row = Flex::default();
+    create_row.set_type(FlexType::Row);
+    create_row.set_spacing(DIALOG_SPACING);
+    labeled(&mut create_row, "Name:", 50);
+    let mut name_input = Input::default();
+    name_input.set_color(theme::input_bg());
+    name_input.set_text_color(theme::text_primary());
+    let mut create_btn = styled_button("Create", theme::button_primary());
+    create_row.fixed(&create_btn, BUTTON_WIDTH);
+    create_row.end();
+    main_flex.fixed(&create_row, INPUT_ROW_HEIGHT);
+
+    let mut browser = HoldBrowser::default();
+    browser.set_color(theme::input_bg());
+    browser.set_selection_color(theme::selection_strong());
+    browser.set_text_size(configured_ui_font_size());
+
+    let mut button_row = Flex::default();
+    button_row.set_type(FlexType::Row);
+    button_row.set_spacing(DIALOG_SPACING);
+    let mut rollback_btn = styled_button("Roll Back To", theme::button_warning());
+    button_row.fixed(&rollback_btn, BUTTON_WIDTH_LARGE);
+    let mut refresh_btn = styled_button("Refresh", theme::button_secondary());
+    button_row.fixed(&refresh_btn, BUTTON_WIDTH);
+    let _spacer = Frame::default();
+    let mut close_btn = styled_button("Close", theme::button_subtle());
+    button_row.fixed(&close_btn, BUTTON_WIDTH);
+    button_row.end();
+    main_flex.fixed(&button_row, BUTTON_ROW_HEIGHT);
+
+    let mut status = Frame::default();
+    status.set_label_color(theme::text_secondary());
+    status.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    main_flex.fixed(&status, LABEL_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    popups.borrow_mut().push(dialog.clone());
+
+    let (sender, receiver) = mpsc::channel::<DialogMessage>();
+    name_input.set_trigger(fltk::enums::CallbackTrigger::EnterKey);
+    name_input.set_callback({
+        let sender = sender.clone();
+        move |_| {
+            let _ = sender.send(DialogMessage::Create);
+            app::awake();
+        }
+    });
+    for (button, message) in [
+        (&mut create_btn, DialogMessage::Create),
+        (&mut rollback_btn, DialogMessage::RollbackTo),
+        (&mut refresh_btn, DialogMessage::Refresh),
+        (&mut close_btn, DialogMessage::Close),
+    ] {
+        let sender = sender.clone();
+        button.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+
+    dialog.show();
+
+    let (result_sender, result_receiver) = mpsc::channel::<Result<TransactionState, String>>();
+    let run = |action: SavepointAction| {
+        let connection = connection.clone();
+        let result_sender = result_sender.clone();
+        thread::spawn(move || {
+            let result = (|| {
+                let Some(mut guard) = try_lock_connection(&connection) else {
+                    return Err("The connection is busy with another query".to_string());
+                };
+                if !guard.is_connected() {
+                    return Err("Not connected to database".to_string());
+                }
+                match &action {
+                    SavepointAction::Refresh => {
+                        guard.confirm_transaction();
+                    }
+                    SavepointAction::Create(name) => guard
+                        .create_savepoint(name)
+                        .map_err(|err| err.to_string())?,
+                    SavepointAction::RollbackTo(name) => guard
+                        .rollback_to_savepoint(name)
+                        .map_err(|err| err.to_string())?,
+                }
+                Ok(guard.transaction().clone())
+            })();
+            let _ = result_sender.send(result);
+            app::awake();
+        });
+    };
+    run(SavepointAction::Refresh);
+
+    let mut savepoints: Vec<String> = Vec::new();
+    while dialog.shown() {
+        app::wait();
+        while let Ok(result) = result_receiver.try_recv() {
+            match result {
+                Ok(state) => {
+                    browser.clear();
+                    // Newest first, the order they are rolled back in.
+                    savepoints = state.savepoints.iter().rev().cloned().collect();
+                    for savepoint in &savepoints {
+                        browser.add(savepoint);
+                    }
+                    status.set_label(&status_text(&state));
+                    editor.notify_transaction_changed(state);
+                }
+                Err(err) => status.set_label(&err),
+            }
+        }
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                DialogMessage::Create => match savepoint_identifier(&name_input.value()) {
+                    Some(name) => {
+                        name_input.set_value("");
+                        run(SavepointAction::Create(name));
+                    }
+                    None => fltk::dialog::alert_default(
+                        "Savepoint names start with a letter and use letters, digits, _, $ or #",
+                    ),
+                },
+                DialogMessage::RollbackTo => {
+                    let selected = usize::try_from(browser.value() - 1)
+                        .ok()
+                        .and_then(|index| savepoints.get(index))
+                        .cloned();
+                    let Some(name) = selected else {
+                        fltk::dialog::alert_default("Select a savepoint");
+                        continue;
+                    };
+                    let choice = fltk::dialog::choice2_default(
+                        &format!(
+                            "Roll back every change made after savepoint {}?\n\
+                             Later savepoints are released.",
+                            name
+                        ),
+                        "Cancel",
+                        "Roll Back",
+                        "",
+                    );
+                    if choice == Some(1) {
+                        run(SavepointAction::RollbackTo(name));
+                    }
+                }
+                DialogMessage::Refresh => run(SavepointAction::Refresh),
+                DialogMessage::Close => dialog.hide(),
+            }
+        }
+    }
+
+    popups
+        .borrow_mut()
+        .retain(|w| w.as_widget_ptr() != dialog.as_widget_ptr());
+    SAVEPOINTS_OPEN.with(|open| open.set(false));
+}
+
+#[cfg(test)]
+mod savepoint_dialog_tests {
+    use super::*;
+
+    #[test]
+    fn status_names_the_savepoints_of_a_pending_transaction() {
+        let mut state = TransactionState::default();
+        assert_eq!(status_text(&state), "No transaction pending");
+        state.pending = true;
+        state.savepoints.push("BEFORE_LOAD".to_string());
+        assert_eq!(status_text(&state), "Transaction pending (1 savepoint)");
+    }
+}
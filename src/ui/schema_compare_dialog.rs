@@ -0,0 +1,508 @@
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    button::Button,
+    enums::{Color, FrameType},
+    frame::Frame,
+    group::{Flex, FlexType},
+    input::Input,
+    menu::Choice,
+    prelude::*,
+    text::{StyleTableEntry, TextBuffer, TextDisplay},
+    window::Window,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use oracle::Connection;
+
+use crate::db::{
+    compare_snapshots, diff_lines, lock_connection, side_by_side, synchronization_script,
+    ObjectDiff, SchemaSnapshot, SharedConnection,
+};
+use crate::ui::constants::*;
+use crate::ui::{center_on_main, configured_editor_profile, configured_ui_font_size, theme};
+
+/// A connection the compare dialog can read from.
+pub struct CompareEndpoint {
+    pub label: String,
+    pub connection: SharedConnection,
+}
+
+/// A synchronization script to open on the target connection.
+pub struct SyncScript {
+    pub script: String,
+    pub target: SharedConnection,
+}
+
+/// A compare dialog: takes the endpoints and the popup list, returns the
+/// script the user generated.
+pub type CompareDialog = fn(Vec<CompareEndpoint>, Rc<RefCell<Vec<Window>>>) -> Option<SyncScript>;
+
+/// Differences found, with the schema owners that were actually compared.
+struct CompareOutcome {
+    diffs: Vec<ObjectDiff>,
+    source_owner: String,
+    target_owner: String,
+}
+
+enum CompareUpdate {
+    Progress(String),
+    Done(Result<CompareOutcome, String>),
+}
+
+const STYLE_SAME: char = 'A';
+const STYLE_REMOVED: char = 'B';
+const STYLE_ADDED: char = 'C';
+
+fn diff_style_table() -> Vec<StyleTableEntry> {
+    let profile = configured_editor_profile();
+    let size = configured_ui_font_size();
+    [
+        theme::text_primary(),
+        theme::button_danger(),
+        theme::button_success(),
+    ]
+    .into_iter()
+    .map(|color| StyleTableEntry {
+        color,
+        font: profile.normal,
+        size,
+    })
+    .collect()
+}
+
+/// Text and style buffers of both sides of a side-by-side DDL diff. Lines
+/// without a counterpart are left blank on the other side.
+fn side_by_side_texts(source: &str, target: &str) -> [(String, String); 2] {
+    let mut sides: [(String, String); 2] = Default::default();
+    for (left, right) in side_by_side(&diff_lines(target, source)) {
+        let changed = left != right;
+        for (index, (line, style)) in [(right, STYLE_ADDED), (left, STYLE_REMOVED)]
+            .into_iter()
+            .enumerate()
+        {
+            let line = line.unwrap_or_default();
+            let style = if changed { style } else { STYLE_SAME };
+            let (text, styles) = &mut sides[index];
+            text.push_str(&line);
+            text.push('\n');
+            styles.extend(std::iter::repeat_n(style, line.len()));
+            styles.push(STYLE_SAME);
+        }
+    }
+    sides
+}
+
+/// The connection handle of an endpoint, for use on a worker thread.
+pub(crate) fn endpoint_connection(
+    connection: &SharedConnection,
+    side: &str,
+) -> Result<Arc<Connection>, String> {
+    // Hold the session only long enough to take its connection handle.
+    let conn = {
+        let guard = lock_connection(connection);
+        guard.get_connection()
+    };
+    conn.ok_or_else(|| format!("The {side} connection is not connected"))
+}
+
+fn load_snapshot(
+    connection: &SharedConnection,
+    schema: &str,
+    side: &str,
+    sender: &mpsc::Sender<CompareUpdate>,
+) -> Result<SchemaSnapshot, String> {
+    let conn = endpoint_connection(connection, side)?;
+    let owner = (!schema.is_empty()).then_some(schema);
+    let mut progress = |object_type: &str| {
+        let _ = sender.send(CompareUpdate::Progress(format!(
+            "Reading {side} {}s...",
+            object_type.to_lowercase()
+        )));
+        app::awake();
+    };
+    SchemaSnapshot::load(&conn, owner, &mut progress).map_err(|err| err.to_string())
+}
+
+pub(crate) fn labeled(parent: &mut Flex, text: &str, width: i32) {
+    let mut label = Frame::default().with_label(text);
+    label.set_label_color(theme::text_primary());
+    parent.fixed(&label, width);
+}
+
+pub(crate) fn endpoint_row(
+    parent: &mut Flex,
+    title: &str,
+    endpoints: &[CompareEndpoint],
+    selected: i32,
+) -> (Choice, Input) {
+    let mut row = Flex::default();
+    row.set_type(FlexType::Row);
+    row.set_spacing(DIALOG_SPACING);
+    labeled(&mut row, title, 60);
+    let mut choice = Choice::default();
+    choice.set_color(theme::input_bg());
+    choice.set_text_color(theme::text_primary());
+    for endpoint in endpoints {
+        choice.add_choice(&endpoint.label.replace('/', "\\/").replace('&', "&&"));
+    }
+    choice.set_value(selected);
+    labeled(&mut row, "Schema:", 60);
+    let mut schema = Input::default();
+    schema.set_color(theme::input_bg());
+    schema.set_text_color(theme::text_primary());
+    schema.set_tooltip("Leave empty for the connected user's schema");
+    row.fixed(&schema, 200);
+    row.end();
+    parent.fixed(&row, INPUT_ROW_HEIGHT);
+    (choice, schema)
+}
+
+pub(crate) fn styled_button(label: &str, color: Color) -> Button {
+    let mut button = Button::default().with_label(label);
+    button.set_color(color);
+    button.set_label_color(theme::text_primary());
+    button.set_frame(FrameType::RFlatBox);
+    button
+}
+
+fn ddl_display(style_buffer: &TextBuffer) -> (TextDisplay, TextBuffer) {
+    let buffer = TextBuffer::default();
+    let mut display = TextDisplay::default();
+    display.set_buffer(buffer.clone());
+    display.set_color(theme::editor_bg());
+    display.set_text_color(theme::text_primary());
+    display.set_text_font(configured_editor_profile().normal);
+    display.set_text_size(configured_ui_font_size());
+    display.set_highlight_data(style_buffer.clone(), diff_style_table());
+    (display, buffer)
+}
+
+/// Compare two schemas and show what differs. Returns the synchronization
+/// script when the user asks for one.
+pub fn show_schema_compare_dialog(
+    endpoints: Vec<CompareEndpoint>,
+    popups: Rc<RefCell<Vec<Window>>>,
+) -> Option<SyncScript> {
+    #[derive(Clone, Copy)]
+    enum DialogMessage {
+        Compare,
+        Select,
+        Script,
+        Close,
+    }
+
+    if endpoints.is_empty() {
+        fltk::dialog::alert_default("Connect at least one query tab to compare schemas");
+        return None;
+    }
+
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 1100;
+    let height = 680;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("Schema Compare");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+    dialog.make_modal(true);
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let (source_choice, source_schema) = endpoint_row(&mut main_flex, "Source:", &endpoints, 0);
+    let target_default = if endpoints.len() > 1 { 1 } else { 0 };
+    let (target_choice, target_schema) =
+        endpoint_row(&mut main_flex, "Target:", &endpoints, target_default);
+
+    let mut status_row = Flex::default();
+    status_row.set_type(FlexType::Row);
+    status_row.set_spacing(DIALOG_SPACING);
+    let mut status = Frame::default().with_label("Choose the source and target to compare");
+    status.set_label_color(theme::text_secondary());
+    status.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    let mut compare_btn = styled_button("Compare", theme::button_primary());
+    status_row.fixed(&compare_btn, BUTTON_WIDTH);
+    status_row.end();
+    main_flex.fixed(&status_row, BUTTON_ROW_HEIGHT);
+
+    let mut content = Flex::default();
+    content.set_type(FlexType::Row);
+    content.set_spacing(DIALOG_SPACING);
+
+    let mut browser = HoldBrowser::default();
+    browser.set_color(theme::input_bg());
+    browser.set_selection_color(theme::selection_strong());
+    browser.set_text_size(configured_ui_font_size());
+    browser.set_column_char('\t');
+    browser.set_column_widths(&[80, 90]);
+    content.fixed(&browser, 340);
+
+    let mut detail_flex = Flex::default();
+    detail_flex.set_type(FlexType::Column);
+    detail_flex.set_spacing(DIALOG_SPACING);
+    let changes_buffer = TextBuffer::default();
+    let mut changes_display = TextDisplay::default();
+    changes_display.set_buffer(changes_buffer.clone());
+    changes_display.set_color(theme::panel_alt());
+    changes_display.set_text_color(theme::text_primary());
+    changes_display.set_text_size(configured_ui_font_size());
+    detail_flex.fixed(&changes_display, 100);
+    let mut header_row = Flex::default();
+    header_row.set_type(FlexType::Row);
+    let mut source_header = Frame::default().with_label("Source DDL");
+    source_header.set_label_color(theme::text_primary());
+    let mut target_header = Frame::default().with_label("Target DDL");
+    target_header.set_label_color(theme::text_primary());
+    header_row.end();
+    detail_flex.fixed(&header_row, LABEL_ROW_HEIGHT);
+    let mut ddl_row = Flex::default();
+    ddl_row.set_type(FlexType::Row);
+    ddl_row.set_spacing(DIALOG_SPACING);
+    let source_styles = TextBuffer::default();
+    let target_styles = TextBuffer::default();
+    let (_source_display, source_buffer) = ddl_display(&source_styles);
+    let (_target_display, target_buffer) = ddl_display(&target_styles);
+    ddl_row.end();
+    detail_flex.end();
+    content.end();
+
+    let mut button_flex = Flex::default();
+    button_flex.set_type(FlexType::Row);
+    button_flex.set_spacing(DIALOG_SPACING);
+    let _spacer = Frame::default();
+    let mut script_btn = styled_button("Generate Script", theme::button_secondary());
+    let mut close_btn = styled_button("Close", theme::button_subtle());
+    button_flex.fixed(&script_btn, BUTTON_WIDTH_LARGE);
+    button_flex.fixed(&close_btn, BUTTON_WIDTH);
+    button_flex.end();
+    main_flex.fixed(&button_flex, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    popups.borrow_mut().push(dialog.clone());
+
+    let (sender, receiver) = mpsc::channel::<DialogMessage>();
+    for (button, message) in [
+        (&mut compare_btn, DialogMessage::Compare),
+        (&mut script_btn, DialogMessage::Script),
+        (&mut close_btn, DialogMessage::Close),
+    ] {
+        let sender = sender.clone();
+        button.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+    let sender_for_browser = sender.clone();
+    browser.set_callback(move |_| {
+        let _ = sender_for_browser.send(DialogMessage::Select);
+        app::awake();
+    });
+
+    dialog.show();
+
+    let (update_sender, update_receiver) = mpsc::channel::<CompareUpdate>();
+    let mut diffs: Vec<ObjectDiff> = Vec::new();
+    let mut compared: Option<(String, String, SharedConnection)> = None;
+    let mut running = false;
+    let mut result: Option<SyncScript> = None;
+
+    while dialog.shown() {
+        app::wait();
+        while let Ok(update) = update_receiver.try_recv() {
+            match update {
+                CompareUpdate::Progress(text) => status.set_label(&text),
+                CompareUpdate::Done(outcome) => {
+                    running = false;
+                    compare_btn.activate();
+                    browser.clear();
+                    match outcome {
+                        Ok(outcome) => {
+                            diffs = outcome.diffs;
+                            if let Some((source_label, target_label, _)) = compared.as_mut() {
+                                source_label.push_str(&format!(" ({})", outcome.source_owner));
+                                target_label.push_str(&format!(" ({})", outcome.target_owner));
+                            }
+                            for diff in &diffs {
+                                browser.add(&format!(
+                                    "@.{}\t@.{}\t@.{}",
+                                    diff.kind.label(),
+                                    diff.object_type,
+                                    diff.name
+                                ));
+                            }
+                            status.set_label(&if diffs.is_empty() {
+                                "The schemas match".to_string()
+                            } else {
+                                format!("{} objects differ", diffs.len())
+                            });
+                        }
+                        Err(err) => {
+                            diffs.clear();
+                            compared = None;
+                            status.set_label("Compare failed");
+                            fltk::dialog::alert_default(&format!("Schema compare failed: {}", err));
+                        }
+                    }
+                }
+            }
+        }
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                DialogMessage::Compare => {
+                    if running {
+                        continue;
+                    }
+                    let source_index = source_choice.value();
+                    let target_index = target_choice.value();
+                    let source_owner = source_schema.value().trim().to_uppercase();
+                    let target_owner = target_schema.value().trim().to_uppercase();
+                    let (Some(source), Some(target)) = (
+                        usize::try_from(source_index)
+                            .ok()
+                            .and_then(|index| endpoints.get(index)),
+                        usize::try_from(target_index)
+                            .ok()
+                            .and_then(|index| endpoints.get(index)),
+                    ) else {
+                        continue;
+                    };
+                    if source_index == target_index && source_owner == target_owner {
+                        fltk::dialog::alert_default(
+                            "Choose a different target connection or schema",
+                        );
+                        continue;
+                    }
+                    compared = Some((
+                        source.label.clone(),
+                        target.label.clone(),
+                        target.connection.clone(),
+                    ));
+                    running = true;
+                    compare_btn.deactivate();
+                    browser.clear();
+                    diffs.clear();
+                    status.set_label("Comparing...");
+                    let source_connection = source.connection.clone();
+                    let target_connection = target.connection.clone();
+                    let update_sender = update_sender.clone();
+                    thread::spawn(move || {
+                        let outcome = load_snapshot(
+                            &source_connection,
+                            &source_owner,
+                            "source",
+                            &update_sender,
+                        )
+                        .and_then(|source| {
+                            let target = load_snapshot(
+                                &target_connection,
+                                &target_owner,
+                                "target",
+                                &update_sender,
+                            )?;
+                            Ok(CompareOutcome {
+                                diffs: compare_snapshots(&source, &target),
+                                source_owner: source.owner,
+                                target_owner: target.owner,
+                            })
+                        });
+                        let _ = update_sender.send(CompareUpdate::Done(outcome));
+                        app::awake();
+                    });
+                }
+                DialogMessage::Select => {
+                    let Some(diff) = usize::try_from(browser.value() - 1)
+                        .ok()
+                        .and_then(|index| diffs.get(index))
+                    else {
+                        continue;
+                    };
+                    let changes = if diff.table_changes.is_empty() {
+                        match diff.kind {
+                            crate::db::DiffKind::MissingInTarget => {
+                                "Exists only in the source".to_string()
+                            }
+                            crate::db::DiffKind::ExtraInTarget => {
+                                "Exists only in the target".to_string()
+                            }
+                            crate::db::DiffKind::Different => "The DDL differs".to_string(),
+                        }
+                    } else {
+                        diff.table_changes
+                            .iter()
+                            .map(|change| change.describe())
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    };
+                    changes_buffer.clone().set_text(&changes);
+                    let [(source_text, source_style), (target_text, target_style)] =
+                        side_by_side_texts(
+                            diff.source_ddl.as_deref().unwrap_or_default(),
+                            diff.target_ddl.as_deref().unwrap_or_default(),
+                        );
+                    source_buffer.clone().set_text(&source_text);
+                    source_styles.clone().set_text(&source_style);
+                    target_buffer.clone().set_text(&target_text);
+                    target_styles.clone().set_text(&target_style);
+                }
+                DialogMessage::Script => {
+                    let Some((source_label, target_label, target)) = &compared else {
+                        fltk::dialog::alert_default("Compare the schemas first");
+                        continue;
+                    };
+                    if running {
+                        continue;
+                    }
+                    if diffs.is_empty() {
+                        fltk::dialog::message_default("The schemas match; there is nothing to do");
+                        continue;
+                    }
+                    result = Some(SyncScript {
+                        script: synchronization_script(&diffs, source_label, target_label),
+                        target: target.clone(),
+                    });
+                    dialog.hide();
+                }
+                DialogMessage::Close => dialog.hide(),
+            }
+        }
+    }
+
+    popups
+        .borrow_mut()
+        .retain(|w| w.as_widget_ptr() != dialog.as_widget_ptr());
+
+    result
+}
+
+#[cfg(test)]
+mod schema_compare_dialog_tests {
+    use super::*;
+
+    #[test]
+    fn side_by_side_styles_cover_every_byte() {
+        let [(source, source_style), (target, target_style)] =
+            side_by_side_texts("create x\n  col é\n", "create x\n");
+        assert_eq!(source, "create x\n  col é\n");
+        assert_eq!(target, "create x\n\n");
+        assert_eq!(source.len(), source_style.len());
+        assert_eq!(target.len(), target_style.len());
+        assert!(source_style.ends_with("ACCCCCCCCA"));
+        assert!(target_style.ends_with("AA"));
+    }
+}
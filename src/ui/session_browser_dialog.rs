@@ -0,0 +1,605 @@
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    button::CheckButton,
+    enums::{CallbackTrigger, Color},
+    frame::Frame,
+    group::{Flex, FlexType},
+    input::{Input, IntInput},
+    prelude::*,
+    text::{TextBuffer, TextDisplay},
+    window::Window,
+};
+use oracle::Connection;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::db::{
+    blocked_sessions, blocking_chain, blocking_roles, format_elapsed, kill_session, list_sessions,
+    lock_mode_name, session_detail, try_lock_connection, BlockRole, SessionDetail, SessionFilter,
+    SessionInfo, SessionLock, SharedConnection,
+};
+use crate::ui::constants::*;
+use crate::ui::schema_compare_dialog::{labeled, styled_button};
+use crate::ui::{center_on_main, configured_editor_profile, configured_ui_font_size, theme};
+
+thread_local! {
+    static SESSION_BROWSER_OPEN: Cell<bool> = const { Cell::new(false) };
+}
+
+const DEFAULT_REFRESH_SECONDS: u64 = 5;
+const SESSION_COLUMN_WIDTHS: &[i32] = &[60, 70, 110, 80, 140, 150, 200, 80, 80];
+
+enum WorkerResult {
+    Sessions(Result<Vec<SessionInfo>, String>),
+    Detail(i64, Result<SessionDetail, String>),
+    Killed(String, Result<(), String>),
+}
+
+/// Run `work` on the session's connection, if it is free.
+fn with_connection<T>(
+    connection: &SharedConnection,
+    work: impl FnOnce(&Connection) -> Result<T, String>,
+) -> Result<T, String> {
+    let Some(guard) = try_lock_connection(connection) else {
+        return Err("The connection is busy with another query".to_string());
+    };
+    let Some(conn) = guard.get_connection() else {
+        return Err("Not connected to database".to_string());
+    };
+    work(conn.as_ref())
+}
+
+/// Browser columns with a color applied to each one.
+fn colored_line(columns: &[String], color: Option<Color>) -> String {
+    let prefix = match color {
+        Some(color) => format!("@C{}@.", color.bits()),
+        None => "@.".to_string(),
+    };
+    columns
+        .iter()
+        .map(|column| format!("{}{}", prefix, column.replace(['\t', '\n', '\r'], " ")))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+fn role_color(role: Option<BlockRole>) -> Option<Color> {
+    match role {
+        Some(BlockRole::Blocker) => Some(theme::button_danger()),
+        Some(BlockRole::Waiter) => Some(theme::button_warning()),
+        None => None,
+    }
+}
+
+fn session_line(session: &SessionInfo, role: Option<BlockRole>) -> String {
+    let event = if session.wait_class.is_empty() || session.wait_class == "Idle" {
+        session.event.clone()
+    } else {
+        format!("{} ({})", session.event, session.wait_class)
+    };
+    let columns = [
+        session.sid.to_string(),
+        session.serial.to_string(),
+        session.username.clone(),
+        session.status.clone(),
+        session.machine.clone(),
+        session.program.clone(),
+        event,
+        format_elapsed(session.elapsed_seconds),
+        session
+            .blocking_session
+            .map(|sid| sid.to_string())
+            .unwrap_or_default(),
+        session.sql_text.clone(),
+    ];
+    colored_line(&columns, role_color(role))
+}
+
+fn lock_line(lock: &SessionLock) -> String {
+    let target = lock
+        .object_name
+        .clone()
+        .unwrap_or_else(|| format!("{} / {}", lock.id1, lock.id2));
+    let color = if lock.blocking {
+        Some(theme::button_danger())
+    } else if lock.mode_requested > 0 {
+        Some(theme::button_warning())
+    } else {
+        None
+    };
+    let columns = [
+        lock.lock_type.clone(),
+        target,
+        lock_mode_name(lock.mode_held).to_string(),
+        lock_mode_name(lock.mode_requested).to_string(),
+        format_elapsed(lock.seconds),
+        if lock.blocking { "Blocking" } else { "" }.to_string(),
+    ];
+    colored_line(&columns, color)
+}
+
+/// How `sid` takes part in blocking chains, for the detail header.
+fn chain_summary(sessions: &[SessionInfo], sid: i64) -> String {
+    let chain = blocking_chain(sessions, sid);
+    let blocked = blocked_sessions(sessions, sid);
+    let mut parts = Vec::new();
+    if chain.len() > 1 {
+        let path = chain
+            .iter()
+            .map(|sid| sid.to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        parts.push(format!("Waiting: {}", path));
+    }
+    if !blocked.is_empty() {
+        let sids = blocked
+            .iter()
+            .map(|sid| sid.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        parts.push(format!("Blocking: {}", sids));
+    }
+    if parts.is_empty() {
+        format!("SID {}: not blocked, not blocking", sid)
+    } else {
+        format!("SID {}: {}", sid, parts.join("   "))
+    }
+}
+
+fn detail_text(detail: &SessionDetail) -> String {
+    let mut text = if detail.sql_text.trim().is_empty() {
+        "-- No current SQL".to_string()
+    } else {
+        detail.sql_text.trim_end().to_string()
+    };
+    if !detail.plan.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&detail.plan.join("\n"));
+    }
+    text.push('\n');
+    text
+}
+
+fn filter_check(row: &mut Flex, label: &str, width: i32, checked: bool) -> CheckButton {
+    let mut check = CheckButton::default().with_label(label);
+    check.set_label_color(theme::text_primary());
+    check.set_value(checked);
+    row.fixed(&check, width);
+    check
+}
+
+fn list_browser(column_widths: &[i32]) -> HoldBrowser {
+    let mut browser = HoldBrowser::default();
+    browser.set_color(theme::input_bg());
+    browser.set_selection_color(theme::selection_strong());
+    browser.set_text_size(configured_ui_font_size());
+    browser.set_column_char('\t');
+    browser.set_column_widths(column_widths);
+    browser
+}
+
+/// Browse the instance's sessions, follow blocking chains and kill
+/// sessions, using the query tab's connection between its queries.
+pub fn show_session_browser_dialog(connection: SharedConnection, popups: Rc<RefCell<Vec<Window>>>) {
+    #[derive(Clone, Copy)]
+    enum DialogMessage {
+        Refilter,
+        Refresh,
+        Select,
+        Kill,
+        Close,
+    }
+
+    if SESSION_BROWSER_OPEN.with(|open| open.replace(true)) {
+        return;
+    }
+
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 1100;
+    let height = 720;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("Session Browser");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let mut filter_row = Flex::default();
+    filter_row.set_type(FlexType::Row);
+    filter_row.set_spacing(DIALOG_SPACING);
+    labeled(&mut filter_row, "Filter:", 50);
+    let mut filter_input = Input::default();
+    filter_input.set_color(theme::input_bg());
+    filter_input.set_text_color(theme::text_primary());
+    let mut active_check = filter_check(&mut filter_row, "Active only", 100, false);
+    let mut background_check = filter_check(&mut filter_row, "Hide background", 135, true);
+    let mut blocking_check = filter_check(&mut filter_row, "Blocking only", 115, false);
+    let auto_check = filter_check(&mut filter_row, "Auto-refresh every", 150, false);
+    let mut interval_input = IntInput::default();
+    interval_input.set_color(theme::input_bg());
+    interval_input.set_text_color(theme::text_primary());
+    interval_input.set_value(&DEFAULT_REFRESH_SECONDS.to_string());
+    filter_row.fixed(&interval_input, 45);
+    labeled(&mut filter_row, "s", 15);
+    let mut refresh_btn = styled_button("Refresh", theme::button_secondary());
+    filter_row.fixed(&refresh_btn, BUTTON_WIDTH);
+    filter_row.end();
+    main_flex.fixed(&filter_row, INPUT_ROW_HEIGHT);
+
+    // The first line is the column header.
+    let mut session_browser = list_browser(SESSION_COLUMN_WIDTHS);
+    session_browser.add(&colored_line(
+        &[
+            "SID",
+            "Serial#",
+            "User",
+            "Status",
+            "Machine",
+            "Program",
+            "Event",
+            "Elapsed",
+            "Blocked by",
+            "SQL",
+        ]
+        .map(str::to_string),
+        Some(theme::text_secondary()),
+    ));
+
+    let mut chain_label = Frame::default().with_label("Select a session");
+    chain_label.set_label_color(theme::text_primary());
+    chain_label.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    main_flex.fixed(&chain_label, LABEL_ROW_HEIGHT);
+
+    let mut detail_row = Flex::default();
+    detail_row.set_type(FlexType::Row);
+    detail_row.set_spacing(DIALOG_SPACING);
+    let detail_buffer = TextBuffer::default();
+    let mut detail_display = TextDisplay::default();
+    detail_display.set_buffer(detail_buffer.clone());
+    detail_display.set_color(theme::editor_bg());
+    detail_display.set_text_color(theme::text_primary());
+    detail_display.set_text_font(configured_editor_profile().normal);
+    detail_display.set_text_size(configured_ui_font_size());
+    let mut side_column = Flex::default();
+    side_column.set_type(FlexType::Column);
+    side_column.set_spacing(DIALOG_SPACING);
+    let mut cursors_label = Frame::default().with_label("Open cursors");
+    cursors_label.set_label_color(theme::text_secondary());
+    cursors_label.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    side_column.fixed(&cursors_label, LABEL_ROW_HEIGHT);
+    let mut cursors_browser = list_browser(&[110]);
+    let mut locks_label = Frame::default().with_label("Locks (type, object, held, requested)");
+    locks_label.set_label_color(theme::text_secondary());
+    locks_label.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    side_column.fixed(&locks_label, LABEL_ROW_HEIGHT);
+    let mut locks_browser = list_browser(&[40, 150, 90, 90, 65]);
+    side_column.end();
+    detail_row.fixed(&side_column, 440);
+    detail_row.end();
+    main_flex.fixed(&detail_row, 260);
+
+    let mut button_row = Flex::default();
+    button_row.set_type(FlexType::Row);
+    button_row.set_spacing(DIALOG_SPACING);
+    let mut status = Frame::default();
+    status.set_label_color(theme::text_secondary());
+    status.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    let mut kill_btn = styled_button("Kill Session", theme::button_danger());
+    button_row.fixed(&kill_btn, BUTTON_WIDTH_LARGE);
+    let mut close_btn = styled_button("Close", theme::button_subtle());
+    button_row.fixed(&close_btn, BUTTON_WIDTH);
+    button_row.end();
+    main_flex.fixed(&button_row, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    popups.borrow_mut().push(dialog.clone());
+
+    let (sender, receiver) = mpsc::channel::<DialogMessage>();
+    fn send_on<W: WidgetExt>(
+        widget: &mut W,
+        sender: &mpsc::Sender<DialogMessage>,
+        message: DialogMessage,
+    ) {
+        let sender = sender.clone();
+        widget.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+    filter_input.set_trigger(CallbackTrigger::Changed);
+    send_on(&mut filter_input, &sender, DialogMessage::Refilter);
+    for check in [
+        &mut active_check,
+        &mut background_check,
+        &mut blocking_check,
+    ] {
+        send_on(check, &sender, DialogMessage::Refilter);
+    }
+    send_on(&mut session_browser, &sender, DialogMessage::Select);
+    for (button, message) in [
+        (&mut refresh_btn, DialogMessage::Refresh),
+        (&mut kill_btn, DialogMessage::Kill),
+        (&mut close_btn, DialogMessage::Close),
+    ] {
+        send_on(button, &sender, message);
+    }
+
+    dialog.show();
+    let _ = filter_input.take_focus();
+
+    let (worker_sender, worker_receiver) = mpsc::channel::<WorkerResult>();
+    let start_worker = |work: Box<dyn FnOnce(&SharedConnection) -> WorkerResult + Send>| {
+        let connection = connection.clone();
+        let worker_sender = worker_sender.clone();
+        thread::spawn(move || {
+            let _ = worker_sender.send(work(&connection));
+            app::awake();
+        });
+    };
+
+    let mut sessions: Vec<SessionInfo> = Vec::new();
+    let mut roles: HashMap<i64, BlockRole> = HashMap::new();
+    // Browser line (after the header) to index in `sessions`.
+    let mut visible: Vec<usize> = Vec::new();
+    let mut selected_sid: Option<i64> = None;
+    let mut busy = false;
+    let mut pending_detail = false;
+    let mut last_refresh = Instant::now();
+    let _ = sender.send(DialogMessage::Refresh);
+
+    while dialog.shown() {
+        let _ = app::wait_for(0.5);
+        let interval = interval_input
+            .value()
+            .trim()
+            .parse::<u64>()
+            .unwrap_or(DEFAULT_REFRESH_SECONDS)
+            .max(1);
+        if auto_check.value() && !busy && last_refresh.elapsed() >= Duration::from_secs(interval) {
+            let _ = sender.send(DialogMessage::Refresh);
+        }
+
+        let mut relist = false;
+        while let Ok(result) = worker_receiver.try_recv() {
+            busy = false;
+            match result {
+                WorkerResult::Sessions(Ok(list)) => {
+                    roles = blocking_roles(&list);
+                    sessions = list;
+                    let blockers = roles
+                        .values()
+                        .filter(|role| **role == BlockRole::Blocker)
+                        .count();
+                    status.set_label(&format!(
+                        "{} sessions, {} blocking, refreshed {}",
+                        sessions.len(),
+                        blockers,
+                        chrono::Local::now().format("%H:%M:%S")
+                    ));
+                    relist = true;
+                    pending_detail = selected_sid.is_some();
+                }
+                WorkerResult::Sessions(Err(err)) => {
+                    status.set_label(&format!("Refresh failed: {}", err));
+                }
+                WorkerResult::Detail(sid, result) => {
+                    if selected_sid != Some(sid) {
+                        continue;
+                    }
+                    cursors_browser.clear();
+                    locks_browser.clear();
+                    match result {
+                        Ok(detail) => {
+                            detail_buffer.clone().set_text(&detail_text(&detail));
+                            for (sql_id, sql_text) in &detail.open_cursors {
+                                cursors_browser
+                                    .add(&colored_line(&[sql_id.clone(), sql_text.clone()], None));
+                            }
+                            for lock in &detail.locks {
+                                locks_browser.add(&lock_line(lock));
+                            }
+                        }
+                        Err(err) => {
+                            detail_buffer
+                                .clone()
+                                .set_text(&format!("-- Failed to load session details: {}", err));
+                        }
+                    }
+                }
+                WorkerResult::Killed(label, result) => match result {
+                    Ok(()) => {
+                        status.set_label(&format!("Killed session {}", label));
+                        let _ = sender.send(DialogMessage::Refresh);
+                    }
+                    Err(err) => {
+                        status.set_label("Kill session failed");
+                        fltk::dialog::alert_default(&format!(
+                            "Failed to kill session {}: {}",
+                            label, err
+                        ));
+                    }
+                },
+            }
+        }
+
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                DialogMessage::Refilter => relist = true,
+                DialogMessage::Refresh => {
+                    last_refresh = Instant::now();
+                    if busy {
+                        continue;
+                    }
+                    busy = true;
+                    status.set_label("Refreshing...");
+                    start_worker(Box::new(|connection| {
+                        WorkerResult::Sessions(with_connection(connection, list_sessions))
+                    }));
+                }
+                DialogMessage::Select => {
+                    let selected = usize::try_from(session_browser.value() - 2)
+                        .ok()
+                        .and_then(|line| visible.get(line))
+                        .and_then(|&index| sessions.get(index));
+                    selected_sid = selected.map(|session| session.sid);
+                    detail_buffer.clone().set_text("");
+                    cursors_browser.clear();
+                    locks_browser.clear();
+                    match selected_sid {
+                        Some(sid) => {
+                            chain_label.set_label(&chain_summary(&sessions, sid));
+                            pending_detail = true;
+                        }
+                        None => chain_label.set_label("Select a session"),
+                    }
+                }
+                DialogMessage::Kill => {
+                    let Some(session) = selected_sid
+                        .and_then(|sid| sessions.iter().find(|session| session.sid == sid))
+                        .cloned()
+                    else {
+                        fltk::dialog::alert_default("Select the session to kill");
+                        continue;
+                    };
+                    let label = format!("{},{}", session.sid, session.serial);
+                    let choice = fltk::dialog::choice2_default(
+                        &format!(
+                            "Kill session {} ({} on {}, {})?\n\
+                             Its open transaction is rolled back.",
+                            label, session.username, session.machine, session.program
+                        ),
+                        "Cancel",
+                        "Kill",
+                        "Kill Immediate",
+                    );
+                    let immediate = match choice {
+                        Some(1) => false,
+                        Some(2) => true,
+                        _ => continue,
+                    };
+                    if busy {
+                        fltk::dialog::alert_default("Wait for the refresh to finish");
+                        continue;
+                    }
+                    busy = true;
+                    status.set_label(&format!("Killing session {}...", label));
+                    start_worker(Box::new(move |connection| {
+                        let result = with_connection(connection, |conn| {
+                            kill_session(conn, session.sid, session.serial, immediate)
+                        });
+                        WorkerResult::Killed(label, result)
+                    }));
+                }
+                DialogMessage::Close => dialog.hide(),
+            }
+        }
+
+        if relist {
+            let filter = SessionFilter {
+                text: filter_input.value(),
+                active_only: active_check.value(),
+                hide_background: background_check.value(),
+                blocking_only: blocking_check.value(),
+            };
+            let scroll = session_browser.position();
+            while session_browser.size() > 1 {
+                session_browser.remove(2);
+            }
+            visible.clear();
+            let mut selected_line = None;
+            for (index, session) in sessions.iter().enumerate() {
+                if !filter.matches(session, &roles) {
+                    continue;
+                }
+                visible.push(index);
+                session_browser.add(&session_line(session, roles.get(&session.sid).copied()));
+                if selected_sid == Some(session.sid) {
+                    selected_line = Some(visible.len() as i32 + 1);
+                }
+            }
+            if let Some(line) = selected_line {
+                session_browser.select(line);
+            }
+            session_browser.set_position(scroll);
+            if let Some(sid) = selected_sid {
+                chain_label.set_label(&chain_summary(&sessions, sid));
+            }
+        }
+
+        if pending_detail && !busy {
+            pending_detail = false;
+            if let Some(session) = selected_sid
+                .and_then(|sid| sessions.iter().find(|session| session.sid == sid))
+                .cloned()
+            {
+                busy = true;
+                start_worker(Box::new(move |connection| {
+                    let result = with_connection(connection, |conn| session_detail(conn, &session));
+                    WorkerResult::Detail(session.sid, result)
+                }));
+            }
+        }
+    }
+
+    popups
+        .borrow_mut()
+        .retain(|w| w.as_widget_ptr() != dialog.as_widget_ptr());
+    SESSION_BROWSER_OPEN.with(|open| open.set(false));
+}
+
+#[cfg(test)]
+mod session_browser_dialog_tests {
+    use super::*;
+
+    fn session(sid: i64, blocking_session: Option<i64>) -> SessionInfo {
+        SessionInfo {
+            sid,
+            blocking_session,
+            ..SessionInfo::default()
+        }
+    }
+
+    #[test]
+    fn chain_summary_describes_both_directions() {
+        let sessions = vec![
+            session(10, None),
+            session(20, Some(10)),
+            session(30, Some(20)),
+        ];
+        assert_eq!(chain_summary(&sessions, 10), "SID 10: Blocking: 20, 30");
+        assert_eq!(
+            chain_summary(&sessions, 20),
+            "SID 20: Waiting: 20 -> 10   Blocking: 30"
+        );
+        assert_eq!(
+            chain_summary(&[session(5, None)], 5),
+            "SID 5: not blocked, not blocking"
+        );
+    }
+
+    #[test]
+    fn columns_are_escaped_and_colored_alike() {
+        assert_eq!(
+            colored_line(&["a\tb".to_string(), "@c".to_string()], None),
+            "@.a b\t@.@c"
+        );
+    }
+}
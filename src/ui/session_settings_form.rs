@@ -0,0 +1,232 @@
+use fltk::{
+    app,
+    button::Button,
+    dialog::{FileDialog, FileDialogType},
+    enums::{Align, FrameType},
+    frame::Frame,
+    group::{Flex, FlexType},
+    input::Input,
+    prelude::*,
+    window::Window,
+};
+
+use crate::db::{SessionSettings, CELL_DATE_MASK};
+use crate::ui::center_on_main;
+use crate::ui::constants::*;
+use crate::ui::theme;
+
+const SESSION_LABEL_WIDTH: i32 = 130;
+
+/// Inputs for [`SessionSettings`], shared by the Session tab of the
+/// settings dialog and the session overrides of a connection profile.
+#[derive(Clone)]
+pub struct SessionSettingsForm {
+    date_format: Input,
+    timestamp_format: Input,
+    numeric_characters: Input,
+    time_zone: Input,
+    current_schema: Input,
+    module: Input,
+    action: Input,
+    login_script: Input,
+}
+
+fn settings_row(column: &mut Flex, label: &str, tooltip: &str) -> (Flex, Input) {
+    let mut row = Flex::default();
+    row.set_type(FlexType::Row);
+    row.set_spacing(DIALOG_SPACING);
+    let mut row_label = Frame::default().with_label(label);
+    row_label.set_label_color(theme::text_primary());
+    row.fixed(&row_label, SESSION_LABEL_WIDTH);
+    let mut input = Input::default();
+    input.set_color(theme::input_bg());
+    input.set_text_color(theme::text_primary());
+    input.set_tooltip(tooltip);
+    column.fixed(&row, INPUT_ROW_HEIGHT);
+    (row, input)
+}
+
+impl SessionSettingsForm {
+    /// Add one row per setting to `column`, which must be the current group.
+    pub fn build(column: &mut Flex) -> Self {
+        fn simple_row(column: &mut Flex, label: &str, tooltip: &str) -> Input {
+            let (row, input) = settings_row(column, label, tooltip);
+            row.end();
+            input
+        }
+        let date_format = simple_row(
+            column,
+            "Date format:",
+            "NLS_DATE_FORMAT for TO_CHAR and implicit conversions, e.g. yyyy-mm-dd hh24:mi:ss",
+        );
+        let timestamp_format = simple_row(
+            column,
+            "Timestamp format:",
+            "NLS_TIMESTAMP_FORMAT for TO_CHAR and implicit conversions, e.g. yyyy-mm-dd hh24:mi:ss.ff3",
+        );
+        // Fetched DATE and TIMESTAMP values are formatted by the client.
+        let mut grid_note = Frame::default().with_label(&format!(
+            "Result grids show dates as {} whatever these formats say.",
+            CELL_DATE_MASK
+        ));
+        grid_note.set_label_color(theme::text_secondary());
+        grid_note.set_align(Align::Left | Align::Inside);
+        column.fixed(&grid_note, LABEL_ROW_HEIGHT);
+        let numeric_characters = simple_row(
+            column,
+            "Numeric characters:",
+            "NLS_NUMERIC_CHARACTERS: decimal then group separator, e.g. .,",
+        );
+        let time_zone = simple_row(
+            column,
+            "Time zone:",
+            "TIME_ZONE, e.g. +09:00, Europe/Berlin or LOCAL",
+        );
+        let current_schema = simple_row(
+            column,
+            "Current schema:",
+            "Schema that unqualified names resolve to",
+        );
+        let module = simple_row(column, "Module:", "DBMS_APPLICATION_INFO module name");
+        let action = simple_row(column, "Action:", "DBMS_APPLICATION_INFO action name");
+
+        let (mut script_row, login_script) = settings_row(
+            column,
+            "Login script:",
+            "Script run after connecting, like SQL*Plus login.sql",
+        );
+        let mut browse_btn = Button::default().with_label("Browse...");
+        browse_btn.set_color(theme::button_secondary());
+        browse_btn.set_label_color(theme::text_primary());
+        browse_btn.set_frame(FrameType::RFlatBox);
+        script_row.fixed(&browse_btn, BUTTON_WIDTH);
+        script_row.end();
+        let mut script_input = login_script.clone();
+        browse_btn.set_callback(move |_| {
+            let mut dialog = FileDialog::new(FileDialogType::BrowseFile);
+            dialog.set_filter("SQL Files\t*.sql\nAll Files\t*.*");
+            dialog.show();
+            let filename = dialog.filename();
+            if !filename.as_os_str().is_empty() {
+                script_input.set_value(&filename.to_string_lossy());
+            }
+        });
+
+        Self {
+            date_format,
+            timestamp_format,
+            numeric_characters,
+            time_zone,
+            current_schema,
+            module,
+            action,
+            login_script,
+        }
+    }
+
+    pub fn load(&mut self, settings: &SessionSettings) {
+        self.date_format.set_value(&settings.nls_date_format);
+        self.timestamp_format
+            .set_value(&settings.nls_timestamp_format);
+        self.numeric_characters
+            .set_value(&settings.nls_numeric_characters);
+        self.time_zone.set_value(&settings.time_zone);
+        self.current_schema.set_value(&settings.current_schema);
+        self.module.set_value(&settings.module);
+        self.action.set_value(&settings.action);
+        self.login_script.set_value(&settings.login_script);
+    }
+
+    pub fn value(&self) -> SessionSettings {
+        let text = |input: &Input| input.value().trim().to_string();
+        SessionSettings {
+            nls_date_format: text(&self.date_format),
+            nls_timestamp_format: text(&self.timestamp_format),
+            nls_numeric_characters: text(&self.numeric_characters),
+            time_zone: text(&self.time_zone),
+            current_schema: text(&self.current_schema),
+            module: text(&self.module),
+            action: text(&self.action),
+            login_script: text(&self.login_script),
+        }
+    }
+}
+
+/// Edit the session settings a connection profile overrides. Returns `None`
+/// when cancelled.
+pub fn edit_profile_session_settings(settings: &SessionSettings) -> Option<SessionSettings> {
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 520;
+    let height = 400;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("Connection Session Settings");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+    dialog.make_modal(true);
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let mut hint =
+        Frame::default().with_label("Empty fields use the defaults from Settings > Preferences.");
+    hint.set_label_color(theme::text_secondary());
+    main_flex.fixed(&hint, LABEL_ROW_HEIGHT);
+
+    let mut form = SessionSettingsForm::build(&mut main_flex);
+    form.load(settings);
+
+    let _spacer = Frame::default();
+
+    let mut button_row = Flex::default();
+    button_row.set_type(FlexType::Row);
+    button_row.set_spacing(DIALOG_SPACING);
+    let _button_spacer = Frame::default();
+    let mut ok_btn = Button::default().with_label("OK");
+    ok_btn.set_color(theme::button_primary());
+    ok_btn.set_label_color(theme::text_primary());
+    ok_btn.set_frame(FrameType::RFlatBox);
+    button_row.fixed(&ok_btn, BUTTON_WIDTH);
+    let mut cancel_btn = Button::default().with_label("Cancel");
+    cancel_btn.set_color(theme::button_subtle());
+    cancel_btn.set_label_color(theme::text_primary());
+    cancel_btn.set_frame(FrameType::RFlatBox);
+    button_row.fixed(&cancel_btn, BUTTON_WIDTH);
+    button_row.end();
+    main_flex.fixed(&button_row, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    let result = std::rc::Rc::new(std::cell::RefCell::new(None::<SessionSettings>));
+    let result_for_ok = result.clone();
+    let mut dialog_for_ok = dialog.clone();
+    ok_btn.set_callback(move |_| {
+        *result_for_ok.borrow_mut() = Some(form.value());
+        dialog_for_ok.hide();
+        app::awake();
+    });
+    let mut dialog_for_cancel = dialog.clone();
+    cancel_btn.set_callback(move |_| {
+        dialog_for_cancel.hide();
+        app::awake();
+    });
+
+    dialog.show();
+    while dialog.shown() {
+        app::wait();
+    }
+
+    let edited = result.borrow_mut().take();
+    edited
+}
@@ -12,8 +12,9 @@ use fltk::{
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::db::SessionSettings;
 use crate::ui::constants::*;
-use crate::ui::{available_font_names, center_on_main, theme};
+use crate::ui::{available_font_names, center_on_main, theme, SessionSettingsForm};
 use crate::utils::AppConfig;
 
 pub struct FontSettings {
@@ -22,6 +23,7 @@ pub struct FontSettings {
     pub editor_size: u32,
     pub result_size: u32,
     pub result_cell_max_chars: u32,
+    pub session_defaults: SessionSettings,
 }
 
 fn validate_size(label: &str, value: &str) -> Option<u32> {
@@ -285,6 +287,35 @@ pub fn show_settings_dialog(config: &AppConfig) -> Option<FontSettings> {
     result_group.resizable(&result_flex);
     result_group.end();
 
+    let mut session_group = Group::new(content_x, tab_body_y, content_w, tab_body_h, None);
+    session_group.set_label("Session");
+    session_group.set_color(theme::panel_bg());
+    session_group.begin();
+
+    let mut session_flex = Flex::new(
+        content_x + DIALOG_MARGIN,
+        tab_body_y + DIALOG_MARGIN,
+        content_w - DIALOG_MARGIN * 2,
+        tab_body_h - DIALOG_MARGIN * 2,
+        None,
+    );
+    session_flex.set_type(FlexType::Column);
+    session_flex.set_spacing(DIALOG_SPACING);
+
+    let mut session_hint =
+        Frame::default().with_label("Applied to every new session; connections can override them.");
+    session_hint.set_label_color(theme::text_secondary());
+    session_flex.fixed(&session_hint, LABEL_ROW_HEIGHT);
+
+    let mut session_form = SessionSettingsForm::build(&mut session_flex);
+    session_form.load(&config.session_defaults);
+
+    let session_filler = Frame::default();
+    session_flex.resizable(&session_filler);
+    session_flex.end();
+    session_group.resizable(&session_flex);
+    session_group.end();
+
     tabs.end();
 
     let mut button_row = Flex::new(
@@ -370,6 +401,7 @@ pub fn show_settings_dialog(config: &AppConfig) -> Option<FontSettings> {
     let global_size_input_ok = global_size_input.clone();
     let result_cell_max_input_ok = result_cell_max_input.clone();
     let selected_font_ok = selected_font.clone();
+    let session_form_ok = session_form.clone();
     ok_btn.set_callback(move |_| {
         let ui_size = match validate_ui_size(&global_size_input_ok.value()) {
             Some(size) => size,
@@ -399,6 +431,7 @@ pub fn show_settings_dialog(config: &AppConfig) -> Option<FontSettings> {
             editor_size,
             result_size,
             result_cell_max_chars,
+            session_defaults: session_form_ok.value(),
         });
         dialog_handle.hide();
         app::awake();
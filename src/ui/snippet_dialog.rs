@@ -0,0 +1,491 @@
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    button::Button,
+    dialog::{FileDialog, FileDialogType},
+    enums::{CallbackTrigger, Color, FrameType},
+    frame::Frame,
+    group::{Flex, FlexType},
+    input::Input,
+    prelude::*,
+    text::{TextBuffer, TextEditor},
+    window::Window,
+};
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+use std::sync::mpsc;
+
+use crate::ui::constants::*;
+use crate::ui::{center_on_main, configured_editor_profile, configured_ui_font_size, theme};
+use crate::utils::{SnippetLibrary, SqlSnippet};
+
+/// Separates the trigger word from the snippet name in autocomplete rows.
+const SNIPPET_SUGGESTION_MARKER: &str = "  [snippet: ";
+
+thread_local! {
+    static SNIPPET_LIBRARY: RefCell<Option<SnippetLibrary>> = const { RefCell::new(None) };
+}
+
+/// Run `f` on the snippet library, loading it on first use.
+pub fn with_snippet_library<R>(f: impl FnOnce(&SnippetLibrary) -> R) -> R {
+    SNIPPET_LIBRARY.with(|library| {
+        let mut library = library.borrow_mut();
+        f(library.get_or_insert_with(SnippetLibrary::load))
+    })
+}
+
+fn store_snippet_library(library: SnippetLibrary) {
+    if let Err(err) = library.save() {
+        fltk::dialog::alert_default(&format!("Failed to save snippets: {}", err));
+    }
+    SNIPPET_LIBRARY.with(|cached| *cached.borrow_mut() = Some(library));
+}
+
+/// Autocomplete rows for the snippets whose trigger starts with `prefix`.
+pub fn snippet_suggestions(prefix: &str) -> Vec<String> {
+    with_snippet_library(|library| {
+        library
+            .triggers_matching(prefix)
+            .into_iter()
+            .map(|snippet| {
+                format!(
+                    "{}{}{}]",
+                    snippet.trigger,
+                    SNIPPET_SUGGESTION_MARKER,
+                    snippet.path()
+                )
+            })
+            .collect()
+    })
+}
+
+/// The snippet an autocomplete row from [`snippet_suggestions`] stands for.
+pub fn snippet_for_suggestion(suggestion: &str) -> Option<SqlSnippet> {
+    let (trigger, _) = suggestion.split_once(SNIPPET_SUGGESTION_MARKER)?;
+    with_snippet_library(|library| library.find_by_trigger(trigger).cloned())
+}
+
+/// Trigger words are typed in the editor, so they must be one identifier.
+fn valid_trigger(trigger: &str) -> bool {
+    trigger
+        .chars()
+        .all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$' || ch == '#')
+}
+
+fn normalize_folder(folder: &str) -> String {
+    folder
+        .split('/')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn listed_snippets(library: &SnippetLibrary, filter: &str) -> Vec<SqlSnippet> {
+    let filter = filter.trim().to_lowercase();
+    library
+        .snippets
+        .iter()
+        .filter(|snippet| {
+            filter.is_empty()
+                || snippet.path().to_lowercase().contains(&filter)
+                || snippet.trigger.to_lowercase().contains(&filter)
+                || snippet.description.to_lowercase().contains(&filter)
+        })
+        .cloned()
+        .collect()
+}
+
+fn styled_input() -> Input {
+    let mut input = Input::default();
+    input.set_color(theme::input_bg());
+    input.set_text_color(theme::text_primary());
+    input
+}
+
+fn styled_button(label: &str, color: Color) -> Button {
+    let mut button = Button::default().with_label(label);
+    button.set_color(color);
+    button.set_label_color(theme::text_primary());
+    button.set_frame(FrameType::RFlatBox);
+    button
+}
+
+fn form_row(parent: &mut Flex, label: &str) -> Input {
+    let mut row = Flex::default();
+    row.set_type(FlexType::Row);
+    row.set_spacing(DIALOG_SPACING);
+    let mut label = Frame::default().with_label(label);
+    label.set_label_color(theme::text_primary());
+    row.fixed(&label, FORM_LABEL_WIDTH);
+    let input = styled_input();
+    row.end();
+    parent.fixed(&row, INPUT_ROW_HEIGHT);
+    input
+}
+
+fn export_snippets(snippets: &[SqlSnippet]) {
+    if snippets.is_empty() {
+        fltk::dialog::alert_default("There are no snippets to export");
+        return;
+    }
+    let mut dialog = FileDialog::new(FileDialogType::BrowseSaveFile);
+    dialog.set_filter("Snippet Bundles\t*.json\nAll Files\t*.*");
+    dialog.show();
+    let mut filename = dialog.filename();
+    if filename.as_os_str().is_empty() {
+        return;
+    }
+    if filename.extension().is_none() {
+        filename.set_extension("json");
+    }
+    let content = match SnippetLibrary::bundle_json(snippets) {
+        Ok(content) => content,
+        Err(err) => {
+            fltk::dialog::alert_default(&format!("Failed to export snippets: {}", err));
+            return;
+        }
+    };
+    match fs::write(&filename, content) {
+        Ok(()) => fltk::dialog::message_default(&format!(
+            "Exported {} snippets to {}",
+            snippets.len(),
+            filename.display()
+        )),
+        Err(err) => fltk::dialog::alert_default(&format!("Failed to export snippets: {}", err)),
+    }
+}
+
+/// Read a bundle chosen by the user and merge it into `library`. Returns
+/// whether anything was imported.
+fn import_snippets(library: &mut SnippetLibrary) -> bool {
+    let mut dialog = FileDialog::new(FileDialogType::BrowseFile);
+    dialog.set_filter("Snippet Bundles\t*.json\nAll Files\t*.*");
+    dialog.show();
+    let filename = dialog.filename();
+    if filename.as_os_str().is_empty() {
+        return false;
+    }
+    let imported = match fs::read_to_string(&filename)
+        .map_err(|err| err.to_string())
+        .and_then(|content| SnippetLibrary::parse_bundle(&content))
+    {
+        Ok(imported) => imported,
+        Err(err) => {
+            fltk::dialog::alert_default(&format!("Failed to import snippets: {}", err));
+            return false;
+        }
+    };
+    let (added, replaced) = library.merge(imported);
+    fltk::dialog::message_default(&format!(
+        "Imported {} new snippets, replaced {}",
+        added, replaced
+    ));
+    added + replaced > 0
+}
+
+/// Browse, edit, import and export the snippet library. Returns the snippet
+/// the user chose to insert.
+pub fn show_snippet_dialog(popups: Rc<RefCell<Vec<Window>>>) -> Option<SqlSnippet> {
+    #[derive(Clone, Copy)]
+    enum DialogMessage {
+        Filter,
+        Select,
+        New,
+        Save,
+        Delete,
+        Import,
+        Export,
+        Insert,
+        Close,
+    }
+
+    let mut library = with_snippet_library(Clone::clone);
+
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 900;
+    let height = 560;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("SQL Snippets");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+    dialog.make_modal(true);
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let mut content = Flex::default();
+    content.set_type(FlexType::Row);
+    content.set_spacing(DIALOG_SPACING);
+
+    // Left - filter and snippet list
+    let mut list_flex = Flex::default();
+    list_flex.set_type(FlexType::Column);
+    list_flex.set_spacing(DIALOG_SPACING);
+    let mut filter_input = styled_input();
+    filter_input.set_tooltip("Filter by folder, name, trigger or description");
+    filter_input.set_trigger(CallbackTrigger::Changed);
+    list_flex.fixed(&filter_input, INPUT_ROW_HEIGHT);
+    let mut browser = HoldBrowser::default();
+    browser.set_color(theme::input_bg());
+    browser.set_selection_color(theme::selection_strong());
+    browser.set_text_size(configured_ui_font_size());
+    browser.set_column_char('\t');
+    browser.set_column_widths(&[230, 80]);
+    list_flex.end();
+    content.fixed(&list_flex, 330);
+
+    // Right - the selected snippet
+    let mut form = Flex::default();
+    form.set_type(FlexType::Column);
+    form.set_spacing(DIALOG_SPACING);
+    let mut name_input = form_row(&mut form, "Name:");
+    let mut folder_input = form_row(&mut form, "Folder:");
+    folder_input.set_tooltip("Nested folders are separated by '/'");
+    let mut trigger_input = form_row(&mut form, "Trigger:");
+    trigger_input.set_tooltip("Type this word in the editor to insert the snippet");
+    let mut description_input = form_row(&mut form, "Description:");
+    let mut hint = Frame::default().with_label("SQL (&name placeholders are asked for on insert):");
+    hint.set_label_color(theme::text_secondary());
+    hint.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    form.fixed(&hint, LABEL_ROW_HEIGHT);
+    let mut sql_buffer = TextBuffer::default();
+    let mut sql_editor = TextEditor::default();
+    sql_editor.set_buffer(sql_buffer.clone());
+    sql_editor.set_color(theme::editor_bg());
+    sql_editor.set_text_color(theme::text_primary());
+    sql_editor.set_cursor_color(theme::text_primary());
+    sql_editor.set_text_font(configured_editor_profile().normal);
+    sql_editor.set_text_size(configured_ui_font_size());
+    form.end();
+
+    content.end();
+
+    let mut button_flex = Flex::default();
+    button_flex.set_type(FlexType::Row);
+    button_flex.set_spacing(DIALOG_SPACING);
+    let mut new_btn = styled_button("New", theme::button_secondary());
+    let mut save_btn = styled_button("Save", theme::button_secondary());
+    let mut delete_btn = styled_button("Delete", theme::button_danger());
+    let mut import_btn = styled_button("Import...", theme::button_subtle());
+    let mut export_btn = styled_button("Export...", theme::button_subtle());
+    let _spacer = Frame::default();
+    let mut insert_btn = styled_button("Insert", theme::button_primary());
+    let mut close_btn = styled_button("Close", theme::button_subtle());
+    for button in [
+        &new_btn,
+        &save_btn,
+        &delete_btn,
+        &import_btn,
+        &export_btn,
+        &insert_btn,
+        &close_btn,
+    ] {
+        button_flex.fixed(button, BUTTON_WIDTH);
+    }
+    button_flex.end();
+    main_flex.fixed(&button_flex, BUTTON_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    popups.borrow_mut().push(dialog.clone());
+
+    let (sender, receiver) = mpsc::channel::<DialogMessage>();
+    for (button, message) in [
+        (&mut new_btn, DialogMessage::New),
+        (&mut save_btn, DialogMessage::Save),
+        (&mut delete_btn, DialogMessage::Delete),
+        (&mut import_btn, DialogMessage::Import),
+        (&mut export_btn, DialogMessage::Export),
+        (&mut insert_btn, DialogMessage::Insert),
+        (&mut close_btn, DialogMessage::Close),
+    ] {
+        let sender = sender.clone();
+        button.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+    let sender_for_filter = sender.clone();
+    filter_input.set_callback(move |_| {
+        let _ = sender_for_filter.send(DialogMessage::Filter);
+        app::awake();
+    });
+    let sender_for_browser = sender.clone();
+    browser.set_callback(move |_| {
+        let message = if app::event_clicks() {
+            DialogMessage::Insert
+        } else {
+            DialogMessage::Select
+        };
+        let _ = sender_for_browser.send(message);
+        app::awake();
+    });
+
+    dialog.show();
+    let _ = filter_input.take_focus();
+
+    let mut listed: Vec<SqlSnippet> = Vec::new();
+    // Path of the snippet shown in the form, if it is saved.
+    let mut editing: Option<String> = None;
+    let mut chosen: Option<SqlSnippet> = None;
+    let _ = sender.send(DialogMessage::Filter);
+
+    while dialog.shown() {
+        app::wait();
+        while let Ok(message) = receiver.try_recv() {
+            let selected = usize::try_from(browser.value() - 1)
+                .ok()
+                .and_then(|index| listed.get(index))
+                .cloned();
+            match message {
+                DialogMessage::Filter => {
+                    listed = listed_snippets(&library, &filter_input.value());
+                    browser.clear();
+                    for snippet in &listed {
+                        browser.add(&format!("@.{}\t@.{}", snippet.path(), snippet.trigger));
+                    }
+                    if let Some(index) = editing
+                        .as_ref()
+                        .and_then(|path| listed.iter().position(|s| &s.path() == path))
+                    {
+                        browser.select(index as i32 + 1);
+                    }
+                }
+                DialogMessage::Select => {
+                    let Some(snippet) = selected else {
+                        continue;
+                    };
+                    name_input.set_value(&snippet.name);
+                    folder_input.set_value(&snippet.folder);
+                    trigger_input.set_value(&snippet.trigger);
+                    description_input.set_value(&snippet.description);
+                    sql_buffer.set_text(&snippet.sql);
+                    editing = Some(snippet.path());
+                }
+                DialogMessage::New => {
+                    let line = browser.value();
+                    if line > 0 {
+                        browser.deselect(line);
+                    }
+                    for input in [&mut name_input, &mut trigger_input, &mut description_input] {
+                        input.set_value("");
+                    }
+                    sql_buffer.set_text("");
+                    editing = None;
+                    let _ = name_input.take_focus();
+                }
+                DialogMessage::Save => {
+                    let snippet = SqlSnippet {
+                        name: name_input.value().trim().replace('/', "-"),
+                        folder: normalize_folder(&folder_input.value()),
+                        trigger: trigger_input.value().trim().to_string(),
+                        description: description_input.value().trim().to_string(),
+                        sql: sql_buffer.text(),
+                    };
+                    if snippet.name.is_empty() {
+                        fltk::dialog::alert_default("Please enter a snippet name");
+                        continue;
+                    }
+                    if !valid_trigger(&snippet.trigger) {
+                        fltk::dialog::alert_default(
+                            "A trigger may only contain letters, digits, '_', '$' and '#'",
+                        );
+                        continue;
+                    }
+                    let path = snippet.path();
+                    if let Some(other) = library.find_by_trigger(&snippet.trigger) {
+                        let other_path = other.path();
+                        if !other_path.eq_ignore_ascii_case(&path)
+                            && editing.as_deref() != Some(other_path.as_str())
+                        {
+                            fltk::dialog::alert_default(&format!(
+                                "The trigger '{}' is already used by {}",
+                                snippet.trigger, other_path
+                            ));
+                            continue;
+                        }
+                    }
+                    // Saving under a new name or folder moves the snippet.
+                    if let Some(previous) = editing.take() {
+                        if !previous.eq_ignore_ascii_case(&path) {
+                            library.remove(&previous);
+                        }
+                    }
+                    library.upsert(snippet);
+                    store_snippet_library(library.clone());
+                    editing = Some(path);
+                    let _ = sender.send(DialogMessage::Filter);
+                }
+                DialogMessage::Delete => {
+                    let Some(snippet) = selected else {
+                        fltk::dialog::alert_default("Please select a snippet from the list");
+                        continue;
+                    };
+                    let choice = fltk::dialog::choice2_default(
+                        &format!("Delete the snippet {}?", snippet.path()),
+                        "Cancel",
+                        "Delete",
+                        "",
+                    );
+                    if choice == Some(1) && library.remove(&snippet.path()) {
+                        store_snippet_library(library.clone());
+                        editing = None;
+                        let _ = sender.send(DialogMessage::New);
+                        let _ = sender.send(DialogMessage::Filter);
+                    }
+                }
+                DialogMessage::Import => {
+                    if import_snippets(&mut library) {
+                        store_snippet_library(library.clone());
+                        let _ = sender.send(DialogMessage::Filter);
+                    }
+                }
+                // Exports what the list shows, so filter to share a subset.
+                DialogMessage::Export => export_snippets(&listed),
+                DialogMessage::Insert => {
+                    if let Some(snippet) = selected {
+                        chosen = Some(snippet);
+                        dialog.hide();
+                    } else {
+                        fltk::dialog::alert_default("Please select a snippet from the list");
+                    }
+                }
+                DialogMessage::Close => dialog.hide(),
+            }
+        }
+    }
+
+    popups
+        .borrow_mut()
+        .retain(|w| w.as_widget_ptr() != dialog.as_widget_ptr());
+
+    chosen
+}
+
+#[cfg(test)]
+mod snippet_dialog_tests {
+    use super::*;
+
+    #[test]
+    fn folders_are_normalized_and_triggers_must_be_one_word() {
+        assert_eq!(normalize_folder(" /tuning// waits /"), "tuning/waits");
+        assert!(valid_trigger("sel_emp"));
+        assert!(valid_trigger(""));
+        assert!(!valid_trigger("sel emp"));
+        assert!(!valid_trigger("a.b"));
+    }
+}
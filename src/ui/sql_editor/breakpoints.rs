@@ -0,0 +1,200 @@
+use fltk::{draw, text::PositionType};
+
+use super::*;
+
+/// Gutter marker size relative to the editor font.
+const MARKER_SCALE_PERCENT: i32 = 60;
+
+/// Where `line` ends up after an edit at `edit_line` adds or removes lines;
+/// `None` when the line was removed.
+pub(super) fn shifted_line(
+    line: usize,
+    edit_line: usize,
+    inserted_lines: usize,
+    deleted_lines: usize,
+) -> Option<usize> {
+    if line <= edit_line {
+        Some(line)
+    } else if line <= edit_line + deleted_lines {
+        None
+    } else {
+        Some(line + inserted_lines - deleted_lines)
+    }
+}
+
+/// Keep breakpoint lines on their text when an edit at `edit_line` adds or
+/// removes lines. Breakpoints on removed lines go away.
+pub(crate) fn shift_breakpoint_lines(
+    lines: &mut BTreeSet<usize>,
+    edit_line: usize,
+    inserted_lines: usize,
+    deleted_lines: usize,
+) {
+    if inserted_lines == 0 && deleted_lines == 0 {
+        return;
+    }
+    *lines = lines
+        .iter()
+        .filter_map(|&line| shifted_line(line, edit_line, inserted_lines, deleted_lines))
+        .collect();
+}
+
+impl SqlEditorWidget {
+    /// Breakpoints, the debugger's current line and compiler diagnostics
+    /// share the line number gutter and follow the text as it is edited.
+    pub(super) fn setup_gutter_markers(&self) {
+        let breakpoints = self.breakpoints.clone();
+        let diagnostics = self.diagnostics.clone();
+        let diagnostics_list = self.diagnostics_list.clone();
+        let mut buffer = self.buffer.clone();
+        buffer.add_modify_callback2(move |buf, pos, ins, _del, _restyled, deleted_text| {
+            if breakpoints.borrow().is_empty() && diagnostics.borrow().is_empty() {
+                return;
+            }
+            let inserted_lines = inserted_text(buf, pos, ins).matches('\n').count();
+            let deleted_lines = deleted_text.matches('\n').count();
+            if inserted_lines == 0 && deleted_lines == 0 {
+                return;
+            }
+            let edit_line = buf.count_lines(0, pos.max(0)) as usize + 1;
+            shift_breakpoint_lines(
+                &mut breakpoints.borrow_mut(),
+                edit_line,
+                inserted_lines,
+                deleted_lines,
+            );
+            if !diagnostics.borrow().is_empty() {
+                diagnostics.borrow_mut().retain_mut(|diagnostic| {
+                    match shifted_line(diagnostic.line, edit_line, inserted_lines, deleted_lines) {
+                        Some(line) => {
+                            diagnostic.line = line;
+                            true
+                        }
+                        None => false,
+                    }
+                });
+                Self::fill_diagnostics_list(&mut diagnostics_list.clone(), &diagnostics.borrow());
+            }
+        });
+
+        let breakpoints = self.breakpoints.clone();
+        let debug_line = self.debug_line.clone();
+        let diagnostics = self.diagnostics.clone();
+        let mut editor = self.editor.clone();
+        editor.draw(move |ed| {
+            let marks = breakpoints.borrow();
+            let current = *debug_line.borrow();
+            let diagnostics = diagnostics.borrow();
+            if marks.is_empty() && current.is_none() && diagnostics.is_empty() {
+                return;
+            }
+            draw::push_clip(ed.x(), ed.y(), ed.w(), ed.h());
+            Self::draw_diagnostics(ed, &diagnostics);
+            let size = (ed.text_size() * MARKER_SCALE_PERCENT / 100).max(6);
+            let left = ed.x() + 4;
+            for &line in marks.iter() {
+                if let Some(top) = Self::line_top(ed, line) {
+                    draw::set_draw_color(theme::button_danger());
+                    draw::draw_pie(left, top + 1, size, size, 0.0, 360.0);
+                }
+            }
+            if let Some(top) = current.and_then(|line| Self::line_top(ed, line)) {
+                let right = left + size + 8;
+                draw::set_draw_color(theme::button_warning());
+                draw::draw_polygon(
+                    left + size / 2,
+                    top + 1,
+                    right,
+                    top + 1 + size / 2,
+                    left + size / 2,
+                    top + 1 + size,
+                );
+            }
+            draw::pop_clip();
+        });
+    }
+
+    /// Buffer position where 1-based `line` starts.
+    pub(super) fn line_start(buffer: &TextBuffer, line: usize) -> Option<i32> {
+        let mut pos = 0;
+        for _ in 1..line {
+            pos = buffer.line_end(pos) + 1;
+            if pos > buffer.length() {
+                return None;
+            }
+        }
+        Some(pos)
+    }
+
+    /// Window y of the top of an editor line, when it is on screen.
+    pub(super) fn line_top(editor: &TextEditor, line: usize) -> Option<i32> {
+        let pos = Self::line_start(&editor.buffer()?, line)?;
+        // position_to_xy reports (0, 0) for positions scrolled out of view.
+        let (_, y) = editor.position_to_xy(pos);
+        (y >= editor.y() && y < editor.y() + editor.h()).then_some(y)
+    }
+
+    /// Toggle the breakpoint of the line clicked in the line number gutter.
+    /// Returns whether the click was in the gutter.
+    pub(super) fn toggle_breakpoint_at_event(
+        editor: &mut TextEditor,
+        breakpoints: &Rc<RefCell<BTreeSet<usize>>>,
+    ) -> bool {
+        if app::event_button() != 1 || app::event_x() >= editor.x() + editor.linenumber_width() {
+            return false;
+        }
+        let Some(buffer) = editor.buffer() else {
+            return false;
+        };
+        let pos = editor.xy_to_position(
+            editor.x() + editor.linenumber_width() + 2,
+            app::event_y(),
+            PositionType::Cursor,
+        );
+        let line = buffer.count_lines(0, pos.max(0)) as usize + 1;
+        {
+            let mut breakpoints = breakpoints.borrow_mut();
+            if !breakpoints.remove(&line) {
+                breakpoints.insert(line);
+            }
+        }
+        editor.redraw();
+        true
+    }
+
+    /// Editor lines with a breakpoint, in order.
+    pub fn breakpoint_lines(&self) -> Vec<usize> {
+        self.breakpoints.borrow().iter().copied().collect()
+    }
+
+    /// Mark `line` as where the debugger stopped and bring it into view, or
+    /// clear the mark.
+    pub fn set_debug_line(&self, line: Option<usize>) {
+        *self.debug_line.borrow_mut() = line;
+        let mut editor = self.editor.clone();
+        if let Some(pos) = line.and_then(|line| Self::line_start(&self.buffer, line)) {
+            editor.set_insert_position(pos);
+            editor.show_insert_position();
+        }
+        editor.redraw();
+    }
+}
+
+#[cfg(test)]
+mod breakpoint_tests {
+    use super::*;
+
+    #[test]
+    fn breakpoints_follow_inserted_and_deleted_lines() {
+        let mut lines: BTreeSet<usize> = [2, 5, 9].into_iter().collect();
+        shift_breakpoint_lines(&mut lines, 3, 2, 0);
+        assert_eq!(lines.iter().copied().collect::<Vec<_>>(), vec![2, 7, 11]);
+
+        // Joining lines 7..=9 into line 6 drops the breakpoint on line 7.
+        shift_breakpoint_lines(&mut lines, 6, 0, 3);
+        assert_eq!(lines.iter().copied().collect::<Vec<_>>(), vec![2, 8]);
+
+        shift_breakpoint_lines(&mut lines, 1, 0, 0);
+        assert_eq!(lines.iter().copied().collect::<Vec<_>>(), vec![2, 8]);
+    }
+}
@@ -0,0 +1,171 @@
+use fltk::draw;
+
+use super::*;
+
+/// Height of the compiler message list under the editor.
+const DIAGNOSTICS_LIST_HEIGHT: i32 = 110;
+
+fn diagnostic_color(diagnostic: &SourceDiagnostic) -> fltk::enums::Color {
+    if diagnostic.is_warning {
+        theme::button_warning()
+    } else {
+        theme::button_danger()
+    }
+}
+
+fn diagnostic_list_line(diagnostic: &SourceDiagnostic) -> String {
+    format!(
+        "@C{}@.Line {}, col {}\t@.{}",
+        diagnostic_color(diagnostic).bits(),
+        diagnostic.line,
+        diagnostic.column,
+        diagnostic.message.replace(['\t', '\n'], " ")
+    )
+}
+
+/// Byte range of `line` covered by the message: the word starting at its
+/// column, or a single character.
+fn diagnostic_span(line: &str, column: usize) -> (usize, usize) {
+    let start = line
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map(|(index, _)| index)
+        .unwrap_or(line.len());
+    let word_len: usize = line[start..]
+        .chars()
+        .take_while(|ch| ch.is_alphanumeric() || matches!(ch, '_' | '$' | '#'))
+        .map(char::len_utf8)
+        .sum();
+    let len = if word_len > 0 {
+        word_len
+    } else {
+        line[start..]
+            .chars()
+            .next()
+            .map(char::len_utf8)
+            .unwrap_or(0)
+    };
+    (start, start + len)
+}
+
+impl SqlEditorWidget {
+    pub(super) fn create_diagnostics_list() -> HoldBrowser {
+        let mut list = HoldBrowser::default();
+        list.set_color(theme::panel_bg());
+        list.set_selection_color(theme::selection_strong());
+        list.set_text_size(crate::ui::configured_ui_font_size());
+        list.set_column_char('\t');
+        list.set_column_widths(&[130]);
+        list.hide();
+        list
+    }
+
+    /// Clicking a message moves the cursor to it.
+    pub(super) fn setup_diagnostics_list(&self) {
+        let diagnostics = self.diagnostics.clone();
+        let buffer = self.buffer.clone();
+        let editor = self.editor.clone();
+        self.diagnostics_list.clone().set_callback(move |list| {
+            let Some(diagnostic) = usize::try_from(list.value() - 1)
+                .ok()
+                .and_then(|index| diagnostics.borrow().get(index).cloned())
+            else {
+                return;
+            };
+            let Some((start, end)) = Self::diagnostic_range(&buffer, &diagnostic) else {
+                return;
+            };
+            let mut editor = editor.clone();
+            buffer.clone().select(start, end);
+            editor.set_insert_position(start);
+            editor.show_insert_position();
+            let _ = editor.take_focus();
+        });
+    }
+
+    pub(super) fn fill_diagnostics_list(list: &mut HoldBrowser, diagnostics: &[SourceDiagnostic]) {
+        list.clear();
+        for diagnostic in diagnostics {
+            list.add(&diagnostic_list_line(diagnostic));
+        }
+    }
+
+    /// Show compiler messages in the gutter, under the text and in a list
+    /// below the editor. An empty list clears them.
+    pub fn set_diagnostics(&self, diagnostics: Vec<SourceDiagnostic>) {
+        let mut list = self.diagnostics_list.clone();
+        let mut group = self.group.clone();
+        Self::fill_diagnostics_list(&mut list, &diagnostics);
+        if diagnostics.is_empty() {
+            list.hide();
+            group.fixed(&list, 0);
+        } else {
+            list.show();
+            group.fixed(&list, DIAGNOSTICS_LIST_HEIGHT);
+        }
+        *self.diagnostics.borrow_mut() = diagnostics;
+        group.layout();
+        group.redraw();
+    }
+
+    fn diagnostic_range(buffer: &TextBuffer, diagnostic: &SourceDiagnostic) -> Option<(i32, i32)> {
+        let line_start = Self::line_start(buffer, diagnostic.line)?;
+        let line = buffer.text_range(line_start, buffer.line_end(line_start))?;
+        let (start, end) = diagnostic_span(&line, diagnostic.column);
+        Some((line_start + start as i32, line_start + end as i32))
+    }
+
+    /// Gutter bars and wavy underlines for compiler messages on screen.
+    pub(super) fn draw_diagnostics(editor: &TextEditor, diagnostics: &[SourceDiagnostic]) {
+        let Some(buffer) = editor.buffer() else {
+            return;
+        };
+        draw::set_font(editor.text_font(), editor.text_size());
+        let line_height = draw::height();
+        let text_left = editor.x() + editor.linenumber_width();
+        for diagnostic in diagnostics {
+            let Some(top) = Self::line_top(editor, diagnostic.line) else {
+                continue;
+            };
+            draw::set_draw_color(diagnostic_color(diagnostic));
+            draw::draw_rectf(text_left - 5, top + 1, 3, line_height - 2);
+            let Some((start, end)) = Self::diagnostic_range(&buffer, diagnostic) else {
+                continue;
+            };
+            let (x1, _) = editor.position_to_xy(start);
+            let (x2, _) = editor.position_to_xy(end);
+            if x1 < text_left {
+                continue;
+            }
+            let right = x2.max(x1 + 6);
+            let base = top + line_height - 1;
+            let mut x = x1;
+            let mut rising = false;
+            while x < right {
+                let next = (x + 2).min(right);
+                let (from, to) = if rising {
+                    (base, base - 2)
+                } else {
+                    (base - 2, base)
+                };
+                draw::draw_line(x, from, next, to);
+                rising = !rising;
+                x = next;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod diagnostics_tests {
+    use super::*;
+
+    #[test]
+    fn span_covers_the_word_at_the_column() {
+        assert_eq!(diagnostic_span("  x := y + 1;", 3), (2, 3));
+        assert_eq!(diagnostic_span("  v_total := 1;", 3), (2, 9));
+        assert_eq!(diagnostic_span("  := 1;", 3), (2, 3));
+        assert_eq!(diagnostic_span("é é_x", 3), (3, 7));
+        assert_eq!(diagnostic_span("end", 10), (3, 3));
+    }
+}
@@ -8,42 +8,35 @@ use fltk::{
     input::Input,
     prelude::*,
 };
-use oracle::{Connection, Error as OracleError};
-use std::env;
-use std::fs;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::panic::{self, AssertUnwindSafe};
-use std::path::{Path, PathBuf};
+use std::cell::Cell;
 use std::sync::atomic::Ordering;
-use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use crate::db::{
-    lock_connection, BindValue, BindVar, ColumnInfo, CursorResult, FormatItem, QueryExecutor,
-    QueryResult, ScriptItem, SessionState, ToolCommand,
+    try_lock_connection, FormatItem, QueryExecutor, ScriptItem, ScriptRunner, SessionState,
+    ToolCommand,
 };
 use crate::ui::SQL_KEYWORDS;
+use crate::utils::SqlSnippet;
 
 use super::*;
 
+/// Answers substitution prompts with the input dialog, on the UI thread.
 #[derive(Default)]
-struct SelectTransformState {
-    break_index: Option<usize>,
-    previous_break_value: Option<String>,
-    compute_of_index: Option<usize>,
-    compute_on_index: Option<usize>,
-    compute_group_value: Option<String>,
-    compute_count: usize,
-    compute_sum: f64,
-    compute_sum_seen: bool,
-    compute_sums: Vec<f64>,
-    compute_seen_numeric: Vec<bool>,
+struct DialogPromptSink {
+    cancelled: Cell<bool>,
 }
 
-const PROGRESS_ROWS_FLUSH_INTERVAL: Duration = Duration::from_millis(0);
-const PROGRESS_ROWS_MAX_BATCH: usize = 1;
+impl ScriptEventSink for DialogPromptSink {
+    fn emit(&self, event: QueryProgress) {
+        if let QueryProgress::PromptInput { prompt, response } = event {
+            let value = SqlEditorWidget::prompt_input_dialog(&prompt);
+            self.cancelled.set(value.is_none());
+            let _ = response.send(value);
+        }
+    }
+}
 
 impl SqlEditorWidget {
     pub fn execute_sql_text(&self, sql: &str) {
@@ -331,7 +324,7 @@ impl SqlEditorWidget {
                         Self::statement_has_unbalanced_paren(statement);
                 }
                 FormatItem::ToolCommand(command) => {
-                    formatted.push_str(&Self::format_tool_command(command));
+                    formatted.push_str(&ScriptRunner::format_tool_command(command));
                     force_select_list_newline_next = false;
                 }
                 FormatItem::Slash => {
@@ -444,235 +437,6 @@ impl SqlEditorWidget {
         depth > 0
     }
 
-    fn format_tool_command(command: &ToolCommand) -> String {
-        match command {
-            ToolCommand::Var { name, data_type } => {
-                format!("VAR {} {}", name, data_type.display())
-            }
-            ToolCommand::Print { name } => match name {
-                Some(name) => format!("PRINT {}", name),
-                None => "PRINT".to_string(),
-            },
-            ToolCommand::SetServerOutput {
-                enabled,
-                size,
-                unlimited,
-            } => {
-                if !*enabled {
-                    "SET SERVEROUTPUT OFF".to_string()
-                } else if *unlimited {
-                    "SET SERVEROUTPUT ON SIZE UNLIMITED".to_string()
-                } else if let Some(size) = size {
-                    format!("SET SERVEROUTPUT ON SIZE {}", size)
-                } else {
-                    "SET SERVEROUTPUT ON".to_string()
-                }
-            }
-            ToolCommand::ShowErrors {
-                object_type,
-                object_name,
-            } => {
-                if let (Some(obj_type), Some(obj_name)) = (object_type, object_name) {
-                    format!("SHOW ERRORS {} {}", obj_type, obj_name)
-                } else {
-                    "SHOW ERRORS".to_string()
-                }
-            }
-            ToolCommand::ShowUser => "SHOW USER".to_string(),
-            ToolCommand::ShowAll => "SHOW ALL".to_string(),
-            ToolCommand::Describe { name } => format!("DESCRIBE {}", name),
-            ToolCommand::Prompt { text } => {
-                if text.trim().is_empty() {
-                    "PROMPT".to_string()
-                } else {
-                    format!("PROMPT {}", text)
-                }
-            }
-            ToolCommand::Pause { message } => match message {
-                Some(text) if !text.trim().is_empty() => format!("PAUSE {}", text),
-                _ => "PAUSE".to_string(),
-            },
-            ToolCommand::Accept { name, prompt } => match prompt {
-                Some(text) => format!("ACCEPT {} PROMPT '{}'", name, text),
-                None => format!("ACCEPT {}", name),
-            },
-            ToolCommand::Define { name, value } => format!("DEFINE {} = {}", name, value),
-            ToolCommand::Undefine { name } => format!("UNDEFINE {}", name),
-            ToolCommand::ColumnNewValue {
-                column_name,
-                variable_name,
-            } => format!("COLUMN {} NEW_VALUE {}", column_name, variable_name),
-            ToolCommand::BreakOn { column_name } => format!("BREAK ON {}", column_name),
-            ToolCommand::BreakOff => "BREAK OFF".to_string(),
-            ToolCommand::ClearBreaks => "CLEAR BREAKS".to_string(),
-            ToolCommand::ClearComputes => "CLEAR COMPUTES".to_string(),
-            ToolCommand::ClearBreaksComputes => "CLEAR BREAKS\nCLEAR COMPUTES".to_string(),
-            ToolCommand::Compute {
-                mode,
-                of_column,
-                on_column,
-            } => {
-                let mode_text = match mode {
-                    crate::db::ComputeMode::Sum => "SUM",
-                    crate::db::ComputeMode::Count => "COUNT",
-                };
-                match (of_column.as_deref(), on_column.as_deref()) {
-                    (Some(of_col), Some(on_col)) => {
-                        format!("COMPUTE {} OF {} ON {}", mode_text, of_col, on_col)
-                    }
-                    _ => format!("COMPUTE {}", mode_text),
-                }
-            }
-            ToolCommand::ComputeOff => "COMPUTE OFF".to_string(),
-            ToolCommand::SetErrorContinue { enabled } => {
-                if *enabled {
-                    "SET ERRORCONTINUE ON".to_string()
-                } else {
-                    "SET ERRORCONTINUE OFF".to_string()
-                }
-            }
-            ToolCommand::SetAutoCommit { enabled } => {
-                if *enabled {
-                    "SET AUTOCOMMIT ON".to_string()
-                } else {
-                    "SET AUTOCOMMIT OFF".to_string()
-                }
-            }
-            ToolCommand::SetDefine {
-                enabled,
-                define_char,
-            } => {
-                if let Some(ch) = define_char {
-                    format!("SET DEFINE '{}'", ch)
-                } else if *enabled {
-                    "SET DEFINE ON".to_string()
-                } else {
-                    "SET DEFINE OFF".to_string()
-                }
-            }
-            ToolCommand::SetScan { enabled } => {
-                if *enabled {
-                    "SET SCAN ON".to_string()
-                } else {
-                    "SET SCAN OFF".to_string()
-                }
-            }
-            ToolCommand::SetVerify { enabled } => {
-                if *enabled {
-                    "SET VERIFY ON".to_string()
-                } else {
-                    "SET VERIFY OFF".to_string()
-                }
-            }
-            ToolCommand::SetEcho { enabled } => {
-                if *enabled {
-                    "SET ECHO ON".to_string()
-                } else {
-                    "SET ECHO OFF".to_string()
-                }
-            }
-            ToolCommand::SetTiming { enabled } => {
-                if *enabled {
-                    "SET TIMING ON".to_string()
-                } else {
-                    "SET TIMING OFF".to_string()
-                }
-            }
-            ToolCommand::SetFeedback { enabled } => {
-                if *enabled {
-                    "SET FEEDBACK ON".to_string()
-                } else {
-                    "SET FEEDBACK OFF".to_string()
-                }
-            }
-            ToolCommand::SetHeading { enabled } => {
-                if *enabled {
-                    "SET HEADING ON".to_string()
-                } else {
-                    "SET HEADING OFF".to_string()
-                }
-            }
-            ToolCommand::SetPageSize { size } => format!("SET PAGESIZE {}", size),
-            ToolCommand::SetLineSize { size } => format!("SET LINESIZE {}", size),
-            ToolCommand::SetTrimSpool { enabled } => {
-                if *enabled {
-                    "SET TRIMSPOOL ON".to_string()
-                } else {
-                    "SET TRIMSPOOL OFF".to_string()
-                }
-            }
-            ToolCommand::SetTrimOut { enabled } => {
-                if *enabled {
-                    "SET TRIMOUT ON".to_string()
-                } else {
-                    "SET TRIMOUT OFF".to_string()
-                }
-            }
-            ToolCommand::SetSqlBlankLines { enabled } => {
-                if *enabled {
-                    "SET SQLBLANKLINES ON".to_string()
-                } else {
-                    "SET SQLBLANKLINES OFF".to_string()
-                }
-            }
-            ToolCommand::SetTab { enabled } => {
-                if *enabled {
-                    "SET TAB ON".to_string()
-                } else {
-                    "SET TAB OFF".to_string()
-                }
-            }
-            ToolCommand::SetColSep { separator } => format!("SET COLSEP {}", separator),
-            ToolCommand::SetNull { null_text } => format!("SET NULL {}", null_text),
-            ToolCommand::Spool { path, append } => match path {
-                Some(path) if *append => format!("SPOOL {} APPEND", path),
-                Some(path) => format!("SPOOL {}", path),
-                None if *append => "SPOOL APPEND".to_string(),
-                None => "SPOOL OFF".to_string(),
-            },
-            ToolCommand::WheneverSqlError { exit, action } => {
-                let mode = if *exit { "EXIT" } else { "CONTINUE" };
-                match action.as_deref() {
-                    Some(extra) if !extra.trim().is_empty() => {
-                        format!("WHENEVER SQLERROR {} {}", mode, extra.trim())
-                    }
-                    _ => format!("WHENEVER SQLERROR {}", mode),
-                }
-            }
-            ToolCommand::WheneverOsError { exit } => {
-                if *exit {
-                    "WHENEVER OSERROR EXIT".to_string()
-                } else {
-                    "WHENEVER OSERROR CONTINUE".to_string()
-                }
-            }
-            ToolCommand::Exit => "EXIT".to_string(),
-            ToolCommand::Quit => "QUIT".to_string(),
-            ToolCommand::RunScript {
-                path,
-                relative_to_caller,
-            } => {
-                if *relative_to_caller {
-                    format!("@@{}", path)
-                } else {
-                    format!("@{}", path)
-                }
-            }
-            ToolCommand::Connect {
-                username,
-                host,
-                port,
-                service_name,
-                ..
-            } => format!(
-                "CONNECT {}/****@{}:{}/{}",
-                username, host, port, service_name
-            ),
-            ToolCommand::Disconnect => "DISCONNECT".to_string(),
-            ToolCommand::Unsupported { raw, .. } => raw.clone(),
-        }
-    }
-
     fn format_statement(statement: &str, force_select_list_newline_on_start: bool) -> String {
         if let Some(formatted) = Self::format_create_table(statement) {
             return formatted;
@@ -2973,6 +2737,15 @@ impl SqlEditorWidget {
                 return;
             };
 
+            // A dropped session offers a reconnect instead of failing the query.
+            if !has_connect_command && conn_guard.is_lost() {
+                let _ = self.progress_sender.send(QueryProgress::ConnectionLost {
+                    message: "The database did not answer; the session is gone".to_string(),
+                });
+                app::awake();
+                return;
+            }
+
             // Only check connection status if this is not a CONNECT/DISCONNECT command
             if !has_connect_command && !conn_guard.is_connected() {
                 fltk::dialog::alert_default("Not connected to database");
@@ -3003,4622 +2776,97 @@ impl SqlEditorWidget {
         app::flush();
 
         thread::spawn(move || {
-            let result = panic::catch_unwind(AssertUnwindSafe(|| {
-                struct ScriptFrame {
-                    items: Vec<ScriptItem>,
-                    index: usize,
-                    base_dir: PathBuf,
-                }
-
-                // Acquire connection lock inside thread and hold it during execution
-                let mut conn_guard = lock_connection(&shared_connection);
+            let sink = ProgressSink { sender };
+            // The ping waits for the network timeout on a half-open session,
+            // so it runs here rather than on the UI thread.
+            if !has_connect_command
+                && !crate::db::lock_connection(&shared_connection)
+                    .ensure_alive(crate::db::IDLE_PING_INTERVAL)
+            {
+                sink.emit(QueryProgress::BatchFinished);
+                sink.emit(QueryProgress::ConnectionLost {
+                    message: "The database did not answer; the session is gone".to_string(),
+                });
+                return;
+            }
+            ScriptRunner::run_batch(
+                shared_connection,
+                sql_text,
+                script_mode,
+                query_timeout,
+                &sink,
+                current_query_connection,
+                cancel_flag,
+            );
+        });
+    }
 
-                let mut conn_opt = conn_guard.get_connection();
-                let mut conn_name = if conn_guard.is_connected() {
-                    conn_guard.get_info().name.clone()
-                } else {
-                    String::new()
-                };
-                let auto_commit = conn_guard.auto_commit();
-                let session = conn_guard.session_state();
-
-                // Store connection for cancel operation (separate from mutex)
-                if let Some(ref conn) = conn_opt {
-                    SqlEditorWidget::set_current_query_connection(
-                        &current_query_connection,
-                        Some(Arc::clone(conn)),
-                    );
-                }
+    pub fn prompt_input_dialog(prompt: &str) -> Option<String> {
+        let current_group = fltk::group::Group::try_current();
+        fltk::group::Group::set_current(None::<&fltk::group::Group>);
 
-                // Keep conn_guard alive (don't drop it) so the lock is held during execution
+        let mut dialog = fltk::window::Window::default()
+            .with_size(420, 150)
+            .with_label("Input");
+        crate::ui::center_on_main(&mut dialog);
+        dialog.set_color(theme::panel_raised());
+        dialog.make_modal(true);
 
-                let items = QueryExecutor::split_script_items(&sql_text);
-                if items.is_empty() {
-                    let _ = sender.send(QueryProgress::BatchFinished);
-                    app::awake();
-                    return;
-                }
+        let mut main_flex = Flex::default().with_pos(10, 10).with_size(400, 130);
+        main_flex.set_type(FlexType::Column);
+        main_flex.set_spacing(8);
 
-                let _ = sender.send(QueryProgress::BatchStart);
-                app::awake();
+        let mut prompt_frame = Frame::default().with_label(prompt);
+        prompt_frame.set_label_color(theme::text_primary());
+        prompt_frame.set_align(Align::Left | Align::Inside | Align::Wrap);
+        main_flex.fixed(&prompt_frame, 48);
 
-                // Set timeout only if we have a connection
-                let mut previous_timeout = conn_opt
-                    .as_ref()
-                    .and_then(|c| c.call_timeout().ok())
-                    .flatten();
-
-                if let Some(conn) = conn_opt.as_ref() {
-                    if let Err(err) = conn.set_call_timeout(query_timeout) {
-                        if script_mode {
-                            let result = QueryResult::new_error(&sql_text, &err.to_string());
-                            SqlEditorWidget::emit_script_result(
-                                &sender, &conn_name, 0, result, false,
-                            );
-                        } else {
-                            SqlEditorWidget::append_spool_output(&session, &[err.to_string()]);
-                            let _ = sender.send(QueryProgress::StatementFinished {
-                                index: 0,
-                                result: QueryResult::new_error(&sql_text, &err.to_string()),
-                                connection_name: conn_name.clone(),
-                                timed_out: false,
-                            });
-                            app::awake();
-                        }
-                        let _ = sender.send(QueryProgress::BatchFinished);
-                        app::awake();
-                        let _ = conn.set_call_timeout(previous_timeout);
-                        return;
-                    }
-                    if let Err(err) =
-                        SqlEditorWidget::sync_serveroutput_with_session(conn.as_ref(), &session)
-                    {
-                        eprintln!("Failed to apply SERVEROUTPUT setting on session start: {err}");
-                    }
-                }
+        let mut input = Input::default();
+        input.set_color(theme::input_bg());
+        input.set_text_color(theme::text_primary());
+        input.set_trigger(CallbackTrigger::EnterKeyAlways);
+        main_flex.fixed(&input, 30);
 
-                let mut result_index = 0usize;
-                let mut auto_commit = auto_commit;
-                let mut continue_on_error = match session.lock() {
-                    Ok(guard) => guard.continue_on_error,
-                    Err(poisoned) => {
-                        eprintln!("Warning: session state lock was poisoned; recovering.");
-                        poisoned.into_inner().continue_on_error
-                    }
-                };
-                let mut stop_execution = false;
-                let working_dir = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-                let mut frames = vec![ScriptFrame {
-                    items,
-                    index: 0,
-                    base_dir: working_dir.clone(),
-                }];
-
-                while let Some(frame) = frames.last_mut() {
-                    if stop_execution || cancel_flag.load(Ordering::Relaxed) {
-                        break;
-                    }
+        let mut button_flex = Flex::default();
+        button_flex.set_type(FlexType::Row);
+        button_flex.set_spacing(8);
 
-                    if frame.index >= frame.items.len() {
-                        frames.pop();
-                        continue;
-                    }
+        let _spacer = Frame::default();
 
-                    let item = frame.items[frame.index].clone();
-                    frame.index += 1;
+        let mut ok_btn = Button::default()
+            .with_size(BUTTON_WIDTH, BUTTON_HEIGHT)
+            .with_label("OK");
+        ok_btn.set_color(theme::button_primary());
+        ok_btn.set_label_color(theme::text_primary());
+        ok_btn.set_frame(FrameType::RFlatBox);
 
-                    let echo_enabled = match session.lock() {
-                        Ok(guard) => guard.echo_enabled,
-                        Err(poisoned) => {
-                            eprintln!("Warning: session state lock was poisoned; recovering.");
-                            poisoned.into_inner().echo_enabled
-                        }
-                    };
-                    if echo_enabled {
-                        let echo_line = match &item {
-                            ScriptItem::Statement(statement) => statement.trim().to_string(),
-                            ScriptItem::ToolCommand(command) => {
-                                SqlEditorWidget::format_tool_command(command)
-                            }
-                        };
-                        if !echo_line.trim().is_empty() {
-                            SqlEditorWidget::emit_script_output(&sender, &session, vec![echo_line]);
-                        }
-                    }
+        let mut cancel_btn = Button::default()
+            .with_size(BUTTON_WIDTH, BUTTON_HEIGHT)
+            .with_label("Cancel");
+        cancel_btn.set_color(theme::button_subtle());
+        cancel_btn.set_label_color(theme::text_primary());
+        cancel_btn.set_frame(FrameType::RFlatBox);
 
-                    match item {
-                        ScriptItem::ToolCommand(command) => {
-                            let mut command_error = false;
-                            match command {
-                                ToolCommand::Var { name, data_type } => {
-                                    let normalized = SessionState::normalize_name(&name);
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.binds.insert(
-                                            normalized.clone(),
-                                            BindVar::new(data_type.clone()),
-                                        );
-                                    }
-                                    let message = format!(
-                                        "Variable :{} declared as {}",
-                                        normalized,
-                                        data_type.display()
-                                    );
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        &format!("VAR {} {}", normalized, data_type.display()),
-                                        &message,
-                                    );
-                                }
-                                ToolCommand::Print { name } => {
-                                    let binds_snapshot = match session.lock() {
-                                        Ok(guard) => guard.binds.clone(),
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            poisoned.into_inner().binds.clone()
-                                        }
-                                    };
-                                    let (heading_enabled, feedback_enabled) =
-                                        SqlEditorWidget::current_output_settings(&session);
-                                    let (_colsep, null_text, _trimspool_enabled) =
-                                        SqlEditorWidget::current_text_output_settings(&session);
-
-                                    if let Some(name) = name {
-                                        let key = SessionState::normalize_name(&name);
-                                        if let Some(bind) = binds_snapshot.get(&key) {
-                                            match &bind.value {
-                                                BindValue::Scalar(value) => {
-                                                    let columns = vec![
-                                                        "NAME".to_string(),
-                                                        "VALUE".to_string(),
-                                                    ];
-                                                    let rows = vec![vec![
-                                                        key.clone(),
-                                                        value
-                                                            .clone()
-                                                            .unwrap_or_else(|| null_text.clone()),
-                                                    ]];
-                                                    let headers =
-                                                        SqlEditorWidget::apply_heading_setting(
-                                                            columns,
-                                                            heading_enabled,
-                                                        );
-                                                    SqlEditorWidget::emit_select_result(
-                                                        &sender,
-                                                        &session,
-                                                        &conn_name,
-                                                        result_index,
-                                                        &format!("PRINT {}", key),
-                                                        headers,
-                                                        rows,
-                                                        true,
-                                                        feedback_enabled,
-                                                    );
-                                                    result_index += 1;
-                                                }
-                                                BindValue::Cursor(Some(cursor)) => {
-                                                    let columns = cursor.columns.clone();
-                                                    let headers =
-                                                        SqlEditorWidget::apply_heading_setting(
-                                                            columns,
-                                                            heading_enabled,
-                                                        );
-                                                    SqlEditorWidget::emit_select_result(
-                                                        &sender,
-                                                        &session,
-                                                        &conn_name,
-                                                        result_index,
-                                                        &format!("PRINT {}", key),
-                                                        headers,
-                                                        cursor.rows.clone(),
-                                                        true,
-                                                        feedback_enabled,
-                                                    );
-                                                    result_index += 1;
-                                                }
-                                                BindValue::Cursor(None) => {
-                                                    SqlEditorWidget::emit_script_message(
-                                                        &sender,
-                                                        &session,
-                                                        &format!("PRINT {}", key),
-                                                        &format!(
-                                                        "Error: Cursor :{} has no data to print.",
-                                                        key
-                                                    ),
-                                                    );
-                                                    command_error = true;
-                                                }
-                                            }
-                                        } else {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                &format!("PRINT {}", key),
-                                                &format!(
-                                                    "Error: Bind variable :{} is not defined.",
-                                                    key
-                                                ),
-                                            );
-                                            command_error = true;
-                                        }
-                                    } else if binds_snapshot.is_empty() {
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            "PRINT",
-                                            "No bind variables declared.",
-                                        );
-                                    } else {
-                                        let mut summary_rows: Vec<Vec<String>> = Vec::new();
-                                        let mut cursor_results: Vec<(String, CursorResult)> =
-                                            Vec::new();
-
-                                        for (name, bind) in binds_snapshot {
-                                            let value_display = match &bind.value {
-                                                BindValue::Scalar(value) => value
-                                                    .clone()
-                                                    .unwrap_or_else(|| null_text.clone()),
-                                                BindValue::Cursor(Some(cursor)) => {
-                                                    cursor_results
-                                                        .push((name.clone(), cursor.clone()));
-                                                    format!(
-                                                        "REFCURSOR ({} rows)",
-                                                        cursor.rows.len()
-                                                    )
-                                                }
-                                                BindValue::Cursor(None) => {
-                                                    "REFCURSOR (empty)".to_string()
-                                                }
-                                            };
-
-                                            summary_rows.push(vec![
-                                                name.clone(),
-                                                bind.data_type.display(),
-                                                value_display,
-                                            ]);
-                                        }
-
-                                        let headers = SqlEditorWidget::apply_heading_setting(
-                                            vec![
-                                                "NAME".to_string(),
-                                                "TYPE".to_string(),
-                                                "VALUE".to_string(),
-                                            ],
-                                            heading_enabled,
-                                        );
-                                        SqlEditorWidget::emit_select_result(
-                                            &sender,
-                                            &session,
-                                            &conn_name,
-                                            result_index,
-                                            "PRINT",
-                                            headers,
-                                            summary_rows,
-                                            true,
-                                            feedback_enabled,
-                                        );
-                                        result_index += 1;
-
-                                        for (cursor_name, cursor) in cursor_results {
-                                            let columns = cursor.columns.clone();
-                                            let headers = SqlEditorWidget::apply_heading_setting(
-                                                columns,
-                                                heading_enabled,
-                                            );
-                                            SqlEditorWidget::emit_select_result(
-                                                &sender,
-                                                &session,
-                                                &conn_name,
-                                                result_index,
-                                                &format!("PRINT {}", cursor_name),
-                                                headers,
-                                                cursor.rows.clone(),
-                                                true,
-                                                feedback_enabled,
-                                            );
-                                            result_index += 1;
-                                        }
-                                    }
-                                }
-                                ToolCommand::SetServerOutput {
-                                    enabled,
-                                    size,
-                                    unlimited,
-                                } => {
-                                    // This command needs a connection
-                                    let conn = match conn_opt.as_ref() {
-                                        Some(c) => c,
-                                        None => {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                "SET SERVEROUTPUT",
-                                                "Error: Not connected to database",
-                                            );
-                                            continue;
-                                        }
-                                    };
-
-                                    let default_size = 1_000_000u32;
-                                    let current_size = match session.lock() {
-                                        Ok(guard) => guard.server_output.size,
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            poisoned.into_inner().server_output.size
-                                        }
-                                    };
-                                    let mut message = String::new();
-                                    let mut success = true;
-
-                                    if enabled {
-                                        if unlimited {
-                                            // SIZE UNLIMITED: pass None to enable unlimited buffer
-                                            let enable_result = QueryExecutor::enable_dbms_output(
-                                                conn.as_ref(),
-                                                None,
-                                            );
-
-                                            match enable_result {
-                                                Ok(()) => {
-                                                    let mut guard = match session.lock() {
-                                                        Ok(guard) => guard,
-                                                        Err(poisoned) => {
-                                                            eprintln!("Warning: session state lock was poisoned; recovering.");
-                                                            poisoned.into_inner()
-                                                        }
-                                                    };
-                                                    guard.server_output.enabled = true;
-                                                    guard.server_output.size = 0; // 0 indicates unlimited
-                                                    message =
-                                                        "SERVEROUTPUT enabled (size UNLIMITED)"
-                                                            .to_string();
-                                                }
-                                                Err(err) => {
-                                                    success = false;
-                                                    message = format!(
-                                                        "SERVEROUTPUT enable failed: {}",
-                                                        err
-                                                    );
-                                                }
-                                            }
-                                        } else {
-                                            let desired_size = size.unwrap_or(current_size);
-                                            let mut applied_size = desired_size;
-                                            let mut enable_result =
-                                                QueryExecutor::enable_dbms_output(
-                                                    conn.as_ref(),
-                                                    Some(desired_size),
-                                                );
-
-                                            if enable_result.is_err()
-                                                && size.is_some()
-                                                && desired_size != default_size
-                                            {
-                                                if QueryExecutor::enable_dbms_output(
-                                                    conn.as_ref(),
-                                                    Some(default_size),
-                                                )
-                                                .is_ok()
-                                                {
-                                                    applied_size = default_size;
-                                                    message = format!(
-                                                        "SERVEROUTPUT enabled with size {} (requested {} not supported)",
-                                                        applied_size, desired_size
-                                                    );
-                                                    enable_result = Ok(());
-                                                }
-                                            }
-
-                                            match enable_result {
-                                                Ok(()) => {
-                                                    let mut guard = match session.lock() {
-                                                        Ok(guard) => guard,
-                                                        Err(poisoned) => {
-                                                            eprintln!("Warning: session state lock was poisoned; recovering.");
-                                                            poisoned.into_inner()
-                                                        }
-                                                    };
-                                                    guard.server_output.enabled = true;
-                                                    guard.server_output.size = applied_size;
-                                                    if message.is_empty() {
-                                                        message = format!(
-                                                            "SERVEROUTPUT enabled (size {})",
-                                                            applied_size
-                                                        );
-                                                    }
-                                                }
-                                                Err(err) => {
-                                                    success = false;
-                                                    message = format!(
-                                                        "SERVEROUTPUT enable failed: {}",
-                                                        err
-                                                    );
-                                                }
-                                            }
-                                        }
-                                    } else {
-                                        match QueryExecutor::disable_dbms_output(conn.as_ref()) {
-                                            Ok(()) => {
-                                                let mut guard = match session.lock() {
-                                                    Ok(guard) => guard,
-                                                    Err(poisoned) => {
-                                                        eprintln!("Warning: session state lock was poisoned; recovering.");
-                                                        poisoned.into_inner()
-                                                    }
-                                                };
-                                                guard.server_output.enabled = false;
-                                                message = "SERVEROUTPUT disabled".to_string();
-                                            }
-                                            Err(err) => {
-                                                success = false;
-                                                message =
-                                                    format!("SERVEROUTPUT disable failed: {}", err);
-                                            }
-                                        }
-                                    }
+        button_flex.fixed(&ok_btn, BUTTON_WIDTH);
+        button_flex.fixed(&cancel_btn, BUTTON_WIDTH);
+        button_flex.end();
+        main_flex.fixed(&button_flex, BUTTON_ROW_HEIGHT);
+        main_flex.end();
+        dialog.end();
+        fltk::group::Group::set_current(current_group.as_ref());
 
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET SERVEROUTPUT",
-                                        &message,
-                                    );
-                                    if !success {
-                                        command_error = true;
-                                    }
-                                }
-                                ToolCommand::ShowErrors {
-                                    object_type,
-                                    object_name,
-                                } => {
-                                    // This command needs a connection
-                                    let conn = match conn_opt.as_ref() {
-                                        Some(c) => c,
-                                        None => {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                "SHOW ERRORS",
-                                                "Error: Not connected to database",
-                                            );
-                                            continue;
-                                        }
-                                    };
-
-                                    let mut target = None;
-                                    if object_type.is_none() {
-                                        target = match session.lock() {
-                                            Ok(guard) => guard.last_compiled.clone(),
-                                            Err(poisoned) => {
-                                                eprintln!("Warning: session state lock was poisoned; recovering.");
-                                                poisoned.into_inner().last_compiled.clone()
-                                            }
-                                        };
-                                    } else if let (Some(obj_type), Some(obj_name)) =
-                                        (object_type.clone(), object_name.clone())
-                                    {
-                                        let (owner, name) = if let Some(dot) = obj_name.find('.') {
-                                            let (owner_raw, name_raw) = obj_name.split_at(dot);
-                                            (
-                                                Some(SqlEditorWidget::normalize_object_name(
-                                                    owner_raw,
-                                                )),
-                                                SqlEditorWidget::normalize_object_name(
-                                                    name_raw.trim_start_matches('.'),
-                                                ),
-                                            )
-                                        } else {
-                                            (
-                                                None,
-                                                SqlEditorWidget::normalize_object_name(&obj_name),
-                                            )
-                                        };
-
-                                        target = Some(crate::db::CompiledObject {
-                                            owner,
-                                            object_type: obj_type.to_uppercase(),
-                                            name,
-                                        });
-                                    }
+        let result: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+        let cancelled = Rc::new(RefCell::new(false));
 
-                                    if let Some(object) = target {
-                                        match QueryExecutor::fetch_compilation_errors(
-                                            conn.as_ref(),
-                                            &object,
-                                        ) {
-                                            Ok(rows) => {
-                                                if rows.is_empty() {
-                                                    SqlEditorWidget::emit_script_message(
-                                                        &sender,
-                                                        &session,
-                                                        "SHOW ERRORS",
-                                                        "No errors found.",
-                                                    );
-                                                } else {
-                                                    let (heading_enabled, feedback_enabled) =
-                                                        SqlEditorWidget::current_output_settings(
-                                                            &session,
-                                                        );
-                                                    let headers =
-                                                        SqlEditorWidget::apply_heading_setting(
-                                                            vec![
-                                                                "LINE".to_string(),
-                                                                "POSITION".to_string(),
-                                                                "TEXT".to_string(),
-                                                            ],
-                                                            heading_enabled,
-                                                        );
-                                                    SqlEditorWidget::emit_select_result(
-                                                        &sender,
-                                                        &session,
-                                                        &conn_name,
-                                                        result_index,
-                                                        "SHOW ERRORS",
-                                                        headers,
-                                                        rows,
-                                                        true,
-                                                        feedback_enabled,
-                                                    );
-                                                    result_index += 1;
-                                                }
-                                            }
-                                            Err(err) => {
-                                                SqlEditorWidget::emit_script_message(
-                                                    &sender,
-                                                    &session,
-                                                    "SHOW ERRORS",
-                                                    &format!("Error: {}", err),
-                                                );
-                                                command_error = true;
-                                            }
-                                        }
-                                    } else {
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            "SHOW ERRORS",
-                                            "Error: No compiled object found to show errors.",
-                                        );
-                                        command_error = true;
-                                    }
-                                }
-                                ToolCommand::ShowUser => {
-                                    // This command needs a connection
-                                    let conn = match conn_opt.as_ref() {
-                                        Some(c) => c,
-                                        None => {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                "SHOW USER",
-                                                "Error: Not connected to database",
-                                            );
-                                            continue;
-                                        }
-                                    };
-
-                                    let sql = "SELECT USER FROM DUAL";
-                                    let user_result: Result<String, OracleError> = (|| {
-                                        let mut stmt = conn.statement(sql).build()?;
-                                        let row = stmt.query_row(&[])?;
-                                        let user: String = row.get(0)?;
-                                        Ok(user)
-                                    })(
-                                    );
-                                    match user_result {
-                                        Ok(user) => {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                "SHOW USER",
-                                                &format!("USER: {}", user),
-                                            );
-                                        }
-                                        Err(err) => {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                "SHOW USER",
-                                                &format!("Error: {}", err),
-                                            );
-                                            command_error = true;
-                                        }
-                                    }
-                                }
-                                ToolCommand::ShowAll => {
-                                    let (
-                                        server_output,
-                                        define_enabled,
-                                        define_char,
-                                        scan_enabled,
-                                        verify_enabled,
-                                        echo_enabled,
-                                        timing_enabled,
-                                        feedback_enabled,
-                                        heading_enabled,
-                                        pagesize,
-                                        linesize,
-                                        trimspool_enabled,
-                                        trimout_enabled,
-                                        sqlblanklines_enabled,
-                                        tab_enabled,
-                                        colsep,
-                                        null_text,
-                                        break_column,
-                                        compute_config,
-                                        continue_on_error,
-                                        spool_path,
-                                    ) = match session.lock() {
-                                        Ok(guard) => (
-                                            guard.server_output.clone(),
-                                            guard.define_enabled,
-                                            guard.define_char,
-                                            guard.scan_enabled,
-                                            guard.verify_enabled,
-                                            guard.echo_enabled,
-                                            guard.timing_enabled,
-                                            guard.feedback_enabled,
-                                            guard.heading_enabled,
-                                            guard.pagesize,
-                                            guard.linesize,
-                                            guard.trimspool_enabled,
-                                            guard.trimout_enabled,
-                                            guard.sqlblanklines_enabled,
-                                            guard.tab_enabled,
-                                            guard.colsep.clone(),
-                                            guard.null_text.clone(),
-                                            guard.break_column.clone(),
-                                            guard.compute.clone(),
-                                            guard.continue_on_error,
-                                            guard.spool_path.clone(),
-                                        ),
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let guard = poisoned.into_inner();
-                                            (
-                                                guard.server_output.clone(),
-                                                guard.define_enabled,
-                                                guard.define_char,
-                                                guard.scan_enabled,
-                                                guard.verify_enabled,
-                                                guard.echo_enabled,
-                                                guard.timing_enabled,
-                                                guard.feedback_enabled,
-                                                guard.heading_enabled,
-                                                guard.pagesize,
-                                                guard.linesize,
-                                                guard.trimspool_enabled,
-                                                guard.trimout_enabled,
-                                                guard.sqlblanklines_enabled,
-                                                guard.tab_enabled,
-                                                guard.colsep.clone(),
-                                                guard.null_text.clone(),
-                                                guard.break_column.clone(),
-                                                guard.compute.clone(),
-                                                guard.continue_on_error,
-                                                guard.spool_path.clone(),
-                                            )
-                                        }
-                                    };
-
-                                    let autocommit_enabled = conn_guard.auto_commit();
-
-                                    let serveroutput_line = if server_output.enabled {
-                                        if server_output.size == 0 {
-                                            "SERVEROUTPUT ON SIZE UNLIMITED".to_string()
-                                        } else {
-                                            format!("SERVEROUTPUT ON SIZE {}", server_output.size)
-                                        }
-                                    } else {
-                                        "SERVEROUTPUT OFF".to_string()
-                                    };
-
-                                    let spool_line = match spool_path {
-                                        Some(path) => format!("SPOOL {}", path.display()),
-                                        None => "SPOOL OFF".to_string(),
-                                    };
-
-                                    let lines = vec![
-                                        format!(
-                                            "AUTOCOMMIT {}",
-                                            if autocommit_enabled { "ON" } else { "OFF" }
-                                        ),
-                                        serveroutput_line,
-                                        if define_enabled {
-                                            format!("DEFINE '{}'", define_char)
-                                        } else {
-                                            "DEFINE OFF".to_string()
-                                        },
-                                        format!("SCAN {}", if scan_enabled { "ON" } else { "OFF" }),
-                                        format!(
-                                            "VERIFY {}",
-                                            if verify_enabled { "ON" } else { "OFF" }
-                                        ),
-                                        format!("ECHO {}", if echo_enabled { "ON" } else { "OFF" }),
-                                        format!(
-                                            "TIMING {}",
-                                            if timing_enabled { "ON" } else { "OFF" }
-                                        ),
-                                        format!(
-                                            "FEEDBACK {}",
-                                            if feedback_enabled { "ON" } else { "OFF" }
-                                        ),
-                                        format!(
-                                            "HEADING {}",
-                                            if heading_enabled { "ON" } else { "OFF" }
-                                        ),
-                                        format!("PAGESIZE {}", pagesize),
-                                        format!("LINESIZE {}", linesize),
-                                        format!(
-                                            "TRIMSPOOL {}",
-                                            if trimspool_enabled { "ON" } else { "OFF" }
-                                        ),
-                                        format!(
-                                            "TRIMOUT {}",
-                                            if trimout_enabled { "ON" } else { "OFF" }
-                                        ),
-                                        format!(
-                                            "SQLBLANKLINES {}",
-                                            if sqlblanklines_enabled { "ON" } else { "OFF" }
-                                        ),
-                                        format!("TAB {}", if tab_enabled { "ON" } else { "OFF" }),
-                                        format!("COLSEP {}", colsep),
-                                        format!("NULL {}", null_text),
-                                        match break_column {
-                                            Some(column) => format!("BREAK ON {}", column),
-                                            None => "BREAK OFF".to_string(),
-                                        },
-                                        match compute_config {
-                                            Some(config) => {
-                                                let mode_text = match config.mode {
-                                                    crate::db::ComputeMode::Sum => "SUM",
-                                                    crate::db::ComputeMode::Count => "COUNT",
-                                                };
-                                                match (
-                                                    config.of_column.as_deref(),
-                                                    config.on_column.as_deref(),
-                                                ) {
-                                                    (Some(of_col), Some(on_col)) => format!(
-                                                        "COMPUTE {} OF {} ON {}",
-                                                        mode_text, of_col, on_col
-                                                    ),
-                                                    _ => format!("COMPUTE {}", mode_text),
-                                                }
-                                            }
-                                            None => "COMPUTE OFF".to_string(),
-                                        },
-                                        format!(
-                                            "ERRORCONTINUE {}",
-                                            if continue_on_error { "ON" } else { "OFF" }
-                                        ),
-                                        spool_line,
-                                    ];
-
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SHOW ALL",
-                                        &lines.join("\n"),
-                                    );
-                                }
-                                ToolCommand::Describe { name } => {
-                                    let conn = match conn_opt.as_ref() {
-                                        Some(c) => c,
-                                        None => {
-                                            if script_mode {
-                                                SqlEditorWidget::emit_script_message(
-                                                    &sender,
-                                                    &session,
-                                                    "DESCRIBE",
-                                                    "Error: Not connected to database",
-                                                );
-                                            } else {
-                                                let emitted =
-                                                    SqlEditorWidget::emit_non_select_result(
-                                                        &sender,
-                                                        &session,
-                                                        &conn_name,
-                                                        result_index,
-                                                        &format!("DESCRIBE {}", name),
-                                                        "Error: Not connected to database"
-                                                            .to_string(),
-                                                        false,
-                                                        false,
-                                                        false,
-                                                    );
-                                                if emitted {
-                                                    result_index += 1;
-                                                }
-                                            }
-                                            continue;
-                                        }
-                                    };
-                                    let title = format!("DESCRIBE {}", name);
-                                    match QueryExecutor::describe_object(conn.as_ref(), &name) {
-                                        Ok(columns) => {
-                                            if columns.is_empty() {
-                                                if script_mode {
-                                                    SqlEditorWidget::emit_script_message(
-                                                        &sender,
-                                                        &session,
-                                                        &title,
-                                                        "Error: Object not found.",
-                                                    );
-                                                } else {
-                                                    let emitted =
-                                                        SqlEditorWidget::emit_non_select_result(
-                                                            &sender,
-                                                            &session,
-                                                            &conn_name,
-                                                            result_index,
-                                                            &title,
-                                                            "Error: Object not found.".to_string(),
-                                                            false,
-                                                            false,
-                                                            false,
-                                                        );
-                                                    if emitted {
-                                                        result_index += 1;
-                                                    }
-                                                }
-                                                command_error = true;
-                                            } else {
-                                                let rows = columns
-                                                    .into_iter()
-                                                    .map(|col| {
-                                                        let type_display = col.get_type_display();
-                                                        let TableColumnDetail {
-                                                            name,
-                                                            nullable,
-                                                            is_primary_key,
-                                                            ..
-                                                        } = col;
-                                                        vec![
-                                                            name,
-                                                            type_display,
-                                                            if nullable {
-                                                                "YES".to_string()
-                                                            } else {
-                                                                "NO".to_string()
-                                                            },
-                                                            if is_primary_key {
-                                                                "PK".to_string()
-                                                            } else {
-                                                                String::new()
-                                                            },
-                                                        ]
-                                                    })
-                                                    .collect::<Vec<Vec<String>>>();
-                                                let (heading_enabled, feedback_enabled) =
-                                                    SqlEditorWidget::current_output_settings(
-                                                        &session,
-                                                    );
-                                                let headers =
-                                                    SqlEditorWidget::apply_heading_setting(
-                                                        vec![
-                                                            "COLUMN".to_string(),
-                                                            "TYPE".to_string(),
-                                                            "NULLABLE".to_string(),
-                                                            "PK".to_string(),
-                                                        ],
-                                                        heading_enabled,
-                                                    );
-                                                SqlEditorWidget::emit_select_result(
-                                                    &sender,
-                                                    &session,
-                                                    &conn_name,
-                                                    result_index,
-                                                    &title,
-                                                    headers,
-                                                    rows,
-                                                    true,
-                                                    feedback_enabled,
-                                                );
-                                                result_index += 1;
-                                            }
-                                        }
-                                        Err(err) => {
-                                            if script_mode {
-                                                SqlEditorWidget::emit_script_message(
-                                                    &sender,
-                                                    &session,
-                                                    &title,
-                                                    &format!("Error: {}", err),
-                                                );
-                                            } else {
-                                                let emitted =
-                                                    SqlEditorWidget::emit_non_select_result(
-                                                        &sender,
-                                                        &session,
-                                                        &conn_name,
-                                                        result_index,
-                                                        &title,
-                                                        format!("Error: {}", err),
-                                                        false,
-                                                        false,
-                                                        false,
-                                                    );
-                                                if emitted {
-                                                    result_index += 1;
-                                                }
-                                            }
-                                            command_error = true;
-                                        }
-                                    }
-                                }
-                                ToolCommand::Prompt { text } => {
-                                    let mut output_text = text;
-                                    let (define_enabled, scan_enabled) = match session.lock() {
-                                        Ok(guard) => (guard.define_enabled, guard.scan_enabled),
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let guard = poisoned.into_inner();
-                                            (guard.define_enabled, guard.scan_enabled)
-                                        }
-                                    };
-                                    if define_enabled && scan_enabled && !output_text.is_empty() {
-                                        match SqlEditorWidget::apply_define_substitution(
-                                            &output_text,
-                                            &session,
-                                            &sender,
-                                        ) {
-                                            Ok(updated) => {
-                                                output_text = updated;
-                                            }
-                                            Err(message) => {
-                                                SqlEditorWidget::emit_script_message(
-                                                    &sender,
-                                                    &session,
-                                                    "PROMPT",
-                                                    &format!("Error: {}", message),
-                                                );
-                                                command_error = true;
-                                            }
-                                        }
-                                    }
-                                    if !command_error {
-                                        SqlEditorWidget::emit_script_output(
-                                            &sender,
-                                            &session,
-                                            vec![output_text],
-                                        );
-                                    }
-                                }
-                                ToolCommand::Pause { message } => {
-                                    let prompt_text = message
-                                        .filter(|text| !text.trim().is_empty())
-                                        .unwrap_or_else(|| "Press ENTER to continue.".to_string());
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "PAUSE",
-                                        &prompt_text,
-                                    );
-                                    match SqlEditorWidget::prompt_for_input_with_sender(
-                                        &sender,
-                                        &prompt_text,
-                                    ) {
-                                        Ok(_) => {}
-                                        Err(_) => {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                "PAUSE",
-                                                "Error: PAUSE cancelled.",
-                                            );
-                                            command_error = true;
-                                        }
-                                    }
-                                }
-                                ToolCommand::Accept { name, prompt } => {
-                                    let prompt_text = prompt
-                                        .unwrap_or_else(|| format!("Enter value for {}:", name));
-                                    match SqlEditorWidget::prompt_for_input_with_sender(
-                                        &sender,
-                                        &prompt_text,
-                                    ) {
-                                        Ok(value) => {
-                                            let key = SessionState::normalize_name(&name);
-                                            match session.lock() {
-                                                Ok(mut guard) => {
-                                                    guard
-                                                        .define_vars
-                                                        .insert(key.clone(), value.clone());
-                                                }
-                                                Err(poisoned) => {
-                                                    eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                    let mut guard = poisoned.into_inner();
-                                                    guard
-                                                        .define_vars
-                                                        .insert(key.clone(), value.clone());
-                                                }
-                                            }
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                &format!("ACCEPT {}", key),
-                                                &format!("Value assigned to {}", key),
-                                            );
-                                        }
-                                        Err(message) => {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                &format!("ACCEPT {}", name),
-                                                &format!("Error: {}", message),
-                                            );
-                                            command_error = true;
-                                        }
-                                    }
-                                }
-                                ToolCommand::Define { name, value } => {
-                                    let (define_enabled, scan_enabled) = match session.lock() {
-                                        Ok(guard) => (guard.define_enabled, guard.scan_enabled),
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let guard = poisoned.into_inner();
-                                            (guard.define_enabled, guard.scan_enabled)
-                                        }
-                                    };
-                                    let mut resolved_value = value;
-                                    if define_enabled && scan_enabled {
-                                        match SqlEditorWidget::apply_define_substitution(
-                                            &resolved_value,
-                                            &session,
-                                            &sender,
-                                        ) {
-                                            Ok(updated) => {
-                                                resolved_value = updated;
-                                            }
-                                            Err(message) => {
-                                                SqlEditorWidget::emit_script_message(
-                                                    &sender,
-                                                    &session,
-                                                    &format!("DEFINE {}", name),
-                                                    &format!("Error: {}", message),
-                                                );
-                                                command_error = true;
-                                            }
-                                        }
-                                    }
-                                    let key = SessionState::normalize_name(&name);
-                                    if !command_error {
-                                        match session.lock() {
-                                            Ok(mut guard) => {
-                                                guard
-                                                    .define_vars
-                                                    .insert(key.clone(), resolved_value.clone());
-                                            }
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                "Warning: session state lock was poisoned; recovering."
-                                            );
-                                                let mut guard = poisoned.into_inner();
-                                                guard
-                                                    .define_vars
-                                                    .insert(key.clone(), resolved_value.clone());
-                                            }
-                                        }
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            &format!("DEFINE {}", key),
-                                            &format!("Defined {} = {}", key, resolved_value),
-                                        );
-                                    }
-                                }
-                                ToolCommand::Undefine { name } => {
-                                    let key = SessionState::normalize_name(&name);
-                                    match session.lock() {
-                                        Ok(mut guard) => {
-                                            guard.define_vars.remove(&key);
-                                        }
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let mut guard = poisoned.into_inner();
-                                            guard.define_vars.remove(&key);
-                                        }
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        &format!("UNDEFINE {}", key),
-                                        &format!("Undefined {}", key),
-                                    );
-                                }
-                                ToolCommand::ColumnNewValue {
-                                    column_name,
-                                    variable_name,
-                                } => {
-                                    let column_key = SessionState::normalize_name(&column_name);
-                                    let variable_key = SessionState::normalize_name(&variable_name);
-                                    match session.lock() {
-                                        Ok(mut guard) => {
-                                            guard
-                                                .column_new_values
-                                                .insert(column_key.clone(), variable_key.clone());
-                                        }
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let mut guard = poisoned.into_inner();
-                                            guard
-                                                .column_new_values
-                                                .insert(column_key.clone(), variable_key.clone());
-                                        }
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        &format!(
-                                            "COLUMN {} NEW_VALUE {}",
-                                            column_key, variable_key
-                                        ),
-                                        &format!(
-                                            "Registered NEW_VALUE mapping: {} -> {}",
-                                            column_key, variable_key
-                                        ),
-                                    );
-                                }
-                                ToolCommand::BreakOn { column_name } => {
-                                    let key = SessionState::normalize_name(&column_name);
-                                    match session.lock() {
-                                        Ok(mut guard) => {
-                                            guard.break_column = Some(key.clone());
-                                        }
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let mut guard = poisoned.into_inner();
-                                            guard.break_column = Some(key.clone());
-                                        }
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "BREAK",
-                                        &format!("BREAK ON {}", key),
-                                    );
-                                }
-                                ToolCommand::BreakOff => {
-                                    match session.lock() {
-                                        Ok(mut guard) => {
-                                            guard.break_column = None;
-                                        }
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let mut guard = poisoned.into_inner();
-                                            guard.break_column = None;
-                                        }
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "BREAK",
-                                        "BREAK OFF",
-                                    );
-                                }
-                                ToolCommand::ClearBreaks => {
-                                    match session.lock() {
-                                        Ok(mut guard) => {
-                                            guard.break_column = None;
-                                        }
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let mut guard = poisoned.into_inner();
-                                            guard.break_column = None;
-                                        }
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "CLEAR",
-                                        "BREAKS cleared",
-                                    );
-                                }
-                                ToolCommand::ClearComputes => {
-                                    match session.lock() {
-                                        Ok(mut guard) => {
-                                            guard.compute = None;
-                                        }
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let mut guard = poisoned.into_inner();
-                                            guard.compute = None;
-                                        }
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "CLEAR",
-                                        "COMPUTES cleared",
-                                    );
-                                }
-                                ToolCommand::ClearBreaksComputes => {
-                                    match session.lock() {
-                                        Ok(mut guard) => {
-                                            guard.break_column = None;
-                                            guard.compute = None;
-                                        }
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let mut guard = poisoned.into_inner();
-                                            guard.break_column = None;
-                                            guard.compute = None;
-                                        }
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "CLEAR",
-                                        "BREAKS and COMPUTES cleared",
-                                    );
-                                }
-                                ToolCommand::Compute {
-                                    mode,
-                                    of_column,
-                                    on_column,
-                                } => {
-                                    match session.lock() {
-                                        Ok(mut guard) => {
-                                            guard.compute = Some(crate::db::ComputeConfig {
-                                                mode,
-                                                of_column: of_column.clone(),
-                                                on_column: on_column.clone(),
-                                            });
-                                        }
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let mut guard = poisoned.into_inner();
-                                            guard.compute = Some(crate::db::ComputeConfig {
-                                                mode,
-                                                of_column: of_column.clone(),
-                                                on_column: on_column.clone(),
-                                            });
-                                        }
-                                    }
-                                    let mode_text = match mode {
-                                        crate::db::ComputeMode::Sum => "COMPUTE SUM",
-                                        crate::db::ComputeMode::Count => "COMPUTE COUNT",
-                                    };
-                                    let label = match (of_column.as_deref(), on_column.as_deref()) {
-                                        (Some(of_col), Some(on_col)) => {
-                                            format!("{} OF {} ON {}", mode_text, of_col, on_col)
-                                        }
-                                        _ => mode_text.to_string(),
-                                    };
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender, &session, "COMPUTE", &label,
-                                    );
-                                }
-                                ToolCommand::ComputeOff => {
-                                    match session.lock() {
-                                        Ok(mut guard) => {
-                                            guard.compute = None;
-                                        }
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            let mut guard = poisoned.into_inner();
-                                            guard.compute = None;
-                                        }
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "COMPUTE",
-                                        "COMPUTE OFF",
-                                    );
-                                }
-                                ToolCommand::SetErrorContinue { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.continue_on_error = enabled;
-                                    }
-                                    continue_on_error = enabled;
-
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET ERRORCONTINUE",
-                                        &format!(
-                                            "ERRORCONTINUE {}",
-                                            if enabled { "ON" } else { "OFF" }
-                                        ),
-                                    );
-                                }
-                                ToolCommand::SetAutoCommit { enabled } => {
-                                    conn_guard.set_auto_commit(enabled);
-                                    auto_commit = enabled;
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET AUTOCOMMIT",
-                                        if enabled {
-                                            "Auto-commit enabled"
-                                        } else {
-                                            "Auto-commit disabled"
-                                        },
-                                    );
-                                    let _ =
-                                        sender.send(QueryProgress::AutoCommitChanged { enabled });
-                                    app::awake();
-                                }
-                                ToolCommand::SetDefine {
-                                    enabled,
-                                    define_char,
-                                } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.define_enabled = enabled;
-                                        if let Some(ch) = define_char {
-                                            guard.define_char = ch;
-                                        }
-                                    }
-                                    let msg = if let Some(ch) = define_char {
-                                        format!("DEFINE '{}'", ch)
-                                    } else {
-                                        format!("DEFINE {}", if enabled { "ON" } else { "OFF" })
-                                    };
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET DEFINE",
-                                        &msg,
-                                    );
-                                }
-                                ToolCommand::SetScan { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.scan_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET SCAN",
-                                        &format!("SCAN {}", if enabled { "ON" } else { "OFF" }),
-                                    );
-                                }
-                                ToolCommand::SetVerify { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.verify_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET VERIFY",
-                                        &format!("VERIFY {}", if enabled { "ON" } else { "OFF" }),
-                                    );
-                                }
-                                ToolCommand::SetEcho { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.echo_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET ECHO",
-                                        &format!("ECHO {}", if enabled { "ON" } else { "OFF" }),
-                                    );
-                                }
-                                ToolCommand::SetTiming { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.timing_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET TIMING",
-                                        &format!("TIMING {}", if enabled { "ON" } else { "OFF" }),
-                                    );
-                                }
-                                ToolCommand::SetFeedback { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.feedback_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET FEEDBACK",
-                                        &format!("FEEDBACK {}", if enabled { "ON" } else { "OFF" }),
-                                    );
-                                }
-                                ToolCommand::SetHeading { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.heading_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET HEADING",
-                                        &format!("HEADING {}", if enabled { "ON" } else { "OFF" }),
-                                    );
-                                }
-                                ToolCommand::SetPageSize { size } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.pagesize = size;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET PAGESIZE",
-                                        &format!("PAGESIZE {}", size),
-                                    );
-                                }
-                                ToolCommand::SetLineSize { size } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.linesize = size;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET LINESIZE",
-                                        &format!("LINESIZE {}", size),
-                                    );
-                                }
-                                ToolCommand::SetTrimSpool { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.trimspool_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET TRIMSPOOL",
-                                        &format!(
-                                            "TRIMSPOOL {}",
-                                            if enabled { "ON" } else { "OFF" }
-                                        ),
-                                    );
-                                }
-                                ToolCommand::SetTrimOut { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.trimout_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET TRIMOUT",
-                                        &format!("TRIMOUT {}", if enabled { "ON" } else { "OFF" }),
-                                    );
-                                }
-                                ToolCommand::SetSqlBlankLines { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.sqlblanklines_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET SQLBLANKLINES",
-                                        &format!(
-                                            "SQLBLANKLINES {}",
-                                            if enabled { "ON" } else { "OFF" }
-                                        ),
-                                    );
-                                }
-                                ToolCommand::SetTab { enabled } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.tab_enabled = enabled;
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET TAB",
-                                        &format!("TAB {}", if enabled { "ON" } else { "OFF" }),
-                                    );
-                                }
-                                ToolCommand::SetColSep { separator } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.colsep = separator.clone();
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET COLSEP",
-                                        &format!("COLSEP {}", separator),
-                                    );
-                                }
-                                ToolCommand::SetNull { null_text } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.null_text = null_text.clone();
-                                    }
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "SET NULL",
-                                        &format!("NULL {}", null_text),
-                                    );
-                                }
-                                ToolCommand::Spool { path, append } => match path {
-                                    Some(path) => {
-                                        let target_path = if Path::new(&path).is_absolute() {
-                                            PathBuf::from(&path)
-                                        } else {
-                                            frame.base_dir.join(&path)
-                                        };
-                                        match session.lock() {
-                                            Ok(mut guard) => {
-                                                guard.spool_path = Some(target_path.clone());
-                                                guard.spool_truncate = !append;
-                                            }
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                "Warning: session state lock was poisoned; recovering."
-                                            );
-                                                let mut guard = poisoned.into_inner();
-                                                guard.spool_path = Some(target_path.clone());
-                                                guard.spool_truncate = !append;
-                                            }
-                                        }
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            "SPOOL",
-                                            &format!(
-                                                "Spooling output to {} ({})",
-                                                target_path.display(),
-                                                if append { "append" } else { "replace" }
-                                            ),
-                                        );
-                                    }
-                                    None if append => {
-                                        let has_spool_target = match session.lock() {
-                                            Ok(mut guard) => {
-                                                let has_target = guard.spool_path.is_some();
-                                                guard.spool_truncate = false;
-                                                has_target
-                                            }
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                "Warning: session state lock was poisoned; recovering."
-                                            );
-                                                let mut guard = poisoned.into_inner();
-                                                let has_target = guard.spool_path.is_some();
-                                                guard.spool_truncate = false;
-                                                has_target
-                                            }
-                                        };
-                                        if has_spool_target {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                "SPOOL",
-                                                "Spooling in append mode",
-                                            );
-                                        } else {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                "SPOOL APPEND",
-                                                "Error: No active spool file. Use SPOOL <file> APPEND.",
-                                            );
-                                            command_error = true;
-                                        }
-                                    }
-                                    None => {
-                                        match session.lock() {
-                                            Ok(mut guard) => {
-                                                guard.spool_path = None;
-                                                guard.spool_truncate = false;
-                                            }
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                "Warning: session state lock was poisoned; recovering."
-                                            );
-                                                let mut guard = poisoned.into_inner();
-                                                guard.spool_path = None;
-                                                guard.spool_truncate = false;
-                                            }
-                                        }
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            "SPOOL",
-                                            "Spooling disabled",
-                                        );
-                                    }
-                                },
-                                ToolCommand::WheneverSqlError { exit, action } => {
-                                    if exit
-                                        && action
-                                            .as_deref()
-                                            .map(|v| v.trim().eq_ignore_ascii_case("SQL.SQLCODE"))
-                                            .unwrap_or(false)
-                                        && !script_mode
-                                    {
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            "WHENEVER SQLERROR",
-                                            "Error: EXIT SQL.SQLCODE is supported only in batch(script) execution.",
-                                        );
-                                        command_error = true;
-                                    } else {
-                                        {
-                                            let mut guard = match session.lock() {
-                                                Ok(guard) => guard,
-                                                Err(poisoned) => {
-                                                    eprintln!(
-                                                        "Warning: session state lock was poisoned; recovering."
-                                                    );
-                                                    poisoned.into_inner()
-                                                }
-                                            };
-                                            guard.continue_on_error = !exit;
-                                        }
-                                        continue_on_error = !exit;
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            "WHENEVER SQLERROR",
-                                            if exit { "Mode EXIT" } else { "Mode CONTINUE" },
-                                        );
-                                    }
-                                }
-                                ToolCommand::WheneverOsError { exit } => {
-                                    {
-                                        let mut guard = match session.lock() {
-                                            Ok(guard) => guard,
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                poisoned.into_inner()
-                                            }
-                                        };
-                                        guard.continue_on_error = !exit;
-                                    }
-                                    continue_on_error = !exit;
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "WHENEVER OSERROR",
-                                        if exit { "Mode EXIT" } else { "Mode CONTINUE" },
-                                    );
-                                }
-                                ToolCommand::Exit => {
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "EXIT",
-                                        "Execution stopped.",
-                                    );
-                                    stop_execution = true;
-                                }
-                                ToolCommand::Quit => {
-                                    SqlEditorWidget::emit_script_message(
-                                        &sender,
-                                        &session,
-                                        "QUIT",
-                                        "Execution stopped.",
-                                    );
-                                    stop_execution = true;
-                                }
-                                ToolCommand::Connect {
-                                    username,
-                                    password,
-                                    host,
-                                    port,
-                                    service_name,
-                                } => {
-                                    let conn_info = ConnectionInfo {
-                                        name: format!("{}@{}", username, host),
-                                        username,
-                                        password,
-                                        host,
-                                        port,
-                                        service_name,
-                                    };
-
-                                    // Use the already-held conn_guard to avoid deadlock
-                                    match conn_guard.connect(conn_info.clone()) {
-                                        Ok(_) => {
-                                            conn_opt = conn_guard.get_connection();
-                                            if conn_guard.is_connected() {
-                                                conn_name = conn_guard.get_info().name.clone();
-                                            } else {
-                                                conn_name.clear();
-                                            }
-                                            // Update cancel connection so break_execution() uses the new connection
-                                            if let Some(ref conn) = conn_opt {
-                                                SqlEditorWidget::set_current_query_connection(
-                                                    &current_query_connection,
-                                                    Some(Arc::clone(conn)),
-                                                );
-                                            }
-                                            match session.lock() {
-                                                Ok(mut guard) => guard.reset(),
-                                                Err(poisoned) => {
-                                                    eprintln!(
-                                                    "Warning: session state lock was poisoned; recovering."
-                                                );
-                                                    poisoned.into_inner().reset();
-                                                }
-                                            }
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                "CONNECT",
-                                                &format!(
-                                                    "Connected to {}",
-                                                    conn_info.display_string()
-                                                ),
-                                            );
-                                            previous_timeout = conn_opt
-                                                .as_ref()
-                                                .and_then(|c| c.call_timeout().ok())
-                                                .flatten();
-                                            if let Some(conn) = conn_opt.as_ref() {
-                                                let _ = conn.set_call_timeout(query_timeout);
-                                                if let Err(err) =
-                                                    SqlEditorWidget::sync_serveroutput_with_session(
-                                                        conn.as_ref(),
-                                                        &session,
-                                                    )
-                                                {
-                                                    eprintln!(
-                                                        "Failed to apply SERVEROUTPUT after CONNECT: {err}"
-                                                    );
-                                                }
-                                            }
-                                            let _ = sender.send(QueryProgress::ConnectionChanged {
-                                                info: Some(conn_info.clone()),
-                                            });
-                                            app::awake();
-                                        }
-                                        Err(err) => {
-                                            let error_msg = format!("Connection failed: {}", err);
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender, &session, "CONNECT", &error_msg,
-                                            );
-                                            command_error = true;
-                                        }
-                                    }
-                                }
-                                ToolCommand::Disconnect => {
-                                    // Use the already-held conn_guard to avoid deadlock
-                                    if conn_guard.is_connected() {
-                                        // Clear cancel connection before disconnect
-                                        SqlEditorWidget::set_current_query_connection(
-                                            &current_query_connection,
-                                            None,
-                                        );
-                                        conn_guard.disconnect();
-                                        conn_opt = conn_guard.get_connection();
-                                        if conn_guard.is_connected() {
-                                            conn_name = conn_guard.get_info().name.clone();
-                                        } else {
-                                            conn_name.clear();
-                                        }
-                                        match session.lock() {
-                                            Ok(mut guard) => guard.reset(),
-                                            Err(poisoned) => {
-                                                eprintln!(
-                                                "Warning: session state lock was poisoned; recovering."
-                                            );
-                                                poisoned.into_inner().reset();
-                                            }
-                                        }
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            "DISCONNECT",
-                                            "Disconnected from database",
-                                        );
-                                        previous_timeout = conn_opt
-                                            .as_ref()
-                                            .and_then(|c| c.call_timeout().ok())
-                                            .flatten();
-                                        let _ = sender
-                                            .send(QueryProgress::ConnectionChanged { info: None });
-                                        app::awake();
-                                    } else {
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            "DISCONNECT",
-                                            "Not connected to any database",
-                                        );
-                                    }
-                                }
-                                ToolCommand::RunScript {
-                                    path,
-                                    relative_to_caller,
-                                } => {
-                                    let base_dir = if relative_to_caller {
-                                        frame.base_dir.clone()
-                                    } else {
-                                        working_dir.clone()
-                                    };
-                                    let target_path = if Path::new(&path).is_absolute() {
-                                        PathBuf::from(&path)
-                                    } else {
-                                        base_dir.join(&path)
-                                    };
-                                    match fs::read_to_string(&target_path) {
-                                        Ok(contents) => {
-                                            let script_items =
-                                                QueryExecutor::split_script_items(&contents);
-                                            let script_dir = target_path
-                                                .parent()
-                                                .unwrap_or(&base_dir)
-                                                .to_path_buf();
-                                            frames.push(ScriptFrame {
-                                                items: script_items,
-                                                index: 0,
-                                                base_dir: script_dir,
-                                            });
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                if relative_to_caller { "@@" } else { "@" },
-                                                &format!(
-                                                    "Running script {}",
-                                                    target_path.display()
-                                                ),
-                                            );
-                                        }
-                                        Err(err) => {
-                                            SqlEditorWidget::emit_script_message(
-                                                &sender,
-                                                &session,
-                                                if relative_to_caller { "@@" } else { "@" },
-                                                &format!(
-                                                    "Error: Failed to read script {}: {}",
-                                                    target_path.display(),
-                                                    err
-                                                ),
-                                            );
-                                            command_error = true;
-                                        }
-                                    }
-                                }
-                                ToolCommand::Unsupported {
-                                    raw,
-                                    message,
-                                    is_error,
-                                } => {
-                                    if is_error {
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            &raw,
-                                            &format!("Error: {}", message),
-                                        );
-                                        command_error = true;
-                                    } else {
-                                        SqlEditorWidget::emit_script_message(
-                                            &sender,
-                                            &session,
-                                            &raw,
-                                            &format!("Warning: {}", message),
-                                        );
-                                    }
-                                }
-                            }
-
-                            if command_error && !continue_on_error {
-                                stop_execution = true;
-                            }
-                        }
-                        ScriptItem::Statement(statement) => {
-                            // For statements, we need a connection
-                            let conn = match conn_opt.as_ref() {
-                                Some(c) => c,
-                                None => {
-                                    // This shouldn't happen as we checked earlier
-                                    eprintln!(
-                                        "Error: No connection available for statement execution"
-                                    );
-                                    let emitted = SqlEditorWidget::emit_non_select_result(
-                                        &sender,
-                                        &session,
-                                        &conn_name,
-                                        result_index,
-                                        &statement,
-                                        "Error: Not connected to database".to_string(),
-                                        false,
-                                        false,
-                                        script_mode,
-                                    );
-                                    if emitted {
-                                        result_index += 1;
-                                    }
-                                    stop_execution = true;
-                                    continue;
-                                }
-                            };
-
-                            let trimmed = statement.trim_start_matches(';').trim();
-                            if trimmed.is_empty() {
-                                continue;
-                            }
-
-                            let mut sql_text = trimmed.to_string();
-                            let (define_enabled, scan_enabled, verify_enabled) =
-                                match session.lock() {
-                                    Ok(guard) => (
-                                        guard.define_enabled,
-                                        guard.scan_enabled,
-                                        guard.verify_enabled,
-                                    ),
-                                    Err(poisoned) => {
-                                        eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                        let guard = poisoned.into_inner();
-                                        (
-                                            guard.define_enabled,
-                                            guard.scan_enabled,
-                                            guard.verify_enabled,
-                                        )
-                                    }
-                                };
-                            if define_enabled && scan_enabled {
-                                let sql_before = sql_text.clone();
-                                match SqlEditorWidget::apply_define_substitution(
-                                    &sql_text, &session, &sender,
-                                ) {
-                                    Ok(updated) => {
-                                        if verify_enabled && updated != sql_before {
-                                            SqlEditorWidget::emit_script_output(
-                                                &sender,
-                                                &session,
-                                                vec![
-                                                    format!("old: {}", sql_before),
-                                                    format!("new: {}", updated),
-                                                ],
-                                            );
-                                        }
-                                        sql_text = updated;
-                                    }
-                                    Err(message) => {
-                                        let emitted = SqlEditorWidget::emit_non_select_result(
-                                            &sender,
-                                            &session,
-                                            &conn_name,
-                                            result_index,
-                                            trimmed,
-                                            format!("Error: {}", message),
-                                            false,
-                                            false,
-                                            script_mode,
-                                        );
-                                        if emitted {
-                                            result_index += 1;
-                                        }
-                                        if !continue_on_error {
-                                            stop_execution = true;
-                                        }
-                                        continue;
-                                    }
-                                }
-                            }
-
-                            let cleaned = SqlEditorWidget::strip_leading_comments(&sql_text);
-                            let upper = cleaned.to_uppercase();
-
-                            if upper.starts_with("COMMIT") {
-                                let mut timed_out = false;
-                                let statement_start = Instant::now();
-                                let mut result = match conn.commit() {
-                                    Ok(()) => QueryResult {
-                                        sql: sql_text.to_string(),
-                                        columns: vec![],
-                                        rows: vec![],
-                                        row_count: 0,
-                                        execution_time: Duration::from_secs(0),
-                                        message: "Commit complete".to_string(),
-                                        is_select: false,
-                                        success: true,
-                                    },
-                                    Err(err) => {
-                                        timed_out = SqlEditorWidget::is_timeout_error(&err);
-                                        QueryResult::new_error(&sql_text, &err.to_string())
-                                    }
-                                };
-                                let timing_duration = statement_start.elapsed();
-                                result.execution_time = timing_duration;
-                                let result_success = result.success;
-                                if script_mode {
-                                    if result_success {
-                                        SqlEditorWidget::emit_script_lines(
-                                            &sender,
-                                            &session,
-                                            &result.message,
-                                        );
-                                    }
-                                    SqlEditorWidget::emit_script_result(
-                                        &sender,
-                                        &conn_name,
-                                        result_index,
-                                        result,
-                                        timed_out,
-                                    );
-                                } else {
-                                    let index = result_index;
-                                    let _ = sender.send(QueryProgress::StatementStart { index });
-                                    app::awake();
-                                    if !result.message.trim().is_empty() {
-                                        SqlEditorWidget::append_spool_output(
-                                            &session,
-                                            &[result.message.clone()],
-                                        );
-                                    }
-                                    let _ = sender.send(QueryProgress::StatementFinished {
-                                        index,
-                                        result,
-                                        connection_name: conn_name.clone(),
-                                        timed_out,
-                                    });
-                                    app::awake();
-                                    result_index += 1;
-                                }
-                                SqlEditorWidget::emit_timing_if_enabled(
-                                    &sender,
-                                    &session,
-                                    timing_duration,
-                                );
-                                if timed_out || (!result_success && !continue_on_error) {
-                                    stop_execution = true;
-                                }
-                                continue;
-                            }
-
-                            if upper.starts_with("ROLLBACK") {
-                                let mut timed_out = false;
-                                let statement_start = Instant::now();
-                                let mut result = match conn.rollback() {
-                                    Ok(()) => QueryResult {
-                                        sql: sql_text.to_string(),
-                                        columns: vec![],
-                                        rows: vec![],
-                                        row_count: 0,
-                                        execution_time: Duration::from_secs(0),
-                                        message: "Rollback complete".to_string(),
-                                        is_select: false,
-                                        success: true,
-                                    },
-                                    Err(err) => {
-                                        timed_out = SqlEditorWidget::is_timeout_error(&err);
-                                        QueryResult::new_error(&sql_text, &err.to_string())
-                                    }
-                                };
-                                let timing_duration = statement_start.elapsed();
-                                result.execution_time = timing_duration;
-                                let result_success = result.success;
-                                if script_mode {
-                                    if result_success {
-                                        SqlEditorWidget::emit_script_lines(
-                                            &sender,
-                                            &session,
-                                            &result.message,
-                                        );
-                                    }
-                                    SqlEditorWidget::emit_script_result(
-                                        &sender,
-                                        &conn_name,
-                                        result_index,
-                                        result,
-                                        timed_out,
-                                    );
-                                } else {
-                                    let index = result_index;
-                                    let _ = sender.send(QueryProgress::StatementStart { index });
-                                    app::awake();
-                                    if !result.message.trim().is_empty() {
-                                        SqlEditorWidget::append_spool_output(
-                                            &session,
-                                            &[result.message.clone()],
-                                        );
-                                    }
-                                    let _ = sender.send(QueryProgress::StatementFinished {
-                                        index,
-                                        result,
-                                        connection_name: conn_name.clone(),
-                                        timed_out,
-                                    });
-                                    app::awake();
-                                    result_index += 1;
-                                }
-                                SqlEditorWidget::emit_timing_if_enabled(
-                                    &sender,
-                                    &session,
-                                    timing_duration,
-                                );
-                                if timed_out || (!result_success && !continue_on_error) {
-                                    stop_execution = true;
-                                }
-                                continue;
-                            }
-
-                            let compiled_object = QueryExecutor::parse_compiled_object(&sql_text);
-                            let is_compiled_plsql = compiled_object.is_some();
-                            if let Some(object) = compiled_object.clone() {
-                                let mut guard = match session.lock() {
-                                    Ok(guard) => guard,
-                                    Err(poisoned) => {
-                                        eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                        poisoned.into_inner()
-                                    }
-                                };
-                                guard.last_compiled = Some(object);
-                            }
-
-                            let exec_call = QueryExecutor::normalize_exec_call(&sql_text);
-                            if exec_call.is_some() {
-                                if let Err(message) =
-                                    QueryExecutor::check_named_positional_mix(&sql_text)
-                                {
-                                    let emitted = SqlEditorWidget::emit_non_select_result(
-                                        &sender,
-                                        &session,
-                                        &conn_name,
-                                        result_index,
-                                        &sql_text,
-                                        format!("Error: {}", message),
-                                        false,
-                                        false,
-                                        script_mode,
-                                    );
-                                    if emitted {
-                                        result_index += 1;
-                                    }
-                                    if !continue_on_error {
-                                        stop_execution = true;
-                                    }
-                                    continue;
-                                }
-                            }
-
-                            let is_plsql_block =
-                                upper.starts_with("BEGIN") || upper.starts_with("DECLARE");
-                            let is_select = QueryExecutor::is_select_statement(&sql_text);
-
-                            if exec_call.is_some() || is_plsql_block {
-                                let mut sql_to_execute =
-                                    exec_call.unwrap_or_else(|| sql_text.to_string());
-                                if is_plsql_block {
-                                    sql_to_execute =
-                                        SqlEditorWidget::ensure_plsql_terminator(&sql_to_execute);
-                                }
-                                let binds = match session.lock() {
-                                    Ok(guard) => {
-                                        QueryExecutor::resolve_binds(&sql_to_execute, &guard)
-                                    }
-                                    Err(poisoned) => {
-                                        eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                        QueryExecutor::resolve_binds(
-                                            &sql_to_execute,
-                                            &poisoned.into_inner(),
-                                        )
-                                    }
-                                };
-
-                                let binds = match binds {
-                                    Ok(binds) => binds,
-                                    Err(message) => {
-                                        let emitted = SqlEditorWidget::emit_non_select_result(
-                                            &sender,
-                                            &session,
-                                            &conn_name,
-                                            result_index,
-                                            &sql_text,
-                                            format!("Error: {}", message),
-                                            false,
-                                            false,
-                                            script_mode,
-                                        );
-                                        if emitted {
-                                            result_index += 1;
-                                        }
-                                        if !continue_on_error {
-                                            stop_execution = true;
-                                        }
-                                        continue;
-                                    }
-                                };
-
-                                let statement_start = Instant::now();
-                                let mut timed_out = false;
-                                let stmt = match QueryExecutor::execute_with_binds(
-                                    conn.as_ref(),
-                                    &sql_to_execute,
-                                    &binds,
-                                ) {
-                                    Ok(stmt) => stmt,
-                                    Err(err) => {
-                                        let cancelled = SqlEditorWidget::is_cancel_error(&err);
-                                        timed_out = SqlEditorWidget::is_timeout_error(&err);
-                                        let message = if cancelled {
-                                            SqlEditorWidget::cancel_message()
-                                        } else if timed_out {
-                                            SqlEditorWidget::timeout_message(query_timeout)
-                                        } else {
-                                            err.to_string()
-                                        };
-                                        if script_mode {
-                                            let result =
-                                                QueryResult::new_error(&sql_text, &message);
-                                            SqlEditorWidget::emit_script_result(
-                                                &sender,
-                                                &conn_name,
-                                                result_index,
-                                                result,
-                                                timed_out,
-                                            );
-                                        } else {
-                                            let index = result_index;
-                                            let _ = sender
-                                                .send(QueryProgress::StatementStart { index });
-                                            app::awake();
-                                            SqlEditorWidget::append_spool_output(
-                                                &session,
-                                                &[message.clone()],
-                                            );
-                                            let result =
-                                                QueryResult::new_error(&sql_text, &message);
-                                            let _ = sender.send(QueryProgress::StatementFinished {
-                                                index,
-                                                result,
-                                                connection_name: conn_name.clone(),
-                                                timed_out,
-                                            });
-                                            app::awake();
-                                            result_index += 1;
-                                        }
-                                        SqlEditorWidget::emit_timing_if_enabled(
-                                            &sender,
-                                            &session,
-                                            statement_start.elapsed(),
-                                        );
-                                        if timed_out || cancelled || !continue_on_error {
-                                            stop_execution = true;
-                                        }
-                                        continue;
-                                    }
-                                };
-
-                                let timing_duration = statement_start.elapsed();
-                                let base_message = if is_plsql_block {
-                                    "PL/SQL block executed successfully".to_string()
-                                } else {
-                                    "Call executed successfully".to_string()
-                                };
-                                let mut result = QueryResult {
-                                    sql: sql_text.to_string(),
-                                    columns: vec![],
-                                    rows: vec![],
-                                    row_count: 0,
-                                    execution_time: timing_duration,
-                                    message: base_message,
-                                    is_select: false,
-                                    success: true,
-                                };
-
-                                let mut out_messages: Vec<String> = Vec::new();
-                                let (_colsep, null_text, _trimspool_enabled) =
-                                    SqlEditorWidget::current_text_output_settings(&session);
-                                if let Ok(updates) =
-                                    QueryExecutor::fetch_scalar_bind_updates(&stmt, &binds)
-                                {
-                                    let mut guard = match session.lock() {
-                                        Ok(guard) => guard,
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            poisoned.into_inner()
-                                        }
-                                    };
-                                    for (name, value) in updates {
-                                        if let Some(bind) = guard.binds.get_mut(&name) {
-                                            bind.value = value.clone();
-                                        }
-                                        if let BindValue::Scalar(val) = value {
-                                            out_messages.push(format!(
-                                                ":{} = {}",
-                                                name,
-                                                val.unwrap_or_else(|| null_text.clone())
-                                            ));
-                                        }
-                                    }
-                                }
-
-                                if !out_messages.is_empty() {
-                                    result.message = format!(
-                                        "{} | OUT: {}",
-                                        result.message,
-                                        out_messages.join(", ")
-                                    );
-                                }
-
-                                if auto_commit {
-                                    if let Err(err) = conn.commit() {
-                                        result = QueryResult::new_error(
-                                            &sql_text,
-                                            &format!("Auto-commit failed: {}", err),
-                                        );
-                                    } else {
-                                        result.message =
-                                            format!("{} | Auto-commit applied", result.message);
-                                    }
-                                }
-
-                                if script_mode {
-                                    if result.success {
-                                        SqlEditorWidget::emit_script_lines(
-                                            &sender,
-                                            &session,
-                                            &result.message,
-                                        );
-                                    }
-                                    SqlEditorWidget::emit_script_result(
-                                        &sender,
-                                        &conn_name,
-                                        result_index,
-                                        result.clone(),
-                                        timed_out,
-                                    );
-                                } else {
-                                    let index = result_index;
-                                    let _ = sender.send(QueryProgress::StatementStart { index });
-                                    app::awake();
-                                    if !result.message.trim().is_empty() {
-                                        SqlEditorWidget::append_spool_output(
-                                            &session,
-                                            &[result.message.clone()],
-                                        );
-                                    }
-                                    let _ = sender.send(QueryProgress::StatementFinished {
-                                        index,
-                                        result: result.clone(),
-                                        connection_name: conn_name.clone(),
-                                        timed_out,
-                                    });
-                                    app::awake();
-                                    result_index += 1;
-                                }
-
-                                let ref_cursors = QueryExecutor::extract_ref_cursors(&stmt, &binds)
-                                    .unwrap_or_default();
-                                let implicit_results =
-                                    QueryExecutor::extract_implicit_results(&stmt)
-                                        .unwrap_or_default();
-
-                                for (cursor_name, mut cursor) in ref_cursors {
-                                    if stop_execution || cancel_flag.load(Ordering::Relaxed) {
-                                        break;
-                                    }
-                                    let index = result_index;
-                                    let _ = sender.send(QueryProgress::StatementStart { index });
-                                    app::awake();
-
-                                    let mut buffered_rows: Vec<Vec<String>> = Vec::new();
-                                    let mut cursor_rows: Vec<Vec<String>> = Vec::new();
-                                    let mut last_flush = Instant::now();
-                                    let cursor_start = Instant::now();
-                                    let mut cursor_timed_out = false;
-                                    let (heading_enabled, feedback_enabled) =
-                                        SqlEditorWidget::current_output_settings(&session);
-                                    let (colsep, null_text, _trimspool_enabled) =
-                                        SqlEditorWidget::current_text_output_settings(&session);
-
-                                    let cursor_label = format!("REFCURSOR :{}", cursor_name);
-                                    let cursor_result = QueryExecutor::execute_ref_cursor_streaming(
-                                        &mut cursor,
-                                        &cursor_label,
-                                        &mut |columns| {
-                                            let names = columns
-                                                .iter()
-                                                .map(|col| col.name.clone())
-                                                .collect::<Vec<String>>();
-                                            let display_columns =
-                                                SqlEditorWidget::apply_heading_setting(
-                                                    names,
-                                                    heading_enabled,
-                                                );
-                                            let _ = sender.send(QueryProgress::SelectStart {
-                                                index,
-                                                columns: display_columns.clone(),
-                                            });
-                                            app::awake();
-                                            if !display_columns.is_empty() {
-                                                SqlEditorWidget::append_spool_output(
-                                                    &session,
-                                                    &[display_columns.join(&colsep)],
-                                                );
-                                            }
-                                        },
-                                        &mut |row| {
-                                            if cancel_flag.load(Ordering::Relaxed) {
-                                                return false;
-                                            }
-                                            if let Some(timeout_duration) = query_timeout {
-                                                if cursor_start.elapsed() >= timeout_duration {
-                                                    cursor_timed_out = true;
-                                                    return false;
-                                                }
-                                            }
-                                            cursor_rows.push(row.clone());
-                                            let mut display_row = row;
-                                            SqlEditorWidget::apply_null_text_to_row(
-                                                &mut display_row,
-                                                &null_text,
-                                            );
-                                            buffered_rows.push(display_row);
-                                            if SqlEditorWidget::should_flush_progress_rows(
-                                                last_flush,
-                                                buffered_rows.len(),
-                                            ) {
-                                                let rows = std::mem::take(&mut buffered_rows);
-                                                SqlEditorWidget::append_spool_rows(&session, &rows);
-                                                let _ = sender
-                                                    .send(QueryProgress::Rows { index, rows });
-                                                app::awake();
-                                                last_flush = Instant::now();
-                                            }
-                                            true
-                                        },
-                                    );
-
-                                    match cursor_result {
-                                        Ok((mut query_result, was_cancelled)) => {
-                                            if !buffered_rows.is_empty() {
-                                                let rows = std::mem::take(&mut buffered_rows);
-                                                SqlEditorWidget::append_spool_rows(&session, &rows);
-                                                let _ = sender
-                                                    .send(QueryProgress::Rows { index, rows });
-                                                app::awake();
-                                            }
-
-                                            if cursor_timed_out {
-                                                query_result.message =
-                                                    SqlEditorWidget::timeout_message(query_timeout);
-                                                query_result.success = false;
-                                                cursor_timed_out = true;
-                                            } else if was_cancelled {
-                                                query_result.message =
-                                                    SqlEditorWidget::cancel_message();
-                                                query_result.success = false;
-                                            }
-                                            SqlEditorWidget::apply_heading_to_result(
-                                                &mut query_result,
-                                                heading_enabled,
-                                            );
-                                            if !feedback_enabled {
-                                                query_result.message.clear();
-                                            }
-
-                                            let column_names: Vec<String> = query_result
-                                                .columns
-                                                .iter()
-                                                .map(|c| c.name.clone())
-                                                .collect();
-
-                                            let _ = sender.send(QueryProgress::StatementFinished {
-                                                index,
-                                                result: query_result.clone(),
-                                                connection_name: conn_name.clone(),
-                                                timed_out: cursor_timed_out,
-                                            });
-                                            app::awake();
-                                            if !query_result.message.trim().is_empty() {
-                                                SqlEditorWidget::append_spool_output(
-                                                    &session,
-                                                    &[query_result.message.clone()],
-                                                );
-                                            }
-                                            result_index += 1;
-
-                                            let mut guard = match session.lock() {
-                                                Ok(guard) => guard,
-                                                Err(poisoned) => {
-                                                    eprintln!("Warning: session state lock was poisoned; recovering.");
-                                                    poisoned.into_inner()
-                                                }
-                                            };
-                                            if let Some(bind) = guard.binds.get_mut(&cursor_name) {
-                                                bind.value =
-                                                    BindValue::Cursor(Some(CursorResult {
-                                                        columns: column_names,
-                                                        rows: cursor_rows,
-                                                    }));
-                                            }
-
-                                            if cursor_timed_out {
-                                                stop_execution = true;
-                                                break;
-                                            }
-                                            if !query_result.success && !continue_on_error {
-                                                stop_execution = true;
-                                                break;
-                                            }
-                                        }
-                                        Err(err) => {
-                                            let cancelled = SqlEditorWidget::is_cancel_error(&err);
-                                            cursor_timed_out =
-                                                SqlEditorWidget::is_timeout_error(&err);
-                                            let message = if cancelled {
-                                                SqlEditorWidget::cancel_message()
-                                            } else if cursor_timed_out {
-                                                SqlEditorWidget::timeout_message(query_timeout)
-                                            } else {
-                                                err.to_string()
-                                            };
-                                            SqlEditorWidget::append_spool_output(
-                                                &session,
-                                                &[message.clone()],
-                                            );
-                                            let _ = sender.send(QueryProgress::StatementFinished {
-                                                index,
-                                                result: QueryResult::new_error(
-                                                    &cursor_label,
-                                                    &message,
-                                                ),
-                                                connection_name: conn_name.clone(),
-                                                timed_out: cursor_timed_out,
-                                            });
-                                            app::awake();
-                                            result_index += 1;
-
-                                            if cursor_timed_out || cancelled || !continue_on_error {
-                                                stop_execution = true;
-                                                break;
-                                            }
-                                        }
-                                    }
-                                }
-
-                                for (idx, mut cursor) in implicit_results.into_iter().enumerate() {
-                                    if stop_execution || cancel_flag.load(Ordering::Relaxed) {
-                                        break;
-                                    }
-                                    let index = result_index;
-                                    let _ = sender.send(QueryProgress::StatementStart { index });
-                                    app::awake();
-
-                                    let mut buffered_rows: Vec<Vec<String>> = Vec::new();
-                                    let mut last_flush = Instant::now();
-                                    let cursor_start = Instant::now();
-                                    let mut cursor_timed_out = false;
-                                    let (heading_enabled, feedback_enabled) =
-                                        SqlEditorWidget::current_output_settings(&session);
-                                    let (colsep, null_text, _trimspool_enabled) =
-                                        SqlEditorWidget::current_text_output_settings(&session);
-                                    let cursor_label = format!("IMPLICIT RESULT {}", idx + 1);
-
-                                    let cursor_result = QueryExecutor::execute_ref_cursor_streaming(
-                                        &mut cursor,
-                                        &cursor_label,
-                                        &mut |columns| {
-                                            let names = columns
-                                                .iter()
-                                                .map(|col| col.name.clone())
-                                                .collect::<Vec<String>>();
-                                            let display_columns =
-                                                SqlEditorWidget::apply_heading_setting(
-                                                    names,
-                                                    heading_enabled,
-                                                );
-                                            let _ = sender.send(QueryProgress::SelectStart {
-                                                index,
-                                                columns: display_columns.clone(),
-                                            });
-                                            app::awake();
-                                            if !display_columns.is_empty() {
-                                                SqlEditorWidget::append_spool_output(
-                                                    &session,
-                                                    &[display_columns.join(&colsep)],
-                                                );
-                                            }
-                                        },
-                                        &mut |row| {
-                                            if cancel_flag.load(Ordering::Relaxed) {
-                                                return false;
-                                            }
-                                            if let Some(timeout_duration) = query_timeout {
-                                                if cursor_start.elapsed() >= timeout_duration {
-                                                    cursor_timed_out = true;
-                                                    return false;
-                                                }
-                                            }
-                                            let mut display_row = row;
-                                            SqlEditorWidget::apply_null_text_to_row(
-                                                &mut display_row,
-                                                &null_text,
-                                            );
-                                            buffered_rows.push(display_row);
-                                            if SqlEditorWidget::should_flush_progress_rows(
-                                                last_flush,
-                                                buffered_rows.len(),
-                                            ) {
-                                                let rows = std::mem::take(&mut buffered_rows);
-                                                SqlEditorWidget::append_spool_rows(&session, &rows);
-                                                let _ = sender
-                                                    .send(QueryProgress::Rows { index, rows });
-                                                app::awake();
-                                                last_flush = Instant::now();
-                                            }
-                                            true
-                                        },
-                                    );
-
-                                    match cursor_result {
-                                        Ok((mut query_result, was_cancelled)) => {
-                                            if !buffered_rows.is_empty() {
-                                                let rows = std::mem::take(&mut buffered_rows);
-                                                SqlEditorWidget::append_spool_rows(&session, &rows);
-                                                let _ = sender
-                                                    .send(QueryProgress::Rows { index, rows });
-                                                app::awake();
-                                            }
-
-                                            if cursor_timed_out {
-                                                query_result.message =
-                                                    SqlEditorWidget::timeout_message(query_timeout);
-                                                query_result.success = false;
-                                                cursor_timed_out = true;
-                                            } else if was_cancelled {
-                                                query_result.message =
-                                                    SqlEditorWidget::cancel_message();
-                                                query_result.success = false;
-                                            }
-                                            SqlEditorWidget::apply_heading_to_result(
-                                                &mut query_result,
-                                                heading_enabled,
-                                            );
-                                            if !feedback_enabled {
-                                                query_result.message.clear();
-                                            }
-
-                                            let _ = sender.send(QueryProgress::StatementFinished {
-                                                index,
-                                                result: query_result.clone(),
-                                                connection_name: conn_name.clone(),
-                                                timed_out: cursor_timed_out,
-                                            });
-                                            app::awake();
-                                            if !query_result.message.trim().is_empty() {
-                                                SqlEditorWidget::append_spool_output(
-                                                    &session,
-                                                    &[query_result.message.clone()],
-                                                );
-                                            }
-                                            result_index += 1;
-
-                                            if cursor_timed_out {
-                                                stop_execution = true;
-                                                break;
-                                            }
-                                            if !query_result.success && !continue_on_error {
-                                                stop_execution = true;
-                                                break;
-                                            }
-                                        }
-                                        Err(err) => {
-                                            let cancelled = SqlEditorWidget::is_cancel_error(&err);
-                                            cursor_timed_out =
-                                                SqlEditorWidget::is_timeout_error(&err);
-                                            let message = if cancelled {
-                                                SqlEditorWidget::cancel_message()
-                                            } else if cursor_timed_out {
-                                                SqlEditorWidget::timeout_message(query_timeout)
-                                            } else {
-                                                err.to_string()
-                                            };
-                                            SqlEditorWidget::append_spool_output(
-                                                &session,
-                                                &[message.clone()],
-                                            );
-                                            let _ = sender.send(QueryProgress::StatementFinished {
-                                                index,
-                                                result: QueryResult::new_error(
-                                                    &cursor_label,
-                                                    &message,
-                                                ),
-                                                connection_name: conn_name.clone(),
-                                                timed_out: cursor_timed_out,
-                                            });
-                                            app::awake();
-                                            result_index += 1;
-
-                                            if cursor_timed_out || cancelled || !continue_on_error {
-                                                stop_execution = true;
-                                                break;
-                                            }
-                                        }
-                                    }
-                                }
-
-                                let _ = SqlEditorWidget::emit_dbms_output(
-                                    &sender,
-                                    &conn_name,
-                                    conn.as_ref(),
-                                    &session,
-                                    &mut result_index,
-                                );
-                                SqlEditorWidget::emit_timing_if_enabled(
-                                    &sender,
-                                    &session,
-                                    timing_duration,
-                                );
-
-                                if timed_out || (!result.success && !continue_on_error) {
-                                    stop_execution = true;
-                                }
-                            } else if is_select {
-                                let sql_to_execute =
-                                    sql_text.trim_end_matches(';').trim().to_string();
-                                let binds = match session.lock() {
-                                    Ok(guard) => {
-                                        QueryExecutor::resolve_binds(&sql_to_execute, &guard)
-                                    }
-                                    Err(poisoned) => {
-                                        eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                        QueryExecutor::resolve_binds(
-                                            &sql_to_execute,
-                                            &poisoned.into_inner(),
-                                        )
-                                    }
-                                };
-
-                                let binds = match binds {
-                                    Ok(binds) => binds,
-                                    Err(message) => {
-                                        let emitted = SqlEditorWidget::emit_non_select_result(
-                                            &sender,
-                                            &session,
-                                            &conn_name,
-                                            result_index,
-                                            &sql_text,
-                                            format!("Error: {}", message),
-                                            false,
-                                            false,
-                                            script_mode,
-                                        );
-                                        if emitted {
-                                            result_index += 1;
-                                        }
-                                        if !continue_on_error {
-                                            stop_execution = true;
-                                        }
-                                        continue;
-                                    }
-                                };
-
-                                let index = result_index;
-                                let _ = sender.send(QueryProgress::StatementStart { index });
-                                app::awake();
-
-                                let (heading_enabled, feedback_enabled) =
-                                    SqlEditorWidget::current_output_settings(&session);
-                                let mut buffered_rows: Vec<Vec<String>> = Vec::new();
-                                let mut select_column_names: Vec<String> = Vec::new();
-                                let select_column_count = std::cell::Cell::new(0usize);
-                                let mut last_select_row: Option<Vec<String>> = None;
-                                let mut last_flush = Instant::now();
-                                let statement_start = Instant::now();
-                                let mut timed_out = false;
-                                let (colsep, null_text, _trimspool_enabled) =
-                                    SqlEditorWidget::current_text_output_settings(&session);
-                                let (break_column, compute_config) = match session.lock() {
-                                    Ok(guard) => {
-                                        (guard.break_column.clone(), guard.compute.clone())
-                                    }
-                                    Err(poisoned) => {
-                                        eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                        let guard = poisoned.into_inner();
-                                        (guard.break_column.clone(), guard.compute.clone())
-                                    }
-                                };
-                                let transform_state =
-                                    std::cell::RefCell::new(SelectTransformState::default());
-
-                                let result =
-                                    match QueryExecutor::execute_select_streaming_with_binds(
-                                        conn.as_ref(),
-                                        &sql_to_execute,
-                                        &binds,
-                                        &mut |columns| {
-                                            let names = columns
-                                                .iter()
-                                                .map(|col| col.name.clone())
-                                                .collect::<Vec<String>>();
-                                            select_column_names = names.clone();
-                                            select_column_count.set(names.len());
-                                            {
-                                                let mut state = transform_state.borrow_mut();
-                                                state.break_index =
-                                                    break_column.as_ref().and_then(|target| {
-                                                        let target_key =
-                                                            SessionState::normalize_name(target);
-                                                        names.iter().position(|column_name| {
-                                                            SessionState::normalize_name(
-                                                                column_name,
-                                                            ) == target_key
-                                                        })
-                                                    });
-                                                state.compute_of_index =
-                                                    compute_config
-                                                        .as_ref()
-                                                        .and_then(|config| {
-                                                            config.of_column.as_ref().and_then(
-                                                                |target| {
-                                                                    let target_key =
-                                                                        SessionState::normalize_name(
-                                                                            target,
-                                                                        );
-                                                                    names.iter().position(
-                                                                        |column_name| {
-                                                                            SessionState::normalize_name(column_name)
-                                                                                == target_key
-                                                                        },
-                                                                    )
-                                                                },
-                                                            )
-                                                        });
-                                                state.compute_on_index =
-                                                    compute_config
-                                                        .as_ref()
-                                                        .and_then(|config| {
-                                                            config.on_column.as_ref().and_then(
-                                                                |target| {
-                                                                    let target_key =
-                                                                        SessionState::normalize_name(
-                                                                            target,
-                                                                        );
-                                                                    names.iter().position(
-                                                                        |column_name| {
-                                                                            SessionState::normalize_name(column_name)
-                                                                                == target_key
-                                                                        },
-                                                                    )
-                                                                },
-                                                            )
-                                                        });
-                                                if compute_config
-                                                    .as_ref()
-                                                    .map(|config| {
-                                                        config.mode == crate::db::ComputeMode::Sum
-                                                            && config.of_column.is_none()
-                                                    })
-                                                    .unwrap_or(false)
-                                                {
-                                                    state.compute_sums = vec![0.0; names.len()];
-                                                    state.compute_seen_numeric =
-                                                        vec![false; names.len()];
-                                                }
-                                            }
-                                            let display_columns =
-                                                SqlEditorWidget::apply_heading_setting(
-                                                    names,
-                                                    heading_enabled,
-                                                );
-                                            let _ = sender.send(QueryProgress::SelectStart {
-                                                index,
-                                                columns: display_columns.clone(),
-                                            });
-                                            app::awake();
-                                            if !display_columns.is_empty() {
-                                                SqlEditorWidget::append_spool_output(
-                                                    &session,
-                                                    &[display_columns.join(&colsep)],
-                                                );
-                                            }
-                                        },
-                                        &mut |row| {
-                                            if cancel_flag.load(Ordering::Relaxed) {
-                                                return false;
-                                            }
-                                            if let Some(timeout_duration) = query_timeout {
-                                                if statement_start.elapsed() >= timeout_duration {
-                                                    timed_out = true;
-                                                    return false;
-                                                }
-                                            }
-
-                                            let mut row = row;
-                                            last_select_row = Some(row.clone());
-                                            {
-                                                let mut state = transform_state.borrow_mut();
-                                                if let Some(config) = compute_config.as_ref() {
-                                                    let grouped_compute =
-                                                        config.of_column.is_some()
-                                                            && config.on_column.is_some()
-                                                            && state.compute_of_index.is_some()
-                                                            && state.compute_on_index.is_some();
-                                                    if grouped_compute {
-                                                        if let Some(on_idx) = state.compute_on_index
-                                                        {
-                                                            if let Some(current_group_value) =
-                                                                row.get(on_idx).cloned()
-                                                            {
-                                                                if let Some(previous_group_value) =
-                                                                    state
-                                                                        .compute_group_value
-                                                                        .clone()
-                                                                {
-                                                                    if previous_group_value
-                                                                        != current_group_value
-                                                                    {
-                                                                        if let Some(summary_row) =
-                                                                            SqlEditorWidget::build_compute_summary_row(
-                                                                                select_column_count.get(),
-                                                                                Some(config),
-                                                                                &state,
-                                                                            )
-                                                                        {
-                                                                            let mut display_summary =
-                                                                                summary_row;
-                                                                            SqlEditorWidget::apply_null_text_to_row(
-                                                                                &mut display_summary,
-                                                                                &null_text,
-                                                                            );
-                                                                            buffered_rows
-                                                                                .push(display_summary);
-                                                                        }
-                                                                        state.compute_count = 0;
-                                                                        state.compute_sum = 0.0;
-                                                                        state.compute_sum_seen =
-                                                                            false;
-                                                                    }
-                                                                }
-                                                                state.compute_group_value =
-                                                                    Some(current_group_value);
-                                                            }
-                                                        }
-                                                    }
-                                                    SqlEditorWidget::accumulate_compute(
-                                                        config, &row, &mut state,
-                                                    );
-                                                }
-                                                if let Some(idx) = state.break_index {
-                                                    if let Some(current_break_value) =
-                                                        row.get(idx).cloned()
-                                                    {
-                                                        if state
-                                                            .previous_break_value
-                                                            .as_ref()
-                                                            .map(|prev| {
-                                                                prev == &current_break_value
-                                                            })
-                                                            .unwrap_or(false)
-                                                        {
-                                                            if let Some(cell) = row.get_mut(idx) {
-                                                                *cell = String::new();
-                                                            }
-                                                        } else {
-                                                            state.previous_break_value =
-                                                                Some(current_break_value);
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                            SqlEditorWidget::apply_null_text_to_row(
-                                                &mut row, &null_text,
-                                            );
-                                            buffered_rows.push(row);
-                                            if SqlEditorWidget::should_flush_progress_rows(
-                                                last_flush,
-                                                buffered_rows.len(),
-                                            ) {
-                                                let rows = std::mem::take(&mut buffered_rows);
-                                                SqlEditorWidget::append_spool_rows(&session, &rows);
-                                                let _ = sender
-                                                    .send(QueryProgress::Rows { index, rows });
-                                                app::awake();
-                                                last_flush = Instant::now();
-                                            }
-                                            true
-                                        },
-                                    ) {
-                                        Ok((mut query_result, was_cancelled)) => {
-                                            SqlEditorWidget::apply_heading_to_result(
-                                                &mut query_result,
-                                                heading_enabled,
-                                            );
-                                            if timed_out {
-                                                query_result.message =
-                                                    SqlEditorWidget::timeout_message(query_timeout);
-                                                query_result.success = false;
-                                                timed_out = true;
-                                            } else if was_cancelled {
-                                                query_result.message =
-                                                    SqlEditorWidget::cancel_message();
-                                                query_result.success = false;
-                                            }
-                                            if !feedback_enabled {
-                                                query_result.message.clear();
-                                            }
-                                            if !query_result.message.trim().is_empty() {
-                                                SqlEditorWidget::append_spool_output(
-                                                    &session,
-                                                    &[query_result.message.clone()],
-                                                );
-                                            }
-                                            query_result
-                                        }
-                                        Err(err) => {
-                                            let cancelled = SqlEditorWidget::is_cancel_error(&err);
-                                            timed_out = SqlEditorWidget::is_timeout_error(&err);
-                                            let message = if cancelled {
-                                                SqlEditorWidget::cancel_message()
-                                            } else if timed_out {
-                                                SqlEditorWidget::timeout_message(query_timeout)
-                                            } else {
-                                                err.to_string()
-                                            };
-                                            let mut error_result =
-                                                QueryResult::new_error(&sql_text, &message);
-                                            // Preserve is_select flag so existing streamed data is kept
-                                            error_result.is_select = true;
-                                            error_result
-                                        }
-                                    };
-                                let transform_state = transform_state.into_inner();
-
-                                if !buffered_rows.is_empty() {
-                                    let rows = std::mem::take(&mut buffered_rows);
-                                    SqlEditorWidget::append_spool_rows(&session, &rows);
-                                    let _ = sender.send(QueryProgress::Rows { index, rows });
-                                    app::awake();
-                                }
-
-                                if !result.message.trim().is_empty() {
-                                    SqlEditorWidget::append_spool_output(
-                                        &session,
-                                        &[result.message.clone()],
-                                    );
-                                }
-                                if result.success {
-                                    let grouped_compute = compute_config
-                                        .as_ref()
-                                        .map(|config| {
-                                            config.of_column.is_some()
-                                                && config.on_column.is_some()
-                                                && transform_state.compute_of_index.is_some()
-                                                && transform_state.compute_on_index.is_some()
-                                        })
-                                        .unwrap_or(false);
-                                    if grouped_compute {
-                                        if transform_state.compute_group_value.is_some() {
-                                            if let Some(summary_row) =
-                                                SqlEditorWidget::build_compute_summary_row(
-                                                    select_column_names.len(),
-                                                    compute_config.as_ref(),
-                                                    &transform_state,
-                                                )
-                                            {
-                                                let rows = vec![summary_row];
-                                                SqlEditorWidget::append_spool_rows(&session, &rows);
-                                                let _ = sender
-                                                    .send(QueryProgress::Rows { index, rows });
-                                                app::awake();
-                                            }
-                                        }
-                                    } else if let Some(summary_row) =
-                                        SqlEditorWidget::build_compute_summary_row(
-                                            select_column_names.len(),
-                                            compute_config.as_ref(),
-                                            &transform_state,
-                                        )
-                                    {
-                                        let rows = vec![summary_row];
-                                        SqlEditorWidget::append_spool_rows(&session, &rows);
-                                        let _ = sender.send(QueryProgress::Rows { index, rows });
-                                        app::awake();
-                                    }
-                                    SqlEditorWidget::apply_column_new_value_from_row(
-                                        &session,
-                                        &select_column_names,
-                                        last_select_row.as_deref(),
-                                    );
-                                }
-                                let timing_duration = if result.execution_time.is_zero() {
-                                    statement_start.elapsed()
-                                } else {
-                                    result.execution_time
-                                };
-                                let _ = sender.send(QueryProgress::StatementFinished {
-                                    index,
-                                    result: result.clone(),
-                                    connection_name: conn_name.clone(),
-                                    timed_out,
-                                });
-                                app::awake();
-                                result_index += 1;
-
-                                let _ = SqlEditorWidget::emit_dbms_output(
-                                    &sender,
-                                    &conn_name,
-                                    conn.as_ref(),
-                                    &session,
-                                    &mut result_index,
-                                );
-                                SqlEditorWidget::emit_timing_if_enabled(
-                                    &sender,
-                                    &session,
-                                    timing_duration,
-                                );
-
-                                if timed_out || (!result.success && !continue_on_error) {
-                                    stop_execution = true;
-                                }
-                            } else {
-                                let sql_to_execute = if is_compiled_plsql {
-                                    SqlEditorWidget::ensure_plsql_terminator(&sql_text)
-                                } else {
-                                    sql_text.trim_end_matches(';').trim().to_string()
-                                };
-                                let binds = match session.lock() {
-                                    Ok(guard) => {
-                                        QueryExecutor::resolve_binds(&sql_to_execute, &guard)
-                                    }
-                                    Err(poisoned) => {
-                                        eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                        QueryExecutor::resolve_binds(
-                                            &sql_to_execute,
-                                            &poisoned.into_inner(),
-                                        )
-                                    }
-                                };
-
-                                let binds = match binds {
-                                    Ok(binds) => binds,
-                                    Err(message) => {
-                                        let emitted = SqlEditorWidget::emit_non_select_result(
-                                            &sender,
-                                            &session,
-                                            &conn_name,
-                                            result_index,
-                                            &sql_text,
-                                            format!("Error: {}", message),
-                                            false,
-                                            false,
-                                            script_mode,
-                                        );
-                                        if emitted {
-                                            result_index += 1;
-                                        }
-                                        if !continue_on_error {
-                                            stop_execution = true;
-                                        }
-                                        continue;
-                                    }
-                                };
-
-                                let statement_start = Instant::now();
-                                let mut timed_out = false;
-                                let stmt = match QueryExecutor::execute_with_binds(
-                                    conn.as_ref(),
-                                    &sql_to_execute,
-                                    &binds,
-                                ) {
-                                    Ok(stmt) => stmt,
-                                    Err(err) => {
-                                        let cancelled = SqlEditorWidget::is_cancel_error(&err);
-                                        timed_out = SqlEditorWidget::is_timeout_error(&err);
-                                        let message = if cancelled {
-                                            SqlEditorWidget::cancel_message()
-                                        } else if timed_out {
-                                            SqlEditorWidget::timeout_message(query_timeout)
-                                        } else {
-                                            err.to_string()
-                                        };
-                                        if script_mode {
-                                            let result =
-                                                QueryResult::new_error(&sql_text, &message);
-                                            SqlEditorWidget::emit_script_result(
-                                                &sender,
-                                                &conn_name,
-                                                result_index,
-                                                result,
-                                                timed_out,
-                                            );
-                                        } else {
-                                            let index = result_index;
-                                            let _ = sender
-                                                .send(QueryProgress::StatementStart { index });
-                                            app::awake();
-                                            let result =
-                                                QueryResult::new_error(&sql_text, &message);
-                                            let _ = sender.send(QueryProgress::StatementFinished {
-                                                index,
-                                                result,
-                                                connection_name: conn_name.clone(),
-                                                timed_out,
-                                            });
-                                            app::awake();
-                                            result_index += 1;
-                                        }
-                                        SqlEditorWidget::emit_timing_if_enabled(
-                                            &sender,
-                                            &session,
-                                            statement_start.elapsed(),
-                                        );
-                                        if timed_out || cancelled || !continue_on_error {
-                                            stop_execution = true;
-                                        }
-                                        continue;
-                                    }
-                                };
-
-                                let execution_time = statement_start.elapsed();
-                                let timing_duration = execution_time;
-                                let dml_type = if upper.starts_with("INSERT") {
-                                    Some("INSERT")
-                                } else if upper.starts_with("UPDATE") {
-                                    Some("UPDATE")
-                                } else if upper.starts_with("DELETE") {
-                                    Some("DELETE")
-                                } else if upper.starts_with("MERGE") {
-                                    Some("MERGE")
-                                } else {
-                                    None
-                                };
-
-                                let mut result = if let Some(statement_type) = dml_type {
-                                    let affected_rows = stmt.row_count().unwrap_or(0);
-                                    QueryResult::new_dml(
-                                        &sql_text,
-                                        affected_rows,
-                                        execution_time,
-                                        statement_type,
-                                    )
-                                } else {
-                                    QueryResult {
-                                        sql: sql_text.to_string(),
-                                        columns: vec![],
-                                        rows: vec![],
-                                        row_count: 0,
-                                        execution_time,
-                                        message: if upper.starts_with("CREATE")
-                                            || upper.starts_with("ALTER")
-                                            || upper.starts_with("DROP")
-                                            || upper.starts_with("TRUNCATE")
-                                            || upper.starts_with("RENAME")
-                                            || upper.starts_with("GRANT")
-                                            || upper.starts_with("REVOKE")
-                                            || upper.starts_with("COMMENT")
-                                        {
-                                            SqlEditorWidget::ddl_message(&upper)
-                                        } else {
-                                            "Statement executed successfully".to_string()
-                                        },
-                                        is_select: false,
-                                        success: true,
-                                    }
-                                };
-
-                                let mut out_messages: Vec<String> = Vec::new();
-                                let (_colsep, null_text, _trimspool_enabled) =
-                                    SqlEditorWidget::current_text_output_settings(&session);
-                                if let Ok(updates) =
-                                    QueryExecutor::fetch_scalar_bind_updates(&stmt, &binds)
-                                {
-                                    let mut guard = match session.lock() {
-                                        Ok(guard) => guard,
-                                        Err(poisoned) => {
-                                            eprintln!(
-                                            "Warning: session state lock was poisoned; recovering."
-                                        );
-                                            poisoned.into_inner()
-                                        }
-                                    };
-                                    for (name, value) in updates {
-                                        if let Some(bind) = guard.binds.get_mut(&name) {
-                                            bind.value = value.clone();
-                                        }
-                                        if let BindValue::Scalar(val) = value {
-                                            out_messages.push(format!(
-                                                ":{} = {}",
-                                                name,
-                                                val.unwrap_or_else(|| null_text.clone())
-                                            ));
-                                        }
-                                    }
-                                }
-
-                                if !out_messages.is_empty() {
-                                    result.message = format!(
-                                        "{} | OUT: {}",
-                                        result.message,
-                                        out_messages.join(", ")
-                                    );
-                                }
-
-                                let mut compile_errors: Option<Vec<Vec<String>>> = None;
-                                if let Some(object) = compiled_object.clone() {
-                                    match QueryExecutor::fetch_compilation_errors(
-                                        conn.as_ref(),
-                                        &object,
-                                    ) {
-                                        Ok(rows) => {
-                                            if !rows.is_empty() {
-                                                result.message = format!(
-                                                    "{} | Compiled with errors",
-                                                    result.message
-                                                );
-                                                result.success = false;
-                                                compile_errors = Some(rows);
-                                            }
-                                        }
-                                        Err(err) => {
-                                            result.message = format!(
-                                                "{} | Failed to fetch compilation errors: {}",
-                                                result.message, err
-                                            );
-                                            result.success = false;
-                                        }
-                                    }
-                                }
-
-                                if dml_type.is_some() && !auto_commit && result.success {
-                                    result.message =
-                                        format!("{} | Commit required", result.message);
-                                }
-
-                                if auto_commit && result.success {
-                                    if let Err(err) = conn.commit() {
-                                        result = QueryResult::new_error(
-                                            &sql_text,
-                                            &format!("Auto-commit failed: {}", err),
-                                        );
-                                    } else {
-                                        result.message =
-                                            format!("{} | Auto-commit applied", result.message);
-                                    }
-                                }
-
-                                if script_mode {
-                                    if result.success {
-                                        SqlEditorWidget::emit_script_lines(
-                                            &sender,
-                                            &session,
-                                            &result.message,
-                                        );
-                                    }
-                                    SqlEditorWidget::emit_script_result(
-                                        &sender,
-                                        &conn_name,
-                                        result_index,
-                                        result.clone(),
-                                        timed_out,
-                                    );
-                                } else {
-                                    let index = result_index;
-                                    let _ = sender.send(QueryProgress::StatementStart { index });
-                                    app::awake();
-                                    if !result.message.trim().is_empty() {
-                                        SqlEditorWidget::append_spool_output(
-                                            &session,
-                                            &[result.message.clone()],
-                                        );
-                                    }
-                                    let _ = sender.send(QueryProgress::StatementFinished {
-                                        index,
-                                        result: result.clone(),
-                                        connection_name: conn_name.clone(),
-                                        timed_out,
-                                    });
-                                    app::awake();
-                                    result_index += 1;
-                                }
-
-                                if let Some(rows) = compile_errors {
-                                    let (heading_enabled, feedback_enabled) =
-                                        SqlEditorWidget::current_output_settings(&session);
-                                    SqlEditorWidget::emit_select_result(
-                                        &sender,
-                                        &session,
-                                        &conn_name,
-                                        result_index,
-                                        "COMPILE ERRORS",
-                                        SqlEditorWidget::apply_heading_setting(
-                                            vec![
-                                                "LINE".to_string(),
-                                                "POSITION".to_string(),
-                                                "TEXT".to_string(),
-                                            ],
-                                            heading_enabled,
-                                        ),
-                                        rows,
-                                        false,
-                                        feedback_enabled,
-                                    );
-                                    result_index += 1;
-                                }
-
-                                let _ = SqlEditorWidget::emit_dbms_output(
-                                    &sender,
-                                    &conn_name,
-                                    conn.as_ref(),
-                                    &session,
-                                    &mut result_index,
-                                );
-                                SqlEditorWidget::emit_timing_if_enabled(
-                                    &sender,
-                                    &session,
-                                    timing_duration,
-                                );
-
-                                if timed_out || (!result.success && !continue_on_error) {
-                                    stop_execution = true;
-                                }
-                            }
-                        }
-                    }
-                }
-
-                // Restore previous timeout if we have a connection
-                if let Some(conn) = conn_opt.as_ref() {
-                    let _ = conn.set_call_timeout(previous_timeout);
-                }
-
-                // Clear current query connection
-                SqlEditorWidget::set_current_query_connection(&current_query_connection, None);
-
-                let _ = sender.send(QueryProgress::BatchFinished);
-                app::awake();
-            })); // end catch_unwind
-
-            if let Err(e) = result {
-                eprintln!("Query thread panicked: {:?}", e);
-                SqlEditorWidget::set_current_query_connection(&current_query_connection, None);
-                let _ = sender.send(QueryProgress::BatchFinished);
-                app::awake();
-            }
-        });
-    }
-
-    fn emit_non_select_result(
-        sender: &mpsc::Sender<QueryProgress>,
-        session: &Arc<Mutex<SessionState>>,
-        conn_name: &str,
-        index: usize,
-        sql: &str,
-        message: String,
-        success: bool,
-        timed_out: bool,
-        script_mode: bool,
-    ) -> bool {
-        if script_mode {
-            if success {
-                SqlEditorWidget::emit_script_lines(sender, session, &message);
-            }
-            let result = QueryResult {
-                sql: sql.to_string(),
-                columns: vec![],
-                rows: vec![],
-                row_count: 0,
-                execution_time: Duration::from_secs(0),
-                message,
-                is_select: false,
-                success,
-            };
-            SqlEditorWidget::emit_script_result(sender, conn_name, index, result, timed_out);
-            return false;
-        }
-
-        let _ = sender.send(QueryProgress::StatementStart { index });
-        app::awake();
-        if !message.trim().is_empty() {
-            SqlEditorWidget::append_spool_output(session, &[message.clone()]);
-        }
-        let result = QueryResult {
-            sql: sql.to_string(),
-            columns: vec![],
-            rows: vec![],
-            row_count: 0,
-            execution_time: Duration::from_secs(0),
-            message,
-            is_select: false,
-            success,
-        };
-        let _ = sender.send(QueryProgress::StatementFinished {
-            index,
-            result,
-            connection_name: conn_name.to_string(),
-            timed_out,
-        });
-        app::awake();
-        true
-    }
-
-    fn emit_script_result(
-        sender: &mpsc::Sender<QueryProgress>,
-        conn_name: &str,
-        index: usize,
-        result: QueryResult,
-        timed_out: bool,
-    ) {
-        let _ = sender.send(QueryProgress::StatementFinished {
-            index,
-            result,
-            connection_name: conn_name.to_string(),
-            timed_out,
-        });
-        app::awake();
-    }
-
-    fn apply_column_new_value_from_row(
-        session: &Arc<Mutex<SessionState>>,
-        column_names: &[String],
-        row: Option<&[String]>,
-    ) {
-        let Some(row_values) = row else {
-            return;
-        };
-
-        match session.lock() {
-            Ok(mut guard) => {
-                if guard.column_new_values.is_empty() {
-                    return;
-                }
-                for (idx, column_name) in column_names.iter().enumerate() {
-                    let column_key = SessionState::normalize_name(column_name);
-                    let Some(variable_key) = guard.column_new_values.get(&column_key).cloned()
-                    else {
-                        continue;
-                    };
-                    let Some(value) = row_values.get(idx).cloned() else {
-                        continue;
-                    };
-                    guard.define_vars.insert(variable_key, value);
-                }
-            }
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                let mut guard = poisoned.into_inner();
-                if guard.column_new_values.is_empty() {
-                    return;
-                }
-                for (idx, column_name) in column_names.iter().enumerate() {
-                    let column_key = SessionState::normalize_name(column_name);
-                    let Some(variable_key) = guard.column_new_values.get(&column_key).cloned()
-                    else {
-                        continue;
-                    };
-                    let Some(value) = row_values.get(idx).cloned() else {
-                        continue;
-                    };
-                    guard.define_vars.insert(variable_key, value);
-                }
-            }
-        }
-    }
-
-    fn current_output_settings(session: &Arc<Mutex<SessionState>>) -> (bool, bool) {
-        match session.lock() {
-            Ok(guard) => (guard.heading_enabled, guard.feedback_enabled),
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                let guard = poisoned.into_inner();
-                (guard.heading_enabled, guard.feedback_enabled)
-            }
-        }
-    }
-
-    fn current_text_output_settings(session: &Arc<Mutex<SessionState>>) -> (String, String, bool) {
-        match session.lock() {
-            Ok(guard) => (
-                guard.colsep.clone(),
-                guard.null_text.clone(),
-                guard.trimspool_enabled,
-            ),
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                let guard = poisoned.into_inner();
-                (
-                    guard.colsep.clone(),
-                    guard.null_text.clone(),
-                    guard.trimspool_enabled,
-                )
-            }
-        }
-    }
-
-    fn current_script_output_settings(session: &Arc<Mutex<SessionState>>) -> (bool, bool) {
-        match session.lock() {
-            Ok(guard) => (guard.trimout_enabled, guard.tab_enabled),
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                let guard = poisoned.into_inner();
-                (guard.trimout_enabled, guard.tab_enabled)
-            }
-        }
-    }
-
-    fn has_spool_target(session: &Arc<Mutex<SessionState>>) -> bool {
-        match session.lock() {
-            Ok(guard) => guard.spool_path.is_some(),
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                poisoned.into_inner().spool_path.is_some()
-            }
-        }
-    }
-
-    fn expand_tabs(text: &str) -> String {
-        const TAB_STOP: usize = 8;
-        let mut out = String::with_capacity(text.len());
-        let mut col = 0usize;
-
-        for ch in text.chars() {
-            if ch == '\t' {
-                let spaces = TAB_STOP - (col % TAB_STOP);
-                for _ in 0..spaces {
-                    out.push(' ');
-                }
-                col += spaces;
-            } else {
-                out.push(ch);
-                col += 1;
-            }
-        }
-
-        out
-    }
-
-    fn format_script_output_line(line: &str, trimout_enabled: bool, tab_enabled: bool) -> String {
-        let mut rendered = if tab_enabled {
-            SqlEditorWidget::expand_tabs(line)
-        } else {
-            line.to_string()
-        };
-
-        if trimout_enabled {
-            rendered = rendered.trim_end().to_string();
-        }
-
-        rendered
-    }
-
-    fn display_cell_value(value: &str, null_text: &str) -> String {
-        if value == "NULL" {
-            null_text.to_string()
-        } else {
-            value.to_string()
-        }
-    }
-
-    fn display_row_values(row: &[String], null_text: &str) -> Vec<String> {
-        row.iter()
-            .map(|value| SqlEditorWidget::display_cell_value(value, null_text))
-            .collect()
-    }
-
-    fn apply_null_text_to_row(row: &mut [String], null_text: &str) {
-        if null_text == "NULL" {
-            return;
-        }
-        for value in row.iter_mut() {
-            if value == "NULL" {
-                value.clear();
-                value.push_str(null_text);
-            }
-        }
-    }
-
-    fn parse_numeric_value(value: &str) -> Option<f64> {
-        let trimmed = value.trim();
-        if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("NULL") {
-            return None;
-        }
-        let normalized = trimmed.replace(',', "");
-        normalized.parse::<f64>().ok()
-    }
-
-    fn format_number(value: f64) -> String {
-        let mut text = format!("{}", value);
-        if text.contains('.') {
-            while text.ends_with('0') {
-                text.pop();
-            }
-            if text.ends_with('.') {
-                text.pop();
-            }
-        }
-        text
-    }
-
-    fn accumulate_compute(
-        config: &crate::db::ComputeConfig,
-        row: &[String],
-        state: &mut SelectTransformState,
-    ) {
-        match config.mode {
-            crate::db::ComputeMode::Count => {
-                if let Some(of_idx) = state.compute_of_index {
-                    let is_not_null = row
-                        .get(of_idx)
-                        .map(|value| !value.eq_ignore_ascii_case("NULL"))
-                        .unwrap_or(false);
-                    if is_not_null {
-                        state.compute_count += 1;
-                    }
-                } else {
-                    state.compute_count += 1;
-                }
-            }
-            crate::db::ComputeMode::Sum => {
-                if let Some(of_idx) = state.compute_of_index {
-                    if let Some(number) = row
-                        .get(of_idx)
-                        .and_then(|value| SqlEditorWidget::parse_numeric_value(value))
-                    {
-                        state.compute_sum += number;
-                        state.compute_sum_seen = true;
-                    }
-                } else {
-                    for (idx, value) in row.iter().enumerate() {
-                        if let Some(number) = SqlEditorWidget::parse_numeric_value(value) {
-                            if let Some(sum_slot) = state.compute_sums.get_mut(idx) {
-                                *sum_slot += number;
-                            }
-                            if let Some(seen_slot) = state.compute_seen_numeric.get_mut(idx) {
-                                *seen_slot = true;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    fn build_compute_summary_row(
-        column_count: usize,
-        compute_config: Option<&crate::db::ComputeConfig>,
-        state: &SelectTransformState,
-    ) -> Option<Vec<String>> {
-        let config = compute_config?;
-        let mode = config.mode;
-        if column_count == 0 {
-            return None;
-        }
-
-        let mut row = vec![String::new(); column_count];
-        if let (Some(of_idx), Some(on_idx)) = (state.compute_of_index, state.compute_on_index) {
-            let label = match mode {
-                crate::db::ComputeMode::Count => "COUNT",
-                crate::db::ComputeMode::Sum => "SUM",
-            };
-            if on_idx < column_count {
-                row[on_idx] = label.to_string();
-            }
-            if of_idx < column_count {
-                row[of_idx] = match mode {
-                    crate::db::ComputeMode::Count => state.compute_count.to_string(),
-                    crate::db::ComputeMode::Sum => {
-                        if state.compute_sum_seen {
-                            SqlEditorWidget::format_number(state.compute_sum)
-                        } else {
-                            "0".to_string()
-                        }
-                    }
-                };
-            }
-            return Some(row);
-        }
-
-        match mode {
-            crate::db::ComputeMode::Count => {
-                if column_count == 1 {
-                    row[0] = state.compute_count.to_string();
-                } else {
-                    row[0] = "COUNT".to_string();
-                    row[column_count - 1] = state.compute_count.to_string();
-                }
-            }
-            crate::db::ComputeMode::Sum => {
-                if let Some(of_idx) = state.compute_of_index {
-                    if column_count == 1 {
-                        row[0] = if state.compute_sum_seen {
-                            SqlEditorWidget::format_number(state.compute_sum)
-                        } else {
-                            "0".to_string()
-                        };
-                    } else {
-                        row[0] = "SUM".to_string();
-                        if of_idx < column_count {
-                            row[of_idx] = if state.compute_sum_seen {
-                                SqlEditorWidget::format_number(state.compute_sum)
-                            } else {
-                                "0".to_string()
-                            };
-                        }
-                    }
-                } else if column_count == 1 {
-                    let total = state.compute_sums.first().copied().unwrap_or(0.0);
-                    row[0] = SqlEditorWidget::format_number(total);
-                } else {
-                    row[0] = "SUM".to_string();
-                    let mut has_any_numeric = false;
-                    for idx in 1..column_count {
-                        if state
-                            .compute_seen_numeric
-                            .get(idx)
-                            .copied()
-                            .unwrap_or(false)
-                        {
-                            let total = state.compute_sums.get(idx).copied().unwrap_or(0.0);
-                            row[idx] = SqlEditorWidget::format_number(total);
-                            has_any_numeric = true;
-                        }
-                    }
-                    if !has_any_numeric {
-                        row[column_count - 1] = "0".to_string();
-                    }
-                }
-            }
-        }
-
-        Some(row)
-    }
-
-    fn format_row_line(row: &[String], colsep: &str, null_text: &str) -> String {
-        let display_row = SqlEditorWidget::display_row_values(row, null_text);
-        display_row.join(colsep)
-    }
-
-    fn apply_heading_setting(column_names: Vec<String>, heading_enabled: bool) -> Vec<String> {
-        if heading_enabled {
-            column_names
-        } else {
-            column_names.into_iter().map(|_| String::new()).collect()
-        }
-    }
-
-    fn apply_heading_to_result(result: &mut QueryResult, heading_enabled: bool) {
-        if heading_enabled {
-            return;
-        }
-        for column in &mut result.columns {
-            column.name.clear();
-        }
-    }
-
-    fn should_flush_progress_rows(last_flush: Instant, buffered_len: usize) -> bool {
-        buffered_len >= PROGRESS_ROWS_MAX_BATCH
-            || last_flush.elapsed() >= PROGRESS_ROWS_FLUSH_INTERVAL
-    }
-
-    fn emit_select_result(
-        sender: &mpsc::Sender<QueryProgress>,
-        session: &Arc<Mutex<SessionState>>,
-        conn_name: &str,
-        index: usize,
-        sql: &str,
-        column_names: Vec<String>,
-        rows: Vec<Vec<String>>,
-        success: bool,
-        feedback_enabled: bool,
-    ) {
-        let _ = sender.send(QueryProgress::StatementStart { index });
-        app::awake();
-        let _ = sender.send(QueryProgress::SelectStart {
-            index,
-            columns: column_names.clone(),
-        });
-        app::awake();
-        let (colsep, null_text, _trimspool_enabled) =
-            SqlEditorWidget::current_text_output_settings(session);
-        if !column_names.is_empty() {
-            SqlEditorWidget::append_spool_output(session, &[column_names.join(&colsep)]);
-        }
-        let display_rows: Vec<Vec<String>> = rows
-            .iter()
-            .map(|row| SqlEditorWidget::display_row_values(row, &null_text))
-            .collect();
-        if !rows.is_empty() {
-            let _ = sender.send(QueryProgress::Rows {
-                index,
-                rows: display_rows.clone(),
-            });
-            app::awake();
-            SqlEditorWidget::append_spool_rows(session, &rows);
-        }
-        let column_info: Vec<ColumnInfo> = column_names
-            .iter()
-            .map(|name| ColumnInfo {
-                name: name.clone(),
-                data_type: "VARCHAR2".to_string(),
-            })
-            .collect();
-        let mut result =
-            QueryResult::new_select(sql, column_info, display_rows, Duration::from_secs(0));
-        result.success = success;
-        if !feedback_enabled {
-            result.message.clear();
-        }
-        if !result.message.trim().is_empty() {
-            SqlEditorWidget::append_spool_output(session, &[result.message.clone()]);
-        }
-        let _ = sender.send(QueryProgress::StatementFinished {
-            index,
-            result,
-            connection_name: conn_name.to_string(),
-            timed_out: false,
-        });
-        app::awake();
-    }
-
-    fn emit_script_output(
-        sender: &mpsc::Sender<QueryProgress>,
-        session: &Arc<Mutex<SessionState>>,
-        lines: Vec<String>,
-    ) {
-        if lines.is_empty() {
-            return;
-        }
-        SqlEditorWidget::append_spool_output(session, &lines);
-        let (trimout_enabled, tab_enabled) =
-            SqlEditorWidget::current_script_output_settings(session);
-        let display_lines: Vec<String> = lines
-            .into_iter()
-            .map(|line| {
-                SqlEditorWidget::format_script_output_line(&line, trimout_enabled, tab_enabled)
-            })
-            .collect();
-        let _ = sender.send(QueryProgress::ScriptOutput {
-            lines: display_lines,
-        });
-        app::awake();
-    }
-
-    fn emit_timing_if_enabled(
-        sender: &mpsc::Sender<QueryProgress>,
-        session: &Arc<Mutex<SessionState>>,
-        duration: Duration,
-    ) {
-        let enabled = match session.lock() {
-            Ok(guard) => guard.timing_enabled,
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                poisoned.into_inner().timing_enabled
-            }
-        };
-        if !enabled {
-            return;
-        }
-        let line = format!("Elapsed: {:.3}s", duration.as_secs_f64());
-        SqlEditorWidget::emit_script_output(sender, session, vec![line]);
-    }
-
-    fn emit_script_lines(
-        sender: &mpsc::Sender<QueryProgress>,
-        session: &Arc<Mutex<SessionState>>,
-        message: &str,
-    ) {
-        let lines: Vec<String> = message.lines().map(|line| line.to_string()).collect();
-        if lines.is_empty() {
-            return;
-        }
-        SqlEditorWidget::emit_script_output(sender, session, lines);
-    }
-
-    fn emit_script_message(
-        sender: &mpsc::Sender<QueryProgress>,
-        session: &Arc<Mutex<SessionState>>,
-        title: &str,
-        message: &str,
-    ) {
-        let mut lines = Vec::new();
-        lines.push(format!("[{}]", title));
-        for line in message.lines() {
-            lines.push(line.to_string());
-        }
-        SqlEditorWidget::emit_script_output(sender, session, lines);
-    }
-
-    fn append_spool_output(session: &Arc<Mutex<SessionState>>, lines: &[String]) {
-        if lines.is_empty() {
-            return;
-        }
-
-        let (path, truncate, trimspool_enabled) = match session.lock() {
-            Ok(mut guard) => {
-                let path = guard.spool_path.clone();
-                let truncate = guard.spool_truncate;
-                if truncate {
-                    guard.spool_truncate = false;
-                }
-                (path, truncate, guard.trimspool_enabled)
-            }
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                let mut guard = poisoned.into_inner();
-                let path = guard.spool_path.clone();
-                let truncate = guard.spool_truncate;
-                if truncate {
-                    guard.spool_truncate = false;
-                }
-                (path, truncate, guard.trimspool_enabled)
-            }
-        };
-
-        let Some(path) = path else {
-            return;
-        };
-
-        let mut options = OpenOptions::new();
-        options.create(true).write(true);
-        if truncate {
-            options.truncate(true);
-        } else {
-            options.append(true);
-        }
-
-        let mut file = match options.open(&path) {
-            Ok(file) => file,
-            Err(err) => {
-                eprintln!("Failed to open spool file {}: {}", path.display(), err);
-                return;
-            }
-        };
-
-        for line in lines {
-            let line_to_write = if trimspool_enabled {
-                line.trim_end()
-            } else {
-                line.as_str()
-            };
-            if let Err(err) = writeln!(file, "{}", line_to_write) {
-                eprintln!("Failed to write to spool file {}: {}", path.display(), err);
-                break;
-            }
-        }
-    }
-
-    fn append_spool_rows(session: &Arc<Mutex<SessionState>>, rows: &[Vec<String>]) {
-        if rows.is_empty() {
-            return;
-        }
-        if !SqlEditorWidget::has_spool_target(session) {
-            return;
-        }
-        let (colsep, null_text, _trimspool_enabled) =
-            SqlEditorWidget::current_text_output_settings(session);
-        let lines: Vec<String> = rows
-            .iter()
-            .map(|row| SqlEditorWidget::format_row_line(row, &colsep, &null_text))
-            .collect();
-        SqlEditorWidget::append_spool_output(session, &lines);
-    }
-
-    fn apply_define_substitution(
-        sql: &str,
-        session: &Arc<Mutex<SessionState>>,
-        sender: &mpsc::Sender<QueryProgress>,
-    ) -> Result<String, String> {
-        let define_char = match session.lock() {
-            Ok(guard) => guard.define_char,
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                poisoned.into_inner().define_char
-            }
-        };
-
-        let mut result = String::with_capacity(sql.len());
-        let mut in_single_quote = false;
-        let mut in_double_quote = false;
-        let mut in_line_comment = false;
-        let mut in_block_comment = false;
-        let mut in_q_quote = false;
-        let mut q_quote_end: Option<char> = None;
-
-        let chars: Vec<char> = sql.chars().collect();
-        let len = chars.len();
-        let mut i = 0usize;
-
-        while i < len {
-            let c = chars[i];
-            let next = if i + 1 < len {
-                Some(chars[i + 1])
-            } else {
-                None
-            };
-            let next2 = if i + 2 < len {
-                Some(chars[i + 2])
-            } else {
-                None
-            };
-
-            if in_line_comment {
-                result.push(c);
-                if c == '\n' {
-                    in_line_comment = false;
-                }
-                i += 1;
-                continue;
-            }
-
-            if in_block_comment {
-                result.push(c);
-                if c == '*' && next == Some('/') {
-                    result.push('/');
-                    in_block_comment = false;
-                    i += 2;
-                    continue;
-                }
-                i += 1;
-                continue;
-            }
-
-            if c == '-' && next == Some('-') {
-                result.push('-');
-                result.push('-');
-                in_line_comment = true;
-                i += 2;
-                continue;
-            }
-
-            if c == '/' && next == Some('*') {
-                result.push('/');
-                result.push('*');
-                in_block_comment = true;
-                i += 2;
-                continue;
-            }
-
-            if c == define_char {
-                let is_double = next == Some(define_char);
-                let start = if is_double { i + 2 } else { i + 1 };
-                let mut j = start;
-                while j < len {
-                    let ch = chars[j];
-                    if ch.is_ascii_alphanumeric() || ch == '_' || ch == '$' || ch == '#' {
-                        j += 1;
-                    } else {
-                        break;
-                    }
-                }
-
-                if j == start {
-                    result.push(c);
-                    if is_double {
-                        result.push(define_char);
-                        i += 2;
-                    } else {
-                        i += 1;
-                    }
-                    continue;
-                }
-
-                let name: String = chars[start..j].iter().collect();
-                let key = SessionState::normalize_name(&name);
-                let (define_value, bind_value) = match session.lock() {
-                    Ok(guard) => (
-                        guard.define_vars.get(&key).cloned(),
-                        guard.binds.get(&key).cloned(),
-                    ),
-                    Err(poisoned) => {
-                        eprintln!("Warning: session state lock was poisoned; recovering.");
-                        let guard = poisoned.into_inner();
-                        (
-                            guard.define_vars.get(&key).cloned(),
-                            guard.binds.get(&key).cloned(),
-                        )
-                    }
-                };
-
-                let mut replacement = if let Some(value) = define_value {
-                    value
-                } else if let Some(bind) = bind_value {
-                    SqlEditorWidget::format_define_value(&key, &bind)?
-                } else {
-                    let prompt = format!("Enter value for {}:", name);
-                    let input = SqlEditorWidget::prompt_for_input_with_sender(sender, &prompt)?;
-                    if is_double {
-                        match session.lock() {
-                            Ok(mut guard) => {
-                                guard.define_vars.insert(key.clone(), input.clone());
-                            }
-                            Err(poisoned) => {
-                                eprintln!("Warning: session state lock was poisoned; recovering.");
-                                let mut guard = poisoned.into_inner();
-                                guard.define_vars.insert(key.clone(), input.clone());
-                            }
-                        }
-                    }
-                    input
-                }
-                .to_string();
-
-                if in_single_quote || in_q_quote {
-                    if let Some(stripped) =
-                        SqlEditorWidget::strip_wrapping_single_quotes(&replacement)
-                    {
-                        replacement = stripped;
-                    }
-                }
-
-                result.push_str(&replacement);
-                i = j;
-                continue;
-            }
-
-            if in_q_quote {
-                result.push(c);
-                if Some(c) == q_quote_end && next == Some('\'') {
-                    result.push('\'');
-                    in_q_quote = false;
-                    q_quote_end = None;
-                    i += 2;
-                    continue;
-                }
-                i += 1;
-                continue;
-            }
-
-            if in_single_quote {
-                result.push(c);
-                if c == '\'' {
-                    if next == Some('\'') {
-                        result.push('\'');
-                        i += 2;
-                        continue;
-                    }
-                    in_single_quote = false;
-                }
-                i += 1;
-                continue;
-            }
-
-            if in_double_quote {
-                result.push(c);
-                if c == '"' {
-                    in_double_quote = false;
-                }
-                i += 1;
-                continue;
-            }
-
-            // Handle nq'[...]' (National Character q-quoted strings)
-            if (c == 'n' || c == 'N')
-                && (next == Some('q') || next == Some('Q'))
-                && i + 2 < len
-                && chars[i + 2] == '\''
-                && i + 3 < len
-            {
-                let delimiter = chars[i + 3];
-                let closing = match delimiter {
-                    '(' => Some(')'),
-                    '[' => Some(']'),
-                    '{' => Some('}'),
-                    '<' => Some('>'),
-                    _ => Some(delimiter),
-                };
-                result.push(c);
-                result.push(chars[i + 1]);
-                result.push('\'');
-                result.push(delimiter);
-                in_q_quote = true;
-                q_quote_end = closing;
-                i += 4;
-                continue;
-            }
-
-            // Handle q'[...]' (q-quoted strings)
-            if (c == 'q' || c == 'Q') && next == Some('\'') && next2.is_some() {
-                let delimiter = chars[i + 2];
-                let closing = match delimiter {
-                    '(' => Some(')'),
-                    '[' => Some(']'),
-                    '{' => Some('}'),
-                    '<' => Some('>'),
-                    _ => Some(delimiter),
-                };
-                result.push(c);
-                result.push('\'');
-                result.push(delimiter);
-                in_q_quote = true;
-                q_quote_end = closing;
-                i += 3;
-                continue;
-            }
-
-            if c == '\'' {
-                result.push(c);
-                in_single_quote = true;
-                i += 1;
-                continue;
-            }
-
-            if c == '"' {
-                result.push(c);
-                in_double_quote = true;
-                i += 1;
-                continue;
-            }
-
-            result.push(c);
-            i += 1;
-        }
-
-        Ok(result)
-    }
-
-    fn prompt_for_input_with_sender(
-        sender: &mpsc::Sender<QueryProgress>,
-        prompt: &str,
-    ) -> Result<String, String> {
-        let (response_tx, response_rx) = mpsc::channel();
-        if sender
-            .send(QueryProgress::PromptInput {
-                prompt: prompt.to_string(),
-                response: response_tx,
-            })
-            .is_err()
-        {
-            return Err("Substitution prompt failed: UI disconnected.".to_string());
-        }
-
-        match response_rx.recv_timeout(Duration::from_secs(300)) {
-            Ok(Some(value)) => Ok(value),
-            Ok(None) => Err("Substitution prompt cancelled.".to_string()),
-            Err(mpsc::RecvTimeoutError::Timeout) => {
-                Err("Substitution prompt timed out.".to_string())
-            }
-            Err(mpsc::RecvTimeoutError::Disconnected) => {
-                Err("Substitution prompt failed: UI disconnected.".to_string())
-            }
-        }
-    }
-
-    pub fn prompt_input_dialog(prompt: &str) -> Option<String> {
-        let current_group = fltk::group::Group::try_current();
-        fltk::group::Group::set_current(None::<&fltk::group::Group>);
-
-        let mut dialog = fltk::window::Window::default()
-            .with_size(420, 150)
-            .with_label("Input");
-        crate::ui::center_on_main(&mut dialog);
-        dialog.set_color(theme::panel_raised());
-        dialog.make_modal(true);
-
-        let mut main_flex = Flex::default().with_pos(10, 10).with_size(400, 130);
-        main_flex.set_type(FlexType::Column);
-        main_flex.set_spacing(8);
-
-        let mut prompt_frame = Frame::default().with_label(prompt);
-        prompt_frame.set_label_color(theme::text_primary());
-        prompt_frame.set_align(Align::Left | Align::Inside | Align::Wrap);
-        main_flex.fixed(&prompt_frame, 48);
-
-        let mut input = Input::default();
-        input.set_color(theme::input_bg());
-        input.set_text_color(theme::text_primary());
-        input.set_trigger(CallbackTrigger::EnterKeyAlways);
-        main_flex.fixed(&input, 30);
-
-        let mut button_flex = Flex::default();
-        button_flex.set_type(FlexType::Row);
-        button_flex.set_spacing(8);
-
-        let _spacer = Frame::default();
-
-        let mut ok_btn = Button::default()
-            .with_size(BUTTON_WIDTH, BUTTON_HEIGHT)
-            .with_label("OK");
-        ok_btn.set_color(theme::button_primary());
-        ok_btn.set_label_color(theme::text_primary());
-        ok_btn.set_frame(FrameType::RFlatBox);
-
-        let mut cancel_btn = Button::default()
-            .with_size(BUTTON_WIDTH, BUTTON_HEIGHT)
-            .with_label("Cancel");
-        cancel_btn.set_color(theme::button_subtle());
-        cancel_btn.set_label_color(theme::text_primary());
-        cancel_btn.set_frame(FrameType::RFlatBox);
-
-        button_flex.fixed(&ok_btn, BUTTON_WIDTH);
-        button_flex.fixed(&cancel_btn, BUTTON_WIDTH);
-        button_flex.end();
-        main_flex.fixed(&button_flex, BUTTON_ROW_HEIGHT);
-        main_flex.end();
-        dialog.end();
-        fltk::group::Group::set_current(current_group.as_ref());
-
-        let result: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
-        let cancelled = Rc::new(RefCell::new(false));
-
-        {
-            let result = result.clone();
-            let mut dialog = dialog.clone();
-            let input = input.clone();
-            ok_btn.set_callback(move |_| {
-                *result.borrow_mut() = Some(input.value());
-                dialog.hide();
-            });
-        }
+        {
+            let result = result.clone();
+            let mut dialog = dialog.clone();
+            let input = input.clone();
+            ok_btn.set_callback(move |_| {
+                *result.borrow_mut() = Some(input.value());
+                dialog.hide();
+            });
+        }
 
         {
             let cancelled = cancelled.clone();
@@ -7664,178 +2912,47 @@ impl SqlEditorWidget {
         }
     }
 
-    fn format_define_value(name: &str, bind: &BindVar) -> Result<String, String> {
-        let BindValue::Scalar(value) = &bind.value else {
-            return Err(format!(
-                "Substitution variable &{} must be a scalar value.",
-                name
-            ));
+    /// Fill the `&name` placeholders of `sql` as scripts do: DEFINE values
+    /// of the connection's session first, then a prompt for each other name.
+    /// Returns `None` when a prompt was cancelled or substitution failed.
+    pub fn resolve_substitutions(connection: &SharedConnection, sql: &str) -> Option<String> {
+        // Don't wait for a running query to release the connection.
+        let session = match try_lock_connection(connection) {
+            Some(guard) => guard.session_state(),
+            None => Arc::new(Mutex::new(SessionState::default())),
         };
-
-        let value = value
-            .as_ref()
-            .ok_or_else(|| format!("Substitution variable &{} has no value.", name))?;
-
-        if value.eq_ignore_ascii_case("NULL") {
-            return Ok("NULL".to_string());
-        }
-
-        match bind.data_type {
-            crate::db::session::BindDataType::Number => Ok(value.clone()),
-            crate::db::session::BindDataType::Date
-            | crate::db::session::BindDataType::Timestamp(_)
-            | crate::db::session::BindDataType::Varchar2(_)
-            | crate::db::session::BindDataType::Clob => {
-                Ok(format!("'{}'", SqlEditorWidget::escape_sql_literal(value)))
+        let sink = DialogPromptSink::default();
+        match ScriptRunner::apply_define_substitution(sql, &session, &sink) {
+            Ok(text) => Some(text),
+            Err(_) if sink.cancelled.get() => None,
+            Err(err) => {
+                fltk::dialog::alert_default(&err);
+                None
             }
-            crate::db::session::BindDataType::RefCursor => Err(format!(
-                "Substitution variable &{} cannot be a REFCURSOR.",
-                name
-            )),
-        }
-    }
-
-    fn escape_sql_literal(value: &str) -> String {
-        value.replace('\'', "''")
-    }
-
-    fn strip_wrapping_single_quotes(value: &str) -> Option<String> {
-        let trimmed = value.trim();
-        if trimmed.len() < 2 {
-            return None;
-        }
-        if trimmed.starts_with('\'') && trimmed.ends_with('\'') {
-            Some(trimmed[1..trimmed.len() - 1].to_string())
-        } else {
-            None
         }
     }
 
-    fn sync_serveroutput_with_session(
-        conn: &Connection,
-        session: &Arc<Mutex<SessionState>>,
-    ) -> Result<(), OracleError> {
-        let (enabled, size) = match session.lock() {
-            Ok(guard) => (guard.server_output.enabled, guard.server_output.size),
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                let guard = poisoned.into_inner();
-                (guard.server_output.enabled, guard.server_output.size)
-            }
+    /// Insert a snippet at the cursor, replacing the selection, after
+    /// filling in its placeholders.
+    pub fn insert_snippet(&self, snippet: &SqlSnippet) {
+        let Some(text) = Self::resolve_substitutions(&self.connection, &snippet.sql) else {
+            return;
         };
-
-        if enabled {
-            let buffer_size = if size == 0 { None } else { Some(size) };
-            QueryExecutor::enable_dbms_output(conn, buffer_size)
-        } else {
-            QueryExecutor::disable_dbms_output(conn)
-        }
-    }
-
-    fn emit_dbms_output(
-        sender: &mpsc::Sender<QueryProgress>,
-        _conn_name: &str,
-        conn: &Connection,
-        session: &Arc<Mutex<SessionState>>,
-        _result_index: &mut usize,
-    ) -> Result<(), OracleError> {
-        let (enabled, size) = match session.lock() {
-            Ok(guard) => (guard.server_output.enabled, guard.server_output.size),
-            Err(poisoned) => {
-                eprintln!("Warning: session state lock was poisoned; recovering.");
-                let guard = poisoned.into_inner();
-                (guard.server_output.enabled, guard.server_output.size)
+        let mut buffer = self.buffer.clone();
+        let mut editor = self.editor.clone();
+        let (start, end) = match buffer.selection_position() {
+            Some((a, b)) if a != b => (a.min(b), a.max(b)),
+            _ => {
+                let pos = editor.insert_position().max(0);
+                (pos, pos)
             }
         };
-
-        if !enabled {
-            return Ok(());
-        }
-
-        let max_lines = if size == 0 {
-            10_000
-        } else {
-            (size / 80).max(1).min(10_000)
-        };
-        let lines = QueryExecutor::get_dbms_output(conn, max_lines)?;
-        if lines.is_empty() {
-            return Ok(());
-        }
-
-        let mut output_lines = Vec::with_capacity(lines.len() + 1);
-        output_lines.push("DBMS_OUTPUT".to_string());
-        output_lines.extend(lines);
-        SqlEditorWidget::emit_script_output(sender, session, output_lines);
-        Ok(())
-    }
-
-    fn ensure_plsql_terminator(sql: &str) -> String {
-        let trimmed = sql.trim_end();
-        if trimmed.ends_with(';') {
-            trimmed.to_string()
-        } else {
-            format!("{};", trimmed)
-        }
-    }
-
-    fn strip_leading_comments(sql: &str) -> String {
-        let mut remaining = sql;
-
-        loop {
-            let trimmed = remaining.trim_start();
-
-            if trimmed.starts_with("--") {
-                if let Some(line_end) = trimmed.find('\n') {
-                    remaining = &trimmed[line_end + 1..];
-                    continue;
-                }
-                return String::new();
-            }
-
-            if trimmed.starts_with("/*") {
-                if let Some(block_end) = trimmed.find("*/") {
-                    remaining = &trimmed[block_end + 2..];
-                    continue;
-                }
-                return String::new();
-            }
-
-            return trimmed.to_string();
-        }
-    }
-
-    fn normalize_object_name(value: &str) -> String {
-        let trimmed = value.trim();
-        if trimmed.starts_with('"') && trimmed.ends_with('"') && trimmed.len() >= 2 {
-            trimmed.trim_matches('"').to_string()
-        } else {
-            trimmed.to_uppercase()
-        }
-    }
-
-    fn ddl_message(sql_upper: &str) -> String {
-        QueryExecutor::ddl_message(sql_upper)
-    }
-
-    fn is_timeout_error(err: &OracleError) -> bool {
-        let message = err.to_string();
-        message.contains("DPI-1067")
-    }
-
-    fn is_cancel_error(err: &OracleError) -> bool {
-        let message = err.to_string();
-        message.contains("ORA-01013")
-    }
-
-    fn timeout_message(timeout: Option<Duration>) -> String {
-        match timeout {
-            Some(duration) => format!("Query timed out after {} seconds", duration.as_secs()),
-            None => "Query timed out".to_string(),
-        }
-    }
-
-    fn cancel_message() -> String {
-        "Query cancelled".to_string()
+        buffer.unselect();
+        buffer.replace(start, end, &text);
+        editor.set_insert_position(start + text.len() as i32);
+        editor.show_insert_position();
+        self.refresh_highlighting();
+        let _ = editor.take_focus();
     }
 
     fn parse_timeout(value: &str) -> Option<Duration> {
@@ -7947,20 +3064,6 @@ END;";
         );
     }
 
-    #[test]
-    fn tab_off_keeps_tab_character_in_script_output() {
-        let line = "A\tB";
-        let rendered = SqlEditorWidget::format_script_output_line(line, false, false);
-        assert_eq!(rendered, "A\tB");
-    }
-
-    #[test]
-    fn tab_on_expands_tab_character_in_script_output() {
-        let line = "A\tB";
-        let rendered = SqlEditorWidget::format_script_output_line(line, false, true);
-        assert_eq!(rendered, "A       B");
-    }
-
     #[test]
     fn nested_case_expression_in_plsql_aligns_else_correctly() {
         let sql = r#"CREATE OR REPLACE PACKAGE BODY oqt_mega_pkg AS
@@ -22,6 +22,7 @@ use crate::ui::intellisense::{
     detect_sql_context, get_word_at_cursor, IntellisenseData, IntellisensePopup, SqlContext,
 };
 use crate::ui::intellisense_context;
+use crate::ui::snippet_dialog::{snippet_for_suggestion, snippet_suggestions};
 use crate::ui::FindReplaceDialog;
 
 use super::*;
@@ -99,6 +100,18 @@ impl SqlEditorWidget {
             popup.set_selected_callback(move |selected| {
                 let cursor_pos = editor_for_insert.insert_position().max(0);
                 let cursor_pos_usize = cursor_pos as usize;
+                let selected = match snippet_for_suggestion(&selected) {
+                    Some(snippet) => {
+                        match Self::resolve_substitutions(&connection_for_insert, &snippet.sql) {
+                            Some(text) => text,
+                            None => {
+                                *completion_range_for_insert.borrow_mut() = None;
+                                return;
+                            }
+                        }
+                    }
+                    None => selected,
+                };
                 let context_text = Self::context_before_cursor(&buffer_for_insert, cursor_pos);
                 let context = detect_sql_context(&context_text, context_text.len());
                 if matches!(context, SqlContext::TableName) {
@@ -158,6 +171,7 @@ impl SqlEditorWidget {
         let ctrl_enter_handled_for_handle = ctrl_enter_handled.clone();
         let pending_intellisense_for_handle = pending_intellisense.clone();
         let dnd_file_drop_pending_for_handle = Rc::new(RefCell::new(false));
+        let breakpoints_for_handle = self.breakpoints.clone();
 
         editor.handle(move |ed, ev| {
             match ev {
@@ -174,6 +188,9 @@ impl SqlEditorWidget {
                     true
                 }
                 Event::Push => {
+                    if Self::toggle_breakpoint_at_event(ed, &breakpoints_for_handle) {
+                        return true;
+                    }
                     let state = fltk::app::event_state();
                     let ctrl_or_cmd = state.contains(fltk::enums::Shortcut::Ctrl)
                         || state.contains(fltk::enums::Shortcut::Command);
@@ -342,6 +359,11 @@ impl SqlEditorWidget {
                             return true;
                         }
 
+                        // Find in Tabs needs every tab, so leave it to the menu.
+                        if shift && (key == Key::from_char('h') || key == Key::from_char('H')) {
+                            return false;
+                        }
+
                         match key {
                             k if k == Key::from_char('z') || k == Key::from_char('Z') => {
                                 widget_for_shortcuts.undo();
@@ -425,6 +447,11 @@ impl SqlEditorWidget {
                         return true;
                     }
 
+                    if key == Key::F6 && fltk::app::is_event_shift() {
+                        widget_for_shortcuts.autotrace_current();
+                        return true;
+                    }
+
                     if key == Key::F6 {
                         widget_for_shortcuts.explain_current();
                         return true;
@@ -935,11 +962,20 @@ impl SqlEditorWidget {
         };
         let context_alias_suggestions =
             Self::collect_context_alias_suggestions(&prefix, &deep_ctx);
-        let suggestions = Self::merge_suggestions_with_context_aliases(
+        let mut suggestions = Self::merge_suggestions_with_context_aliases(
             suggestions,
             context_alias_suggestions,
             matches!(context, SqlContext::TableName),
         );
+        // Snippets are offered by trigger word wherever a keyword could go.
+        if qualifier.is_none() && matches!(context, SqlContext::General) {
+            let mut snippets = snippet_suggestions(&prefix);
+            if !snippets.is_empty() {
+                snippets.append(&mut suggestions);
+                snippets.truncate(MAX_MERGED_SUGGESTIONS);
+                suggestions = snippets;
+            }
+        }
 
         let should_refresh_when_columns_ready = include_columns && columns_loading;
         if should_refresh_when_columns_ready {
@@ -1157,19 +1193,20 @@ impl SqlEditorWidget {
             return;
         }
 
-        let table_key = {
+        let (table_key, owner) = {
             let mut data = intellisense_data.borrow_mut();
             let selected = table_key_candidates
                 .iter()
                 .find(|candidate| data.is_known_relation(candidate))
-                .cloned();
-            let Some(selected) = selected else {
+                .map(|candidate| (candidate.clone(), None))
+                .or_else(|| Self::schema_qualified_relation(&table_key_candidates[0], &data));
+            let Some((selected, owner)) = selected else {
                 return;
             };
             if !data.mark_columns_loading(&selected) {
                 return;
             }
-            selected
+            (selected, owner)
         };
 
         let connection = connection.clone();
@@ -1204,10 +1241,20 @@ impl SqlEditorWidget {
             let (columns, cache_columns) = if !conn_guard.is_connected() {
                 (Vec::new(), false)
             } else if let Some(conn) = conn_guard.get_connection() {
-                match crate::db::ObjectBrowser::get_table_columns(
+                let relation = if owner.is_some() {
+                    table_key_for_thread
+                        .rsplit('.')
+                        .next()
+                        .unwrap_or(&table_key_for_thread)
+                } else {
+                    &table_key_for_thread
+                };
+                let result = crate::db::ObjectBrowser::get_table_columns_for_owner(
                     conn.as_ref(),
-                    &table_key_for_thread,
-                ) {
+                    owner.as_deref(),
+                    relation,
+                );
+                match result {
                     Ok(cols) => (cols.into_iter().map(|col| col.name).collect(), true),
                     Err(_) => (Vec::new(), false),
                 }
@@ -1224,6 +1271,24 @@ impl SqlEditorWidget {
         });
     }
 
+    /// Resolve `OWNER.NAME` against the visible schemas so columns of relations
+    /// in other schemas can be loaded through ALL_TAB_COLUMNS.
+    fn schema_qualified_relation(
+        table_name: &str,
+        data: &IntellisenseData,
+    ) -> Option<(String, Option<String>)> {
+        let (owner, relation) = table_name.split_once('.')?;
+        let owner = owner.trim();
+        let relation = relation.trim();
+        if owner.is_empty() || relation.is_empty() || relation.contains('.') {
+            return None;
+        }
+        if !data.is_known_schema(owner) {
+            return None;
+        }
+        Some((table_name.to_string(), Some(owner.to_uppercase())))
+    }
+
     fn table_lookup_key_candidates(table_name: &str) -> Vec<String> {
         let normalized = Self::strip_identifier_quotes(table_name.trim());
         if normalized.is_empty() {
@@ -1457,6 +1522,20 @@ impl SqlEditorWidget {
             }
         }
 
+        if let Some(owner) = qualifier {
+            // OWNER.NAME: a table or view in another schema.
+            let owner_upper = owner.to_uppercase();
+            if let Ok(columns) = ObjectBrowser::get_table_structure_for_owner(
+                conn,
+                Some(&owner_upper),
+                &object_name_upper,
+            ) {
+                if !columns.is_empty() {
+                    return Ok(QuickDescribeData::TableColumns(columns));
+                }
+            }
+        }
+
         if let Ok(columns) = ObjectBrowser::get_table_structure(conn, &object_name_upper) {
             if !columns.is_empty() {
                 return Ok(QuickDescribeData::TableColumns(columns));
@@ -2289,6 +2368,47 @@ mod intellisense_regression_tests {
         assert!(!update.cache_columns);
     }
 
+    #[test]
+    fn request_table_columns_uses_schema_qualified_name_for_known_schema() {
+        let data = Rc::new(RefCell::new(IntellisenseData::new()));
+        {
+            let mut guard = data.borrow_mut();
+            guard.tables = vec!["EMP".to_string()];
+            guard.schemas = vec!["HR".to_string()];
+            guard.rebuild_indices();
+        }
+
+        let (sender, receiver) = mpsc::channel::<ColumnLoadUpdate>();
+        let connection = create_shared_connection();
+        let _conn_guard = connection.lock().ok();
+
+        SqlEditorWidget::request_table_columns("hr.departments", &data, &sender, &connection);
+
+        let update = receiver
+            .recv_timeout(Duration::from_secs(1))
+            .expect("relations in a visible schema should be loaded by qualified name");
+        assert_eq!(update.table, "hr.departments");
+        assert!(!update.cache_columns);
+    }
+
+    #[test]
+    fn request_table_columns_ignores_unknown_schema_qualifier() {
+        let data = Rc::new(RefCell::new(IntellisenseData::new()));
+        {
+            let mut guard = data.borrow_mut();
+            guard.schemas = vec!["HR".to_string()];
+            guard.rebuild_indices();
+        }
+
+        let (sender, receiver) = mpsc::channel::<ColumnLoadUpdate>();
+        let connection = create_shared_connection();
+
+        SqlEditorWidget::request_table_columns("SCOTT.DEPT", &data, &sender, &connection);
+
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+        assert!(data.borrow().columns_loading.is_empty());
+    }
+
     #[test]
     fn column_loading_scope_detects_unqualified_pending_refresh() {
         let mut data = IntellisenseData::new();
@@ -1,5 +1,6 @@
 use fltk::{
     app,
+    browser::HoldBrowser,
     draw::set_cursor,
     enums::{Cursor, FrameType},
     frame::Frame,
@@ -9,6 +10,7 @@ use fltk::{
     text::{TextBuffer, TextEditor, WrapMode},
 };
 use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -16,9 +18,13 @@ use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
-use crate::db::{ConnectionInfo, QueryExecutor, QueryResult, SharedConnection, TableColumnDetail};
+use crate::db::{
+    CellValue, DmlStatement, ExecutionPlan, QueryExecutor, QueryProgress, QueryResult,
+    ScriptEventSink, SharedBackend, SharedConnection, SourceDiagnostic, TableColumnDetail,
+    TransactionState,
+};
 use crate::ui::constants::*;
-use crate::ui::font_settings::{configured_editor_profile, configured_ui_font_size, FontProfile};
+use crate::ui::font_settings::{configured_editor_profile, FontProfile};
 use crate::ui::intellisense::{IntellisenseData, IntellisensePopup};
 use crate::ui::query_history::QueryHistoryDialog;
 use crate::ui::syntax_highlight::{
@@ -27,8 +33,9 @@ use crate::ui::syntax_highlight::{
 };
 use crate::ui::theme;
 use crate::utils::{AppConfig, QueryHistory};
-use oracle::Connection;
 
+mod breakpoints;
+mod diagnostics;
 mod execution;
 mod intellisense;
 
@@ -53,6 +60,9 @@ const EDITOR_TOP_PADDING: i32 = 4;
 enum EditGranularity {
     Word,
     Other,
+    /// A range replaced in one edit, e.g. Replace All or Format SQL. Always
+    /// its own undo step.
+    Replace,
 }
 
 #[derive(Clone)]
@@ -93,42 +103,49 @@ impl WordUndoRedoState {
             .map(|text| text == current_text)
             .unwrap_or(false)
     }
+
+    /// Record an edit, merging it into the current step when it continues
+    /// an edit of the same granularity.
+    fn record(&mut self, current_text: String, granularity: EditGranularity) {
+        let current_index = self.index;
+        if current_index + 1 < self.history.len() {
+            self.history.truncate(current_index + 1);
+        }
+
+        if self.active_group == Some(granularity) {
+            if let Some(snapshot) = self.history.get_mut(current_index) {
+                *snapshot = current_text;
+            } else {
+                self.history.push(current_text);
+                self.index = self.history.len().saturating_sub(1);
+                self.active_group = Some(granularity);
+            }
+        } else {
+            self.history.push(current_text);
+            if self.history.len() > MAX_WORD_UNDO_HISTORY {
+                self.history.remove(0);
+            }
+            self.index = self.history.len().saturating_sub(1);
+            self.active_group = if granularity == EditGranularity::Replace {
+                None
+            } else {
+                Some(granularity)
+            };
+        }
+    }
 }
 
-#[derive(Clone)]
-pub enum QueryProgress {
-    BatchStart,
-    StatementStart {
-        index: usize,
-    },
-    SelectStart {
-        index: usize,
-        columns: Vec<String>,
-    },
-    Rows {
-        index: usize,
-        rows: Vec<Vec<String>>,
-    },
-    ScriptOutput {
-        lines: Vec<String>,
-    },
-    PromptInput {
-        prompt: String,
-        response: mpsc::Sender<Option<String>>,
-    },
-    AutoCommitChanged {
-        enabled: bool,
-    },
-    ConnectionChanged {
-        info: Option<ConnectionInfo>,
-    },
-    StatementFinished {
-        index: usize,
-        result: QueryResult,
-        connection_name: String,
-        timed_out: bool,
-    },
-    BatchFinished,
+/// Forwards script events to the editor's progress channel and wakes the
+/// FLTK event loop so they are handled promptly.
+struct ProgressSink {
+    sender: mpsc::Sender<QueryProgress>,
+}
+
+impl ScriptEventSink for ProgressSink {
+    fn emit(&self, event: QueryProgress) {
+        let _ = self.sender.send(event);
+        app::awake();
+    }
 }
 
 #[derive(Clone)]
@@ -151,17 +168,21 @@ pub(crate) enum QuickDescribeData {
 
 #[derive(Clone)]
 enum UiActionResult {
-    ExplainPlan(Result<Vec<String>, String>),
+    ExplainPlan(Result<ExecutionPlan, String>),
     QuickDescribe {
         object_name: String,
         result: Result<QuickDescribeData, String>,
     },
     Commit(Result<(), String>),
     Rollback(Result<(), String>),
+    GridChanges(Result<String, String>),
     Cancel(Result<(), String>),
     QueryAlreadyRunning,
 }
 
+/// Receives the outcome of applying result grid edits.
+pub type GridChangesCallback = Box<dyn FnOnce(Result<String, String>)>;
+
 #[derive(Clone)]
 pub struct SqlEditorWidget {
     group: Flex,
@@ -175,7 +196,7 @@ pub struct SqlEditorWidget {
     column_sender: mpsc::Sender<ColumnLoadUpdate>,
     ui_action_sender: mpsc::Sender<UiActionResult>,
     query_running: Rc<RefCell<bool>>,
-    current_query_connection: Arc<Mutex<Option<Arc<Connection>>>>,
+    current_query_connection: Arc<Mutex<Option<SharedBackend>>>,
     cancel_flag: Arc<AtomicBool>,
     intellisense_data: Rc<RefCell<IntellisenseData>>,
     intellisense_popup: Rc<RefCell<IntellisensePopup>>,
@@ -190,6 +211,15 @@ pub struct SqlEditorWidget {
     history_cursor: Rc<RefCell<Option<usize>>>,
     history_original: Rc<RefCell<Option<String>>>,
     undo_redo_state: Rc<RefCell<WordUndoRedoState>>,
+    /// Completion for the grid edits currently being applied.
+    grid_changes_callback: Rc<RefCell<Option<GridChangesCallback>>>,
+    /// Editor lines carrying a debugger breakpoint.
+    breakpoints: Rc<RefCell<BTreeSet<usize>>>,
+    /// Line the debugger is stopped on.
+    debug_line: Rc<RefCell<Option<usize>>>,
+    /// Compiler messages of the last compile of an edited stored program.
+    diagnostics: Rc<RefCell<Vec<SourceDiagnostic>>>,
+    diagnostics_list: HoldBrowser,
 }
 
 impl SqlEditorWidget {
@@ -287,6 +317,9 @@ impl SqlEditorWidget {
         let style_table = create_style_table_with(editor_profile, editor_size);
         editor.set_highlight_data(style_buffer.clone(), style_table);
 
+        let diagnostics_list = Self::create_diagnostics_list();
+        group.fixed(&diagnostics_list, 0);
+
         // Add editor to flex and make it resizable (takes remaining space)
         group.resizable(&editor);
         group.end();
@@ -344,11 +377,18 @@ impl SqlEditorWidget {
             history_cursor,
             history_original,
             undo_redo_state,
+            grid_changes_callback: Rc::new(RefCell::new(None)),
+            breakpoints: Rc::new(RefCell::new(BTreeSet::new())),
+            debug_line: Rc::new(RefCell::new(None)),
+            diagnostics: Rc::new(RefCell::new(Vec::new())),
+            diagnostics_list,
         };
 
         widget.setup_intellisense();
         widget.setup_word_undo_redo();
         widget.setup_syntax_highlighting();
+        widget.setup_gutter_markers();
+        widget.setup_diagnostics_list();
         widget.setup_progress_handler(progress_receiver, progress_callback, query_running);
         widget.setup_column_loader(column_receiver);
         widget.setup_ui_action_handler(ui_action_receiver);
@@ -374,30 +414,7 @@ impl SqlEditorWidget {
             }
 
             let granularity = classify_edit_granularity(ins, del, &inserted, deleted_text);
-
-            let current_index = state.index;
-            if current_index + 1 < state.history.len() {
-                state.history.truncate(current_index + 1);
-            }
-
-            if state.active_group == Some(granularity) {
-                if let Some(snapshot) = state.history.get_mut(current_index) {
-                    *snapshot = current_text;
-                } else {
-                    state.history.push(current_text);
-                    state.index = state.history.len().saturating_sub(1);
-                    state.active_group = Some(granularity);
-                }
-            } else {
-                state.history.push(current_text);
-                if state.history.len() > MAX_WORD_UNDO_HISTORY {
-                    state.history.remove(0);
-                    state.index = state.history.len().saturating_sub(1);
-                } else {
-                    state.index = state.history.len().saturating_sub(1);
-                }
-                state.active_group = Some(granularity);
-            }
+            state.record(current_text, granularity);
         });
     }
 
@@ -424,9 +441,9 @@ impl SqlEditorWidget {
             let mut disconnected = false;
             let mut processed = 0usize;
             let mut hit_budget = false;
-            let mut pending_rows: Vec<(usize, Vec<Vec<String>>)> = Vec::new();
+            let mut pending_rows: Vec<(usize, Vec<Vec<CellValue>>)> = Vec::new();
 
-            let flush_rows = |pending_rows: &mut Vec<(usize, Vec<Vec<String>>)>,
+            let flush_rows = |pending_rows: &mut Vec<(usize, Vec<Vec<CellValue>>)>,
                               cancelled: bool| {
                 if pending_rows.is_empty() {
                     return;
@@ -483,6 +500,13 @@ impl SqlEditorWidget {
                                 let _ = response.send(value);
                                 app::awake();
                             }
+                            QueryProgress::ConfirmStatement { message, response } => {
+                                flush_rows(&mut pending_rows, cancelled);
+                                let choice =
+                                    fltk::dialog::choice2_default(message, "Cancel", "Run", "");
+                                let _ = response.send(choice == Some(1));
+                                app::awake();
+                            }
                             QueryProgress::StatementFinished {
                                 result,
                                 connection_name,
@@ -733,13 +757,8 @@ impl SqlEditorWidget {
                         let should_reset_cursor = !matches!(&action, UiActionResult::Cancel(_));
                         match action {
                             UiActionResult::ExplainPlan(result) => match result {
-                                Ok(plan_lines) => {
-                                    let plan_text = if plan_lines.is_empty() {
-                                        "No plan output.".to_string()
-                                    } else {
-                                        plan_lines.join("\n")
-                                    };
-                                    SqlEditorWidget::show_plan_dialog(&plan_text);
+                                Ok(plan) => {
+                                    crate::ui::plan_view::show_plan_dialog(&plan);
                                 }
                                 Err(err) => {
                                     let _ =
@@ -810,6 +829,27 @@ impl SqlEditorWidget {
                                     widget.emit_status("Rollback failed");
                                 }
                             },
+                            UiActionResult::GridChanges(result) => {
+                                match &result {
+                                    Ok(message) => widget.emit_status(message),
+                                    Err(err) => {
+                                        let _ = widget.progress_sender.send(
+                                            QueryProgress::ScriptOutput {
+                                                lines: vec![format!(
+                                                    "Applying grid changes failed: {}",
+                                                    err
+                                                )],
+                                            },
+                                        );
+                                        app::awake();
+                                        widget.emit_status("Applying grid changes failed");
+                                    }
+                                }
+                                let callback = widget.grid_changes_callback.borrow_mut().take();
+                                if let Some(callback) = callback {
+                                    callback(result);
+                                }
+                            }
                             UiActionResult::Cancel(result) => {
                                 if let Err(err) = result {
                                     let _ =
@@ -935,7 +975,30 @@ impl SqlEditorWidget {
             fltk::dialog::alert_default("No SQL at cursor");
             return;
         };
+        self.spawn_plan_request(move |conn| QueryExecutor::get_explain_plan(conn, &sql));
+    }
+
+    /// Run the query at the cursor with plan statistics and show the plan
+    /// it actually used.
+    pub fn autotrace_current(&self) {
+        let sql = self.buffer.text();
+        let cursor_pos = self.editor.insert_position() as usize;
+        let Some(sql) = QueryExecutor::statement_at_cursor(&sql, cursor_pos) else {
+            fltk::dialog::alert_default("No SQL at cursor");
+            return;
+        };
+        if !QueryExecutor::is_select_statement(&sql) {
+            fltk::dialog::alert_default("Autotrace runs queries only (SELECT or WITH).");
+            return;
+        }
+        self.emit_status("Running autotrace...");
+        self.spawn_plan_request(move |conn| QueryExecutor::autotrace(conn, &sql));
+    }
 
+    fn spawn_plan_request<F>(&self, request: F)
+    where
+        F: FnOnce(&oracle::Connection) -> Result<ExecutionPlan, oracle::Error> + Send + 'static,
+    {
         let connection = self.connection.clone();
         let sender = self.ui_action_sender.clone();
         set_cursor(Cursor::Wait);
@@ -952,8 +1015,7 @@ impl SqlEditorWidget {
             let result = if !conn_guard.is_connected() {
                 Err("Not connected to database".to_string())
             } else if let Some(db_conn) = conn_guard.get_connection() {
-                QueryExecutor::get_explain_plan(db_conn.as_ref(), &sql)
-                    .map_err(|err| err.to_string())
+                request(db_conn.as_ref()).map_err(|err| err.to_string())
             } else {
                 Err("Not connected to database".to_string())
             };
@@ -963,58 +1025,6 @@ impl SqlEditorWidget {
         });
     }
 
-    fn show_plan_dialog(plan_text: &str) {
-        use fltk::{prelude::*, text::TextDisplay, window::Window};
-
-        let current_group = fltk::group::Group::try_current();
-
-        fltk::group::Group::set_current(None::<&fltk::group::Group>);
-
-        let mut dialog = Window::default()
-            .with_size(800, 500)
-            .with_label("Explain Plan");
-        crate::ui::center_on_main(&mut dialog);
-        dialog.set_color(theme::panel_raised());
-        dialog.make_modal(true);
-        dialog.begin();
-
-        let mut display = TextDisplay::default().with_pos(10, 10).with_size(780, 440);
-        display.set_color(theme::editor_bg());
-        display.set_text_color(theme::text_primary());
-        display.set_text_font(configured_editor_profile().normal);
-        display.set_text_size(configured_ui_font_size());
-
-        let mut buffer = fltk::text::TextBuffer::default();
-        buffer.set_text(plan_text);
-        display.set_buffer(buffer);
-
-        let mut close_btn = fltk::button::Button::default()
-            .with_pos(690, 455)
-            .with_size(BUTTON_WIDTH_LARGE, BUTTON_HEIGHT)
-            .with_label("Close");
-        close_btn.set_color(theme::button_secondary());
-        close_btn.set_label_color(theme::text_primary());
-
-        let (sender, receiver) = mpsc::channel::<()>();
-        close_btn.set_callback(move |_| {
-            let _ = sender.send(());
-            app::awake();
-        });
-
-        dialog.end();
-        dialog.show();
-        fltk::group::Group::set_current(current_group.as_ref());
-        let _ = dialog.take_focus();
-        let _ = close_btn.take_focus();
-
-        while dialog.shown() {
-            app::wait();
-            if receiver.try_recv().is_ok() {
-                dialog.hide();
-            }
-        }
-    }
-
     fn emit_status(&self, message: &str) {
         Self::invoke_status_callback(&self.status_callback, message);
     }
@@ -1034,11 +1044,12 @@ impl SqlEditorWidget {
     pub fn commit(&self) {
         let connection = self.connection.clone();
         let sender = self.ui_action_sender.clone();
+        let progress_sender = self.progress_sender.clone();
         set_cursor(Cursor::Wait);
         app::flush();
         thread::spawn(move || {
             // Try to acquire connection lock without blocking
-            let Some(conn_guard) = crate::db::try_lock_connection(&connection) else {
+            let Some(mut conn_guard) = crate::db::try_lock_connection(&connection) else {
                 // Query is already running, notify user
                 let _ = sender.send(UiActionResult::QueryAlreadyRunning);
                 app::awake();
@@ -1047,11 +1058,16 @@ impl SqlEditorWidget {
 
             let result = if !conn_guard.is_connected() {
                 Err("Not connected to database".to_string())
-            } else if let Some(db_conn) = conn_guard.get_connection() {
-                db_conn.commit().map_err(|err| err.to_string())
             } else {
-                Err("Not connected to database".to_string())
+                conn_guard
+                    .commit_transaction()
+                    .map_err(|err| err.to_string())
             };
+            if result.is_ok() {
+                let _ = progress_sender.send(QueryProgress::TransactionChanged {
+                    state: conn_guard.transaction().clone(),
+                });
+            }
 
             let _ = sender.send(UiActionResult::Commit(result));
             app::awake();
@@ -1061,11 +1077,12 @@ impl SqlEditorWidget {
     pub fn rollback(&self) {
         let connection = self.connection.clone();
         let sender = self.ui_action_sender.clone();
+        let progress_sender = self.progress_sender.clone();
         set_cursor(Cursor::Wait);
         app::flush();
         thread::spawn(move || {
             // Try to acquire connection lock without blocking
-            let Some(conn_guard) = crate::db::try_lock_connection(&connection) else {
+            let Some(mut conn_guard) = crate::db::try_lock_connection(&connection) else {
                 // Query is already running, notify user
                 let _ = sender.send(UiActionResult::QueryAlreadyRunning);
                 app::awake();
@@ -1074,13 +1091,105 @@ impl SqlEditorWidget {
 
             let result = if !conn_guard.is_connected() {
                 Err("Not connected to database".to_string())
+            } else {
+                conn_guard
+                    .rollback_transaction()
+                    .map_err(|err| err.to_string())
+            };
+            if result.is_ok() {
+                let _ = progress_sender.send(QueryProgress::TransactionChanged {
+                    state: conn_guard.transaction().clone(),
+                });
+            }
+
+            let _ = sender.send(UiActionResult::Rollback(result));
+            app::awake();
+        });
+    }
+
+    /// Report a transaction change made outside a batch, e.g. by the
+    /// savepoint manager, to the progress callback.
+    pub fn notify_transaction_changed(&self, state: TransactionState) {
+        let _ = self
+            .progress_sender
+            .send(QueryProgress::TransactionChanged { state });
+        app::awake();
+    }
+
+    /// Apply DML generated from result grid edits inside the current
+    /// transaction. Nothing is committed here: `commit` and `rollback`
+    /// finalize or discard the changes, unless auto-commit is on.
+    pub fn apply_grid_changes(&self, statements: Vec<DmlStatement>, on_done: GridChangesCallback) {
+        let connection = self.connection.clone();
+        let sender = self.ui_action_sender.clone();
+        let progress_sender = self.progress_sender.clone();
+        *self.grid_changes_callback.borrow_mut() = Some(on_done);
+        set_cursor(Cursor::Wait);
+        app::flush();
+        thread::spawn(move || {
+            let Some(mut conn_guard) = crate::db::try_lock_connection(&connection) else {
+                let _ = sender.send(UiActionResult::GridChanges(Err(
+                    "A query is already running. Please wait for it to complete.".to_string(),
+                )));
+                app::awake();
+                return;
+            };
+
+            let refusal = conn_guard
+                .get_info()
+                .production_guards()
+                .and_then(|guards| {
+                    statements.iter().find_map(|statement| {
+                        match QueryExecutor::check_statement_guard(&statement.sql, guards) {
+                            crate::db::StatementGuard::Refused(reason) => Some(reason),
+                            _ => None,
+                        }
+                    })
+                });
+
+            let result = if !conn_guard.is_connected() {
+                Err("Not connected to database".to_string())
+            } else if let Some(reason) = refusal {
+                Err(reason)
             } else if let Some(db_conn) = conn_guard.get_connection() {
-                db_conn.rollback().map_err(|err| err.to_string())
+                match QueryExecutor::execute_dml_statements(db_conn.as_ref(), &statements) {
+                    Ok(counts) => {
+                        let rows: u64 = counts.iter().sum();
+                        if let Some(position) = counts.iter().position(|count| *count == 0) {
+                            // The row is gone or its ROWID changed since it was fetched.
+                            if let Err(err) =
+                                QueryExecutor::rollback_dml_statements(db_conn.as_ref())
+                            {
+                                eprintln!("Database operation failed: {err}");
+                            }
+                            Err(format!(
+                                "No row matched statement {}: {}",
+                                position + 1,
+                                statements[position].sql
+                            ))
+                        } else if conn_guard.auto_commit() {
+                            db_conn
+                                .commit()
+                                .map(|_| format!("{} row(s) changed | Auto-commit applied", rows))
+                                .map_err(|err| err.to_string())
+                        } else {
+                            Ok(format!("{} row(s) changed | Commit required", rows))
+                        }
+                    }
+                    Err(err) => Err(err.to_string()),
+                }
             } else {
                 Err("Not connected to database".to_string())
             };
 
-            let _ = sender.send(UiActionResult::Rollback(result));
+            if result.is_ok() {
+                conn_guard.mark_transaction_pending();
+                let _ = progress_sender.send(QueryProgress::TransactionChanged {
+                    state: conn_guard.transaction().clone(),
+                });
+            }
+
+            let _ = sender.send(UiActionResult::GridChanges(result));
             app::awake();
         });
     }
@@ -1101,7 +1210,7 @@ impl SqlEditorWidget {
             let conn = SqlEditorWidget::clone_current_query_connection(&current_query_connection);
 
             let result = if let Some(db_conn) = conn {
-                db_conn.break_execution().map_err(|err| err.to_string())
+                db_conn.cancel().map_err(|err| err.to_string())
             } else {
                 Err("No active query connection".to_string())
             };
@@ -1112,8 +1221,8 @@ impl SqlEditorWidget {
     }
 
     fn clone_current_query_connection(
-        current_query_connection: &Arc<Mutex<Option<Arc<Connection>>>>,
-    ) -> Option<Arc<Connection>> {
+        current_query_connection: &Arc<Mutex<Option<SharedBackend>>>,
+    ) -> Option<SharedBackend> {
         match current_query_connection.lock() {
             Ok(guard) => guard.clone(),
             Err(poisoned) => {
@@ -1123,21 +1232,6 @@ impl SqlEditorWidget {
         }
     }
 
-    fn set_current_query_connection(
-        current_query_connection: &Arc<Mutex<Option<Arc<Connection>>>>,
-        value: Option<Arc<Connection>>,
-    ) {
-        match current_query_connection.lock() {
-            Ok(mut guard) => {
-                *guard = value;
-            }
-            Err(poisoned) => {
-                eprintln!("Warning: current query connection lock was poisoned; recovering.");
-                *poisoned.into_inner() = value;
-            }
-        }
-    }
-
     pub fn set_execute_callback<F>(&mut self, callback: F)
     where
         F: FnMut(&QueryResult) + 'static,
@@ -1483,6 +1577,10 @@ fn classify_edit_granularity(ins: i32, del: i32, inserted: &str, deleted: &str)
         return EditGranularity::Other;
     }
 
+    if ins > 0 && del > 0 {
+        return EditGranularity::Replace;
+    }
+
     if (ins > 0 && inserted.chars().all(is_word_edit_char))
         || (del > 0 && deleted.chars().all(is_word_edit_char))
     {
@@ -1603,3 +1603,18 @@ END;"#;
 
     assert_eq!(formatted, expected);
 }
+
+#[test]
+fn replace_edits_are_single_undo_steps() {
+    let mut state = WordUndoRedoState::new(String::new());
+    state.record("(".to_string(), EditGranularity::Other);
+    state.record("()".to_string(), EditGranularity::Other);
+    assert_eq!(state.history, vec!["", "()"]);
+
+    let replace = classify_edit_granularity(2, 2, "[]", "()");
+    assert!(replace == EditGranularity::Replace);
+    state.record("[]".to_string(), replace);
+    state.record("[];".to_string(), EditGranularity::Other);
+    assert_eq!(state.history, vec!["", "()", "[]", "[];"]);
+    assert_eq!(state.index, 3);
+}
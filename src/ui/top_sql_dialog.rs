@@ -0,0 +1,337 @@
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    frame::Frame,
+    group::{Flex, FlexType},
+    input::{Input, IntInput},
+    menu::Choice,
+    prelude::*,
+    window::Window,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::db::{
+    cursor_plan_query, load_top_sql, parsing_schemas, sql_monitor_query, sql_text_query,
+    top_sql_query, try_lock_connection, tuning_pack_enabled, valid_sql_id, SharedConnection,
+    TopSqlEntry, TopSqlMetric,
+};
+use crate::ui::constants::*;
+use crate::ui::schema_compare_dialog::{labeled, styled_button};
+use crate::ui::{center_on_main, configured_ui_font_size, theme, SqlEditorWidget};
+
+thread_local! {
+    static TOP_SQL_OPEN: Cell<bool> = const { Cell::new(false) };
+}
+
+const DEFAULT_TOP_COUNT: usize = 50;
+const ALL_SCHEMAS: &str = "All schemas";
+
+struct TopSqlLoad {
+    entries: Vec<TopSqlEntry>,
+    /// Filled on the first load only.
+    schemas: Option<Vec<String>>,
+    tuning_pack: Option<bool>,
+}
+
+fn entry_line(entry: &TopSqlEntry) -> String {
+    format!(
+        "@.{}\t@.{}\t@r{}\t@r{:.3}\t@r{:.3}\t@r{}\t@r{:.3}\t@.{}",
+        entry.sql_id,
+        entry.parsing_schema,
+        entry.executions,
+        entry.elapsed_seconds,
+        entry.cpu_seconds,
+        entry.buffer_gets,
+        entry.elapsed_per_execution_ms(),
+        entry.sql_text.replace(['\t', '\n', '\r'], " ")
+    )
+}
+
+/// List the shared pool's top statements and open their text, actual plan
+/// or SQL Monitor report in the result tabs of `editor`.
+pub fn show_top_sql_dialog(
+    editor: SqlEditorWidget,
+    connection: SharedConnection,
+    popups: Rc<RefCell<Vec<Window>>>,
+) {
+    #[derive(Clone, Copy)]
+    enum DialogMessage {
+        Refresh,
+        Select,
+        ShowList,
+        FullText,
+        Plan,
+        Monitor,
+        Close,
+    }
+
+    if TOP_SQL_OPEN.with(|open| open.replace(true)) {
+        return;
+    }
+
+    let current_group = fltk::group::Group::try_current();
+    fltk::group::Group::set_current(None::<&fltk::group::Group>);
+
+    let width = 1000;
+    let height = 600;
+    let mut dialog = Window::default()
+        .with_size(width, height)
+        .with_label("Top SQL");
+    center_on_main(&mut dialog);
+    dialog.set_color(theme::panel_raised());
+
+    let mut main_flex = Flex::new(
+        DIALOG_MARGIN,
+        DIALOG_MARGIN,
+        width - DIALOG_MARGIN * 2,
+        height - DIALOG_MARGIN * 2,
+        None,
+    );
+    main_flex.set_type(FlexType::Column);
+    main_flex.set_spacing(DIALOG_SPACING);
+
+    let mut filter_row = Flex::default();
+    filter_row.set_type(FlexType::Row);
+    filter_row.set_spacing(DIALOG_SPACING);
+    labeled(&mut filter_row, "Order by:", 70);
+    let mut metric_choice = Choice::default();
+    metric_choice.set_color(theme::input_bg());
+    metric_choice.set_text_color(theme::text_primary());
+    for metric in TopSqlMetric::ALL {
+        metric_choice.add_choice(metric.label());
+    }
+    metric_choice.set_value(0);
+    filter_row.fixed(&metric_choice, 140);
+    labeled(&mut filter_row, "Schema:", 60);
+    let mut schema_choice = Choice::default();
+    schema_choice.set_color(theme::input_bg());
+    schema_choice.set_text_color(theme::text_primary());
+    schema_choice.add_choice(ALL_SCHEMAS);
+    schema_choice.set_value(0);
+    labeled(&mut filter_row, "Top:", 40);
+    let mut count_input = IntInput::default();
+    count_input.set_color(theme::input_bg());
+    count_input.set_text_color(theme::text_primary());
+    count_input.set_value(&DEFAULT_TOP_COUNT.to_string());
+    filter_row.fixed(&count_input, 60);
+    let mut refresh_btn = styled_button("Refresh", theme::button_secondary());
+    filter_row.fixed(&refresh_btn, BUTTON_WIDTH);
+    let mut list_btn = styled_button("Show in Results", theme::button_secondary());
+    filter_row.fixed(&list_btn, BUTTON_WIDTH_LARGE);
+    filter_row.end();
+    main_flex.fixed(&filter_row, INPUT_ROW_HEIGHT);
+
+    // The first line is the column header.
+    let mut browser = HoldBrowser::default();
+    browser.set_color(theme::input_bg());
+    browser.set_selection_color(theme::selection_strong());
+    browser.set_text_size(configured_ui_font_size());
+    browser.set_column_char('\t');
+    browser.set_column_widths(&[120, 110, 80, 90, 90, 100, 100]);
+    browser.add(&format!(
+        "@C{}@.SQL ID\t@.Schema\t@.Executions\t@.Elapsed s\t@.CPU s\t@.Buffer gets\t@.Ms per exec\t@.SQL",
+        theme::text_secondary().bits()
+    ));
+
+    let mut action_row = Flex::default();
+    action_row.set_type(FlexType::Row);
+    action_row.set_spacing(DIALOG_SPACING);
+    labeled(&mut action_row, "SQL ID:", 55);
+    let mut sql_id_input = Input::default();
+    sql_id_input.set_color(theme::input_bg());
+    sql_id_input.set_text_color(theme::text_primary());
+    action_row.fixed(&sql_id_input, 140);
+    let mut text_btn = styled_button("Full Text", theme::button_secondary());
+    action_row.fixed(&text_btn, BUTTON_WIDTH);
+    let mut plan_btn = styled_button("Actual Plan", theme::button_secondary());
+    action_row.fixed(&plan_btn, BUTTON_WIDTH);
+    let mut monitor_btn = styled_button("SQL Monitor Report", theme::button_secondary());
+    action_row.fixed(&monitor_btn, BUTTON_WIDTH_LARGE + 30);
+    let _spacer = Frame::default();
+    let mut close_btn = styled_button("Close", theme::button_subtle());
+    action_row.fixed(&close_btn, BUTTON_WIDTH);
+    action_row.end();
+    main_flex.fixed(&action_row, BUTTON_ROW_HEIGHT);
+
+    let mut status = Frame::default();
+    status.set_label_color(theme::text_secondary());
+    status.set_align(fltk::enums::Align::Left | fltk::enums::Align::Inside);
+    main_flex.fixed(&status, LABEL_ROW_HEIGHT);
+
+    main_flex.end();
+    dialog.end();
+    fltk::group::Group::set_current(current_group.as_ref());
+
+    popups.borrow_mut().push(dialog.clone());
+
+    let (sender, receiver) = mpsc::channel::<DialogMessage>();
+    browser.set_callback({
+        let sender = sender.clone();
+        move |_| {
+            let _ = sender.send(DialogMessage::Select);
+            app::awake();
+        }
+    });
+    metric_choice.set_callback({
+        let sender = sender.clone();
+        move |_| {
+            let _ = sender.send(DialogMessage::Refresh);
+            app::awake();
+        }
+    });
+    schema_choice.set_callback({
+        let sender = sender.clone();
+        move |_| {
+            let _ = sender.send(DialogMessage::Refresh);
+            app::awake();
+        }
+    });
+    for (button, message) in [
+        (&mut refresh_btn, DialogMessage::Refresh),
+        (&mut list_btn, DialogMessage::ShowList),
+        (&mut text_btn, DialogMessage::FullText),
+        (&mut plan_btn, DialogMessage::Plan),
+        (&mut monitor_btn, DialogMessage::Monitor),
+        (&mut close_btn, DialogMessage::Close),
+    ] {
+        let sender = sender.clone();
+        button.set_callback(move |_| {
+            let _ = sender.send(message);
+            app::awake();
+        });
+    }
+
+    dialog.show();
+
+    let (load_sender, load_receiver) = mpsc::channel::<Result<TopSqlLoad, String>>();
+    let mut entries: Vec<TopSqlEntry> = Vec::new();
+    let mut loading = false;
+    let mut first_load = true;
+    let _ = sender.send(DialogMessage::Refresh);
+
+    while dialog.shown() {
+        app::wait();
+        while let Ok(result) = load_receiver.try_recv() {
+            loading = false;
+            refresh_btn.activate();
+            match result {
+                Ok(load) => {
+                    entries = load.entries;
+                    while browser.size() > 1 {
+                        browser.remove(2);
+                    }
+                    for entry in &entries {
+                        browser.add(&entry_line(entry));
+                    }
+                    if let Some(schemas) = load.schemas {
+                        for schema in schemas {
+                            schema_choice.add_choice(&schema.replace('/', "\\/"));
+                        }
+                    }
+                    if load.tuning_pack == Some(false) {
+                        monitor_btn.deactivate();
+                        monitor_btn.set_tooltip(
+                            "SQL Monitor reports need the Tuning Pack \
+                             (CONTROL_MANAGEMENT_PACK_ACCESS)",
+                        );
+                    }
+                    status.set_label(&format!("{} statements", entries.len()));
+                }
+                Err(err) => {
+                    first_load = true;
+                    status.set_label(&format!("Failed to load top SQL: {}", err));
+                }
+            }
+        }
+        while let Ok(message) = receiver.try_recv() {
+            let metric = usize::try_from(metric_choice.value())
+                .ok()
+                .and_then(|index| TopSqlMetric::ALL.get(index).copied())
+                .unwrap_or(TopSqlMetric::ElapsedTime);
+            let schema = schema_choice
+                .choice()
+                .filter(|schema| schema != ALL_SCHEMAS)
+                .map(|schema| schema.replace("\\/", "/"));
+            let limit = count_input
+                .value()
+                .trim()
+                .parse::<usize>()
+                .unwrap_or(DEFAULT_TOP_COUNT);
+            let sql_id = sql_id_input.value().trim().to_string();
+            let run_for_sql_id = |query: fn(&str) -> String| {
+                if valid_sql_id(&sql_id) {
+                    editor.execute_sql_text(&query(&sql_id));
+                } else {
+                    fltk::dialog::alert_default("Select a statement or enter its SQL ID");
+                }
+            };
+            match message {
+                DialogMessage::Refresh => {
+                    if loading {
+                        continue;
+                    }
+                    loading = true;
+                    refresh_btn.deactivate();
+                    status.set_label("Loading...");
+                    let with_lookups = std::mem::take(&mut first_load);
+                    let connection = connection.clone();
+                    let load_sender = load_sender.clone();
+                    thread::spawn(move || {
+                        let result = (|| {
+                            let Some(guard) = try_lock_connection(&connection) else {
+                                return Err("The connection is busy with another query".to_string());
+                            };
+                            let Some(conn) = guard.get_connection() else {
+                                return Err("Not connected to database".to_string());
+                            };
+                            let entries = load_top_sql(&conn, metric, schema.as_deref(), limit)?;
+                            let (schemas, tuning_pack) = if with_lookups {
+                                // Without access to V$PARAMETER, leave the report to fail
+                                // on its own if the pack is missing.
+                                (
+                                    Some(parsing_schemas(&conn)?),
+                                    tuning_pack_enabled(&conn).ok(),
+                                )
+                            } else {
+                                (None, None)
+                            };
+                            Ok(TopSqlLoad {
+                                entries,
+                                schemas,
+                                tuning_pack,
+                            })
+                        })();
+                        let _ = load_sender.send(result);
+                        app::awake();
+                    });
+                }
+                DialogMessage::Select => {
+                    if let Some(entry) = usize::try_from(browser.value() - 2)
+                        .ok()
+                        .and_then(|index| entries.get(index))
+                    {
+                        sql_id_input.set_value(&entry.sql_id);
+                        if app::event_clicks() {
+                            editor.execute_sql_text(&sql_text_query(&entry.sql_id));
+                        }
+                    }
+                }
+                DialogMessage::ShowList => {
+                    editor.execute_sql_text(&top_sql_query(metric, schema.as_deref(), limit));
+                }
+                DialogMessage::FullText => run_for_sql_id(sql_text_query),
+                DialogMessage::Plan => run_for_sql_id(cursor_plan_query),
+                DialogMessage::Monitor => run_for_sql_id(sql_monitor_query),
+                DialogMessage::Close => dialog.hide(),
+            }
+        }
+    }
+
+    popups
+        .borrow_mut()
+        .retain(|w| w.as_widget_ptr() != dialog.as_widget_ptr());
+    TOP_SQL_OPEN.with(|open| open.set(false));
+}
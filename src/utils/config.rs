@@ -1,12 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::fs;
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
-use crate::db::ConnectionInfo;
+use crate::db::{ConnectionInfo, SessionSettings};
 use crate::utils::credential_store;
 
 const APP_DIR_NAME: &str = "space_query";
@@ -16,6 +17,9 @@ const LEGACY_APP_DIR_NAME: &str = "oracle_query_tool";
 #[serde(default)]
 pub struct AppConfig {
     pub recent_connections: Vec<ConnectionInfo>,
+    /// Imported profiles, kept apart from the recent list so they are not
+    /// evicted from it.
+    pub saved_connections: Vec<ConnectionInfo>,
     pub last_connection: Option<String>,
     pub editor_font: String,
     pub ui_font_size: u32,
@@ -25,6 +29,8 @@ pub struct AppConfig {
     pub result_cell_max_chars: u32,
     pub max_rows: u32,
     pub auto_commit: bool,
+    /// Session settings of every connection; profiles override them.
+    pub session_defaults: SessionSettings,
 }
 
 impl AppConfig {
@@ -47,6 +53,7 @@ impl AppConfig {
     pub fn new() -> Self {
         Self {
             recent_connections: Vec::new(),
+            saved_connections: Vec::new(),
             last_connection: None,
             editor_font: "Courier".to_string(),
             ui_font_size: 14,
@@ -56,6 +63,7 @@ impl AppConfig {
             result_cell_max_chars: crate::ui::constants::RESULT_CELL_MAX_DISPLAY_CHARS_DEFAULT,
             max_rows: 1000,
             auto_commit: false,
+            session_defaults: SessionSettings::application_defaults(),
         }
     }
 
@@ -89,7 +97,11 @@ impl AppConfig {
         // Migrate plain-text passwords from old config to keyring.
         // Passwords are NOT loaded eagerly; use get_password_for_connection() on demand.
         let mut needs_resave = false;
-        for conn in &mut config.recent_connections {
+        for conn in config
+            .recent_connections
+            .iter_mut()
+            .chain(config.saved_connections.iter_mut())
+        {
             if !conn.password.is_empty() {
                 if let Err(e) = credential_store::store_password(&conn.name, &conn.password) {
                     eprintln!("Keyring migration warning: {}", e);
@@ -161,6 +173,15 @@ impl AppConfig {
         }
         info.clear_password();
 
+        // An imported profile keeps the edits made to it
+        if let Some(saved) = self
+            .saved_connections
+            .iter_mut()
+            .find(|c| c.name == info.name)
+        {
+            *saved = info.clone();
+        }
+
         // Remove existing connection with same name
         self.recent_connections.retain(|c| c.name != info.name);
 
@@ -171,8 +192,74 @@ impl AppConfig {
         self.recent_connections.truncate(10);
     }
 
+    /// Add connections imported from another tool to the saved profiles.
+    /// Names match ignoring case, and only the first connection of a name in
+    /// `imported` is used. A profile of the same name gets the imported
+    /// target and credentials when `replace_existing` is set, keeping its
+    /// environment, guards and session settings, and is skipped otherwise.
+    /// Returns how many were added and how many replaced.
+    pub fn import_connections(
+        &mut self,
+        imported: Vec<ConnectionInfo>,
+        replace_existing: bool,
+    ) -> (usize, usize) {
+        let mut added = 0;
+        let mut replaced = 0;
+        let mut seen: Vec<String> = Vec::new();
+        for mut info in imported {
+            if seen.iter().any(|name| info.has_name(name)) {
+                continue;
+            }
+            seen.push(info.name.clone());
+            let existing = self
+                .get_all_connections()
+                .into_iter()
+                .find(|c| c.has_name(&info.name))
+                .cloned();
+            if existing.is_some() && !replace_existing {
+                continue;
+            }
+            if let Some(profile) = &existing {
+                info.name = profile.name.clone();
+            }
+            if !info.password.is_empty() {
+                if let Err(e) = credential_store::store_password(&info.name, &info.password) {
+                    eprintln!("Keyring store warning: {}", e);
+                }
+            }
+            info.clear_password();
+            match existing {
+                Some(mut profile) => {
+                    apply_imported_fields(&mut profile, info);
+                    for recent in &mut self.recent_connections {
+                        if recent.name == profile.name {
+                            *recent = profile.clone();
+                        }
+                    }
+                    match self
+                        .saved_connections
+                        .iter_mut()
+                        .find(|c| c.name == profile.name)
+                    {
+                        Some(saved) => *saved = profile,
+                        None => self.saved_connections.push(profile),
+                    }
+                    replaced += 1;
+                }
+                None => {
+                    self.saved_connections.push(info);
+                    added += 1;
+                }
+            }
+        }
+        (added, replaced)
+    }
+
     pub fn get_connection_by_name(&self, name: &str) -> Option<&ConnectionInfo> {
-        self.recent_connections.iter().find(|c| c.name == name)
+        self.recent_connections
+            .iter()
+            .chain(&self.saved_connections)
+            .find(|c| c.name == name)
     }
 
     /// Retrieve the password for a saved connection from the OS keyring on demand.
@@ -194,26 +281,65 @@ impl AppConfig {
             eprintln!("Keyring delete warning: {}", e);
         }
         self.recent_connections.retain(|c| c.name != name);
+        self.saved_connections.retain(|c| c.name != name);
     }
 
-    pub fn get_all_connections(&self) -> &Vec<ConnectionInfo> {
-        &self.recent_connections
+    /// Recent connections first, then the saved profiles not among them.
+    pub fn get_all_connections(&self) -> Vec<&ConnectionInfo> {
+        let mut connections: Vec<&ConnectionInfo> = self.recent_connections.iter().collect();
+        for saved in &self.saved_connections {
+            if !self.recent_connections.iter().any(|c| c.name == saved.name) {
+                connections.push(saved);
+            }
+        }
+        connections
     }
 }
 
+/// Copy where `imported` connects to, and as whom, onto a saved profile.
+fn apply_imported_fields(saved: &mut ConnectionInfo, imported: ConnectionInfo) {
+    saved.username = imported.username;
+    saved.host = imported.host;
+    saved.port = imported.port;
+    saved.service_name = imported.service_name;
+    saved.connection_type = imported.connection_type;
+    saved.tns_alias = imported.tns_alias;
+    saved.easy_connect = imported.easy_connect;
+    saved.privilege = imported.privilege;
+    saved.use_wallet = imported.use_wallet;
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Unpinned history entries beyond this many are dropped, oldest first.
+pub const MAX_HISTORY_ENTRIES: usize = 50_000;
+/// The log is rewritten once it holds this many times the records the
+/// history can keep.
+const HISTORY_COMPACT_FACTOR: usize = 2;
+
+/// Query history, newest first.
+///
+/// Stored as `history.jsonl`, one [`HistoryRecord`] per line. Runs and
+/// changes are appended and the size limit applies in memory; the file is
+/// rewritten only when superseded records pile up, so saving stays cheap
+/// however long the history is.
+#[derive(Debug, Default)]
 pub struct QueryHistory {
-    pub queries: Vec<QueryHistoryEntry>,
+    pub queries: VecDeque<QueryHistoryEntry>,
+    next_id: u64,
+    pinned_count: usize,
+    /// Records in the log file, including superseded ones.
+    log_records: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryHistoryEntry {
+    #[serde(default)]
+    pub id: u64,
     pub sql: String,
     pub timestamp: String,
     pub execution_time_ms: u64,
@@ -225,66 +351,114 @@ pub struct QueryHistoryEntry {
     pub error_message: Option<String>,
     #[serde(default)]
     pub error_line: Option<usize>,
+    /// Pinned entries are kept by Clear History and the size limit.
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 fn default_query_success() -> bool {
     true
 }
 
+/// One line of the history log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum HistoryRecord {
+    Add {
+        entry: QueryHistoryEntry,
+    },
+    Pin {
+        id: u64,
+        pinned: bool,
+    },
+    Tags {
+        id: u64,
+        tags: Vec<String>,
+    },
+    /// Remove every unpinned entry.
+    Clear,
+}
+
+/// The pre-log format, a single JSON document.
+#[derive(Deserialize)]
+struct LegacyQueryHistory {
+    queries: Vec<QueryHistoryEntry>,
+}
+
 impl QueryHistory {
-    fn history_path_for(app_dir: &str) -> Option<PathBuf> {
-        AppConfig::app_file_path(dirs::data_dir(), app_dir, "history.json")
+    fn history_dir_file(app_dir: &str, file_name: &str) -> Option<PathBuf> {
+        AppConfig::app_file_path(dirs::data_dir(), app_dir, file_name)
     }
 
     pub fn new() -> Self {
-        Self {
-            queries: Vec::new(),
-        }
+        Self::default()
     }
 
     pub fn history_path() -> Option<PathBuf> {
-        Self::history_path_for(APP_DIR_NAME)
+        Self::history_dir_file(APP_DIR_NAME, "history.jsonl")
     }
 
-    fn legacy_history_path() -> Option<PathBuf> {
-        Self::history_path_for(LEGACY_APP_DIR_NAME)
+    /// `history.json` files written before the log format, newest location
+    /// first.
+    fn legacy_history_paths() -> Vec<PathBuf> {
+        [APP_DIR_NAME, LEGACY_APP_DIR_NAME]
+            .iter()
+            .filter_map(|app_dir| Self::history_dir_file(app_dir, "history.json"))
+            .collect()
     }
 
-    pub fn load() -> Self {
-        let mut loaded_from_legacy = false;
+    /// Rebuild the history from log text. Lines that fail to parse, such as
+    /// a partly written last line, are skipped.
+    pub fn from_log(content: &str) -> Self {
+        let mut history = Self::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str::<HistoryRecord>(line) {
+                Ok(record) => {
+                    history.apply(record);
+                    history.log_records += 1;
+                }
+                Err(err) => eprintln!("Skipping unreadable history record: {err}"),
+            }
+        }
+        history
+    }
 
-        let loaded = if let Some(path) = Self::history_path() {
+    pub fn load() -> Self {
+        if let Some(path) = Self::history_path() {
             if path.exists() {
-                fs::read_to_string(&path)
-                    .ok()
-                    .and_then(|content| serde_json::from_str::<Self>(&content).ok())
-            } else if let Some(legacy_path) = Self::legacy_history_path() {
-                if legacy_path.exists() {
-                    loaded_from_legacy = true;
-                    fs::read_to_string(&legacy_path)
-                        .ok()
-                        .and_then(|content| serde_json::from_str::<Self>(&content).ok())
-                } else {
-                    None
-                }
-            } else {
-                None
+                return match fs::read_to_string(&path) {
+                    Ok(content) => Self::from_log(&content),
+                    Err(err) => {
+                        eprintln!("History load error: {err}");
+                        Self::new()
+                    }
+                };
             }
-        } else {
-            None
-        };
+        }
 
-        if let Some(history) = loaded {
-            if loaded_from_legacy {
-                let _ = history.save();
+        // Migrate the first legacy file found to the log format.
+        for legacy_path in Self::legacy_history_paths() {
+            let Some(legacy) = fs::read_to_string(&legacy_path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<LegacyQueryHistory>(&content).ok())
+            else {
+                continue;
+            };
+            let mut history = Self::new();
+            for entry in legacy.queries.into_iter().rev() {
+                history.add_entry(entry);
             }
+            let _ = history.save();
             return history;
         }
 
         Self::new()
     }
 
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+    /// Rewrite the log with one record per entry.
+    pub fn save(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(path) = Self::history_path() {
             if let Some(parent) = path.parent() {
                 match fs::create_dir_all(parent) {
@@ -295,31 +469,585 @@ impl QueryHistory {
                     }
                 }
             }
-            let file = match fs::File::create(&path) {
+            let temp_path = path.with_extension("jsonl.tmp");
+            let file = match fs::File::create(&temp_path) {
                 Ok(f) => f,
                 Err(err) => {
                     eprintln!("History persistence error: {err}");
                     return Err(Box::new(err));
                 }
             };
-            let writer = BufWriter::new(file);
-            if let Err(err) = serde_json::to_writer(writer, self) {
+            let mut writer = BufWriter::new(file);
+            for entry in self.queries.iter().rev() {
+                let record = HistoryRecord::Add {
+                    entry: entry.clone(),
+                };
+                if let Err(err) = write_record(&mut writer, &record) {
+                    eprintln!("History persistence error: {err}");
+                    return Err(err);
+                }
+            }
+            if let Err(err) = writer.flush() {
+                eprintln!("History persistence error: {err}");
+                return Err(Box::new(err));
+            }
+            drop(writer);
+            if let Err(err) = fs::rename(&temp_path, &path) {
                 eprintln!("History persistence error: {err}");
                 return Err(Box::new(err));
             }
         }
+        self.log_records = self.queries.len();
         Ok(())
     }
 
-    pub fn add_entry(&mut self, entry: QueryHistoryEntry) {
-        self.queries.insert(0, entry);
-        // Keep only last 1000 queries
-        self.queries.truncate(1000);
+    /// Whether appending `count` more records takes the log past the size
+    /// at which it is rewritten.
+    fn needs_compaction(&self, count: usize) -> bool {
+        self.log_records + count
+            > HISTORY_COMPACT_FACTOR * (self.pinned_count + MAX_HISTORY_ENTRIES)
+    }
+
+    /// Apply `records` and persist them, appending to the log or compacting
+    /// it when it has grown well past what the history can keep.
+    pub fn commit(
+        &mut self,
+        records: Vec<HistoryRecord>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut appended = Vec::with_capacity(records.len());
+        for record in records {
+            if let Some(record) = self.apply(record) {
+                appended.push(record);
+            }
+        }
+        if self.needs_compaction(appended.len()) {
+            return self.save();
+        }
+
+        let Some(path) = Self::history_path() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        let mut writer = BufWriter::new(file);
+        for record in &appended {
+            write_record(&mut writer, record)?;
+        }
+        writer.flush()?;
+        self.log_records += appended.len();
+        Ok(())
+    }
+
+    /// Apply a record in memory. Returns the record as it should be logged,
+    /// with the id of an added entry filled in.
+    pub fn apply(&mut self, record: HistoryRecord) -> Option<HistoryRecord> {
+        match record {
+            HistoryRecord::Add { entry } => {
+                let id = self.add_entry(entry);
+                let entry = self.queries.iter().find(|entry| entry.id == id)?.clone();
+                Some(HistoryRecord::Add { entry })
+            }
+            HistoryRecord::Pin { id, pinned } => {
+                let entry = self.queries.iter_mut().find(|entry| entry.id == id)?;
+                if entry.pinned != pinned {
+                    entry.pinned = pinned;
+                    if pinned {
+                        self.pinned_count += 1;
+                    } else {
+                        self.pinned_count = self.pinned_count.saturating_sub(1);
+                    }
+                }
+                Some(HistoryRecord::Pin { id, pinned })
+            }
+            HistoryRecord::Tags { id, tags } => {
+                let entry = self.queries.iter_mut().find(|entry| entry.id == id)?;
+                entry.tags = tags.clone();
+                Some(HistoryRecord::Tags { id, tags })
+            }
+            HistoryRecord::Clear => {
+                self.clear_unpinned();
+                Some(HistoryRecord::Clear)
+            }
+        }
+    }
+
+    /// Add an entry as the most recent one and return its id. Entries read
+    /// back from the log keep their id.
+    pub fn add_entry(&mut self, mut entry: QueryHistoryEntry) -> u64 {
+        if entry.id == 0 {
+            entry.id = self.next_id.max(1);
+        }
+        self.next_id = self.next_id.max(entry.id + 1);
+        let id = entry.id;
+        if entry.pinned {
+            self.pinned_count += 1;
+        }
+        self.queries.push_front(entry);
+
+        let mut excess = self
+            .queries
+            .len()
+            .saturating_sub(self.pinned_count + MAX_HISTORY_ENTRIES);
+        // Oldest entries are at the back.
+        let mut index = self.queries.len();
+        while excess > 0 && index > 0 {
+            index -= 1;
+            if !self.queries[index].pinned {
+                self.queries.remove(index);
+                excess -= 1;
+            }
+        }
+        id
+    }
+
+    pub fn clear_unpinned(&mut self) {
+        self.queries.retain(|entry| entry.pinned);
+        self.pinned_count = self.queries.len();
     }
 }
 
-impl Default for QueryHistory {
-    fn default() -> Self {
-        Self::new()
+fn write_record(
+    writer: &mut impl Write,
+    record: &HistoryRecord,
+) -> Result<(), Box<dyn std::error::Error>> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Identifies a snippet bundle file.
+const SNIPPET_BUNDLE_FORMAT: &str = "space_query.snippets";
+const SNIPPET_BUNDLE_VERSION: u32 = 1;
+
+/// A saved SQL template. `&name` placeholders in the SQL are filled in when
+/// the snippet is inserted, like substitution variables in scripts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SqlSnippet {
+    pub name: String,
+    /// Folder path with `/` between levels; empty for the top level.
+    pub folder: String,
+    /// Short word that offers the snippet in autocomplete.
+    pub trigger: String,
+    pub description: String,
+    pub sql: String,
+}
+
+impl SqlSnippet {
+    /// `folder/name`, or just the name at the top level.
+    pub fn path(&self) -> String {
+        if self.folder.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}/{}", self.folder, self.name)
+        }
+    }
+
+    fn same_path(&self, other: &SqlSnippet) -> bool {
+        self.folder.eq_ignore_ascii_case(&other.folder)
+            && self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+/// The shareable file written by snippet export.
+#[derive(Serialize, Deserialize)]
+struct SnippetBundle {
+    format: String,
+    version: u32,
+    snippets: Vec<SqlSnippet>,
+}
+
+/// The user's snippet library, kept sorted by folder and name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnippetLibrary {
+    pub snippets: Vec<SqlSnippet>,
+}
+
+impl SnippetLibrary {
+    pub fn library_path() -> Option<PathBuf> {
+        AppConfig::app_file_path(dirs::data_dir(), APP_DIR_NAME, "snippets.json")
+    }
+
+    pub fn load() -> Self {
+        let Some(path) = Self::library_path() else {
+            return Self::default();
+        };
+        if !path.exists() {
+            return Self::default();
+        }
+        match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<Self>(&content) {
+                Ok(mut library) => {
+                    library.sort();
+                    library
+                }
+                Err(err) => {
+                    eprintln!("Snippet library load error: {err}");
+                    Self::default()
+                }
+            },
+            Err(err) => {
+                eprintln!("Snippet library load error: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = Self::library_path() {
+            if let Some(parent) = path.parent() {
+                match fs::create_dir_all(parent) {
+                    Ok(()) => {}
+                    Err(err) => {
+                        eprintln!("Snippet library persistence error: {err}");
+                        return Err(Box::new(err));
+                    }
+                }
+            }
+            let content = match serde_json::to_string_pretty(self) {
+                Ok(content) => content,
+                Err(err) => {
+                    eprintln!("Snippet library persistence error: {err}");
+                    return Err(Box::new(err));
+                }
+            };
+            match fs::write(&path, content) {
+                Ok(()) => {}
+                Err(err) => {
+                    eprintln!("Snippet library persistence error: {err}");
+                    return Err(Box::new(err));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn sort(&mut self) {
+        self.snippets
+            .sort_by_key(|snippet| (snippet.folder.to_lowercase(), snippet.name.to_lowercase()));
+    }
+
+    /// Add `snippet`, replacing the one at the same path. Returns whether
+    /// one was replaced.
+    pub fn upsert(&mut self, snippet: SqlSnippet) -> bool {
+        let replaced = match self.snippets.iter_mut().find(|s| s.same_path(&snippet)) {
+            Some(existing) => {
+                *existing = snippet;
+                true
+            }
+            None => {
+                self.snippets.push(snippet);
+                false
+            }
+        };
+        self.sort();
+        replaced
+    }
+
+    pub fn remove(&mut self, path: &str) -> bool {
+        let before = self.snippets.len();
+        self.snippets
+            .retain(|snippet| !snippet.path().eq_ignore_ascii_case(path));
+        self.snippets.len() != before
+    }
+
+    pub fn find_by_trigger(&self, trigger: &str) -> Option<&SqlSnippet> {
+        let trigger = trigger.trim();
+        if trigger.is_empty() {
+            return None;
+        }
+        self.snippets
+            .iter()
+            .find(|snippet| snippet.trigger.eq_ignore_ascii_case(trigger))
+    }
+
+    /// Snippets whose trigger starts with `prefix`, ignoring case.
+    pub fn triggers_matching(&self, prefix: &str) -> Vec<&SqlSnippet> {
+        let prefix = prefix.to_lowercase();
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+        self.snippets
+            .iter()
+            .filter(|snippet| {
+                !snippet.trigger.is_empty() && snippet.trigger.to_lowercase().starts_with(&prefix)
+            })
+            .collect()
+    }
+
+    /// Add or replace every imported snippet; returns how many were added
+    /// and how many replaced.
+    pub fn merge(&mut self, imported: Vec<SqlSnippet>) -> (usize, usize) {
+        let mut added = 0;
+        let mut replaced = 0;
+        for snippet in imported {
+            if self.upsert(snippet) {
+                replaced += 1;
+            } else {
+                added += 1;
+            }
+        }
+        (added, replaced)
+    }
+
+    /// Serialize snippets as a bundle file for sharing.
+    pub fn bundle_json(snippets: &[SqlSnippet]) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&SnippetBundle {
+            format: SNIPPET_BUNDLE_FORMAT.to_string(),
+            version: SNIPPET_BUNDLE_VERSION,
+            snippets: snippets.to_vec(),
+        })
+    }
+
+    /// Read the snippets of a bundle file.
+    pub fn parse_bundle(content: &str) -> Result<Vec<SqlSnippet>, String> {
+        let bundle: SnippetBundle = match serde_json::from_str(content) {
+            Ok(bundle) => bundle,
+            Err(err) => return Err(format!("Not a snippet bundle: {err}")),
+        };
+        if bundle.format != SNIPPET_BUNDLE_FORMAT {
+            return Err(format!(
+                "Not a snippet bundle: format is {:?}",
+                bundle.format
+            ));
+        }
+        if bundle.version > SNIPPET_BUNDLE_VERSION {
+            return Err(format!(
+                "Snippet bundle version {} is newer than this application supports",
+                bundle.version
+            ));
+        }
+        Ok(bundle
+            .snippets
+            .into_iter()
+            .filter(|snippet| !snippet.name.trim().is_empty())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod query_history_log_tests {
+    use super::*;
+
+    fn entry(sql: &str) -> QueryHistoryEntry {
+        QueryHistoryEntry {
+            id: 0,
+            sql: sql.to_string(),
+            timestamp: "2024-05-01 10:00:00".to_string(),
+            execution_time_ms: 5,
+            row_count: 1,
+            connection_name: "dev".to_string(),
+            success: true,
+            error_message: None,
+            error_line: None,
+            pinned: false,
+            tags: Vec::new(),
+        }
+    }
+
+    fn log_of(records: &[HistoryRecord]) -> String {
+        records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap() + "\n")
+            .collect()
+    }
+
+    #[test]
+    fn replays_adds_pins_tags_and_clear() {
+        let mut writer = QueryHistory::new();
+        let mut logged = Vec::new();
+        for record in [
+            HistoryRecord::Add {
+                entry: entry("select 1 from dual"),
+            },
+            HistoryRecord::Add {
+                entry: entry("select 2 from dual"),
+            },
+            HistoryRecord::Pin {
+                id: 1,
+                pinned: true,
+            },
+            HistoryRecord::Tags {
+                id: 1,
+                tags: vec!["smoke".to_string()],
+            },
+            HistoryRecord::Clear,
+            HistoryRecord::Add {
+                entry: entry("select 3 from dual"),
+            },
+        ] {
+            logged.extend(writer.apply(record));
+        }
+
+        let mut text = log_of(&logged);
+        text.push_str("{\"op\":\"add\",\"entry\":{\"sq");
+        let history = QueryHistory::from_log(&text);
+        let summary: Vec<(u64, &str, bool)> = history
+            .queries
+            .iter()
+            .map(|entry| (entry.id, entry.sql.as_str(), entry.pinned))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (3, "select 3 from dual", false),
+                (1, "select 1 from dual", true)
+            ]
+        );
+        assert_eq!(history.queries[1].tags, vec!["smoke".to_string()]);
+        assert_eq!(history.log_records, 6);
+    }
+
+    #[test]
+    fn size_limit_drops_oldest_unpinned_entries() {
+        let mut history = QueryHistory::new();
+        let first = history.add_entry(QueryHistoryEntry {
+            pinned: true,
+            ..entry("pinned")
+        });
+        for index in 0..MAX_HISTORY_ENTRIES + 1 {
+            history.add_entry(entry(&format!("select {index} from dual")));
+        }
+        assert_eq!(history.queries.len(), MAX_HISTORY_ENTRIES + 1);
+        assert_eq!(history.queries.back().map(|entry| entry.id), Some(first));
+        assert_eq!(
+            history.queries[MAX_HISTORY_ENTRIES - 1].sql,
+            "select 1 from dual"
+        );
+    }
+
+    #[test]
+    fn full_history_keeps_appending_until_the_log_doubles() {
+        let mut history = QueryHistory::new();
+        for index in 0..MAX_HISTORY_ENTRIES + 10 {
+            history.add_entry(entry(&format!("select {index} from dual")));
+        }
+        history.log_records = MAX_HISTORY_ENTRIES + 10;
+        assert!(!history.needs_compaction(1));
+
+        history.log_records = 2 * MAX_HISTORY_ENTRIES;
+        assert!(history.needs_compaction(1));
+    }
+}
+
+#[cfg(test)]
+mod snippet_library_tests {
+    use super::*;
+
+    fn snippet(folder: &str, name: &str, trigger: &str) -> SqlSnippet {
+        SqlSnippet {
+            name: name.to_string(),
+            folder: folder.to_string(),
+            trigger: trigger.to_string(),
+            sql: format!("select * from {name} where id = &id"),
+            ..SqlSnippet::default()
+        }
+    }
+
+    #[test]
+    fn merge_replaces_same_path_and_keeps_library_sorted() {
+        let mut library = SnippetLibrary::default();
+        library.upsert(snippet("tuning", "waits", "waits"));
+        library.upsert(snippet("", "locks", "lk"));
+        let imported = vec![
+            snippet("Tuning", "Waits", "wt"),
+            snippet("admin", "users", ""),
+        ];
+        assert_eq!(library.merge(imported), (1, 1));
+        let paths: Vec<String> = library.snippets.iter().map(SqlSnippet::path).collect();
+        assert_eq!(paths, vec!["locks", "admin/users", "Tuning/Waits"]);
+        assert_eq!(
+            library.find_by_trigger("WT").map(|s| s.name.as_str()),
+            Some("Waits")
+        );
+        assert!(library.find_by_trigger("waits").is_none());
+        assert_eq!(library.triggers_matching("l").len(), 1);
+        assert!(library.remove("tuning/waits"));
+    }
+
+    #[test]
+    fn bundle_round_trips_and_rejects_other_json() {
+        let snippets = vec![snippet("", "locks", "lk")];
+        let json = SnippetLibrary::bundle_json(&snippets).unwrap();
+        assert_eq!(SnippetLibrary::parse_bundle(&json).unwrap(), snippets);
+        assert!(SnippetLibrary::parse_bundle(r#"{"recent_connections": []}"#).is_err());
+        let future = json.replace("\"version\": 1", "\"version\": 9");
+        assert!(SnippetLibrary::parse_bundle(&future).is_err());
+    }
+}
+
+#[cfg(test)]
+mod connection_import_tests {
+    use super::*;
+    use crate::db::ConnectionEnvironment;
+
+    fn connection(name: &str, host: &str) -> ConnectionInfo {
+        ConnectionInfo::new(name, "scott", "", host, 1521, "ORCL")
+    }
+
+    #[test]
+    fn imports_skip_or_replace_saved_names() {
+        let mut config = AppConfig::new();
+        for index in 0..12 {
+            config.add_recent_connection(connection(&format!("saved{index}"), "old"));
+        }
+        assert_eq!(config.recent_connections.len(), 10);
+
+        let imported = vec![connection("saved3", "new"), connection("fresh", "new")];
+        assert_eq!(config.import_connections(imported.clone(), false), (1, 0));
+        assert_eq!(config.get_connection_by_name("saved3").unwrap().host, "old");
+        assert_eq!(config.import_connections(imported, true), (0, 2));
+        assert_eq!(config.get_connection_by_name("saved3").unwrap().host, "new");
+        assert_eq!(config.recent_connections.len(), 10);
+        assert_eq!(config.get_all_connections().len(), 11);
+        assert_eq!(config.get_all_connections()[10].name, "fresh");
+    }
+
+    #[test]
+    fn imported_profiles_outlive_the_recent_list() {
+        let mut config = AppConfig::new();
+        config.import_connections(vec![connection("Billing", "db1")], false);
+        let mut billing = config.get_connection_by_name("Billing").unwrap().clone();
+        billing.port = 1522;
+        config.add_recent_connection(billing);
+        for index in 0..10 {
+            config.add_recent_connection(connection(&format!("recent{index}"), "db"));
+        }
+        assert!(config
+            .recent_connections
+            .iter()
+            .all(|c| c.name != "Billing"));
+        assert_eq!(config.get_connection_by_name("Billing").unwrap().port, 1522);
+        assert_eq!(config.get_all_connections().len(), 11);
+
+        config.remove_connection("Billing");
+        assert!(config.get_connection_by_name("Billing").is_none());
+    }
+
+    #[test]
+    fn replacing_keeps_the_profile_settings() {
+        let mut config = AppConfig::new();
+        let mut saved = connection("Billing", "old");
+        saved.environment = ConnectionEnvironment::Production;
+        saved.guards.read_only = true;
+        saved.session.current_schema = "BILLING".to_string();
+        config.add_recent_connection(saved);
+
+        let mut renamed = connection("BILLING", "new");
+        renamed.port = 1522;
+        let imported = vec![renamed, connection("billing", "other")];
+        assert_eq!(config.import_connections(imported, true), (0, 1));
+        assert_eq!(config.get_all_connections().len(), 1);
+        let billing = config.get_connection_by_name("Billing").unwrap();
+        assert_eq!((billing.host.as_str(), billing.port), ("new", 1522));
+        assert_eq!(billing.environment, ConnectionEnvironment::Production);
+        assert!(billing.guards.read_only);
+        assert_eq!(billing.session.current_schema, "BILLING");
     }
 }